@@ -3,6 +3,7 @@ use crate::block_processing_utils::{
 };
 use crate::blocks_delay_tracker::BlocksDelayTracker;
 use crate::chain_update::ChainUpdate;
+use crate::chunk_apply_results::{ChunkApplyResult, ChunkApplyResults};
 use crate::crypto_hash_timer::CryptoHashTimer;
 use crate::lightclient::get_epoch_block_producers_view;
 use crate::migrations::check_if_block_is_first_with_chunk_of_version;
@@ -58,7 +59,9 @@ use near_primitives::congestion_info::CongestionInfo;
 #[cfg(feature = "new_epoch_sync")]
 use near_primitives::epoch_manager::epoch_sync::EpochSyncInfo;
 #[cfg(feature = "new_epoch_sync")]
-use near_primitives::errors::epoch_sync::EpochSyncHashType;
+use near_primitives::errors::epoch_sync::{
+    EpochSyncHashType, EpochSyncInfoError as PrimitivesEpochSyncInfoError,
+};
 use near_primitives::errors::EpochError;
 use near_primitives::hash::{hash, CryptoHash};
 use near_primitives::merkle::{
@@ -285,6 +288,9 @@ pub struct Chain {
     // A handle that allows the main process to interrupt resharding if needed.
     // This typically happens when the main process is interrupted.
     pub resharding_handle: ReshardingHandle,
+
+    /// Subscriptions of the embedders to the results of applying the chunks.
+    pub chunk_apply_results: ChunkApplyResults,
 }
 
 impl Drop for Chain {
@@ -386,6 +392,7 @@ impl Chain {
                 "resharding_config",
             ),
             resharding_handle: ReshardingHandle::new(),
+            chunk_apply_results: ChunkApplyResults::default(),
         })
     }
 
@@ -409,6 +416,7 @@ impl Chain {
             chain_genesis.height,
             chain_config.save_trie_changes,
         );
+        chain_store.save_account_index = chain_config.save_account_index;
         let genesis_chunks = genesis_chunks(
             state_roots.clone(),
             &epoch_manager.shard_ids(&EpochId::default())?,
@@ -589,6 +597,7 @@ impl Chain {
             snapshot_callbacks,
             resharding_config: chain_config.resharding_config,
             resharding_handle: ReshardingHandle::new(),
+            chunk_apply_results: ChunkApplyResults::default(),
         })
     }
 
@@ -1560,9 +1569,12 @@ impl Chain {
             // Add validator proposals for given header.
             let last_finalized_height =
                 chain_store_update.get_block_height(header.last_final_block())?;
-            let epoch_manager_update = self
-                .epoch_manager
-                .add_validator_proposals(BlockHeaderInfo::new(header, last_finalized_height))?;
+            let prev_header = chain_store_update.get_block_header(header.prev_hash())?;
+            let mut block_header_info = BlockHeaderInfo::new(header, last_finalized_height);
+            block_header_info.chunk_mask =
+                Self::chunk_production_mask(self.epoch_manager.as_ref(), header, &prev_header)?;
+            let epoch_manager_update =
+                self.epoch_manager.add_validator_proposals(block_header_info)?;
             chain_store_update.merge(epoch_manager_update);
             chain_store_update.commit()?;
 
@@ -1889,6 +1901,22 @@ impl Chain {
                 }
             }
         }
+        // Copied only if there are subscribers, as the results are consumed by
+        // the postprocessing.
+        let chunk_apply_results: Vec<_> = if self.chunk_apply_results.has_subscribers() {
+            apply_results
+                .iter()
+                .filter_map(|(_, result)| {
+                    ChunkApplyResult::new(
+                        block_hash,
+                        block.header().height(),
+                        result.as_ref().ok()?,
+                    )
+                })
+                .collect()
+        } else {
+            vec![]
+        };
         let new_head =
             match self.postprocess_block_only(me, &block, block_preprocess_info, apply_results) {
                 Err(err) => {
@@ -1898,6 +1926,7 @@ impl Chain {
                 }
                 Ok(new_head) => new_head,
             };
+        self.chunk_apply_results.notify(chunk_apply_results);
 
         // Update flat storage head to be the last final block. Note that this update happens
         // in a separate db transaction from the update from block processing. This is intentional
@@ -3180,7 +3209,7 @@ impl Chain {
     }
 
     /// Returns execution status based on the list of currently existing outcomes
-    fn get_execution_status(
+    pub fn get_execution_status(
         &self,
         outcomes: &[ExecutionOutcomeWithIdView],
         transaction_hash: &CryptoHash,
@@ -4454,6 +4483,51 @@ impl Chain {
             })
             .collect()
     }
+
+    /// Returns whether none of the chunks in the block carry outgoing
+    /// receipts, so that no shard gets incoming receipts on top of it. Only the
+    /// header is needed, so that the nodes syncing headers agree with the nodes
+    /// processing blocks.
+    pub fn block_has_no_receipts(
+        epoch_manager: &dyn EpochManagerAdapter,
+        header: &BlockHeader,
+    ) -> Result<bool, Error> {
+        let shard_layout = epoch_manager.get_shard_layout(header.epoch_id())?;
+        let (empty_chunk_root, _) = merklize(&Self::build_receipts_hashes(&[], &shard_layout));
+        let (empty_root, _) = merklize(&vec![empty_chunk_root; header.chunk_mask().len()]);
+        Ok(header.prev_chunk_outgoing_receipts_root() == &empty_root)
+    }
+
+    /// Returns whether the chunk producers may omit the chunks of idle
+    /// shards at the protocol version. With `SkipEmptyChunks`, it's only
+    /// allowed on the benchmark networks, so that a missed chunk is never
+    /// counted as produced on the other chains.
+    pub fn skips_empty_chunks(
+        epoch_manager: &dyn EpochManagerAdapter,
+        protocol_version: ProtocolVersion,
+    ) -> bool {
+        ProtocolFeature::SkipEmptyChunks.enabled(protocol_version)
+            && epoch_manager.chain_id().starts_with(near_primitives::chains::BENCHMARKNET)
+    }
+
+    /// Returns the chunk mask of the block as the epoch manager counts the
+    /// produced chunks. Where the chunk producers may omit the chunks of idle
+    /// shards, the chunks missing on top of a block without receipts count as
+    /// produced empty chunks. Whether the chunk producer had transactions
+    /// can't be told, they count as well.
+    pub fn chunk_production_mask(
+        epoch_manager: &dyn EpochManagerAdapter,
+        header: &BlockHeader,
+        prev_header: &BlockHeader,
+    ) -> Result<Vec<bool>, Error> {
+        let protocol_version = epoch_manager.get_epoch_protocol_version(header.epoch_id())?;
+        if Self::skips_empty_chunks(epoch_manager, protocol_version)
+            && Self::block_has_no_receipts(epoch_manager, prev_header)?
+        {
+            return Ok(vec![true; header.chunk_mask().len()]);
+        }
+        Ok(header.chunk_mask().to_vec())
+    }
 }
 
 /// Sandbox node specific operations
@@ -4472,7 +4546,12 @@ impl Chain {
 /// Epoch sync specific functions.
 #[cfg(feature = "new_epoch_sync")]
 impl Chain {
-    /// TODO(posvyatokum): validate `epoch_sync_info` before `store_update` commit.
+    /// The epoch doesn't have to follow an epoch known to this node. If the block
+    /// merkle tree of the last block of the previous epoch is missing, the one
+    /// from `epoch_sync_info` is used, after checking that the block merkle
+    /// trees built on top of it match the roots in the epoch headers.
+    ///
+    /// TODO(posvyatokum): validate the rest of `epoch_sync_info` before `store_update` commit.
     pub fn validate_and_record_epoch_sync_info(
         &mut self,
         epoch_sync_info: &EpochSyncInfo,
@@ -4503,12 +4582,28 @@ impl Chain {
 
         // construct and save all new BlockMerkleTree's
 
-        let mut cur_block_merkle_tree = (*chain_store_update
-            .get_block_merkle_tree(epoch_sync_info.get_epoch_first_header()?.prev_hash())?)
-        .clone();
         let mut prev_hash = epoch_sync_info.get_epoch_first_header()?.prev_hash();
+        let mut cur_block_merkle_tree = match chain_store_update.get_block_merkle_tree(prev_hash) {
+            Ok(block_merkle_tree) => (*block_merkle_tree).clone(),
+            // The previous epoch is unknown, e.g. epoch sync started from this epoch.
+            Err(Error::DBNotFoundErr(_)) => {
+                epoch_sync_info.prev_epoch_last_block_merkle_tree.clone()
+            }
+            Err(err) => return Err(err.into()),
+        };
         for hash in &epoch_sync_info.all_block_hashes {
             cur_block_merkle_tree.insert(*prev_hash);
+            // The roots in the headers commit to all the previous block hashes,
+            // so checking them validates `all_block_hashes` as well.
+            if let Some(header) = epoch_sync_info.headers.get(hash) {
+                if header.block_merkle_root() != &cur_block_merkle_tree.root() {
+                    return Err(PrimitivesEpochSyncInfoError::InvalidBlockMerkleRoot {
+                        hash: *hash,
+                        epoch_height: epoch_sync_info.epoch_info.epoch_height(),
+                    }
+                    .into());
+                }
+            }
             chain_store_update.save_block_merkle_tree(*hash, cur_block_merkle_tree.clone());
             prev_hash = hash;
         }
@@ -364,6 +364,12 @@ impl<'a> ChainUpdate<'a> {
                 self.chain_store_update.merge(store_update);
 
                 self.chain_store_update.save_trie_changes(apply_result.trie_changes);
+                self.chain_store_update.save_account_index(
+                    block,
+                    shard_id,
+                    &apply_result.outcomes,
+                    &apply_result.outgoing_receipts,
+                )?;
                 self.chain_store_update.save_outgoing_receipt(
                     block_hash,
                     shard_id,
@@ -503,9 +509,14 @@ impl<'a> ChainUpdate<'a> {
             self.chain_store_update.get_block_header(last_final_block)?.height()
         };
 
-        let epoch_manager_update = self
-            .epoch_manager
-            .add_validator_proposals(BlockHeaderInfo::new(block.header(), last_finalized_height))?;
+        let prev_header = self.chain_store_update.get_block_header(block.header().prev_hash())?;
+        let mut block_header_info = BlockHeaderInfo::new(block.header(), last_finalized_height);
+        block_header_info.chunk_mask = Chain::chunk_production_mask(
+            self.epoch_manager.as_ref(),
+            block.header(),
+            &prev_header,
+        )?;
+        let epoch_manager_update = self.epoch_manager.add_validator_proposals(block_header_info)?;
         self.chain_store_update.merge(epoch_manager_update);
 
         #[cfg(feature = "new_epoch_sync")]
@@ -1096,6 +1107,11 @@ impl<'a> ChainUpdate<'a> {
         let next_epoch_id = self.epoch_manager.get_next_epoch_id(last_block_info.hash())?;
         let next_next_epoch_id = near_primitives::types::EpochId(*last_block_info.hash());
 
+        let epoch_first_header = &headers[last_block_info.epoch_first_block()];
+        let prev_epoch_last_block_merkle_tree =
+            (*self.chain_store_update.get_block_merkle_tree(epoch_first_header.prev_hash())?)
+                .clone();
+
         Ok(EpochSyncInfo {
             all_block_hashes,
             headers,
@@ -1105,6 +1121,7 @@ impl<'a> ChainUpdate<'a> {
             next_epoch_info: (*self.epoch_manager.get_epoch_info(&next_epoch_id)?).clone(),
             next_next_epoch_info: (*self.epoch_manager.get_epoch_info(&next_next_epoch_id)?)
                 .clone(),
+            prev_epoch_last_block_merkle_tree,
         })
     }
 }
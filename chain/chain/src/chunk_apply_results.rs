@@ -0,0 +1,123 @@
+//! Subscriptions to the results of applying the chunks, for the projects
+//! embedding the client (custom indexers, analytics nodes) that need the state
+//! changes and the outcomes of the chunks as they are applied, without going
+//! through the indexer.
+use crate::update_shard::ShardUpdateResult;
+use near_async::messaging::Sender;
+use near_primitives::hash::CryptoHash;
+use near_primitives::receipt::Receipt;
+use near_primitives::shard_layout::ShardUId;
+use near_primitives::transaction::ExecutionOutcomeWithId;
+use near_primitives::types::{Balance, BlockHeight, Gas, RawStateChangesWithTrieKey, StateRoot};
+use std::sync::{Arc, RwLock};
+
+/// Result of updating a shard for a block, delivered to the subscribers once
+/// the block is saved.
+#[derive(Clone, Debug)]
+pub struct ChunkApplyResult {
+    pub block_hash: CryptoHash,
+    pub block_height: BlockHeight,
+    pub shard_uid: ShardUId,
+    /// Whether the block has a new chunk for the shard. Otherwise, the chunk
+    /// of the previous block is carried over, with no transactions and
+    /// receipts applied.
+    pub is_new_chunk: bool,
+    pub new_root: StateRoot,
+    pub state_changes: Vec<RawStateChangesWithTrieKey>,
+    pub outcomes: Vec<ExecutionOutcomeWithId>,
+    pub outgoing_receipts: Vec<Receipt>,
+    pub total_gas_burnt: Gas,
+    pub total_balance_burnt: Balance,
+}
+
+impl ChunkApplyResult {
+    /// Returns None for the resharding results, which have no chunk applied.
+    pub(crate) fn new(
+        block_hash: CryptoHash,
+        block_height: BlockHeight,
+        result: &ShardUpdateResult,
+    ) -> Option<Self> {
+        let (shard_uid, is_new_chunk, apply_result) = match result {
+            ShardUpdateResult::NewChunk(result) => (result.shard_uid, true, &result.apply_result),
+            ShardUpdateResult::OldChunk(result) => (result.shard_uid, false, &result.apply_result),
+            ShardUpdateResult::Resharding(_) => return None,
+        };
+        Some(Self {
+            block_hash,
+            block_height,
+            shard_uid,
+            is_new_chunk,
+            new_root: apply_result.new_root,
+            state_changes: apply_result.trie_changes.state_changes().to_vec(),
+            outcomes: apply_result.outcomes.clone(),
+            outgoing_receipts: apply_result.outgoing_receipts.clone(),
+            total_gas_burnt: apply_result.total_gas_burnt,
+            total_balance_burnt: apply_result.total_balance_burnt,
+        })
+    }
+}
+
+/// Senders registered for the results of applying the chunks of the shards
+/// the node tracks. Cloning shares the subscriptions.
+#[derive(Clone, Default)]
+pub struct ChunkApplyResults {
+    subscribers: Arc<RwLock<Vec<Sender<ChunkApplyResult>>>>,
+}
+
+impl ChunkApplyResults {
+    /// Registers the sender for the results of the blocks processed from now
+    /// on. The results are sent from the client thread, so the sender should
+    /// only queue them.
+    pub fn subscribe(&self, sender: Sender<ChunkApplyResult>) {
+        self.subscribers.write().unwrap().push(sender);
+    }
+
+    pub(crate) fn has_subscribers(&self) -> bool {
+        !self.subscribers.read().unwrap().is_empty()
+    }
+
+    pub(crate) fn notify(&self, results: Vec<ChunkApplyResult>) {
+        let subscribers = self.subscribers.read().unwrap();
+        for result in results {
+            for subscriber in subscribers.iter() {
+                subscriber.send(result.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(block_height: BlockHeight) -> ChunkApplyResult {
+        ChunkApplyResult {
+            block_hash: CryptoHash::default(),
+            block_height,
+            shard_uid: ShardUId::single_shard(),
+            is_new_chunk: true,
+            new_root: StateRoot::default(),
+            state_changes: vec![],
+            outcomes: vec![],
+            outgoing_receipts: vec![],
+            total_gas_burnt: 0,
+            total_balance_burnt: 0,
+        }
+    }
+
+    #[test]
+    fn test_chunk_apply_results() {
+        let results = ChunkApplyResults::default();
+        assert!(!results.has_subscribers());
+        let received = Arc::new(RwLock::new(vec![]));
+        for _ in 0..2 {
+            let received = received.clone();
+            results.clone().subscribe(Sender::from_fn(move |result: ChunkApplyResult| {
+                received.write().unwrap().push(result.block_height)
+            }));
+        }
+        assert!(results.has_subscribers());
+        results.notify(vec![result(1), result(2)]);
+        assert_eq!(*received.read().unwrap(), vec![1, 1, 2, 2]);
+    }
+}
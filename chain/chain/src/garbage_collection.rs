@@ -2,7 +2,8 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::{fmt, io};
 
-use near_chain_configs::GCConfig;
+use borsh::BorshDeserialize;
+use near_chain_configs::{GCColumnGroup, GCConfig};
 use near_chain_primitives::Error;
 use near_epoch_manager::EpochManagerAdapter;
 use near_primitives::block::Block;
@@ -12,9 +13,9 @@ use near_primitives::state_sync::{StateHeaderKey, StatePartKey};
 use near_primitives::types::{BlockHeight, BlockHeightDelta, EpochId, NumBlocks, ShardId};
 use near_primitives::utils::{get_block_shard_id, get_outcome_id_block_hash, index_to_bytes};
 use near_store::flat::store_helper;
-use near_store::{DBCol, KeyForStateChanges, ShardTries, ShardUId};
+use near_store::{DBCol, KeyForStateChanges, ShardTries, ShardUId, COLUMN_GROUP_TAIL_KEY_PREFIX};
 
-use crate::types::RuntimeAdapter;
+use crate::types::{RuntimeAdapter, Tip};
 use crate::{metrics, Chain, ChainStore, ChainStoreAccess, ChainStoreUpdate};
 
 #[derive(Clone)]
@@ -85,6 +86,7 @@ impl ChainStore {
     // 4. Before actual clearing is started, Block Reference Map should be built.
     // 5. `clear_data()` executes every time when block at new height is added.
     // 6. In case of State Sync, State Sync Clearing happens.
+    // 7. Column groups with their own retention are cleared separately by Column Group Clearing.
     //
     // Forks Clearing:
     // 1. Any fork which ends up on height `height` INCLUSIVELY and earlier will be completely deleted
@@ -132,6 +134,15 @@ impl ChainStore {
     //    and the Trie is updated with having only Genesis data.
     // 4. State Sync Clearing happens in `reset_data_pre_state_sync()`.
     //
+    // Column Group Clearing:
+    // 1. Canonical Chain Clearing skips the columns of the groups configured with their own
+    //    number of epochs to keep, Forks Clearing and State Sync Clearing don't.
+    // 2. Each group has its own tail and GC Stop Height, which may be lower or higher than
+    //    the ones of the chain.  The data of the group is deleted for the canonical blocks
+    //    from the group tail up to the group GC Stop Height EXCLUSIVELY.
+    // 3. The canonical blocks are found by `DBCol::BlockHeight`, which is never garbage
+    //    collected, so the data of the group can be deleted after the blocks themselves.
+    //
     pub fn clear_data(
         &mut self,
         gc_config: &GCConfig,
@@ -162,7 +173,10 @@ impl ChainStore {
             chain_store_update.commit()?;
             fork_tail = gc_stop_height;
         }
+        self.clear_column_groups_data(gc_config, runtime_adapter.as_ref(), &head)?;
         let mut gc_blocks_remaining = gc_config.gc_blocks_limit;
+        let kept_column_groups: Vec<_> =
+            gc_config.gc_column_groups().map(|(group, _)| group).collect();
 
         // Forks Cleaning
         let gc_fork_clean_step = gc_config.gc_fork_clean_step;
@@ -205,10 +219,11 @@ impl ChainStore {
                     break;
                 } else if prev_block_refcount == 1 {
                     debug_assert_eq!(blocks_current_height.len(), 1);
-                    chain_store_update.clear_block_data(
+                    chain_store_update.clear_block_data_keeping_column_groups(
                         epoch_manager.as_ref(),
                         *block_hash,
                         GCMode::Canonical(tries.clone()),
+                        &kept_column_groups,
                     )?;
                     chain_store_update.clear_resharding_data(
                         runtime.as_ref(),
@@ -222,6 +237,7 @@ impl ChainStore {
                     ));
                 }
             }
+            chain_store_update.gc_equivocation_evidence(height)?;
             chain_store_update.update_tail(height)?;
             chain_store_update.commit()?;
         }
@@ -257,6 +273,53 @@ impl ChainStore {
         chain_store_update.commit()
     }
 
+    /// Garbage collects the data of the column groups configured with their own
+    /// number of epochs to keep, going through at most `gc_blocks_limit` heights
+    /// per group. The heights without blocks count as well, so that a run over
+    /// a long stretch of skipped heights stays bounded.
+    fn clear_column_groups_data(
+        &mut self,
+        gc_config: &GCConfig,
+        runtime_adapter: &dyn RuntimeAdapter,
+        head: &Tip,
+    ) -> Result<(), Error> {
+        for (group, num_epochs_to_keep) in gc_config.gc_column_groups() {
+            let gc_stop_height = runtime_adapter
+                .get_gc_stop_height_for_num_epochs(&head.last_block_hash, num_epochs_to_keep);
+            let mut height = self.column_group_tail(group)?;
+            let mut gc_blocks_remaining = gc_config.gc_blocks_limit;
+            let mut chain_store_update = self.store_update();
+            while height < gc_stop_height && gc_blocks_remaining > 0 {
+                match chain_store_update.get_block_hash_by_height(height) {
+                    Ok(block_hash) => chain_store_update.gc_column_group(group, &block_hash)?,
+                    // No block at this height on the canonical chain.
+                    Err(Error::DBNotFoundErr(_)) => {}
+                    Err(err) => return Err(err),
+                }
+                gc_blocks_remaining -= 1;
+                height += 1;
+            }
+            chain_store_update.update_column_group_tail(group, height)?;
+            chain_store_update.commit()?;
+            metrics::COLUMN_GROUP_TAIL_HEIGHT
+                .with_label_values(&[group.as_str()])
+                .set(height as i64);
+        }
+        Ok(())
+    }
+
+    /// Returns the lowest height whose data of the column group may not be
+    /// garbage collected yet.  Without a tail of its own, the group starts at
+    /// the tail of the chain.
+    fn column_group_tail(&self, group: GCColumnGroup) -> Result<BlockHeight, Error> {
+        let tail =
+            self.store().get_ser::<BlockHeight>(DBCol::BlockMisc, &column_group_tail_key(group))?;
+        match tail {
+            Some(tail) => Ok(tail),
+            None => self.tail(),
+        }
+    }
+
     fn clear_forks_data(
         &mut self,
         tries: ShardTries,
@@ -578,10 +641,22 @@ impl<'a> ChainStoreUpdate<'a> {
     // Clearing block data of `block_hash`, if on a fork.
     // Clearing block data of `block_hash.prev`, if on the Canonical Chain.
     pub fn clear_block_data(
+        &mut self,
+        epoch_manager: &dyn EpochManagerAdapter,
+        block_hash: CryptoHash,
+        gc_mode: GCMode,
+    ) -> Result<(), Error> {
+        self.clear_block_data_keeping_column_groups(epoch_manager, block_hash, gc_mode, &[])
+    }
+
+    // Same as `clear_block_data`, but keeps the data of the column groups,
+    // which is garbage collected separately.
+    fn clear_block_data_keeping_column_groups(
         &mut self,
         epoch_manager: &dyn EpochManagerAdapter,
         mut block_hash: CryptoHash,
         gc_mode: GCMode,
+        kept_column_groups: &[GCColumnGroup],
     ) -> Result<(), Error> {
         let mut store_update = self.store().store_update();
 
@@ -674,17 +749,16 @@ impl<'a> ChainStoreUpdate<'a> {
         self.gc_col(DBCol::NextBlockHashes, block_hash.as_bytes());
         self.gc_col(DBCol::ChallengedBlocks, block_hash.as_bytes());
         self.gc_col(DBCol::BlocksToCatchup, block_hash.as_bytes());
-        let storage_key = KeyForStateChanges::for_block(&block_hash);
-        let stored_state_changes: Vec<Box<[u8]>> = self
-            .store()
-            .iter_prefix(DBCol::StateChanges, storage_key.as_ref())
-            .map(|item| item.map(|(key, _)| key))
-            .collect::<io::Result<Vec<_>>>()?;
-        for key in stored_state_changes {
-            self.gc_col(DBCol::StateChanges, &key);
+        if !kept_column_groups.contains(&GCColumnGroup::StateChanges) {
+            self.gc_state_changes(&block_hash)?;
         }
         self.gc_col(DBCol::BlockRefCount, block_hash.as_bytes());
-        self.gc_outcomes(&block)?;
+        if !kept_column_groups.contains(&GCColumnGroup::TransactionResults) {
+            self.gc_outcomes(&block)?;
+        }
+        if !kept_column_groups.contains(&GCColumnGroup::AccountIndex) {
+            self.gc_account_index(&block_hash)?;
+        }
         match gc_mode {
             GCMode::StateSync { clear_block_info: false } => {}
             _ => self.gc_col(DBCol::BlockInfo, block_hash.as_bytes()),
@@ -775,17 +849,10 @@ impl<'a> ChainStoreUpdate<'a> {
         self.gc_col(DBCol::NextBlockHashes, block_hash.as_bytes());
         self.gc_col(DBCol::ChallengedBlocks, block_hash.as_bytes());
         self.gc_col(DBCol::BlocksToCatchup, block_hash.as_bytes());
-        let storage_key = KeyForStateChanges::for_block(&block_hash);
-        let stored_state_changes: Vec<Box<[u8]>> = self
-            .store()
-            .iter_prefix(DBCol::StateChanges, storage_key.as_ref())
-            .map(|item| item.map(|(key, _)| key))
-            .collect::<io::Result<Vec<_>>>()?;
-        for key in stored_state_changes {
-            self.gc_col(DBCol::StateChanges, &key);
-        }
+        self.gc_state_changes(&block_hash)?;
         self.gc_col(DBCol::BlockRefCount, block_hash.as_bytes());
         self.gc_outcomes(&block)?;
+        self.gc_account_index(&block_hash)?;
         self.gc_col(DBCol::BlockInfo, block_hash.as_bytes());
         self.gc_col(DBCol::StateDlInfos, block_hash.as_bytes());
 
@@ -926,6 +993,75 @@ impl<'a> ChainStoreUpdate<'a> {
         Ok(())
     }
 
+    fn gc_state_changes(&mut self, block_hash: &CryptoHash) -> Result<(), Error> {
+        let storage_key = KeyForStateChanges::for_block(block_hash);
+        let stored_state_changes: Vec<Box<[u8]>> = self
+            .store()
+            .iter_prefix(DBCol::StateChanges, storage_key.as_ref())
+            .map(|item| item.map(|(key, _)| key))
+            .collect::<io::Result<Vec<_>>>()?;
+        for key in stored_state_changes {
+            self.gc_col(DBCol::StateChanges, &key);
+        }
+        Ok(())
+    }
+
+    fn gc_account_index(&mut self, block_hash: &CryptoHash) -> Result<(), Error> {
+        // The keys are listed by the block hash followed by the shard id.
+        let stored_keys = self
+            .store()
+            .iter_prefix(DBCol::AccountIndexKeys, block_hash.as_ref())
+            .collect::<io::Result<Vec<_>>>()?;
+        for (key, value) in stored_keys {
+            for index_key in Vec::<Vec<u8>>::try_from_slice(&value)? {
+                self.gc_col(DBCol::AccountIndex, &index_key);
+            }
+            self.gc_col(DBCol::AccountIndexKeys, &key);
+        }
+        Ok(())
+    }
+
+    /// Deletes the data of the column group of the block, which doesn't have
+    /// to exist anymore.
+    fn gc_column_group(
+        &mut self,
+        group: GCColumnGroup,
+        block_hash: &CryptoHash,
+    ) -> Result<(), Error> {
+        match group {
+            GCColumnGroup::TransactionResults => {
+                // The outcome ids are keyed by the block hash followed by the shard id.
+                let stored_outcome_ids = self
+                    .store()
+                    .iter_prefix(DBCol::OutcomeIds, block_hash.as_ref())
+                    .collect::<io::Result<Vec<_>>>()?;
+                for (key, value) in stored_outcome_ids {
+                    for outcome_id in Vec::<CryptoHash>::try_from_slice(&value)? {
+                        self.gc_col(
+                            DBCol::TransactionResultForBlock,
+                            &get_outcome_id_block_hash(&outcome_id, block_hash),
+                        );
+                    }
+                    self.gc_col(DBCol::OutcomeIds, &key);
+                }
+            }
+            GCColumnGroup::StateChanges => self.gc_state_changes(block_hash)?,
+            GCColumnGroup::AccountIndex => self.gc_account_index(block_hash)?,
+        }
+        Ok(())
+    }
+
+    fn update_column_group_tail(
+        &mut self,
+        group: GCColumnGroup,
+        height: BlockHeight,
+    ) -> Result<(), Error> {
+        let mut store_update = self.store().store_update();
+        store_update.set_ser(DBCol::BlockMisc, &column_group_tail_key(group), &height)?;
+        self.merge(store_update);
+        Ok(())
+    }
+
     fn gc_col(&mut self, col: DBCol, key: &[u8]) {
         let mut store_update = self.store().store_update();
         match col {
@@ -1043,6 +1179,12 @@ impl<'a> ChainStoreUpdate<'a> {
             DBCol::LatestWitnessesByIndex => {
                 store_update.delete(col, key);
             }
+            DBCol::AccountIndex => {
+                store_update.delete(col, key);
+            }
+            DBCol::AccountIndexKeys => {
+                store_update.delete(col, key);
+            }
             DBCol::DbVersion
             | DBCol::BlockMisc
             | DBCol::_GCCount
@@ -1072,6 +1214,7 @@ impl<'a> ChainStoreUpdate<'a> {
             | DBCol::FlatStateDeltaMetadata
             | DBCol::FlatStorageStatus
             | DBCol::Misc
+            | DBCol::EquivocationEvidence
             => unreachable!(),
             #[cfg(feature = "new_epoch_sync")]
             DBCol::EpochSyncInfo => unreachable!(),
@@ -1079,3 +1222,7 @@ impl<'a> ChainStoreUpdate<'a> {
         self.merge(store_update);
     }
 }
+
+fn column_group_tail_key(group: GCColumnGroup) -> Vec<u8> {
+    [&COLUMN_GROUP_TAIL_KEY_PREFIX[..], group.as_str().as_bytes()].concat()
+}
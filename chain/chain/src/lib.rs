@@ -13,6 +13,7 @@ mod block_processing_utils;
 pub mod blocks_delay_tracker;
 pub mod chain;
 mod chain_update;
+pub mod chunk_apply_results;
 pub mod chunks_store;
 pub mod crypto_hash_timer;
 mod doomslug;
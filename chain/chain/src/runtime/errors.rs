@@ -59,6 +59,9 @@ impl QueryError {
             node_runtime::state_viewer::errors::ViewContractCodeError::NoContractCode {
                 contract_account_id,
             } => Self::NoContractCode { contract_account_id, block_height, block_hash },
+            node_runtime::state_viewer::errors::ViewContractCodeError::UnknownCodeHash {
+                code_hash,
+            } => Self::UnknownCodeHash { code_hash, block_height, block_hash },
         }
     }
 
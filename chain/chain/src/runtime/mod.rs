@@ -1,7 +1,8 @@
 use crate::types::{
     ApplyChunkBlockContext, ApplyChunkResult, ApplyChunkShardContext, ApplyResultForResharding,
-    PrepareTransactionsBlockContext, PrepareTransactionsChunkContext, PrepareTransactionsLimit,
-    PreparedTransactions, RuntimeAdapter, RuntimeStorageConfig, StorageDataSource, Tip,
+    BlockHeader, PrepareTransactionsBlockContext, PrepareTransactionsChunkContext,
+    PrepareTransactionsLimit, PreparedTransactions, RuntimeAdapter, RuntimeStorageConfig,
+    StorageDataSource, Tip,
 };
 use crate::Error;
 use borsh::BorshDeserialize;
@@ -12,7 +13,9 @@ use near_chain_configs::{
 };
 use near_crypto::PublicKey;
 use near_epoch_manager::{EpochManagerAdapter, EpochManagerHandle};
-use near_parameters::{ActionCosts, ExtCosts, RuntimeConfig, RuntimeConfigStore};
+use near_parameters::{
+    ActionCosts, ExtCosts, RuntimeConfig, RuntimeConfigStore, StorageUsageConfig,
+};
 use near_pool::types::TransactionGroupIterator;
 use near_primitives::account::{AccessKey, Account};
 use near_primitives::apply::ApplyChunkReason;
@@ -24,26 +27,28 @@ use near_primitives::receipt::{DelayedReceiptIndices, Receipt};
 use near_primitives::runtime::migration_data::{MigrationData, MigrationFlags};
 use near_primitives::sandbox::state_patch::SandboxStatePatch;
 use near_primitives::shard_layout::{
-    account_id_to_shard_id, account_id_to_shard_uid, ShardLayout, ShardUId,
+    account_id_to_shard_id, account_id_to_shard_uid, get_block_shard_uid, ShardLayout, ShardUId,
 };
 use near_primitives::state_part::PartId;
-use near_primitives::transaction::SignedTransaction;
+use near_primitives::transaction::{ExecutionOutcomeWithId, SignedTransaction};
 use near_primitives::trie_key::TrieKey;
+use near_primitives::types::chunk_extra::ChunkExtra;
 use near_primitives::types::{
     AccountId, Balance, BlockHeight, EpochHeight, EpochId, EpochInfoProvider, Gas, MerkleHash,
     ShardId, StateChangeCause, StateChangesForResharding, StateRoot, StateRootNode,
 };
 use near_primitives::version::{ProtocolFeature, ProtocolVersion};
 use near_primitives::views::{
-    AccessKeyInfoView, CallResult, ContractCodeView, QueryRequest, QueryResponse,
-    QueryResponseKind, ViewApplyState, ViewStateResult,
+    AccessKeyInfoView, AccessKeyList, AccessKeyMetadataView, AccountStateOverride, CallResult,
+    ContractCodeView, QueryRequest, QueryResponse, QueryResponseKind, StorageUsageView,
+    ViewApplyState, ViewStateResult,
 };
 use near_store::config::StateSnapshotType;
 use near_store::flat::FlatStorageManager;
 use near_store::metadata::DbKind;
 use near_store::{
     ApplyStatePartResult, DBCol, ShardTries, StateSnapshotConfig, Store, Trie, TrieConfig,
-    TrieUpdate, WrappedTrieChanges, COLD_HEAD_KEY,
+    TrieUpdate, WrappedTrieChanges, COLD_HEAD_KEY, HEAD_KEY,
 };
 use near_vm_runner::ContractCode;
 use near_vm_runner::{precompile_contract, ContractRuntimeCache, FilesystemContractRuntimeCache};
@@ -64,6 +69,13 @@ pub mod migrations;
 #[cfg(test)]
 mod tests;
 
+/// Number of the largest contract data keys returned by the storage usage
+/// query, unless requested otherwise.
+const DEFAULT_NUM_LARGEST_DATA_KEYS: u64 = 10;
+/// Maximum number of the largest contract data keys returned by the storage
+/// usage query, which bounds the memory used while iterating over the data.
+const MAX_NUM_LARGEST_DATA_KEYS: u64 = 1000;
+
 /// Defines Nightshade state transition and validator rotation.
 /// TODO: this possibly should be merged with the runtime cargo or at least reconciled on the interfaces.
 pub struct NightshadeRuntime {
@@ -97,6 +109,12 @@ impl NightshadeRuntime {
             Some(store) => store,
             None => RuntimeConfigStore::for_chain_id(&genesis_config.chain_id),
         };
+        let runtime_config_store = if genesis_config.congestion_control_overrides.is_empty() {
+            runtime_config_store
+        } else {
+            runtime_config_store
+                .with_congestion_control_overrides(&genesis_config.congestion_control_overrides)
+        };
 
         let runtime = Runtime::new();
         let trie_viewer = TrieViewer::new(trie_viewer_state_size_limit, max_gas_burnt_view);
@@ -504,7 +522,11 @@ impl NightshadeRuntime {
         Ok(())
     }
 
-    fn get_gc_stop_height_impl(&self, block_hash: &CryptoHash) -> Result<BlockHeight, Error> {
+    fn get_gc_stop_height_impl(
+        &self,
+        block_hash: &CryptoHash,
+        num_epochs_to_keep: u64,
+    ) -> Result<BlockHeight, Error> {
         let epoch_manager = self.epoch_manager.read();
         // an epoch must have a first block.
         let epoch_first_block = *epoch_manager.get_block_info(block_hash)?.epoch_first_block();
@@ -512,7 +534,7 @@ impl NightshadeRuntime {
         // maintain pointers to avoid cloning.
         let mut last_block_in_prev_epoch = *epoch_first_block_info.prev_hash();
         let mut epoch_start_height = epoch_first_block_info.height();
-        for _ in 0..self.gc_num_epochs_to_keep - 1 {
+        for _ in 0..num_epochs_to_keep.saturating_sub(1) {
             let epoch_first_block =
                 *epoch_manager.get_block_info(&last_block_in_prev_epoch)?.epoch_first_block();
             let epoch_first_block_info = epoch_manager.get_block_info(&epoch_first_block)?;
@@ -587,6 +609,41 @@ impl NightshadeRuntime {
 
         Ok(state_part)
     }
+
+    /// Compiles the most used contracts of the shards tracked at the head,
+    /// unless they are compiled already, so that their first execution after a
+    /// restart doesn't have to.  Loads the compiled contracts which are cached
+    /// on disk as well, so they are in the page cache.
+    pub fn prewarm_contracts(&self, limit: usize) -> Result<(), Error> {
+        let _span = tracing::debug_span!(target: "runtime", "prewarm_contracts", limit).entered();
+        let Some(head) = self.store.get_ser::<Tip>(DBCol::BlockMisc, HEAD_KEY)? else {
+            return Ok(());
+        };
+        let shard_layout = self.epoch_manager.get_shard_layout(&head.epoch_id)?;
+        let mut state_roots = HashMap::new();
+        for shard_uid in shard_layout.shard_uids() {
+            // Only the tracked shards have the chunk extras.
+            let key = get_block_shard_uid(&head.last_block_hash, &shard_uid);
+            if let Some(chunk_extra) = self.store.get_ser::<ChunkExtra>(DBCol::ChunkExtra, &key)? {
+                state_roots.insert(shard_uid, *chunk_extra.state_root());
+            }
+        }
+        let mut contract_codes = vec![];
+        for usage in self.compiled_contract_cache.most_used(limit) {
+            let shard_uid = account_id_to_shard_uid(&usage.account_id, &shard_layout);
+            let Some(state_root) = state_roots.get(&shard_uid) else {
+                continue;
+            };
+            let trie = self.tries.get_view_trie_for_shard(shard_uid, *state_root);
+            // The contract may have been redeployed since it was used.
+            match near_store::get_code(&trie, &usage.account_id, None)? {
+                Some(code) if *code.hash() == usage.code_hash => contract_codes.push(code),
+                _ => {}
+            }
+        }
+        info!(target: "runtime", num_contracts = contract_codes.len(), "Prewarming contracts");
+        self.precompile_contracts(&head.epoch_id, contract_codes)
+    }
 }
 
 fn format_total_gas_burnt(gas: Gas) -> String {
@@ -834,7 +891,7 @@ impl RuntimeAdapter for NightshadeRuntime {
                             prev_block.congestion_info.get(&receiving_shard)
                         {
                             let congestion_control = CongestionControl::new(
-                                runtime_config.congestion_control_config,
+                                runtime_config.shard_congestion_control_config(receiving_shard),
                                 congestion_info.congestion_info,
                                 congestion_info.missed_chunks_count,
                             );
@@ -908,7 +965,15 @@ impl RuntimeAdapter for NightshadeRuntime {
     }
 
     fn get_gc_stop_height(&self, block_hash: &CryptoHash) -> BlockHeight {
-        let result = self.get_gc_stop_height_impl(block_hash);
+        self.get_gc_stop_height_for_num_epochs(block_hash, self.gc_num_epochs_to_keep)
+    }
+
+    fn get_gc_stop_height_for_num_epochs(
+        &self,
+        block_hash: &CryptoHash,
+        num_epochs_to_keep: u64,
+    ) -> BlockHeight {
+        let result = self.get_gc_stop_height_impl(block_hash, num_epochs_to_keep);
         match result {
             Ok(gc_stop_height) => gc_stop_height,
             Err(error) => {
@@ -1020,6 +1085,25 @@ impl RuntimeAdapter for NightshadeRuntime {
                     block_hash: *block_hash,
                 })
             }
+            QueryRequest::ViewCodeByHash { code_hash } => {
+                let contract_code = self
+                    .view_code_by_hash(&shard_uid, *state_root, code_hash)
+                    .map_err(|err| {
+                        crate::near_chain_primitives::error::QueryError::from_view_contract_code_error(
+                            err,
+                            block_height,
+                            *block_hash,
+                        )
+                    })?;
+                Ok(QueryResponse {
+                    kind: QueryResponseKind::ViewCode(ContractCodeView {
+                        hash: *code_hash,
+                        code: contract_code.into_code(),
+                    }),
+                    block_height,
+                    block_hash: *block_hash,
+                })
+            }
             QueryRequest::CallFunction { account_id, method_name, args } => {
                 let mut logs = vec![];
                 let (epoch_height, current_protocol_version) = {
@@ -1089,25 +1173,73 @@ impl RuntimeAdapter for NightshadeRuntime {
                     block_hash: *block_hash,
                 })
             }
-            QueryRequest::ViewAccessKeyList { account_id } => {
-                let access_key_list =
-                    self.view_access_keys(&shard_uid, *state_root, account_id).map_err(|err| {
+            QueryRequest::ViewAccessKeyList { account_id, after_public_key, limit } => {
+                let limit = limit.map(|limit| usize::try_from(limit).unwrap_or(usize::MAX));
+                // One more key is read to tell whether there are more.
+                let mut access_key_list = self
+                    .view_access_keys(
+                        &shard_uid,
+                        *state_root,
+                        account_id,
+                        after_public_key.as_ref(),
+                        limit.map(|limit| limit.saturating_add(1)),
+                    )
+                    .map_err(|err| {
                         crate::near_chain_primitives::error::QueryError::from_view_access_key_error(
                             err,
                             block_height,
                             *block_hash,
                         )
                     })?;
+                let has_more = limit.is_some_and(|limit| access_key_list.len() > limit);
+                if let Some(limit) = limit {
+                    access_key_list.truncate(limit);
+                }
+                let keys = access_key_list
+                    .into_iter()
+                    .map(|(public_key, access_key)| AccessKeyInfoView {
+                        public_key,
+                        metadata: Some(AccessKeyMetadataView::new(&access_key, block_height)),
+                        access_key: access_key.into(),
+                    })
+                    .collect();
+                Ok(QueryResponse {
+                    kind: QueryResponseKind::AccessKeyList(AccessKeyList { keys, has_more }),
+                    block_height,
+                    block_hash: *block_hash,
+                })
+            }
+            QueryRequest::ViewStorageUsage { account_id, num_largest_data_keys } => {
+                let protocol_version =
+                    self.epoch_manager.get_epoch_protocol_version(epoch_id).map_err(|err| {
+                        crate::near_chain_primitives::error::QueryError::from_epoch_error(
+                            err,
+                            block_height,
+                            *block_hash,
+                        )
+                    })?;
+                let runtime_config = self.runtime_config_store.get_config(protocol_version);
+                let num_largest_data_keys = num_largest_data_keys
+                    .unwrap_or(DEFAULT_NUM_LARGEST_DATA_KEYS)
+                    .min(MAX_NUM_LARGEST_DATA_KEYS)
+                    as usize;
+                let storage_usage = self
+                    .view_storage_usage(
+                        &shard_uid,
+                        *state_root,
+                        account_id,
+                        &runtime_config.fees.storage_usage_config,
+                        num_largest_data_keys,
+                    )
+                    .map_err(|err| {
+                        crate::near_chain_primitives::error::QueryError::from_view_state_error(
+                            err,
+                            block_height,
+                            *block_hash,
+                        )
+                    })?;
                 Ok(QueryResponse {
-                    kind: QueryResponseKind::AccessKeyList(
-                        access_key_list
-                            .into_iter()
-                            .map(|(public_key, access_key)| AccessKeyInfoView {
-                                public_key,
-                                access_key: access_key.into(),
-                            })
-                            .collect(),
-                    ),
+                    kind: QueryResponseKind::StorageUsage(storage_usage),
                     block_height,
                     block_hash: *block_hash,
                 })
@@ -1131,6 +1263,62 @@ impl RuntimeAdapter for NightshadeRuntime {
         }
     }
 
+    fn simulate_transaction(
+        &self,
+        header: &BlockHeader,
+        state_roots: &HashMap<ShardUId, StateRoot>,
+        transaction: &SignedTransaction,
+        verify_signature: bool,
+        state_overrides: &[AccountStateOverride],
+    ) -> Result<Result<Vec<ExecutionOutcomeWithId>, InvalidTxError>, Error> {
+        let prev_block_hash = header.hash();
+        let epoch_id = self.epoch_manager.get_epoch_id_from_prev_block(prev_block_hash)?;
+        let epoch_height = self.epoch_manager.get_epoch_height_from_prev_block(prev_block_hash)?;
+        let current_protocol_version = self.epoch_manager.get_epoch_protocol_version(&epoch_id)?;
+        let mut apply_state = ApplyState {
+            apply_reason: None,
+            block_height: header.height() + 1,
+            prev_block_hash: *prev_block_hash,
+            // The next block doesn't exist yet.
+            block_hash: CryptoHash::default(),
+            // Set by the simulation for each receipt.
+            shard_id: 0,
+            epoch_id,
+            epoch_height,
+            gas_price: header.next_gas_price(),
+            block_timestamp: header.raw_timestamp(),
+            gas_limit: None,
+            random_seed: *header.random_value(),
+            current_protocol_version,
+            config: self.runtime_config_store.get_config(current_protocol_version).clone(),
+            cache: Some(self.compiled_contract_cache.handle()),
+            is_new_chunk: true,
+            migration_data: Arc::clone(&self.migration_data),
+            migration_flags: MigrationFlags::default(),
+            congestion_info: HashMap::new(),
+        };
+        let mut state_updates = state_roots
+            .iter()
+            .map(|(shard_uid, state_root)| {
+                (shard_uid.shard_id(), self.tries.new_trie_update_view(*shard_uid, *state_root))
+            })
+            .collect();
+        match self.runtime.simulate_transaction(
+            &mut state_updates,
+            &mut apply_state,
+            transaction,
+            verify_signature,
+            state_overrides,
+            self.epoch_manager.as_ref(),
+        ) {
+            Ok(outcomes) => Ok(Ok(outcomes)),
+            Err(RuntimeError::InvalidTxError(err)) => Ok(Err(err)),
+            Err(RuntimeError::StorageError(err)) => Err(Error::StorageError(err)),
+            Err(RuntimeError::ValidatorError(err)) => Err(err.into()),
+            Err(err) => Err(Error::Other(format!("Simulation failed: {err:?}"))),
+        }
+    }
+
     // Wrapper to get the metrics.
     fn obtain_state_part(
         &self,
@@ -1319,6 +1507,10 @@ impl RuntimeAdapter for NightshadeRuntime {
         Ok(ProtocolConfig { genesis_config, runtime_config })
     }
 
+    fn get_runtime_config(&self, protocol_version: ProtocolVersion) -> &RuntimeConfig {
+        self.runtime_config_store.get_config(protocol_version)
+    }
+
     fn will_shard_layout_change_next_epoch(&self, parent_hash: &CryptoHash) -> Result<bool, Error> {
         let epoch_manager = self.epoch_manager.read();
         Ok(epoch_manager.will_shard_layout_change(parent_hash)?)
@@ -1337,7 +1529,7 @@ fn chunk_tx_gas_limit(
     if ProtocolFeature::CongestionControl.enabled(protocol_version) {
         if let Some(own_congestion) = prev_block.congestion_info.get(&shard_id) {
             let congestion_control = CongestionControl::new(
-                runtime_config.congestion_control_config,
+                runtime_config.shard_congestion_control_config(shard_id),
                 own_congestion.congestion_info,
                 own_congestion.missed_chunks_count,
             );
@@ -1347,7 +1539,7 @@ fn chunk_tx_gas_limit(
             // Using the default (no congestion) is a reasonable choice in this case.
             let own_congestion = ExtendedCongestionInfo::default();
             let congestion_control = CongestionControl::new(
-                runtime_config.congestion_control_config,
+                runtime_config.shard_congestion_control_config(shard_id),
                 own_congestion.congestion_info,
                 own_congestion.missed_chunks_count,
             );
@@ -1419,6 +1611,16 @@ impl node_runtime::adapter::ViewRuntimeAdapter for NightshadeRuntime {
         )
     }
 
+    fn view_code_by_hash(
+        &self,
+        shard_uid: &ShardUId,
+        state_root: MerkleHash,
+        code_hash: &CryptoHash,
+    ) -> Result<ContractCode, node_runtime::state_viewer::errors::ViewContractCodeError> {
+        let state_update = self.tries.new_trie_update_view(*shard_uid, state_root);
+        self.trie_viewer.view_code_by_hash(&state_update, code_hash)
+    }
+
     fn view_access_key(
         &self,
         shard_uid: &ShardUId,
@@ -1435,10 +1637,12 @@ impl node_runtime::adapter::ViewRuntimeAdapter for NightshadeRuntime {
         shard_uid: &ShardUId,
         state_root: MerkleHash,
         account_id: &AccountId,
+        after_public_key: Option<&PublicKey>,
+        limit: Option<usize>,
     ) -> Result<Vec<(PublicKey, AccessKey)>, node_runtime::state_viewer::errors::ViewAccessKeyError>
     {
         let state_update = self.tries.new_trie_update_view(*shard_uid, state_root);
-        self.trie_viewer.view_access_keys(&state_update, account_id)
+        self.trie_viewer.view_access_keys(&state_update, account_id, after_public_key, limit)
     }
 
     fn view_state(
@@ -1452,4 +1656,21 @@ impl node_runtime::adapter::ViewRuntimeAdapter for NightshadeRuntime {
         let state_update = self.tries.new_trie_update_view(*shard_uid, state_root);
         self.trie_viewer.view_state(&state_update, account_id, prefix, include_proof)
     }
+
+    fn view_storage_usage(
+        &self,
+        shard_uid: &ShardUId,
+        state_root: MerkleHash,
+        account_id: &AccountId,
+        storage_usage_config: &StorageUsageConfig,
+        num_largest_data_keys: usize,
+    ) -> Result<StorageUsageView, node_runtime::state_viewer::errors::ViewStateError> {
+        let state_update = self.tries.new_trie_update_view(*shard_uid, state_root);
+        self.trie_viewer.view_storage_usage(
+            &state_update,
+            account_id,
+            storage_usage_config,
+            num_largest_data_keys,
+        )
+    }
 }
@@ -0,0 +1,351 @@
+//! Optional index of the transactions and receipts involving every account,
+//! kept when `save_account_index` is enabled in the client config.
+//!
+//! A transaction is indexed under its signer and receiver, a receipt under the
+//! account executing it and the account sending it. The entries are garbage
+//! collected along with their block, or later when the `account_index` column
+//! group has its own number of epochs to keep.
+
+use borsh::BorshDeserialize;
+use near_chain_primitives::error::Error;
+use near_primitives::block::Block;
+use near_primitives::hash::CryptoHash;
+use near_primitives::receipt::Receipt;
+use near_primitives::transaction::ExecutionOutcomeWithId;
+use near_primitives::types::{AccountId, BlockHeight, ShardId};
+use near_primitives::utils::{get_block_shard_id, index_to_bytes};
+use near_primitives::views::{AccountIndexEntryKind, AccountIndexEntryView};
+use near_store::DBCol;
+use std::collections::HashSet;
+
+use crate::ChainStoreAccess;
+
+use super::{ChainStore, ChainStoreUpdate};
+
+/// Length of the part of the key after the account id prefix: the height, the
+/// id and the block hash.
+const KEY_SUFFIX_LEN: usize = 8 + 32 + 32;
+
+/// Prefix of the keys of the account in `DBCol::AccountIndex`. The account id
+/// is terminated by a zero byte, which never appears in account ids, so that
+/// the entries of `alice` don't share the prefix with the ones of `alice.near`.
+fn account_prefix(account_id: &AccountId) -> Vec<u8> {
+    [account_id.as_str().as_bytes(), &[0]].concat()
+}
+
+/// Key of an entry in `DBCol::AccountIndex`. The big-endian height follows the
+/// account id so that the entries of an account are iterated in the order of
+/// heights. The block hash keeps apart the entries of the same transaction or
+/// receipt included in blocks on different forks.
+fn entry_key(
+    account_id: &AccountId,
+    height: BlockHeight,
+    id: &CryptoHash,
+    block_hash: &CryptoHash,
+) -> Vec<u8> {
+    let prefix = account_prefix(account_id);
+    [prefix.as_slice(), &height.to_be_bytes(), id.as_ref(), block_hash.as_ref()].concat()
+}
+
+impl ChainStore {
+    /// Returns up to `limit` entries of the account in the order of heights,
+    /// only the ones after the `(height, id)` cursor if given. The entries in
+    /// blocks which are not on the canonical chain are skipped.
+    pub fn get_account_index(
+        &self,
+        account_id: &AccountId,
+        after: Option<(BlockHeight, CryptoHash)>,
+        limit: usize,
+    ) -> Result<Vec<AccountIndexEntryView>, std::io::Error> {
+        let prefix = account_prefix(account_id);
+        let lower_bound = match &after {
+            Some((height, id)) => [prefix.as_slice(), &height.to_be_bytes(), id.as_ref()].concat(),
+            None => prefix.clone(),
+        };
+        let upper_bound = [account_id.as_str().as_bytes(), &[1]].concat();
+        let mut entries = vec![];
+        for item in
+            self.store().iter_range(DBCol::AccountIndex, Some(&lower_bound), Some(&upper_bound))
+        {
+            if entries.len() >= limit {
+                break;
+            }
+            let (key, value) = item?;
+            let suffix = &key[prefix.len()..];
+            if suffix.len() != KEY_SUFFIX_LEN {
+                continue;
+            }
+            let block_height = BlockHeight::from_be_bytes(suffix[..8].try_into().unwrap());
+            let id = CryptoHash::try_from(&suffix[8..40]).unwrap();
+            let block_hash = CryptoHash::try_from(&suffix[40..]).unwrap();
+            if after == Some((block_height, id)) {
+                continue;
+            }
+            let canonical_hash = self
+                .store()
+                .get_ser::<CryptoHash>(DBCol::BlockHeight, &index_to_bytes(block_height))?;
+            if canonical_hash != Some(block_hash) {
+                continue;
+            }
+            let kind = AccountIndexEntryKind::try_from_slice(&value)?;
+            entries.push(AccountIndexEntryView { id, kind, block_height, block_hash });
+        }
+        Ok(entries)
+    }
+}
+
+impl<'a> ChainStoreUpdate<'a> {
+    /// Indexes the transactions of the new chunk of the shard in the block,
+    /// the receipts executed when applying it and the receipts it sends. Does
+    /// nothing unless the node keeps the account index.
+    pub fn save_account_index(
+        &mut self,
+        block: &Block,
+        shard_id: ShardId,
+        outcomes: &[ExecutionOutcomeWithId],
+        outgoing_receipts: &[Receipt],
+    ) -> Result<(), Error> {
+        if !self.chain_store.save_account_index {
+            return Ok(());
+        }
+        let block_hash = block.hash();
+        let height = block.header().height();
+        let chunk_header = &block.chunks()[shard_id as usize];
+        let chunk = self.get_chunk(&chunk_header.chunk_hash())?;
+
+        let mut entries = vec![];
+        let mut transaction_hashes = HashSet::new();
+        for transaction in chunk.transactions() {
+            let hash = transaction.get_hash();
+            transaction_hashes.insert(hash);
+            let signer_id = transaction.transaction.signer_id();
+            let receiver_id = transaction.transaction.receiver_id();
+            entries.push((signer_id.clone(), hash, AccountIndexEntryKind::Transaction));
+            if receiver_id != signer_id {
+                entries.push((receiver_id.clone(), hash, AccountIndexEntryKind::Transaction));
+            }
+        }
+        for outcome in outcomes {
+            if !transaction_hashes.contains(&outcome.id) {
+                entries.push((
+                    outcome.outcome.executor_id.clone(),
+                    outcome.id,
+                    AccountIndexEntryKind::Receipt,
+                ));
+            }
+        }
+        // The refunds are sent by the system account, which would otherwise
+        // get an entry for nearly every receipt.
+        for receipt in
+            outgoing_receipts.iter().filter(|receipt| !receipt.predecessor_id().is_system())
+        {
+            entries.push((
+                receipt.predecessor_id().clone(),
+                *receipt.receipt_id(),
+                AccountIndexEntryKind::Receipt,
+            ));
+        }
+
+        let mut store_update = self.store().store_update();
+        let mut keys = Vec::with_capacity(entries.len());
+        for (account_id, id, kind) in entries {
+            let key = entry_key(&account_id, height, &id, block_hash);
+            store_update.set_ser(DBCol::AccountIndex, &key, &kind)?;
+            keys.push(key);
+        }
+        // The keys are listed per block, so that they can be garbage
+        // collected without knowing the accounts.
+        store_update.set_ser(
+            DBCol::AccountIndexKeys,
+            &get_block_shard_id(block_hash, shard_id),
+            &keys,
+        )?;
+        self.merge(store_update);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::get_chain;
+    use near_async::time::Clock;
+    use near_crypto::{InMemorySigner, KeyType};
+    use near_primitives::receipt::{ActionReceipt, ReceiptEnum, ReceiptPriority, ReceiptV0};
+    use near_primitives::sharding::{ShardChunk, ShardChunkV2};
+    use near_primitives::transaction::{ExecutionOutcome, SignedTransaction};
+    use near_store::test_utils::create_test_store;
+
+    fn save_entry(
+        chain_store: &ChainStore,
+        account_id: &AccountId,
+        height: BlockHeight,
+        id: &CryptoHash,
+        block_hash: &CryptoHash,
+    ) {
+        let mut store_update = chain_store.store().store_update();
+        store_update
+            .set_ser(
+                DBCol::AccountIndex,
+                &entry_key(account_id, height, id, block_hash),
+                &AccountIndexEntryKind::Transaction,
+            )
+            .unwrap();
+        store_update.commit().unwrap();
+    }
+
+    fn set_canonical(chain_store: &ChainStore, height: BlockHeight, block_hash: &CryptoHash) {
+        let mut store_update = chain_store.store().store_update();
+        store_update.set_ser(DBCol::BlockHeight, &index_to_bytes(height), block_hash).unwrap();
+        store_update.commit().unwrap();
+    }
+
+    #[test]
+    fn test_account_index() {
+        let chain_store = ChainStore::new(create_test_store(), 0, true);
+        let alice: AccountId = "alice".parse().unwrap();
+        let alice_near: AccountId = "alice.near".parse().unwrap();
+        let block = |height: BlockHeight| CryptoHash::hash_bytes(&height.to_le_bytes());
+        let fork_block = CryptoHash::hash_bytes(b"fork");
+        let ids: Vec<_> = (0..4u8).map(|i| CryptoHash::hash_bytes(&[i])).collect();
+        for height in [10, 20] {
+            set_canonical(&chain_store, height, &block(height));
+        }
+        save_entry(&chain_store, &alice, 20, &ids[0], &block(20));
+        save_entry(&chain_store, &alice, 10, &ids[1], &block(10));
+        save_entry(&chain_store, &alice, 10, &ids[2], &block(10));
+        save_entry(&chain_store, &alice_near, 10, &ids[3], &block(10));
+        // The entries in blocks not on the canonical chain are skipped.
+        save_entry(&chain_store, &alice, 20, &ids[3], &fork_block);
+
+        let ids_of = |after, limit| {
+            chain_store
+                .get_account_index(&alice, after, limit)
+                .unwrap()
+                .iter()
+                .map(|entry| (entry.block_height, entry.id))
+                .collect::<Vec<_>>()
+        };
+        let mut heights_and_ids = vec![(10, ids[1]), (10, ids[2]), (20, ids[0])];
+        heights_and_ids[..2].sort();
+        assert_eq!(ids_of(None, 10), heights_and_ids);
+        assert_eq!(ids_of(None, 2), heights_and_ids[..2].to_vec());
+        assert_eq!(ids_of(Some(heights_and_ids[1]), 10), heights_and_ids[2..].to_vec());
+        assert_eq!(ids_of(Some(heights_and_ids[2]), 10), vec![]);
+    }
+
+    /// Indexes a chunk of the transactions and the receipts from applying it,
+    /// as done when the block is processed.
+    #[test]
+    fn test_save_account_index() {
+        let mut chain = get_chain(Clock::real());
+        let genesis = chain.get_block_by_height(0).unwrap();
+        let [alice, bob, carol]: [AccountId; 3] =
+            ["alice", "bob", "carol"].map(|account_id| account_id.parse().unwrap());
+        let signer = InMemorySigner::from_seed(alice.clone(), KeyType::ED25519, "alice");
+        let transfer = SignedTransaction::send_money(
+            1,
+            alice.clone(),
+            bob.clone(),
+            &signer,
+            100,
+            *genesis.hash(),
+        );
+        let self_transfer = SignedTransaction::send_money(
+            2,
+            alice.clone(),
+            alice.clone(),
+            &signer,
+            100,
+            *genesis.hash(),
+        );
+        let chunk_header = genesis.chunks()[0].clone();
+        let chunk = ShardChunk::V2(ShardChunkV2 {
+            chunk_hash: chunk_header.chunk_hash(),
+            header: chunk_header,
+            transactions: vec![transfer.clone(), self_transfer.clone()],
+            prev_outgoing_receipts: vec![],
+        });
+
+        // The outcomes of the transactions are not indexed as receipts.
+        let outcome = |id: CryptoHash, executor_id: &AccountId| ExecutionOutcomeWithId {
+            id,
+            outcome: ExecutionOutcome { executor_id: executor_id.clone(), ..Default::default() },
+        };
+        let executed_receipt_id = CryptoHash::hash_bytes(b"executed");
+        let outcomes = vec![
+            outcome(transfer.get_hash(), &alice),
+            outcome(self_transfer.get_hash(), &alice),
+            outcome(executed_receipt_id, &bob),
+        ];
+        let sent_receipt = Receipt::V0(ReceiptV0 {
+            predecessor_id: bob.clone(),
+            receiver_id: carol.clone(),
+            receipt_id: CryptoHash::hash_bytes(b"sent"),
+            receipt: ReceiptEnum::Action(ActionReceipt {
+                signer_id: bob.clone(),
+                signer_public_key: signer.public_key.clone(),
+                gas_price: 0,
+                output_data_receivers: vec![],
+                input_data_ids: vec![],
+                actions: vec![],
+            }),
+        });
+        let refund = Receipt::new_balance_refund(&alice, 1, ReceiptPriority::NoPriority);
+        let outgoing_receipts = vec![sent_receipt.clone(), refund.clone()];
+
+        let chain_store = chain.mut_chain_store();
+        chain_store.save_account_index = true;
+        let mut store_update = chain_store.store_update();
+        store_update.save_chunk(chunk);
+        store_update.save_account_index(&genesis, 0, &outcomes, &outgoing_receipts).unwrap();
+        store_update.commit().unwrap();
+
+        let entries = |account_id: &AccountId| {
+            let mut entries = chain_store
+                .get_account_index(account_id, None, 10)
+                .unwrap()
+                .into_iter()
+                .map(|entry| {
+                    assert_eq!(entry.block_hash, *genesis.hash());
+                    (entry.id, entry.kind)
+                })
+                .collect::<Vec<_>>();
+            entries.sort_by_key(|(id, _)| *id);
+            entries
+        };
+        let sorted = |mut entries: Vec<(CryptoHash, AccountIndexEntryKind)>| {
+            entries.sort_by_key(|(id, _)| *id);
+            entries
+        };
+        assert_eq!(
+            entries(&alice),
+            sorted(vec![
+                (transfer.get_hash(), AccountIndexEntryKind::Transaction),
+                (self_transfer.get_hash(), AccountIndexEntryKind::Transaction),
+            ])
+        );
+        assert_eq!(
+            entries(&bob),
+            sorted(vec![
+                (transfer.get_hash(), AccountIndexEntryKind::Transaction),
+                (executed_receipt_id, AccountIndexEntryKind::Receipt),
+                (*sent_receipt.receipt_id(), AccountIndexEntryKind::Receipt),
+            ])
+        );
+        // The receipts are indexed under the receiver once executed.
+        assert_eq!(entries(&carol), vec![]);
+        // The refunds of the system account are not indexed.
+        assert_eq!(entries(refund.predecessor_id()), vec![]);
+
+        let keys = chain_store
+            .store()
+            .get_ser::<Vec<Vec<u8>>>(
+                DBCol::AccountIndexKeys,
+                &get_block_shard_id(genesis.hash(), 0),
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(keys.len(), 5);
+    }
+}
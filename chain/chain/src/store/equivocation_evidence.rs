@@ -0,0 +1,236 @@
+//! Storage of the evidence of validators signing conflicting blocks or
+//! approvals, see `EquivocationEvidence`. Only the first evidence of a kind is
+//! kept per validator and height, and the evidence kept per validator is
+//! capped as well. The evidence is garbage collected with the blocks of its
+//! height.
+
+use borsh::BorshDeserialize;
+use near_primitives::equivocation::EquivocationEvidence;
+use near_primitives::types::{AccountId, BlockHeight};
+use near_store::DBCol;
+
+use crate::ChainStoreAccess;
+
+use super::{ChainStore, ChainStoreUpdate};
+
+/// Maximum number of the evidence kept per validator.
+const MAX_EQUIVOCATION_EVIDENCE_PER_ACCOUNT: usize = 100;
+
+/// Prefix of the keys of the evidence, followed by the big-endian height, the
+/// kind and the account id, so that the evidence is iterated and garbage
+/// collected in the order of heights.
+const EVIDENCE_KEY_PREFIX: u8 = 0;
+
+/// Prefix of the keys indexing the evidence by validator, followed by the
+/// length of the account id, the account id, the big-endian height and the
+/// kind. The values are empty.
+const ACCOUNT_KEY_PREFIX: u8 = 1;
+
+fn evidence_kind(evidence: &EquivocationEvidence) -> u8 {
+    match evidence {
+        EquivocationEvidence::Block { .. } => 0,
+        EquivocationEvidence::Approval { .. } => 1,
+    }
+}
+
+fn evidence_key_prefix(height: BlockHeight) -> Vec<u8> {
+    [[EVIDENCE_KEY_PREFIX].as_slice(), &height.to_be_bytes()].concat()
+}
+
+/// Key of the evidence in `DBCol::EquivocationEvidence`.
+fn evidence_key(evidence: &EquivocationEvidence) -> Vec<u8> {
+    [
+        evidence_key_prefix(evidence.height()).as_slice(),
+        &[evidence_kind(evidence)],
+        evidence.account_id().as_str().as_bytes(),
+    ]
+    .concat()
+}
+
+/// The account ids are at most 64 bytes long, and prefixed by their length so
+/// that the prefix of an account doesn't match the accounts it's a prefix of.
+fn account_key_prefix(account_id: &AccountId) -> Vec<u8> {
+    let account_id = account_id.as_str().as_bytes();
+    [[ACCOUNT_KEY_PREFIX, account_id.len() as u8].as_slice(), account_id].concat()
+}
+
+/// Key indexing the evidence by its validator in `DBCol::EquivocationEvidence`.
+fn account_key(evidence: &EquivocationEvidence) -> Vec<u8> {
+    [
+        account_key_prefix(evidence.account_id()).as_slice(),
+        &evidence.height().to_be_bytes(),
+        &[evidence_kind(evidence)],
+    ]
+    .concat()
+}
+
+impl ChainStore {
+    /// Saves the evidence, unless there already is evidence of the same kind
+    /// for the validator and height, or `MAX_EQUIVOCATION_EVIDENCE_PER_ACCOUNT`
+    /// of the validator. Returns whether the evidence was saved.
+    pub fn save_equivocation_evidence(
+        &self,
+        evidence: &EquivocationEvidence,
+    ) -> Result<bool, std::io::Error> {
+        let key = evidence_key(evidence);
+        if self.store().exists(DBCol::EquivocationEvidence, &key)? {
+            return Ok(false);
+        }
+        let account_key_prefix = account_key_prefix(evidence.account_id());
+        let num_evidence = self
+            .store()
+            .iter_prefix(DBCol::EquivocationEvidence, &account_key_prefix)
+            .take(MAX_EQUIVOCATION_EVIDENCE_PER_ACCOUNT)
+            .collect::<Result<Vec<_>, _>>()?
+            .len();
+        if num_evidence >= MAX_EQUIVOCATION_EVIDENCE_PER_ACCOUNT {
+            return Ok(false);
+        }
+        let mut store_update = self.store().store_update();
+        store_update.set(DBCol::EquivocationEvidence, &key, &borsh::to_vec(evidence)?);
+        store_update.set(DBCol::EquivocationEvidence, &account_key(evidence), &[]);
+        store_update.commit()?;
+        Ok(true)
+    }
+
+    /// Returns the stored evidence from the given height on, in the order of
+    /// heights.
+    pub fn get_equivocation_evidence(
+        &self,
+        from_height: BlockHeight,
+    ) -> Result<Vec<EquivocationEvidence>, std::io::Error> {
+        let lower_bound = evidence_key_prefix(from_height);
+        self.store()
+            .iter_range(
+                DBCol::EquivocationEvidence,
+                Some(&lower_bound),
+                Some(&[ACCOUNT_KEY_PREFIX]),
+            )
+            .map(|item| {
+                let (_, value) = item?;
+                EquivocationEvidence::try_from_slice(&value)
+            })
+            .collect()
+    }
+}
+
+impl<'a> ChainStoreUpdate<'a> {
+    /// Deletes the evidence at the height, once its blocks are garbage
+    /// collected.
+    pub(crate) fn gc_equivocation_evidence(
+        &mut self,
+        height: BlockHeight,
+    ) -> Result<(), std::io::Error> {
+        let mut store_update = self.store().store_update();
+        for item in
+            self.store().iter_prefix(DBCol::EquivocationEvidence, &evidence_key_prefix(height))
+        {
+            let (key, value) = item?;
+            let evidence = EquivocationEvidence::try_from_slice(&value)?;
+            store_update.delete(DBCol::EquivocationEvidence, &account_key(&evidence));
+            store_update.delete(DBCol::EquivocationEvidence, &key);
+        }
+        self.merge(store_update);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_primitives::block_header::Approval;
+    use near_primitives::hash::CryptoHash;
+    use near_primitives::test_utils::create_test_signer;
+    use near_primitives::types::EpochId;
+    use near_store::test_utils::create_test_store;
+
+    fn approval_evidence(account_id: &str, target_height: BlockHeight) -> EquivocationEvidence {
+        let signer = create_test_signer(account_id);
+        EquivocationEvidence::Approval {
+            epoch_id: EpochId::default(),
+            left: Approval::new(
+                CryptoHash::hash_bytes(b"a"),
+                target_height - 1,
+                target_height,
+                &signer,
+            ),
+            right: Approval::new(
+                CryptoHash::hash_bytes(b"b"),
+                target_height - 1,
+                target_height,
+                &signer,
+            ),
+        }
+    }
+
+    #[test]
+    fn test_equivocation_evidence() {
+        let chain_store = ChainStore::new(create_test_store(), 0, true);
+        assert!(chain_store.save_equivocation_evidence(&approval_evidence("bob", 300)).unwrap());
+        assert!(chain_store.save_equivocation_evidence(&approval_evidence("alice", 20)).unwrap());
+        assert!(chain_store.save_equivocation_evidence(&approval_evidence("alice", 300)).unwrap());
+        // Only the first evidence is kept per validator and height.
+        assert!(!chain_store.save_equivocation_evidence(&approval_evidence("bob", 300)).unwrap());
+
+        let heights_and_accounts = |from_height| {
+            chain_store
+                .get_equivocation_evidence(from_height)
+                .unwrap()
+                .iter()
+                .map(|evidence| (evidence.height(), evidence.account_id().to_string()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            heights_and_accounts(0),
+            vec![(20, "alice".to_string()), (300, "alice".to_string()), (300, "bob".to_string())]
+        );
+        assert_eq!(
+            heights_and_accounts(21),
+            vec![(300, "alice".to_string()), (300, "bob".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_equivocation_evidence_per_account_limit() {
+        let chain_store = ChainStore::new(create_test_store(), 0, true);
+        for height in 1..=MAX_EQUIVOCATION_EVIDENCE_PER_ACCOUNT as BlockHeight {
+            assert!(chain_store
+                .save_equivocation_evidence(&approval_evidence("alice", height))
+                .unwrap());
+        }
+        let next_height = MAX_EQUIVOCATION_EVIDENCE_PER_ACCOUNT as BlockHeight + 1;
+        assert!(!chain_store
+            .save_equivocation_evidence(&approval_evidence("alice", next_height))
+            .unwrap());
+        // The other validators are not affected by the limit, even those whose
+        // account id starts with the one of the validator.
+        assert!(chain_store.save_equivocation_evidence(&approval_evidence("bob", 1)).unwrap());
+        assert!(chain_store
+            .save_equivocation_evidence(&approval_evidence("alice.near", 1))
+            .unwrap());
+        assert_eq!(
+            chain_store.get_equivocation_evidence(0).unwrap().len(),
+            MAX_EQUIVOCATION_EVIDENCE_PER_ACCOUNT + 2
+        );
+    }
+
+    #[test]
+    fn test_gc_equivocation_evidence() {
+        let mut chain_store = ChainStore::new(create_test_store(), 0, true);
+        for height in [10, 20] {
+            for account_id in ["alice", "bob"] {
+                let evidence = approval_evidence(account_id, height);
+                assert!(chain_store.save_equivocation_evidence(&evidence).unwrap());
+            }
+        }
+        let mut store_update = chain_store.store_update();
+        store_update.gc_equivocation_evidence(10).unwrap();
+        store_update.commit().unwrap();
+
+        let evidence = chain_store.get_equivocation_evidence(0).unwrap();
+        assert_eq!(evidence.iter().map(|evidence| evidence.height()).collect::<Vec<_>>(), [20, 20]);
+        // The garbage collected evidence doesn't count against the limit.
+        let num_keys = chain_store.store().iter(DBCol::EquivocationEvidence).count();
+        assert_eq!(num_keys, 4);
+    }
+}
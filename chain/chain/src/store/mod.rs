@@ -54,6 +54,8 @@ use near_primitives::stateless_validation::StoredChunkStateTransitionData;
 use near_store::db::{StoreStatistics, STATE_SYNC_DUMP_KEY};
 use std::sync::Arc;
 
+mod account_index;
+mod equivocation_evidence;
 mod latest_witnesses;
 
 /// lru cache size
@@ -456,6 +458,9 @@ pub struct ChainStore {
     /// - archive is true, cold_store is configured and migration to split_storage is finished - node
     /// working in split storage mode needs trie changes in order to do garbage collection on hot.
     save_trie_changes: bool,
+    /// Whether to keep the index of the transactions and receipts of the
+    /// accounts, see `ChainConfig::save_account_index`.
+    pub(crate) save_account_index: bool,
 }
 
 fn option_to_not_found<T, F>(res: io::Result<Option<T>>, field_name: F) -> Result<T, Error>
@@ -498,6 +503,7 @@ impl ChainStore {
             block_ordinal_to_hash: CellLruCache::new(CACHE_SIZE),
             processed_block_heights: CellLruCache::new(CACHE_SIZE),
             save_trie_changes,
+            save_account_index: false,
         }
     }
 
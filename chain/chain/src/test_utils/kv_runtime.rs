@@ -12,6 +12,7 @@ use near_chain_primitives::Error;
 use near_crypto::{KeyType, PublicKey, SecretKey, Signature};
 use near_epoch_manager::types::BlockHeaderInfo;
 use near_epoch_manager::{EpochManagerAdapter, RngSeed};
+use near_parameters::{RuntimeConfig, RuntimeConfigStore};
 use near_pool::types::TransactionGroupIterator;
 use near_primitives::account::{AccessKey, Account};
 use near_primitives::apply::ApplyChunkReason;
@@ -43,8 +44,9 @@ use near_primitives::types::{
 };
 use near_primitives::version::{ProtocolFeature, ProtocolVersion, PROTOCOL_VERSION};
 use near_primitives::views::{
-    AccessKeyInfoView, AccessKeyList, CallResult, ContractCodeView, EpochValidatorInfo,
-    QueryRequest, QueryResponse, QueryResponseKind, ViewStateResult,
+    AccessKeyInfoView, AccessKeyList, AccountStateOverride, CallResult, ContractCodeView,
+    EpochValidatorInfo, QueryRequest, QueryResponse, QueryResponseKind, StorageUsageView,
+    ViewStateResult,
 };
 use near_primitives::{checked_feature, shard_layout};
 use near_store::test_utils::TestTriesBuilder;
@@ -83,6 +85,7 @@ pub struct KeyValueRuntime {
     state: RwLock<HashMap<StateRoot, KVState>>,
     state_size: RwLock<HashMap<StateRoot, u64>>,
     headers_cache: RwLock<HashMap<CryptoHash, BlockHeader>>,
+    runtime_config_store: RuntimeConfigStore,
 }
 
 /// DEPRECATED. DO NOT USE for new tests. Use the real EpochManager, familiarize
@@ -378,6 +381,7 @@ impl KeyValueRuntime {
             headers_cache: RwLock::new(HashMap::new()),
             state: RwLock::new(state),
             state_size: RwLock::new(state_size),
+            runtime_config_store: RuntimeConfigStore::test(),
         })
     }
 
@@ -438,6 +442,10 @@ impl EpochManagerAdapter for MockEpochManager {
         12 + (self.num_shards as usize + 1) % 50
     }
 
+    fn chain_id(&self) -> String {
+        "unittest".to_string()
+    }
+
     fn num_data_parts(&self) -> usize {
         // Same as in Nightshade Runtime
         let total_parts = self.num_total_parts();
@@ -982,6 +990,17 @@ impl EpochManagerAdapter for MockEpochManager {
         Ok(false)
     }
 
+    fn cares_about_shard_in_epoch(
+        &self,
+        epoch_id: &EpochId,
+        account_id: &AccountId,
+        shard_id: ShardId,
+    ) -> Result<bool, EpochError> {
+        let valset = self.get_valset_for_epoch(epoch_id)?;
+        let chunk_producers = self.get_chunk_producers(valset, shard_id);
+        Ok(chunk_producers.iter().any(|validator| validator.account_id() == account_id))
+    }
+
     fn cares_about_shard_next_epoch_from_prev_block(
         &self,
         parent_hash: &CryptoHash,
@@ -1310,20 +1329,24 @@ impl RuntimeAdapter for KeyValueRuntime {
                 block_height,
                 block_hash: *block_hash,
             }),
-            QueryRequest::ViewCode { .. } => Ok(QueryResponse {
-                kind: QueryResponseKind::ViewCode(ContractCodeView {
-                    code: vec![],
-                    hash: CryptoHash::default(),
-                }),
-                block_height,
-                block_hash: *block_hash,
-            }),
+            QueryRequest::ViewCode { .. } | QueryRequest::ViewCodeByHash { .. } => {
+                Ok(QueryResponse {
+                    kind: QueryResponseKind::ViewCode(ContractCodeView {
+                        code: vec![],
+                        hash: CryptoHash::default(),
+                    }),
+                    block_height,
+                    block_hash: *block_hash,
+                })
+            }
             QueryRequest::ViewAccessKeyList { .. } => Ok(QueryResponse {
                 kind: QueryResponseKind::AccessKeyList(AccessKeyList {
                     keys: vec![AccessKeyInfoView {
                         public_key: PublicKey::empty(KeyType::ED25519),
                         access_key: AccessKey::full_access().into(),
+                        metadata: None,
                     }],
+                    has_more: false,
                 }),
                 block_height,
                 block_hash: *block_hash,
@@ -1349,9 +1372,41 @@ impl RuntimeAdapter for KeyValueRuntime {
                 block_height,
                 block_hash: *block_hash,
             }),
+            QueryRequest::ViewStorageUsage { .. } => Ok(QueryResponse {
+                kind: QueryResponseKind::StorageUsage(StorageUsageView {
+                    storage_usage: 0,
+                    account: 0,
+                    code: 0,
+                    access_keys: 0,
+                    num_access_keys: 0,
+                    data: 0,
+                    num_data_keys: 0,
+                    largest_data_keys: vec![],
+                }),
+                block_height,
+                block_hash: *block_hash,
+            }),
         }
     }
 
+    fn simulate_transaction(
+        &self,
+        _header: &BlockHeader,
+        _state_roots: &HashMap<ShardUId, StateRoot>,
+        transaction: &SignedTransaction,
+        _verify_signature: bool,
+        _state_overrides: &[AccountStateOverride],
+    ) -> Result<Result<Vec<ExecutionOutcomeWithId>, InvalidTxError>, Error> {
+        Ok(Ok(vec![ExecutionOutcomeWithId {
+            id: transaction.get_hash(),
+            outcome: ExecutionOutcome {
+                status: ExecutionStatus::SuccessValue(vec![]),
+                executor_id: transaction.transaction.signer_id().clone(),
+                ..Default::default()
+            },
+        }]))
+    }
+
     fn obtain_state_part(
         &self,
         _shard_id: ShardId,
@@ -1414,6 +1469,14 @@ impl RuntimeAdapter for KeyValueRuntime {
     }
 
     fn get_gc_stop_height(&self, block_hash: &CryptoHash) -> BlockHeight {
+        self.get_gc_stop_height_for_num_epochs(block_hash, DEFAULT_GC_NUM_EPOCHS_TO_KEEP)
+    }
+
+    fn get_gc_stop_height_for_num_epochs(
+        &self,
+        block_hash: &CryptoHash,
+        num_epochs_to_keep: u64,
+    ) -> BlockHeight {
         if !self.no_gc {
             // This code is 'incorrect' - as production one is always setting the GC to the
             // first block of the epoch.
@@ -1424,7 +1487,7 @@ impl RuntimeAdapter for KeyValueRuntime {
                 .unwrap_or_default()
                 .map(|h| h.height())
                 .unwrap_or_default();
-            block_height.saturating_sub(DEFAULT_GC_NUM_EPOCHS_TO_KEEP * self.epoch_length)
+            block_height.saturating_sub(num_epochs_to_keep * self.epoch_length)
         /*  // TODO: use this version of the code instead - after we fix the block creation
             // issue in multiple tests.
         // We have to return the first block of the epoch T-DEFAULT_GC_NUM_EPOCHS_TO_KEEP.
@@ -1451,6 +1514,10 @@ impl RuntimeAdapter for KeyValueRuntime {
         unreachable!("get_protocol_config should not be called in KeyValueRuntime");
     }
 
+    fn get_runtime_config(&self, protocol_version: ProtocolVersion) -> &RuntimeConfig {
+        self.runtime_config_store.get_config(protocol_version)
+    }
+
     fn will_shard_layout_change_next_epoch(
         &self,
         _parent_hash: &CryptoHash,
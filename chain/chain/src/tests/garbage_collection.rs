@@ -12,7 +12,7 @@ use crate::test_utils::{
 use crate::types::Tip;
 use crate::{ChainStoreAccess, StoreValidator};
 
-use near_chain_configs::{GCConfig, GenesisConfig, DEFAULT_GC_NUM_EPOCHS_TO_KEEP};
+use near_chain_configs::{GCColumnGroup, GCConfig, GenesisConfig, DEFAULT_GC_NUM_EPOCHS_TO_KEEP};
 use near_epoch_manager::EpochManagerAdapter;
 use near_primitives::block::Block;
 use near_primitives::epoch_manager::block_info::BlockInfo;
@@ -20,9 +20,10 @@ use near_primitives::merkle::PartialMerkleTree;
 use near_primitives::shard_layout::ShardUId;
 use near_primitives::test_utils::{create_test_signer, TestBlockBuilder};
 use near_primitives::types::{BlockHeight, NumBlocks, StateRoot};
+use near_primitives::utils::{get_block_shard_id, get_outcome_id_block_hash};
 use near_primitives::validator_signer::InMemoryValidatorSigner;
 use near_store::test_utils::gen_changes;
-use near_store::{DBCol, ShardTries, Trie, WrappedTrieChanges};
+use near_store::{DBCol, KeyForStateChanges, ShardTries, Trie, WrappedTrieChanges};
 
 // Build a chain of num_blocks on top of prev_block
 fn do_fork(
@@ -724,6 +725,155 @@ fn test_clear_old_data() {
     }
 }
 
+/// Test that the data of the column groups with their own retention is kept
+/// for their configured number of epochs, rather than along with the blocks.
+#[test]
+fn test_clear_old_data_column_groups() {
+    let max_height = 14usize;
+    let mut chain = get_chain_with_epoch_length(Clock::real(), 1);
+    let epoch_manager = chain.epoch_manager.clone();
+    let genesis = chain.get_block_by_height(0).unwrap();
+    let signer = Arc::new(create_test_signer("test1"));
+    let mut prev_block = genesis;
+    let mut blocks = vec![prev_block.clone()];
+    for i in 1..=max_height {
+        add_block(
+            &mut chain,
+            epoch_manager.as_ref(),
+            &mut prev_block,
+            &mut blocks,
+            signer.clone(),
+            i as BlockHeight,
+        );
+    }
+
+    let state_changes_key = |block: &Block| {
+        let mut key = KeyForStateChanges::for_block(block.hash()).as_ref().to_vec();
+        key.push(0);
+        key
+    };
+    let outcome_id = |block: &Block| near_primitives::hash::hash(block.hash().as_ref());
+    let account_index_key = |block: &Block| [b"alice\0".as_slice(), block.hash().as_ref()].concat();
+    let mut store_update = chain.chain_store().store().store_update();
+    for block in &blocks[1..] {
+        store_update.set(DBCol::AccountIndex, &account_index_key(block), &[1]);
+        store_update
+            .set_ser(
+                DBCol::AccountIndexKeys,
+                &get_block_shard_id(block.hash(), 0),
+                &vec![account_index_key(block)],
+            )
+            .unwrap();
+        store_update.set(DBCol::StateChanges, &state_changes_key(block), &[1]);
+        store_update
+            .set_ser(
+                DBCol::OutcomeIds,
+                &get_block_shard_id(block.hash(), 0),
+                &vec![outcome_id(block)],
+            )
+            .unwrap();
+        store_update.insert(
+            DBCol::TransactionResultForBlock,
+            get_outcome_id_block_hash(&outcome_id(block), block.hash()),
+            vec![1],
+        );
+    }
+    store_update.commit().unwrap();
+
+    let gc_config = GCConfig {
+        gc_blocks_limit: 100,
+        gc_column_group_num_epochs_to_keep: [
+            (GCColumnGroup::TransactionResults, 10),
+            (GCColumnGroup::StateChanges, 2),
+            (GCColumnGroup::AccountIndex, 7),
+        ]
+        .into(),
+        ..GCConfig::default()
+    };
+    chain.clear_data(&gc_config).unwrap();
+
+    let store = chain.chain_store().store();
+    for i in 1..=max_height {
+        let block = &blocks[i];
+        let block_removed = i < max_height - DEFAULT_GC_NUM_EPOCHS_TO_KEEP as usize;
+        assert_eq!(chain.get_block(block.hash()).is_err(), block_removed);
+        let state_changes_removed = i < max_height - 2;
+        let state_changes = store.get(DBCol::StateChanges, &state_changes_key(block)).unwrap();
+        assert_eq!(state_changes.is_none(), state_changes_removed, "height {i}");
+        let outcomes_removed = i < max_height - 10;
+        let outcome_ids =
+            store.get(DBCol::OutcomeIds, &get_block_shard_id(block.hash(), 0)).unwrap();
+        assert_eq!(outcome_ids.is_none(), outcomes_removed, "height {i}");
+        let outcome = store
+            .get(
+                DBCol::TransactionResultForBlock,
+                &get_outcome_id_block_hash(&outcome_id(block), block.hash()),
+            )
+            .unwrap();
+        assert_eq!(outcome.is_none(), outcomes_removed, "height {i}");
+        let account_index_removed = i < max_height - 7;
+        let account_index = store.get(DBCol::AccountIndex, &account_index_key(block)).unwrap();
+        assert_eq!(account_index.is_none(), account_index_removed, "height {i}");
+        let account_index_keys =
+            store.get(DBCol::AccountIndexKeys, &get_block_shard_id(block.hash(), 0)).unwrap();
+        assert_eq!(account_index_keys.is_none(), account_index_removed, "height {i}");
+    }
+}
+
+/// Test that the heights without blocks count against `gc_blocks_limit` when
+/// garbage collecting the column groups.
+#[test]
+fn test_clear_old_data_column_groups_skipped_heights() {
+    let mut chain = get_chain_with_epoch_length(Clock::real(), 1);
+    let epoch_manager = chain.epoch_manager.clone();
+    let genesis = chain.get_block_by_height(0).unwrap();
+    let signer = Arc::new(create_test_signer("test1"));
+    let mut prev_block = genesis;
+    let mut blocks = vec![prev_block.clone()];
+    // Two heights without a block before every block.
+    for i in 1..=10 {
+        add_block(
+            &mut chain,
+            epoch_manager.as_ref(),
+            &mut prev_block,
+            &mut blocks,
+            signer.clone(),
+            3 * i,
+        );
+    }
+    let state_changes_key = |block: &Block| {
+        let mut key = KeyForStateChanges::for_block(block.hash()).as_ref().to_vec();
+        key.push(0);
+        key
+    };
+    let mut store_update = chain.chain_store().store().store_update();
+    for block in &blocks[1..] {
+        store_update.set(DBCol::StateChanges, &state_changes_key(block), &[1]);
+    }
+    store_update.commit().unwrap();
+
+    let gc_config = GCConfig {
+        gc_blocks_limit: 3,
+        gc_column_group_num_epochs_to_keep: [(GCColumnGroup::StateChanges, 2)].into(),
+        ..GCConfig::default()
+    };
+    let has_state_changes = |chain: &Chain, block: &Block| {
+        chain
+            .chain_store()
+            .store()
+            .get(DBCol::StateChanges, &state_changes_key(block))
+            .unwrap()
+            .is_some()
+    };
+    // The first run goes through the heights 0 to 2, with the genesis only.
+    chain.clear_data(&gc_config).unwrap();
+    assert!(has_state_changes(&chain, &blocks[1]));
+    // The second one through the heights 3 to 5, with the first block only.
+    chain.clear_data(&gc_config).unwrap();
+    assert!(!has_state_changes(&chain, &blocks[1]));
+    assert!(has_state_changes(&chain, &blocks[2]));
+}
+
 // Adds block to the chain at given height after prev_block.
 fn add_block(
     chain: &mut Chain,
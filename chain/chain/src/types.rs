@@ -6,6 +6,7 @@ use near_chain_configs::ProtocolConfig;
 use near_chain_configs::ReshardingConfig;
 use near_chain_primitives::Error;
 pub use near_epoch_manager::EpochManagerAdapter;
+use near_parameters::RuntimeConfig;
 use near_pool::types::TransactionGroupIterator;
 use near_primitives::apply::ApplyChunkReason;
 pub use near_primitives::block::{Block, BlockHeader, Tip};
@@ -32,7 +33,7 @@ use near_primitives::version::{
     ProtocolVersion, MIN_GAS_PRICE_NEP_92, MIN_GAS_PRICE_NEP_92_FIX, MIN_PROTOCOL_VERSION_NEP_92,
     MIN_PROTOCOL_VERSION_NEP_92_FIX,
 };
-use near_primitives::views::{QueryRequest, QueryResponse};
+use near_primitives::views::{AccountStateOverride, QueryRequest, QueryResponse};
 use near_store::flat::FlatStorageManager;
 use near_store::{PartialStorage, ShardTries, Store, Trie, WrappedTrieChanges};
 use num_rational::Rational32;
@@ -224,6 +225,9 @@ pub struct ChainConfig {
     pub background_migration_threads: usize,
     /// The resharding configuration.
     pub resharding_config: MutableConfigValue<ReshardingConfig>,
+    /// Whether to keep the index of the transactions and receipts involving
+    /// every account.
+    pub save_account_index: bool,
 }
 
 impl ChainConfig {
@@ -235,6 +239,7 @@ impl ChainConfig {
                 ReshardingConfig::default(),
                 "resharding_config",
             ),
+            save_account_index: false,
         }
     }
 }
@@ -446,6 +451,14 @@ pub trait RuntimeAdapter: Send + Sync {
     /// Get the block height for which garbage collection should not go over
     fn get_gc_stop_height(&self, block_hash: &CryptoHash) -> BlockHeight;
 
+    /// Get the block height for which garbage collection of data kept for
+    /// `num_epochs_to_keep` epochs should not go over
+    fn get_gc_stop_height_for_num_epochs(
+        &self,
+        block_hash: &CryptoHash,
+        num_epochs_to_keep: u64,
+    ) -> BlockHeight;
+
     /// Apply transactions and receipts to given state root and return store update
     /// and new state root.
     /// Also returns transaction result for each transaction and new receipts.
@@ -472,6 +485,22 @@ pub trait RuntimeAdapter: Send + Sync {
         request: &QueryRequest,
     ) -> Result<QueryResponse, near_chain_primitives::error::QueryError>;
 
+    /// Executes the transaction and the receipts it generates on top of the
+    /// given state roots of the shards at the end of the block, as if the
+    /// transaction was included in the next block, with the overrides applied
+    /// first. Nothing is stored. Returns the outcomes of the transaction and
+    /// of the executed receipts, or the `InvalidTxError` if the transaction is
+    /// invalid. The receipts of the shards without a state root are not
+    /// executed.
+    fn simulate_transaction(
+        &self,
+        header: &BlockHeader,
+        state_roots: &HashMap<ShardUId, StateRoot>,
+        transaction: &SignedTransaction,
+        verify_signature: bool,
+        state_overrides: &[AccountStateOverride],
+    ) -> Result<Result<Vec<ExecutionOutcomeWithId>, InvalidTxError>, Error>;
+
     /// Get part of the state corresponding to the given state root.
     /// `prev_hash` is a block whose post state root is `state_root`.
     /// Returns error when storage is inconsistent.
@@ -525,6 +554,10 @@ pub trait RuntimeAdapter: Send + Sync {
     ) -> bool;
 
     fn get_protocol_config(&self, epoch_id: &EpochId) -> Result<ProtocolConfig, Error>;
+
+    /// Runtime config of the protocol version, without the copy of the genesis
+    /// config made by `get_protocol_config`.
+    fn get_runtime_config(&self, protocol_version: ProtocolVersion) -> &RuntimeConfig;
 }
 
 /// The last known / checked height and time when we have processed it.
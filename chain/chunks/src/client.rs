@@ -54,6 +54,15 @@ impl ShardedTransactionPool {
         self.tx_pools.get_mut(&shard_uid).map(|pool| pool.pool_iterator())
     }
 
+    /// Like `get_pool_iterator`, but the transactions with a higher priority
+    /// fee are pulled first.
+    pub fn get_priority_fee_pool_iterator(
+        &mut self,
+        shard_uid: ShardUId,
+    ) -> Option<PoolIteratorWrapper<'_>> {
+        self.tx_pools.get_mut(&shard_uid).map(|pool| pool.priority_fee_pool_iterator())
+    }
+
     /// Tries to insert the transaction into the pool for a given shard.
     pub fn insert_transaction(
         &mut self,
@@ -80,6 +89,15 @@ impl ShardedTransactionPool {
         res
     }
 
+    /// Changes the size limit of the pools. Transactions already in a pool over
+    /// the new limit are kept.
+    pub fn set_pool_size_limit(&mut self, pool_size_limit: Option<u64>) {
+        self.pool_size_limit = pool_size_limit;
+        for pool in self.tx_pools.values_mut() {
+            pool.set_total_transaction_size_limit(pool_size_limit);
+        }
+    }
+
     fn pool_for_shard(&mut self, shard_uid: ShardUId) -> &mut TransactionPool {
         self.tx_pools.entry(shard_uid).or_insert_with(|| {
             TransactionPool::new(
@@ -90,6 +108,11 @@ impl ShardedTransactionPool {
         })
     }
 
+    /// Returns the pools of all shards which received at least one transaction.
+    pub fn pools(&self) -> impl Iterator<Item = (&ShardUId, &TransactionPool)> {
+        self.tx_pools.iter()
+    }
+
     pub fn debug_status(&self) -> String {
         self.tx_pools
             .iter()
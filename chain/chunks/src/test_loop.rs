@@ -5,7 +5,6 @@ use crate::{
     test_utils::{default_tip, tip},
 };
 use near_async::test_loop::delay_sender::DelaySender;
-use near_async::time;
 use near_async::time::Clock;
 use near_async::{
     messaging::Sender,
@@ -19,7 +18,7 @@ use near_epoch_manager::{
 };
 use near_network::{
     shards_manager::ShardsManagerRequestFromNetwork,
-    test_loop::SupportsRoutingLookup,
+    test_loop::{NetworkConditions, SupportsRoutingLookup},
     types::{NetworkRequests, PeerManagerMessageRequest},
 };
 use near_primitives::congestion_info::CongestionInfo;
@@ -53,7 +52,8 @@ pub fn forward_network_request_to_shards_manager(
 }
 
 /// Routes network messages that are issued by ShardsManager to other instances
-/// in a multi-instance test.
+/// in a multi-instance test. Messages are delayed or lost according to
+/// `network_conditions`.
 ///
 /// TODO: This logic should ideally not be duplicated from the real
 /// PeerManagerActor and PeerActor.
@@ -65,8 +65,9 @@ pub fn route_shards_manager_network_messages<
 >(
     sender: DelaySender<(usize, Event)>,
     clock: Clock,
-    network_delay: time::Duration,
+    network_conditions: impl Into<NetworkConditions>,
 ) -> LoopEventHandler<Data, (usize, Event)> {
+    let network_conditions = network_conditions.into();
     let mut route_back_lookup: HashMap<CryptoHash, usize> = HashMap::new();
     let mut next_hash: u64 = 0;
     LoopEventHandler::new(move |event: (usize, Event), data: &mut Data| {
@@ -80,26 +81,32 @@ pub fn route_shards_manager_network_messages<
                         let route_back = CryptoHash::hash_borsh(next_hash);
                         route_back_lookup.insert(route_back, idx);
                         next_hash += 1;
+                        let Some(delay) = network_conditions.sample(idx, target_idx) else {
+                            return Ok(());
+                        };
                         sender.send_with_delay(
                             (target_idx,
                             ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkRequest {
                                 partial_encoded_chunk_request: request,
                                 route_back,
                             }.into()),
-                            network_delay,
+                            delay,
                         );
                         Ok(())
                     }
                     NetworkRequests::PartialEncodedChunkResponse { route_back, response } => {
                         let target_idx =
                             *route_back_lookup.get(&route_back).expect("Route back not found");
+                        let Some(delay) = network_conditions.sample(idx, target_idx) else {
+                            return Ok(());
+                        };
                         sender.send_with_delay(
                             (target_idx,
                             ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkResponse {
                                 partial_encoded_chunk_response: response,
                                 received_time: clock.now().into(), // TODO: use clock
                             }.into()),
-                            network_delay,
+                            delay,
                         );
                         Ok(())
                     }
@@ -108,6 +115,9 @@ pub fn route_shards_manager_network_messages<
                         partial_encoded_chunk,
                     } => {
                         let target_idx = data.index_for_account(&account_id);
+                        let Some(delay) = network_conditions.sample(idx, target_idx) else {
+                            return Ok(());
+                        };
                         sender.send_with_delay(
                             (
                                 target_idx,
@@ -116,12 +126,15 @@ pub fn route_shards_manager_network_messages<
                                 )
                                 .into(),
                             ),
-                            network_delay,
+                            delay,
                         );
                         Ok(())
                     }
                     NetworkRequests::PartialEncodedChunkForward { account_id, forward } => {
                         let target_idx = data.index_for_account(&account_id);
+                        let Some(delay) = network_conditions.sample(idx, target_idx) else {
+                            return Ok(());
+                        };
                         sender.send_with_delay(
                             (
                                 target_idx,
@@ -130,7 +143,7 @@ pub fn route_shards_manager_network_messages<
                                 )
                                 .into(),
                             ),
-                            network_delay,
+                            delay,
                         );
                         Ok(())
                     }
@@ -2,6 +2,8 @@
 //! without backwards compatibility of JSON encoding.
 use crate::types::StatusError;
 use near_async::time::Utc;
+use near_crypto::PublicKey;
+pub use near_epoch_manager::shard_tracker::ShardTrackingReason;
 use near_primitives::types::EpochId;
 use near_primitives::views::{
     CatchupStatusView, ChainProcessingInfo, EpochValidatorInfo, RequestedStatePartsView,
@@ -10,8 +12,9 @@ use near_primitives::views::{
 use near_primitives::{
     block_header::ApprovalInner,
     hash::CryptoHash,
+    shard_layout::ShardVersion,
     sharding::ChunkHash,
-    types::{AccountId, BlockHeight},
+    types::{AccountId, BlockHeight, EpochHeight, Nonce, ProtocolVersion, ShardId},
     views::ValidatorInfo,
 };
 use std::collections::HashMap;
@@ -22,6 +25,40 @@ pub struct TrackedShardsView {
     pub shards_tracked_next_epoch: Vec<bool>,
 }
 
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct ShardAssignmentView {
+    pub shard_id: ShardId,
+    // The shard contains the accounts from `start_account` (inclusive) to
+    // `end_account` (exclusive).  None if unbounded, and for both of them if
+    // the accounts are assigned to the shards by their hashes.
+    pub start_account: Option<AccountId>,
+    pub end_account: Option<AccountId>,
+    // Set if the shard layout changes at the start of the epoch.
+    pub parent_shard_id: Option<ShardId>,
+    pub chunk_producers: Vec<AccountId>,
+    // Why this node tracks the shard, empty if it doesn't.
+    pub tracking_reasons: Vec<ShardTrackingReason>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct EpochShardsView {
+    pub epoch_id: CryptoHash,
+    pub epoch_height: EpochHeight,
+    pub protocol_version: ProtocolVersion,
+    pub shard_layout_version: ShardVersion,
+    pub shards: Vec<ShardAssignmentView>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct ShardsView {
+    // The validator account of this node, if any.
+    pub validator_account_id: Option<AccountId>,
+    pub this_epoch: EpochShardsView,
+    pub next_epoch: EpochShardsView,
+    // Whether the shard layout changes in the next epoch.
+    pub resharding_next_epoch: bool,
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Debug)]
 pub struct EpochInfoView {
     pub epoch_id: CryptoHash,
@@ -60,6 +97,151 @@ pub struct DebugBlockStatus {
     pub gas_price_ratio: f64,
 }
 
+/// Congestion of a single shard as seen in the chunk header included in a block.
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct ShardCongestionView {
+    pub shard_id: ShardId,
+    pub congestion_level: f64,
+    pub delayed_receipts_gas: u128,
+    pub buffered_receipts_gas: u128,
+    pub receipt_bytes: u64,
+    // The shard that is allowed to send receipts to this shard even when it is fully congested.
+    pub allowed_shard: u16,
+    pub missed_chunks_count: u64,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct CongestionAtHeightView {
+    pub block_height: BlockHeight,
+    pub block_hash: CryptoHash,
+    pub shards: Vec<ShardCongestionView>,
+}
+
+/// A chunk endorsement sent by this node, and whether it made it into a block.
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct SentChunkEndorsementView {
+    pub chunk_hash: ChunkHash,
+    pub shard_id: ShardId,
+    pub height_created: BlockHeight,
+    // Block producers the endorsement was sent to.
+    pub block_producers: Vec<AccountId>,
+    // Canonical block at the height the chunk was created at, None if there is none (yet).
+    pub block_hash: Option<CryptoHash>,
+    // Whether the block includes the chunk.
+    pub chunk_included: bool,
+    // Whether the block includes the endorsement, i.e. it arrived at the block producer in time.
+    pub endorsement_included: bool,
+    // Endorsements of the chunk included in the block, one character per chunk validator in
+    // the order of the block: '1' for an included endorsement, '0' for a missing one.
+    pub endorsement_bitmap: String,
+    pub num_endorsements: usize,
+}
+
+/// Parts of a state witness received by this node as a chunk validator.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct PartialWitnessView {
+    pub shard_id: ShardId,
+    pub height_created: BlockHeight,
+    // Time from the first part to having enough parts to decode the witness, None if not
+    // decoded (yet).
+    pub decoded_after_ms: Option<u64>,
+    // Parts in the order of the part ordinals.
+    pub parts: Vec<PartialWitnessPartView>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct PartialWitnessPartView {
+    // Chunk validator the chunk producer sent the part to, to be forwarded to the others.
+    pub owner: AccountId,
+    // Time from the first part of the witness to this one, None if it never arrived.
+    pub received_after_ms: Option<u64>,
+}
+
+/// How the node routes the transactions of a signer, as of the current head.
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct TxRoutingView {
+    pub signer_id: AccountId,
+    pub epoch_id: EpochId,
+    // Shard of the signer under the shard layout of the epoch.
+    pub shard_id: ShardId,
+    pub shard_layout_version: ShardVersion,
+    // Shard of the signer in the next epoch, set if the next epoch may start before the
+    // transaction is included.
+    pub next_epoch_shard_id: Option<ShardId>,
+    // Chunk producers of the shard for the next heights, one of which is expected to include
+    // the transaction.
+    pub chunk_producers: Vec<(BlockHeight, AccountId)>,
+    // Whether the node tracks the shard in this or the next epoch.
+    pub tracks_shard: bool,
+    // Whether the node adds the transaction to its transaction pool.
+    pub pools: bool,
+    // Chunk producers the node forwards the transaction to.
+    pub forwards_to: Vec<AccountId>,
+}
+
+/// A transaction waiting in the transaction pool.
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct PooledTransactionView {
+    pub hash: CryptoHash,
+    pub signer_id: AccountId,
+    pub public_key: PublicKey,
+    pub receiver_id: AccountId,
+    pub nonce: Nonce,
+    pub priority_fee: Option<u64>,
+    pub size: u64,
+    // Number of blocks between the block referenced by the transaction and the head.
+    // None if the referenced block is not known to this node.
+    pub age_in_blocks: Option<BlockHeight>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct ShardTxPoolView {
+    pub shard_id: ShardId,
+    pub shard_layout_version: u32,
+    // Total number of transactions in the pool, may be larger than the number of
+    // returned transactions.
+    pub num_transactions: usize,
+    // Total size of all transactions in the pool in bytes.
+    pub total_size: u64,
+    // Oldest transactions first, capped at a fixed number of entries.
+    pub transactions: Vec<PooledTransactionView>,
+}
+
+/// Size and compaction state of a column of the database, as reported by RocksDB.
+/// A field is None if RocksDB doesn't report the property for the column.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default)]
+pub struct StoreColumnStatsView {
+    pub column: String,
+    pub live_sst_files_size: Option<i64>,
+    pub estimate_live_data_size: Option<i64>,
+    // Estimated, may be off for columns with merge operations and deletions.
+    pub estimate_num_keys: Option<i64>,
+    pub size_all_mem_tables: Option<i64>,
+    pub block_cache_usage: Option<i64>,
+    pub compaction_pending: Option<i64>,
+    pub num_running_compactions: Option<i64>,
+    pub estimate_pending_compaction_bytes: Option<i64>,
+    // Number of SST files, by level.
+    pub num_files_at_level: Vec<i64>,
+}
+
+/// Statistics of the hot database.  The counters are only set with
+/// `store.enable_statistics` in the config, and count since the node started.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default)]
+pub struct StoreStatsView {
+    pub columns: Vec<StoreColumnStatsView>,
+    pub block_cache_hit: Option<i64>,
+    pub block_cache_miss: Option<i64>,
+    pub block_cache_data_hit: Option<i64>,
+    pub block_cache_data_miss: Option<i64>,
+    pub block_cache_index_hit: Option<i64>,
+    pub block_cache_index_miss: Option<i64>,
+    pub block_cache_filter_hit: Option<i64>,
+    pub block_cache_filter_miss: Option<i64>,
+    pub compaction_read_bytes: Option<i64>,
+    pub compaction_write_bytes: Option<i64>,
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Debug)]
 pub struct MissedHeightInfo {
     pub block_height: u64,
@@ -185,6 +367,20 @@ pub enum DebugStatus {
     ChainProcessingStatus,
     // The state parts already requested.
     RequestedStateParts,
+    // Congestion info of all shards for the last couple blocks.
+    Congestion,
+    // Transactions currently waiting in the transaction pool.
+    TxPool,
+    // Chunk endorsements recently sent by this node.
+    ChunkEndorsements,
+    // How the transactions of the signer are routed.
+    TxRouting(AccountId),
+    // Sizes and compaction state of the columns of the database.
+    StoreStats,
+    // Arrival of the parts of the state witnesses recently received by this node.
+    PartialWitnesses,
+    // Shard layout of this and the next epoch, and who produces and tracks the shards.
+    Shards,
 }
 
 impl actix::Message for DebugStatus {
@@ -206,4 +402,18 @@ pub enum DebugStatusResponse {
     ChainProcessingStatus(ChainProcessingInfo),
     // The state parts already requested.
     RequestedStateParts(Vec<RequestedStatePartsView>),
+    // Congestion info of all shards, in descending order of block height.
+    Congestion(Vec<CongestionAtHeightView>),
+    // Contents of the transaction pool of every shard.
+    TxPool(Vec<ShardTxPoolView>),
+    // Chunk endorsements sent by this node, in descending order of height.
+    ChunkEndorsements(Vec<SentChunkEndorsementView>),
+    // How the transactions of a signer are routed.
+    TxRouting(TxRoutingView),
+    // RocksDB statistics of the database and its columns.
+    StoreStats(StoreStatsView),
+    // State witnesses recently received by this node, in descending order of height.
+    PartialWitnesses(Vec<PartialWitnessView>),
+    // Shard layout and assignment of this and the next epoch.
+    Shards(ShardsView),
 }
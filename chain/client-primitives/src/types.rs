@@ -1,19 +1,24 @@
 use actix::Message;
 use near_chain_configs::{ClientConfig, ProtocolConfigView};
+use near_primitives::errors::InvalidTxError;
 use near_primitives::hash::CryptoHash;
 use near_primitives::merkle::{MerklePath, PartialMerkleTree};
 use near_primitives::network::PeerId;
 use near_primitives::sharding::ChunkHash;
+use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::{
     AccountId, BlockHeight, BlockReference, EpochId, EpochReference, MaybeBlockId, ShardId,
     TransactionOrReceiptId,
 };
 use near_primitives::views::validator_stake_view::ValidatorStakeView;
 use near_primitives::views::{
-    BlockView, ChunkView, DownloadStatusView, EpochValidatorInfo, ExecutionOutcomeWithIdView,
-    GasPriceView, LightClientBlockLiteView, LightClientBlockView, MaintenanceWindowsView,
-    QueryRequest, QueryResponse, ReceiptView, ShardSyncDownloadView, SplitStorageInfoView,
-    StateChangesKindsView, StateChangesRequestView, StateChangesView, SyncStatusView, TxStatusView,
+    AccountIndexEntryView, AccountStateOverride, BlockCongestionInfoView, BlockDelegateActionsView,
+    BlockView, ChunkView, ColdStoreCopyStatusView, DownloadStatusView, EpochValidatorInfo,
+    EquivocationEvidenceView, ExecutionOutcomeWithIdView, FinalExecutionOutcomeView, GasPriceView,
+    LightClientBlockLiteView, LightClientBlockView, MaintenanceWindowsView,
+    NextEpochValidatorScheduleView, QueryRequest, QueryResponse, ReceiptView,
+    ShardSyncDownloadView, SplitStorageInfoView, StateChangesKindsView, StateChangesRequestView,
+    StateChangesView, SyncStatusView, TxStatusView,
 };
 pub use near_primitives::views::{StatusResponse, StatusSyncInfo};
 use std::collections::HashMap;
@@ -529,6 +534,14 @@ pub enum QueryError {
         block_height: near_primitives::types::BlockHeight,
         block_hash: near_primitives::hash::CryptoHash,
     },
+    #[error(
+        "Contract code with hash {code_hash} is not stored in the state of the shards tracked by the node"
+    )]
+    UnknownCodeHash {
+        code_hash: near_primitives::hash::CryptoHash,
+        block_height: near_primitives::types::BlockHeight,
+        block_hash: near_primitives::hash::CryptoHash,
+    },
     #[error("State of contract {contract_account_id} is too large to be viewed")]
     TooLargeContractState {
         contract_account_id: near_primitives::types::AccountId,
@@ -796,6 +809,18 @@ impl Message for GetValidatorOrdered {
     type Result = Result<Vec<ValidatorStakeView>, GetValidatorInfoError>;
 }
 
+#[derive(Debug)]
+pub struct GetNextEpochValidatorSchedule {
+    pub block_id: MaybeBlockId,
+    /// If set, only the heights at which the account produces the block or a
+    /// chunk are returned.
+    pub account_id: Option<AccountId>,
+}
+
+impl Message for GetNextEpochValidatorSchedule {
+    type Result = Result<NextEpochValidatorScheduleView, GetValidatorInfoError>;
+}
+
 #[derive(Debug)]
 pub struct GetStateChanges {
     pub block_hash: CryptoHash,
@@ -1046,6 +1071,112 @@ impl From<near_chain_primitives::Error> for GetProtocolConfigError {
     }
 }
 
+#[derive(Debug)]
+pub struct GetCongestionInfo(pub BlockReference);
+
+impl Message for GetCongestionInfo {
+    type Result = Result<BlockCongestionInfoView, GetCongestionInfoError>;
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum GetCongestionInfoError {
+    #[error("IO Error: {0}")]
+    IOError(String),
+    #[error("Block has never been observed: {0}")]
+    UnknownBlock(String),
+    // NOTE: Currently, the underlying errors are too broad, and while we tried to handle
+    // expected cases, we cannot statically guarantee that no other errors will be returned
+    // in the future.
+    // TODO #3851: Remove this variant once we can exhaustively match all the underlying errors
+    #[error("It is a bug if you receive this error type, please, report this incident: https://github.com/near/nearcore/issues/new/choose. Details: {0}")]
+    Unreachable(String),
+}
+
+impl From<near_chain_primitives::Error> for GetCongestionInfoError {
+    fn from(error: near_chain_primitives::Error) -> Self {
+        match error {
+            near_chain_primitives::Error::IOErr(error) => Self::IOError(error.to_string()),
+            near_chain_primitives::Error::DBNotFoundErr(s) => Self::UnknownBlock(s),
+            _ => Self::Unreachable(error.to_string()),
+        }
+    }
+}
+
+/// Gets the delegate actions applied in a block, optionally only those of the
+/// given relayer or sender.
+#[derive(Debug)]
+pub struct GetDelegateActions {
+    pub block_reference: BlockReference,
+    pub relayer_id: Option<AccountId>,
+    pub sender_id: Option<AccountId>,
+}
+
+impl Message for GetDelegateActions {
+    type Result = Result<BlockDelegateActionsView, GetDelegateActionsError>;
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum GetDelegateActionsError {
+    #[error("IO Error: {0}")]
+    IOError(String),
+    #[error("Block has never been observed: {0}")]
+    UnknownBlock(String),
+    // NOTE: Currently, the underlying errors are too broad, and while we tried to handle
+    // expected cases, we cannot statically guarantee that no other errors will be returned
+    // in the future.
+    // TODO #3851: Remove this variant once we can exhaustively match all the underlying errors
+    #[error("It is a bug if you receive this error type, please, report this incident: https://github.com/near/nearcore/issues/new/choose. Details: {0}")]
+    Unreachable(String),
+}
+
+impl From<near_chain_primitives::Error> for GetDelegateActionsError {
+    fn from(error: near_chain_primitives::Error) -> Self {
+        match error {
+            near_chain_primitives::Error::IOErr(error) => Self::IOError(error.to_string()),
+            near_chain_primitives::Error::DBNotFoundErr(s) => Self::UnknownBlock(s),
+            _ => Self::Unreachable(error.to_string()),
+        }
+    }
+}
+
+/// Simulates the execution of a transaction on top of the state at the head,
+/// without broadcasting it.
+#[derive(Debug)]
+pub struct SimulateTransaction {
+    pub transaction: SignedTransaction,
+    pub verify_signature: bool,
+    pub state_overrides: Vec<AccountStateOverride>,
+}
+
+impl Message for SimulateTransaction {
+    type Result = Result<FinalExecutionOutcomeView, SimulateTransactionError>;
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SimulateTransactionError {
+    #[error("IO Error: {0}")]
+    IOError(String),
+    #[error("Transaction is invalid: {0:?}")]
+    InvalidTransaction(InvalidTxError),
+    #[error("Node doesn't track the shard {0}")]
+    UnavailableShard(ShardId),
+    // NOTE: Currently, the underlying errors are too broad, and while we tried to handle
+    // expected cases, we cannot statically guarantee that no other errors will be returned
+    // in the future.
+    // TODO #3851: Remove this variant once we can exhaustively match all the underlying errors
+    #[error("It is a bug if you receive this error type, please, report this incident: https://github.com/near/nearcore/issues/new/choose. Details: {0}")]
+    Unreachable(String),
+}
+
+impl From<near_chain_primitives::Error> for SimulateTransactionError {
+    fn from(error: near_chain_primitives::Error) -> Self {
+        match error {
+            near_chain_primitives::Error::IOErr(error) => Self::IOError(error.to_string()),
+            _ => Self::Unreachable(error.to_string()),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct GetMaintenanceWindows {
     pub account_id: AccountId,
@@ -1134,12 +1265,105 @@ impl From<std::io::Error> for GetSplitStorageInfoError {
     }
 }
 
+/// Pauses, resumes or limits the rate of copying blocks to the cold store.
+#[derive(Debug)]
+pub enum ControlColdStoreCopy {
+    Pause,
+    Resume,
+    /// Limits the number of blocks copied per second, None removes the limit.
+    SetMaxBlocksPerSecond(Option<u64>),
+}
+
+impl Message for ControlColdStoreCopy {
+    type Result = Result<ColdStoreCopyStatusView, ControlColdStoreCopyError>;
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ControlColdStoreCopyError {
+    #[error("The cold store loop is not running on this node")]
+    NotRunning,
+    #[error("IO Error: {0}")]
+    IOError(String),
+}
+
+impl From<std::io::Error> for ControlColdStoreCopyError {
+    fn from(error: std::io::Error) -> Self {
+        Self::IOError(error.to_string())
+    }
+}
+
+/// Gets the evidence of validators signing conflicting blocks or approvals
+/// recorded by the node, optionally only from the given height on.
+#[derive(Debug)]
+pub struct GetEquivocationEvidence {
+    pub from_height: Option<BlockHeight>,
+}
+
+impl Message for GetEquivocationEvidence {
+    type Result = Result<Vec<EquivocationEvidenceView>, GetEquivocationEvidenceError>;
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum GetEquivocationEvidenceError {
+    #[error("IO Error: {0}")]
+    IOError(String),
+}
+
+impl From<std::io::Error> for GetEquivocationEvidenceError {
+    fn from(error: std::io::Error) -> Self {
+        Self::IOError(error.to_string())
+    }
+}
+
+/// Gets a page of the transactions and receipts involving the account from
+/// the account index of the node, in the order of heights.
+#[derive(Debug)]
+pub struct GetAccountActivity {
+    pub account_id: AccountId,
+    /// Returns only the entries after the given height and id.
+    pub after: Option<(BlockHeight, CryptoHash)>,
+    pub limit: Option<u32>,
+}
+
+pub struct GetAccountActivityResponse {
+    pub entries: Vec<AccountIndexEntryView>,
+    /// Whether the account has more entries after the last one returned.
+    pub has_more: bool,
+}
+
+impl Message for GetAccountActivity {
+    type Result = Result<GetAccountActivityResponse, GetAccountActivityError>;
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum GetAccountActivityError {
+    #[error("The account index is not enabled on this node")]
+    AccountIndexDisabled,
+    #[error("IO Error: {0}")]
+    IOError(String),
+}
+
+impl From<std::io::Error> for GetAccountActivityError {
+    fn from(error: std::io::Error) -> Self {
+        Self::IOError(error.to_string())
+    }
+}
+
 #[cfg(feature = "sandbox")]
 #[derive(Debug)]
 pub enum SandboxMessage {
-    SandboxPatchState(Vec<near_primitives::state_record::StateRecord>),
+    SandboxPatchState {
+        records: Vec<near_primitives::state_record::StateRecord>,
+        access_key_patches: Vec<near_primitives::sandbox::AccessKeyPatch>,
+    },
     SandboxPatchStateStatus,
-    SandboxFastForward(near_primitives::types::BlockHeightDelta),
+    SandboxFastForward {
+        delta_height: near_primitives::types::BlockHeightDelta,
+        /// Shards that skip chunk production until `delta_height + skip_chunks_delta_height`
+        /// blocks past the current head.
+        skip_chunks_shard_ids: Vec<near_primitives::types::ShardId>,
+        skip_chunks_delta_height: near_primitives::types::BlockHeightDelta,
+    },
     SandboxFastForwardStatus,
 }
 
@@ -17,6 +17,7 @@ pub fn client_sender_for_network(
         block_headers_request: view_client_addr.clone().into_sender(),
         block_request: view_client_addr.clone().into_sender(),
         challenge: client_addr.clone().into_sender(),
+        equivocation_evidence: client_addr.clone().into_sender(),
         network_info: client_addr.clone().into_sender(),
         state_request_header: view_client_addr.clone().into_sender(),
         state_request_part: view_client_addr.clone().into_sender(),
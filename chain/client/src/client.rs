@@ -5,6 +5,8 @@ use crate::chunk_distribution_network::{ChunkDistributionClient, ChunkDistributi
 use crate::chunk_inclusion_tracker::ChunkInclusionTracker;
 use crate::debug::BlockProductionTracker;
 use crate::debug::PRODUCTION_TIMES_CACHE_SIZE;
+use crate::equivocation::{verify_evidence, EquivocationDetector};
+use crate::production_budget::ProductionPhase;
 use crate::stateless_validation::chunk_endorsement_tracker::ChunkEndorsementTracker;
 use crate::stateless_validation::chunk_validator::ChunkValidator;
 use crate::stateless_validation::partial_witness::partial_witness_actor::PartialWitnessSenderForClient;
@@ -16,6 +18,7 @@ use crate::sync::state::{StateSync, StateSyncResult};
 use crate::SyncAdapter;
 use crate::SyncMessage;
 use crate::{metrics, SyncStatus};
+use borsh::BorshDeserialize;
 use itertools::Itertools;
 use near_async::futures::{AsyncComputationSpawner, FutureSpawner};
 use near_async::messaging::IntoSender;
@@ -39,7 +42,7 @@ use near_chain::{
     BlockProcessingArtifact, BlockStatus, Chain, ChainGenesis, ChainStoreAccess, Doomslug,
     DoomslugThresholdMode, Provenance,
 };
-use near_chain_configs::{ClientConfig, LogSummaryStyle, UpdateableClientConfig};
+use near_chain_configs::{ClientConfig, LogSummaryStyle, SyncConfig, UpdateableClientConfig};
 use near_chunks::adapter::ShardsManagerRequestFromClient;
 use near_chunks::client::ShardedTransactionPool;
 use near_chunks::logic::{
@@ -62,8 +65,9 @@ use near_network::types::{
 use near_pool::InsertTransactionResult;
 use near_primitives::block::{Approval, ApprovalInner, ApprovalMessage, Block, BlockHeader, Tip};
 use near_primitives::block_header::ApprovalType;
-use near_primitives::challenge::{Challenge, ChallengeBody, PartialState};
+use near_primitives::challenge::{BlockDoubleSign, Challenge, ChallengeBody, PartialState};
 use near_primitives::epoch_manager::RngSeed;
+use near_primitives::equivocation::EquivocationEvidence;
 use near_primitives::errors::EpochError;
 use near_primitives::hash::CryptoHash;
 use near_primitives::merkle::{merklize, MerklePath, PartialMerkleTree};
@@ -73,13 +77,14 @@ use near_primitives::sharding::StateSyncInfo;
 use near_primitives::sharding::{
     ChunkHash, EncodedShardChunk, PartialEncodedChunk, ShardChunk, ShardChunkHeader, ShardInfo,
 };
+use near_primitives::state_sync::ReceiptProofResponse;
 use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::chunk_extra::ChunkExtra;
 use near_primitives::types::{AccountId, ApprovalStake, BlockHeight, EpochId, NumBlocks, ShardId};
 use near_primitives::unwrap_or_return;
 use near_primitives::utils::MaybeValidated;
 use near_primitives::validator_signer::ValidatorSigner;
-use near_primitives::version::PROTOCOL_VERSION;
+use near_primitives::version::{ProtocolVersion, PROTOCOL_VERSION};
 use near_primitives::views::{CatchupStatusView, DroppedReason};
 use near_store::ShardUId;
 use reed_solomon_erasure::galois_8::ReedSolomon;
@@ -127,10 +132,18 @@ pub struct Client {
     pub produce_invalid_chunks: bool,
     #[cfg(feature = "test_features")]
     pub produce_invalid_tx_in_chunks: bool,
+    /// Protocol version voted for in the produced blocks instead of the one of
+    /// the upgrade schedule, for scheduling the protocol upgrades in tests.
+    #[cfg(feature = "test_features")]
+    pub adv_protocol_version_vote: Option<ProtocolVersion>,
 
     /// Fast Forward accrued delta height used to calculate fast forwarded timestamps for each block.
     #[cfg(feature = "sandbox")]
     pub(crate) accrued_fastforward_delta: near_primitives::types::BlockHeightDelta,
+    /// Shards which don't produce chunks up to and including the given height, requested by
+    /// the sandbox fast forward to simulate missing chunks.
+    #[cfg(feature = "sandbox")]
+    pub(crate) sandbox_skip_chunks_until: HashMap<ShardId, BlockHeight>,
 
     pub clock: Clock,
     pub config: ClientConfig,
@@ -164,6 +177,8 @@ pub struct Client {
     pub state_sync: StateSync,
     /// List of currently accumulated challenges.
     pub challenges: HashMap<CryptoHash, Challenge>,
+    /// Detects the validators approving conflicting blocks.
+    equivocation_detector: EquivocationDetector,
     /// A ReedSolomon instance to reconstruct shard.
     pub rs_for_chunk_production: ReedSolomon,
     /// Blocks that have been re-broadcast recently. They should not be broadcast again.
@@ -198,12 +213,64 @@ pub struct Client {
 }
 
 impl Client {
-    pub(crate) fn update_client_config(&self, update_client_config: UpdateableClientConfig) {
+    pub(crate) fn update_client_config(&mut self, update_client_config: UpdateableClientConfig) {
         self.config.expected_shutdown.update(update_client_config.expected_shutdown);
         self.config.resharding_config.update(update_client_config.resharding_config);
         self.config
             .produce_chunk_add_transactions_time_limit
             .update(update_client_config.produce_chunk_add_transactions_time_limit);
+        if self.config.transaction_pool_size_limit
+            != update_client_config.transaction_pool_size_limit
+        {
+            tracing::info!(target: "config", from = ?self.config.transaction_pool_size_limit, to = ?update_client_config.transaction_pool_size_limit, "Updated config field 'transaction_pool_size_limit'");
+            self.config.transaction_pool_size_limit =
+                update_client_config.transaction_pool_size_limit;
+            self.sharded_tx_pool.set_pool_size_limit(self.config.transaction_pool_size_limit);
+        }
+        if let SyncConfig::ExternalStorage(external) = &mut self.config.state_sync.sync {
+            if let Some(num_concurrent_requests) =
+                update_client_config.state_sync_num_concurrent_requests
+            {
+                external.num_concurrent_requests = num_concurrent_requests;
+                self.state_sync.set_num_concurrent_requests(num_concurrent_requests);
+            }
+            if let Some(num_concurrent_requests) =
+                update_client_config.state_sync_num_concurrent_requests_during_catchup
+            {
+                external.num_concurrent_requests_during_catchup = num_concurrent_requests;
+                for (state_sync, _, _) in self.catchup_state_syncs.values_mut() {
+                    state_sync.set_num_concurrent_requests(num_concurrent_requests);
+                }
+            }
+        }
+    }
+
+    /// Whether the height after the head is to be signed with the key of the
+    /// given signer, which is also the case if the validator doesn't validate
+    /// in the epoch of that height.
+    pub(crate) fn is_validator_key_active_at_next_height(
+        &self,
+        signer: &dyn ValidatorSigner,
+    ) -> bool {
+        let Ok(head) = self.chain.head() else {
+            return false;
+        };
+        let Ok(epoch_id) = self.epoch_manager.get_epoch_id_from_prev_block(&head.last_block_hash)
+        else {
+            return false;
+        };
+        match self.epoch_manager.get_validator_by_account_id(
+            &epoch_id,
+            &head.last_block_hash,
+            signer.validator_id(),
+        ) {
+            Ok((validator_stake, _)) => validator_stake.public_key() == &signer.public_key(),
+            Err(EpochError::NotAValidator(_, _)) => true,
+            Err(err) => {
+                tracing::warn!(target: "client", ?err, "Failed checking the validator key of the next height");
+                false
+            }
+        }
     }
 }
 
@@ -265,6 +332,7 @@ impl Client {
             save_trie_changes: config.save_trie_changes,
             background_migration_threads: config.client_background_migration_threads,
             resharding_config: config.resharding_config.clone(),
+            save_account_index: config.save_account_index,
         };
         let chain = Chain::new(
             clock.clone(),
@@ -379,8 +447,12 @@ impl Client {
             produce_invalid_chunks: false,
             #[cfg(feature = "test_features")]
             produce_invalid_tx_in_chunks: false,
+            #[cfg(feature = "test_features")]
+            adv_protocol_version_vote: None,
             #[cfg(feature = "sandbox")]
             accrued_fastforward_delta: 0,
+            #[cfg(feature = "sandbox")]
+            sandbox_skip_chunks_until: HashMap::new(),
             clock: clock.clone(),
             config,
             sync_status,
@@ -401,6 +473,7 @@ impl Client {
             block_sync,
             state_sync,
             challenges: Default::default(),
+            equivocation_detector: EquivocationDetector::new(),
             rs_for_chunk_production: ReedSolomon::new(data_parts, parity_parts).unwrap(),
             rebroadcasted_blocks: lru::LruCache::new(NUM_REBROADCAST_BLOCKS),
             last_time_head_progress_made: clock.now(),
@@ -788,7 +861,9 @@ impl Client {
         let next_epoch_protocol_version =
             self.epoch_manager.get_epoch_protocol_version(&next_epoch_id)?;
 
-        let block = Block::produce(
+        let signing_timer = self.clock.now();
+        #[allow(unused_mut)]
+        let mut block = Block::produce(
             this_epoch_protocol_version,
             next_epoch_protocol_version,
             prev_header,
@@ -811,6 +886,15 @@ impl Client {
             block_merkle_root,
             block_timestamp,
         );
+        #[cfg(feature = "test_features")]
+        if let Some(protocol_version) = self.adv_protocol_version_vote {
+            block.mut_header().set_latest_protocol_version(protocol_version);
+            block.mut_header().resign(&*validator_signer);
+        }
+        ProductionPhase::Signing.record(
+            self.clock.now().signed_duration_since(signing_timer),
+            self.config.min_block_production_delay,
+        );
 
         // Update latest known even before returning block out, to prevent race conditions.
         self.chain
@@ -898,8 +982,13 @@ impl Client {
             .get_chunk_extra(&prev_block_hash, &shard_uid)
             .map_err(|err| Error::ChunkProducer(format!("No chunk extra available: {}", err)))?;
 
+        let tx_selection_timer = self.clock.now();
         let prepared_transactions =
             self.prepare_transactions(shard_uid, prev_block, chunk_extra.as_ref())?;
+        ProductionPhase::TxSelection.record(
+            self.clock.now().signed_duration_since(tx_selection_timer),
+            self.config.min_block_production_delay,
+        );
         #[cfg(feature = "test_features")]
         let prepared_transactions = Self::maybe_insert_invalid_transaction(
             prepared_transactions,
@@ -916,10 +1005,25 @@ impl Client {
 
         let outgoing_receipts_root = self.calculate_receipts_root(epoch_id, &outgoing_receipts)?;
         let protocol_version = self.epoch_manager.get_epoch_protocol_version(epoch_id)?;
+        if num_filtered_transactions == 0
+            && outgoing_receipts.is_empty()
+            && self.can_skip_empty_chunk(
+                prev_block,
+                shard_id,
+                &last_header,
+                chunk_extra.as_ref(),
+                protocol_version,
+            )?
+        {
+            debug!(target: "client", next_height, shard_id, "Not producing an empty chunk of an idle shard");
+            metrics::CHUNK_SKIPPED_EMPTY_TOTAL.inc();
+            return Ok(None);
+        }
         let gas_used = chunk_extra.gas_used();
         #[cfg(feature = "test_features")]
         let gas_used = if self.produce_invalid_chunks { gas_used + 1 } else { gas_used };
         let congestion_info = chunk_extra.congestion_info().unwrap_or_default();
+        let chunk_header_timer = self.clock.now();
         let (encoded_chunk, merkle_paths) = ShardsManagerActor::create_encoded_shard_chunk(
             prev_block_hash,
             *chunk_extra.state_root(),
@@ -939,6 +1043,10 @@ impl Client {
             &mut self.rs_for_chunk_production,
             protocol_version,
         )?;
+        ProductionPhase::ChunkHeaderCreation.record(
+            self.clock.now().signed_duration_since(chunk_header_timer),
+            self.config.min_block_production_delay,
+        );
 
         span.record("chunk_hash", tracing::field::debug(encoded_chunk.chunk_hash()));
         debug!(target: "client",
@@ -975,6 +1083,47 @@ impl Client {
         }))
     }
 
+    /// Returns whether the chunk of the shard may be omitted, with its
+    /// transactions and outgoing receipts being empty: nothing waits to be
+    /// processed in the shard, and the other nodes count the omitted chunk as
+    /// produced.
+    fn can_skip_empty_chunk(
+        &self,
+        prev_block: &Block,
+        shard_id: ShardId,
+        last_header: &ShardChunkHeader,
+        chunk_extra: &ChunkExtra,
+        protocol_version: ProtocolVersion,
+    ) -> Result<bool, Error> {
+        if !self.config.skip_empty_chunks
+            || !Chain::skips_empty_chunks(self.epoch_manager.as_ref(), protocol_version)
+        {
+            return Ok(false);
+        }
+        let Some(congestion_info) = chunk_extra.congestion_info() else {
+            return Ok(false);
+        };
+        if congestion_info.delayed_receipts_gas() != 0
+            || congestion_info.buffered_receipts_gas() != 0
+            || chunk_extra.validator_proposals().next().is_some()
+        {
+            return Ok(false);
+        }
+        if !Chain::block_has_no_receipts(self.epoch_manager.as_ref(), prev_block.header())? {
+            return Ok(false);
+        }
+        // The receipts of the blocks since the last chunk of the shard.
+        let incoming_receipts = self.chain.chain_store().get_incoming_receipts_for_shard(
+            self.epoch_manager.as_ref(),
+            shard_id,
+            *prev_block.hash(),
+            last_header.height_included(),
+        )?;
+        Ok(incoming_receipts
+            .iter()
+            .all(|ReceiptProofResponse(_, proofs)| proofs.iter().all(|proof| proof.0.is_empty())))
+    }
+
     /// Calculates the root of receipt proofs.
     /// All receipts are groupped by receiver_id and hash is calculated
     /// for each such group. Then we merkalize these hashes to calculate
@@ -1029,12 +1178,19 @@ impl Client {
         prev_block: &Block,
         chunk_extra: &ChunkExtra,
     ) -> Result<PreparedTransactions, Error> {
+        let epoch_id = self.epoch_manager.get_epoch_id_from_prev_block(prev_block.hash())?;
+        let protocol_version = self.epoch_manager.get_epoch_protocol_version(&epoch_id)?;
+        let priority_fee_enabled =
+            self.runtime_adapter.get_runtime_config(protocol_version).priority_fee_enabled;
         let Self { chain, sharded_tx_pool, runtime_adapter: runtime, .. } = self;
         let shard_id = shard_uid.shard_id as ShardId;
 
-        let prepared_transactions = if let Some(mut iter) =
+        let pool_iterator = if priority_fee_enabled {
+            sharded_tx_pool.get_priority_fee_pool_iterator(shard_uid)
+        } else {
             sharded_tx_pool.get_pool_iterator(shard_uid)
-        {
+        };
+        let prepared_transactions = if let Some(mut iter) = pool_iterator {
             let storage_config = RuntimeStorageConfig {
                 state_root: *chunk_extra.state_root(),
                 use_flat_storage: true,
@@ -1063,6 +1219,11 @@ impl Client {
     }
 
     pub fn send_challenges(&mut self, challenges: Vec<ChallengeBody>) {
+        for body in &challenges {
+            if let ChallengeBody::BlockDoubleSign(double_sign) = body {
+                self.on_block_double_sign(double_sign);
+            }
+        }
         if let Some(validator_signer) = &self.validator_signer {
             for body in challenges {
                 let challenge = Challenge::produce(body, &**validator_signer);
@@ -1074,6 +1235,82 @@ impl Client {
         }
     }
 
+    /// Records the evidence of the block producer producing both blocks of the
+    /// double sign challenge.
+    fn on_block_double_sign(&mut self, double_sign: &BlockDoubleSign) {
+        let (Ok(left), Ok(right)) = (
+            BlockHeader::try_from_slice(&double_sign.left_block_header),
+            BlockHeader::try_from_slice(&double_sign.right_block_header),
+        ) else {
+            return;
+        };
+        let account_id = match self.epoch_manager.get_block_producer(left.epoch_id(), left.height())
+        {
+            Ok(account_id) => account_id,
+            Err(err) => {
+                debug!(target: "client", ?err, height = left.height(), "Failed to get the producer of the double signed blocks");
+                return;
+            }
+        };
+        self.on_equivocation_observed(EquivocationEvidence::Block {
+            account_id,
+            left: Box::new(left),
+            right: Box::new(right),
+        });
+    }
+
+    /// Records the evidence observed by the node itself, and gossips it to the
+    /// peers if the `equivocation_gossip` feature is enabled.
+    fn on_equivocation_observed(&mut self, evidence: EquivocationEvidence) {
+        if self.record_equivocation_evidence(&evidence, "observed")
+            && cfg!(feature = "equivocation_gossip")
+        {
+            self.network_adapter.send(PeerManagerMessageRequest::NetworkRequests(
+                NetworkRequests::EquivocationEvidence(Box::new(evidence)),
+            ));
+        }
+    }
+
+    /// Persists the evidence, unless the node already has evidence of the
+    /// validator equivocating at the height or too much evidence of the
+    /// validator. Returns whether the evidence was persisted.
+    fn record_equivocation_evidence(&self, evidence: &EquivocationEvidence, source: &str) -> bool {
+        match self.chain.chain_store().save_equivocation_evidence(evidence) {
+            Ok(true) => {}
+            Ok(false) => return false,
+            Err(err) => {
+                error!(target: "client", ?err, "Failed to save the equivocation evidence");
+                return false;
+            }
+        }
+        warn!(
+            target: "client",
+            account_id = %evidence.account_id(),
+            height = evidence.height(),
+            kind = evidence.kind(),
+            source,
+            "Validator signed conflicting messages",
+        );
+        metrics::EQUIVOCATION_EVIDENCE.with_label_values(&[evidence.kind(), source]).inc();
+        true
+    }
+
+    /// Processes the evidence gossiped by a peer, recording it if it is valid
+    /// and new to the node. The received evidence is not gossiped further,
+    /// every node only broadcasts the evidence it observed itself.
+    pub fn process_equivocation_evidence(
+        &mut self,
+        evidence: EquivocationEvidence,
+    ) -> Result<(), Error> {
+        let head = self.chain.head()?;
+        if !verify_evidence(self.epoch_manager.as_ref(), &head, &evidence)? {
+            debug!(target: "client", account_id = %evidence.account_id(), height = evidence.height(), "Received invalid equivocation evidence");
+            return Ok(());
+        }
+        self.record_equivocation_evidence(&evidence, "received");
+        Ok(())
+    }
+
     /// Processes received block. Ban peer if the block header is invalid or the block is ill-formed.
     // This function is just a wrapper for process_block_impl that makes error propagation easier.
     pub fn receive_block(
@@ -1777,6 +2014,21 @@ impl Client {
                 continue;
             }
 
+            #[cfg(feature = "sandbox")]
+            if self
+                .sandbox_skip_chunks_until
+                .get(&shard_id)
+                .is_some_and(|until| next_height <= *until)
+            {
+                tracing::info!(
+                    target: "client",
+                    next_height,
+                    shard_id,
+                    "sandbox: skipping chunk production"
+                );
+                continue;
+            }
+
             let _span = debug_span!(
                 target: "client",
                 "on_block_accepted",
@@ -1795,6 +2047,7 @@ impl Client {
                 shard_id,
             ) {
                 Ok(Some(result)) => {
+                    let broadcast_timer = self.clock.now();
                     let shard_chunk = self
                         .persist_and_distribute_encoded_chunk(
                             result.chunk,
@@ -1812,6 +2065,10 @@ impl Client {
                     ) {
                         tracing::error!(target: "client", ?err, "Failed to send chunk state witness to chunk validators");
                     }
+                    ProductionPhase::Broadcast.record(
+                        self.clock.now().signed_duration_since(broadcast_timer),
+                        self.config.min_block_production_delay,
+                    );
                 }
                 Ok(None) => {}
                 Err(err) => {
@@ -2083,6 +2340,11 @@ impl Client {
                 Ok(true) => {}
                 _ => return,
             }
+            if let Some(evidence) =
+                self.equivocation_detector.on_approval(&validator_epoch_id, approval)
+            {
+                self.on_equivocation_observed(evidence);
+            }
         }
 
         let is_block_producer =
@@ -2121,10 +2383,14 @@ impl Client {
         self.doomslug.on_approval_message(approval, &block_producer_stakes);
     }
 
-    /// Forwards given transaction to upcoming validators.
-    fn forward_tx(&self, epoch_id: &EpochId, tx: &SignedTransaction) -> Result<(), Error> {
-        let shard_id =
-            self.epoch_manager.account_id_to_shard_id(tx.transaction.signer_id(), epoch_id)?;
+    /// Returns the upcoming chunk producers of the shard of the signer, other
+    /// than this node, which its transactions are forwarded to.
+    pub(crate) fn tx_forward_targets(
+        &self,
+        epoch_id: &EpochId,
+        signer_id: &AccountId,
+    ) -> Result<HashSet<AccountId>, Error> {
+        let shard_id = self.epoch_manager.account_id_to_shard_id(signer_id, epoch_id)?;
         // Use the header head to make sure the list of validators is as
         // up-to-date as possible.
         let head = self.chain.header_head()?;
@@ -2139,9 +2405,8 @@ impl Client {
                 self.epoch_manager.get_chunk_producer(epoch_id, target_height, shard_id)?;
             validators.insert(validator);
             if let Some(next_epoch_id) = &maybe_next_epoch_id {
-                let next_shard_id = self
-                    .epoch_manager
-                    .account_id_to_shard_id(tx.transaction.signer_id(), next_epoch_id)?;
+                let next_shard_id =
+                    self.epoch_manager.account_id_to_shard_id(signer_id, next_epoch_id)?;
                 let validator = self.epoch_manager.get_chunk_producer(
                     next_epoch_id,
                     target_height,
@@ -2154,7 +2419,14 @@ impl Client {
         if let Some(account_id) = self.validator_signer.as_ref().map(|bp| bp.validator_id()) {
             validators.remove(account_id);
         }
-        for validator in validators {
+        Ok(validators)
+    }
+
+    /// Forwards given transaction to upcoming validators.
+    fn forward_tx(&self, epoch_id: &EpochId, tx: &SignedTransaction) -> Result<(), Error> {
+        let shard_id =
+            self.epoch_manager.account_id_to_shard_id(tx.transaction.signer_id(), epoch_id)?;
+        for validator in self.tx_forward_targets(epoch_id, tx.transaction.signer_id())? {
             trace!(target: "client", me = ?self.validator_signer.as_ref().map(|bp| bp.validator_id()), ?tx, ?validator, shard_id, "Routing a transaction");
 
             // Send message to network to actually forward transaction.
@@ -2185,7 +2457,10 @@ impl Client {
     }
 
     /// If we are close to epoch boundary, return next epoch id, otherwise return None.
-    fn get_next_epoch_id_if_at_boundary(&self, head: &Tip) -> Result<Option<EpochId>, Error> {
+    pub(crate) fn get_next_epoch_id_if_at_boundary(
+        &self,
+        head: &Tip,
+    ) -> Result<Option<EpochId>, Error> {
         let next_epoch_started =
             self.epoch_manager.is_next_block_epoch_start(&head.last_block_hash)?;
         if next_epoch_started {
@@ -2258,8 +2533,6 @@ impl Client {
             self.shard_tracker.care_about_shard(me, &head.last_block_hash, shard_id, true);
         let will_care_about_shard =
             self.shard_tracker.will_care_about_shard(me, &head.last_block_hash, shard_id, true);
-        // TODO(resharding) will_care_about_shard should be called with the
-        // account shard id from the next epoch, in case shard layout changes
         if care_about_shard || will_care_about_shard {
             let shard_uid = self.epoch_manager.shard_id_to_uid(shard_id, &epoch_id)?;
             let state_root = match self.chain.get_chunk_extra(&head.last_block_hash, &shard_uid) {
@@ -2343,7 +2616,7 @@ impl Client {
     }
 
     /// Determine if I am a validator in next few blocks for specified shard, assuming epoch doesn't change.
-    fn active_validator(&self, shard_id: ShardId) -> Result<bool, Error> {
+    pub(crate) fn active_validator(&self, shard_id: ShardId) -> Result<bool, Error> {
         let head = self.chain.head()?;
         let epoch_id = self.epoch_manager.get_epoch_id_from_prev_block(&head.last_block_hash)?;
 
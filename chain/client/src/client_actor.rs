@@ -11,8 +11,11 @@ use crate::client::{Client, EPOCH_START_INFO_BLOCKS};
 use crate::config_updater::ConfigUpdater;
 use crate::debug::new_network_info_view;
 use crate::info::{display_sync_status, InfoHelper};
+use crate::production_budget::ProductionPhase;
 use crate::stateless_validation::partial_witness::partial_witness_actor::PartialWitnessSenderForClient;
+use crate::stateless_validation::partial_witness::partial_witness_history::PartialWitnessHistory;
 use crate::sync::adapter::{SyncMessage, SyncShardInfo};
+use crate::sync::progress::SyncProgressTracker;
 use crate::sync::state::{StateSync, StateSyncResult};
 use crate::sync_jobs_actor::{ClientSenderForSyncJobs, SyncJobsActor};
 use crate::{metrics, StatusResponse, SyncAdapter};
@@ -30,6 +33,7 @@ use near_chain::chain::{
     ApplyChunksDoneMessage, ApplyStatePartsRequest, ApplyStatePartsResponse, BlockCatchUpRequest,
     BlockCatchUpResponse, ChunkStateWitnessMessage, LoadMemtrieRequest, LoadMemtrieResponse,
 };
+use near_chain::chunk_apply_results::ChunkApplyResults;
 use near_chain::rayon_spawner::RayonAsyncComputationSpawner;
 use near_chain::resharding::{ReshardingRequest, ReshardingResponse};
 use near_chain::state_snapshot_actor::SnapshotCallbacks;
@@ -54,7 +58,7 @@ use near_epoch_manager::shard_tracker::ShardTracker;
 use near_epoch_manager::{EpochManagerAdapter, RngSeed};
 use near_network::client::{
     BlockApproval, BlockHeadersResponse, BlockResponse, ChunkEndorsementMessage, ProcessTxRequest,
-    ProcessTxResponse, RecvChallenge, SetNetworkInfo, StateResponse,
+    ProcessTxResponse, RecvChallenge, RecvEquivocationEvidence, SetNetworkInfo, StateResponse,
 };
 use near_network::types::ReasonForBan;
 use near_network::types::{
@@ -64,6 +68,7 @@ use near_performance_metrics;
 use near_performance_metrics_macros::perf;
 use near_primitives::block::Tip;
 use near_primitives::block_header::ApprovalType;
+use near_primitives::congestion_info::CongestionControl;
 use near_primitives::hash::CryptoHash;
 use near_primitives::network::{AnnounceAccount, PeerId};
 use near_primitives::types::{BlockHeight, EpochId};
@@ -81,6 +86,7 @@ use rand::{thread_rng, Rng};
 use std::collections::HashMap;
 use std::fmt;
 use std::sync::{Arc, RwLock};
+use time::ext::InstantExt as _;
 use tokio::sync::broadcast;
 use tracing::{debug, debug_span, error, info, trace, warn};
 
@@ -120,6 +126,8 @@ pub struct StartClientResult {
     pub client_actor: actix::Addr<ClientActor>,
     pub client_arbiter_handle: actix::ArbiterHandle,
     pub resharding_handle: ReshardingHandle,
+    /// Subscriptions to the results of applying the chunks.
+    pub chunk_apply_results: ChunkApplyResults,
 }
 
 /// Starts client in a separate Arbiter (thread).
@@ -141,6 +149,7 @@ pub fn start_client(
     adv: crate::adversarial::Controls,
     config_updater: Option<ConfigUpdater>,
     partial_witness_adapter: PartialWitnessSenderForClient,
+    partial_witness_history: PartialWitnessHistory,
     enable_doomslug: bool,
     seed: Option<RngSeed>,
 ) -> StartClientResult {
@@ -167,6 +176,7 @@ pub fn start_client(
     )
     .unwrap();
     let resharding_handle = client.chain.resharding_handle.clone();
+    let chunk_apply_results = client.chain.chunk_apply_results.clone();
 
     let client_sender_for_sync_jobs = LateBoundSender::<ClientSenderForSyncJobs>::new();
     let sync_jobs_actor = SyncJobsActor::new(client_sender_for_sync_jobs.as_multi_sender());
@@ -189,6 +199,7 @@ pub fn start_client(
             config_updater,
             sync_jobs_actor_addr.with_auto_span_context().into_multi_sender(),
             Box::new(ActixArbiterHandleFutureSpawner(sync_jobs_arbiter)),
+            partial_witness_history,
         )
         .unwrap();
         ActixWrapper::new(client_actor_inner)
@@ -198,7 +209,12 @@ pub fn start_client(
         .bind(client_addr.clone().with_auto_span_context().into_multi_sender());
     client_sender_for_client.bind(client_addr.clone().with_auto_span_context().into_multi_sender());
 
-    StartClientResult { client_actor: client_addr, client_arbiter_handle, resharding_handle }
+    StartClientResult {
+        client_actor: client_addr,
+        client_arbiter_handle,
+        resharding_handle,
+        chunk_apply_results,
+    }
 }
 
 #[derive(Clone, MultiSend, MultiSenderFrom, MultiSendMessage)]
@@ -263,6 +279,8 @@ pub struct ClientActorInner {
     sync_started: bool,
     sync_jobs_sender: SyncJobsSenderForClient,
     state_parts_future_spawner: Box<dyn FutureSpawner>,
+    /// Tracks the sync progress reported by the status.
+    sync_progress_tracker: SyncProgressTracker,
 
     #[cfg(feature = "sandbox")]
     fastforward_delta: near_primitives::types::BlockHeightDelta,
@@ -273,6 +291,9 @@ pub struct ClientActorInner {
 
     /// Manages updating the config.
     config_updater: Option<ConfigUpdater>,
+
+    /// Arrival of the parts of the recent state witnesses, for the debug page.
+    pub(crate) partial_witness_history: PartialWitnessHistory,
 }
 
 impl messaging::Actor for ClientActorInner {
@@ -320,6 +341,7 @@ impl ClientActorInner {
         config_updater: Option<ConfigUpdater>,
         sync_jobs_sender: SyncJobsSenderForClient,
         state_parts_future_spawner: Box<dyn FutureSpawner>,
+        partial_witness_history: PartialWitnessHistory,
     ) -> Result<Self, Error> {
         if let Some(vs) = &validator_signer {
             info!(target: "client", "Starting validator node: {}", vs.validator_id());
@@ -361,6 +383,8 @@ impl ClientActorInner {
             config_updater,
             sync_jobs_sender,
             state_parts_future_spawner,
+            sync_progress_tracker: SyncProgressTracker::default(),
+            partial_witness_history,
         })
     }
 }
@@ -619,6 +643,18 @@ impl Handler<RecvChallenge> for ClientActorInner {
     }
 }
 
+impl Handler<RecvEquivocationEvidence> for ClientActorInner {
+    fn handle(&mut self, msg: RecvEquivocationEvidence) {
+        if !cfg!(feature = "equivocation_gossip") {
+            return;
+        }
+        let RecvEquivocationEvidence(evidence) = msg;
+        if let Err(err) = self.client.process_equivocation_evidence(evidence) {
+            debug!(target: "client", ?err, "Error processing equivocation evidence");
+        }
+    }
+}
+
 impl Handler<SetNetworkInfo> for ClientActorInner {
     fn handle(&mut self, msg: SetNetworkInfo) {
         // SetNetworkInfo is a large message. Avoid printing it at the `debug` verbosity.
@@ -634,9 +670,13 @@ impl Handler<near_client_primitives::types::SandboxMessage> for ClientActorInner
         msg: near_client_primitives::types::SandboxMessage,
     ) -> near_client_primitives::types::SandboxResponse {
         match msg {
-            near_client_primitives::types::SandboxMessage::SandboxPatchState(state) => {
+            near_client_primitives::types::SandboxMessage::SandboxPatchState {
+                records,
+                access_key_patches,
+            } => {
                 self.client.chain.patch_state(
-                    near_primitives::sandbox::state_patch::SandboxStatePatch::new(state),
+                    near_primitives::sandbox::state_patch::SandboxStatePatch::new(records)
+                        .with_access_key_patches(access_key_patches),
                 );
                 near_client_primitives::types::SandboxResponse::SandboxNoResponse
             }
@@ -645,12 +685,29 @@ impl Handler<near_client_primitives::types::SandboxMessage> for ClientActorInner
                     !self.client.chain.patch_state_in_progress(),
                 )
             }
-            near_client_primitives::types::SandboxMessage::SandboxFastForward(delta_height) => {
+            near_client_primitives::types::SandboxMessage::SandboxFastForward {
+                delta_height,
+                skip_chunks_shard_ids,
+                skip_chunks_delta_height,
+            } => {
                 if self.fastforward_delta > 0 {
                     return near_client_primitives::types::SandboxResponse::SandboxFastForwardFailed(
                         "Consecutive fast_forward requests cannot be made while a current one is going on.".to_string());
                 }
 
+                if !skip_chunks_shard_ids.is_empty() {
+                    let head_height = match self.client.chain.head() {
+                        Ok(head) => head.height,
+                        Err(err) => {
+                            return near_client_primitives::types::SandboxResponse::SandboxFastForwardFailed(
+                                format!("Failed to get chain head: {err}"));
+                        }
+                    };
+                    let skip_until = head_height + delta_height + skip_chunks_delta_height;
+                    for shard_id in skip_chunks_shard_ids {
+                        self.client.sandbox_skip_chunks_until.insert(shard_id, skip_until);
+                    }
+                }
                 self.fastforward_delta = delta_height;
                 near_client_primitives::types::SandboxResponse::SandboxNoResponse
             }
@@ -753,6 +810,8 @@ impl Handler<Status> for ClientActorInner {
             None
         };
         let uptime_sec = self.clock.now_utc().unix_timestamp() - self.info_helper.boot_time_seconds;
+        let sync_progress =
+            self.sync_progress_tracker.progress(&self.client.sync_status, self.clock.now());
         Ok(StatusResponse {
             version: self.client.config.version.clone(),
             protocol_version,
@@ -772,6 +831,7 @@ impl Handler<Status> for ClientActorInner {
                 earliest_block_time,
                 epoch_id: Some(head.epoch_id),
                 epoch_start_height,
+                sync_progress,
             },
             validator_account_id,
             validator_public_key,
@@ -1160,9 +1220,12 @@ impl ClientActorInner {
     pub(crate) fn check_triggers(&mut self, ctx: &mut dyn DelayedActionRunner<Self>) -> Duration {
         let _span = tracing::debug_span!(target: "client", "check_triggers").entered();
         if let Some(config_updater) = &mut self.config_updater {
-            config_updater.try_update(&|updateable_client_config| {
+            config_updater.try_update(&mut |updateable_client_config| {
                 self.client.update_client_config(updateable_client_config)
             });
+            config_updater.try_rotate_validator_signer(&|signer| {
+                self.client.is_validator_key_active_at_next_height(signer)
+            });
         }
 
         // Check block height to trigger expected shutdown
@@ -1295,9 +1358,14 @@ impl ClientActorInner {
         let _span = tracing::debug_span!(target: "client", "produce_block", next_height).entered();
         if let Some(block) = self.client.produce_block_on_head(next_height, false)? {
             // If we produced the block, send it out before we apply the block.
+            let broadcast_timer = self.clock.now();
             self.network_adapter.send(PeerManagerMessageRequest::NetworkRequests(
                 NetworkRequests::Block { block: block.clone() },
             ));
+            ProductionPhase::Broadcast.record(
+                self.clock.now().signed_duration_since(broadcast_timer),
+                self.client.config.min_block_production_delay,
+            );
             // We’ve produced the block so that counts as validated block.
             let block = MaybeValidated::from_validated(block);
             let res = self.client.start_process_block(
@@ -1339,6 +1407,33 @@ impl ClientActorInner {
         }
     }
 
+    fn send_congestion_metrics(&mut self, block: &Block) {
+        let shards_congestion_info = block.shards_congestion_info();
+        if shards_congestion_info.is_empty() {
+            return;
+        }
+        let runtime_config = match self
+            .client
+            .runtime_adapter
+            .get_protocol_config(block.header().epoch_id())
+        {
+            Ok(protocol_config) => protocol_config.runtime_config,
+            Err(err) => {
+                warn!(target: "client", ?err, "Failed to get the runtime config for congestion metrics");
+                return;
+            }
+        };
+        for (shard_id, info) in shards_congestion_info {
+            let congestion_level = CongestionControl::new(
+                runtime_config.shard_congestion_control_config(shard_id),
+                info.congestion_info,
+                info.missed_chunks_count,
+            )
+            .congestion_level();
+            self.info_helper.shard_congestion_level(shard_id, congestion_level);
+        }
+    }
+
     fn send_block_metrics(&mut self, block: &Block) {
         let chunks_in_block = block.header().chunk_mask().iter().filter(|&&m| m).count();
         let gas_used = Block::compute_gas_used(block.chunks().iter(), block.header().height());
@@ -1389,6 +1484,7 @@ impl ClientActorInner {
             let block = self.client.chain.get_block(&accepted_block).unwrap().clone();
             self.send_chunks_metrics(&block);
             self.send_block_metrics(&block);
+            self.send_congestion_metrics(&block);
             self.check_send_announce_account(*block.header().last_final_block());
         }
     }
@@ -1,5 +1,6 @@
 use near_chain_configs::UpdateableClientConfig;
 use near_dyn_configs::{UpdateableConfigLoaderError, UpdateableConfigs};
+use near_primitives::validator_signer::{RotatableValidatorSigner, ValidatorSigner};
 use std::sync::Arc;
 use tokio::sync::broadcast::Receiver;
 
@@ -10,18 +11,39 @@ pub struct ConfigUpdater {
 
     /// Represents the latest Error of reading the dynamically reloadable configs.
     updateable_configs_error: Option<Arc<UpdateableConfigLoaderError>>,
+
+    /// Validator signer of the node, if its key can be rotated.
+    validator_signer: Option<Arc<RotatableValidatorSigner>>,
+    /// Signer with the reloaded validator key, waiting for the key to become
+    /// active.
+    pending_validator_signer: Option<Arc<dyn ValidatorSigner>>,
 }
 
 impl ConfigUpdater {
     pub fn new(
         rx_config_update: Receiver<Result<UpdateableConfigs, Arc<UpdateableConfigLoaderError>>>,
     ) -> Self {
-        Self { rx_config_update, updateable_configs_error: None }
+        Self {
+            rx_config_update,
+            updateable_configs_error: None,
+            validator_signer: None,
+            pending_validator_signer: None,
+        }
+    }
+
+    /// Rotates the key of the given validator signer when the validator key is
+    /// reloaded.
+    pub fn with_validator_signer(
+        mut self,
+        validator_signer: Arc<RotatableValidatorSigner>,
+    ) -> Self {
+        self.validator_signer = Some(validator_signer);
+        self
     }
 
     /// Check if any of the configs were updated.
     /// If they did, the receiver (rx_config_update) will contain a clone of the new configs.
-    pub fn try_update(&mut self, update_client_config_fn: &dyn Fn(UpdateableClientConfig)) {
+    pub fn try_update(&mut self, update_client_config_fn: &mut dyn FnMut(UpdateableClientConfig)) {
         while let Ok(maybe_updateable_configs) = self.rx_config_update.try_recv() {
             match maybe_updateable_configs {
                 Ok(updateable_configs) => {
@@ -29,6 +51,9 @@ impl ConfigUpdater {
                         update_client_config_fn(client_config);
                         tracing::info!(target: "config", "Updated ClientConfig");
                     }
+                    if let Some(validator_signer) = updateable_configs.validator_signer {
+                        self.stage_validator_signer(validator_signer);
+                    }
                     self.updateable_configs_error = None;
                 }
                 Err(err) => {
@@ -38,6 +63,55 @@ impl ConfigUpdater {
         }
     }
 
+    fn stage_validator_signer(&mut self, new_signer: Arc<dyn ValidatorSigner>) {
+        let Some(validator_signer) = &self.validator_signer else {
+            return;
+        };
+        if new_signer.validator_id() != validator_signer.validator_id() {
+            tracing::warn!(
+                target: "config",
+                validator_id = ?validator_signer.validator_id(),
+                new_validator_id = ?new_signer.validator_id(),
+                "The reloaded validator key is of a different account, ignoring it");
+            return;
+        }
+        if new_signer.public_key() == validator_signer.public_key() {
+            self.pending_validator_signer = None;
+            return;
+        }
+        tracing::info!(
+            target: "config",
+            public_key = ?new_signer.public_key(),
+            "Reloaded the validator key, waiting for the key to become active");
+        self.pending_validator_signer = Some(new_signer);
+    }
+
+    /// Switches to the reloaded validator key if `is_key_active` confirms that
+    /// the next height is signed with it.
+    ///
+    /// Call this between heights, so that everything of one height is signed
+    /// with the same key.
+    pub fn try_rotate_validator_signer(
+        &mut self,
+        is_key_active: &dyn Fn(&dyn ValidatorSigner) -> bool,
+    ) {
+        let (Some(validator_signer), Some(new_signer)) =
+            (&self.validator_signer, &self.pending_validator_signer)
+        else {
+            return;
+        };
+        if !is_key_active(new_signer.as_ref()) {
+            return;
+        }
+        let old_signer = validator_signer.rotate(new_signer.clone());
+        tracing::info!(
+            target: "config",
+            old_public_key = ?old_signer.public_key(),
+            new_public_key = ?new_signer.public_key(),
+            "Rotated the validator key");
+        self.pending_validator_signer = None;
+    }
+
     /// Prints an error if it's present.
     pub fn report_status(&self) {
         if let Some(updateable_configs_error) = &self.updateable_configs_error {
@@ -48,3 +122,51 @@ impl ConfigUpdater {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_crypto::KeyType;
+    use near_primitives::validator_signer::InMemoryValidatorSigner;
+
+    fn signer(account_id: &str, seed: &str) -> Arc<dyn ValidatorSigner> {
+        Arc::new(InMemoryValidatorSigner::from_seed(
+            account_id.parse().unwrap(),
+            KeyType::ED25519,
+            seed,
+        ))
+    }
+
+    #[test]
+    fn test_rotate_validator_signer() {
+        let (tx, rx) = tokio::sync::broadcast::channel(16);
+        let old_signer = signer("test", "old");
+        let validator_signer = Arc::new(RotatableValidatorSigner::new(old_signer.clone()));
+        let mut config_updater =
+            ConfigUpdater::new(rx).with_validator_signer(validator_signer.clone());
+        let reload = |signer| {
+            tx.send(Ok(UpdateableConfigs { validator_signer: Some(signer), ..Default::default() }))
+                .unwrap();
+        };
+
+        // A key of another account is ignored.
+        reload(signer("other", "new"));
+        config_updater.try_update(&mut |_| {});
+        config_updater.try_rotate_validator_signer(&|_| true);
+        assert_eq!(validator_signer.public_key(), old_signer.public_key());
+
+        // The new key is only used once it is active.
+        let new_signer = signer("test", "new");
+        reload(new_signer.clone());
+        config_updater.try_update(&mut |_| {});
+        config_updater.try_rotate_validator_signer(&|_| false);
+        assert_eq!(validator_signer.public_key(), old_signer.public_key());
+        config_updater
+            .try_rotate_validator_signer(&|signer| signer.public_key() == new_signer.public_key());
+        assert_eq!(validator_signer.public_key(), new_signer.public_key());
+        let chunk_hash = near_primitives::sharding::ChunkHash::default();
+        assert!(validator_signer
+            .sign_chunk_hash(&chunk_hash)
+            .verify(chunk_hash.as_ref(), &new_signer.public_key()));
+    }
+}
@@ -7,8 +7,11 @@ use near_async::time::{Clock, Instant};
 use near_chain::crypto_hash_timer::CryptoHashTimer;
 use near_chain::{near_chain_primitives, Chain, ChainStoreAccess};
 use near_client_primitives::debug::{
-    ApprovalAtHeightStatus, BlockProduction, ChunkCollection, DebugBlockStatusData, DebugStatus,
-    DebugStatusResponse, MissedHeightInfo, ProductionAtHeight, ValidatorStatus,
+    ApprovalAtHeightStatus, BlockProduction, ChunkCollection, CongestionAtHeightView,
+    DebugBlockStatusData, DebugStatus, DebugStatusResponse, EpochShardsView, MissedHeightInfo,
+    PooledTransactionView, ProductionAtHeight, SentChunkEndorsementView, ShardAssignmentView,
+    ShardCongestionView, ShardTxPoolView, ShardsView, StoreColumnStatsView, StoreStatsView,
+    TxRoutingView, ValidatorStatus,
 };
 use near_client_primitives::types::Error;
 use near_client_primitives::{
@@ -18,6 +21,8 @@ use near_client_primitives::{
 use near_epoch_manager::EpochManagerAdapter;
 use near_o11y::log_assert;
 use near_performance_metrics_macros::perf;
+use near_primitives::congestion_info::CongestionControl;
+use near_primitives::shard_layout::ShardLayout;
 use near_primitives::state_sync::get_num_state_parts;
 use near_primitives::types::{AccountId, BlockHeight, NumShards, ShardId, ValidatorInfoIdentifier};
 use near_primitives::{
@@ -26,8 +31,10 @@ use near_primitives::{
     types::EpochId,
     views::ValidatorInfo,
 };
+use near_store::db::StatsValue;
 use near_store::DBCol;
 use std::cmp::{max, min};
+use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
 use time::ext::InstantExt as _;
 
@@ -48,6 +55,9 @@ const DEBUG_PRODUCTION_OLD_BLOCKS_TO_SHOW: u64 = 50;
 // Maximum number of blocks to show.
 const DEBUG_MAX_PRODUCTION_BLOCKS_TO_SHOW: u64 = 1000;
 
+// Maximum number of transactions per shard to return from the transaction pool.
+const DEBUG_MAX_TX_POOL_ENTRIES_TO_SHOW: usize = 1000;
+
 /// Number of blocks (and chunks) for which to keep the detailed timing information for debug purposes.
 pub const PRODUCTION_TIMES_CACHE_SIZE: usize = 1000;
 
@@ -175,6 +185,24 @@ impl Handler<DebugStatus> for ClientActorInner {
             DebugStatus::ChainProcessingStatus => Ok(DebugStatusResponse::ChainProcessingStatus(
                 self.client.chain.get_chain_processing_info(),
             )),
+            DebugStatus::Congestion => {
+                Ok(DebugStatusResponse::Congestion(self.get_congestion_view()?))
+            }
+            DebugStatus::TxPool => Ok(DebugStatusResponse::TxPool(self.get_tx_pool_view()?)),
+            DebugStatus::ChunkEndorsements => {
+                Ok(DebugStatusResponse::ChunkEndorsements(self.get_sent_chunk_endorsements_view()?))
+            }
+            DebugStatus::TxRouting(signer_id) => Ok(DebugStatusResponse::TxRouting(
+                self.get_tx_routing_view(signer_id)
+                    .map_err(|err| StatusError::InternalError { error_message: err.to_string() })?,
+            )),
+            DebugStatus::StoreStats => {
+                Ok(DebugStatusResponse::StoreStats(self.get_store_stats_view()))
+            }
+            DebugStatus::PartialWitnesses => {
+                Ok(DebugStatusResponse::PartialWitnesses(self.partial_witness_history.views()))
+            }
+            DebugStatus::Shards => Ok(DebugStatusResponse::Shards(self.get_shards_view()?)),
         }
     }
 }
@@ -351,6 +379,377 @@ impl ClientActorInner {
         Ok(TrackedShardsView { shards_tracked_this_epoch, shards_tracked_next_epoch })
     }
 
+    /// Returns the shard layout of this and the next epoch as of the head,
+    /// with the chunk producers of every shard and why this node tracks it.
+    fn get_shards_view(&self) -> Result<ShardsView, near_chain_primitives::Error> {
+        let head = self.client.chain.head()?;
+        let me = self.client.validator_signer.as_ref().map(|x| x.validator_id().clone());
+        let next_epoch_id = self.client.epoch_manager.get_next_epoch_id(&head.last_block_hash)?;
+        let shard_layout = self.client.epoch_manager.get_shard_layout(&head.epoch_id)?;
+        let next_shard_layout = self.client.epoch_manager.get_shard_layout(&next_epoch_id)?;
+        let resharding_next_epoch = shard_layout != next_shard_layout;
+        let this_epoch = self.get_epoch_shards_view(&head.epoch_id, me.as_ref(), None)?;
+        let next_epoch = self.get_epoch_shards_view(
+            &next_epoch_id,
+            me.as_ref(),
+            Some(&shard_layout).filter(|_| resharding_next_epoch),
+        )?;
+        Ok(ShardsView { validator_account_id: me, this_epoch, next_epoch, resharding_next_epoch })
+    }
+
+    /// `previous_shard_layout` is the layout of the previous epoch, given if
+    /// the layout changes at the start of this one.
+    fn get_epoch_shards_view(
+        &self,
+        epoch_id: &EpochId,
+        me: Option<&AccountId>,
+        previous_shard_layout: Option<&ShardLayout>,
+    ) -> Result<EpochShardsView, near_chain_primitives::Error> {
+        let epoch_info = self.client.epoch_manager.get_epoch_info(epoch_id)?;
+        let shard_layout = self.client.epoch_manager.get_shard_layout(epoch_id)?;
+        let boundary_accounts = shard_layout.boundary_accounts();
+        let mut shards = vec![];
+        for shard_id in shard_layout.shard_ids() {
+            let chunk_producers: Vec<AccountId> = epoch_info
+                .chunk_producers_settlement()
+                .get(shard_id as usize)
+                .into_iter()
+                .flatten()
+                .map(|validator_id| epoch_info.validator_account_id(*validator_id).clone())
+                .collect();
+            let tracking_reasons =
+                self.client.shard_tracker.tracking_reasons(me, shard_id, epoch_id)?;
+            let index = shard_id as usize;
+            shards.push(ShardAssignmentView {
+                shard_id,
+                start_account: boundary_accounts
+                    .and_then(|accounts| accounts.get(index.checked_sub(1)?))
+                    .cloned(),
+                end_account: boundary_accounts.and_then(|accounts| accounts.get(index)).cloned(),
+                parent_shard_id: previous_shard_layout.and_then(|layout| {
+                    layout.shard_ids().find(|&parent_shard_id| {
+                        shard_layout
+                            .get_children_shards_ids(parent_shard_id)
+                            .is_some_and(|children| children.contains(&shard_id))
+                    })
+                }),
+                chunk_producers,
+                tracking_reasons,
+            });
+        }
+        Ok(EpochShardsView {
+            epoch_id: epoch_id.0,
+            epoch_height: epoch_info.epoch_height(),
+            protocol_version: epoch_info.protocol_version(),
+            shard_layout_version: shard_layout.version(),
+            shards,
+        })
+    }
+
+    /// Returns the congestion info of all shards for the last
+    /// `DEBUG_BLOCKS_TO_FETCH` blocks on the canonical chain, starting at head.
+    fn get_congestion_view(
+        &self,
+    ) -> Result<Vec<CongestionAtHeightView>, near_chain_primitives::Error> {
+        let mut configs = HashMap::new();
+        let mut block_hash = self.client.chain.head()?.last_block_hash;
+        let mut result = vec![];
+        for _ in 0..DEBUG_BLOCKS_TO_FETCH {
+            let Ok(block) = self.client.chain.get_block(&block_hash) else {
+                break;
+            };
+            let epoch_id = block.header().epoch_id().clone();
+            let config = match configs.entry(epoch_id) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let protocol_config =
+                        self.client.runtime_adapter.get_protocol_config(entry.key())?;
+                    entry.insert(protocol_config.runtime_config)
+                }
+            };
+            let mut shards = block
+                .shards_congestion_info()
+                .into_iter()
+                .map(|(shard_id, info)| {
+                    let congestion_level = CongestionControl::new(
+                        config.shard_congestion_control_config(shard_id),
+                        info.congestion_info,
+                        info.missed_chunks_count,
+                    )
+                    .congestion_level();
+                    ShardCongestionView {
+                        shard_id,
+                        congestion_level,
+                        delayed_receipts_gas: info.congestion_info.delayed_receipts_gas(),
+                        buffered_receipts_gas: info.congestion_info.buffered_receipts_gas(),
+                        receipt_bytes: info.congestion_info.receipt_bytes(),
+                        allowed_shard: info.congestion_info.allowed_shard(),
+                        missed_chunks_count: info.missed_chunks_count,
+                    }
+                })
+                .collect::<Vec<_>>();
+            shards.sort_by_key(|shard| shard.shard_id);
+            result.push(CongestionAtHeightView {
+                block_height: block.header().height(),
+                block_hash,
+                shards,
+            });
+            if block.header().is_genesis() {
+                break;
+            }
+            block_hash = *block.header().prev_hash();
+        }
+        Ok(result)
+    }
+
+    /// Returns the chunk endorsements recently sent by this node, newest first,
+    /// checked against the canonical block at the height of each chunk. With
+    /// stateless validation, the block at the height a chunk was created at
+    /// is the only one which can include the chunk and its endorsements.
+    fn get_sent_chunk_endorsements_view(
+        &self,
+    ) -> Result<Vec<SentChunkEndorsementView>, near_chain_primitives::Error> {
+        let me = self.client.validator_signer.as_ref().map(|x| x.validator_id().clone());
+        let mut result = vec![];
+        for (chunk_hash, sent) in self.client.chunk_endorsement_tracker.sent_chunk_endorsements() {
+            let mut view = SentChunkEndorsementView {
+                chunk_hash: chunk_hash.clone(),
+                shard_id: sent.shard_id,
+                height_created: sent.height_created,
+                block_producers: sent.block_producers,
+                block_hash: None,
+                chunk_included: false,
+                endorsement_included: false,
+                endorsement_bitmap: String::new(),
+                num_endorsements: 0,
+            };
+            let block = match self.client.chain.get_block_hash_by_height(sent.height_created) {
+                Ok(block_hash) => self.client.chain.get_block(&block_hash).ok(),
+                Err(_) => None,
+            };
+            if let Some(block) = block {
+                view.block_hash = Some(*block.hash());
+                view.chunk_included = block
+                    .chunks()
+                    .get(sent.shard_id as usize)
+                    .is_some_and(|chunk| chunk.chunk_hash() == chunk_hash);
+                if view.chunk_included {
+                    let signatures = block
+                        .chunk_endorsements()
+                        .get(sent.shard_id as usize)
+                        .map(|signatures| signatures.as_slice())
+                        .unwrap_or_default();
+                    let chunk_validators = self
+                        .client
+                        .epoch_manager
+                        .get_chunk_validator_assignments(
+                            block.header().epoch_id(),
+                            sent.shard_id,
+                            sent.height_created,
+                        )?
+                        .ordered_chunk_validators();
+                    view.endorsement_included = chunk_validators
+                        .iter()
+                        .position(|account_id| Some(account_id) == me.as_ref())
+                        .is_some_and(|index| signatures.get(index).is_some_and(Option::is_some));
+                    view.endorsement_bitmap = signatures
+                        .iter()
+                        .map(|signature| if signature.is_some() { '1' } else { '0' })
+                        .collect();
+                    view.num_endorsements =
+                        signatures.iter().filter(|signature| signature.is_some()).count();
+                }
+            }
+            result.push(view);
+        }
+        result.sort_by_key(|view| (std::cmp::Reverse(view.height_created), view.shard_id));
+        Ok(result)
+    }
+
+    /// Returns how a valid transaction of the signer received from a user is
+    /// routed, following `Client::process_tx_internal`.
+    fn get_tx_routing_view(&self, signer_id: AccountId) -> Result<TxRoutingView, Error> {
+        let client = &self.client;
+        let head = client.chain.head()?;
+        let me = client.validator_signer.as_ref().map(|x| x.validator_id().clone());
+        let epoch_id = client.epoch_manager.get_epoch_id_from_prev_block(&head.last_block_hash)?;
+        let shard_layout = client.epoch_manager.get_shard_layout(&epoch_id)?;
+        let shard_id = client.epoch_manager.account_id_to_shard_id(&signer_id, &epoch_id)?;
+        let next_epoch_id = client.get_next_epoch_id_if_at_boundary(&head)?;
+        let next_epoch_shard_id = match &next_epoch_id {
+            Some(next_epoch_id) => {
+                Some(client.epoch_manager.account_id_to_shard_id(&signer_id, next_epoch_id)?)
+            }
+            None => None,
+        };
+        let chunk_producers = (1..=client.config.tx_routing_height_horizon)
+            .map(|i| {
+                let height = head.height + i;
+                Ok((height, client.epoch_manager.get_chunk_producer(&epoch_id, height, shard_id)?))
+            })
+            .collect::<Result<_, Error>>()?;
+
+        let tracks_shard = client.shard_tracker.care_about_shard(
+            me.as_ref(),
+            &head.last_block_hash,
+            shard_id,
+            true,
+        ) || client.shard_tracker.will_care_about_shard(
+            me.as_ref(),
+            &head.last_block_hash,
+            shard_id,
+            true,
+        );
+        let shard_uid = client.epoch_manager.shard_id_to_uid(shard_id, &epoch_id)?;
+        let caught_up =
+            tracks_shard && client.chain.get_chunk_extra(&head.last_block_hash, &shard_uid).is_ok();
+        // A node which doesn't track the shard or hasn't caught up with it
+        // only forwards, the active validators of the shard forward to the
+        // validators of the next epoch.
+        let (pools, forward_epoch_id) = if !caught_up {
+            (false, epoch_id.clone())
+        } else if client.active_validator(shard_id)? {
+            (me.is_some(), next_epoch_id.unwrap_or_else(|| head.epoch_id.clone()))
+        } else {
+            (me.is_some(), epoch_id.clone())
+        };
+        let mut forwards_to: Vec<_> =
+            client.tx_forward_targets(&forward_epoch_id, &signer_id)?.into_iter().collect();
+        forwards_to.sort();
+
+        Ok(TxRoutingView {
+            signer_id,
+            epoch_id,
+            shard_id,
+            shard_layout_version: shard_layout.version(),
+            next_epoch_shard_id,
+            chunk_producers,
+            tracks_shard,
+            pools,
+            forwards_to,
+        })
+    }
+
+    /// Collects the RocksDB properties of the columns and the statistics of
+    /// the hot database.  Everything is empty for databases other than
+    /// RocksDB.
+    fn get_store_stats_view(&self) -> StoreStatsView {
+        let mut view = StoreStatsView::default();
+        let Some(stats) = self.client.chain.chain_store().store().get_store_statistics() else {
+            return view;
+        };
+        let mut columns = HashMap::<DBCol, StoreColumnStatsView>::new();
+        for (name, values) in stats.data {
+            for value in values {
+                match value {
+                    StatsValue::ColumnValue(col, value) => {
+                        let column = columns.entry(col).or_insert_with(|| StoreColumnStatsView {
+                            column: <&str>::from(col).to_string(),
+                            ..Default::default()
+                        });
+                        let field = match name.as_str() {
+                            "rocksdb.live-sst-files-size" => &mut column.live_sst_files_size,
+                            "rocksdb.estimate-live-data-size" => {
+                                &mut column.estimate_live_data_size
+                            }
+                            "rocksdb.estimate-num-keys" => &mut column.estimate_num_keys,
+                            "rocksdb.size-all-mem-tables" => &mut column.size_all_mem_tables,
+                            "rocksdb.block-cache-usage" => &mut column.block_cache_usage,
+                            "rocksdb.compaction-pending" => &mut column.compaction_pending,
+                            "rocksdb.num-running-compactions" => {
+                                &mut column.num_running_compactions
+                            }
+                            "rocksdb.estimate-pending-compaction-bytes" => {
+                                &mut column.estimate_pending_compaction_bytes
+                            }
+                            name => {
+                                let level = name
+                                    .strip_prefix("rocksdb.num-files-at-level")
+                                    .and_then(|level| level.parse::<usize>().ok());
+                                if let Some(level) = level {
+                                    let levels = &mut column.num_files_at_level;
+                                    if levels.len() <= level {
+                                        levels.resize(level + 1, 0);
+                                    }
+                                    levels[level] = value;
+                                }
+                                continue;
+                            }
+                        };
+                        *field = Some(value);
+                    }
+                    StatsValue::Count(count) => {
+                        let field = match name.as_str() {
+                            "rocksdb.block.cache.hit" => &mut view.block_cache_hit,
+                            "rocksdb.block.cache.miss" => &mut view.block_cache_miss,
+                            "rocksdb.block.cache.data.hit" => &mut view.block_cache_data_hit,
+                            "rocksdb.block.cache.data.miss" => &mut view.block_cache_data_miss,
+                            "rocksdb.block.cache.index.hit" => &mut view.block_cache_index_hit,
+                            "rocksdb.block.cache.index.miss" => &mut view.block_cache_index_miss,
+                            "rocksdb.block.cache.filter.hit" => &mut view.block_cache_filter_hit,
+                            "rocksdb.block.cache.filter.miss" => &mut view.block_cache_filter_miss,
+                            "rocksdb.compact.read.bytes" => &mut view.compaction_read_bytes,
+                            "rocksdb.compact.write.bytes" => &mut view.compaction_write_bytes,
+                            _ => continue,
+                        };
+                        *field = Some(count);
+                    }
+                    StatsValue::Sum(_) | StatsValue::Percentile(..) => {}
+                }
+            }
+        }
+        view.columns = columns.into_values().collect();
+        view.columns.sort_by(|a, b| a.column.cmp(&b.column));
+        view
+    }
+
+    /// Returns the transactions in the pool of every shard, oldest first. The
+    /// age of a transaction is measured from the block it references, as that
+    /// is what determines when it expires.
+    fn get_tx_pool_view(&self) -> Result<Vec<ShardTxPoolView>, near_chain_primitives::Error> {
+        let head_height = self.client.chain.head()?.height;
+        let mut reference_heights = HashMap::new();
+        let mut result = vec![];
+        for (shard_uid, pool) in self.client.sharded_tx_pool.pools() {
+            let mut transactions = pool
+                .transactions()
+                .map(|tx| {
+                    let block_hash = *tx.transaction.block_hash();
+                    let reference_height =
+                        *reference_heights.entry(block_hash).or_insert_with(|| {
+                            self.client
+                                .chain
+                                .get_block_header(&block_hash)
+                                .ok()
+                                .map(|header| header.height())
+                        });
+                    PooledTransactionView {
+                        hash: tx.get_hash(),
+                        signer_id: tx.transaction.signer_id().clone(),
+                        public_key: tx.transaction.public_key().clone(),
+                        receiver_id: tx.transaction.receiver_id().clone(),
+                        nonce: tx.transaction.nonce(),
+                        priority_fee: tx.transaction.priority_fee(),
+                        size: tx.get_size(),
+                        age_in_blocks: reference_height
+                            .map(|height| head_height.saturating_sub(height)),
+                    }
+                })
+                .collect::<Vec<_>>();
+            transactions.sort_by_key(|tx| std::cmp::Reverse(tx.age_in_blocks));
+            transactions.truncate(DEBUG_MAX_TX_POOL_ENTRIES_TO_SHOW);
+            result.push(ShardTxPoolView {
+                shard_id: shard_uid.shard_id as ShardId,
+                shard_layout_version: shard_uid.version,
+                num_transactions: pool.len(),
+                total_size: pool.transaction_size(),
+                transactions,
+            });
+        }
+        result.sort_by_key(|view| (view.shard_layout_version, view.shard_id));
+        Ok(result)
+    }
+
     fn get_recent_epoch_info(
         &mut self,
     ) -> Result<Vec<EpochInfoView>, near_chain_primitives::Error> {
@@ -0,0 +1,183 @@
+//! Detection of validators signing conflicting blocks or approvals, see
+//! `EquivocationEvidence`.
+//!
+//! The conflicting blocks are detected by the chain when validating the
+//! headers, and reported as double sign challenges. The conflicting
+//! approvals are detected here, by remembering the recent approvals received
+//! by the node.
+use lru::LruCache;
+use near_epoch_manager::EpochManagerAdapter;
+use near_primitives::block::{Approval, Tip};
+use near_primitives::equivocation::EquivocationEvidence;
+use near_primitives::types::{AccountId, BlockHeight, BlockHeightDelta, EpochId};
+
+/// Number of the recent approvals remembered, enough for all the block
+/// producers approving a few heights.
+const NUM_RECENT_APPROVALS: usize = 10_000;
+
+/// How far ahead of the head the evidence received from the peers can be.
+/// The approvals are not accepted for the heights further ahead either.
+const MAX_EVIDENCE_HEIGHT_AHEAD: BlockHeightDelta = 100;
+
+pub(crate) struct EquivocationDetector {
+    /// The first approval seen of every validator for the target height,
+    /// with the epoch of the key the approval is signed with.
+    approvals: LruCache<(AccountId, BlockHeight), (EpochId, Approval)>,
+}
+
+impl EquivocationDetector {
+    pub fn new() -> Self {
+        Self { approvals: LruCache::new(NUM_RECENT_APPROVALS) }
+    }
+
+    /// Records the approval, its signature already verified with the key of
+    /// the validator in `epoch_id`. Returns the evidence if the validator
+    /// approved a different block for the same target height before.
+    pub fn on_approval(
+        &mut self,
+        epoch_id: &EpochId,
+        approval: &Approval,
+    ) -> Option<EquivocationEvidence> {
+        let key = (approval.account_id.clone(), approval.target_height);
+        let Some((first_epoch_id, first)) = self.approvals.get(&key) else {
+            self.approvals.put(key, (epoch_id.clone(), approval.clone()));
+            return None;
+        };
+        // The evidence is verified with the keys of a single epoch, so only
+        // the approvals signed with the same ones are compared.
+        if first.inner == approval.inner || first_epoch_id != epoch_id {
+            return None;
+        }
+        Some(EquivocationEvidence::Approval {
+            epoch_id: epoch_id.clone(),
+            left: first.clone(),
+            right: approval.clone(),
+        })
+    }
+}
+
+/// Checks that the evidence received from a peer is conflicting, signed by
+/// the validator, and for a height around the head in the epoch of the keys
+/// it is signed with. The older evidence is rejected, the node can't tell
+/// the epoch of its height apart anymore.
+pub(crate) fn verify_evidence(
+    epoch_manager: &dyn EpochManagerAdapter,
+    head: &Tip,
+    evidence: &EquivocationEvidence,
+) -> Result<bool, near_chain::Error> {
+    if !evidence.is_conflicting() {
+        return Ok(false);
+    }
+    let height = evidence.height();
+    if height > head.height + MAX_EVIDENCE_HEIGHT_AHEAD {
+        return Ok(false);
+    }
+    let epoch_ids = epoch_manager.possible_epochs_of_height_around_tip(head, height)?;
+    if !epoch_ids.contains(evidence.epoch_id()) {
+        return Ok(false);
+    }
+    let last_known_block_hash = &head.last_block_hash;
+    match evidence {
+        EquivocationEvidence::Block { account_id, left, right } => {
+            if &epoch_manager.get_block_producer(left.epoch_id(), left.height())? != account_id {
+                return Ok(false);
+            }
+            for header in [left, right] {
+                if !epoch_manager.verify_validator_signature(
+                    header.epoch_id(),
+                    last_known_block_hash,
+                    account_id,
+                    header.hash().as_ref(),
+                    header.signature(),
+                )? {
+                    return Ok(false);
+                }
+            }
+        }
+        EquivocationEvidence::Approval { epoch_id, left, right } => {
+            for approval in [left, right] {
+                if !epoch_manager.verify_validator_signature(
+                    epoch_id,
+                    last_known_block_hash,
+                    &approval.account_id,
+                    &Approval::get_data_for_sig(&approval.inner, approval.target_height),
+                    &approval.signature,
+                )? {
+                    return Ok(false);
+                }
+            }
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestEnv;
+    use near_chain_configs::Genesis;
+    use near_primitives::hash::CryptoHash;
+    use near_primitives::test_utils::create_test_signer;
+
+    #[test]
+    fn test_conflicting_approvals() {
+        let signer = create_test_signer("test");
+        let endorsement = Approval::new(CryptoHash::hash_bytes(b"a"), 9, 10, &signer);
+        let other_endorsement = Approval::new(CryptoHash::hash_bytes(b"b"), 9, 10, &signer);
+        let skip = Approval::new(CryptoHash::hash_bytes(b"a"), 8, 10, &signer);
+        let epoch_id = EpochId::default();
+
+        let mut detector = EquivocationDetector::new();
+        assert_eq!(detector.on_approval(&epoch_id, &endorsement), None);
+        // Receiving the same approval again is fine.
+        assert_eq!(detector.on_approval(&epoch_id, &endorsement), None);
+        assert_eq!(
+            detector.on_approval(&epoch_id, &other_endorsement),
+            Some(EquivocationEvidence::Approval {
+                epoch_id: epoch_id.clone(),
+                left: endorsement.clone(),
+                right: other_endorsement
+            })
+        );
+        assert_eq!(
+            detector.on_approval(&epoch_id, &skip),
+            Some(EquivocationEvidence::Approval { epoch_id, left: endorsement, right: skip })
+        );
+    }
+
+    /// The evidence is only accepted for the heights around the head, with
+    /// the epoch of the height.
+    #[test]
+    fn test_verify_evidence() {
+        let mut genesis = Genesis::test(vec!["test0".parse().unwrap()], 1);
+        genesis.config.epoch_length = 10;
+        let mut env = TestEnv::builder(&genesis.config).build();
+        for height in 1..=5 {
+            env.produce_block(0, height);
+        }
+        let head = env.clients[0].chain.head().unwrap();
+        let epoch_manager = env.clients[0].epoch_manager.as_ref();
+
+        let signer = create_test_signer("test0");
+        let evidence = |epoch_id: &EpochId, target_height| EquivocationEvidence::Approval {
+            epoch_id: epoch_id.clone(),
+            left: Approval::new(CryptoHash::hash_bytes(b"a"), 5, target_height, &signer),
+            right: Approval::new(CryptoHash::hash_bytes(b"b"), 5, target_height, &signer),
+        };
+        assert!(verify_evidence(epoch_manager, &head, &evidence(&head.epoch_id, 6)).unwrap());
+        let other_epoch_id = EpochId(CryptoHash::hash_bytes(b"other"));
+        assert!(!verify_evidence(epoch_manager, &head, &evidence(&other_epoch_id, 6)).unwrap());
+        let far_ahead = head.height + MAX_EVIDENCE_HEIGHT_AHEAD + 1;
+        assert!(
+            !verify_evidence(epoch_manager, &head, &evidence(&head.epoch_id, far_ahead)).unwrap()
+        );
+
+        let stranger = create_test_signer("test1");
+        let forged = EquivocationEvidence::Approval {
+            epoch_id: head.epoch_id.clone(),
+            left: Approval::new(CryptoHash::hash_bytes(b"a"), 5, 6, &stranger),
+            right: Approval::new(CryptoHash::hash_bytes(b"b"), 5, 6, &stranger),
+        };
+        assert!(!verify_evidence(epoch_manager, &head, &forged).is_ok_and(|valid| valid));
+    }
+}
@@ -116,6 +116,12 @@ impl InfoHelper {
         metrics::CHUNK_SKIPPED_TOTAL.with_label_values(&[&shard_id.to_string()]).inc();
     }
 
+    pub fn shard_congestion_level(&mut self, shard_id: ShardId, congestion_level: f64) {
+        metrics::BLOCK_CONGESTION_LEVEL
+            .with_label_values(&[&shard_id.to_string()])
+            .set(congestion_level);
+    }
+
     pub fn block_processed(
         &mut self,
         gas_used: Gas,
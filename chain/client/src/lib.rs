@@ -1,11 +1,12 @@
 pub use near_client_primitives::types::{
-    Error, GetBlock, GetBlockProof, GetBlockProofResponse, GetBlockWithMerkleTree, GetChunk,
-    GetClientConfig, GetExecutionOutcome, GetExecutionOutcomeResponse,
-    GetExecutionOutcomesForBlock, GetGasPrice, GetMaintenanceWindows, GetNetworkInfo,
-    GetNextLightClientBlock, GetProtocolConfig, GetReceipt, GetSplitStorageInfo, GetStateChanges,
-    GetStateChangesInBlock, GetStateChangesWithCauseInBlock,
-    GetStateChangesWithCauseInBlockForTrackedShards, GetValidatorInfo, GetValidatorOrdered, Query,
-    QueryError, Status, StatusResponse, SyncStatus, TxStatus, TxStatusError,
+    ControlColdStoreCopy, Error, GetBlock, GetBlockProof, GetBlockProofResponse,
+    GetBlockWithMerkleTree, GetChunk, GetClientConfig, GetCongestionInfo, GetDelegateActions,
+    GetExecutionOutcome, GetExecutionOutcomeResponse, GetExecutionOutcomesForBlock, GetGasPrice,
+    GetMaintenanceWindows, GetNetworkInfo, GetNextEpochValidatorSchedule, GetNextLightClientBlock,
+    GetProtocolConfig, GetReceipt, GetSplitStorageInfo, GetStateChanges, GetStateChangesInBlock,
+    GetStateChangesWithCauseInBlock, GetStateChangesWithCauseInBlockForTrackedShards,
+    GetValidatorInfo, GetValidatorOrdered, Query, QueryError, Status, StatusResponse, SyncStatus,
+    TxStatus, TxStatusError,
 };
 
 pub use crate::client::{Client, ProduceChunkResult};
@@ -13,9 +14,11 @@ pub use crate::client::{Client, ProduceChunkResult};
 pub use crate::client_actor::NetworkAdversarialMessage;
 pub use crate::client_actor::{start_client, ClientActor, StartClientResult};
 pub use crate::config_updater::ConfigUpdater;
+pub use crate::shadow_validator::{ShadowValidator, ShadowValidatorSender};
 pub use crate::stateless_validation::chunk_validator::orphan_witness_handling::HandleOrphanWitnessOutcome;
 pub use crate::sync::adapter::{SyncAdapter, SyncMessage};
 pub use crate::view_client_actor::{ViewClientActor, ViewClientActorInner};
+pub use near_chain::chunk_apply_results::{ChunkApplyResult, ChunkApplyResults};
 pub use near_client_primitives::debug::DebugStatus;
 pub use near_network::client::{
     BlockApproval, BlockResponse, ProcessTxRequest, ProcessTxResponse, SetNetworkInfo,
@@ -23,6 +26,7 @@ pub use near_network::client::{
 pub use stateless_validation::partial_witness::partial_witness_actor::{
     DistributeStateWitnessRequest, PartialWitnessActor, PartialWitnessSenderForClientMessage,
 };
+pub use stateless_validation::partial_witness::partial_witness_history::PartialWitnessHistory;
 pub use stateless_validation::processing_tracker::{ProcessingDoneTracker, ProcessingDoneWaiter};
 
 pub mod adapter;
@@ -33,9 +37,12 @@ mod client;
 pub mod client_actor;
 mod config_updater;
 pub mod debug;
+mod equivocation;
 pub mod gc_actor;
 mod info;
 mod metrics;
+mod production_budget;
+mod shadow_validator;
 mod stateless_validation;
 pub mod sync;
 pub mod sync_jobs_actor;
@@ -1,8 +1,8 @@
 use near_o11y::metrics::{
     exponential_buckets, linear_buckets, try_create_counter, try_create_gauge,
-    try_create_histogram, try_create_histogram_vec, try_create_int_counter,
+    try_create_gauge_vec, try_create_histogram, try_create_histogram_vec, try_create_int_counter,
     try_create_int_counter_vec, try_create_int_gauge, try_create_int_gauge_vec, Counter, Gauge,
-    Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
+    GaugeVec, Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
 };
 use near_primitives::stateless_validation::ChunkStateWitness;
 use once_cell::sync::Lazy;
@@ -23,6 +23,14 @@ pub(crate) static CHUNK_PRODUCED_TOTAL: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+pub(crate) static CHUNK_SKIPPED_EMPTY_TOTAL: Lazy<IntCounter> = Lazy::new(|| {
+    try_create_int_counter(
+        "near_chunk_skipped_empty_total",
+        "Total number of empty chunks of idle shards not produced since starting this node",
+    )
+    .unwrap()
+});
+
 pub(crate) static PRODUCED_CHUNKS_SOME_POOL_TRANSACTIONS_DIDNT_FIT: Lazy<IntCounterVec> = Lazy::new(
     || {
         try_create_int_counter_vec(
@@ -294,6 +302,15 @@ pub(crate) static GAS_PRICE: Lazy<Gauge> = Lazy::new(|| {
     try_create_gauge("near_gas_price", "Gas price of the latest processed block").unwrap()
 });
 
+pub(crate) static BLOCK_CONGESTION_LEVEL: Lazy<GaugeVec> = Lazy::new(|| {
+    try_create_gauge_vec(
+        "near_block_congestion_level",
+        "Congestion level of every shard between 0.0 and 1.0, as recorded in the chunk headers of the latest processed block",
+        &["shard_id"],
+    )
+    .unwrap()
+});
+
 pub(crate) static BALANCE_BURNT: Lazy<Counter> = Lazy::new(|| {
     try_create_counter("near_balance_burnt", "Balance burnt by processed blocks in NEAR tokens")
         .unwrap()
@@ -379,6 +396,33 @@ pub(crate) static PRODUCE_CHUNK_TIME: Lazy<HistogramVec> = Lazy::new(|| {
     .unwrap()
 });
 
+pub(crate) static BLOCK_PRODUCTION_PHASE_TIME: Lazy<HistogramVec> = Lazy::new(|| {
+    try_create_histogram_vec(
+        "near_block_production_phase_time",
+        "Time taken by the phases of producing a block and its chunks",
+        &["phase"],
+        Some(exponential_buckets(0.001, 2.0, 16).unwrap()),
+    )
+    .unwrap()
+});
+
+pub(crate) static BLOCK_PRODUCTION_PHASE_OVER_BUDGET: Lazy<IntCounterVec> = Lazy::new(|| {
+    try_create_int_counter_vec(
+        "near_block_production_phase_over_budget_total",
+        "Number of times a phase of producing a block or a chunk took longer than its share of the block time",
+        &["phase"],
+    )
+    .unwrap()
+});
+
+pub(crate) static BLOCK_PRODUCTION_BUDGET_OVERRUN: Lazy<Counter> = Lazy::new(|| {
+    try_create_counter(
+        "near_block_production_budget_overrun_seconds_total",
+        "Total time by which the phases of producing blocks and chunks went over their share of the block time",
+    )
+    .unwrap()
+});
+
 pub(crate) static VIEW_CLIENT_MESSAGE_TIME: Lazy<HistogramVec> = Lazy::new(|| {
     try_create_histogram_vec(
         "near_view_client_messages_processing_time",
@@ -533,6 +577,24 @@ pub(crate) static STATE_SYNC_DUMP_PUT_OBJECT_ELAPSED: Lazy<HistogramVec> = Lazy:
     .unwrap()
 });
 
+pub(crate) static STATE_SYNC_DUMP_UPLOADED_BYTES: Lazy<IntCounterVec> = Lazy::new(|| {
+    try_create_int_counter_vec(
+        "near_state_sync_dump_uploaded_bytes_total",
+        "Bytes uploaded to external storage",
+        &["shard_id", "type"],
+    )
+    .unwrap()
+});
+
+pub(crate) static STATE_SYNC_DUMP_PUT_OBJECT_RETRIES: Lazy<IntCounterVec> = Lazy::new(|| {
+    try_create_int_counter_vec(
+        "near_state_sync_dump_put_object_retries_total",
+        "Retried requests uploading to S3",
+        &["shard_id", "request"],
+    )
+    .unwrap()
+});
+
 pub(crate) static STATE_SYNC_DUMP_LIST_OBJECT_ELAPSED: Lazy<HistogramVec> = Lazy::new(|| {
     try_create_histogram_vec(
         "near_state_sync_dump_list_object_elapsed_sec",
@@ -579,6 +641,15 @@ pub(crate) static CHUNK_STATE_WITNESS_VALIDATION_TIME: Lazy<HistogramVec> = Lazy
     .unwrap()
 });
 
+pub(crate) static CHUNK_STATE_WITNESS_VALIDATION_CACHE: Lazy<IntCounterVec> = Lazy::new(|| {
+    try_create_int_counter_vec(
+        "near_chunk_state_witness_validation_cache_total",
+        "Lookups in the caches skipping the repeated work of the state witness validation, by cache and result (hit or miss)",
+        &["shard_id", "cache", "result"],
+    )
+    .unwrap()
+});
+
 pub(crate) static CHUNK_STATE_WITNESS_TOTAL_SIZE: Lazy<HistogramVec> = Lazy::new(|| {
     try_create_histogram_vec(
         "near_chunk_state_witness_total_size",
@@ -834,6 +905,42 @@ pub(crate) static PARTIAL_WITNESS_PARTS_RECEIVED_RATIO: Lazy<HistogramVec> = Laz
     .unwrap()
 });
 
+pub(crate) static PARTIAL_WITNESS_PARTS_SENT: Lazy<IntCounterVec> = Lazy::new(|| {
+    try_create_int_counter_vec(
+        "near_partial_witness_parts_sent_total",
+        "Number of the partial state witness parts sent to the other chunk validators, produced by this node or forwarded",
+        &["shard_id", "kind"],
+    )
+    .unwrap()
+});
+
+pub(crate) static PARTIAL_WITNESS_PARTS_RECEIVED: Lazy<IntCounterVec> = Lazy::new(|| {
+    try_create_int_counter_vec(
+        "near_partial_witness_parts_received_total",
+        "Number of the valid partial state witness parts received, from the chunk producer or forwarded by the chunk validators owning them",
+        &["shard_id", "kind"],
+    )
+    .unwrap()
+});
+
+pub(crate) static PARTIAL_WITNESS_PARTS_MISSING: Lazy<IntCounterVec> = Lazy::new(|| {
+    try_create_int_counter_vec(
+        "near_partial_witness_parts_missing_total",
+        "Number of the partial state witness parts never received, counted when the witness is evicted from the cache",
+        &["shard_id"],
+    )
+    .unwrap()
+});
+
+pub(crate) static PARTIAL_WITNESS_PARTS_LATE: Lazy<IntCounterVec> = Lazy::new(|| {
+    try_create_int_counter_vec(
+        "near_partial_witness_parts_late_total",
+        "Number of the partial state witness parts received after the witness was decoded, counted when the witness is evicted from the cache",
+        &["shard_id"],
+    )
+    .unwrap()
+});
+
 pub(crate) static PARTIAL_WITNESS_CACHE_SIZE: Lazy<Gauge> = Lazy::new(|| {
     try_create_gauge(
         "near_partial_witness_cache_size",
@@ -841,3 +948,31 @@ pub(crate) static PARTIAL_WITNESS_CACHE_SIZE: Lazy<Gauge> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub(crate) static SHADOW_VALIDATOR_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
+    try_create_int_counter_vec(
+        "near_shadow_validator_messages_total",
+        "Number of the messages produced and not sent in the shadow validator mode, by whether they would have been sent in time",
+        &["kind", "outcome"],
+    )
+    .unwrap()
+});
+
+pub(crate) static SHADOW_VALIDATOR_MESSAGE_DELAY: Lazy<HistogramVec> = Lazy::new(|| {
+    try_create_histogram_vec(
+        "near_shadow_validator_message_delay_seconds",
+        "Time from the previous block to producing a message in the shadow validator mode",
+        &["kind"],
+        Some(exponential_buckets(0.01, 1.5, 16).unwrap()),
+    )
+    .unwrap()
+});
+
+pub(crate) static EQUIVOCATION_EVIDENCE: Lazy<IntCounterVec> = Lazy::new(|| {
+    try_create_int_counter_vec(
+        "near_equivocation_evidence_total",
+        "Number of the validators found signing conflicting messages, by the kind of the messages (block or approval) and whether the evidence was observed by this node or received from a peer",
+        &["kind", "source"],
+    )
+    .unwrap()
+});
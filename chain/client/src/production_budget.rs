@@ -0,0 +1,70 @@
+//! Tracking of the time the phases of producing a block and its chunks take
+//! against the block time.
+//!
+//! Each phase is given a share of `min_block_production_delay`. Phases taking
+//! longer than their share are counted in
+//! `near_block_production_phase_over_budget_total`, by how much in
+//! `near_block_production_budget_overrun_seconds_total`, so that alerts can
+//! fire before slow production starts causing skipped blocks and chunks.
+use crate::metrics;
+use near_async::time::Duration;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub(crate) enum ProductionPhase {
+    /// Selecting the transactions of a chunk from the pool.
+    TxSelection,
+    /// Encoding a chunk and creating and signing its header.
+    ChunkHeaderCreation,
+    /// Creating and signing the block header.
+    Signing,
+    /// Distributing a chunk or the block to the peers.
+    Broadcast,
+}
+
+impl ProductionPhase {
+    /// Percentage of the block time the phase is expected to take at most.
+    /// The shares don't add up to the whole block time, to leave room for the
+    /// rest of the work and for the network latency.
+    fn budget_percent(self) -> i32 {
+        match self {
+            ProductionPhase::TxSelection => 30,
+            ProductionPhase::ChunkHeaderCreation => 20,
+            ProductionPhase::Signing => 5,
+            ProductionPhase::Broadcast => 20,
+        }
+    }
+
+    pub(crate) fn budget(self, block_time: Duration) -> Duration {
+        block_time * self.budget_percent() / 100
+    }
+
+    /// Records the time the phase took, and whether it went over its budget.
+    pub(crate) fn record(self, elapsed: Duration, block_time: Duration) {
+        let phase: &'static str = self.into();
+        let elapsed = elapsed.as_seconds_f64();
+        metrics::BLOCK_PRODUCTION_PHASE_TIME.with_label_values(&[phase]).observe(elapsed);
+        let budget = self.budget(block_time).as_seconds_f64();
+        if elapsed <= budget {
+            return;
+        }
+        metrics::BLOCK_PRODUCTION_PHASE_OVER_BUDGET.with_label_values(&[phase]).inc();
+        metrics::BLOCK_PRODUCTION_BUDGET_OVERRUN.inc_by(elapsed - budget);
+        tracing::debug!(target: "client", phase, ?elapsed, ?budget, "Block production phase over its budget");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProductionPhase;
+    use near_async::time::Duration;
+
+    #[test]
+    fn test_phase_budget() {
+        let block_time = Duration::seconds(1);
+        assert_eq!(ProductionPhase::TxSelection.budget(block_time), Duration::milliseconds(300));
+        assert_eq!(ProductionPhase::Signing.budget(block_time), Duration::milliseconds(50));
+        let phase: &str = ProductionPhase::ChunkHeaderCreation.into();
+        assert_eq!(phase, "chunk_header_creation");
+    }
+}
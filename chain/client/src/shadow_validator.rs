@@ -0,0 +1,263 @@
+//! Shadow validator mode, for rehearsing the duties of a validator without
+//! staking.
+//!
+//! With `shadow_validator_account_id` configured, the node acts as that
+//! validator, signing with a random key. It can't announce the account with
+//! that key, so the other validators keep sending their approvals, chunk parts,
+//! state witnesses and endorsements to the validator itself. The shadow thus
+//! only rehearses the duties which need nothing but the chain: it produces the
+//! approvals of the blocks it receives, and the chunks and state witnesses of
+//! the validator. It doesn't produce blocks, which need the approvals of the
+//! others, nor endorsements, which need their state witnesses.
+//!
+//! Instead of sending its messages, `ShadowValidatorSender` drops them and
+//! counts them in `near_shadow_validator_messages_total` by whether they would
+//! have been in time: within `min_block_production_delay` after the previous
+//! block, so that the block producer can still include them. The previous block
+//! is the one the message builds on, or the head if the message doesn't tell.
+//! Blocks and endorsements are dropped too, should the shadow ever produce
+//! them, with `max_block_production_delay` as the deadline of blocks.
+use crate::metrics;
+use near_async::messaging::{CanSend, MessageWithCallback};
+use near_async::time::{Clock, Duration, Utc};
+use near_chain_configs::ClientConfig;
+use near_crypto::PublicKey;
+use near_network::types::{
+    NetworkRequests, NetworkResponses, PeerManagerMessageRequest, PeerManagerMessageResponse,
+    SetChainInfo,
+};
+use near_primitives::block::Tip;
+use near_primitives::block_header::{ApprovalInner, BlockHeader};
+use near_primitives::hash::CryptoHash;
+use near_primitives::types::AccountId;
+use near_store::{DBCol, Store, HEAD_KEY};
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+enum ShadowMessageKind {
+    Block,
+    Approval,
+    ChunkParts,
+    StateWitnessParts,
+    ChunkEndorsement,
+}
+
+pub struct ShadowValidator {
+    clock: Clock,
+    store: Store,
+    account_id: AccountId,
+    /// Random key the node signs with, which tells its own blocks apart from
+    /// the rebroadcast blocks of others.
+    public_key: PublicKey,
+    min_block_production_delay: Duration,
+    max_block_production_delay: Duration,
+}
+
+impl ShadowValidator {
+    pub fn new(
+        clock: Clock,
+        store: Store,
+        account_id: AccountId,
+        public_key: PublicKey,
+        client_config: &ClientConfig,
+    ) -> Self {
+        tracing::info!(target: "client", %account_id, "Running as a shadow validator, the validator messages are not sent");
+        Self {
+            clock,
+            store,
+            account_id,
+            public_key,
+            min_block_production_delay: client_config.min_block_production_delay,
+            max_block_production_delay: client_config.max_block_production_delay,
+        }
+    }
+
+    /// Returns the kind of the message and the block it builds on, if the
+    /// message is produced by the shadowed validator.
+    fn own_message(
+        &self,
+        request: &NetworkRequests,
+    ) -> Option<(ShadowMessageKind, Option<CryptoHash>)> {
+        match request {
+            NetworkRequests::Block { block } => block
+                .header()
+                .verify_block_producer(&self.public_key)
+                .then(|| (ShadowMessageKind::Block, Some(*block.header().prev_hash()))),
+            NetworkRequests::Approval { approval_message } => {
+                let approval = &approval_message.approval;
+                if approval.account_id != self.account_id {
+                    return None;
+                }
+                let prev_hash = match approval.inner {
+                    ApprovalInner::Endorsement(parent_hash) => Some(parent_hash),
+                    ApprovalInner::Skip(_) => None,
+                };
+                Some((ShadowMessageKind::Approval, prev_hash))
+            }
+            // Only the chunk producer distributes the parts to their owners.
+            NetworkRequests::PartialEncodedChunkMessage { partial_encoded_chunk, .. } => Some((
+                ShadowMessageKind::ChunkParts,
+                Some(*partial_encoded_chunk.header.prev_block_hash()),
+            )),
+            // Only the chunk producer distributes the parts to the chunk
+            // validators, who then forward them to each other.
+            NetworkRequests::PartialEncodedStateWitness(_) => {
+                Some((ShadowMessageKind::StateWitnessParts, None))
+            }
+            NetworkRequests::ChunkEndorsement(_, endorsement) => (endorsement.account_id
+                == self.account_id)
+                .then_some((ShadowMessageKind::ChunkEndorsement, None)),
+            _ => None,
+        }
+    }
+
+    fn prev_block_timestamp(&self, prev_hash: Option<CryptoHash>) -> Option<Utc> {
+        let prev_hash = match prev_hash {
+            Some(prev_hash) => prev_hash,
+            None => self.store.get_ser::<Tip>(DBCol::BlockMisc, HEAD_KEY).ok()??.last_block_hash,
+        };
+        let header = self.store.get_ser::<BlockHeader>(DBCol::BlockHeader, prev_hash.as_ref());
+        Some(header.ok()??.timestamp())
+    }
+
+    fn record(&self, kind: ShadowMessageKind, prev_hash: Option<CryptoHash>) {
+        let kind_label: &'static str = kind.into();
+        let deadline = match kind {
+            ShadowMessageKind::Block => self.max_block_production_delay,
+            _ => self.min_block_production_delay,
+        };
+        let outcome = match self.prev_block_timestamp(prev_hash) {
+            Some(prev_timestamp) => {
+                let delay = self.clock.now_utc() - prev_timestamp;
+                metrics::SHADOW_VALIDATOR_MESSAGE_DELAY
+                    .with_label_values(&[kind_label])
+                    .observe(delay.as_seconds_f64());
+                if delay <= deadline {
+                    "in_time"
+                } else {
+                    "late"
+                }
+            }
+            None => "unknown",
+        };
+        metrics::SHADOW_VALIDATOR_MESSAGES.with_label_values(&[kind_label, outcome]).inc();
+        tracing::debug!(target: "client", kind = kind_label, outcome, "Dropped a message of the shadow validator");
+    }
+}
+
+/// Sends the requests to the network, except for the messages produced by the
+/// shadow validator, if there is one.
+#[derive(Clone)]
+pub struct ShadowValidatorSender<S> {
+    inner: S,
+    shadow: Option<Arc<ShadowValidator>>,
+}
+
+impl<S> ShadowValidatorSender<S> {
+    pub fn new(inner: S, shadow: Option<ShadowValidator>) -> Self {
+        Self { inner, shadow: shadow.map(Arc::new) }
+    }
+
+    /// Records and returns whether the request is a message of the shadow
+    /// validator, which is not sent.
+    fn drop_own_message(&self, request: &PeerManagerMessageRequest) -> bool {
+        let (Some(shadow), PeerManagerMessageRequest::NetworkRequests(request)) =
+            (&self.shadow, request)
+        else {
+            return false;
+        };
+        let Some((kind, prev_hash)) = shadow.own_message(request) else {
+            return false;
+        };
+        shadow.record(kind, prev_hash);
+        true
+    }
+}
+
+impl<S: CanSend<PeerManagerMessageRequest>> CanSend<PeerManagerMessageRequest>
+    for ShadowValidatorSender<S>
+{
+    fn send(&self, message: PeerManagerMessageRequest) {
+        if !self.drop_own_message(&message) {
+            self.inner.send(message);
+        }
+    }
+}
+
+impl<S> CanSend<MessageWithCallback<PeerManagerMessageRequest, PeerManagerMessageResponse>>
+    for ShadowValidatorSender<S>
+where
+    S: CanSend<MessageWithCallback<PeerManagerMessageRequest, PeerManagerMessageResponse>>,
+{
+    fn send(
+        &self,
+        message: MessageWithCallback<PeerManagerMessageRequest, PeerManagerMessageResponse>,
+    ) {
+        if self.drop_own_message(&message.message) {
+            (message.callback)(Ok(NetworkResponses::NoResponse.into()));
+        } else {
+            self.inner.send(message);
+        }
+    }
+}
+
+impl<S: CanSend<SetChainInfo>> CanSend<SetChainInfo> for ShadowValidatorSender<S> {
+    fn send(&self, message: SetChainInfo) {
+        self.inner.send(message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_primitives::sharding::ChunkHash;
+    use near_primitives::stateless_validation::ChunkEndorsement;
+    use near_primitives::test_utils::create_test_signer;
+    use near_primitives::validator_signer::{InMemoryValidatorSigner, ValidatorSigner};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingSender(Mutex<Vec<PeerManagerMessageRequest>>);
+
+    impl CanSend<PeerManagerMessageRequest> for Arc<RecordingSender> {
+        fn send(&self, message: PeerManagerMessageRequest) {
+            self.0.lock().unwrap().push(message);
+        }
+    }
+
+    #[test]
+    fn test_drop_own_messages() {
+        let signer = create_test_signer("shadow");
+        let other_signer = create_test_signer("other");
+        let client_config = ClientConfig::test(true, 100, 200, 1, false, true, true, false);
+        let shadow = ShadowValidator::new(
+            Clock::real(),
+            near_store::test_utils::create_test_store(),
+            signer.validator_id().clone(),
+            signer.public_key(),
+            &client_config,
+        );
+        let recording = Arc::new(RecordingSender::default());
+        let sender = ShadowValidatorSender::new(recording.clone(), Some(shadow));
+        let endorsement = |signer: &InMemoryValidatorSigner| {
+            PeerManagerMessageRequest::NetworkRequests(NetworkRequests::ChunkEndorsement(
+                "producer".parse().unwrap(),
+                ChunkEndorsement::new(ChunkHash::default(), signer),
+            ))
+        };
+        sender.send(endorsement(&signer));
+        sender.send(endorsement(&other_signer));
+
+        let sent = recording.0.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        let PeerManagerMessageRequest::NetworkRequests(NetworkRequests::ChunkEndorsement(
+            _,
+            endorsement,
+        )) = &sent[0]
+        else {
+            panic!("unexpected message {:?}", sent[0]);
+        };
+        assert_eq!(&endorsement.account_id, other_signer.validator_id());
+    }
+}
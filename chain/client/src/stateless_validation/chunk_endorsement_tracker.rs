@@ -9,7 +9,7 @@ use near_primitives::block_body::ChunkEndorsementSignatures;
 use near_primitives::checked_feature;
 use near_primitives::sharding::{ChunkHash, ShardChunkHeader};
 use near_primitives::stateless_validation::{ChunkEndorsement, EndorsementStats};
-use near_primitives::types::AccountId;
+use near_primitives::types::{AccountId, BlockHeight, ShardId};
 
 use crate::Client;
 
@@ -17,6 +17,9 @@ use crate::Client;
 // Ideally, we should not be processing more than num_shards chunks at a time.
 const NUM_CHUNKS_IN_CHUNK_ENDORSEMENTS_CACHE: usize = 100;
 
+// This is the number of chunks for which we remember the endorsements sent by this node.
+const NUM_SENT_CHUNK_ENDORSEMENTS_TO_TRACK: usize = 1000;
+
 pub enum ChunkEndorsementsState {
     Endorsed(Option<EndorsementStats>, ChunkEndorsementSignatures),
     NotEnoughStake(Option<EndorsementStats>),
@@ -31,6 +34,16 @@ impl ChunkEndorsementsState {
     }
 }
 
+/// Chunk endorsement sent by this node, kept to check later whether it was
+/// included in a block.
+#[derive(Clone, Debug)]
+pub struct SentChunkEndorsement {
+    pub shard_id: ShardId,
+    pub height_created: BlockHeight,
+    /// Block producers the endorsement was sent to.
+    pub block_producers: Vec<AccountId>,
+}
+
 /// Module to track chunk endorsements received from chunk validators.
 pub struct ChunkEndorsementTracker {
     epoch_manager: Arc<dyn EpochManagerAdapter>,
@@ -42,6 +55,8 @@ pub struct ChunkEndorsementTracker {
     /// chunks ready at the time we received that endorsements from validators.
     /// This is keyed on chunk_hash and account_id of validator to avoid duplicates.
     pending_chunk_endorsements: SyncLruCache<ChunkHash, HashMap<AccountId, ChunkEndorsement>>,
+    /// Chunk endorsements sent by this node as a chunk validator, for debugging.
+    sent_chunk_endorsements: SyncLruCache<ChunkHash, SentChunkEndorsement>,
 }
 
 impl Client {
@@ -73,9 +88,32 @@ impl ChunkEndorsementTracker {
             chunk_endorsements: SyncLruCache::new(NUM_CHUNKS_IN_CHUNK_ENDORSEMENTS_CACHE),
             // We can use a different cache size if needed, it does not have to be the same as for `chunk_endorsements`.
             pending_chunk_endorsements: SyncLruCache::new(NUM_CHUNKS_IN_CHUNK_ENDORSEMENTS_CACHE),
+            sent_chunk_endorsements: SyncLruCache::new(NUM_SENT_CHUNK_ENDORSEMENTS_TO_TRACK),
         }
     }
 
+    /// Remembers that this node sent an endorsement of the chunk to the block producers.
+    pub(crate) fn record_sent_chunk_endorsement(
+        &self,
+        chunk_header: &ShardChunkHeader,
+        block_producers: Vec<AccountId>,
+    ) {
+        self.sent_chunk_endorsements.put(
+            chunk_header.chunk_hash(),
+            SentChunkEndorsement {
+                shard_id: chunk_header.shard_id(),
+                height_created: chunk_header.height_created(),
+                block_producers,
+            },
+        );
+    }
+
+    /// Returns the recently sent chunk endorsements, in no particular order.
+    pub fn sent_chunk_endorsements(&self) -> Vec<(ChunkHash, SentChunkEndorsement)> {
+        let guard = self.sent_chunk_endorsements.lock();
+        guard.iter().map(|(chunk_hash, sent)| (chunk_hash.clone(), sent.clone())).collect()
+    }
+
     /// Process pending endorsements for the given chunk header.
     /// It removes these endorsements from the `pending_chunk_endorsements` cache.
     pub fn process_pending_endorsements(&self, chunk_header: &ShardChunkHeader) {
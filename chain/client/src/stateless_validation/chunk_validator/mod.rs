@@ -38,9 +38,13 @@ use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::chunk_extra::ChunkExtra;
 use near_primitives::types::ShardId;
 use near_primitives::validator_signer::ValidatorSigner;
+use near_primitives::version::ProtocolFeature;
 use near_store::{PartialStorage, ShardUId};
 use near_vm_runner::logic::ProtocolVersion;
 use orphan_witness_pool::OrphanStateWitnessPool;
+use rayon::iter::{
+    IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator,
+};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
@@ -64,6 +68,28 @@ pub struct ChunkStateWitnessValidationResult {
 pub type MainStateTransitionCache =
     Arc<Mutex<HashMap<ShardUId, LruCache<CryptoHash, ChunkStateWitnessValidationResult>>>>;
 
+/// The number of chunks validated successfully to remember.
+const NUM_VALIDATED_CHUNKS_CACHE_ENTRIES: usize = 100;
+
+/// Chunks validated successfully, keyed by the chunk hash and the prev state
+/// root, so that the same chunk is endorsed again without validating its state
+/// witness again, e.g. when the witness is received again for a fork.
+///
+/// The chunk hash commits to the parent block, so the validation result, which
+/// depends on it, is only reused for the same parent. The failures are not
+/// cached, as they can be caused by an invalid witness for a valid chunk.
+pub type ValidatedChunksCache = Arc<Mutex<LruCache<(ChunkHash, CryptoHash), ()>>>;
+
+fn record_validation_cache_lookup(shard_id: ShardId, cache: &str, hit: bool) {
+    metrics::CHUNK_STATE_WITNESS_VALIDATION_CACHE
+        .with_label_values(&[
+            shard_id.to_string().as_str(),
+            cache,
+            if hit { "hit" } else { "miss" },
+        ])
+        .inc();
+}
+
 /// A module that handles chunk validation logic. Chunk validation refers to a
 /// critical process of stateless validation, where chunk validators (certain
 /// validators selected to validate the chunk) verify that the chunk's state
@@ -79,6 +105,7 @@ pub struct ChunkValidator {
     orphan_witness_pool: OrphanStateWitnessPool,
     validation_spawner: Arc<dyn AsyncComputationSpawner>,
     main_state_transition_result_cache: MainStateTransitionCache,
+    validated_chunks_cache: ValidatedChunksCache,
 }
 
 impl ChunkValidator {
@@ -100,6 +127,9 @@ impl ChunkValidator {
             orphan_witness_pool: OrphanStateWitnessPool::new(orphan_witness_pool_size),
             validation_spawner,
             main_state_transition_result_cache: MainStateTransitionCache::default(),
+            validated_chunks_cache: Arc::new(Mutex::new(LruCache::new(
+                NUM_VALIDATED_CHUNKS_CACHE_ENTRIES,
+            ))),
         }
     }
 
@@ -123,6 +153,40 @@ impl ChunkValidator {
             )));
         }
 
+        let chunk_header = state_witness.chunk_header.clone();
+        let network_sender = self.network_sender.clone();
+        let signer = self.my_signer.as_ref().ok_or(Error::NotAValidator)?.clone();
+        let chunk_endorsement_tracker = self.chunk_endorsement_tracker.clone();
+        let epoch_manager = self.epoch_manager.clone();
+        let validated_chunk_key = (chunk_header.chunk_hash(), chunk_header.prev_state_root());
+        let is_validated =
+            self.validated_chunks_cache.lock().unwrap().get(&validated_chunk_key).is_some();
+        record_validation_cache_lookup(chunk_header.shard_id(), "validated_chunks", is_validated);
+        if is_validated {
+            // The chunk hash doesn't cover the signature of the chunk header,
+            // so the header of the validated chunk may have been re-signed by
+            // anyone else to have it endorsed again.
+            if !epoch_manager.verify_chunk_header_signature(
+                &chunk_header,
+                &epoch_id,
+                prev_block_hash,
+            )? {
+                return Err(Error::InvalidChunkStateWitness(format!(
+                    "Invalid signature of the chunk header {:?}",
+                    validated_chunk_key.0
+                )));
+            }
+            tracing::debug!(target: "client", chunk_hash = ?validated_chunk_key.0, "Chunk already validated, sending the endorsement again");
+            send_chunk_endorsement_to_block_producers(
+                &chunk_header,
+                epoch_manager.as_ref(),
+                signer.as_ref(),
+                &network_sender,
+                chunk_endorsement_tracker.as_ref(),
+            );
+            return Ok(());
+        }
+
         let pre_validation_result = pre_validate_chunk_state_witness(
             &state_witness,
             chain,
@@ -130,11 +194,6 @@ impl ChunkValidator {
             self.runtime_adapter.as_ref(),
         )?;
 
-        let chunk_header = state_witness.chunk_header.clone();
-        let network_sender = self.network_sender.clone();
-        let signer = self.my_signer.as_ref().ok_or(Error::NotAValidator)?.clone();
-        let chunk_endorsement_tracker = self.chunk_endorsement_tracker.clone();
-        let epoch_manager = self.epoch_manager.clone();
         // If we have the chunk extra for the previous block, we can validate the chunk without state witness.
         // This usually happens because we are a chunk producer and
         // therefore have the chunk extra for the previous block saved on disk.
@@ -178,6 +237,7 @@ impl ChunkValidator {
 
         let runtime_adapter = self.runtime_adapter.clone();
         let cache = self.main_state_transition_result_cache.clone();
+        let validated_chunks_cache = self.validated_chunks_cache.clone();
         self.validation_spawner.spawn("stateless_validation", move || {
             // processing_done_tracker must survive until the processing is finished.
             let _processing_done_tracker_capture: Option<ProcessingDoneTracker> =
@@ -191,6 +251,7 @@ impl ChunkValidator {
                 &cache,
             ) {
                 Ok(()) => {
+                    validated_chunks_cache.lock().unwrap().put(validated_chunk_key, ());
                     send_chunk_endorsement_to_block_producers(
                         &chunk_header,
                         epoch_manager.as_ref(),
@@ -422,25 +483,37 @@ fn validate_source_receipt_proofs(
     // from the newest blocks to the oldest.
     for block in receipt_source_blocks {
         // Collect all receipts coming from this block.
+        let chunks = block.chunks();
         let mut block_receipt_proofs = Vec::new();
 
-        for chunk in block.chunks().iter() {
+        for chunk in chunks.iter() {
             if !chunk.is_new_chunk(block.header().height()) {
                 continue;
             }
 
-            // Collect receipts coming from this chunk and validate that they are correct.
+            // Collect receipts coming from this chunk, they are validated below.
             let Some(receipt_proof) = source_receipt_proofs.get(&chunk.chunk_hash()) else {
                 return Err(Error::InvalidChunkStateWitness(format!(
                     "Missing source receipt proof for chunk {:?}",
                     chunk.chunk_hash()
                 )));
             };
-            validate_receipt_proof(receipt_proof, chunk, target_chunk_shard_id)?;
 
             expected_proofs_len += 1;
-            block_receipt_proofs.push(receipt_proof);
+            block_receipt_proofs.push((receipt_proof, chunk));
+        }
+
+        // The proofs are independent of each other, so they are validated in
+        // parallel. The first invalid one in block order is reported.
+        if let Some(err) =
+            block_receipt_proofs.par_iter().find_map_first(|(receipt_proof, chunk)| {
+                validate_receipt_proof(receipt_proof, chunk, target_chunk_shard_id).err()
+            })
+        {
+            return Err(err);
         }
+        let mut block_receipt_proofs: Vec<&ReceiptProof> =
+            block_receipt_proofs.into_iter().map(|(receipt_proof, _)| receipt_proof).collect();
 
         // Arrange the receipts in the order in which they should be applied.
         shuffle_receipt_proofs(&mut block_receipt_proofs, block.hash());
@@ -523,7 +596,7 @@ pub(crate) struct PreValidationOutput {
 }
 
 pub(crate) fn validate_chunk_state_witness(
-    state_witness: ChunkStateWitness,
+    mut state_witness: ChunkStateWitness,
     pre_validation_output: PreValidationOutput,
     epoch_manager: &dyn EpochManagerAdapter,
     runtime_adapter: &dyn RuntimeAdapter,
@@ -533,6 +606,9 @@ pub(crate) fn validate_chunk_state_witness(
         .with_label_values(&[&state_witness.chunk_header.shard_id().to_string()])
         .start_timer();
     let span = tracing::debug_span!(target: "client", "validate_chunk_state_witness").entered();
+    // The transitions are applied on the rayon thread pool, make sure they are
+    // all tracked as children of this span.
+    let parent_span: &tracing::Span = &span;
     let block_hash = pre_validation_output.main_transition_params.block_hash();
     let epoch_id = epoch_manager.get_epoch_id(&block_hash)?;
     let shard_uid = epoch_manager
@@ -542,14 +618,22 @@ pub(crate) fn validate_chunk_state_witness(
         let mut shard_cache = main_state_transition_cache.lock().unwrap();
         shard_cache.get_mut(&shard_uid).and_then(|cache| cache.get(&block_hash).cloned())
     };
-    let (mut chunk_extra, outgoing_receipts) =
+    if let MainTransition::NewChunk(_) = &pre_validation_output.main_transition_params {
+        record_validation_cache_lookup(
+            shard_uid.shard_id(),
+            "main_state_transition",
+            cache_result.is_some(),
+        );
+    }
+    let main_post_state_root = state_witness.main_state_transition.post_state_root;
+    let apply_main_transition = || -> Result<(ChunkExtra, Vec<Receipt>), Error> {
         match (pre_validation_output.main_transition_params, cache_result) {
-            (MainTransition::Genesis { chunk_extra, .. }, _) => (chunk_extra, vec![]),
+            (MainTransition::Genesis { chunk_extra, .. }, _) => Ok((chunk_extra, vec![])),
             (MainTransition::NewChunk(new_chunk_data), None) => {
                 let chunk_header = new_chunk_data.chunk_header.clone();
                 let NewChunkResult { apply_result: mut main_apply_result, .. } = apply_new_chunk(
                     ApplyChunkReason::ValidateChunkStateWitness,
-                    &span,
+                    parent_span,
                     new_chunk_data,
                     ShardContext {
                         shard_uid,
@@ -565,18 +649,100 @@ pub(crate) fn validate_chunk_state_witness(
                 let chunk_extra =
                     apply_result_to_chunk_extra(protocol_version, main_apply_result, &chunk_header);
 
-                (chunk_extra, outgoing_receipts)
+                Ok((chunk_extra, outgoing_receipts))
             }
-            (_, Some(result)) => (result.chunk_extra, result.outgoing_receipts),
-        };
-    if chunk_extra.state_root() != &state_witness.main_state_transition.post_state_root {
-        // This is an early check, it's not for correctness, only for better
-        // error reporting in case of an invalid state witness due to a bug.
-        // Only the final state root check against the chunk header is required.
+            (_, Some(result)) => Ok((result.chunk_extra, result.outgoing_receipts)),
+        }
+    };
+
+    // Applying an implicit transition only uses the state root, the validator
+    // proposals and the gas limit of the previous chunk extra. Instead of
+    // waiting for the previous transition, every implicit transition is applied
+    // on top of the post state root the witness claims for the previous one and
+    // the values from the chunk header, which the final chunk extra has to
+    // match. Every claimed post state root is checked below and the chunk
+    // header is checked at the end, so if the witness is valid, every
+    // transition was applied on top of the actual result of the previous one,
+    // and the result is the same as when applying them one after another.
+    let chunk_header = &state_witness.chunk_header;
+    let implicit_prev_chunk_extra = |state_root: &CryptoHash| {
+        ChunkExtra::new(
+            protocol_version,
+            state_root,
+            chunk_header.prev_outcome_root(),
+            chunk_header.prev_validator_proposals().collect(),
+            chunk_header.prev_gas_used(),
+            chunk_header.gas_limit(),
+            chunk_header.prev_balance_burnt(),
+            // Not used when applying implicit transitions.
+            ProtocolFeature::CongestionControl
+                .enabled(protocol_version)
+                .then(|| chunk_header.congestion_info().unwrap_or_default()),
+        )
+    };
+    let implicit_transitions = std::mem::take(&mut state_witness.implicit_transitions);
+    let implicit_prev_state_roots: Vec<CryptoHash> = std::iter::once(main_post_state_root)
+        .chain(implicit_transitions.iter().map(|transition| transition.post_state_root))
+        .collect();
+    let apply_implicit_transitions = || -> Vec<Result<CryptoHash, Error>> {
+        pre_validation_output
+            .implicit_transition_params
+            .into_par_iter()
+            .zip(implicit_transitions)
+            .zip(implicit_prev_state_roots)
+            .map(|((block, transition), prev_state_root)| {
+                let block_hash = block.block_hash;
+                let old_chunk_data = OldChunkData {
+                    prev_chunk_extra: implicit_prev_chunk_extra(&prev_state_root),
+                    resharding_state_roots: None,
+                    block,
+                    storage_context: StorageContext {
+                        storage_data_source: StorageDataSource::Recorded(PartialStorage {
+                            nodes: transition.base_state,
+                        }),
+                        state_patch: Default::default(),
+                    },
+                };
+                let OldChunkResult { apply_result, .. } = apply_old_chunk(
+                    ApplyChunkReason::ValidateChunkStateWitness,
+                    parent_span,
+                    old_chunk_data,
+                    ShardContext {
+                        // Consider other shard uid in case of resharding.
+                        shard_uid,
+                        cares_about_shard_this_epoch: true,
+                        will_shard_layout_change: false,
+                        should_apply_chunk: false,
+                        need_to_reshard: false,
+                    },
+                    runtime_adapter,
+                    epoch_manager,
+                )?;
+                if apply_result.new_root != transition.post_state_root {
+                    // Required for correctness: the next implicit transition is
+                    // applied on top of the post state root claimed here, so this
+                    // check ties it to the actual result of this transition.
+                    return Err(Error::InvalidChunkStateWitness(format!(
+                        "Post state root {:?} for implicit transition at block {:?}, does not match expected state root {:?}",
+                        apply_result.new_root, block_hash, transition.post_state_root
+                    )));
+                }
+                Ok(apply_result.new_root)
+            })
+            .collect()
+    };
+
+    let (main_result, implicit_results) =
+        rayon::join(apply_main_transition, apply_implicit_transitions);
+    let (mut chunk_extra, outgoing_receipts) = main_result?;
+    if chunk_extra.state_root() != &main_post_state_root {
+        // Required for correctness: the first implicit transition is applied on
+        // top of the claimed main post state root, so this check ties it to the
+        // actual result of the main transition.
         return Err(Error::InvalidChunkStateWitness(format!(
             "Post state root {:?} for main transition does not match expected post state root {:?}",
             chunk_extra.state_root(),
-            state_witness.main_state_transition.post_state_root,
+            main_post_state_root,
         )));
     }
 
@@ -601,48 +767,10 @@ pub(crate) fn validate_chunk_state_witness(
         );
     }
 
-    for (block, transition) in pre_validation_output
-        .implicit_transition_params
-        .into_iter()
-        .zip(state_witness.implicit_transitions.into_iter())
-    {
-        let block_hash = block.block_hash;
-        let old_chunk_data = OldChunkData {
-            prev_chunk_extra: chunk_extra.clone(),
-            resharding_state_roots: None,
-            block,
-            storage_context: StorageContext {
-                storage_data_source: StorageDataSource::Recorded(PartialStorage {
-                    nodes: transition.base_state,
-                }),
-                state_patch: Default::default(),
-            },
-        };
-        let OldChunkResult { apply_result, .. } = apply_old_chunk(
-            ApplyChunkReason::ValidateChunkStateWitness,
-            &span,
-            old_chunk_data,
-            ShardContext {
-                // Consider other shard uid in case of resharding.
-                shard_uid,
-                cares_about_shard_this_epoch: true,
-                will_shard_layout_change: false,
-                should_apply_chunk: false,
-                need_to_reshard: false,
-            },
-            runtime_adapter,
-            epoch_manager,
-        )?;
-        *chunk_extra.state_root_mut() = apply_result.new_root;
-        if chunk_extra.state_root() != &transition.post_state_root {
-            // This is an early check, it's not for correctness, only for better
-            // error reporting in case of an invalid state witness due to a bug.
-            // Only the final state root check against the chunk header is required.
-            return Err(Error::InvalidChunkStateWitness(format!(
-                "Post state root {:?} for implicit transition at block {:?}, does not match expected state root {:?}",
-                chunk_extra.state_root(), block_hash, transition.post_state_root
-            )));
-        }
+    // Report the first failed transition, same as when applying them one
+    // after another.
+    for result in implicit_results {
+        *chunk_extra.state_root_mut() = result?;
     }
 
     // Finally, verify that the newly proposed chunk matches everything we have computed.
@@ -701,6 +829,7 @@ pub(crate) fn send_chunk_endorsement_to_block_producers(
     );
 
     let endorsement = ChunkEndorsement::new(chunk_header.chunk_hash(), signer);
+    chunk_endorsement_tracker.record_sent_chunk_endorsement(chunk_header, block_producers.clone());
     for block_producer in block_producers {
         if signer.validator_id() == &block_producer {
             // Unwrap here as we always expect our own endorsements to be valid
@@ -1,2 +1,3 @@
 pub mod partial_witness_actor;
+pub mod partial_witness_history;
 mod partial_witness_tracker;
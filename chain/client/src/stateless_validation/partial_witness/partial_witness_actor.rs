@@ -24,6 +24,7 @@ use crate::client_actor::ClientSenderForPartialWitness;
 use crate::metrics;
 use crate::stateless_validation::state_witness_tracker::ChunkStateWitnessTracker;
 
+use super::partial_witness_history::PartialWitnessHistory;
 use super::partial_witness_tracker::{PartialEncodedStateWitnessTracker, RsMap};
 
 pub struct PartialWitnessActor {
@@ -96,9 +97,10 @@ impl PartialWitnessActor {
         client_sender: ClientSenderForPartialWitness,
         my_signer: Arc<dyn ValidatorSigner>,
         epoch_manager: Arc<dyn EpochManagerAdapter>,
+        history: PartialWitnessHistory,
     ) -> Self {
         let partial_witness_tracker =
-            PartialEncodedStateWitnessTracker::new(client_sender, epoch_manager.clone());
+            PartialEncodedStateWitnessTracker::new(client_sender, epoch_manager.clone(), history);
         Self {
             network_adapter,
             my_signer,
@@ -208,6 +210,13 @@ impl PartialWitnessActor {
             chunk_validators.clone(),
         );
         encode_timer.observe_duration();
+        let num_parts_sent = validator_witness_tuple
+            .iter()
+            .filter(|(validator, _)| validator != self.my_signer.validator_id())
+            .count();
+        metrics::PARTIAL_WITNESS_PARTS_SENT
+            .with_label_values(&[shard_id_label.as_str(), "produced"])
+            .inc_by(num_parts_sent as u64);
 
         // Since we can't send network message to ourselves, we need to send the PartialEncodedStateWitnessForward
         // message for our part.
@@ -248,6 +257,10 @@ impl PartialWitnessActor {
 
         // Validate the partial encoded state witness.
         self.validate_partial_encoded_state_witness(&partial_witness)?;
+        let shard_id_label = partial_witness.shard_id().to_string();
+        metrics::PARTIAL_WITNESS_PARTS_RECEIVED
+            .with_label_values(&[shard_id_label.as_str(), "from_producer"])
+            .inc();
 
         // Store the partial encoded state witness for self.
         self.partial_witness_tracker
@@ -262,6 +275,13 @@ impl PartialWitnessActor {
                 partial_witness.height_created(),
             )?
             .ordered_chunk_validators();
+        let num_parts_sent = chunk_validators
+            .iter()
+            .filter(|validator| *validator != self.my_signer.validator_id())
+            .count();
+        metrics::PARTIAL_WITNESS_PARTS_SENT
+            .with_label_values(&[shard_id_label.as_str(), "forwarded"])
+            .inc_by(num_parts_sent as u64);
 
         self.network_adapter.send(PeerManagerMessageRequest::NetworkRequests(
             NetworkRequests::PartialEncodedStateWitnessForward(chunk_validators, partial_witness),
@@ -277,6 +297,9 @@ impl PartialWitnessActor {
     ) -> Result<(), Error> {
         // Validate the partial encoded state witness.
         self.validate_partial_encoded_state_witness(&partial_witness)?;
+        metrics::PARTIAL_WITNESS_PARTS_RECEIVED
+            .with_label_values(&[partial_witness.shard_id().to_string().as_str(), "forwarded"])
+            .inc();
 
         // Store the partial encoded state witness for self.
         self.partial_witness_tracker.store_partial_encoded_state_witness(partial_witness)?;
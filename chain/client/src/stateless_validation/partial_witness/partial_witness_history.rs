@@ -0,0 +1,110 @@
+use std::sync::{Arc, Mutex};
+
+use lru::LruCache;
+use near_async::time::Duration;
+use near_client_primitives::debug::{PartialWitnessPartView, PartialWitnessView};
+use near_primitives::stateless_validation::ChunkProductionKey;
+use near_primitives::types::AccountId;
+
+/// Number of the most recent state witnesses kept for the debug page.
+const NUM_WITNESSES_IN_HISTORY: usize = 50;
+
+/// Arrival of the parts of the recent state witnesses received by this node,
+/// shared by the partial witness actor recording it and the client showing it
+/// on the debug page.
+#[derive(Clone)]
+pub struct PartialWitnessHistory(Arc<Mutex<LruCache<ChunkProductionKey, PartialWitnessView>>>);
+
+impl PartialWitnessHistory {
+    pub fn new() -> Self {
+        Self(Arc::new(Mutex::new(LruCache::new(NUM_WITNESSES_IN_HISTORY))))
+    }
+
+    /// Starts recording the witness, with the chunk validators owning the
+    /// parts in the order of the parts.
+    pub(crate) fn record_witness(&self, key: &ChunkProductionKey, part_owners: Vec<AccountId>) {
+        let parts = part_owners
+            .into_iter()
+            .map(|owner| PartialWitnessPartView { owner, received_after_ms: None })
+            .collect();
+        self.0.lock().unwrap().put(
+            key.clone(),
+            PartialWitnessView {
+                shard_id: key.shard_id,
+                height_created: key.height_created,
+                decoded_after_ms: None,
+                parts,
+            },
+        );
+    }
+
+    /// Records the part received `received_after` the first part of the witness.
+    pub(crate) fn record_part(
+        &self,
+        key: &ChunkProductionKey,
+        part_ord: usize,
+        received_after: Duration,
+    ) {
+        if let Some(part) =
+            self.0.lock().unwrap().get_mut(key).and_then(|witness| witness.parts.get_mut(part_ord))
+        {
+            part.received_after_ms = Some(received_after.whole_milliseconds() as u64);
+        }
+    }
+
+    /// Records the witness decoded `decoded_after` its first part.
+    pub(crate) fn record_decoded(&self, key: &ChunkProductionKey, decoded_after: Duration) {
+        if let Some(witness) = self.0.lock().unwrap().get_mut(key) {
+            witness.decoded_after_ms = Some(decoded_after.whole_milliseconds() as u64);
+        }
+    }
+
+    /// Returns the recorded witnesses, in descending order of height.
+    pub fn views(&self) -> Vec<PartialWitnessView> {
+        let mut views: Vec<_> =
+            self.0.lock().unwrap().iter().map(|(_, witness)| witness.clone()).collect();
+        views.sort_by(|a, b| (b.height_created, b.shard_id).cmp(&(a.height_created, a.shard_id)));
+        views
+    }
+}
+
+impl Default for PartialWitnessHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_primitives::types::EpochId;
+
+    #[test]
+    fn test_partial_witness_history() {
+        let history = PartialWitnessHistory::new();
+        let key = |height_created| ChunkProductionKey {
+            shard_id: 0,
+            epoch_id: EpochId::default(),
+            height_created,
+        };
+        let owners: Vec<AccountId> = vec!["alice".parse().unwrap(), "bob".parse().unwrap()];
+        history.record_witness(&key(1), owners.clone());
+        history.record_witness(&key(2), owners);
+        history.record_part(&key(1), 1, Duration::milliseconds(0));
+        history.record_part(&key(1), 0, Duration::milliseconds(20));
+        history.record_decoded(&key(1), Duration::milliseconds(10));
+        // Unknown witnesses and parts are ignored.
+        history.record_part(&key(3), 0, Duration::milliseconds(0));
+        history.record_part(&key(2), 2, Duration::milliseconds(0));
+
+        let views = history.views();
+        assert_eq!(views.iter().map(|view| view.height_created).collect::<Vec<_>>(), vec![2, 1]);
+        assert_eq!(views[0].decoded_after_ms, None);
+        assert!(views[0].parts.iter().all(|part| part.received_after_ms.is_none()));
+        assert_eq!(views[1].decoded_after_ms, Some(10));
+        assert_eq!(
+            views[1].parts.iter().map(|part| part.received_after_ms).collect::<Vec<_>>(),
+            vec![Some(20), Some(0)]
+        );
+    }
+}
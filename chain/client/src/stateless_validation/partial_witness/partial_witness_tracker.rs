@@ -11,13 +11,15 @@ use near_primitives::reed_solomon::reed_solomon_decode;
 use near_primitives::stateless_validation::{
     ChunkProductionKey, EncodedChunkStateWitness, PartialEncodedStateWitness,
 };
-use near_primitives::types::ShardId;
+use near_primitives::types::{AccountId, ShardId};
 use reed_solomon_erasure::galois_8::ReedSolomon;
 use time::ext::InstantExt as _;
 
 use crate::client_actor::ClientSenderForPartialWitness;
 use crate::metrics;
 
+use super::partial_witness_history::PartialWitnessHistory;
+
 /// Max number of chunks to keep in the witness tracker cache. We reach here only after validation
 /// of the partial_witness so the LRU cache size need not be too large.
 const NUM_CHUNKS_IN_WITNESS_TRACKER_CACHE: usize = 200;
@@ -61,7 +63,11 @@ struct CacheEntry {
     pub duration_to_last_part: Duration,
     pub data_parts_present: usize,
     pub data_parts_required: usize,
+    /// Number of the parts received after the state witness was decoded.
+    pub parts_after_decode: usize,
     pub parts: Vec<Option<Box<[u8]>>>,
+    /// Whether each part was received, as decoding reconstructs the missing `parts`.
+    pub received: Vec<bool>,
     pub rs: Arc<Option<ReedSolomon>>,
     pub total_parts_size: usize,
 }
@@ -79,7 +85,9 @@ impl CacheEntry {
             duration_to_last_part: Duration::seconds(0),
             data_parts_present: 0,
             data_parts_required: data_parts,
+            parts_after_decode: 0,
             parts: vec![None; total_parts],
+            received: vec![false; total_parts],
             total_parts_size: 0,
             rs,
         }
@@ -96,7 +104,7 @@ impl CacheEntry {
         let (part_ord, part, encoded_length) = partial_witness.decompose();
 
         // Check if the part is already present.
-        if self.parts[part_ord].is_some() {
+        if self.received[part_ord] {
             tracing::warn!(
                 target: "client",
                 ?shard_id,
@@ -110,6 +118,7 @@ impl CacheEntry {
         // Increment the count of data parts present even if the part has been decoded before.
         // We use this in metrics to track the number of parts received. Insert the part into the cache entry.
         self.data_parts_present += 1;
+        self.received[part_ord] = true;
         self.total_parts_size += part.len();
         self.parts[part_ord] = Some(part);
         self.shard_id = shard_id;
@@ -117,6 +126,7 @@ impl CacheEntry {
 
         // Check if we have already decoded the state witness.
         if self.is_decoded {
+            self.parts_after_decode += 1;
             return None;
         }
 
@@ -166,18 +176,22 @@ pub struct PartialEncodedStateWitnessTracker {
     parts_cache: LruCache<ChunkProductionKey, CacheEntry>,
     /// Reed Solomon encoder for decoding state witness parts.
     rs_map: RsMap,
+    /// Arrival of the parts of the recent state witnesses, for the debug page.
+    history: PartialWitnessHistory,
 }
 
 impl PartialEncodedStateWitnessTracker {
     pub fn new(
         client_sender: ClientSenderForPartialWitness,
         epoch_manager: Arc<dyn EpochManagerAdapter>,
+        history: PartialWitnessHistory,
     ) -> Self {
         Self {
             client_sender,
             epoch_manager,
             parts_cache: LruCache::new(NUM_CHUNKS_IN_WITNESS_TRACKER_CACHE),
             rs_map: RsMap::new(),
+            history,
         }
     }
 
@@ -192,8 +206,15 @@ impl PartialEncodedStateWitnessTracker {
         let key = partial_witness.chunk_production_key();
         let entry = self.parts_cache.get_mut(&key).unwrap();
 
-        if let Some(encoded_witness) = entry.insert_in_cache_entry(partial_witness) {
+        let part_ord = partial_witness.part_ord();
+        let is_new_part = entry.received.get(part_ord) == Some(&false);
+        let decoded = entry.insert_in_cache_entry(partial_witness);
+        if is_new_part {
+            self.history.record_part(&key, part_ord, entry.duration_to_last_part);
+        }
+        if let Some(encoded_witness) = decoded {
             tracing::debug!(target: "client", ?key, "Sending encoded witness to client.");
+            self.history.record_decoded(&key, entry.duration_to_last_part);
 
             // Record the time taken from receiving first part to decoding partial witness.
             metrics::PARTIAL_WITNESS_DECODE_TIME
@@ -206,8 +227,11 @@ impl PartialEncodedStateWitnessTracker {
         Ok(())
     }
 
-    fn get_num_parts(&self, partial_witness: &PartialEncodedStateWitness) -> Result<usize, Error> {
-        // The expected number of parts for the Reed Solomon encoding is the number of chunk validators.
+    /// Returns the chunk validators in the order of the parts they own.
+    fn get_part_owners(
+        &self,
+        partial_witness: &PartialEncodedStateWitness,
+    ) -> Result<Vec<AccountId>, Error> {
         Ok(self
             .epoch_manager
             .get_chunk_validator_assignments(
@@ -215,7 +239,7 @@ impl PartialEncodedStateWitnessTracker {
                 partial_witness.shard_id(),
                 partial_witness.height_created(),
             )?
-            .len())
+            .ordered_chunk_validators())
     }
 
     // Function to insert a new entry into the cache for the chunk hash if it does not already exist
@@ -229,10 +253,20 @@ impl PartialEncodedStateWitnessTracker {
         if self.parts_cache.contains(&key) {
             return Ok(());
         }
-        let num_parts = self.get_num_parts(&partial_witness)?;
-        let rs = self.rs_map.entry(num_parts);
+        // The expected number of parts for the Reed Solomon encoding is the number of chunk validators.
+        let part_owners = self.get_part_owners(&partial_witness)?;
+        let rs = self.rs_map.entry(part_owners.len());
         let new_entry = CacheEntry::new(rs);
+        self.history.record_witness(&key, part_owners);
         if let Some((evicted_chunk_hash, evicted_entry)) = self.parts_cache.push(key, new_entry) {
+            let shard_id_label = evicted_entry.shard_id.to_string();
+            metrics::PARTIAL_WITNESS_PARTS_MISSING
+                .with_label_values(&[shard_id_label.as_str()])
+                .inc_by((evicted_entry.parts.len() - evicted_entry.data_parts_present) as u64);
+            metrics::PARTIAL_WITNESS_PARTS_LATE
+                .with_label_values(&[shard_id_label.as_str()])
+                .inc_by(evicted_entry.parts_after_decode as u64);
+
             // Record the ratio of parts received to parts required for the evicted entry.
             // Note that this includes the parts received after decoding the state witness.
             let parts_received_ratio =
@@ -2,6 +2,7 @@ use std::time::Instant;
 
 use near_chain::types::{RuntimeStorageConfig, StorageDataSource};
 use near_chain::{Block, BlockHeader};
+use near_chain_configs::NodeRole;
 use near_chain_primitives::Error;
 use near_primitives::sharding::{ShardChunk, ShardChunkHeader};
 use near_primitives::stateless_validation::EncodedChunkStateWitness;
@@ -16,7 +17,10 @@ impl Client {
     // Temporary feature to make node produce state witness for every chunk in every processed block
     // and then self-validate it.
     pub(crate) fn shadow_validate_block_chunks(&mut self, block: &Block) -> Result<(), Error> {
-        if !cfg!(feature = "shadow_chunk_validation") {
+        // A read replica skips the validation work to save the CPU for reads.
+        if !cfg!(feature = "shadow_chunk_validation")
+            || self.config.node_role == NodeRole::ReadReplica
+        {
             return Ok(());
         }
         let block_hash = block.hash();
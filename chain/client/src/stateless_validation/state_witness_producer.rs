@@ -76,6 +76,7 @@ impl Client {
         Ok(())
     }
 
+    #[tracing::instrument(target = "client", level = "debug", "create_state_witness", skip_all, fields(shard_id = chunk.shard_id(), height_created = chunk.height_created()))]
     pub(crate) fn create_state_witness(
         &mut self,
         chunk_producer: AccountId,
@@ -127,6 +128,7 @@ impl Client {
 
     /// Collect state transition data necessary to produce state witness for
     /// `chunk_header`.
+    #[tracing::instrument(target = "client", level = "debug", "collect_state_transition_data", skip_all, fields(shard_id = chunk_header.shard_id()))]
     fn collect_state_transition_data(
         &mut self,
         chunk_header: &ShardChunkHeader,
@@ -203,6 +205,7 @@ impl Client {
     /// boundaries, because it removes the receipts that target the other half of a split shard,
     /// which makes the proof invalid. We need to collect the original proof and later, after verifcation,
     /// filter it to remove the receipts that were meant for the other half of the split shard.
+    #[tracing::instrument(target = "client", level = "debug", "collect_source_receipt_proofs", skip_all, fields(shard_id = prev_chunk_header.shard_id()))]
     fn collect_source_receipt_proofs(
         &self,
         prev_block_header: &BlockHeader,
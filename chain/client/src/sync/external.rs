@@ -1,5 +1,6 @@
 use crate::metrics;
 use futures::TryStreamExt;
+use near_chain_configs::S3UploadConfig;
 use near_primitives::types::{EpochId, ShardId};
 use std::io::{Read, Write};
 use std::path::PathBuf;
@@ -46,6 +47,7 @@ impl StateFileType {
 pub enum ExternalConnection {
     S3 {
         bucket: Arc<s3::Bucket>,
+        upload: S3UploadConfig,
     },
     Filesystem {
         root_dir: PathBuf,
@@ -74,7 +76,7 @@ impl ExternalConnection {
             .with_label_values(&[&shard_id.to_string(), &file_type.to_string()])
             .start_timer();
         match self {
-            ExternalConnection::S3 { bucket } => {
+            ExternalConnection::S3 { bucket, .. } => {
                 let response = bucket.get_object(location).await?;
                 tracing::debug!(target: "sync", %shard_id, location, response_code = response.status_code(), num_bytes = response.bytes().len(), "S3 request finished");
                 if response.status_code() == 200 {
@@ -141,8 +143,27 @@ impl ExternalConnection {
         location: &str,
     ) -> Result<(), anyhow::Error> {
         match self {
-            ExternalConnection::S3 { bucket } => {
-                bucket.put_object(&location, data).await?;
+            ExternalConnection::S3 { bucket, upload } => {
+                if data.len() as u64 <= upload.multipart_part_size.as_u64() {
+                    with_retries(upload, shard_id, "put_object", || async {
+                        let response = bucket.put_object(location, data).await?;
+                        anyhow::ensure!(
+                            response.status_code() == 200,
+                            "Bad response status code: {}",
+                            response.status_code()
+                        );
+                        if upload.verify_checksums {
+                            check_etag(response.headers().get("etag"), data)?;
+                        }
+                        Ok(())
+                    })
+                    .await?;
+                } else {
+                    Self::put_multipart_s3(bucket, upload, data, shard_id, location).await?;
+                }
+                metrics::STATE_SYNC_DUMP_UPLOADED_BYTES
+                    .with_label_values(&[&shard_id.to_string(), &file_type.to_string()])
+                    .inc_by(data.len() as u64);
                 tracing::debug!(target: "state_sync_dump", shard_id, part_length = data.len(), ?location, ?file_type, "Wrote a state part to S3");
                 Ok(())
             }
@@ -171,6 +192,68 @@ impl ExternalConnection {
         }
     }
 
+    /// Uploads the data in chunks of the configured part size, so that a
+    /// failure only needs the affected chunk to be uploaded again.
+    async fn put_multipart_s3(
+        bucket: &s3::Bucket,
+        upload: &S3UploadConfig,
+        data: &[u8],
+        shard_id: ShardId,
+        location: &str,
+    ) -> Result<(), anyhow::Error> {
+        const CONTENT_TYPE: &str = "application/octet-stream";
+        let upload_id = with_retries(upload, shard_id, "initiate_multipart_upload", || async {
+            Ok(bucket.initiate_multipart_upload(location, CONTENT_TYPE).await?.upload_id)
+        })
+        .await?;
+        let result = async {
+            let mut parts = vec![];
+            let part_size = upload.multipart_part_size.as_u64() as usize;
+            for (index, chunk) in data.chunks(part_size).enumerate() {
+                // Part numbers start at 1.
+                let part_number = index as u32 + 1;
+                let part = with_retries(upload, shard_id, "put_multipart_chunk", || async {
+                    let part = bucket
+                        .put_multipart_chunk(
+                            chunk.to_vec(),
+                            location,
+                            part_number,
+                            &upload_id,
+                            CONTENT_TYPE,
+                        )
+                        .await?;
+                    if upload.verify_checksums {
+                        check_etag(Some(&part.etag), chunk)?;
+                    }
+                    Ok(part)
+                })
+                .await?;
+                parts.push(part);
+            }
+            tracing::debug!(target: "state_sync_dump", shard_id, ?location, num_chunks = parts.len(), "Completing a multipart upload to S3");
+            with_retries(upload, shard_id, "complete_multipart_upload", || async {
+                let response =
+                    bucket.complete_multipart_upload(location, &upload_id, parts.clone()).await?;
+                anyhow::ensure!(
+                    response.status_code() == 200,
+                    "Bad response status code: {}",
+                    response.status_code()
+                );
+                Ok(())
+            })
+            .await
+        }
+        .await;
+        if result.is_err() {
+            // Otherwise the uploaded chunks are kept, and paid for, until the
+            // bucket's lifecycle rules remove them.
+            if let Err(err) = bucket.abort_upload(location, &upload_id).await {
+                tracing::warn!(target: "state_sync_dump", shard_id, ?location, ?err, "Failed to abort a multipart upload to S3");
+            }
+        }
+        result
+    }
+
     fn extract_file_name_from_full_path(full_path: String) -> String {
         return Self::extract_file_name_from_path_buf(PathBuf::from(full_path));
     }
@@ -191,7 +274,7 @@ impl ExternalConnection {
             .with_label_values(&[&shard_id.to_string()])
             .start_timer();
         match self {
-            ExternalConnection::S3 { bucket } => {
+            ExternalConnection::S3 { bucket, .. } => {
                 let prefix = format!("{}/", directory_path);
                 let list_results = bucket.list(prefix.clone(), Some("/".to_string())).await?;
                 tracing::debug!(target: "state_sync_dump", shard_id, ?directory_path, "List state parts in s3");
@@ -272,6 +355,52 @@ impl ExternalConnection {
     }
 }
 
+/// Runs the request, retrying it with exponential backoff until it succeeds or
+/// the retries configured for S3 uploads are used up.
+async fn with_retries<T, F, Fut>(
+    config: &S3UploadConfig,
+    shard_id: ShardId,
+    request: &str,
+    mut f: F,
+) -> Result<T, anyhow::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, anyhow::Error>>,
+{
+    let mut backoff = config.initial_retry_backoff;
+    let mut attempt = 0;
+    loop {
+        match f().await {
+            Ok(result) => return Ok(result),
+            Err(err) if attempt < config.max_retries => {
+                tracing::warn!(target: "state_sync_dump", shard_id, request, attempt, ?err, ?backoff, "S3 request failed, retrying");
+                metrics::STATE_SYNC_DUMP_PUT_OBJECT_RETRIES
+                    .with_label_values(&[&shard_id.to_string(), request])
+                    .inc();
+                tokio::time::sleep(backoff.unsigned_abs()).await;
+                backoff = backoff * 2;
+                attempt += 1;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Checks that the ETag returned by S3 is the MD5 checksum of the uploaded
+/// data, which is the case for uploads without server-side encryption by KMS.
+fn check_etag(etag: Option<&String>, data: &[u8]) -> Result<(), anyhow::Error> {
+    let Some(etag) = etag else {
+        anyhow::bail!("S3 response has no ETag");
+    };
+    let expected = format!("{:x}", md5::compute(data));
+    let etag = etag.trim_matches('"');
+    anyhow::ensure!(
+        etag == expected,
+        "Checksum mismatch: S3 reported {etag}, the data has {expected}"
+    );
+    Ok(())
+}
+
 /// Construct the state file location on the external storage.
 pub fn external_storage_location(
     chain_id: &str,
@@ -406,7 +535,7 @@ fn create_bucket(
 #[cfg(test)]
 mod test {
     use crate::sync::external::{
-        get_num_parts_from_filename, get_part_id_from_filename, is_part_filename,
+        check_etag, get_num_parts_from_filename, get_part_id_from_filename, is_part_filename,
         ExternalConnection, StateFileType,
     };
     use near_o11y::testonly::init_test_logger;
@@ -416,6 +545,16 @@ mod test {
         Alphanumeric.sample_string(&mut rand::thread_rng(), rand_len)
     }
 
+    #[test]
+    fn test_check_etag() {
+        let data = b"state part";
+        let etag = format!("\"{:x}\"", md5::compute(data));
+        assert!(check_etag(Some(&etag), data).is_ok());
+        assert!(check_etag(Some(&etag.trim_matches('"').to_string()), data).is_ok());
+        assert!(check_etag(Some(&etag), b"other part").is_err());
+        assert!(check_etag(None, data).is_err());
+    }
+
     #[test]
     fn test_match_filename() {
         let filename = StateFileType::StatePart { part_id: 5, num_parts: 15 }.filename();
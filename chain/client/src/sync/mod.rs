@@ -3,5 +3,6 @@ pub mod block;
 pub mod epoch;
 pub mod external;
 pub mod header;
+pub mod progress;
 pub mod state;
 pub mod sync_actor;
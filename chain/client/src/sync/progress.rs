@@ -0,0 +1,149 @@
+use near_async::time::Instant;
+use near_client_primitives::types::{ShardSyncDownload, ShardSyncStatus, SyncStatus};
+use near_primitives::types::ShardId;
+use near_primitives::views::{ShardSyncProgressView, SyncProgressView};
+
+/// Remembers when the node entered the current sync phase and how far it was
+/// at that point, to estimate the sync rate and the remaining time.
+#[derive(Default)]
+pub struct SyncProgressTracker {
+    phase_start: Option<PhaseStart>,
+}
+
+struct PhaseStart {
+    phase: u8,
+    time: Instant,
+    done: u64,
+}
+
+impl SyncProgressTracker {
+    /// Returns the progress of the given sync status, or None if the node is not
+    /// syncing.
+    pub fn progress(&mut self, sync_status: &SyncStatus, now: Instant) -> Option<SyncProgressView> {
+        if !sync_status.is_syncing() {
+            self.phase_start = None;
+            return None;
+        }
+        let mut shards = vec![];
+        let (done, total) = match sync_status {
+            SyncStatus::HeaderSync { start_height, current_height, highest_height }
+            | SyncStatus::BlockSync { start_height, current_height, highest_height } => (
+                Some(current_height.saturating_sub(*start_height)),
+                Some(highest_height.saturating_sub(*start_height)),
+            ),
+            SyncStatus::StateSync(state_sync_status) => {
+                shards = state_sync_status
+                    .sync_status
+                    .iter()
+                    .map(|(shard_id, download)| shard_progress(*shard_id, download))
+                    .collect::<Vec<_>>();
+                shards.sort_by_key(|shard| shard.shard_id);
+                // The number of parts of a shard is only known once its header is downloaded.
+                let num_parts_known = state_sync_status
+                    .sync_status
+                    .values()
+                    .all(|download| download.status != ShardSyncStatus::StateDownloadHeader);
+                if num_parts_known {
+                    (
+                        Some(shards.iter().map(|shard| shard.parts_downloaded).sum()),
+                        Some(shards.iter().map(|shard| shard.num_parts).sum()),
+                    )
+                } else {
+                    (None, None)
+                }
+            }
+            _ => (None, None),
+        };
+
+        let phase = sync_status.repr();
+        let phase_start = match self.phase_start.take() {
+            Some(phase_start) if phase_start.phase == phase => phase_start,
+            _ => PhaseStart { phase, time: now, done: done.unwrap_or(0) },
+        };
+
+        let progress_percent = match (done, total) {
+            (Some(done), Some(total)) if total > 0 => Some(done as f64 * 100. / total as f64),
+            _ => None,
+        };
+        let elapsed = (now - phase_start.time).as_secs_f64();
+        let rate_per_second = match done {
+            Some(done) if elapsed > 0. => {
+                Some(done.saturating_sub(phase_start.done) as f64 / elapsed)
+            }
+            _ => None,
+        };
+        let estimated_seconds_remaining = match (done, total, rate_per_second) {
+            (Some(done), Some(total), Some(rate)) if rate > 0. => {
+                Some((total.saturating_sub(done) as f64 / rate).ceil() as u64)
+            }
+            _ => None,
+        };
+        self.phase_start = Some(phase_start);
+        Some(SyncProgressView {
+            phase: sync_status.as_variant_name().to_string(),
+            progress_percent,
+            rate_per_second,
+            estimated_seconds_remaining,
+            shards,
+        })
+    }
+}
+
+fn shard_progress(shard_id: ShardId, download: &ShardSyncDownload) -> ShardSyncProgressView {
+    let (parts_downloaded, num_parts) = match download.status {
+        ShardSyncStatus::StateDownloadHeader => (0, 0),
+        ShardSyncStatus::StateDownloadParts => (
+            download.downloads.iter().filter(|download| download.done).count() as u64,
+            download.downloads.len() as u64,
+        ),
+        // All parts are downloaded once the shard moves past the download phase.
+        _ => (download.downloads.len() as u64, download.downloads.len() as u64),
+    };
+    ShardSyncProgressView {
+        shard_id,
+        status: download.status.to_string(),
+        parts_downloaded,
+        num_parts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SyncProgressTracker;
+    use near_async::time::{Duration, FakeClock, Utc};
+    use near_client_primitives::types::SyncStatus;
+
+    #[test]
+    fn test_header_sync_progress() {
+        let clock = FakeClock::new(Utc::UNIX_EPOCH);
+        let mut tracker = SyncProgressTracker::default();
+        assert!(tracker.progress(&SyncStatus::NoSync, clock.now()).is_none());
+
+        let status = |current_height| SyncStatus::HeaderSync {
+            start_height: 100,
+            current_height,
+            highest_height: 1100,
+        };
+        let progress = tracker.progress(&status(100), clock.now()).unwrap();
+        assert_eq!(progress.phase, "HeaderSync");
+        assert_eq!(progress.progress_percent, Some(0.));
+        assert_eq!(progress.rate_per_second, None);
+        assert_eq!(progress.estimated_seconds_remaining, None);
+
+        clock.advance(Duration::seconds(10));
+        let progress = tracker.progress(&status(600), clock.now()).unwrap();
+        assert_eq!(progress.progress_percent, Some(50.));
+        assert_eq!(progress.rate_per_second, Some(50.));
+        assert_eq!(progress.estimated_seconds_remaining, Some(10));
+
+        // Entering a new phase restarts the rate estimation.
+        let block_sync = SyncStatus::BlockSync {
+            start_height: 1000,
+            current_height: 1000,
+            highest_height: 1100,
+        };
+        let progress = tracker.progress(&block_sync, clock.now()).unwrap();
+        assert_eq!(progress.phase, "BlockSync");
+        assert_eq!(progress.rate_per_second, None);
+    }
+}
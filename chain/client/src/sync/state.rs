@@ -126,6 +126,8 @@ enum StateSyncInner {
         chain_id: String,
         /// This semaphore imposes a restriction on the maximum number of simultaneous downloads
         semaphore: Arc<tokio::sync::Semaphore>,
+        /// Total number of permits of the semaphore, including the acquired ones.
+        num_permits: usize,
         /// Connection to the external storage.
         external: ExternalConnection,
     },
@@ -188,7 +190,10 @@ impl StateSync {
                         if let Err(err) = bucket {
                             panic!("Failed to create an S3 bucket: {}", err);
                         }
-                        ExternalConnection::S3 { bucket: Arc::new(bucket.unwrap()) }
+                        ExternalConnection::S3 {
+                            bucket: Arc::new(bucket.unwrap()),
+                            upload: Default::default(),
+                        }
                     }
                     ExternalStorageLocation::Filesystem { root_dir } => {
                         ExternalConnection::Filesystem { root_dir: root_dir.clone() }
@@ -207,6 +212,7 @@ impl StateSync {
                 StateSyncInner::External {
                     chain_id: chain_id.to_string(),
                     semaphore: Arc::new(tokio::sync::Semaphore::new(num_permits)),
+                    num_permits,
                     external,
                 }
             }
@@ -225,6 +231,28 @@ impl StateSync {
         }
     }
 
+    /// Changes the maximum number of simultaneous downloads of state parts from
+    /// the external storage. Ongoing downloads are not interrupted, so the
+    /// limit is only lowered as far as there are no downloads left to finish.
+    pub fn set_num_concurrent_requests(&mut self, num_concurrent_requests: u32) {
+        let StateSyncInner::External { semaphore, num_permits, .. } = &mut self.inner else {
+            return;
+        };
+        let target = num_concurrent_requests as usize;
+        if target > *num_permits {
+            semaphore.add_permits(target - *num_permits);
+            *num_permits = target;
+        }
+        while target < *num_permits {
+            let Ok(permit) = semaphore.try_acquire() else {
+                tracing::info!(target: "sync", num_permits, target, "Some state part downloads are still running, keeping their permits");
+                break;
+            };
+            permit.forget();
+            *num_permits -= 1;
+        }
+    }
+
     // The return value indicates whether state sync is
     // finished, in which case the client will transition to block sync
     fn sync_shards_status(
@@ -708,7 +736,7 @@ impl StateSync {
                     );
                 }
             }
-            StateSyncInner::External { chain_id, semaphore, external } => {
+            StateSyncInner::External { chain_id, semaphore, external, .. } => {
                 let sync_block_header = chain.get_block_header(&sync_hash).unwrap();
                 let epoch_id = sync_block_header.epoch_id();
                 let epoch_info = chain.epoch_manager.get_epoch_info(epoch_id).unwrap();
@@ -6,6 +6,9 @@ use std::mem::swap;
 use std::sync::{Arc, RwLock};
 
 use crate::client::ProduceChunkResult;
+use crate::stateless_validation::chunk_validator::{
+    pre_validate_chunk_state_witness, validate_chunk_state_witness, MainStateTransitionCache,
+};
 use crate::Client;
 use actix_rt::{Arbiter, System};
 use itertools::Itertools;
@@ -21,7 +24,7 @@ use near_primitives::block::Block;
 use near_primitives::hash::CryptoHash;
 use near_primitives::merkle::{merklize, PartialMerkleTree};
 use near_primitives::sharding::{EncodedShardChunk, ShardChunk};
-use near_primitives::stateless_validation::ChunkEndorsement;
+use near_primitives::stateless_validation::{ChunkEndorsement, ChunkStateWitness};
 use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::{BlockHeight, ShardId};
 use near_primitives::utils::MaybeValidated;
@@ -85,6 +88,27 @@ impl Client {
         vec![]
     }
 
+    /// Validates the state witness and waits for the result, unlike
+    /// `process_chunk_state_witness` which validates it in the background.
+    pub fn validate_chunk_state_witness_test(
+        &self,
+        witness: ChunkStateWitness,
+    ) -> Result<(), near_chain::Error> {
+        let pre_validation_output = pre_validate_chunk_state_witness(
+            &witness,
+            &self.chain,
+            self.epoch_manager.as_ref(),
+            self.runtime_adapter.as_ref(),
+        )?;
+        validate_chunk_state_witness(
+            witness,
+            pre_validation_output,
+            self.epoch_manager.as_ref(),
+            self.runtime_adapter.as_ref(),
+            &MainStateTransitionCache::default(),
+        )
+    }
+
     /// Manually produce a single chunk on the given shard and send out the corresponding network messages
     pub fn produce_one_chunk(&mut self, height: BlockHeight, shard_id: ShardId) -> ShardChunk {
         let ProduceChunkResult {
@@ -8,8 +8,8 @@ use crate::stateless_validation::partial_witness::partial_witness_actor::{
     PartialWitnessActor, PartialWitnessSenderForClient,
 };
 use crate::{
-    start_client, Client, ClientActor, StartClientResult, SyncAdapter, SyncStatus, ViewClientActor,
-    ViewClientActorInner,
+    start_client, Client, ClientActor, PartialWitnessHistory, StartClientResult, SyncAdapter,
+    SyncStatus, ViewClientActor, ViewClientActorInner,
 };
 use actix::{Actor, Addr, Context};
 use futures::{future, FutureExt};
@@ -124,6 +124,7 @@ pub fn setup(
                 ReshardingConfig::default(),
                 "resharding_config",
             ),
+            save_account_index: false,
         },
         None,
         Arc::new(RayonAsyncComputationSpawner),
@@ -161,6 +162,7 @@ pub fn setup(
         network_adapter.clone(),
         config.clone(),
         adv.clone(),
+        None,
     );
 
     let state_sync_adapter = Arc::new(RwLock::new(SyncAdapter::new(
@@ -169,12 +171,14 @@ pub fn setup(
         SyncAdapter::actix_actor_maker(),
     )));
 
+    let partial_witness_history = PartialWitnessHistory::new();
     let (partial_witness_addr, _) = spawn_actix_actor(PartialWitnessActor::new(
         clock.clone(),
         network_adapter.clone(),
         noop().into_multi_sender(),
         signer.clone(),
         epoch_manager.clone(),
+        partial_witness_history.clone(),
     ));
     let partial_witness_adapter = partial_witness_addr.with_auto_span_context();
 
@@ -197,6 +201,7 @@ pub fn setup(
         adv,
         None,
         partial_witness_adapter.into_multi_sender(),
+        partial_witness_history,
         enable_doomslug,
         Some(TEST_SEED),
     );
@@ -268,6 +273,7 @@ pub fn setup_only_view(
                 ReshardingConfig::default(),
                 "resharding_config",
             ),
+            save_account_index: false,
         },
         None,
         Arc::new(RayonAsyncComputationSpawner),
@@ -300,6 +306,7 @@ pub fn setup_only_view(
         network_adapter,
         config,
         adv,
+        None,
     )
 }
 
@@ -858,6 +865,7 @@ pub fn setup_mock_all_validators(
                     | NetworkRequests::TxStatus(_, _, _)
                     | NetworkRequests::SnapshotHostInfo { .. }
                     | NetworkRequests::Challenge(_)
+                    | NetworkRequests::EquivocationEvidence(_)
                     | NetworkRequests::ChunkStateWitnessAck(_, _)
                     | NetworkRequests::ChunkEndorsement(_, _)
                     | NetworkRequests::PartialEncodedStateWitness(_)
@@ -1027,6 +1035,7 @@ pub fn setup_synchronous_shards_manager(
                 ReshardingConfig::default(),
                 "resharding_config",
             ),
+            save_account_index: false,
         }, // irrelevant
         None,
         Arc::new(RayonAsyncComputationSpawner),
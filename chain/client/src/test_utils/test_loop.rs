@@ -1,3 +1,4 @@
+pub mod chaos;
 pub mod client_actor;
 pub mod partial_witness_actor;
 pub mod sync_actor;
@@ -8,8 +9,6 @@ use near_async::messaging::{CanSend, Handler, SendAsync};
 use near_async::test_loop::delay_sender::DelaySender;
 use near_async::test_loop::event_handler::{LoopEventHandler, TryIntoOrSelf};
 
-use near_async::time::Duration;
-
 use crate::Client;
 use near_network::client::{
     BlockApproval, BlockResponse, ChunkEndorsementMessage, ClientSenderForNetwork,
@@ -20,7 +19,7 @@ use near_network::state_witness::{
     PartialEncodedStateWitnessMessage, PartialWitnessSenderForNetwork,
     PartialWitnessSenderForNetworkMessage,
 };
-use near_network::test_loop::SupportsRoutingLookup;
+use near_network::test_loop::{NetworkConditions, SupportsRoutingLookup};
 use near_network::types::{NetworkRequests, PeerManagerMessageRequest};
 use near_primitives::hash::CryptoHash;
 use near_primitives::network::PeerId;
@@ -83,6 +82,7 @@ where
 }
 
 /// Handles outgoing network messages, and turns them into incoming client messages.
+/// Messages are delayed or lost according to `network_conditions`.
 pub fn route_network_messages_to_client<
     Data: SupportsRoutingLookup,
     Event: TryIntoOrSelf<PeerManagerMessageRequest>
@@ -91,10 +91,9 @@ pub fn route_network_messages_to_client<
         + From<PartialWitnessSenderForNetworkMessage>,
 >(
     sender: DelaySender<(usize, Event)>,
-    network_delay: Duration,
+    network_conditions: impl Into<NetworkConditions>,
 ) -> LoopEventHandler<Data, (usize, Event)> {
-    // let mut route_back_lookup: HashMap<CryptoHash, usize> = HashMap::new();
-    // let mut next_hash: u64 = 0;
+    let network_conditions = network_conditions.into();
     LoopEventHandler::new(move |event: (usize, Event), data: &mut Data| {
         let (idx, event) = event;
         let message = event.try_into_or_self().map_err(|event| (idx, event.into()))?;
@@ -102,43 +101,48 @@ pub fn route_network_messages_to_client<
             return Err((idx, message.into()));
         };
 
-        let client_senders = (0..data.num_accounts())
-            .map(|idx| {
-                sender
-                    .with_additional_delay(network_delay)
-                    .for_index(idx)
-                    .into_wrapped_multi_sender::<ClientSenderForNetworkMessage, ClientSenderForNetwork>()
+        // Senders to the other instances, None if the message is lost.
+        let link_sender = |other_idx: usize| {
+            let Some(delay) = network_conditions.sample(idx, other_idx) else {
+                tracing::debug!(target: "test_loop", idx, other_idx, "Dropping lost message");
+                return None;
+            };
+            Some(sender.with_additional_delay(delay).for_index(other_idx))
+        };
+        let client_sender = |other_idx: usize| {
+            link_sender(other_idx).map(|sender| {
+                sender.into_wrapped_multi_sender::<ClientSenderForNetworkMessage, ClientSenderForNetwork>()
             })
-            .collect::<Vec<_>>();
-
-        let state_witness_senders = (0..data.num_accounts())
-            .map(|idx| {
-                sender
-                    .with_additional_delay(network_delay)
-                    .for_index(idx)
-                    .into_wrapped_multi_sender::<PartialWitnessSenderForNetworkMessage, PartialWitnessSenderForNetwork>()
+        };
+        let state_witness_sender = |other_idx: usize| {
+            link_sender(other_idx).map(|sender| {
+                sender.into_wrapped_multi_sender::<PartialWitnessSenderForNetworkMessage, PartialWitnessSenderForNetwork>()
             })
-            .collect::<Vec<_>>();
+        };
 
         match request {
             NetworkRequests::Block { block } => {
                 for other_idx in 0..data.num_accounts() {
                     if other_idx != idx {
-                        drop(client_senders[other_idx].send_async(BlockResponse {
-                            block: block.clone(),
-                            peer_id: PeerId::random(),
-                            was_requested: false,
-                        }));
+                        if let Some(client_sender) = client_sender(other_idx) {
+                            drop(client_sender.send_async(BlockResponse {
+                                block: block.clone(),
+                                peer_id: PeerId::random(),
+                                was_requested: false,
+                            }));
+                        }
                     }
                 }
             }
             NetworkRequests::Approval { approval_message } => {
                 let other_idx = data.index_for_account(&approval_message.target);
                 if other_idx != idx {
-                    drop(
-                        client_senders[other_idx]
-                            .send_async(BlockApproval(approval_message.approval, PeerId::random())),
-                    );
+                    if let Some(client_sender) = client_sender(other_idx) {
+                        drop(client_sender.send_async(BlockApproval(
+                            approval_message.approval,
+                            PeerId::random(),
+                        )));
+                    }
                 } else {
                     tracing::warn!("Dropping message to self");
                 }
@@ -146,11 +150,13 @@ pub fn route_network_messages_to_client<
             NetworkRequests::ForwardTx(account, transaction) => {
                 let other_idx = data.index_for_account(&account);
                 if other_idx != idx {
-                    drop(client_senders[other_idx].send_async(ProcessTxRequest {
-                        transaction,
-                        is_forwarded: true,
-                        check_only: false,
-                    }))
+                    if let Some(client_sender) = client_sender(other_idx) {
+                        drop(client_sender.send_async(ProcessTxRequest {
+                            transaction,
+                            is_forwarded: true,
+                            check_only: false,
+                        }))
+                    }
                 } else {
                     tracing::warn!("Dropping message to self");
                 }
@@ -158,9 +164,9 @@ pub fn route_network_messages_to_client<
             NetworkRequests::ChunkEndorsement(target, endorsement) => {
                 let other_idx = data.index_for_account(&target);
                 if other_idx != idx {
-                    drop(
-                        client_senders[other_idx].send_async(ChunkEndorsementMessage(endorsement)),
-                    );
+                    if let Some(client_sender) = client_sender(other_idx) {
+                        drop(client_sender.send_async(ChunkEndorsementMessage(endorsement)));
+                    }
                 } else {
                     tracing::warn!("Dropping message to self");
                 }
@@ -168,7 +174,9 @@ pub fn route_network_messages_to_client<
             NetworkRequests::ChunkStateWitnessAck(target, witness_ack) => {
                 let other_idx = data.index_for_account(&target);
                 if other_idx != idx {
-                    state_witness_senders[other_idx].send(ChunkStateWitnessAckMessage(witness_ack));
+                    if let Some(state_witness_sender) = state_witness_sender(other_idx) {
+                        state_witness_sender.send(ChunkStateWitnessAckMessage(witness_ack));
+                    }
                 } else {
                     tracing::warn!("Dropping state-witness-ack message to self");
                 }
@@ -177,8 +185,10 @@ pub fn route_network_messages_to_client<
                 for (target, partial_witness) in validator_witness_tuple.into_iter() {
                     let other_idx = data.index_for_account(&target);
                     if other_idx != idx {
-                        state_witness_senders[other_idx]
-                            .send(PartialEncodedStateWitnessMessage(partial_witness));
+                        if let Some(state_witness_sender) = state_witness_sender(other_idx) {
+                            state_witness_sender
+                                .send(PartialEncodedStateWitnessMessage(partial_witness));
+                        }
                     } else {
                         tracing::warn!("Dropping state-witness message to self");
                     }
@@ -191,9 +201,11 @@ pub fn route_network_messages_to_client<
                 for target in chunk_validators {
                     let other_idx = data.index_for_account(&target);
                     if other_idx != idx {
-                        state_witness_senders[other_idx].send(
-                            PartialEncodedStateWitnessForwardMessage(partial_witness.clone()),
-                        );
+                        if let Some(state_witness_sender) = state_witness_sender(other_idx) {
+                            state_witness_sender.send(PartialEncodedStateWitnessForwardMessage(
+                                partial_witness.clone(),
+                            ));
+                        }
                     } else {
                         tracing::warn!("Dropping state-witness-forward message to self");
                     }
@@ -0,0 +1,191 @@
+//! Fault injection for multi-instance TestLoop tests.
+//!
+//! A `ChaosScenario` schedules faults at block heights: dropping chunk parts,
+//! partitioning the network and crashing or restarting instances. The faults
+//! are applied once the highest head among the running instances reaches the
+//! height, e.g.
+//!
+//! ```ignore
+//! let network_conditions = NetworkConditions::from(NETWORK_DELAY);
+//! let chaos = ChaosScenario::new(network_conditions.clone())
+//!     .at_height(10005, Fault::Partition(vec![vec![0, 1], vec![2, 3]]))
+//!     .at_height(10010, Fault::HealPartition)
+//!     .at_height(10012, Fault::Crash(3))
+//!     .at_height(10020, Fault::Restart(3, RestartStore::Stale))
+//!     .on_restart(|idx, data, store| rebuild_instance(idx, data, store));
+//! // Registered before any other handler, so that it sees all the events.
+//! test.register_handler(chaos.into_handler());
+//! ```
+//!
+//! The same `NetworkConditions` have to be given to the network routing
+//! handlers for partitions to take effect.
+use crate::Client;
+use near_async::test_loop::event_handler::{LoopEventHandler, TryIntoOrSelf};
+use near_network::test_loop::NetworkConditions;
+use near_network::types::{NetworkRequests, PeerManagerMessageRequest};
+use near_primitives::sharding::{ChunkHash, PartialEncodedChunkPart};
+use near_primitives::types::{BlockHeight, ShardId};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Store an instance is restarted with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RestartStore {
+    /// The store the instance had when it crashed.
+    Stale,
+    /// An empty store, initialized with the genesis only.
+    Fresh,
+}
+
+#[derive(Clone, Debug)]
+pub enum Fault {
+    /// Drops the parts with the given ordinals of the chunks of the shard
+    /// produced at and after the height of the fault, up to and including
+    /// `until_height`. The parts are dropped both when they are distributed
+    /// and when they are requested.
+    DropChunkParts {
+        shard_id: ShardId,
+        part_ords: Vec<u64>,
+        until_height: BlockHeight,
+    },
+    /// Partitions the network into the given groups of instances.
+    Partition(Vec<Vec<usize>>),
+    HealPartition,
+    /// Stops the instance, dropping all the events sent to it.
+    Crash(usize),
+    /// Starts a crashed instance again with the restart callback.
+    Restart(usize, RestartStore),
+}
+
+type RestartFn<Data> = Box<dyn FnMut(usize, &mut Vec<Data>, RestartStore)>;
+
+pub struct ChaosScenario<Data> {
+    network_conditions: NetworkConditions,
+    faults: Vec<(BlockHeight, Fault)>,
+    restart: Option<RestartFn<Data>>,
+}
+
+impl<Data: AsRef<Client> + 'static> ChaosScenario<Data> {
+    pub fn new(network_conditions: NetworkConditions) -> Self {
+        Self { network_conditions, faults: vec![], restart: None }
+    }
+
+    pub fn at_height(mut self, height: BlockHeight, fault: Fault) -> Self {
+        self.faults.push((height, fault));
+        self
+    }
+
+    /// Sets the callback restarting an instance. The callback has to replace
+    /// the data of the instance with newly created components using a stale
+    /// or fresh store, and send the events starting them. Any events of the
+    /// instance from before the crash are gone.
+    pub fn on_restart(
+        mut self,
+        restart: impl FnMut(usize, &mut Vec<Data>, RestartStore) + 'static,
+    ) -> Self {
+        self.restart = Some(Box::new(restart));
+        self
+    }
+
+    /// Creates the handler applying the faults. It never handles events, only
+    /// drops them, so it has to be registered before all other handlers.
+    pub fn into_handler<Event>(self) -> LoopEventHandler<Vec<Data>, (usize, Event)>
+    where
+        Event: TryIntoOrSelf<PeerManagerMessageRequest> + From<PeerManagerMessageRequest>,
+    {
+        let Self { network_conditions, mut faults, mut restart } = self;
+        // Applied in the order of the heights, and of scheduling for the same height.
+        faults.sort_by_key(|(height, _)| *height);
+        let mut faults = faults.into_iter().peekable();
+        let mut crashed = BTreeSet::new();
+        let mut dropped_parts = vec![];
+        // Chunks from which parts are dropped, with the index of the fault.
+        let mut dropped_chunks = HashMap::<ChunkHash, usize>::new();
+        LoopEventHandler::new(move |(idx, event): (usize, Event), data: &mut Vec<Data>| {
+            let height = data
+                .iter()
+                .enumerate()
+                .filter(|(other_idx, _)| !crashed.contains(other_idx))
+                .map(|(_, data)| data.as_ref().chain.head().unwrap().height)
+                .max()
+                .unwrap_or_default();
+            while let Some((_, fault)) = faults.next_if(|(fault_height, _)| *fault_height <= height)
+            {
+                tracing::info!(target: "test_loop", height, ?fault, "Injecting fault");
+                match fault {
+                    Fault::DropChunkParts { shard_id, part_ords, until_height } => {
+                        dropped_parts.push(DroppedParts {
+                            shard_id,
+                            part_ords: part_ords.into_iter().collect(),
+                            heights: height..=until_height,
+                        });
+                    }
+                    Fault::Partition(groups) => network_conditions.partition(&groups),
+                    Fault::HealPartition => network_conditions.heal_partition(),
+                    Fault::Crash(crashed_idx) => {
+                        crashed.insert(crashed_idx);
+                    }
+                    Fault::Restart(restarted_idx, store) => {
+                        assert!(
+                            crashed.remove(&restarted_idx),
+                            "Restarting instance {restarted_idx} which is running"
+                        );
+                        let restart = restart.as_mut().expect("No restart callback set");
+                        restart(restarted_idx, data, store);
+                    }
+                }
+            }
+
+            if crashed.contains(&idx) {
+                tracing::debug!(target: "test_loop", idx, "Dropping event of crashed instance");
+                return Ok(());
+            }
+            if dropped_parts.is_empty() {
+                return Err((idx, event));
+            }
+            let message = event.try_into_or_self().map_err(|event| (idx, event))?;
+            let PeerManagerMessageRequest::NetworkRequests(mut request) = message else {
+                return Err((idx, message.into()));
+            };
+            match &mut request {
+                NetworkRequests::PartialEncodedChunkMessage { partial_encoded_chunk, .. } => {
+                    let header = &partial_encoded_chunk.header;
+                    if let Some(index) = dropped_parts.iter().position(|dropped| {
+                        dropped.shard_id == header.shard_id()
+                            && dropped.heights.contains(&header.height_created())
+                    }) {
+                        dropped_chunks.insert(header.chunk_hash(), index);
+                        dropped_parts[index].retain(&mut partial_encoded_chunk.parts);
+                    }
+                }
+                NetworkRequests::PartialEncodedChunkForward { forward, .. } => {
+                    if let Some(index) = dropped_parts.iter().position(|dropped| {
+                        dropped.shard_id == forward.shard_id
+                            && dropped.heights.contains(&forward.height_created)
+                    }) {
+                        dropped_chunks.insert(forward.chunk_hash.clone(), index);
+                        dropped_parts[index].retain(&mut forward.parts);
+                    }
+                }
+                NetworkRequests::PartialEncodedChunkResponse { response, .. } => {
+                    if let Some(index) = dropped_chunks.get(&response.chunk_hash) {
+                        dropped_parts[*index].retain(&mut response.parts);
+                    }
+                }
+                _ => {}
+            }
+            Err((idx, PeerManagerMessageRequest::NetworkRequests(request).into()))
+        })
+    }
+}
+
+struct DroppedParts {
+    shard_id: ShardId,
+    part_ords: HashSet<u64>,
+    heights: std::ops::RangeInclusive<BlockHeight>,
+}
+
+impl DroppedParts {
+    fn retain(&self, parts: &mut Vec<PartialEncodedChunkPart>) {
+        parts.retain(|part| !self.part_ords.contains(&part.part_ord));
+    }
+}
@@ -28,6 +28,9 @@ pub fn forward_client_messages_from_network_to_client_actor(
             ClientSenderForNetworkMessage::_challenge(msg) => {
                 (msg.callback)(Ok(client_actor.handle(msg.message)));
             }
+            ClientSenderForNetworkMessage::_equivocation_evidence(msg) => {
+                (msg.callback)(Ok(client_actor.handle(msg.message)));
+            }
             ClientSenderForNetworkMessage::_network_info(msg) => {
                 (msg.callback)(Ok(client_actor.handle(msg.message)));
             }
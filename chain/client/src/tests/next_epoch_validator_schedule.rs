@@ -0,0 +1,57 @@
+use crate::test_utils::setup_no_network;
+use actix::System;
+use near_actix_test_utils::run_actix;
+use near_async::time::Clock;
+use near_client_primitives::types::GetNextEpochValidatorSchedule;
+use near_o11y::testonly::init_test_logger;
+use near_o11y::WithSpanContextExt;
+use near_primitives::types::{AccountId, BlockId};
+
+/// get the validator schedule of the next epoch from view client
+#[test]
+fn test_get_next_epoch_validator_schedule() {
+    init_test_logger();
+    run_actix(async {
+        let actor_handles = setup_no_network(
+            Clock::real(),
+            vec!["test".parse().unwrap(), "other".parse().unwrap()],
+            "other".parse().unwrap(),
+            true,
+            true,
+        );
+        let view_client = actor_handles.view_client_actor;
+        actix::spawn(async move {
+            let request = |account_id: Option<&str>| GetNextEpochValidatorSchedule {
+                block_id: Some(BlockId::Height(0)),
+                account_id: account_id.map(|account_id| account_id.parse().unwrap()),
+            };
+            let schedule = view_client.send(request(None).with_span_context()).await.unwrap();
+            let schedule = schedule.unwrap();
+            // The epoch length is 10, the epoch after genesis starts at height 10.
+            assert_eq!(schedule.start_height, 10);
+            let heights: Vec<_> = schedule.schedule.iter().map(|p| p.height).collect();
+            assert_eq!(heights, (10..20).collect::<Vec<_>>());
+
+            // The second request is served from the cache of the epoch.
+            let cached = view_client.send(request(None).with_span_context()).await.unwrap();
+            assert_eq!(cached.unwrap(), schedule);
+
+            let test: AccountId = "test".parse().unwrap();
+            let for_test = view_client.send(request(Some("test")).with_span_context()).await;
+            let for_test = for_test.unwrap().unwrap();
+            let expected: Vec<_> = schedule
+                .schedule
+                .iter()
+                .filter(|p| p.block_producer == test || p.chunk_producers.contains(&test))
+                .cloned()
+                .collect();
+            assert!(!expected.is_empty());
+            assert_eq!(for_test.epoch_id, schedule.epoch_id);
+            assert_eq!(for_test.schedule, expected);
+
+            let for_alice = view_client.send(request(Some("alice")).with_span_context()).await;
+            assert!(for_alice.unwrap().unwrap().schedule.is_empty());
+            System::current().stop();
+        });
+    });
+}
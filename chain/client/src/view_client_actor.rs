@@ -16,13 +16,17 @@ use near_chain::{
 use near_chain_configs::{ClientConfig, ProtocolConfigView};
 use near_chain_primitives::error::EpochErrorResultToChainError;
 use near_client_primitives::types::{
-    Error, GetBlock, GetBlockError, GetBlockProof, GetBlockProofError, GetBlockProofResponse,
-    GetBlockWithMerkleTree, GetChunkError, GetExecutionOutcome, GetExecutionOutcomeError,
-    GetExecutionOutcomesForBlock, GetGasPrice, GetGasPriceError, GetMaintenanceWindows,
-    GetMaintenanceWindowsError, GetNextLightClientBlockError, GetProtocolConfig,
-    GetProtocolConfigError, GetReceipt, GetReceiptError, GetSplitStorageInfo,
-    GetSplitStorageInfoError, GetStateChangesError, GetStateChangesWithCauseInBlock,
-    GetStateChangesWithCauseInBlockForTrackedShards, GetValidatorInfoError, Query, QueryError,
+    ControlColdStoreCopy, ControlColdStoreCopyError, Error, GetAccountActivity,
+    GetAccountActivityError, GetAccountActivityResponse, GetBlock, GetBlockError, GetBlockProof,
+    GetBlockProofError, GetBlockProofResponse, GetBlockWithMerkleTree, GetChunkError,
+    GetCongestionInfo, GetCongestionInfoError, GetDelegateActions, GetDelegateActionsError,
+    GetEquivocationEvidence, GetEquivocationEvidenceError, GetExecutionOutcome,
+    GetExecutionOutcomeError, GetExecutionOutcomesForBlock, GetGasPrice, GetGasPriceError,
+    GetMaintenanceWindows, GetMaintenanceWindowsError, GetNextEpochValidatorSchedule,
+    GetNextLightClientBlockError, GetProtocolConfig, GetProtocolConfigError, GetReceipt,
+    GetReceiptError, GetSplitStorageInfo, GetSplitStorageInfoError, GetStateChangesError,
+    GetStateChangesWithCauseInBlock, GetStateChangesWithCauseInBlockForTrackedShards,
+    GetValidatorInfoError, Query, QueryError, SimulateTransaction, SimulateTransactionError,
     TxStatus, TxStatusError,
 };
 use near_epoch_manager::shard_tracker::ShardTracker;
@@ -37,28 +41,35 @@ use near_network::types::{
 };
 use near_performance_metrics_macros::perf;
 use near_primitives::block::{Block, BlockHeader};
+use near_primitives::congestion_info::CongestionControl;
 use near_primitives::epoch_manager::epoch_info::EpochInfo;
+use near_primitives::errors::EpochError;
 use near_primitives::hash::CryptoHash;
 use near_primitives::merkle::{merklize, PartialMerkleTree};
 use near_primitives::network::AnnounceAccount;
-use near_primitives::receipt::Receipt;
+use near_primitives::receipt::{Receipt, ReceiptEnum};
+use near_primitives::shard_layout::account_id_to_shard_uid;
 use near_primitives::sharding::ShardChunk;
 use near_primitives::state_sync::{
     ShardStateSyncResponse, ShardStateSyncResponseHeader, ShardStateSyncResponseV3,
 };
-use near_primitives::transaction::SignedTransaction;
+use near_primitives::transaction::{Action, SignedTransaction};
 use near_primitives::types::{
-    AccountId, BlockHeight, BlockId, BlockReference, EpochReference, Finality, MaybeBlockId,
-    ShardId, SyncCheckpoint, TransactionOrReceiptId, ValidatorInfoIdentifier,
+    AccountId, BlockHeight, BlockId, BlockReference, EpochId, EpochReference, Finality,
+    MaybeBlockId, ShardId, SyncCheckpoint, TransactionOrReceiptId, ValidatorInfoIdentifier,
 };
 use near_primitives::views::validator_stake_view::ValidatorStakeView;
 use near_primitives::views::{
-    BlockView, ChunkView, EpochValidatorInfo, ExecutionOutcomeWithIdView, ExecutionStatusView,
+    BlockCongestionInfoView, BlockDelegateActionsView, BlockView, ChunkView,
+    ColdStoreCopyStatusView, DelegateActionOutcomeView, EpochValidatorInfo,
+    EquivocationEvidenceView, ExecutionOutcomeWithIdView, ExecutionStatusView,
     FinalExecutionOutcomeView, FinalExecutionOutcomeViewEnum, FinalExecutionStatus, GasPriceView,
-    LightClientBlockView, MaintenanceWindowsView, QueryRequest, QueryResponse, ReceiptView,
-    SignedTransactionView, SplitStorageInfoView, StateChangesKindsView, StateChangesView,
-    TxExecutionStatus, TxStatusView,
+    HeightProducersView, LightClientBlockView, MaintenanceWindowsView,
+    NextEpochValidatorScheduleView, QueryRequest, QueryResponse, ReceiptView,
+    ShardCongestionInfoView, SignedTransactionView, SplitStorageInfoView, StateChangesKindsView,
+    StateChangesView, TxExecutionStatus, TxStatusView,
 };
+use near_store::cold_storage::ColdStoreCopyControl;
 use near_store::flat::{FlatStorageReadyStatus, FlatStorageStatus};
 use near_store::{DBCol, COLD_HEAD_KEY, FINAL_HEAD_KEY, HEAD_KEY};
 use std::cmp::Ordering;
@@ -74,6 +85,16 @@ const REQUEST_WAIT_TIME: i64 = 1000;
 
 const POISONED_LOCK_ERR: &str = "The lock was poisoned.";
 
+/// Number of the account index entries returned per page by default, and at
+/// most.
+const DEFAULT_ACCOUNT_ACTIVITY_LIMIT: u32 = 100;
+const MAX_ACCOUNT_ACTIVITY_LIMIT: u32 = 1000;
+
+/// Number of the epochs whose validator schedule is kept.  Only the schedule
+/// of the next epoch is requested, but the head can be in either of two
+/// epochs around the epoch switch.
+const NEXT_EPOCH_SCHEDULE_CACHE_SIZE: usize = 4;
+
 /// Request and response manager across all instances of ViewClientActor.
 pub struct ViewClientRequestManager {
     /// Transaction query that needs to be forwarded to other shards
@@ -86,6 +107,9 @@ pub struct ViewClientRequestManager {
     pub query_responses: lru::LruCache<String, Result<QueryResponse, String>>,
     /// Receipt outcome requests
     pub receipt_outcome_requests: lru::LruCache<CryptoHash, Instant>,
+    /// Validator schedules of the epochs, computed for all the heights and
+    /// the shards of the epoch on first request.
+    pub next_epoch_validator_schedules: lru::LruCache<EpochId, Arc<NextEpochValidatorScheduleView>>,
 }
 
 pub type ViewClientActor = SyncActixWrapper<ViewClientActorInner>;
@@ -105,6 +129,8 @@ pub struct ViewClientActorInner {
     pub config: ClientConfig,
     request_manager: Arc<RwLock<ViewClientRequestManager>>,
     state_request_cache: Arc<Mutex<VecDeque<Instant>>>,
+    /// Control of the cold store loop, if it is running on this node.
+    cold_store_copy_control: Option<Arc<ColdStoreCopyControl>>,
 }
 
 impl ViewClientRequestManager {
@@ -115,6 +141,7 @@ impl ViewClientRequestManager {
             query_requests: lru::LruCache::new(QUERY_REQUEST_LIMIT),
             query_responses: lru::LruCache::new(QUERY_REQUEST_LIMIT),
             receipt_outcome_requests: lru::LruCache::new(QUERY_REQUEST_LIMIT),
+            next_epoch_validator_schedules: lru::LruCache::new(NEXT_EPOCH_SCHEDULE_CACHE_SIZE),
         }
     }
 }
@@ -133,6 +160,7 @@ impl ViewClientActorInner {
         network_adapter: PeerManagerAdapter,
         config: ClientConfig,
         adv: crate::adversarial::Controls,
+        cold_store_copy_control: Option<Arc<ColdStoreCopyControl>>,
     ) -> Addr<ViewClientActor> {
         SyncArbiter::start(config.view_client_threads, move || {
             // TODO: should we create shared ChainStore that is passed to both Client and ViewClient?
@@ -159,6 +187,7 @@ impl ViewClientActorInner {
                 config: config.clone(),
                 request_manager: Arc::new(RwLock::new(ViewClientRequestManager::new())),
                 state_request_cache: Arc::new(Mutex::new(VecDeque::default())),
+                cold_store_copy_control: cold_store_copy_control.clone(),
             };
             SyncActixWrapper::new(view_client_actor)
         })
@@ -320,6 +349,94 @@ impl ViewClientActorInner {
         Ok(windows)
     }
 
+    fn get_next_epoch_validator_schedule(
+        &self,
+        block_id: MaybeBlockId,
+        account_id: Option<AccountId>,
+    ) -> Result<NextEpochValidatorScheduleView, near_chain::Error> {
+        let header = self.maybe_block_id_to_block_header(block_id)?;
+        let next_epoch_id = self.epoch_manager.get_next_epoch_id(header.hash())?;
+        let cached = self
+            .request_manager
+            .write()
+            .expect(POISONED_LOCK_ERR)
+            .next_epoch_validator_schedules
+            .get(&next_epoch_id)
+            .cloned();
+        let schedule = match cached {
+            Some(schedule) => schedule,
+            None => {
+                let schedule =
+                    Arc::new(self.compute_validator_schedule(&header, next_epoch_id.clone())?);
+                self.request_manager
+                    .write()
+                    .expect(POISONED_LOCK_ERR)
+                    .next_epoch_validator_schedules
+                    .put(next_epoch_id, schedule.clone());
+                schedule
+            }
+        };
+        let Some(account_id) = account_id else {
+            return Ok(schedule.as_ref().clone());
+        };
+        Ok(NextEpochValidatorScheduleView {
+            epoch_id: schedule.epoch_id.clone(),
+            epoch_height: schedule.epoch_height,
+            start_height: schedule.start_height,
+            schedule: schedule
+                .schedule
+                .iter()
+                .filter(|producers| {
+                    producers.block_producer == account_id
+                        || producers.chunk_producers.contains(&account_id)
+                })
+                .cloned()
+                .collect(),
+        })
+    }
+
+    /// Samples the producers of all the heights of the next epoch of the
+    /// block.
+    fn compute_validator_schedule(
+        &self,
+        header: &BlockHeader,
+        next_epoch_id: EpochId,
+    ) -> Result<NextEpochValidatorScheduleView, near_chain::Error> {
+        let epoch_id = header.epoch_id();
+        let next_epoch_info = self.epoch_manager.get_epoch_info(&next_epoch_id)?;
+        let shard_ids = self.epoch_manager.shard_ids(&next_epoch_id)?;
+        let start_height = self.epoch_manager.get_epoch_start_height(header.hash())?
+            + self.epoch_manager.get_epoch_config(epoch_id)?.epoch_length;
+        let end_height =
+            start_height + self.epoch_manager.get_epoch_config(&next_epoch_id)?.epoch_length;
+
+        let mut schedule = Vec::new();
+        for height in start_height..end_height {
+            let bp = next_epoch_info.sample_block_producer(height);
+            let block_producer = next_epoch_info.get_validator(bp).account_id().clone();
+            let chunk_producers = shard_ids
+                .iter()
+                .map(|&shard_id| {
+                    let cp = next_epoch_info.sample_chunk_producer(height, shard_id).ok_or_else(
+                        || {
+                            EpochError::ChunkProducerSelectionError(format!(
+                                "Invalid shard {shard_id} for height {height}"
+                            ))
+                        },
+                    )?;
+                    Ok(next_epoch_info.get_validator(cp).account_id().clone())
+                })
+                .collect::<Result<Vec<_>, EpochError>>()?;
+            schedule.push(HeightProducersView { height, block_producer, chunk_producers });
+        }
+        Ok(NextEpochValidatorScheduleView {
+            epoch_id: next_epoch_id,
+            epoch_height: next_epoch_info.epoch_height(),
+            start_height,
+            schedule,
+        })
+    }
+
     fn handle_query(&mut self, msg: Query) -> Result<QueryResponse, QueryError> {
         let header = self.get_block_header_by_reference(&msg.block_reference);
         let header = match header {
@@ -341,11 +458,49 @@ impl ViewClientActorInner {
             QueryRequest::ViewAccessKeyList { account_id, .. } => account_id,
             QueryRequest::CallFunction { account_id, .. } => account_id,
             QueryRequest::ViewCode { account_id, .. } => account_id,
+            QueryRequest::ViewStorageUsage { account_id, .. } => account_id,
+            QueryRequest::ViewCodeByHash { .. } => {
+                return self.query_all_shards(&header, &msg.request)
+            }
         };
         let shard_id = self
             .epoch_manager
             .account_id_to_shard_id(account_id, header.epoch_id())
             .map_err(|err| QueryError::InternalError { error_message: err.to_string() })?;
+        self.query_shard(&header, shard_id, &msg.request)
+    }
+
+    /// Queries the shards tracked by the node one after another, for the
+    /// requests which don't tell the shard of the data. Returns the first
+    /// response, or the error of the last tracked shard.
+    fn query_all_shards(
+        &self,
+        header: &BlockHeader,
+        request: &QueryRequest,
+    ) -> Result<QueryResponse, QueryError> {
+        let shard_ids = self
+            .epoch_manager
+            .shard_ids(header.epoch_id())
+            .map_err(|err| QueryError::InternalError { error_message: err.to_string() })?;
+        let mut error = None;
+        for shard_id in shard_ids {
+            match self.query_shard(header, shard_id, request) {
+                Err(err @ QueryError::UnavailableShard { .. }) => {
+                    error.get_or_insert(err);
+                }
+                Err(err @ QueryError::UnknownCodeHash { .. }) => error = Some(err),
+                result => return result,
+            }
+        }
+        Err(error.expect("Epoch without shards"))
+    }
+
+    fn query_shard(
+        &self,
+        header: &BlockHeader,
+        shard_id: ShardId,
+        request: &QueryRequest,
+    ) -> Result<QueryResponse, QueryError> {
         let shard_uid = self
             .epoch_manager
             .shard_id_to_uid(shard_id, header.epoch_id())
@@ -383,7 +538,7 @@ impl ViewClientActorInner {
             header.prev_hash(),
             header.hash(),
             header.epoch_id(),
-            &msg.request,
+            request,
         ) {
             Ok(query_response) => Ok(query_response),
             Err(query_error) => Err(match query_error {
@@ -411,6 +566,11 @@ impl ViewClientActorInner {
                     block_height,
                     block_hash,
                 } => QueryError::UnknownAccessKey { public_key, block_height, block_hash },
+                near_chain::near_chain_primitives::error::QueryError::UnknownCodeHash {
+                    code_hash,
+                    block_height,
+                    block_hash,
+                } => QueryError::UnknownCodeHash { code_hash, block_height, block_hash },
                 near_chain::near_chain_primitives::error::QueryError::ContractExecutionError {
                     error_message,
                     block_hash,
@@ -1162,6 +1322,191 @@ impl Handler<GetProtocolConfig> for ViewClientActorInner {
     }
 }
 
+impl Handler<GetCongestionInfo> for ViewClientActorInner {
+    #[perf]
+    fn handle(
+        &mut self,
+        msg: GetCongestionInfo,
+    ) -> Result<BlockCongestionInfoView, GetCongestionInfoError> {
+        tracing::debug!(target: "client", ?msg);
+        let _timer = metrics::VIEW_CLIENT_MESSAGE_TIME
+            .with_label_values(&["GetCongestionInfo"])
+            .start_timer();
+        let block = match self.get_block_by_reference(&msg.0)? {
+            None => {
+                return Err(GetCongestionInfoError::UnknownBlock("EarliestAvailable".to_string()))
+            }
+            Some(block) => block,
+        };
+        let header = block.header();
+        let runtime_config = self.runtime.get_protocol_config(header.epoch_id())?.runtime_config;
+        let mut shards = block
+            .shards_congestion_info()
+            .into_iter()
+            .map(|(shard_id, info)| ShardCongestionInfoView {
+                shard_id,
+                congestion_info: info.congestion_info.into(),
+                missed_chunks_count: info.missed_chunks_count,
+                congestion_level: CongestionControl::new(
+                    runtime_config.shard_congestion_control_config(shard_id),
+                    info.congestion_info,
+                    info.missed_chunks_count,
+                )
+                .congestion_level(),
+            })
+            .collect::<Vec<_>>();
+        shards.sort_by_key(|shard| shard.shard_id);
+        Ok(BlockCongestionInfoView {
+            block_hash: *header.hash(),
+            block_height: header.height(),
+            shards,
+        })
+    }
+}
+
+impl Handler<GetDelegateActions> for ViewClientActorInner {
+    #[perf]
+    fn handle(
+        &mut self,
+        msg: GetDelegateActions,
+    ) -> Result<BlockDelegateActionsView, GetDelegateActionsError> {
+        tracing::debug!(target: "client", ?msg);
+        let _timer = metrics::VIEW_CLIENT_MESSAGE_TIME
+            .with_label_values(&["GetDelegateActions"])
+            .start_timer();
+        let block = match self.get_block_by_reference(&msg.block_reference)? {
+            None => {
+                return Err(GetDelegateActionsError::UnknownBlock("EarliestAvailable".to_string()))
+            }
+            Some(block) => block,
+        };
+        let header = block.header();
+        let chain_store = self.chain.chain_store();
+        let mut delegate_actions = vec![];
+        let mut outcomes = chain_store
+            .get_block_execution_outcomes(header.hash())?
+            .into_iter()
+            .collect::<Vec<_>>();
+        outcomes.sort_by_key(|(shard_id, _)| *shard_id);
+        for (shard_id, outcomes) in outcomes {
+            for outcome in outcomes {
+                let outcome = outcome.outcome_with_id;
+                // Skips the outcomes of the transactions and of the receipts
+                // which aren't stored, like the local ones.
+                let Some(receipt) = chain_store.get_receipt(&outcome.id)? else {
+                    continue;
+                };
+                let ReceiptEnum::Action(action_receipt) = receipt.receipt() else {
+                    continue;
+                };
+                if msg.relayer_id.as_ref().is_some_and(|id| id != &action_receipt.signer_id) {
+                    continue;
+                }
+                for action in &action_receipt.actions {
+                    let Action::Delegate(signed_delegate_action) = action else {
+                        continue;
+                    };
+                    let delegate_action = &signed_delegate_action.delegate_action;
+                    if msg.sender_id.as_ref().is_some_and(|id| id != &delegate_action.sender_id) {
+                        continue;
+                    }
+                    delegate_actions.push(DelegateActionOutcomeView {
+                        receipt_id: outcome.id,
+                        shard_id,
+                        relayer_id: action_receipt.signer_id.clone(),
+                        sender_id: delegate_action.sender_id.clone(),
+                        receiver_id: delegate_action.receiver_id.clone(),
+                        gas_burnt: outcome.outcome.gas_burnt,
+                        prepaid_gas: delegate_action
+                            .get_actions()
+                            .iter()
+                            .map(Action::get_prepaid_gas)
+                            .sum(),
+                        tokens_burnt: outcome.outcome.tokens_burnt,
+                        status: outcome.outcome.status.clone().into(),
+                    });
+                }
+            }
+        }
+        Ok(BlockDelegateActionsView {
+            block_hash: *header.hash(),
+            block_height: header.height(),
+            delegate_actions,
+        })
+    }
+}
+
+impl Handler<SimulateTransaction> for ViewClientActorInner {
+    #[perf]
+    fn handle(
+        &mut self,
+        msg: SimulateTransaction,
+    ) -> Result<FinalExecutionOutcomeView, SimulateTransactionError> {
+        tracing::debug!(target: "client", ?msg);
+        let _timer = metrics::VIEW_CLIENT_MESSAGE_TIME
+            .with_label_values(&["SimulateTransaction"])
+            .start_timer();
+        let head = self.chain.head()?;
+        let header = self.chain.get_block_header(&head.last_block_hash)?;
+        let shard_layout =
+            self.epoch_manager.get_shard_layout(header.epoch_id()).into_chain_error()?;
+        let mut state_roots = HashMap::new();
+        for shard_uid in shard_layout.shard_uids() {
+            match self.chain.get_chunk_extra(header.hash(), &shard_uid) {
+                Ok(chunk_extra) => {
+                    state_roots.insert(shard_uid, *chunk_extra.state_root());
+                }
+                // Not tracked.
+                Err(near_chain::near_chain_primitives::Error::DBNotFoundErr(_)) => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+        let signer_id = msg.transaction.transaction.signer_id();
+        let overridden_ids =
+            msg.state_overrides.iter().map(|state_override| &state_override.account_id);
+        for account_id in std::iter::once(signer_id).chain(overridden_ids) {
+            let shard_uid = account_id_to_shard_uid(account_id, &shard_layout);
+            if !state_roots.contains_key(&shard_uid) {
+                return Err(SimulateTransactionError::UnavailableShard(shard_uid.shard_id()));
+            }
+        }
+
+        let outcomes = self
+            .runtime
+            .simulate_transaction(
+                &header,
+                &state_roots,
+                &msg.transaction,
+                msg.verify_signature,
+                &msg.state_overrides,
+            )?
+            .map_err(SimulateTransactionError::InvalidTransaction)?;
+        let mut outcomes: Vec<_> = outcomes
+            .into_iter()
+            .map(|outcome_with_id| ExecutionOutcomeWithIdView {
+                proof: vec![],
+                block_hash: CryptoHash::default(),
+                id: outcome_with_id.id,
+                outcome: outcome_with_id.outcome.into(),
+            })
+            .collect();
+        if outcomes.is_empty() {
+            return Err(SimulateTransactionError::Unreachable(
+                "simulation without the outcome of the transaction".to_string(),
+            ));
+        }
+        let status = self.chain.get_execution_status(&outcomes, &msg.transaction.get_hash());
+        let receipts_outcome = outcomes.split_off(1);
+        let transaction_outcome = outcomes.pop().unwrap();
+        Ok(FinalExecutionOutcomeView {
+            status,
+            transaction: msg.transaction.into(),
+            transaction_outcome,
+            receipts_outcome,
+        })
+    }
+}
+
 #[cfg(feature = "test_features")]
 use crate::NetworkAdversarialMessage;
 
@@ -1493,6 +1838,20 @@ impl Handler<GetMaintenanceWindows> for ViewClientActorInner {
     }
 }
 
+impl Handler<GetNextEpochValidatorSchedule> for ViewClientActorInner {
+    #[perf]
+    fn handle(
+        &mut self,
+        msg: GetNextEpochValidatorSchedule,
+    ) -> Result<NextEpochValidatorScheduleView, GetValidatorInfoError> {
+        tracing::debug!(target: "client", ?msg);
+        let _timer = metrics::VIEW_CLIENT_MESSAGE_TIME
+            .with_label_values(&["GetNextEpochValidatorSchedule"])
+            .start_timer();
+        Ok(self.get_next_epoch_validator_schedule(msg.block_id, msg.account_id)?)
+    }
+}
+
 impl Handler<GetSplitStorageInfo> for ViewClientActorInner {
     fn handle(
         &mut self,
@@ -1507,11 +1866,87 @@ impl Handler<GetSplitStorageInfo> for ViewClientActorInner {
 
         let hot_db_kind = store.get_db_kind()?.map(|kind| kind.to_string());
 
+        let final_head_height = final_head.map(|tip| tip.height);
+        let cold_head_height = cold_head.map(|tip| tip.height);
+        let cold_store_copy = self
+            .cold_store_copy_control
+            .as_ref()
+            .map(|control| control.status(cold_head_height, final_head_height, self.clock.now()));
         Ok(SplitStorageInfoView {
             head_height: head.map(|tip| tip.height),
-            final_head_height: final_head.map(|tip| tip.height),
-            cold_head_height: cold_head.map(|tip| tip.height),
+            final_head_height,
+            cold_head_height,
             hot_db_kind,
+            cold_store_copy,
         })
     }
 }
+
+impl Handler<GetAccountActivity> for ViewClientActorInner {
+    #[perf]
+    fn handle(
+        &mut self,
+        msg: GetAccountActivity,
+    ) -> Result<GetAccountActivityResponse, GetAccountActivityError> {
+        tracing::debug!(target: "client", ?msg);
+        let _timer = metrics::VIEW_CLIENT_MESSAGE_TIME
+            .with_label_values(&["GetAccountActivity"])
+            .start_timer();
+        if !self.config.save_account_index {
+            return Err(GetAccountActivityError::AccountIndexDisabled);
+        }
+        let limit =
+            msg.limit.unwrap_or(DEFAULT_ACCOUNT_ACTIVITY_LIMIT).min(MAX_ACCOUNT_ACTIVITY_LIMIT)
+                as usize;
+        // One more entry is read to tell whether there is a next page.
+        let mut entries =
+            self.chain.chain_store().get_account_index(&msg.account_id, msg.after, limit + 1)?;
+        let has_more = entries.len() > limit;
+        entries.truncate(limit);
+        Ok(GetAccountActivityResponse { entries, has_more })
+    }
+}
+
+impl Handler<GetEquivocationEvidence> for ViewClientActorInner {
+    #[perf]
+    fn handle(
+        &mut self,
+        msg: GetEquivocationEvidence,
+    ) -> Result<Vec<EquivocationEvidenceView>, GetEquivocationEvidenceError> {
+        tracing::debug!(target: "client", ?msg);
+        let _timer = metrics::VIEW_CLIENT_MESSAGE_TIME
+            .with_label_values(&["GetEquivocationEvidence"])
+            .start_timer();
+        let evidence =
+            self.chain.chain_store().get_equivocation_evidence(msg.from_height.unwrap_or(0))?;
+        Ok(evidence.into_iter().map(Into::into).collect())
+    }
+}
+
+impl Handler<ControlColdStoreCopy> for ViewClientActorInner {
+    fn handle(
+        &mut self,
+        msg: ControlColdStoreCopy,
+    ) -> Result<ColdStoreCopyStatusView, ControlColdStoreCopyError> {
+        tracing::info!(target: "client", ?msg, "Controlling the cold store loop");
+
+        let control =
+            self.cold_store_copy_control.as_ref().ok_or(ControlColdStoreCopyError::NotRunning)?;
+        match msg {
+            ControlColdStoreCopy::Pause => control.pause(),
+            ControlColdStoreCopy::Resume => control.resume(),
+            ControlColdStoreCopy::SetMaxBlocksPerSecond(max_blocks_per_second) => {
+                control.set_max_blocks_per_second(max_blocks_per_second)
+            }
+        }
+
+        let store = self.chain.chain_store().store();
+        let final_head = store.get_ser::<Tip>(DBCol::BlockMisc, FINAL_HEAD_KEY)?;
+        let cold_head = store.get_ser::<Tip>(DBCol::BlockMisc, COLD_HEAD_KEY)?;
+        Ok(control.status(
+            cold_head.map(|tip| tip.height),
+            final_head.map(|tip| tip.height),
+            self.clock.now(),
+        ))
+    }
+}
@@ -47,6 +47,9 @@ pub trait EpochManagerAdapter: Send + Sync {
     /// parts.
     fn num_total_parts(&self) -> usize;
 
+    /// Returns the id of the chain the epochs belong to.
+    fn chain_id(&self) -> String;
+
     /// How many Reed-Solomon parts are data parts.
     ///
     /// That is, fetching this many parts should be enough to reconstruct a
@@ -423,6 +426,14 @@ pub trait EpochManagerAdapter: Send + Sync {
         shard_id: ShardId,
     ) -> Result<bool, EpochError>;
 
+    /// Whether the account produces the chunks of the shard in the epoch.
+    fn cares_about_shard_in_epoch(
+        &self,
+        epoch_id: &EpochId,
+        account_id: &AccountId,
+        shard_id: ShardId,
+    ) -> Result<bool, EpochError>;
+
     fn cares_about_shard_next_epoch_from_prev_block(
         &self,
         parent_hash: &CryptoHash,
@@ -466,6 +477,11 @@ impl EpochManagerAdapter for EpochManagerHandle {
         epoch_manager.get_epoch_info(epoch_id).is_ok()
     }
 
+    fn chain_id(&self) -> String {
+        let epoch_manager = self.read();
+        epoch_manager.config.chain_id().into()
+    }
+
     fn shard_ids(&self, epoch_id: &EpochId) -> Result<Vec<ShardId>, EpochError> {
         let epoch_manager = self.read();
         Ok(epoch_manager.get_shard_layout(epoch_id)?.shard_ids().collect())
@@ -1079,6 +1095,16 @@ impl EpochManagerAdapter for EpochManagerHandle {
         epoch_manager.cares_about_shard_from_prev_block(parent_hash, account_id, shard_id)
     }
 
+    fn cares_about_shard_in_epoch(
+        &self,
+        epoch_id: &EpochId,
+        account_id: &AccountId,
+        shard_id: ShardId,
+    ) -> Result<bool, EpochError> {
+        let epoch_manager = self.read();
+        epoch_manager.cares_about_shard_in_epoch(epoch_id.clone(), account_id, shard_id)
+    }
+
     fn cares_about_shard_next_epoch_from_prev_block(
         &self,
         parent_hash: &CryptoHash,
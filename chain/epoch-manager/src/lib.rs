@@ -1,5 +1,4 @@
 use crate::metrics::{PROTOCOL_VERSION_NEXT, PROTOCOL_VERSION_VOTES};
-use crate::proposals::proposals_to_epoch_info;
 use crate::types::EpochInfoAggregator;
 use near_cache::SyncLruCache;
 use near_chain_configs::GenesisConfig;
@@ -35,6 +34,7 @@ use tracing::{debug, warn};
 use types::BlockHeaderInfo;
 
 pub use crate::adapter::EpochManagerAdapter;
+pub use crate::proposals::proposals_to_epoch_info;
 pub use crate::reward_calculator::RewardCalculator;
 pub use crate::reward_calculator::NUM_SECONDS_IN_A_YEAR;
 pub use crate::types::RngSeed;
@@ -124,6 +124,15 @@ impl EpochInfoProvider for EpochManagerHandle {
     ) -> Result<ShardId, EpochError> {
         EpochManagerAdapter::account_id_to_shard_id(self, account_id, epoch_id)
     }
+
+    fn chunk_producer(
+        &self,
+        epoch_id: &EpochId,
+        height: BlockHeight,
+        shard_id: ShardId,
+    ) -> Result<AccountId, EpochError> {
+        EpochManagerAdapter::get_chunk_producer(self, epoch_id, height, shard_id)
+    }
 }
 
 /// Tracks epoch information across different forks, such as validators.
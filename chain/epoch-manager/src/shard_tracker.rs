@@ -32,6 +32,19 @@ impl TrackedConfig {
     }
 }
 
+/// Why the node tracks a shard.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub enum ShardTrackingReason {
+    // The node's validator produces chunks of the shard.
+    ChunkProducer,
+    // The node is configured to track all the shards.
+    AllShards,
+    // The shard contains these accounts the node is configured to track.
+    TrackedAccounts(Vec<AccountId>),
+    // The shard is in the node's tracked shard schedule for the epoch.
+    Schedule,
+}
+
 // bit mask for which shard to track
 type BitMask = Vec<bool>;
 
@@ -62,6 +75,10 @@ impl ShardTracker {
         Self::new(TrackedConfig::new_empty(), epoch_manager)
     }
 
+    pub fn tracked_config(&self) -> &TrackedConfig {
+        &self.tracked_config
+    }
+
     fn tracks_shard_at_epoch(
         &self,
         shard_id: ShardId,
@@ -93,6 +110,44 @@ impl ShardTracker {
         }
     }
 
+    /// Returns why the node tracks the shard in the epoch, empty if it
+    /// doesn't.  `me` is the account of the node's validator, if any.
+    pub fn tracking_reasons(
+        &self,
+        me: Option<&AccountId>,
+        shard_id: ShardId,
+        epoch_id: &EpochId,
+    ) -> Result<Vec<ShardTrackingReason>, EpochError> {
+        let mut reasons = vec![];
+        if let Some(me) = me {
+            if self.epoch_manager.cares_about_shard_in_epoch(epoch_id, me, shard_id)? {
+                reasons.push(ShardTrackingReason::ChunkProducer);
+            }
+        }
+        match &self.tracked_config {
+            TrackedConfig::Accounts(tracked_accounts) => {
+                let shard_layout = self.epoch_manager.get_shard_layout(epoch_id)?;
+                let accounts: Vec<_> = tracked_accounts
+                    .iter()
+                    .filter(|account_id| {
+                        account_id_to_shard_id(account_id, &shard_layout) == shard_id
+                    })
+                    .cloned()
+                    .collect();
+                if !accounts.is_empty() {
+                    reasons.push(ShardTrackingReason::TrackedAccounts(accounts));
+                }
+            }
+            TrackedConfig::AllShards => reasons.push(ShardTrackingReason::AllShards),
+            TrackedConfig::Schedule(_) => {
+                if self.tracks_shard_at_epoch(shard_id, epoch_id)? {
+                    reasons.push(ShardTrackingReason::Schedule);
+                }
+            }
+        }
+        Ok(reasons)
+    }
+
     fn tracks_shard(&self, shard_id: ShardId, prev_hash: &CryptoHash) -> Result<bool, EpochError> {
         let epoch_id = self.epoch_manager.get_epoch_id_from_prev_block(prev_hash)?;
         self.tracks_shard_at_epoch(shard_id, &epoch_id)
@@ -104,7 +159,21 @@ impl ShardTracker {
         prev_hash: &CryptoHash,
     ) -> Result<bool, EpochError> {
         let epoch_id = self.epoch_manager.get_next_epoch_id_from_prev_block(prev_hash)?;
-        self.tracks_shard_at_epoch(shard_id, &epoch_id)
+        if !self.epoch_manager.will_shard_layout_change(prev_hash)? {
+            return self.tracks_shard_at_epoch(shard_id, &epoch_id);
+        }
+        // `shard_id` is in the shard layout of this epoch, so check the shards it
+        // is split into, which e.g. contain the tracked accounts in the next epoch.
+        let shard_layout = self.epoch_manager.get_shard_layout(&epoch_id)?;
+        let split_shards = shard_layout
+            .get_children_shards_ids(shard_id)
+            .expect("all shard layouts except the first one must have a split map");
+        for next_shard_id in split_shards {
+            if self.tracks_shard_at_epoch(next_shard_id, &epoch_id)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
     }
 
     /// Whether the client cares about some shard right now.
@@ -196,7 +265,7 @@ impl ShardTracker {
 
 #[cfg(test)]
 mod tests {
-    use super::{account_id_to_shard_id, ShardTracker};
+    use super::{account_id_to_shard_id, ShardTracker, ShardTrackingReason};
     use crate::shard_tracker::TrackedConfig;
     use crate::test_utils::hash_range;
     use crate::{EpochManager, EpochManagerAdapter, EpochManagerHandle, RewardCalculator};
@@ -206,7 +275,9 @@ mod tests {
     use near_primitives::hash::CryptoHash;
     use near_primitives::shard_layout::ShardLayout;
     use near_primitives::types::validator_stake::ValidatorStake;
-    use near_primitives::types::{BlockHeight, EpochId, NumShards, ProtocolVersion, ShardId};
+    use near_primitives::types::{
+        AccountId, BlockHeight, EpochId, NumShards, ProtocolVersion, ShardId,
+    };
     use near_primitives::version::ProtocolFeature::SimpleNightshade;
     use near_primitives::version::PROTOCOL_VERSION;
     use near_store::test_utils::create_test_store;
@@ -342,6 +413,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_tracking_reasons() {
+        let epoch_manager = Arc::new(get_epoch_manager(PROTOCOL_VERSION, 4, false));
+        let epoch_id = EpochId::default();
+        let shard_layout = epoch_manager.read().get_shard_layout(&epoch_id).unwrap();
+        let test1: AccountId = "test1".parse().unwrap();
+        let test1_shard_id = account_id_to_shard_id(&test1, &shard_layout);
+        let tracker =
+            ShardTracker::new(TrackedConfig::Accounts(vec![test1.clone()]), epoch_manager.clone());
+        for shard_id in 0..4 {
+            let reasons = tracker.tracking_reasons(None, shard_id, &epoch_id).unwrap();
+            if shard_id == test1_shard_id {
+                assert_eq!(
+                    reasons,
+                    vec![ShardTrackingReason::TrackedAccounts(vec![test1.clone()])]
+                );
+            } else {
+                assert_eq!(reasons, vec![]);
+            }
+            // Only the validators are chunk producers.
+            let me = "alice".parse().unwrap();
+            assert_eq!(tracker.tracking_reasons(Some(&me), shard_id, &epoch_id).unwrap(), reasons);
+        }
+        let me = "test".parse().unwrap();
+        let reasons = tracker.tracking_reasons(Some(&me), 0, &epoch_id).unwrap();
+        assert_eq!(reasons.first(), Some(&ShardTrackingReason::ChunkProducer));
+
+        let tracker = ShardTracker::new(TrackedConfig::AllShards, epoch_manager.clone());
+        assert_eq!(
+            tracker.tracking_reasons(None, 0, &epoch_id).unwrap(),
+            vec![ShardTrackingReason::AllShards]
+        );
+
+        let tracker = ShardTracker::new(TrackedConfig::Schedule(vec![vec![1]]), epoch_manager);
+        assert_eq!(tracker.tracking_reasons(None, 0, &epoch_id).unwrap(), vec![]);
+        assert_eq!(
+            tracker.tracking_reasons(None, 1, &epoch_id).unwrap(),
+            vec![ShardTrackingReason::Schedule]
+        );
+    }
+
     #[test]
     fn test_track_all_shards() {
         let shard_ids: Vec<_> = (0..4).collect();
@@ -455,13 +567,22 @@ mod tests {
             let mut total_tracked_shards = HashSet::new();
             let epoch_id = epoch_manager.get_epoch_id_from_prev_block(&h[i - 1]).unwrap();
             let shard_layout = epoch_manager.get_shard_layout(&epoch_id).unwrap();
+            let will_shard_layout_change =
+                epoch_manager.will_shard_layout_change(&h[i - 1]).unwrap();
 
             for account_id in tracked_accounts.iter() {
                 let shard_id = account_id_to_shard_id(account_id, &shard_layout);
                 total_tracked_shards.insert(shard_id);
 
+                // The shards of the next epoch are tracked as the shards of this
+                // epoch they are split from.
                 let next_shard_id = account_id_to_shard_id(account_id, &next_shard_layout);
-                total_next_tracked_shards.insert(next_shard_id);
+                if will_shard_layout_change {
+                    total_next_tracked_shards
+                        .insert(next_shard_layout.get_parent_shard_id(next_shard_id).unwrap());
+                } else {
+                    total_next_tracked_shards.insert(next_shard_id);
+                }
             }
 
             assert_eq!(
@@ -475,7 +596,7 @@ mod tests {
             assert_eq!(
                 get_all_shards_will_care_about(
                     &tracker,
-                    &next_shard_layout.shard_ids().collect::<Vec<_>>(),
+                    &shard_layout.shard_ids().collect::<Vec<_>>(),
                     &h[i - 1]
                 ),
                 total_next_tracked_shards
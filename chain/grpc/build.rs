@@ -0,0 +1,32 @@
+fn main() -> anyhow::Result<()> {
+    println!("cargo:rerun-if-changed=src/streamer.proto");
+    protobuf_codegen::Codegen::new()
+        .pure()
+        .includes(["src/"])
+        .input("src/streamer.proto")
+        .cargo_out_dir("proto")
+        .run()?;
+
+    // The service of streamer.proto. tonic's code generation from the proto
+    // files works only with prost, while the messages are generated with
+    // rust-protobuf like the ones of the network protocol.
+    let method = |name: &str, route_name: &str, output_type: &str| {
+        tonic_build::manual::Method::builder()
+            .name(name)
+            .route_name(route_name)
+            .input_type("crate::proto::StreamRequest")
+            .output_type(output_type)
+            .codec_path("crate::codec::ProtobufCodec")
+            .server_streaming()
+            .build()
+    };
+    let service = tonic_build::manual::Service::builder()
+        .name("Streamer")
+        .package("streamer")
+        .method(method("stream_blocks", "StreamBlocks", "crate::proto::Block"))
+        .method(method("stream_chunks", "StreamChunks", "crate::proto::Chunk"))
+        .method(method("stream_outcomes", "StreamOutcomes", "crate::proto::ExecutionOutcome"))
+        .build();
+    tonic_build::manual::Builder::new().build_client(false).compile(&[service]);
+    Ok(())
+}
@@ -0,0 +1,60 @@
+//! tonic codec of the messages generated by rust-protobuf.
+use bytes::{Buf, BufMut};
+use std::marker::PhantomData;
+use tonic::codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder};
+use tonic::Status;
+
+/// Encodes the messages of type `T` and decodes the ones of type `U`.
+pub(crate) struct ProtobufCodec<T, U>(PhantomData<(T, U)>);
+
+impl<T, U> Default for ProtobufCodec<T, U> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T, U> Codec for ProtobufCodec<T, U>
+where
+    T: protobuf::Message + Send + 'static,
+    U: protobuf::Message + Send + 'static,
+{
+    type Encode = T;
+    type Decode = U;
+    type Encoder = ProtobufEncoder<T>;
+    type Decoder = ProtobufDecoder<U>;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        ProtobufEncoder(PhantomData)
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        ProtobufDecoder(PhantomData)
+    }
+}
+
+pub(crate) struct ProtobufEncoder<T>(PhantomData<T>);
+
+impl<T: protobuf::Message> Encoder for ProtobufEncoder<T> {
+    type Item = T;
+    type Error = Status;
+
+    fn encode(&mut self, item: T, dst: &mut EncodeBuf<'_>) -> Result<(), Status> {
+        let bytes = item.write_to_bytes().map_err(|err| Status::internal(err.to_string()))?;
+        dst.put_slice(&bytes);
+        Ok(())
+    }
+}
+
+pub(crate) struct ProtobufDecoder<U>(PhantomData<U>);
+
+impl<U: protobuf::Message> Decoder for ProtobufDecoder<U> {
+    type Item = U;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<U>, Status> {
+        let bytes = src.copy_to_bytes(src.remaining());
+        U::parse_from_bytes(&bytes)
+            .map(Some)
+            .map_err(|err| Status::invalid_argument(err.to_string()))
+    }
+}
@@ -0,0 +1,30 @@
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct GrpcConfig {
+    pub addr: String,
+    /// Maximum number of streams served at a time. Every stream reads the
+    /// store on a thread of its own.
+    #[serde(default = "default_max_streams")]
+    pub max_streams: usize,
+    /// Maximum number of streams served at a time to a client, by its IP
+    /// address, so that a single client can't take all the streams.
+    #[serde(default = "default_max_streams_per_client")]
+    pub max_streams_per_client: usize,
+}
+
+fn default_max_streams() -> usize {
+    64
+}
+
+fn default_max_streams_per_client() -> usize {
+    4
+}
+
+impl Default for GrpcConfig {
+    fn default() -> Self {
+        Self {
+            addr: "127.0.0.1:3050".to_owned(),
+            max_streams: default_max_streams(),
+            max_streams_per_client: default_max_streams_per_client(),
+        }
+    }
+}
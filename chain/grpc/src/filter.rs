@@ -0,0 +1,92 @@
+use crate::proto;
+use near_primitives::sharding::ShardChunk;
+use near_primitives::transaction::ExecutionOutcomeWithId;
+use near_primitives::types::{AccountId, ShardId};
+use std::collections::HashSet;
+use tonic::Status;
+
+/// Server-side filter of the streams, see `StreamRequest` in streamer.proto.
+pub(crate) struct Filter {
+    shard_ids: HashSet<ShardId>,
+    account_ids: HashSet<AccountId>,
+}
+
+impl Filter {
+    pub fn new(request: &proto::StreamRequest) -> Result<Self, Status> {
+        let account_ids: HashSet<AccountId> = request
+            .account_ids
+            .iter()
+            .map(|account_id| {
+                account_id.parse().map_err(|err| {
+                    Status::invalid_argument(format!("invalid account id {account_id}: {err}"))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { shard_ids: request.shard_ids.iter().copied().collect(), account_ids })
+    }
+
+    pub fn matches_shard(&self, shard_id: ShardId) -> bool {
+        self.shard_ids.is_empty() || self.shard_ids.contains(&shard_id)
+    }
+
+    fn matches_account(&self, account_id: &AccountId) -> bool {
+        self.account_ids.contains(account_id)
+    }
+
+    /// Whether the chunk has a transaction or an outgoing receipt from or to
+    /// one of the accounts.
+    pub fn matches_chunk(&self, chunk: &ShardChunk) -> bool {
+        if self.account_ids.is_empty() {
+            return true;
+        }
+        chunk.transactions().iter().any(|tx| {
+            self.matches_account(tx.transaction.signer_id())
+                || self.matches_account(tx.transaction.receiver_id())
+        }) || chunk.prev_outgoing_receipts().iter().any(|receipt| {
+            self.matches_account(receipt.predecessor_id())
+                || self.matches_account(receipt.receiver_id())
+        })
+    }
+
+    pub fn matches_outcome(&self, outcome: &ExecutionOutcomeWithId) -> bool {
+        self.account_ids.is_empty() || self.matches_account(&outcome.outcome.executor_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_primitives::transaction::ExecutionOutcome;
+
+    fn outcome(executor_id: &str) -> ExecutionOutcomeWithId {
+        ExecutionOutcomeWithId {
+            outcome: ExecutionOutcome {
+                executor_id: executor_id.parse().unwrap(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_filter() {
+        let filter = Filter::new(&proto::StreamRequest::default()).unwrap();
+        assert!(filter.matches_shard(3));
+        assert!(filter.matches_outcome(&outcome("alice.near")));
+
+        let filter = Filter::new(&proto::StreamRequest {
+            shard_ids: vec![0, 2],
+            account_ids: vec!["alice.near".to_string()],
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(filter.matches_shard(2));
+        assert!(!filter.matches_shard(1));
+        assert!(filter.matches_outcome(&outcome("alice.near")));
+        assert!(!filter.matches_outcome(&outcome("bob.near")));
+
+        let invalid =
+            proto::StreamRequest { account_ids: vec!["Alice!".to_string()], ..Default::default() };
+        assert_eq!(Filter::new(&invalid).err().unwrap().code(), tonic::Code::InvalidArgument);
+    }
+}
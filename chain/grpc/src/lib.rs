@@ -0,0 +1,236 @@
+//! Optional gRPC server, alongside the JSON-RPC one, streaming the final
+//! blocks, the chunks and the execution outcomes to the indexers, for which a
+//! JSON-RPC request per block and shard is too slow. See streamer.proto for the
+//! service.
+use crate::filter::Filter;
+use _service::streamer_server::{Streamer, StreamerServer};
+use actix_rt::ArbiterHandle;
+use anyhow::Context as _;
+use near_chain::ChainStore;
+use near_primitives::types::BlockHeight;
+use near_store::Store;
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, Semaphore};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+
+mod codec;
+mod config;
+mod filter;
+mod streamer;
+
+mod _proto {
+    include!(concat!(env!("OUT_DIR"), "/proto/mod.rs"));
+}
+
+pub use _proto::streamer as proto;
+
+mod _service {
+    include!(concat!(env!("OUT_DIR"), "/streamer.Streamer.rs"));
+}
+
+pub use config::GrpcConfig;
+
+/// Number of the items buffered per stream for a client reading them slower
+/// than they are read from the store.
+const STREAM_BUFFER: usize = 128;
+
+type ItemStream<T> = ReceiverStream<Result<T, Status>>;
+
+/// Number of the streams served to each client, by its IP address. Bounded by
+/// the number of the streams served at a time, as the clients without streams
+/// are removed.
+struct ClientStreams {
+    max_streams_per_client: usize,
+    streams: Mutex<HashMap<IpAddr, usize>>,
+}
+
+/// Stream of a client, counted until dropped.
+struct ClientStream {
+    clients: Arc<ClientStreams>,
+    ip: IpAddr,
+}
+
+impl ClientStreams {
+    fn acquire(self: &Arc<Self>, ip: IpAddr) -> Result<ClientStream, Status> {
+        let mut streams = self.streams.lock().unwrap();
+        let num_streams = streams.entry(ip).or_default();
+        if *num_streams >= self.max_streams_per_client {
+            return Err(Status::resource_exhausted("too many streams of the client"));
+        }
+        *num_streams += 1;
+        Ok(ClientStream { clients: self.clone(), ip })
+    }
+}
+
+impl Drop for ClientStream {
+    fn drop(&mut self) {
+        let mut streams = self.clients.streams.lock().unwrap();
+        if let Some(num_streams) = streams.get_mut(&self.ip) {
+            *num_streams -= 1;
+            if *num_streams == 0 {
+                streams.remove(&self.ip);
+            }
+        }
+    }
+}
+
+struct StreamerService {
+    store: Store,
+    genesis_height: BlockHeight,
+    streams: Arc<Semaphore>,
+    clients: Arc<ClientStreams>,
+}
+
+impl StreamerService {
+    fn new(store: Store, genesis_height: BlockHeight, config: &GrpcConfig) -> Self {
+        Self {
+            store,
+            genesis_height,
+            streams: Arc::new(Semaphore::new(config.max_streams)),
+            clients: Arc::new(ClientStreams {
+                max_streams_per_client: config.max_streams_per_client,
+                streams: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+
+    fn stream<T: Send + 'static>(
+        &self,
+        request: Request<proto::StreamRequest>,
+        items: streamer::Items<T>,
+    ) -> Result<Response<ItemStream<T>>, Status> {
+        // The requests are served over TCP, which provides the address.
+        let ip = request.remote_addr().map_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED), |addr| addr.ip());
+        let request = request.into_inner();
+        let filter = Filter::new(&request)?;
+        let permit = self
+            .streams
+            .clone()
+            .try_acquire_owned()
+            .map_err(|_| Status::resource_exhausted("too many streams"))?;
+        let client_stream = self.clients.acquire(ip)?;
+        let chain_store = ChainStore::new(self.store.clone(), self.genesis_height, false);
+        let height = streamer::start_height(&chain_store, request.start_height)?;
+        let (sender, receiver) = mpsc::channel(STREAM_BUFFER);
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            let _client_stream = client_stream;
+            streamer::run(chain_store, height, filter, items, sender);
+        });
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+}
+
+#[tonic::async_trait]
+impl Streamer for StreamerService {
+    type StreamBlocksStream = ItemStream<proto::Block>;
+    type StreamChunksStream = ItemStream<proto::Chunk>;
+    type StreamOutcomesStream = ItemStream<proto::ExecutionOutcome>;
+
+    async fn stream_blocks(
+        &self,
+        request: Request<proto::StreamRequest>,
+    ) -> Result<Response<Self::StreamBlocksStream>, Status> {
+        self.stream(request, streamer::block_items)
+    }
+
+    async fn stream_chunks(
+        &self,
+        request: Request<proto::StreamRequest>,
+    ) -> Result<Response<Self::StreamChunksStream>, Status> {
+        self.stream(request, streamer::chunk_items)
+    }
+
+    async fn stream_outcomes(
+        &self,
+        request: Request<proto::StreamRequest>,
+    ) -> Result<Response<Self::StreamOutcomesStream>, Status> {
+        self.stream(request, streamer::outcome_items)
+    }
+}
+
+/// Starts the gRPC server on an arbiter of its own, reading the data from the
+/// store.
+pub fn start_grpc(
+    config: GrpcConfig,
+    store: Store,
+    genesis_height: BlockHeight,
+) -> anyhow::Result<ArbiterHandle> {
+    let listener = std::net::TcpListener::bind(&config.addr)
+        .with_context(|| format!("failed to bind the gRPC server to {}", config.addr))?;
+    listener.set_nonblocking(true)?;
+    tracing::info!(target: "grpc", addr = config.addr, "Starting the gRPC server");
+    let service = StreamerService::new(store, genesis_height, &config);
+    let arbiter = actix_rt::Arbiter::new();
+    arbiter.spawn(async move {
+        let listener = match tokio::net::TcpListener::from_std(listener) {
+            Ok(listener) => listener,
+            Err(err) => {
+                tracing::error!(target: "grpc", ?err, "Failed to start the gRPC server");
+                return;
+            }
+        };
+        let result = tonic::transport::Server::builder()
+            .add_service(StreamerServer::new(service))
+            .serve_with_incoming(tokio_stream::wrappers::TcpListenerStream::new(listener))
+            .await;
+        if let Err(err) = result {
+            tracing::error!(target: "grpc", ?err, "The gRPC server failed");
+        }
+    });
+    Ok(arbiter.handle())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_async::time::Clock;
+    use near_chain::test_utils::get_chain;
+    use std::net::SocketAddr;
+    use tokio_stream::StreamExt;
+    use tonic::transport::server::TcpConnectInfo;
+
+    fn request(remote_addr: &str) -> Request<proto::StreamRequest> {
+        let mut request = Request::new(proto::StreamRequest::default());
+        let remote_addr: SocketAddr = remote_addr.parse().unwrap();
+        request
+            .extensions_mut()
+            .insert(TcpConnectInfo { local_addr: None, remote_addr: Some(remote_addr) });
+        request
+    }
+
+    #[tokio::test]
+    async fn test_stream_final_block() {
+        let chain = get_chain(Clock::real());
+        let genesis = chain.genesis().clone();
+        let service = StreamerService::new(
+            chain.chain_store().store().clone(),
+            genesis.height(),
+            &GrpcConfig::default(),
+        );
+        let mut stream = service.stream_blocks(request("1.2.3.4:5678")).await.unwrap().into_inner();
+        let block = stream.next().await.unwrap().unwrap();
+        assert_eq!(block.height, genesis.height());
+        assert_eq!(block.hash, genesis.hash().0.to_vec());
+        assert_eq!(block.header, borsh::to_vec(&genesis).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_max_streams_per_client() {
+        let chain = get_chain(Clock::real());
+        let config = GrpcConfig { max_streams_per_client: 1, ..GrpcConfig::default() };
+        let service = StreamerService::new(
+            chain.chain_store().store().clone(),
+            chain.genesis().height(),
+            &config,
+        );
+        let _stream = service.stream_blocks(request("1.2.3.4:5678")).await.unwrap();
+        let status = service.stream_blocks(request("1.2.3.4:5679")).await.err().unwrap();
+        assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+        // The other clients are not affected by the limit.
+        let _stream = service.stream_blocks(request("5.6.7.8:5678")).await.unwrap();
+    }
+}
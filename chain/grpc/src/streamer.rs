@@ -0,0 +1,170 @@
+//! Streams of the items of the final blocks, read from the store on a
+//! blocking thread per stream.
+use crate::filter::Filter;
+use crate::proto;
+use near_chain::{ChainStore, ChainStoreAccess, Error};
+use near_primitives::block::Block;
+use near_primitives::transaction::ExecutionOutcomeWithId;
+use near_primitives::types::BlockHeight;
+use tokio::sync::mpsc;
+use tonic::Status;
+
+/// How often the final head is checked for new blocks once the stream caught
+/// up with it.
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Returns the items of the block passing the filter.
+pub(crate) type Items<T> = fn(&ChainStore, &Block, &Filter) -> Result<Vec<T>, Error>;
+
+pub(crate) fn block_items(
+    _chain_store: &ChainStore,
+    block: &Block,
+    filter: &Filter,
+) -> Result<Vec<proto::Block>, Error> {
+    let chunks = block.chunks();
+    let chunk_headers = chunks
+        .iter()
+        .filter(|chunk_header| filter.matches_shard(chunk_header.shard_id()))
+        .map(|chunk_header| borsh::to_vec(chunk_header).unwrap())
+        .collect();
+    Ok(vec![proto::Block {
+        height: block.header().height(),
+        hash: block.hash().0.to_vec(),
+        header: borsh::to_vec(block.header()).unwrap(),
+        chunk_headers,
+        ..Default::default()
+    }])
+}
+
+pub(crate) fn chunk_items(
+    chain_store: &ChainStore,
+    block: &Block,
+    filter: &Filter,
+) -> Result<Vec<proto::Chunk>, Error> {
+    let mut items = vec![];
+    for chunk_header in block.chunks().iter() {
+        if chunk_header.height_included() != block.header().height()
+            || !filter.matches_shard(chunk_header.shard_id())
+        {
+            continue;
+        }
+        let chunk = match chain_store.get_chunk(&chunk_header.chunk_hash()) {
+            Ok(chunk) => chunk,
+            // The shard is not tracked.
+            Err(Error::ChunkMissing(_)) => continue,
+            Err(err) => return Err(err),
+        };
+        if !filter.matches_chunk(&chunk) {
+            continue;
+        }
+        items.push(proto::Chunk {
+            block_height: block.header().height(),
+            block_hash: block.hash().0.to_vec(),
+            shard_id: chunk_header.shard_id(),
+            chunk: borsh::to_vec(&*chunk).unwrap(),
+            ..Default::default()
+        });
+    }
+    Ok(items)
+}
+
+pub(crate) fn outcome_items(
+    chain_store: &ChainStore,
+    block: &Block,
+    filter: &Filter,
+) -> Result<Vec<proto::ExecutionOutcome>, Error> {
+    let mut items = vec![];
+    for chunk_header in block.chunks().iter() {
+        let shard_id = chunk_header.shard_id();
+        if !filter.matches_shard(shard_id) {
+            continue;
+        }
+        for id in chain_store.get_outcomes_by_block_hash_and_shard_id(block.hash(), shard_id)? {
+            let Some(outcome) = chain_store.get_outcome_by_id_and_block_hash(&id, block.hash())?
+            else {
+                continue;
+            };
+            let outcome = ExecutionOutcomeWithId { id, outcome: outcome.outcome };
+            if !filter.matches_outcome(&outcome) {
+                continue;
+            }
+            items.push(proto::ExecutionOutcome {
+                block_height: block.header().height(),
+                block_hash: block.hash().0.to_vec(),
+                shard_id,
+                outcome: borsh::to_vec(&outcome).unwrap(),
+                ..Default::default()
+            });
+        }
+    }
+    Ok(items)
+}
+
+fn internal(err: Error) -> Status {
+    Status::internal(err.to_string())
+}
+
+/// Returns the height the stream starts at, failing if the blocks from the
+/// height have been garbage collected.
+pub(crate) fn start_height(chain_store: &ChainStore, start_height: u64) -> Result<u64, Status> {
+    if start_height == 0 {
+        return Ok(chain_store.final_head().map_err(internal)?.height);
+    }
+    let tail = chain_store.tail().map_err(internal)?;
+    if start_height < tail {
+        return Err(Status::out_of_range(format!(
+            "the blocks below height {tail} have been garbage collected"
+        )));
+    }
+    Ok(start_height)
+}
+
+fn send_items<T>(
+    chain_store: &ChainStore,
+    mut height: BlockHeight,
+    filter: &Filter,
+    items: Items<T>,
+    sender: &mpsc::Sender<Result<T, Status>>,
+) -> Result<(), Status> {
+    loop {
+        let final_height = chain_store.final_head().map_err(internal)?.height;
+        while height <= final_height {
+            match chain_store.get_block_hash_by_height(height) {
+                Ok(hash) => {
+                    let block = chain_store.get_block(&hash).map_err(internal)?;
+                    for item in items(chain_store, &block, filter).map_err(internal)? {
+                        if sender.blocking_send(Ok(item)).is_err() {
+                            return Ok(());
+                        }
+                    }
+                }
+                // No block at the height, unless the stream fell behind the
+                // garbage collection.
+                Err(Error::DBNotFoundErr(_)) => {
+                    start_height(chain_store, height)?;
+                }
+                Err(err) => return Err(internal(err)),
+            }
+            height += 1;
+        }
+        if sender.is_closed() {
+            return Ok(());
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Sends the items of the final blocks from the height on, until the receiver
+/// is dropped.
+pub(crate) fn run<T>(
+    chain_store: ChainStore,
+    height: BlockHeight,
+    filter: Filter,
+    items: Items<T>,
+    sender: mpsc::Sender<Result<T, Status>>,
+) {
+    if let Err(status) = send_items(&chain_store, height, &filter, items, &sender) {
+        tracing::warn!(target: "grpc", %status, "Stream failed");
+        let _ = sender.blocking_send(Err(status));
+    }
+}
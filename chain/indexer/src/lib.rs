@@ -1,11 +1,14 @@
 #![doc = include_str!("../README.md")]
 
+use std::collections::HashSet;
+
 use anyhow::Context;
 use tokio::sync::mpsc;
 
 use near_chain_configs::GenesisValidationMode;
 pub use near_primitives;
-use near_primitives::types::Gas;
+use near_primitives::types::{AccountId, Gas};
+use near_primitives::views;
 pub use nearcore::{get_default_home, init_configs, NearConfig};
 
 pub use near_indexer_primitives::{
@@ -14,7 +17,7 @@ pub use near_indexer_primitives::{
     StreamerMessage,
 };
 
-pub use streamer::build_streamer_message;
+pub use streamer::{build_filtered_streamer_message, build_streamer_message};
 
 mod streamer;
 
@@ -72,6 +75,137 @@ pub enum AwaitForNodeSyncedEnum {
     StreamWhileSyncing,
 }
 
+/// Enum to define which receipt execution outcomes to stream depending on their status
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ReceiptStatusFilterEnum {
+    /// Stream all receipt execution outcomes
+    #[default]
+    All,
+    /// Stream only the outcomes of successfully executed receipts
+    Success,
+    /// Stream only the outcomes of failed receipts
+    Failure,
+}
+
+/// Kinds of actions to stream transactions and receipts of
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ActionKindFilterEnum {
+    CreateAccount,
+    DeployContract,
+    FunctionCall,
+    /// Includes nonrefundable storage transfers
+    Transfer,
+    Stake,
+    AddKey,
+    DeleteKey,
+    DeleteAccount,
+    Delegate,
+}
+
+impl From<&views::ActionView> for ActionKindFilterEnum {
+    fn from(action: &views::ActionView) -> Self {
+        match action {
+            views::ActionView::CreateAccount => Self::CreateAccount,
+            views::ActionView::DeployContract { .. } => Self::DeployContract,
+            views::ActionView::FunctionCall { .. } => Self::FunctionCall,
+            views::ActionView::Transfer { .. } => Self::Transfer,
+            #[cfg(feature = "protocol_feature_nonrefundable_transfer_nep491")]
+            views::ActionView::NonrefundableStorageTransfer { .. } => Self::Transfer,
+            views::ActionView::Stake { .. } => Self::Stake,
+            views::ActionView::AddKey { .. } => Self::AddKey,
+            views::ActionView::DeleteKey { .. } => Self::DeleteKey,
+            views::ActionView::DeleteAccount { .. } => Self::DeleteAccount,
+            views::ActionView::Delegate { .. } => Self::Delegate,
+        }
+    }
+}
+
+/// Filter to stream only the data related to the given accounts, for indexers
+/// which only care about a handful of contracts.
+///
+/// Transactions, receipts, receipt execution outcomes and state changes which
+/// don't match the filter are dropped before the `StreamerMessage` is built.
+/// Blocks and chunk headers are always streamed.
+#[derive(Debug, Clone, Default)]
+pub struct IndexerFilter {
+    /// Exact account ids to stream the data of. If both `account_ids` and
+    /// `account_id_prefixes` are empty, the data of all accounts is streamed.
+    pub account_ids: HashSet<AccountId>,
+    /// Account id prefixes to stream the data of, an account matches if its id
+    /// starts with one of them.
+    pub account_id_prefixes: Vec<String>,
+    /// Which receipt execution outcomes to stream depending on their status
+    pub receipt_status: ReceiptStatusFilterEnum,
+    /// Kinds of actions to stream the transactions, receipts and receipt
+    /// execution outcomes of. Those with at least one action of the given
+    /// kinds match, data receipts never match. If empty, all of them are
+    /// streamed.
+    pub action_kinds: HashSet<ActionKindFilterEnum>,
+}
+
+impl IndexerFilter {
+    pub(crate) fn matches_account(&self, account_id: &AccountId) -> bool {
+        (self.account_ids.is_empty() && self.account_id_prefixes.is_empty())
+            || self.account_ids.contains(account_id)
+            || self.account_id_prefixes.iter().any(|prefix| account_id.as_str().starts_with(prefix))
+    }
+
+    fn matches_actions(&self, actions: &[views::ActionView]) -> bool {
+        self.action_kinds.is_empty()
+            || actions.iter().any(|action| self.action_kinds.contains(&action.into()))
+    }
+
+    pub(crate) fn matches_receipt_actions(&self, receipt: &views::ReceiptView) -> bool {
+        match &receipt.receipt {
+            views::ReceiptEnumView::Action { actions, .. } => self.matches_actions(actions),
+            views::ReceiptEnumView::Data { .. } => self.action_kinds.is_empty(),
+        }
+    }
+
+    /// A transaction matches if its signer or its receiver matches, and it
+    /// has actions of the filtered kinds.
+    pub(crate) fn matches_transaction(&self, transaction: &views::SignedTransactionView) -> bool {
+        (self.matches_account(&transaction.signer_id)
+            || self.matches_account(&transaction.receiver_id))
+            && self.matches_actions(&transaction.actions)
+    }
+
+    /// A receipt matches if its predecessor or its receiver matches, and it
+    /// has actions of the filtered kinds.
+    pub(crate) fn matches_receipt(&self, receipt: &views::ReceiptView) -> bool {
+        (self.matches_account(&receipt.predecessor_id)
+            || self.matches_account(&receipt.receiver_id))
+            && self.matches_receipt_actions(receipt)
+    }
+
+    /// Checks the execution outcome of a receipt, the same way as the receipt
+    /// itself. The receipt is missing for delayed local receipts, whose
+    /// predecessor is the executor, so it's enough to check the executor. Their
+    /// actions have to be checked with `matches_receipt_actions` once the
+    /// receipt is found.
+    pub(crate) fn matches_receipt_execution_outcome(
+        &self,
+        outcome: &views::ExecutionOutcomeWithIdView,
+        receipt: Option<&views::ReceiptView>,
+    ) -> bool {
+        let status_matches = match self.receipt_status {
+            ReceiptStatusFilterEnum::All => true,
+            ReceiptStatusFilterEnum::Success => matches!(
+                outcome.outcome.status,
+                views::ExecutionStatusView::SuccessValue(_)
+                    | views::ExecutionStatusView::SuccessReceiptId(_)
+            ),
+            ReceiptStatusFilterEnum::Failure => {
+                matches!(outcome.outcome.status, views::ExecutionStatusView::Failure(_))
+            }
+        };
+        status_matches
+            && (self.matches_account(&outcome.outcome.executor_id)
+                || receipt.is_some_and(|receipt| self.matches_account(&receipt.predecessor_id)))
+            && receipt.map_or(true, |receipt| self.matches_receipt_actions(receipt))
+    }
+}
+
 /// NEAR Indexer configuration to be provided to `Indexer::new(IndexerConfig)`
 #[derive(Debug, Clone)]
 pub struct IndexerConfig {
@@ -83,6 +217,8 @@ pub struct IndexerConfig {
     pub await_for_node_synced: AwaitForNodeSyncedEnum,
     /// Tells whether to validate the genesis file before starting
     pub validate_genesis: bool,
+    /// Filter of the data to stream, everything is streamed by default
+    pub filter: IndexerFilter,
 }
 
 /// This is the core component, which handles `nearcore` and internal `streamer`.
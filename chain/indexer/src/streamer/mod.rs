@@ -26,7 +26,7 @@ use self::fetchers::{
 use self::utils::convert_transactions_sir_into_local_receipts;
 use crate::streamer::fetchers::fetch_protocol_config;
 use crate::INDEXER;
-use crate::{AwaitForNodeSyncedEnum, IndexerConfig};
+use crate::{AwaitForNodeSyncedEnum, IndexerConfig, IndexerFilter};
 
 mod errors;
 mod fetchers;
@@ -76,6 +76,16 @@ fn test_problematic_blocks_hash() {
 pub async fn build_streamer_message(
     client: &Addr<near_client::ViewClientActor>,
     block: views::BlockView,
+) -> Result<StreamerMessage, FailedToFetchData> {
+    build_filtered_streamer_message(client, block, &IndexerFilter::default()).await
+}
+
+/// Same as `build_streamer_message`, but drops the transactions, receipts,
+/// receipt execution outcomes and state changes not matching the `filter`.
+pub async fn build_filtered_streamer_message(
+    client: &Addr<near_client::ViewClientActor>,
+    block: views::BlockView,
+    filter: &IndexerFilter,
 ) -> Result<StreamerMessage, FailedToFetchData> {
     let _timer = metrics::BUILD_STREAMER_MESSAGE_TIME.start_timer();
     let chunks = fetch_block_chunks(&client, &block).await?;
@@ -95,11 +105,16 @@ pub async fn build_streamer_message(
     )
     .await?;
     let mut indexer_shards = (0..num_shards)
-        .map(|shard_id| IndexerShard {
-            shard_id,
-            chunk: None,
-            receipt_execution_outcomes: vec![],
-            state_changes: state_changes.remove(&shard_id).unwrap_or_default(),
+        .map(|shard_id| {
+            let mut shard_state_changes = state_changes.remove(&shard_id).unwrap_or_default();
+            shard_state_changes
+                .retain(|change| filter.matches_account(change.value.affected_account_id()));
+            IndexerShard {
+                shard_id,
+                chunk: None,
+                receipt_execution_outcomes: vec![],
+                state_changes: shard_state_changes,
+            }
         })
         .collect::<Vec<_>>();
 
@@ -116,7 +131,7 @@ pub async fn build_streamer_message(
         // Take execution outcomes for receipts from the vec and keep only the ones for transactions
         let mut receipt_outcomes = outcomes.split_off(transactions.len());
 
-        let indexer_transactions = transactions
+        let mut indexer_transactions = transactions
             .into_iter()
             .zip(outcomes.into_iter())
             .map(|(transaction, outcome)| {
@@ -128,6 +143,9 @@ pub async fn build_streamer_message(
             })
             .collect::<Vec<IndexerTransactionWithOutcome>>();
 
+        // The local receipts of all transactions are needed, not only of the
+        // ones matching the filter, because the outcomes of their receipts may
+        // match it.
         let chunk_local_receipts = convert_transactions_sir_into_local_receipts(
             &client,
             &runtime_config,
@@ -139,29 +157,30 @@ pub async fn build_streamer_message(
             protocol_config_view.protocol_version,
         )
         .await?;
+        indexer_transactions
+            .retain(|transaction| filter.matches_transaction(&transaction.transaction));
 
-        // Add local receipts to corresponding outcomes
-        for receipt in &chunk_local_receipts {
-            if let Some(outcome) = receipt_outcomes
-                .iter_mut()
-                .find(|outcome| outcome.execution_outcome.id == receipt.receipt_id)
-            {
-                debug_assert!(outcome.receipt.is_none());
-                outcome.receipt = Some(receipt.clone());
-            } else {
-                if let Ok(mut cache) = DELAYED_LOCAL_RECEIPTS_CACHE.write() {
-                    cache.insert(receipt.receipt_id, receipt.clone());
-                } else {
-                    tracing::warn!(
-                        target: INDEXER,
-                        "Unable to insert receipt {} into DELAYED_LOCAL_RECEIPTS_CACHE",
-                        receipt.receipt_id,
-                    );
-                }
+        let receipt_outcomes = match DELAYED_LOCAL_RECEIPTS_CACHE.write() {
+            Ok(mut cache) => {
+                match_local_receipts(&mut receipt_outcomes, &chunk_local_receipts, &mut cache);
+                take_cached_receipts(receipt_outcomes, filter, &mut cache)
             }
-        }
+            Err(_) => {
+                // Failed to acquire lock, log this event and fetch the missing receipts
+                tracing::warn!(
+                    target: INDEXER,
+                    "Failed to acquire DELAYED_LOCAL_RECEIPTS_CACHE lock, the missing receipts are looked for in up to 1000 blocks back in time",
+                );
+                let mut cache = HashMap::new();
+                match_local_receipts(&mut receipt_outcomes, &chunk_local_receipts, &mut cache);
+                take_cached_receipts(receipt_outcomes, filter, &mut cache)
+            }
+        };
 
-        let mut chunk_receipts = chunk_local_receipts;
+        let mut chunk_receipts = chunk_local_receipts
+            .into_iter()
+            .filter(|receipt| filter.matches_receipt(receipt))
+            .collect::<Vec<views::ReceiptView>>();
 
         let mut receipt_execution_outcomes: Vec<IndexerExecutionOutcomeWithReceipt> = vec![];
         for outcome in receipt_outcomes {
@@ -169,46 +188,24 @@ pub async fn build_streamer_message(
             let receipt = if let Some(receipt) = receipt {
                 receipt
             } else {
-                // Attempt to extract the receipt or decide to fetch it based on cache access success
-                let maybe_receipt = {
-                    match DELAYED_LOCAL_RECEIPTS_CACHE.write() {
-                        Ok(mut cache) => {
-                            // Lock acquired, attempt to remove the receipt
-                            cache.remove(&execution_outcome.id)
-                        }
-                        Err(_) => {
-                            // Failed to acquire lock, log this event and decide to fetch the receipt
-                            tracing::warn!(
-                                target: INDEXER,
-                                "Failed to acquire DELAYED_LOCAL_RECEIPTS_CACHE lock, starting to look for receipt {} in up to 1000 blocks back in time",
-                                execution_outcome.id,
-                            );
-                            None // Indicate that receipt needs to be fetched
-                        }
-                    }
-                };
-
-                // Depending on whether you got the receipt from the cache, proceed
-                if let Some(receipt) = maybe_receipt {
-                    // Receipt was found in cache
-                    receipt
-                } else {
-                    // Receipt not found in cache or failed to acquire lock, proceed to look it up
-                    // in the history of blocks (up to 1000 blocks back)
-                    tracing::warn!(
-                        target: INDEXER,
-                        "Receipt {} is missing in block and in DELAYED_LOCAL_RECEIPTS_CACHE, looking for it in up to 1000 blocks back in time",
-                        execution_outcome.id,
-                    );
-                    lookup_delayed_local_receipt_in_previous_blocks(
-                        &client,
-                        &runtime_config,
-                        block.clone(),
-                        execution_outcome.id,
-                    )
-                    .await?
-                }
+                // Receipt not found in cache, look it up in the history of
+                // blocks (up to 1000 blocks back)
+                tracing::warn!(
+                    target: INDEXER,
+                    "Receipt {} is missing in block and in DELAYED_LOCAL_RECEIPTS_CACHE, looking for it in up to 1000 blocks back in time",
+                    execution_outcome.id,
+                );
+                lookup_delayed_local_receipt_in_previous_blocks(
+                    &client,
+                    &runtime_config,
+                    block.clone(),
+                    execution_outcome.id,
+                )
+                .await?
             };
+            if !filter.matches_receipt_execution_outcome(&execution_outcome, Some(&receipt)) {
+                continue;
+            }
             receipt_execution_outcomes
                 .push(IndexerExecutionOutcomeWithReceipt { execution_outcome, receipt });
         }
@@ -236,7 +233,9 @@ pub async fn build_streamer_message(
             chunk_receipts.extend(restored_receipts);
         }
 
-        chunk_receipts.extend(chunk_non_local_receipts);
+        chunk_receipts.extend(
+            chunk_non_local_receipts.into_iter().filter(|receipt| filter.matches_receipt(receipt)),
+        );
 
         indexer_shards[shard_id].receipt_execution_outcomes = receipt_execution_outcomes;
         // Put the chunk into corresponding indexer shard
@@ -253,16 +252,73 @@ pub async fn build_streamer_message(
     // That might happen before the fix https://github.com/near/nearcore/pull/4228
     for (shard_id, outcomes) in shards_outcomes {
         indexer_shards[shard_id as usize].receipt_execution_outcomes.extend(
-            outcomes.into_iter().map(|outcome| IndexerExecutionOutcomeWithReceipt {
-                execution_outcome: outcome.execution_outcome,
-                receipt: outcome.receipt.expect("`receipt` must be present at this moment"),
-            }),
+            outcomes
+                .into_iter()
+                .filter(|outcome| {
+                    filter.matches_receipt_execution_outcome(
+                        &outcome.execution_outcome,
+                        outcome.receipt.as_ref(),
+                    )
+                })
+                .map(|outcome| IndexerExecutionOutcomeWithReceipt {
+                    execution_outcome: outcome.execution_outcome,
+                    receipt: outcome.receipt.expect("`receipt` must be present at this moment"),
+                }),
         )
     }
 
     Ok(StreamerMessage { block, shards: indexer_shards })
 }
 
+/// Attaches the local receipts of a chunk to their execution outcomes. The
+/// receipts whose outcomes are not in the chunk are delayed, they are cached
+/// until their outcomes appear.
+fn match_local_receipts(
+    receipt_outcomes: &mut [IndexerExecutionOutcomeWithOptionalReceipt],
+    local_receipts: &[views::ReceiptView],
+    cache: &mut HashMap<CryptoHash, views::ReceiptView>,
+) {
+    for receipt in local_receipts {
+        if let Some(outcome) = receipt_outcomes
+            .iter_mut()
+            .find(|outcome| outcome.execution_outcome.id == receipt.receipt_id)
+        {
+            debug_assert!(outcome.receipt.is_none());
+            outcome.receipt = Some(receipt.clone());
+        } else {
+            cache.insert(receipt.receipt_id, receipt.clone());
+        }
+    }
+}
+
+/// Takes the receipts missing in the outcomes, which are delayed local
+/// receipts, from the cache.
+///
+/// The predecessor of a delayed local receipt is its executor, so the outcomes
+/// whose receipts are missing can be filtered before their receipt is looked
+/// up. Their receipts are evicted from the cache. The receipts which are
+/// neither in the outcome nor in the cache stay missing and have to be looked
+/// up in the previous blocks.
+fn take_cached_receipts(
+    receipt_outcomes: Vec<IndexerExecutionOutcomeWithOptionalReceipt>,
+    filter: &IndexerFilter,
+    cache: &mut HashMap<CryptoHash, views::ReceiptView>,
+) -> Vec<IndexerExecutionOutcomeWithOptionalReceipt> {
+    receipt_outcomes
+        .into_iter()
+        .filter_map(|mut outcome| {
+            if outcome.receipt.is_none() {
+                let cached_receipt = cache.remove(&outcome.execution_outcome.id);
+                if !filter.matches_receipt_execution_outcome(&outcome.execution_outcome, None) {
+                    return None;
+                }
+                outcome.receipt = cached_receipt;
+            }
+            Some(outcome)
+        })
+        .collect()
+}
+
 // Receipt might be missing only in case of delayed local receipt
 // that appeared in some of the previous blocks
 // we will be iterating over previous blocks until we found the receipt
@@ -434,7 +490,9 @@ pub(crate) async fn start(
         for block_height in start_syncing_block_height..=latest_block_height {
             metrics::CURRENT_BLOCK_HEIGHT.set(block_height as i64);
             if let Ok(block) = fetch_block_by_height(&view_client, block_height).await {
-                let response = build_streamer_message(&view_client, block).await;
+                let response =
+                    build_filtered_streamer_message(&view_client, block, &indexer_config.filter)
+                        .await;
 
                 match response {
                     Ok(streamer_message) => {
@@ -463,3 +521,100 @@ pub(crate) async fn start(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_crypto::{KeyType, PublicKey};
+    use near_primitives::hash::hash;
+    use near_primitives::types::AccountId;
+
+    fn receipt(id: &[u8], account_id: &str) -> views::ReceiptView {
+        let account_id: AccountId = account_id.parse().unwrap();
+        views::ReceiptView {
+            predecessor_id: account_id.clone(),
+            receiver_id: account_id.clone(),
+            receipt_id: hash(id),
+            receipt: views::ReceiptEnumView::Action {
+                signer_id: account_id,
+                signer_public_key: PublicKey::empty(KeyType::ED25519),
+                gas_price: 0,
+                output_data_receivers: vec![],
+                input_data_ids: vec![],
+                actions: vec![views::ActionView::Transfer { deposit: 1 }],
+                is_promise_yield: false,
+            },
+            priority: 0,
+        }
+    }
+
+    fn outcome(id: &[u8], executor_id: &str) -> IndexerExecutionOutcomeWithOptionalReceipt {
+        IndexerExecutionOutcomeWithOptionalReceipt {
+            execution_outcome: views::ExecutionOutcomeWithIdView {
+                proof: vec![],
+                block_hash: CryptoHash::default(),
+                id: hash(id),
+                outcome: views::ExecutionOutcomeView {
+                    logs: vec![],
+                    receipt_ids: vec![],
+                    gas_burnt: 0,
+                    tokens_burnt: 0,
+                    executor_id: executor_id.parse().unwrap(),
+                    status: views::ExecutionStatusView::SuccessValue(vec![]),
+                    metadata: Default::default(),
+                },
+            },
+            receipt: None,
+        }
+    }
+
+    #[test]
+    fn test_match_local_receipts() {
+        let mut outcomes = vec![outcome(b"a", "alice.near")];
+        let mut cache = HashMap::new();
+        match_local_receipts(
+            &mut outcomes,
+            &[receipt(b"a", "alice.near"), receipt(b"b", "bob.near")],
+            &mut cache,
+        );
+        assert_eq!(outcomes[0].receipt, Some(receipt(b"a", "alice.near")));
+        // The receipt executed in a later block is kept until its outcome appears.
+        assert_eq!(cache.keys().collect::<Vec<_>>(), vec![&hash(b"b")]);
+    }
+
+    /// The delayed local receipts of filtered outcomes must not stay in the
+    /// cache forever.
+    #[test]
+    fn test_take_cached_receipts_evicts_filtered() {
+        let filter = IndexerFilter {
+            account_ids: ["alice.near".parse().unwrap()].into(),
+            ..Default::default()
+        };
+        let mut cache = HashMap::from([
+            (hash(b"a"), receipt(b"a", "alice.near")),
+            (hash(b"b"), receipt(b"b", "bob.near")),
+        ]);
+        let outcomes = take_cached_receipts(
+            vec![outcome(b"a", "alice.near"), outcome(b"b", "bob.near")],
+            &filter,
+            &mut cache,
+        );
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(outcomes[0].receipt, Some(receipt(b"a", "alice.near")));
+        assert!(cache.is_empty());
+    }
+
+    /// A receipt which is not cached, for example because the indexer was
+    /// restarted, is left to be looked up in the previous blocks.
+    #[test]
+    fn test_take_cached_receipts_missing() {
+        let mut cache = HashMap::new();
+        let outcomes = take_cached_receipts(
+            vec![outcome(b"a", "alice.near")],
+            &IndexerFilter::default(),
+            &mut cache,
+        );
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(outcomes[0].receipt, None);
+    }
+}
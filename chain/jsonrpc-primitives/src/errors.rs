@@ -28,6 +28,17 @@ pub enum RpcErrorKind {
     RequestValidationError(RpcRequestValidationErrorKind),
     HandlerError(Value),
     InternalError(Value),
+    /// The client called the method too often and has to wait before calling
+    /// it again.
+    RateLimitError {
+        method_name: String,
+        retry_after_ms: u64,
+    },
+    /// The method can be called only with a valid token.
+    AuthenticationError {
+        method_name: String,
+        error_message: String,
+    },
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone, PartialEq)]
@@ -35,6 +46,7 @@ pub enum RpcErrorKind {
 pub enum RpcRequestValidationErrorKind {
     MethodNotFound { method_name: String },
     ParseError { error_message: String },
+    MethodNotAllowedForOrigin { method_name: String, origin: String },
 }
 
 /// A general Server Error
@@ -137,6 +149,49 @@ impl RpcError {
         }
     }
 
+    /// Create an error for a call of the method above its rate limit.
+    pub fn rate_limited(method: String, retry_after: std::time::Duration) -> Self {
+        RpcError {
+            code: -32_000,
+            message: "Rate limit exceeded".to_owned(),
+            data: Some(Value::String(method.clone())),
+            error_struct: Some(RpcErrorKind::RateLimitError {
+                method_name: method,
+                retry_after_ms: retry_after.as_millis().try_into().unwrap_or(u64::MAX),
+            }),
+        }
+    }
+
+    /// Create an error for a call of a method requiring authentication
+    /// without a valid token.
+    pub fn unauthorized(method: String, error_message: String) -> Self {
+        RpcError {
+            code: -32_000,
+            message: "Unauthorized".to_owned(),
+            data: Some(Value::String(error_message.clone())),
+            error_struct: Some(RpcErrorKind::AuthenticationError {
+                method_name: method,
+                error_message,
+            }),
+        }
+    }
+
+    /// Create an error for a call of a method which the origin of the
+    /// request isn't allowed to call.
+    pub fn method_not_allowed_for_origin(method: String, origin: String) -> Self {
+        RpcError {
+            code: -32_601,
+            message: "Method not allowed for origin".to_owned(),
+            data: Some(Value::String(method.clone())),
+            error_struct: Some(RpcErrorKind::RequestValidationError(
+                RpcRequestValidationErrorKind::MethodNotAllowedForOrigin {
+                    method_name: method,
+                    origin,
+                },
+            )),
+        }
+    }
+
     /// Create a method not found error.
     pub fn method_not_found(method: String) -> Self {
         RpcError {
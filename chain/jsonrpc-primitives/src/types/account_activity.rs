@@ -0,0 +1,62 @@
+use near_primitives::hash::CryptoHash;
+use near_primitives::types::{AccountId, BlockHeight};
+use near_primitives::views::AccountIndexEntryView;
+use serde_json::Value;
+
+/// Position in the entries of the account, after the entry with the height and
+/// id.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AccountActivityCursor {
+    pub block_height: BlockHeight,
+    pub id: CryptoHash,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcAccountActivityRequest {
+    pub account_id: AccountId,
+    /// Returns only the entries after the cursor, the `next_cursor` of the
+    /// previous page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<AccountActivityCursor>,
+    /// Maximum number of the entries returned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcAccountActivityResponse {
+    pub entries: Vec<AccountIndexEntryView>,
+    /// Cursor of the next page, none if there are no more entries.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<AccountActivityCursor>,
+}
+
+#[derive(thiserror::Error, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "name", content = "info", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RpcAccountActivityError {
+    #[error("The account index is not enabled on this node")]
+    AccountIndexDisabled,
+    #[error("The node reached its limits. Try again later. More details: {error_message}")]
+    InternalError { error_message: String },
+}
+
+impl From<RpcAccountActivityError> for crate::errors::RpcError {
+    fn from(error: RpcAccountActivityError) -> Self {
+        let error_data = match &error {
+            RpcAccountActivityError::AccountIndexDisabled => Some(Value::String(error.to_string())),
+            RpcAccountActivityError::InternalError { .. } => Some(Value::String(error.to_string())),
+        };
+
+        let error_data_value = match serde_json::to_value(error) {
+            Ok(value) => value,
+            Err(err) => {
+                return Self::new_internal_error(
+                    None,
+                    format!("Failed to serialize RpcAccountActivityError: {:?}", err),
+                )
+            }
+        };
+
+        Self::new_internal_or_handler_error(error_data, error_data_value)
+    }
+}
@@ -1,3 +1,5 @@
+use near_parameters::parameter_table::ParameterChange;
+use near_primitives::types::ProtocolVersion;
 use serde_json::Value;
 
 #[derive(serde::Serialize, serde::Deserialize, Debug)]
@@ -46,3 +48,48 @@ impl From<RpcProtocolConfigError> for crate::errors::RpcError {
         Self::new_internal_or_handler_error(error_data, error_data_value)
     }
 }
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcProtocolConfigDiffRequest {
+    pub from_protocol_version: ProtocolVersion,
+    pub to_protocol_version: ProtocolVersion,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcProtocolConfigDiffResponse {
+    pub from_protocol_version: ProtocolVersion,
+    pub to_protocol_version: ProtocolVersion,
+    /// Runtime parameters with different values at the two versions, with
+    /// their values at `from_protocol_version` as `old`.
+    pub parameters: Vec<ParameterChange>,
+    /// Protocol features enabled by upgrading from the lower of the two
+    /// versions to the higher one.
+    pub features: Vec<String>,
+}
+
+#[derive(thiserror::Error, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "name", content = "info", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RpcProtocolConfigDiffError {
+    #[error("Protocol version {protocol_version} is not supported by the node, the latest supported one is {latest_protocol_version}")]
+    UnsupportedProtocolVersion {
+        protocol_version: ProtocolVersion,
+        latest_protocol_version: ProtocolVersion,
+    },
+}
+
+impl From<RpcProtocolConfigDiffError> for crate::errors::RpcError {
+    fn from(error: RpcProtocolConfigDiffError) -> Self {
+        let error_data = Some(Value::String(error.to_string()));
+        let error_data_value = match serde_json::to_value(error) {
+            Ok(value) => value,
+            Err(err) => {
+                return Self::new_internal_error(
+                    None,
+                    format!("Failed to serialize RpcProtocolConfigDiffError: {:?}", err),
+                )
+            }
+        };
+
+        Self::new_internal_or_handler_error(error_data, error_data_value)
+    }
+}
@@ -0,0 +1,48 @@
+use serde_json::Value;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcCongestionInfoRequest {
+    #[serde(flatten)]
+    pub block_reference: near_primitives::types::BlockReference,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcCongestionInfoResponse {
+    #[serde(flatten)]
+    pub congestion_info: near_primitives::views::BlockCongestionInfoView,
+}
+
+#[derive(thiserror::Error, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "name", content = "info", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RpcCongestionInfoError {
+    #[error("Block has never been observed: {error_message}")]
+    UnknownBlock {
+        #[serde(skip_serializing)]
+        error_message: String,
+    },
+    #[error("The node reached its limits. Try again later. More details: {error_message}")]
+    InternalError { error_message: String },
+}
+
+impl From<RpcCongestionInfoError> for crate::errors::RpcError {
+    fn from(error: RpcCongestionInfoError) -> Self {
+        let error_data = match &error {
+            RpcCongestionInfoError::UnknownBlock { error_message } => {
+                Some(Value::String(format!("Block Not Found: {}", error_message)))
+            }
+            RpcCongestionInfoError::InternalError { .. } => Some(Value::String(error.to_string())),
+        };
+
+        let error_data_value = match serde_json::to_value(error) {
+            Ok(value) => value,
+            Err(err) => {
+                return Self::new_internal_error(
+                    None,
+                    format!("Failed to serialize RpcCongestionInfoError: {:?}", err),
+                )
+            }
+        };
+
+        Self::new_internal_or_handler_error(error_data, error_data_value)
+    }
+}
@@ -0,0 +1,55 @@
+use near_primitives::types::AccountId;
+use serde_json::Value;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcDelegateActionsRequest {
+    #[serde(flatten)]
+    pub block_reference: near_primitives::types::BlockReference,
+    /// Returns only the delegate actions relayed by the account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relayer_id: Option<AccountId>,
+    /// Returns only the delegate actions signed by the account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sender_id: Option<AccountId>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcDelegateActionsResponse {
+    #[serde(flatten)]
+    pub delegate_actions: near_primitives::views::BlockDelegateActionsView,
+}
+
+#[derive(thiserror::Error, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "name", content = "info", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RpcDelegateActionsError {
+    #[error("Block has never been observed: {error_message}")]
+    UnknownBlock {
+        #[serde(skip_serializing)]
+        error_message: String,
+    },
+    #[error("The node reached its limits. Try again later. More details: {error_message}")]
+    InternalError { error_message: String },
+}
+
+impl From<RpcDelegateActionsError> for crate::errors::RpcError {
+    fn from(error: RpcDelegateActionsError) -> Self {
+        let error_data = match &error {
+            RpcDelegateActionsError::UnknownBlock { error_message } => {
+                Some(Value::String(format!("Block Not Found: {}", error_message)))
+            }
+            RpcDelegateActionsError::InternalError { .. } => Some(Value::String(error.to_string())),
+        };
+
+        let error_data_value = match serde_json::to_value(error) {
+            Ok(value) => value,
+            Err(err) => {
+                return Self::new_internal_error(
+                    None,
+                    format!("Failed to serialize RpcDelegateActionsError: {:?}", err),
+                )
+            }
+        };
+
+        Self::new_internal_or_handler_error(error_data, error_data_value)
+    }
+}
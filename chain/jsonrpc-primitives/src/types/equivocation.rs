@@ -0,0 +1,44 @@
+use near_primitives::types::BlockHeight;
+use near_primitives::views::EquivocationEvidenceView;
+use serde_json::Value;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcEquivocationEvidenceRequest {
+    /// Returns only the evidence from the height on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_height: Option<BlockHeight>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcEquivocationEvidenceResponse {
+    pub evidence: Vec<EquivocationEvidenceView>,
+}
+
+#[derive(thiserror::Error, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "name", content = "info", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RpcEquivocationEvidenceError {
+    #[error("The node reached its limits. Try again later. More details: {error_message}")]
+    InternalError { error_message: String },
+}
+
+impl From<RpcEquivocationEvidenceError> for crate::errors::RpcError {
+    fn from(error: RpcEquivocationEvidenceError) -> Self {
+        let error_data = match &error {
+            RpcEquivocationEvidenceError::InternalError { .. } => {
+                Some(Value::String(error.to_string()))
+            }
+        };
+
+        let error_data_value = match serde_json::to_value(error) {
+            Ok(value) => value,
+            Err(err) => {
+                return Self::new_internal_error(
+                    None,
+                    format!("Failed to serialize RpcEquivocationEvidenceError: {:?}", err),
+                )
+            }
+        };
+
+        Self::new_internal_or_handler_error(error_data, error_data_value)
+    }
+}
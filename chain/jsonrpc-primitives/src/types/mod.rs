@@ -1,9 +1,13 @@
+pub mod account_activity;
 pub mod blocks;
 pub mod changes;
 pub mod chunks;
 pub mod client_config;
 pub mod config;
+pub mod congestion;
+pub mod delegate_actions;
 pub mod entity_debug;
+pub mod equivocation;
 pub mod gas_price;
 pub mod light_client;
 pub mod maintenance;
@@ -11,6 +15,7 @@ pub mod network_info;
 pub mod query;
 pub mod receipts;
 pub mod sandbox;
+pub mod simulation;
 pub mod split_storage;
 pub mod status;
 pub mod transactions;
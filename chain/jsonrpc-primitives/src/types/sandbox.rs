@@ -1,9 +1,13 @@
+use near_primitives::sandbox::AccessKeyPatch;
 use near_primitives::state_record::StateRecord;
-use near_primitives::types::BlockHeightDelta;
+use near_primitives::types::{BlockHeightDelta, ShardId};
 
 #[derive(serde::Deserialize, serde::Serialize, Debug)]
 pub struct RpcSandboxPatchStateRequest {
     pub records: Vec<StateRecord>,
+    /// Removals of access keys and nonce overrides, applied after `records`.
+    #[serde(default)]
+    pub access_key_patches: Vec<AccessKeyPatch>,
 }
 
 #[derive(serde::Deserialize, serde::Serialize, Debug)]
@@ -34,6 +38,13 @@ impl From<RpcSandboxPatchStateError> for crate::errors::RpcError {
 #[derive(serde::Deserialize, serde::Serialize)]
 pub struct RpcSandboxFastForwardRequest {
     pub delta_height: BlockHeightDelta,
+    /// Shards which produce no chunks while fast forwarding, simulating missing chunks.
+    #[serde(default)]
+    pub skip_chunks_shard_ids: Vec<ShardId>,
+    /// Number of blocks after the fast forward for which `skip_chunks_shard_ids` keep
+    /// missing their chunks.
+    #[serde(default)]
+    pub skip_chunks_delta_height: BlockHeightDelta,
 }
 
 #[derive(serde::Deserialize, serde::Serialize)]
@@ -0,0 +1,61 @@
+use near_primitives::types::ShardId;
+use serde_json::Value;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcSimulateTransactionRequest {
+    #[serde(rename = "signed_tx_base64")]
+    pub signed_transaction: near_primitives::transaction::SignedTransaction,
+    /// Skips the verification of the signature, to simulate a transaction
+    /// which isn't signed yet.
+    #[serde(default)]
+    pub skip_signature_verification: bool,
+    /// Changes of the state applied before the simulation.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub state_overrides: Vec<near_primitives::views::AccountStateOverride>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcSimulateTransactionResponse {
+    #[serde(flatten)]
+    pub final_execution_outcome: near_primitives::views::FinalExecutionOutcomeView,
+}
+
+#[derive(thiserror::Error, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "name", content = "info", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RpcSimulateTransactionError {
+    #[error("An error happened during transaction execution: {context:?}")]
+    InvalidTransaction {
+        #[serde(skip_serializing)]
+        context: near_primitives::errors::InvalidTxError,
+    },
+    #[error("Shard {requested_shard_id} is not tracked by the node")]
+    UnavailableShard { requested_shard_id: ShardId },
+    #[error("The node reached its limits. Try again later. More details: {error_message}")]
+    InternalError { error_message: String },
+}
+
+impl From<RpcSimulateTransactionError> for crate::errors::RpcError {
+    fn from(error: RpcSimulateTransactionError) -> Self {
+        let error_data = match &error {
+            RpcSimulateTransactionError::InvalidTransaction { context } => {
+                serde_json::to_value(crate::errors::ServerError::TxExecutionError(
+                    near_primitives::errors::TxExecutionError::InvalidTxError(context.clone()),
+                ))
+                .unwrap_or_else(|_| Value::String(error.to_string()))
+            }
+            _ => Value::String(error.to_string()),
+        };
+
+        let error_data_value = match serde_json::to_value(error) {
+            Ok(value) => value,
+            Err(err) => {
+                return Self::new_internal_error(
+                    None,
+                    format!("Failed to serialize RpcSimulateTransactionError: {:?}", err),
+                )
+            }
+        };
+
+        Self::new_internal_or_handler_error(Some(error_data), error_data_value)
+    }
+}
@@ -1,4 +1,4 @@
-use near_primitives::views::SplitStorageInfoView;
+use near_primitives::views::{ColdStoreCopyStatusView, SplitStorageInfoView};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -54,3 +54,58 @@ impl RpcSplitStorageInfoError {
         }
     }
 }
+
+/// Pauses, resumes or limits the rate of copying blocks to the cold store.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum RpcColdStoreCopyControlRequest {
+    Pause,
+    Resume,
+    /// Limits the number of blocks copied per second, null removes the limit.
+    SetRateLimit {
+        max_blocks_per_second: Option<u64>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RpcColdStoreCopyControlResponse {
+    #[serde(flatten)]
+    pub result: ColdStoreCopyStatusView,
+}
+
+#[derive(thiserror::Error, Debug, Serialize, Deserialize)]
+#[serde(tag = "name", content = "info", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RpcColdStoreCopyControlError {
+    #[error("Controlling the cold store loop requires `enable_debug_rpc` in the RPC config")]
+    DebugRpcDisabled,
+    #[error("The cold store loop is not running on this node")]
+    ColdStoreLoopNotRunning,
+    #[error("The node reached its limits. Try again later. More details: {error_message}")]
+    InternalError { error_message: String },
+}
+
+impl From<RpcColdStoreCopyControlError> for crate::errors::RpcError {
+    fn from(error: RpcColdStoreCopyControlError) -> Self {
+        let error_data = match &error {
+            RpcColdStoreCopyControlError::DebugRpcDisabled
+            | RpcColdStoreCopyControlError::ColdStoreLoopNotRunning => {
+                Some(Value::String(error.to_string()))
+            }
+            RpcColdStoreCopyControlError::InternalError { .. } => {
+                Some(Value::String(error.to_string()))
+            }
+        };
+
+        let error_data_value = match serde_json::to_value(error) {
+            Ok(value) => value,
+            Err(err) => {
+                return Self::new_internal_error(
+                    None,
+                    format!("Failed to serialize RpcColdStoreCopyControlError: {:?}", err),
+                )
+            }
+        };
+
+        Self::new_internal_or_handler_error(error_data, error_data_value)
+    }
+}
@@ -1,6 +1,8 @@
 #[cfg(feature = "debug_types")]
 use near_client_primitives::debug::{
-    DebugBlockStatusData, EpochInfoView, TrackedShardsView, ValidatorStatus,
+    CongestionAtHeightView, DebugBlockStatusData, EpochInfoView, PartialWitnessView,
+    SentChunkEndorsementView, ShardTxPoolView, ShardsView, StoreStatsView, TrackedShardsView,
+    TxRoutingView, ValidatorStatus,
 };
 #[cfg(feature = "debug_types")]
 use near_primitives::views::{
@@ -36,6 +38,19 @@ pub enum DebugStatusResponse {
     Routes(NetworkRoutesView),
     SnapshotHosts(SnapshotHostsView),
     SplitStoreStatus(SplitStorageInfoView),
+    // Congestion info of all shards, in descending order of block height.
+    Congestion(Vec<CongestionAtHeightView>),
+    // Contents of the transaction pool of every shard.
+    TxPool(Vec<ShardTxPoolView>),
+    // Chunk endorsements sent by this node, in descending order of height.
+    ChunkEndorsements(Vec<SentChunkEndorsementView>),
+    TxRouting(TxRoutingView),
+    // RocksDB statistics of the database and its columns.
+    StoreStats(StoreStatsView),
+    // State witnesses recently received by this node, in descending order of height.
+    PartialWitnesses(Vec<PartialWitnessView>),
+    // Shard layout and assignment of this and the next epoch.
+    Shards(ShardsView),
 }
 
 #[cfg(feature = "debug_types")]
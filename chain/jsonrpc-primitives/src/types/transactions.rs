@@ -49,6 +49,8 @@ pub enum RpcTransactionError {
     InternalError { debug_info: String },
     #[error("Timeout")]
     TimeoutError,
+    #[error("Too many transactions signed by {signer_id}. Retry after {retry_after_ms} ms")]
+    SignerRateLimited { signer_id: AccountId, retry_after_ms: u64 },
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug)]
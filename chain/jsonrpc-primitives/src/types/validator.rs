@@ -25,6 +25,17 @@ pub struct RpcValidatorsOrderedRequest {
     pub block_id: near_primitives::types::MaybeBlockId,
 }
 
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcNextEpochValidatorScheduleRequest {
+    pub block_id: near_primitives::types::MaybeBlockId,
+    /// If set, only the heights at which the account produces the block or a
+    /// chunk are returned.
+    pub account_id: Option<near_primitives::types::AccountId>,
+}
+
+pub type RpcNextEpochValidatorScheduleResponse =
+    near_primitives::views::NextEpochValidatorScheduleView;
+
 #[derive(serde::Serialize, serde::Deserialize, Debug)]
 pub struct RpcValidatorResponse {
     #[serde(flatten)]
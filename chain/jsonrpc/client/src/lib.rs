@@ -250,6 +250,32 @@ impl JsonRpcClient {
         call_method(&self.client, &self.server_addr, "EXPERIMENTAL_receipt", request)
     }
 
+    #[allow(non_snake_case)]
+    pub fn EXPERIMENTAL_congestion_info(
+        &self,
+        request: near_jsonrpc_primitives::types::congestion::RpcCongestionInfoRequest,
+    ) -> RpcRequest<near_jsonrpc_primitives::types::congestion::RpcCongestionInfoResponse> {
+        call_method(&self.client, &self.server_addr, "EXPERIMENTAL_congestion_info", request)
+    }
+
+    #[allow(non_snake_case)]
+    pub fn EXPERIMENTAL_delegate_actions(
+        &self,
+        request: near_jsonrpc_primitives::types::delegate_actions::RpcDelegateActionsRequest,
+    ) -> RpcRequest<near_jsonrpc_primitives::types::delegate_actions::RpcDelegateActionsResponse>
+    {
+        call_method(&self.client, &self.server_addr, "EXPERIMENTAL_delegate_actions", request)
+    }
+
+    #[allow(non_snake_case)]
+    pub fn EXPERIMENTAL_simulate_tx(
+        &self,
+        request: near_jsonrpc_primitives::types::simulation::RpcSimulateTransactionRequest,
+    ) -> RpcRequest<near_jsonrpc_primitives::types::simulation::RpcSimulateTransactionResponse>
+    {
+        call_method(&self.client, &self.server_addr, "EXPERIMENTAL_simulate_tx", request)
+    }
+
     #[allow(non_snake_case)]
     pub fn EXPERIMENTAL_protocol_config(
         &self,
@@ -258,6 +284,46 @@ impl JsonRpcClient {
         call_method(&self.client, &self.server_addr, "EXPERIMENTAL_protocol_config", request)
     }
 
+    #[allow(non_snake_case)]
+    pub fn EXPERIMENTAL_protocol_config_diff(
+        &self,
+        request: near_jsonrpc_primitives::types::config::RpcProtocolConfigDiffRequest,
+    ) -> RpcRequest<near_jsonrpc_primitives::types::config::RpcProtocolConfigDiffResponse> {
+        call_method(&self.client, &self.server_addr, "EXPERIMENTAL_protocol_config_diff", request)
+    }
+
+    #[allow(non_snake_case)]
+    pub fn EXPERIMENTAL_next_epoch_validator_schedule(
+        &self,
+        request: near_jsonrpc_primitives::types::validator::RpcNextEpochValidatorScheduleRequest,
+    ) -> RpcRequest<near_jsonrpc_primitives::types::validator::RpcNextEpochValidatorScheduleResponse>
+    {
+        call_method(
+            &self.client,
+            &self.server_addr,
+            "EXPERIMENTAL_next_epoch_validator_schedule",
+            request,
+        )
+    }
+
+    #[allow(non_snake_case)]
+    pub fn EXPERIMENTAL_account_activity(
+        &self,
+        request: near_jsonrpc_primitives::types::account_activity::RpcAccountActivityRequest,
+    ) -> RpcRequest<near_jsonrpc_primitives::types::account_activity::RpcAccountActivityResponse>
+    {
+        call_method(&self.client, &self.server_addr, "EXPERIMENTAL_account_activity", request)
+    }
+
+    #[allow(non_snake_case)]
+    pub fn EXPERIMENTAL_equivocation_evidence(
+        &self,
+        request: near_jsonrpc_primitives::types::equivocation::RpcEquivocationEvidenceRequest,
+    ) -> RpcRequest<near_jsonrpc_primitives::types::equivocation::RpcEquivocationEvidenceResponse>
+    {
+        call_method(&self.client, &self.server_addr, "EXPERIMENTAL_equivocation_evidence", request)
+    }
+
     #[allow(non_snake_case)]
     pub fn EXPERIMENTAL_split_storage_info(
         &self,
@@ -267,6 +333,20 @@ impl JsonRpcClient {
         call_method(&self.client, &self.server_addr, "EXPERIMENTAL_split_storage_info", request)
     }
 
+    #[allow(non_snake_case)]
+    pub fn EXPERIMENTAL_cold_store_copy_control(
+        &self,
+        request: near_jsonrpc_primitives::types::split_storage::RpcColdStoreCopyControlRequest,
+    ) -> RpcRequest<near_jsonrpc_primitives::types::split_storage::RpcColdStoreCopyControlResponse>
+    {
+        call_method(
+            &self.client,
+            &self.server_addr,
+            "EXPERIMENTAL_cold_store_copy_control",
+            request,
+        )
+    }
+
     pub fn validators(
         &self,
         epoch_id_or_block_id: Option<EpochReference>,
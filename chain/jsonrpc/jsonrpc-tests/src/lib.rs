@@ -37,6 +37,24 @@ pub fn start_all_with_validity_period_and_no_epoch_sync(
     node_type: NodeType,
     transaction_validity_period: NumBlocks,
     enable_doomslug: bool,
+) -> (Addr<ViewClientActor>, tcp::ListenerAddr) {
+    start_all_with_rpc_config(
+        clock,
+        node_type,
+        transaction_validity_period,
+        enable_doomslug,
+        RpcConfig::new,
+    )
+}
+
+/// Like `start_all_with_validity_period_and_no_epoch_sync`, with the RPC
+/// config created from the address of the server.
+pub fn start_all_with_rpc_config(
+    clock: Clock,
+    node_type: NodeType,
+    transaction_validity_period: NumBlocks,
+    enable_doomslug: bool,
+    rpc_config: impl FnOnce(tcp::ListenerAddr) -> RpcConfig,
 ) -> (Addr<ViewClientActor>, tcp::ListenerAddr) {
     let actor_handles = setup_no_network_with_validity_period_and_no_epoch_sync(
         clock,
@@ -53,7 +71,7 @@ pub fn start_all_with_validity_period_and_no_epoch_sync(
 
     let addr = tcp::ListenerAddr::reserve_for_test();
     start_http(
-        RpcConfig::new(addr),
+        rpc_config(addr),
         TEST_GENESIS_CONFIG.clone(),
         actor_handles.client_actor.clone().with_auto_span_context().into_multi_sender(),
         actor_handles.view_client_actor.clone().with_auto_span_context().into_multi_sender(),
@@ -1,9 +1,11 @@
 use actix::System;
 use futures::{future, FutureExt};
+use serde_json::json;
 
 use near_actix_test_utils::run_actix;
 use near_async::time::Clock;
 use near_jsonrpc::client::new_http_client;
+use near_jsonrpc::{RpcAuthConfig, RpcConfig};
 use near_o11y::testonly::init_test_logger;
 
 use near_jsonrpc_tests as test_utils;
@@ -28,3 +30,135 @@ fn test_status() {
         }));
     });
 }
+
+/// Sends a JSON RPC request with the given headers, returning the status and
+/// the headers of the response.
+async fn post(
+    addr: &near_network::tcp::ListenerAddr,
+    method: &str,
+    headers: &[(&str, &str)],
+) -> (awc::http::StatusCode, awc::http::header::HeaderMap) {
+    let mut request = awc::Client::new().post(format!("http://{addr}")).no_decompress();
+    for header in headers {
+        request = request.insert_header(*header);
+    }
+    let response = request
+        .send_json(&json!({"jsonrpc": "2.0", "method": method, "id": "dontcare", "params": []}))
+        .await
+        .unwrap();
+    (response.status(), response.headers().clone())
+}
+
+/// Origins with allowed methods can only call those, other origins can call
+/// all the methods.
+#[test]
+fn test_cors_allowed_methods() {
+    init_test_logger();
+
+    run_actix(async {
+        let (_view_client_addr, addr) = test_utils::start_all_with_rpc_config(
+            Clock::real(),
+            test_utils::NodeType::NonValidator,
+            100,
+            false,
+            |addr| {
+                let mut config = RpcConfig::new(addr);
+                config.cors_allowed_methods =
+                    [("https://wallet.example".to_string(), vec!["status".to_string()])].into();
+                config
+            },
+        );
+
+        actix::spawn(async move {
+            let wallet = [("Origin", "https://wallet.example")];
+            let (status, _) = post(&addr, "status", &wallet).await;
+            assert_eq!(status, awc::http::StatusCode::OK);
+            let (status, _) = post(&addr, "block", &wallet).await;
+            assert_eq!(status, awc::http::StatusCode::FORBIDDEN);
+            let (status, _) = post(&addr, "block", &[("Origin", "https://other.example")]).await;
+            assert_eq!(status, awc::http::StatusCode::OK);
+            let (status, _) = post(&addr, "block", &[]).await;
+            assert_eq!(status, awc::http::StatusCode::OK);
+            System::current().stop();
+        });
+    });
+}
+
+/// The responses are compressed with an encoding the client accepts, unless
+/// the compression is disabled.
+#[test]
+fn test_compression() {
+    init_test_logger();
+
+    for enable_compression in [true, false] {
+        run_actix(async move {
+            let (_view_client_addr, addr) = test_utils::start_all_with_rpc_config(
+                Clock::real(),
+                test_utils::NodeType::NonValidator,
+                100,
+                false,
+                |addr| {
+                    let mut config = RpcConfig::new(addr);
+                    config.enable_compression = enable_compression;
+                    config
+                },
+            );
+
+            actix::spawn(async move {
+                let (status, headers) = post(&addr, "status", &[("Accept-Encoding", "gzip")]).await;
+                assert_eq!(status, awc::http::StatusCode::OK);
+                let encoding = headers
+                    .get(awc::http::header::CONTENT_ENCODING)
+                    .map(|encoding| encoding.to_str().unwrap().to_string());
+                assert_eq!(encoding, enable_compression.then(|| "gzip".to_string()));
+                System::current().stop();
+            });
+        });
+    }
+}
+
+/// The debug endpoints and pages require the token however their path is
+/// encoded.
+#[test]
+fn test_debug_auth() {
+    init_test_logger();
+
+    run_actix(async {
+        let (_view_client_addr, addr) = test_utils::start_all_with_rpc_config(
+            Clock::real(),
+            test_utils::NodeType::NonValidator,
+            100,
+            false,
+            |addr| {
+                let mut config = RpcConfig::new(addr);
+                config.enable_debug_rpc = true;
+                config.auth_config = Some(RpcAuthConfig {
+                    debug: true,
+                    api_keys: vec!["secret".to_string()],
+                    ..Default::default()
+                });
+                config
+            },
+        );
+
+        actix::spawn(async move {
+            let get = |path: &'static str, token: Option<&'static str>| {
+                let mut request = awc::Client::new().get(format!("http://{addr}{path}"));
+                if let Some(token) = token {
+                    request = request.bearer_auth(token);
+                }
+                async move { request.send().await.unwrap().status() }
+            };
+            for path in
+                ["/debug", "/debug/pages/shards", "/%64ebug/pages/shards", "/debug/%70ages/shards"]
+            {
+                assert_eq!(get(path, None).await, awc::http::StatusCode::UNAUTHORIZED, "{path}");
+                assert_eq!(get(path, Some("wrong")).await, awc::http::StatusCode::UNAUTHORIZED);
+                assert_eq!(get(path, Some("secret")).await, awc::http::StatusCode::OK, "{path}");
+            }
+            // Other endpoints don't require the token.
+            assert_eq!(get("/status", None).await, awc::http::StatusCode::OK);
+            System::current().stop();
+        });
+    });
+}
@@ -7,16 +7,22 @@ use serde_json::json;
 
 use near_actix_test_utils::run_actix;
 use near_async::time::Clock;
-use near_crypto::{KeyType, PublicKey, Signature};
+use near_crypto::{InMemorySigner, KeyType, PublicKey, Signature};
 use near_jsonrpc::client::{new_client, ChunkId};
+use near_jsonrpc_primitives::errors::RpcErrorKind;
+use near_jsonrpc_primitives::types::congestion::RpcCongestionInfoRequest;
+use near_jsonrpc_primitives::types::delegate_actions::RpcDelegateActionsRequest;
 use near_jsonrpc_primitives::types::query::QueryResponseKind;
+use near_jsonrpc_primitives::types::simulation::RpcSimulateTransactionRequest;
 use near_jsonrpc_primitives::types::validator::RpcValidatorsOrderedRequest;
 use near_network::test_utils::wait_or_timeout;
 use near_o11y::testonly::init_test_logger;
 use near_primitives::account::{AccessKey, AccessKeyPermission};
 use near_primitives::hash::CryptoHash;
+use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::{BlockId, BlockReference, EpochId, SyncCheckpoint};
-use near_primitives::views::QueryRequest;
+use near_primitives::version::{ProtocolFeature, PROTOCOL_VERSION};
+use near_primitives::views::{FinalExecutionStatus, QueryRequest};
 
 use near_jsonrpc_tests::{self as test_utils, test_with_client};
 
@@ -223,7 +229,11 @@ fn test_query_access_keys() {
         let query_response = client
             .query(near_jsonrpc_primitives::types::query::RpcQueryRequest {
                 block_reference: BlockReference::latest(),
-                request: QueryRequest::ViewAccessKeyList { account_id: "test".parse().unwrap() },
+                request: QueryRequest::ViewAccessKeyList {
+                    account_id: "test".parse().unwrap(),
+                    after_public_key: None,
+                    limit: None,
+                },
             })
             .await
             .unwrap();
@@ -477,6 +487,88 @@ fn test_genesis_config() {
     });
 }
 
+/// Retrieve congestion info of all shards via JSON RPC.
+#[test]
+fn test_congestion_info() {
+    test_with_client!(test_utils::NodeType::NonValidator, client, async move {
+        let request = RpcCongestionInfoRequest {
+            block_reference: BlockReference::BlockId(BlockId::Height(0)),
+        };
+        let response = client.EXPERIMENTAL_congestion_info(request).await.unwrap();
+        let congestion_info = response.congestion_info;
+        assert_eq!(congestion_info.block_height, 0);
+        if ProtocolFeature::CongestionControl.enabled(PROTOCOL_VERSION) {
+            assert!(!congestion_info.shards.is_empty());
+        }
+        for shard in congestion_info.shards {
+            assert_eq!(shard.congestion_level, 0.0);
+            assert_eq!(shard.missed_chunks_count, 0);
+        }
+
+        let request = RpcCongestionInfoRequest {
+            block_reference: BlockReference::BlockId(BlockId::Height(1_000_000)),
+        };
+        let error = client.EXPERIMENTAL_congestion_info(request).await.unwrap_err();
+        match error.error_struct {
+            Some(RpcErrorKind::HandlerError(error)) => assert_eq!(error["name"], "UNKNOWN_BLOCK"),
+            other => panic!("unexpected error {other:?}"),
+        }
+    });
+}
+
+/// Retrieve the delegate actions of a block via JSON RPC.
+#[test]
+fn test_delegate_actions() {
+    test_with_client!(test_utils::NodeType::NonValidator, client, async move {
+        let request = RpcDelegateActionsRequest {
+            block_reference: BlockReference::BlockId(BlockId::Height(0)),
+            relayer_id: Some("test1".parse().unwrap()),
+            sender_id: None,
+        };
+        let response = client.EXPERIMENTAL_delegate_actions(request).await.unwrap();
+        assert_eq!(response.delegate_actions.block_height, 0);
+        assert!(response.delegate_actions.delegate_actions.is_empty());
+
+        let request = RpcDelegateActionsRequest {
+            block_reference: BlockReference::BlockId(BlockId::Height(1_000_000)),
+            relayer_id: None,
+            sender_id: None,
+        };
+        let error = client.EXPERIMENTAL_delegate_actions(request).await.unwrap_err();
+        match error.error_struct {
+            Some(RpcErrorKind::HandlerError(error)) => assert_eq!(error["name"], "UNKNOWN_BLOCK"),
+            other => panic!("unexpected error {other:?}"),
+        }
+    });
+}
+
+/// Simulate a transaction via JSON RPC.
+#[test]
+fn test_simulate_tx() {
+    test_with_client!(test_utils::NodeType::NonValidator, client, async move {
+        let block = client.block(BlockReference::latest()).await.unwrap();
+        let signer = InMemorySigner::from_seed("test1".parse().unwrap(), KeyType::ED25519, "test1");
+        let tx = SignedTransaction::send_money(
+            1,
+            "test1".parse().unwrap(),
+            "test2".parse().unwrap(),
+            &signer,
+            100,
+            block.header.hash,
+        );
+        let request = RpcSimulateTransactionRequest {
+            signed_transaction: tx.clone(),
+            skip_signature_verification: false,
+            state_overrides: vec![],
+        };
+        let outcome =
+            client.EXPERIMENTAL_simulate_tx(request).await.unwrap().final_execution_outcome;
+        assert_eq!(outcome.transaction.hash, tx.get_hash());
+        assert_eq!(outcome.transaction_outcome.id, tx.get_hash());
+        assert_eq!(outcome.status, FinalExecutionStatus::SuccessValue(vec![]));
+    });
+}
+
 /// Retrieve gas price
 #[test]
 fn test_gas_price_by_height() {
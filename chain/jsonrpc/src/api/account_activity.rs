@@ -0,0 +1,32 @@
+use near_async::messaging::AsyncSendError;
+use near_client_primitives::types::GetAccountActivityError;
+use near_jsonrpc_primitives::errors::RpcParseError;
+use near_jsonrpc_primitives::types::account_activity::{
+    RpcAccountActivityError, RpcAccountActivityRequest,
+};
+use serde_json::Value;
+
+use super::{Params, RpcFrom, RpcRequest};
+
+impl RpcRequest for RpcAccountActivityRequest {
+    fn parse(value: Value) -> Result<Self, RpcParseError> {
+        Params::parse(value)
+    }
+}
+
+impl RpcFrom<AsyncSendError> for RpcAccountActivityError {
+    fn rpc_from(error: AsyncSendError) -> Self {
+        Self::InternalError { error_message: error.to_string() }
+    }
+}
+
+impl RpcFrom<GetAccountActivityError> for RpcAccountActivityError {
+    fn rpc_from(error: GetAccountActivityError) -> Self {
+        match error {
+            GetAccountActivityError::AccountIndexDisabled => Self::AccountIndexDisabled,
+            GetAccountActivityError::IOError(error_message) => {
+                Self::InternalError { error_message }
+            }
+        }
+    }
+}
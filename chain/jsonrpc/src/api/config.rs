@@ -1,7 +1,9 @@
 use near_async::messaging::AsyncSendError;
 use near_client_primitives::types::GetProtocolConfigError;
 use near_jsonrpc_primitives::errors::RpcParseError;
-use near_jsonrpc_primitives::types::config::{RpcProtocolConfigError, RpcProtocolConfigRequest};
+use near_jsonrpc_primitives::types::config::{
+    RpcProtocolConfigDiffRequest, RpcProtocolConfigError, RpcProtocolConfigRequest,
+};
 use serde_json::Value;
 
 use super::{Params, RpcFrom, RpcRequest};
@@ -12,6 +14,12 @@ impl RpcRequest for RpcProtocolConfigRequest {
     }
 }
 
+impl RpcRequest for RpcProtocolConfigDiffRequest {
+    fn parse(value: Value) -> Result<Self, RpcParseError> {
+        Params::parse(value)
+    }
+}
+
 impl RpcFrom<AsyncSendError> for RpcProtocolConfigError {
     fn rpc_from(error: AsyncSendError) -> Self {
         Self::InternalError { error_message: error.to_string() }
@@ -0,0 +1,39 @@
+use near_async::messaging::AsyncSendError;
+use near_client_primitives::types::GetCongestionInfoError;
+use near_jsonrpc_primitives::errors::RpcParseError;
+use near_jsonrpc_primitives::types::congestion::{
+    RpcCongestionInfoError, RpcCongestionInfoRequest,
+};
+use serde_json::Value;
+
+use super::{Params, RpcFrom, RpcRequest};
+
+impl RpcRequest for RpcCongestionInfoRequest {
+    fn parse(value: Value) -> Result<Self, RpcParseError> {
+        Params::parse(value).map(|block_reference| Self { block_reference })
+    }
+}
+
+impl RpcFrom<AsyncSendError> for RpcCongestionInfoError {
+    fn rpc_from(error: AsyncSendError) -> Self {
+        Self::InternalError { error_message: error.to_string() }
+    }
+}
+
+impl RpcFrom<GetCongestionInfoError> for RpcCongestionInfoError {
+    fn rpc_from(error: GetCongestionInfoError) -> Self {
+        match error {
+            GetCongestionInfoError::UnknownBlock(error_message) => {
+                Self::UnknownBlock { error_message }
+            }
+            GetCongestionInfoError::IOError(error_message) => Self::InternalError { error_message },
+            GetCongestionInfoError::Unreachable(ref error_message) => {
+                tracing::warn!(target: "jsonrpc", "Unreachable error occurred: {}", error_message);
+                crate::metrics::RPC_UNREACHABLE_ERROR_COUNT
+                    .with_label_values(&["RpcCongestionInfoError"])
+                    .inc();
+                Self::InternalError { error_message: error.to_string() }
+            }
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use near_async::messaging::AsyncSendError;
+use near_client_primitives::types::GetDelegateActionsError;
+use near_jsonrpc_primitives::errors::RpcParseError;
+use near_jsonrpc_primitives::types::delegate_actions::{
+    RpcDelegateActionsError, RpcDelegateActionsRequest,
+};
+use serde_json::Value;
+
+use super::{Params, RpcFrom, RpcRequest};
+
+impl RpcRequest for RpcDelegateActionsRequest {
+    fn parse(value: Value) -> Result<Self, RpcParseError> {
+        Params::parse(value)
+    }
+}
+
+impl RpcFrom<AsyncSendError> for RpcDelegateActionsError {
+    fn rpc_from(error: AsyncSendError) -> Self {
+        Self::InternalError { error_message: error.to_string() }
+    }
+}
+
+impl RpcFrom<GetDelegateActionsError> for RpcDelegateActionsError {
+    fn rpc_from(error: GetDelegateActionsError) -> Self {
+        match error {
+            GetDelegateActionsError::UnknownBlock(error_message) => {
+                Self::UnknownBlock { error_message }
+            }
+            GetDelegateActionsError::IOError(error_message) => {
+                Self::InternalError { error_message }
+            }
+            GetDelegateActionsError::Unreachable(ref error_message) => {
+                tracing::warn!(target: "jsonrpc", "Unreachable error occurred: {}", error_message);
+                crate::metrics::RPC_UNREACHABLE_ERROR_COUNT
+                    .with_label_values(&["RpcDelegateActionsError"])
+                    .inc();
+                Self::InternalError { error_message: error.to_string() }
+            }
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use near_async::messaging::AsyncSendError;
+use near_client_primitives::types::GetEquivocationEvidenceError;
+use near_jsonrpc_primitives::errors::RpcParseError;
+use near_jsonrpc_primitives::types::equivocation::{
+    RpcEquivocationEvidenceError, RpcEquivocationEvidenceRequest,
+};
+use serde_json::Value;
+
+use super::{Params, RpcFrom, RpcRequest};
+
+impl RpcRequest for RpcEquivocationEvidenceRequest {
+    fn parse(value: Value) -> Result<Self, RpcParseError> {
+        Params::parse(value)
+    }
+}
+
+impl RpcFrom<AsyncSendError> for RpcEquivocationEvidenceError {
+    fn rpc_from(error: AsyncSendError) -> Self {
+        Self::InternalError { error_message: error.to_string() }
+    }
+}
+
+impl RpcFrom<GetEquivocationEvidenceError> for RpcEquivocationEvidenceError {
+    fn rpc_from(error: GetEquivocationEvidenceError) -> Self {
+        match error {
+            GetEquivocationEvidenceError::IOError(error_message) => {
+                Self::InternalError { error_message }
+            }
+        }
+    }
+}
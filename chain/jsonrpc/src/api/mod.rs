@@ -4,11 +4,15 @@ use serde_json::Value;
 use near_jsonrpc_primitives::errors::RpcParseError;
 use near_jsonrpc_primitives::errors::{RpcError, ServerError};
 
+mod account_activity;
 mod blocks;
 mod changes;
 mod chunks;
 mod client_config;
 mod config;
+mod congestion;
+mod delegate_actions;
+mod equivocation;
 mod gas_price;
 mod light_client;
 mod maintenance;
@@ -16,6 +20,7 @@ mod network_info;
 mod query;
 mod receipts;
 mod sandbox;
+mod simulation;
 mod split_storage;
 mod status;
 mod transactions;
@@ -59,7 +59,9 @@ fn parse_path_data(path: String, data: String) -> Result<RpcQueryRequest, RpcPar
     let request = match query_command {
         "account" => QueryRequest::ViewAccount { account_id },
         "access_key" => match maybe_extra_arg {
-            None => QueryRequest::ViewAccessKeyList { account_id },
+            None => {
+                QueryRequest::ViewAccessKeyList { account_id, after_public_key: None, limit: None }
+            }
             Some(pk) => QueryRequest::ViewAccessKey {
                 account_id,
                 public_key: pk
@@ -114,6 +116,9 @@ impl RpcFrom<QueryError> for RpcQueryError {
             QueryError::NoContractCode { contract_account_id, block_height, block_hash } => {
                 Self::NoContractCode { contract_account_id, block_height, block_hash }
             }
+            QueryError::UnknownCodeHash { code_hash, block_height, block_hash } => {
+                Self::UnknownCodeHash { code_hash, block_height, block_hash }
+            }
             QueryError::UnknownAccessKey { public_key, block_height, block_hash } => {
                 Self::UnknownAccessKey { public_key, block_height, block_hash }
             }
@@ -167,6 +172,9 @@ impl RpcFrom<near_primitives::views::QueryResponseKind>
             near_primitives::views::QueryResponseKind::AccessKeyList(access_key_list) => {
                 Self::AccessKeyList(access_key_list)
             }
+            near_primitives::views::QueryResponseKind::StorageUsage(storage_usage) => {
+                Self::StorageUsage(storage_usage)
+            }
         }
     }
 }
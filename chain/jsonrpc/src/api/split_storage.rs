@@ -1,8 +1,11 @@
 use near_async::messaging::AsyncSendError;
-use near_client_primitives::types::GetSplitStorageInfoError;
+use near_client_primitives::types::{ControlColdStoreCopyError, GetSplitStorageInfoError};
 use near_jsonrpc_primitives::{
     errors::RpcParseError,
-    types::split_storage::{RpcSplitStorageInfoError, RpcSplitStorageInfoRequest},
+    types::split_storage::{
+        RpcColdStoreCopyControlError, RpcColdStoreCopyControlRequest, RpcSplitStorageInfoError,
+        RpcSplitStorageInfoRequest,
+    },
 };
 use serde_json::Value;
 
@@ -36,3 +39,26 @@ impl RpcFrom<GetSplitStorageInfoError> for RpcSplitStorageInfoError {
         }
     }
 }
+
+impl RpcRequest for RpcColdStoreCopyControlRequest {
+    fn parse(value: Value) -> Result<Self, RpcParseError> {
+        Params::parse(value)
+    }
+}
+
+impl RpcFrom<AsyncSendError> for RpcColdStoreCopyControlError {
+    fn rpc_from(error: AsyncSendError) -> Self {
+        Self::InternalError { error_message: error.to_string() }
+    }
+}
+
+impl RpcFrom<ControlColdStoreCopyError> for RpcColdStoreCopyControlError {
+    fn rpc_from(error: ControlColdStoreCopyError) -> Self {
+        match error {
+            ControlColdStoreCopyError::NotRunning => Self::ColdStoreLoopNotRunning,
+            ControlColdStoreCopyError::IOError(error_message) => {
+                Self::InternalError { error_message }
+            }
+        }
+    }
+}
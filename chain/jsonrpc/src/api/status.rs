@@ -50,6 +50,27 @@ impl RpcFrom<near_client_primitives::debug::DebugStatusResponse>
                     x,
                 )
             }
+            near_client_primitives::debug::DebugStatusResponse::Congestion(x) => {
+                near_jsonrpc_primitives::types::status::DebugStatusResponse::Congestion(x)
+            }
+            near_client_primitives::debug::DebugStatusResponse::TxPool(x) => {
+                near_jsonrpc_primitives::types::status::DebugStatusResponse::TxPool(x)
+            }
+            near_client_primitives::debug::DebugStatusResponse::ChunkEndorsements(x) => {
+                near_jsonrpc_primitives::types::status::DebugStatusResponse::ChunkEndorsements(x)
+            }
+            near_client_primitives::debug::DebugStatusResponse::TxRouting(x) => {
+                near_jsonrpc_primitives::types::status::DebugStatusResponse::TxRouting(x)
+            }
+            near_client_primitives::debug::DebugStatusResponse::StoreStats(x) => {
+                near_jsonrpc_primitives::types::status::DebugStatusResponse::StoreStats(x)
+            }
+            near_client_primitives::debug::DebugStatusResponse::PartialWitnesses(x) => {
+                near_jsonrpc_primitives::types::status::DebugStatusResponse::PartialWitnesses(x)
+            }
+            near_client_primitives::debug::DebugStatusResponse::Shards(x) => {
+                near_jsonrpc_primitives::types::status::DebugStatusResponse::Shards(x)
+            }
         }
     }
 }
@@ -0,0 +1,164 @@
+//! Authentication of the calls of selected JSON RPC methods and of the debug
+//! endpoints.
+//!
+//! The clients pass `Authorization: Bearer <token>`, where the token is either
+//! one of the configured static API keys, or a JWT signed with the EdDSA
+//! algorithm by one of the configured ed25519 keys.  A JWT with an `exp`
+//! claim is rejected after that unix timestamp.
+use actix_web::http::header::{HeaderMap, AUTHORIZATION};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use near_crypto::{KeyType, PublicKey, Signature};
+use subtle::ConstantTimeEq;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct RpcAuthConfig {
+    /// JSON RPC methods which can be called only with a valid token, e.g.
+    /// `sandbox_patch_state` or `send_tx`.
+    #[serde(default)]
+    pub methods: Vec<String>,
+    /// Whether the debug endpoints and pages require a valid token.
+    #[serde(default)]
+    pub debug: bool,
+    /// Static API keys accepted as tokens.
+    #[serde(default)]
+    pub api_keys: Vec<String>,
+    /// Keys of which the signed JWTs are accepted as tokens.
+    #[serde(default)]
+    pub jwt_public_keys: Vec<PublicKey>,
+}
+
+#[derive(derive_more::Display, Debug, PartialEq)]
+pub(crate) enum AuthError {
+    #[display(fmt = "missing bearer token")]
+    MissingToken,
+    #[display(fmt = "invalid token")]
+    InvalidToken,
+    #[display(fmt = "expired token")]
+    ExpiredToken,
+}
+
+#[derive(serde::Deserialize)]
+struct JwtHeader {
+    alg: String,
+}
+
+#[derive(serde::Deserialize)]
+struct JwtClaims {
+    exp: Option<u64>,
+}
+
+pub(crate) struct Authenticator {
+    config: RpcAuthConfig,
+}
+
+impl Authenticator {
+    pub fn new(config: Option<RpcAuthConfig>) -> Self {
+        Self { config: config.unwrap_or_default() }
+    }
+
+    pub fn method_requires_auth(&self, method: &str) -> bool {
+        self.config.methods.iter().any(|protected| protected == method)
+    }
+
+    pub fn debug_requires_auth(&self) -> bool {
+        self.config.debug
+    }
+
+    /// Verifies the bearer token of the request, `now` being the current unix
+    /// timestamp in seconds.
+    pub fn authenticate(&self, headers: &HeaderMap, now: u64) -> Result<(), AuthError> {
+        let token = headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or(AuthError::MissingToken)?
+            .trim();
+        if self.config.api_keys.iter().any(|key| bool::from(key.as_bytes().ct_eq(token.as_bytes())))
+        {
+            return Ok(());
+        }
+        self.verify_jwt(token, now)
+    }
+
+    fn verify_jwt(&self, token: &str, now: u64) -> Result<(), AuthError> {
+        let decode = |part: &str| URL_SAFE_NO_PAD.decode(part).map_err(|_| AuthError::InvalidToken);
+        let (signed, signature) = token.rsplit_once('.').ok_or(AuthError::InvalidToken)?;
+        let (header, claims) = signed.split_once('.').ok_or(AuthError::InvalidToken)?;
+        let header: JwtHeader =
+            serde_json::from_slice(&decode(header)?).map_err(|_| AuthError::InvalidToken)?;
+        if header.alg != "EdDSA" {
+            return Err(AuthError::InvalidToken);
+        }
+        let signature = Signature::from_parts(KeyType::ED25519, &decode(signature)?)
+            .map_err(|_| AuthError::InvalidToken)?;
+        if !self.config.jwt_public_keys.iter().any(|key| signature.verify(signed.as_bytes(), key)) {
+            return Err(AuthError::InvalidToken);
+        }
+        let claims: JwtClaims =
+            serde_json::from_slice(&decode(claims)?).map_err(|_| AuthError::InvalidToken)?;
+        match claims.exp {
+            Some(exp) if exp <= now => Err(AuthError::ExpiredToken),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::header::HeaderValue;
+    use near_crypto::SecretKey;
+
+    fn jwt(key: &SecretKey, alg: &str, claims: serde_json::Value) -> String {
+        let header = URL_SAFE_NO_PAD.encode(serde_json::json!({ "alg": alg }).to_string());
+        let claims = URL_SAFE_NO_PAD.encode(claims.to_string());
+        let signed = format!("{header}.{claims}");
+        let signature = key.sign(signed.as_bytes()).to_string();
+        let signature =
+            bs58::decode(signature.strip_prefix("ed25519:").unwrap()).into_vec().unwrap();
+        format!("{signed}.{}", URL_SAFE_NO_PAD.encode(signature))
+    }
+
+    fn authenticate(authenticator: &Authenticator, token: Option<&str>) -> Result<(), AuthError> {
+        let mut headers = HeaderMap::new();
+        if let Some(token) = token {
+            headers
+                .insert(AUTHORIZATION, HeaderValue::from_str(&format!("Bearer {token}")).unwrap());
+        }
+        authenticator.authenticate(&headers, 1000)
+    }
+
+    #[test]
+    fn test_authenticate() {
+        let key = SecretKey::from_seed(KeyType::ED25519, "rpc");
+        let other_key = SecretKey::from_seed(KeyType::ED25519, "other");
+        let authenticator = Authenticator::new(Some(RpcAuthConfig {
+            methods: vec!["send_tx".to_owned()],
+            debug: false,
+            api_keys: vec!["secret".to_owned()],
+            jwt_public_keys: vec![key.public_key()],
+        }));
+        assert!(authenticator.method_requires_auth("send_tx"));
+        assert!(!authenticator.method_requires_auth("block"));
+
+        assert_eq!(authenticate(&authenticator, None), Err(AuthError::MissingToken));
+        assert_eq!(authenticate(&authenticator, Some("secret")), Ok(()));
+        assert_eq!(authenticate(&authenticator, Some("wrong")), Err(AuthError::InvalidToken));
+
+        let claims = serde_json::json!({ "sub": "operator" });
+        assert_eq!(authenticate(&authenticator, Some(&jwt(&key, "EdDSA", claims.clone()))), Ok(()));
+        assert_eq!(
+            authenticate(&authenticator, Some(&jwt(&other_key, "EdDSA", claims.clone()))),
+            Err(AuthError::InvalidToken)
+        );
+        assert_eq!(
+            authenticate(&authenticator, Some(&jwt(&key, "none", claims))),
+            Err(AuthError::InvalidToken)
+        );
+        let token = jwt(&key, "EdDSA", serde_json::json!({ "exp": 1001 }));
+        assert_eq!(authenticate(&authenticator, Some(&token)), Ok(()));
+        let token = jwt(&key, "EdDSA", serde_json::json!({ "exp": 1000 }));
+        assert_eq!(authenticate(&authenticator, Some(&token)), Err(AuthError::ExpiredToken));
+    }
+}
@@ -1,11 +1,13 @@
 #![doc = include_str!("../README.md")]
 
 use actix_cors::Cors;
+use actix_web::dev::Service;
 use actix_web::http::header;
 use actix_web::HttpRequest;
 use actix_web::{get, http, middleware, web, App, Error as HttpError, HttpResponse, HttpServer};
 use api::RpcRequest;
 pub use api::{RpcFrom, RpcInto};
+use auth::Authenticator;
 use futures::Future;
 use futures::FutureExt;
 use near_async::actix::ActixResult;
@@ -15,15 +17,21 @@ use near_async::messaging::{
 use near_chain_configs::GenesisConfig;
 use near_client::{
     DebugStatus, GetBlock, GetBlockProof, GetChunk, GetClientConfig, GetExecutionOutcome,
-    GetGasPrice, GetMaintenanceWindows, GetNetworkInfo, GetNextLightClientBlock, GetProtocolConfig,
-    GetReceipt, GetStateChanges, GetStateChangesInBlock, GetValidatorInfo, GetValidatorOrdered,
-    ProcessTxRequest, ProcessTxResponse, Query, Status, TxStatus,
+    GetGasPrice, GetMaintenanceWindows, GetNetworkInfo, GetNextEpochValidatorSchedule,
+    GetNextLightClientBlock, GetProtocolConfig, GetReceipt, GetStateChanges,
+    GetStateChangesInBlock, GetValidatorInfo, GetValidatorOrdered, ProcessTxRequest,
+    ProcessTxResponse, Query, Status, TxStatus,
+};
+use near_client_primitives::types::{
+    ControlColdStoreCopy, GetAccountActivity, GetCongestionInfo, GetDelegateActions,
+    GetEquivocationEvidence, GetSplitStorageInfo, SimulateTransaction,
 };
-use near_client_primitives::types::GetSplitStorageInfo;
 pub use near_jsonrpc_client as client;
 use near_jsonrpc_primitives::errors::RpcError;
 use near_jsonrpc_primitives::message::{Message, Request};
-use near_jsonrpc_primitives::types::config::RpcProtocolConfigResponse;
+use near_jsonrpc_primitives::types::config::{
+    RpcProtocolConfigDiffError, RpcProtocolConfigDiffResponse, RpcProtocolConfigResponse,
+};
 use near_jsonrpc_primitives::types::entity_debug::{EntityDebugHandler, EntityQuery};
 use near_jsonrpc_primitives::types::query::RpcQueryRequest;
 use near_jsonrpc_primitives::types::split_storage::{
@@ -34,20 +42,30 @@ use near_jsonrpc_primitives::types::transactions::{
 };
 use near_network::debug::GetDebugStatus;
 use near_network::tcp;
-use near_o11y::metrics::{prometheus, Encoder, TextEncoder};
+use near_o11y::metrics::{Encoder, TextEncoder};
+use near_parameters::config_store::parameters_diff;
 use near_primitives::hash::CryptoHash;
 use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::{AccountId, BlockHeight};
+use near_primitives::version::{ProtocolFeature, PROTOCOL_VERSION};
 use near_primitives::views::{QueryRequest, TxExecutionStatus};
+use rate_limit::{RateLimiter, SignerRateLimiter};
 use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 use tokio::time::{sleep, timeout};
 use tracing::{error, info};
 
 mod api;
+mod auth;
 mod metrics;
+mod profiler;
+mod rate_limit;
+
+pub use auth::RpcAuthConfig;
+pub use rate_limit::RpcRateLimit;
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug)]
 pub struct RpcPollingConfig {
@@ -68,11 +86,30 @@ impl Default for RpcPollingConfig {
 pub struct RpcLimitsConfig {
     /// Maximum byte size of the json payload.
     pub json_payload_max_size: usize,
+    /// Rate limits of the methods, applied to each client IP, or IPv6 /64
+    /// network, separately.  Methods without a limit aren't limited, except
+    /// for `EXPERIMENTAL_simulate_tx`, limited to 1 call per second with a
+    /// burst of 10 by default.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub method_rate_limits: HashMap<String, RpcRateLimit>,
+    /// Rate limit of the transactions sent with the JSON RPC, applied to each
+    /// signer account separately before the transactions are added to the
+    /// pool.  The transactions aren't limited by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_signer_rate_limit: Option<RpcRateLimit>,
+    /// Signer accounts whose transactions aren't rate limited.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transaction_signer_allowlist: Vec<AccountId>,
 }
 
 impl Default for RpcLimitsConfig {
     fn default() -> Self {
-        Self { json_payload_max_size: 10 * 1024 * 1024 }
+        Self {
+            json_payload_max_size: 10 * 1024 * 1024,
+            method_rate_limits: HashMap::new(),
+            transaction_signer_rate_limit: None,
+            transaction_signer_allowlist: vec![],
+        }
     }
 }
 
@@ -80,12 +117,25 @@ fn default_enable_debug_rpc() -> bool {
     false
 }
 
+fn default_enable_compression() -> bool {
+    true
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
 pub struct RpcConfig {
     pub addr: tcp::ListenerAddr,
     // If provided, will start an http server exporting only Prometheus metrics on that address.
     pub prometheus_addr: Option<String>,
     pub cors_allowed_origins: Vec<String>,
+    /// JSON RPC methods which the given origins are allowed to call.  The
+    /// origins are allowed in addition to `cors_allowed_origins`, and the
+    /// origins not listed here can call all the methods.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub cors_allowed_methods: HashMap<String, Vec<String>>,
+    /// If true, the responses are compressed with one of the encodings the
+    /// client accepts.
+    #[serde(default = "default_enable_compression")]
+    pub enable_compression: bool,
     pub polling_config: RpcPollingConfig,
     #[serde(default)]
     pub limits_config: RpcLimitsConfig,
@@ -97,6 +147,10 @@ pub struct RpcConfig {
     // be read from this directory, instead of the contents compiled into the binary. This allows
     // for quick iterative development.
     pub experimental_debug_pages_src_path: Option<String>,
+    /// If provided, the listed methods and optionally the debug endpoints
+    /// can be called only with one of the configured tokens.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_config: Option<RpcAuthConfig>,
 }
 
 impl Default for RpcConfig {
@@ -105,10 +159,13 @@ impl Default for RpcConfig {
             addr: tcp::ListenerAddr::new("0.0.0.0:3030".parse().unwrap()),
             prometheus_addr: None,
             cors_allowed_origins: vec!["*".to_owned()],
+            cors_allowed_methods: HashMap::new(),
+            enable_compression: true,
             polling_config: Default::default(),
             limits_config: Default::default(),
             enable_debug_rpc: false,
             experimental_debug_pages_src_path: None,
+            auth_config: None,
         }
     }
 }
@@ -247,17 +304,24 @@ pub struct ViewClientSenderForRpc(
     AsyncSender<GetBlockProof, ActixResult<GetBlockProof>>,
     AsyncSender<GetChunk, ActixResult<GetChunk>>,
     AsyncSender<GetExecutionOutcome, ActixResult<GetExecutionOutcome>>,
+    AsyncSender<GetAccountActivity, ActixResult<GetAccountActivity>>,
     AsyncSender<GetGasPrice, ActixResult<GetGasPrice>>,
+    AsyncSender<GetCongestionInfo, ActixResult<GetCongestionInfo>>,
+    AsyncSender<GetDelegateActions, ActixResult<GetDelegateActions>>,
+    AsyncSender<GetEquivocationEvidence, ActixResult<GetEquivocationEvidence>>,
     AsyncSender<GetMaintenanceWindows, ActixResult<GetMaintenanceWindows>>,
+    AsyncSender<GetNextEpochValidatorSchedule, ActixResult<GetNextEpochValidatorSchedule>>,
     AsyncSender<GetNextLightClientBlock, ActixResult<GetNextLightClientBlock>>,
     AsyncSender<GetProtocolConfig, ActixResult<GetProtocolConfig>>,
     AsyncSender<GetReceipt, ActixResult<GetReceipt>>,
     AsyncSender<GetSplitStorageInfo, ActixResult<GetSplitStorageInfo>>,
+    AsyncSender<ControlColdStoreCopy, ActixResult<ControlColdStoreCopy>>,
     AsyncSender<GetStateChanges, ActixResult<GetStateChanges>>,
     AsyncSender<GetStateChangesInBlock, ActixResult<GetStateChangesInBlock>>,
     AsyncSender<GetValidatorInfo, ActixResult<GetValidatorInfo>>,
     AsyncSender<GetValidatorOrdered, ActixResult<GetValidatorOrdered>>,
     AsyncSender<Query, ActixResult<Query>>,
+    AsyncSender<SimulateTransaction, ActixResult<SimulateTransaction>>,
     AsyncSender<TxStatus, ActixResult<TxStatus>>,
     #[cfg(feature = "test_features")] Sender<near_client::NetworkAdversarialMessage>,
 );
@@ -281,10 +345,12 @@ struct JsonRpcHandler {
     #[cfg(feature = "test_features")]
     gc_sender: GCSenderForRpc,
     polling_config: RpcPollingConfig,
+    cors_allowed_methods: HashMap<String, Vec<String>>,
     genesis_config: GenesisConfig,
     enable_debug_rpc: bool,
     debug_pages_src_path: Option<PathBuf>,
     entity_debug_handler: Arc<dyn EntityDebugHandler>,
+    signer_rate_limiter: Arc<SignerRateLimiter>,
 }
 
 impl JsonRpcHandler {
@@ -347,6 +413,7 @@ impl JsonRpcHandler {
                 let metrics_name = match params.request {
                     QueryRequest::ViewAccount { .. } => "query_view_account",
                     QueryRequest::ViewCode { .. } => "query_view_code",
+                    QueryRequest::ViewCodeByHash { .. } => "query_view_code_by_hash",
                     QueryRequest::ViewState { include_proof, .. } => {
                         if include_proof {
                             "query_view_state_with_proof"
@@ -357,6 +424,7 @@ impl JsonRpcHandler {
                     QueryRequest::ViewAccessKey { .. } => "query_view_access_key",
                     QueryRequest::ViewAccessKeyList { .. } => "query_view_access_key_list",
                     QueryRequest::CallFunction { .. } => "query_call_function",
+                    QueryRequest::ViewStorageUsage { .. } => "query_view_storage_usage",
                 };
                 (metrics_name.to_string(), process_query_response(self.query(params).await))
             }
@@ -375,8 +443,10 @@ impl JsonRpcHandler {
             "block" => process_method_call(request, |params| self.block(params)).await,
             "broadcast_tx_async" => {
                 process_method_call(request, |params| async {
-                    let tx = self.send_tx_async(params).await.to_string();
-                    Result::<_, std::convert::Infallible>::Ok(tx)
+                    let tx = self.send_tx_async(params, "broadcast_tx_async").await?;
+                    Ok::<_, near_jsonrpc_primitives::types::transactions::RpcTransactionError>(
+                        tx.to_string(),
+                    )
                 })
                 .await
             }
@@ -396,7 +466,9 @@ impl JsonRpcHandler {
                 process_method_call(request, |params| self.next_light_client_block(params)).await
             }
             "network_info" => process_method_call(request, |_params: ()| self.network_info()).await,
-            "send_tx" => process_method_call(request, |params| self.send_tx(params)).await,
+            "send_tx" => {
+                process_method_call(request, |params| self.send_tx(params, "send_tx")).await
+            }
             "status" => process_method_call(request, |_params: ()| self.status()).await,
             "tx" => {
                 process_method_call(request, |params| self.tx_status_common(params, false)).await
@@ -411,6 +483,12 @@ impl JsonRpcHandler {
             "EXPERIMENTAL_changes_in_block" => {
                 process_method_call(request, |params| self.changes_in_block(params)).await
             }
+            "EXPERIMENTAL_congestion_info" => {
+                process_method_call(request, |params| self.congestion_info(params)).await
+            }
+            "EXPERIMENTAL_delegate_actions" => {
+                process_method_call(request, |params| self.delegate_actions(params)).await
+            }
             "EXPERIMENTAL_genesis_config" => {
                 process_method_call(request, |_params: ()| async {
                     Result::<_, std::convert::Infallible>::Ok(&self.genesis_config)
@@ -429,21 +507,40 @@ impl JsonRpcHandler {
             "EXPERIMENTAL_protocol_config" => {
                 process_method_call(request, |params| self.protocol_config(params)).await
             }
+            "EXPERIMENTAL_protocol_config_diff" => {
+                process_method_call(request, |params| self.protocol_config_diff(params)).await
+            }
             "EXPERIMENTAL_receipt" => {
                 process_method_call(request, |params| self.receipt(params)).await
             }
+            "EXPERIMENTAL_simulate_tx" => {
+                process_method_call(request, |params| self.simulate_tx(params)).await
+            }
             "EXPERIMENTAL_tx_status" => {
                 process_method_call(request, |params| self.tx_status_common(params, true)).await
             }
             "EXPERIMENTAL_validators_ordered" => {
                 process_method_call(request, |params| self.validators_ordered(params)).await
             }
+            "EXPERIMENTAL_next_epoch_validator_schedule" => {
+                process_method_call(request, |params| self.next_epoch_validator_schedule(params))
+                    .await
+            }
+            "EXPERIMENTAL_account_activity" => {
+                process_method_call(request, |params| self.account_activity(params)).await
+            }
+            "EXPERIMENTAL_equivocation_evidence" => {
+                process_method_call(request, |params| self.equivocation_evidence(params)).await
+            }
             "EXPERIMENTAL_maintenance_windows" => {
                 process_method_call(request, |params| self.maintenance_windows(params)).await
             }
             "EXPERIMENTAL_split_storage_info" => {
                 process_method_call(request, |params| self.split_storage_info(params)).await
             }
+            "EXPERIMENTAL_cold_store_copy_control" => {
+                process_method_call(request, |params| self.cold_store_copy_control(params)).await
+            }
             #[cfg(feature = "sandbox")]
             "sandbox_patch_state" => {
                 process_method_call(request, |params| self.sandbox_patch_state(params)).await
@@ -525,18 +622,50 @@ impl JsonRpcHandler {
         self.peer_manager_sender.send_async(msg).await.map_err(RpcFrom::rpc_from)
     }
 
+    /// Checks the rate limit of the signer of the transaction about to be
+    /// sent to the client.  The signer is only charged once the client
+    /// accepts the transaction, so that nobody can use up the limit of
+    /// another account with transactions it didn't sign.
+    fn check_signer_rate_limit(
+        &self,
+        tx: &SignedTransaction,
+        method: &str,
+    ) -> Result<(), near_jsonrpc_primitives::types::transactions::RpcTransactionError> {
+        let signer_id = tx.transaction.signer_id();
+        self.signer_rate_limiter.check(signer_id, Instant::now()).map_err(|retry_after| {
+            metrics::RPC_THROTTLED_TRANSACTIONS_COUNT.with_label_values(&[method]).inc();
+            near_jsonrpc_primitives::types::transactions::RpcTransactionError::SignerRateLimited {
+                signer_id: signer_id.clone(),
+                retry_after_ms: retry_after.as_millis().try_into().unwrap_or(u64::MAX),
+            }
+        })
+    }
+
     async fn send_tx_async(
         &self,
         request_data: near_jsonrpc_primitives::types::transactions::RpcSendTransactionRequest,
-    ) -> CryptoHash {
+        method: &str,
+    ) -> Result<CryptoHash, near_jsonrpc_primitives::types::transactions::RpcTransactionError> {
         let tx = request_data.signed_transaction;
+        self.check_signer_rate_limit(&tx, method)?;
         let hash = tx.get_hash();
-        self.client_sender.send(ProcessTxRequest {
+        let signer_id = tx.transaction.signer_id().clone();
+        let response = self.client_sender.send_async(ProcessTxRequest {
             transaction: tx,
             is_forwarded: false,
             check_only: false, // if we set true here it will not actually send the transaction
         });
-        hash
+        // The hash is returned without waiting for the client to validate the
+        // transaction, so the signer is charged in the background.
+        let signer_rate_limiter = self.signer_rate_limiter.clone();
+        tokio::spawn(async move {
+            if let Ok(ProcessTxResponse::ValidTx | ProcessTxResponse::RequestRouted) =
+                response.await
+            {
+                signer_rate_limiter.charge(&signer_id, Instant::now());
+            }
+        });
+        Ok(hash)
     }
 
     async fn tx_exists(
@@ -684,20 +813,23 @@ impl JsonRpcHandler {
     async fn send_tx(
         &self,
         request_data: near_jsonrpc_primitives::types::transactions::RpcSendTransactionRequest,
+        method: &str,
     ) -> Result<
         near_jsonrpc_primitives::types::transactions::RpcTransactionResponse,
         near_jsonrpc_primitives::types::transactions::RpcTransactionError,
     > {
         if request_data.wait_until == TxExecutionStatus::None {
-            self.send_tx_async(request_data).await;
+            self.send_tx_async(request_data, method).await?;
             return Ok(RpcTransactionResponse {
                 final_execution_outcome: None,
                 final_execution_status: TxExecutionStatus::None,
             });
         }
         let tx = request_data.signed_transaction;
+        self.check_signer_rate_limit(&tx, method)?;
         match self.send_tx_internal(tx.clone(), false).await? {
             ProcessTxResponse::ValidTx | ProcessTxResponse::RequestRouted => {
+                self.signer_rate_limiter.charge(tx.transaction.signer_id(), Instant::now());
                 self.tx_status_fetch(
                     near_jsonrpc_primitives::types::transactions::TransactionInfo::from_signed_tx(tx.clone()),
                     request_data.wait_until,
@@ -721,10 +853,13 @@ impl JsonRpcHandler {
         near_jsonrpc_primitives::types::transactions::RpcTransactionResponse,
         near_jsonrpc_primitives::types::transactions::RpcTransactionError,
     > {
-        self.send_tx(RpcSendTransactionRequest {
-            signed_transaction: request_data.signed_transaction,
-            wait_until: TxExecutionStatus::ExecutedOptimistic,
-        })
+        self.send_tx(
+            RpcSendTransactionRequest {
+                signed_transaction: request_data.signed_transaction,
+                wait_until: TxExecutionStatus::ExecutedOptimistic,
+            },
+            "broadcast_tx_commit",
+        )
         .await
     }
 
@@ -797,6 +932,20 @@ impl JsonRpcHandler {
                     "/debug/api/requested_state_parts" => {
                         self.client_send(DebugStatus::RequestedStateParts).await?.rpc_into()
                     }
+                    "/debug/api/congestion" => {
+                        self.client_send(DebugStatus::Congestion).await?.rpc_into()
+                    }
+                    "/debug/api/tx_pool" => self.client_send(DebugStatus::TxPool).await?.rpc_into(),
+                    "/debug/api/chunk_endorsements" => {
+                        self.client_send(DebugStatus::ChunkEndorsements).await?.rpc_into()
+                    }
+                    "/debug/api/store_stats" => {
+                        self.client_send(DebugStatus::StoreStats).await?.rpc_into()
+                    }
+                    "/debug/api/partial_witnesses" => {
+                        self.client_send(DebugStatus::PartialWitnesses).await?.rpc_into()
+                    }
+                    "/debug/api/shards" => self.client_send(DebugStatus::Shards).await?.rpc_into(),
                     "/debug/api/peer_store" => self
                         .peer_manager_send(near_network::debug::GetDebugStatus::PeerStore)
                         .await?
@@ -836,6 +985,24 @@ impl JsonRpcHandler {
         }
     }
 
+    pub async fn debug_tx_routing(
+        &self,
+        signer_id: AccountId,
+    ) -> Result<
+        Option<near_jsonrpc_primitives::types::status::RpcDebugStatusResponse>,
+        near_jsonrpc_primitives::types::status::RpcStatusError,
+    > {
+        if self.enable_debug_rpc {
+            let debug_status =
+                self.client_send(DebugStatus::TxRouting(signer_id)).await?.rpc_into();
+            Ok(Some(near_jsonrpc_primitives::types::status::RpcDebugStatusResponse {
+                status_response: debug_status,
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
     pub async fn debug_block_status(
         &self,
         starting_height: Option<BlockHeight>,
@@ -866,6 +1033,86 @@ impl JsonRpcHandler {
         Ok(RpcProtocolConfigResponse { config_view })
     }
 
+    /// Returns the differences of the runtime parameters and the protocol
+    /// features between two protocol versions, to prepare for an upgrade.
+    pub async fn protocol_config_diff(
+        &self,
+        request_data: near_jsonrpc_primitives::types::config::RpcProtocolConfigDiffRequest,
+    ) -> Result<RpcProtocolConfigDiffResponse, RpcProtocolConfigDiffError> {
+        let near_jsonrpc_primitives::types::config::RpcProtocolConfigDiffRequest {
+            from_protocol_version,
+            to_protocol_version,
+        } = request_data;
+        for protocol_version in [from_protocol_version, to_protocol_version] {
+            if protocol_version > PROTOCOL_VERSION {
+                return Err(RpcProtocolConfigDiffError::UnsupportedProtocolVersion {
+                    protocol_version,
+                    latest_protocol_version: PROTOCOL_VERSION,
+                });
+            }
+        }
+        Ok(RpcProtocolConfigDiffResponse {
+            from_protocol_version,
+            to_protocol_version,
+            parameters: parameters_diff(from_protocol_version, to_protocol_version),
+            features: ProtocolFeature::enabled_between(from_protocol_version, to_protocol_version)
+                .map(|feature| format!("{feature:?}"))
+                .collect(),
+        })
+    }
+
+    async fn congestion_info(
+        &self,
+        request_data: near_jsonrpc_primitives::types::congestion::RpcCongestionInfoRequest,
+    ) -> Result<
+        near_jsonrpc_primitives::types::congestion::RpcCongestionInfoResponse,
+        near_jsonrpc_primitives::types::congestion::RpcCongestionInfoError,
+    > {
+        let congestion_info =
+            self.view_client_send(GetCongestionInfo(request_data.block_reference)).await?;
+        Ok(near_jsonrpc_primitives::types::congestion::RpcCongestionInfoResponse {
+            congestion_info,
+        })
+    }
+
+    async fn delegate_actions(
+        &self,
+        request_data: near_jsonrpc_primitives::types::delegate_actions::RpcDelegateActionsRequest,
+    ) -> Result<
+        near_jsonrpc_primitives::types::delegate_actions::RpcDelegateActionsResponse,
+        near_jsonrpc_primitives::types::delegate_actions::RpcDelegateActionsError,
+    > {
+        let delegate_actions = self
+            .view_client_send(GetDelegateActions {
+                block_reference: request_data.block_reference,
+                relayer_id: request_data.relayer_id,
+                sender_id: request_data.sender_id,
+            })
+            .await?;
+        Ok(near_jsonrpc_primitives::types::delegate_actions::RpcDelegateActionsResponse {
+            delegate_actions,
+        })
+    }
+
+    async fn simulate_tx(
+        &self,
+        request_data: near_jsonrpc_primitives::types::simulation::RpcSimulateTransactionRequest,
+    ) -> Result<
+        near_jsonrpc_primitives::types::simulation::RpcSimulateTransactionResponse,
+        near_jsonrpc_primitives::types::simulation::RpcSimulateTransactionError,
+    > {
+        let final_execution_outcome = self
+            .view_client_send(SimulateTransaction {
+                transaction: request_data.signed_transaction,
+                verify_signature: !request_data.skip_signature_verification,
+                state_overrides: request_data.state_overrides,
+            })
+            .await?;
+        Ok(near_jsonrpc_primitives::types::simulation::RpcSimulateTransactionResponse {
+            final_execution_outcome,
+        })
+    }
+
     async fn query(
         &self,
         request_data: near_jsonrpc_primitives::types::query::RpcQueryRequest,
@@ -1097,6 +1344,76 @@ impl JsonRpcHandler {
         Ok(validators)
     }
 
+    /// Returns the block and chunk producers of the heights of the next epoch,
+    /// optionally only those at which the given account is on duty.
+    async fn next_epoch_validator_schedule(
+        &self,
+        request: near_jsonrpc_primitives::types::validator::RpcNextEpochValidatorScheduleRequest,
+    ) -> Result<
+        near_jsonrpc_primitives::types::validator::RpcNextEpochValidatorScheduleResponse,
+        near_jsonrpc_primitives::types::validator::RpcValidatorError,
+    > {
+        let near_jsonrpc_primitives::types::validator::RpcNextEpochValidatorScheduleRequest {
+            block_id,
+            account_id,
+        } = request;
+        let schedule =
+            self.view_client_send(GetNextEpochValidatorSchedule { block_id, account_id }).await?;
+        Ok(schedule)
+    }
+
+    /// Returns a page of the transactions and receipts involving the account,
+    /// from the account index kept by the node.
+    async fn account_activity(
+        &self,
+        request: near_jsonrpc_primitives::types::account_activity::RpcAccountActivityRequest,
+    ) -> Result<
+        near_jsonrpc_primitives::types::account_activity::RpcAccountActivityResponse,
+        near_jsonrpc_primitives::types::account_activity::RpcAccountActivityError,
+    > {
+        let near_jsonrpc_primitives::types::account_activity::RpcAccountActivityRequest {
+            account_id,
+            cursor,
+            limit,
+        } = request;
+        let response = self
+            .view_client_send(GetAccountActivity {
+                account_id,
+                after: cursor.map(|cursor| (cursor.block_height, cursor.id)),
+                limit,
+            })
+            .await?;
+        let next_cursor = response.entries.last().filter(|_| response.has_more).map(|last| {
+            near_jsonrpc_primitives::types::account_activity::AccountActivityCursor {
+                block_height: last.block_height,
+                id: last.id,
+            }
+        });
+        let entries = response.entries;
+        Ok(near_jsonrpc_primitives::types::account_activity::RpcAccountActivityResponse {
+            entries,
+            next_cursor,
+        })
+    }
+
+    /// Returns the evidence of validators signing conflicting blocks or
+    /// approvals recorded by the node.
+    async fn equivocation_evidence(
+        &self,
+        request: near_jsonrpc_primitives::types::equivocation::RpcEquivocationEvidenceRequest,
+    ) -> Result<
+        near_jsonrpc_primitives::types::equivocation::RpcEquivocationEvidenceResponse,
+        near_jsonrpc_primitives::types::equivocation::RpcEquivocationEvidenceError,
+    > {
+        let near_jsonrpc_primitives::types::equivocation::RpcEquivocationEvidenceRequest {
+            from_height,
+        } = request;
+        let evidence = self.view_client_send(GetEquivocationEvidence { from_height }).await?;
+        Ok(near_jsonrpc_primitives::types::equivocation::RpcEquivocationEvidenceResponse {
+            evidence,
+        })
+    }
+
     /// If experimental_debug_pages_src_path config is set, reads the html file from that
     /// directory. Otherwise, returns None.
     fn read_html_file_override(&self, html_file: &'static str) -> Option<String> {
@@ -1145,6 +1462,35 @@ impl JsonRpcHandler {
         let split_storage = self.view_client_send(GetSplitStorageInfo {}).await?;
         Ok(RpcSplitStorageInfoResponse { result: split_storage })
     }
+
+    /// Pauses, resumes or limits the rate of copying blocks to the cold store.
+    /// Only allowed when the debug RPC is enabled, because it affects how the
+    /// node operates.
+    pub async fn cold_store_copy_control(
+        &self,
+        request_data: near_jsonrpc_primitives::types::split_storage::RpcColdStoreCopyControlRequest,
+    ) -> Result<
+        near_jsonrpc_primitives::types::split_storage::RpcColdStoreCopyControlResponse,
+        near_jsonrpc_primitives::types::split_storage::RpcColdStoreCopyControlError,
+    > {
+        use near_jsonrpc_primitives::types::split_storage::{
+            RpcColdStoreCopyControlError, RpcColdStoreCopyControlRequest,
+            RpcColdStoreCopyControlResponse,
+        };
+
+        if !self.enable_debug_rpc {
+            return Err(RpcColdStoreCopyControlError::DebugRpcDisabled);
+        }
+        let msg = match request_data {
+            RpcColdStoreCopyControlRequest::Pause => ControlColdStoreCopy::Pause,
+            RpcColdStoreCopyControlRequest::Resume => ControlColdStoreCopy::Resume,
+            RpcColdStoreCopyControlRequest::SetRateLimit { max_blocks_per_second } => {
+                ControlColdStoreCopy::SetMaxBlocksPerSecond(max_blocks_per_second)
+            }
+        };
+        let status = self.view_client_send(msg).await?;
+        Ok(RpcColdStoreCopyControlResponse { result: status })
+    }
 }
 
 #[cfg(feature = "sandbox")]
@@ -1157,9 +1503,10 @@ impl JsonRpcHandler {
         near_jsonrpc_primitives::types::sandbox::RpcSandboxPatchStateError,
     > {
         self.client_sender
-            .send_async(near_client_primitives::types::SandboxMessage::SandboxPatchState(
-                patch_state_request.records,
-            ))
+            .send_async(near_client_primitives::types::SandboxMessage::SandboxPatchState {
+                records: patch_state_request.records,
+                access_key_patches: patch_state_request.access_key_patches,
+            })
             .await
             .map_err(RpcFrom::rpc_from)?;
 
@@ -1196,9 +1543,11 @@ impl JsonRpcHandler {
         use near_client_primitives::types::SandboxResponse;
 
         self.client_sender
-            .send_async(near_client_primitives::types::SandboxMessage::SandboxFastForward(
-                fast_forward_request.delta_height,
-            ))
+            .send_async(near_client_primitives::types::SandboxMessage::SandboxFastForward {
+                delta_height: fast_forward_request.delta_height,
+                skip_chunks_shard_ids: fast_forward_request.skip_chunks_shard_ids,
+                skip_chunks_delta_height: fast_forward_request.skip_chunks_delta_height,
+            })
             .await
             .map_err(RpcFrom::rpc_from)?;
 
@@ -1317,9 +1666,49 @@ impl JsonRpcHandler {
 }
 
 fn rpc_handler(
+    req: HttpRequest,
     message: web::Json<Message>,
     handler: web::Data<JsonRpcHandler>,
+    rate_limiter: web::Data<RateLimiter>,
+    authenticator: web::Data<Authenticator>,
 ) -> impl Future<Output = Result<HttpResponse, HttpError>> {
+    if let Message::Request(request) = &message.0 {
+        let origin = req.headers().get(header::ORIGIN).and_then(|origin| origin.to_str().ok());
+        if let Some((origin, methods)) =
+            origin.and_then(|origin| handler.cors_allowed_methods.get_key_value(origin))
+        {
+            if !methods.contains(&request.method) {
+                let error =
+                    RpcError::method_not_allowed_for_origin(request.method.clone(), origin.clone());
+                let response = HttpResponse::Forbidden()
+                    .json(&Message::response(request.id.clone(), Err(error)));
+                return futures::future::ready(Ok(response)).boxed();
+            }
+        }
+        if authenticator.method_requires_auth(&request.method) {
+            if let Err(err) = authenticator.authenticate(req.headers(), unix_timestamp()) {
+                let error = RpcError::unauthorized(request.method.clone(), err.to_string());
+                let response = HttpResponse::Unauthorized()
+                    .insert_header((header::WWW_AUTHENTICATE, "Bearer"))
+                    .json(&Message::response(request.id.clone(), Err(error)));
+                return futures::future::ready(Ok(response)).boxed();
+            }
+        }
+    }
+    if let (Message::Request(request), Some(peer_addr)) = (&message.0, req.peer_addr()) {
+        if let Err(retry_after) =
+            rate_limiter.check(&request.method, peer_addr.ip(), Instant::now())
+        {
+            metrics::RPC_RATE_LIMITED_COUNT.with_label_values(&[&request.method]).inc();
+            let error = RpcError::rate_limited(request.method.clone(), retry_after);
+            let retry_after_secs =
+                retry_after.as_secs().saturating_add(u64::from(retry_after.subsec_nanos() > 0));
+            let response = HttpResponse::TooManyRequests()
+                .insert_header((header::RETRY_AFTER, retry_after_secs.to_string()))
+                .json(&Message::response(request.id.clone(), Err(error)));
+            return futures::future::ready(Ok(response)).boxed();
+        }
+    }
     let response = async move {
         let message = handler.process(message.0).await?;
         Ok(HttpResponse::Ok().json(&message))
@@ -1327,6 +1716,10 @@ fn rpc_handler(
     response.boxed()
 }
 
+fn unix_timestamp() -> u64 {
+    SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).map_or(0, |since| since.as_secs())
+}
+
 fn status_handler(
     handler: web::Data<JsonRpcHandler>,
 ) -> impl Future<Output = Result<HttpResponse, HttpError>> {
@@ -1370,6 +1763,17 @@ async fn handle_entity_debug(
     }
 }
 
+async fn debug_tx_routing_handler(
+    path: web::Path<AccountId>,
+    handler: web::Data<JsonRpcHandler>,
+) -> Result<HttpResponse, HttpError> {
+    match handler.debug_tx_routing(path.into_inner()).await {
+        Ok(Some(value)) => Ok(HttpResponse::Ok().json(&value)),
+        Ok(None) => Ok(HttpResponse::MethodNotAllowed().finish()),
+        Err(_) => Ok(HttpResponse::ServiceUnavailable().finish()),
+    }
+}
+
 async fn debug_block_status_handler(
     path: web::Path<u64>,
     handler: web::Data<JsonRpcHandler>,
@@ -1410,7 +1814,7 @@ pub async fn prometheus_handler() -> Result<HttpResponse, HttpError> {
 
     let mut buffer = vec![];
     let encoder = TextEncoder::new();
-    encoder.encode(&prometheus::gather(), &mut buffer).unwrap();
+    encoder.encode(&near_o11y::metrics::gather(), &mut buffer).unwrap();
 
     match String::from_utf8(buffer) {
         Ok(text) => Ok(HttpResponse::Ok().body(text)),
@@ -1418,6 +1822,77 @@ pub async fn prometheus_handler() -> Result<HttpResponse, HttpError> {
     }
 }
 
+async fn debug_profile_handler(
+    query: web::Query<profiler::ProfileRequest>,
+    handler: web::Data<JsonRpcHandler>,
+) -> Result<HttpResponse, HttpError> {
+    if !handler.enable_debug_rpc {
+        return Ok(HttpResponse::MethodNotAllowed().finish());
+    }
+    let request = query.into_inner();
+    let format = request.format;
+    match tokio::task::spawn_blocking(move || profiler::profile_chunk_application(&request)).await {
+        Ok(Ok(body)) => Ok(HttpResponse::Ok().content_type(format.content_type()).body(body)),
+        Ok(Err(err @ profiler::ProfileError::AlreadyRunning)) => {
+            Ok(HttpResponse::Conflict().body(err.to_string()))
+        }
+        Ok(Err(err)) => Ok(HttpResponse::InternalServerError().body(err.to_string())),
+        Err(_) => Ok(HttpResponse::ServiceUnavailable().finish()),
+    }
+}
+
+async fn debug_jemalloc_stats_handler(
+    handler: web::Data<JsonRpcHandler>,
+) -> Result<HttpResponse, HttpError> {
+    if !handler.enable_debug_rpc {
+        return Ok(HttpResponse::MethodNotAllowed().finish());
+    }
+    match near_o11y::jemalloc::JemallocStats::read() {
+        Ok(stats) => Ok(HttpResponse::Ok().json(&stats)),
+        Err(err) => Ok(HttpResponse::InternalServerError().body(err.to_string())),
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct HeapProfilingRequest {
+    active: bool,
+}
+
+/// Starts or stops sampling the allocations for the heap profile.
+async fn debug_heap_profiling_handler(
+    req: web::Json<HeapProfilingRequest>,
+    handler: web::Data<JsonRpcHandler>,
+) -> Result<HttpResponse, HttpError> {
+    if !handler.enable_debug_rpc {
+        return Ok(HttpResponse::MethodNotAllowed().finish());
+    }
+    match near_o11y::jemalloc::set_heap_profiling_active(req.active) {
+        Ok(()) => Ok(HttpResponse::Ok().finish()),
+        Err(err) => Ok(HttpResponse::InternalServerError().body(err.to_string())),
+    }
+}
+
+/// Returns the heap profile of the allocations sampled since the heap profiling
+/// was started, to be opened with `jeprof`.
+async fn debug_heap_profile_handler(
+    handler: web::Data<JsonRpcHandler>,
+) -> Result<HttpResponse, HttpError> {
+    if !handler.enable_debug_rpc {
+        return Ok(HttpResponse::MethodNotAllowed().finish());
+    }
+    let path = std::env::temp_dir().join(format!("neard-{}.heap", std::process::id()));
+    let profile = near_o11y::jemalloc::dump_heap_profile(&path)
+        .map_err(|err| err.to_string())
+        .and_then(|()| std::fs::read(&path).map_err(|err| err.to_string()));
+    let _ = std::fs::remove_file(&path);
+    match profile {
+        Ok(profile) => {
+            Ok(HttpResponse::Ok().content_type("application/octet-stream").body(profile))
+        }
+        Err(err) => Ok(HttpResponse::InternalServerError().body(err)),
+    }
+}
+
 fn client_config_handler(
     handler: web::Data<JsonRpcHandler>,
 ) -> impl Future<Output = Result<HttpResponse, HttpError>> {
@@ -1451,14 +1926,20 @@ macro_rules! debug_page_string {
     };
 }
 
-#[get("/debug")]
+#[get("")]
 async fn debug_html(
     handler: web::Data<JsonRpcHandler>,
 ) -> actix_web::Result<impl actix_web::Responder> {
     Ok(HttpResponse::Ok().body(debug_page_string!("debug.html", handler)))
 }
 
-#[get("/debug/pages/{page}")]
+/// Shortcut to the shards page, which operators look for often.
+#[get("/shards")]
+async fn debug_shards_html() -> actix_web::Result<impl actix_web::Responder> {
+    Ok(HttpResponse::Found().insert_header((header::LOCATION, "/debug/pages/shards")).finish())
+}
+
+#[get("/pages/{page}")]
 async fn display_debug_html(
     path: web::Path<(String,)>,
     handler: web::Data<JsonRpcHandler>,
@@ -1482,6 +1963,11 @@ async fn display_debug_html(
         "validator" => Some(debug_page_string!("validator.html", handler)),
         "validator.css" => Some(debug_page_string!("validator.css", handler)),
         "split_store" => Some(debug_page_string!("split_store.html", handler)),
+        "congestion" => Some(debug_page_string!("congestion.html", handler)),
+        "tx_pool" => Some(debug_page_string!("tx_pool.html", handler)),
+        "store_stats" => Some(debug_page_string!("store_stats.html", handler)),
+        "partial_witnesses" => Some(debug_page_string!("partial_witnesses.html", handler)),
+        "shards" => Some(debug_page_string!("shards.html", handler)),
         _ => None,
     };
 
@@ -1516,13 +2002,26 @@ pub fn start_http(
     let RpcConfig {
         addr,
         prometheus_addr,
-        cors_allowed_origins,
+        mut cors_allowed_origins,
+        cors_allowed_methods,
+        enable_compression,
         polling_config,
         limits_config,
         enable_debug_rpc,
         experimental_debug_pages_src_path: debug_pages_src_path,
+        auth_config,
     } = config;
     let prometheus_addr = prometheus_addr.filter(|it| it != &addr.to_string());
+    // Shared by all the workers, so that the limits apply to the whole server.
+    let rate_limiter = web::Data::new(RateLimiter::new(limits_config.method_rate_limits.clone()));
+    let signer_rate_limiter = Arc::new(SignerRateLimiter::new(
+        limits_config.transaction_signer_rate_limit,
+        &limits_config.transaction_signer_allowlist,
+    ));
+    let authenticator = web::Data::new(Authenticator::new(auth_config));
+    if cors_allowed_origins != ["*".to_string()] {
+        cors_allowed_origins.extend(cors_allowed_methods.keys().cloned());
+    }
     let cors_allowed_origins_clone = cors_allowed_origins.clone();
     info!(target:"network", "Starting http server at {}", addr);
     let mut servers = Vec::new();
@@ -1534,15 +2033,20 @@ pub fn start_http(
                 view_client_sender: view_client_sender.clone(),
                 peer_manager_sender: peer_manager_sender.clone(),
                 polling_config,
+                cors_allowed_methods: cors_allowed_methods.clone(),
                 genesis_config: genesis_config.clone(),
                 enable_debug_rpc,
                 debug_pages_src_path: debug_pages_src_path.clone().map(Into::into),
                 entity_debug_handler: entity_debug_handler.clone(),
+                signer_rate_limiter: signer_rate_limiter.clone(),
                 #[cfg(feature = "test_features")]
                 gc_sender: gc_sender.clone(),
             }))
+            .app_data(rate_limiter.clone())
+            .app_data(authenticator.clone())
             .app_data(web::JsonConfig::default().limit(limits_config.json_payload_max_size))
             .wrap(middleware::Logger::default())
+            .wrap(middleware::Condition::new(enable_compression, middleware::Compress::default()))
             .service(web::resource("/").route(web::post().to(rpc_handler)))
             .service(
                 web::resource("/status")
@@ -1556,17 +2060,56 @@ pub fn start_http(
             )
             .service(web::resource("/network_info").route(web::get().to(network_info_handler)))
             .service(web::resource("/metrics").route(web::get().to(prometheus_handler)))
-            .service(web::resource("/debug/api/entity").route(web::post().to(handle_entity_debug)))
-            .service(web::resource("/debug/api/{api}").route(web::get().to(debug_handler)))
             .service(
-                web::resource("/debug/api/block_status/{starting_height}")
-                    .route(web::get().to(debug_block_status_handler)),
-            )
-            .service(
-                web::resource("/debug/client_config").route(web::get().to(client_config_handler)),
+                // The token is checked for the whole scope rather than for the
+                // paths starting with /debug, as the routes are matched against
+                // the percent-decoded path.
+                web::scope("/debug")
+                    .wrap_fn({
+                        let authenticator = authenticator.clone();
+                        move |req, srv| {
+                            let authorized = !authenticator.debug_requires_auth()
+                                || authenticator
+                                    .authenticate(req.headers(), unix_timestamp())
+                                    .is_ok();
+                            if authorized {
+                                futures::future::Either::Left(srv.call(req))
+                            } else {
+                                futures::future::Either::Right(futures::future::ready(Err(
+                                    actix_web::error::ErrorUnauthorized("Unauthorized"),
+                                )))
+                            }
+                        }
+                    })
+                    .service(
+                        web::resource("/api/entity").route(web::post().to(handle_entity_debug)),
+                    )
+                    .service(web::resource("/api/{api}").route(web::get().to(debug_handler)))
+                    .service(
+                        web::resource("/api/block_status/{starting_height}")
+                            .route(web::get().to(debug_block_status_handler)),
+                    )
+                    .service(
+                        web::resource("/api/tx_routing/{signer_id}")
+                            .route(web::get().to(debug_tx_routing_handler)),
+                    )
+                    .service(
+                        web::resource("/client_config").route(web::get().to(client_config_handler)),
+                    )
+                    .service(web::resource("/profile").route(web::get().to(debug_profile_handler)))
+                    .service(
+                        web::resource("/jemalloc/stats")
+                            .route(web::get().to(debug_jemalloc_stats_handler)),
+                    )
+                    .service(
+                        web::resource("/jemalloc/heap_profile")
+                            .route(web::get().to(debug_heap_profile_handler))
+                            .route(web::post().to(debug_heap_profiling_handler)),
+                    )
+                    .service(debug_html)
+                    .service(display_debug_html)
+                    .service(debug_shards_html),
             )
-            .service(debug_html)
-            .service(display_debug_html)
     });
 
     match listener.listen(addr.std_listener().unwrap()) {
@@ -55,3 +55,19 @@ pub static RPC_UNREACHABLE_ERROR_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+pub static RPC_THROTTLED_TRANSACTIONS_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    near_o11y::metrics::try_create_int_counter_vec(
+        "near_rpc_throttled_transactions_total",
+        "Total count of transactions rejected by the rate limit of their signer, by method",
+        &["method"],
+    )
+    .unwrap()
+});
+pub static RPC_RATE_LIMITED_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    near_o11y::metrics::try_create_int_counter_vec(
+        "near_rpc_rate_limited_total",
+        "Total count of RPC requests rejected by the rate limits, by method",
+        &["method"],
+    )
+    .unwrap()
+});
@@ -0,0 +1,129 @@
+//! Sampling CPU profiler of the chunk application, controlled through the
+//! debug RPC.
+//!
+//! Attaching an external profiler to a validator is risky, so the node can
+//! profile itself instead: all the threads of the process are sampled for a
+//! bounded time, and only the stacks going through `do_apply_chunks` are kept,
+//! which are the ones of the threads applying the chunks.  Only one profile is
+//! taken at a time.
+use pprof::protos::Message;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// Longest time a profile can be taken for.
+const MAX_DURATION: Duration = Duration::from_secs(60);
+const SAMPLING_FREQUENCY: i32 = 1000;
+/// Frame of all the stacks of the threads applying the chunks.
+const CHUNK_APPLY_FRAME: &str = "do_apply_chunks";
+
+static PROFILING: AtomicBool = AtomicBool::new(false);
+
+fn default_seconds() -> u64 {
+    10
+}
+
+#[derive(serde::Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ProfileFormat {
+    /// SVG flamegraph, to be opened in a browser.
+    #[default]
+    Flamegraph,
+    /// Protobuf profile, to be opened with `go tool pprof`.
+    Pprof,
+}
+
+impl ProfileFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Flamegraph => "image/svg+xml",
+            Self::Pprof => "application/octet-stream",
+        }
+    }
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub(crate) struct ProfileRequest {
+    /// Time to take the profile for, capped at a minute.
+    #[serde(default = "default_seconds")]
+    pub seconds: u64,
+    #[serde(default)]
+    pub format: ProfileFormat,
+}
+
+#[derive(Debug)]
+pub(crate) enum ProfileError {
+    AlreadyRunning,
+    Profiler(pprof::Error),
+}
+
+impl std::fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AlreadyRunning => write!(f, "a profile is already being taken"),
+            Self::Profiler(err) => write!(f, "profiler failed: {err}"),
+        }
+    }
+}
+
+impl From<pprof::Error> for ProfileError {
+    fn from(err: pprof::Error) -> Self {
+        Self::Profiler(err)
+    }
+}
+
+/// Held for as long as a profile is being taken.
+struct ProfilingLock;
+
+impl ProfilingLock {
+    fn acquire() -> Option<Self> {
+        PROFILING.compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire).ok()?;
+        Some(Self)
+    }
+}
+
+impl Drop for ProfilingLock {
+    fn drop(&mut self) {
+        PROFILING.store(false, Ordering::Release);
+    }
+}
+
+/// Profiles the chunk application for the requested time and returns the
+/// profile in the requested format.  Blocks for the whole time.
+pub(crate) fn profile_chunk_application(request: &ProfileRequest) -> Result<Vec<u8>, ProfileError> {
+    let _lock = ProfilingLock::acquire().ok_or(ProfileError::AlreadyRunning)?;
+    let duration = Duration::from_secs(request.seconds).min(MAX_DURATION);
+    tracing::info!(target: "jsonrpc", ?duration, "Profiling the chunk application");
+    let guard = pprof::ProfilerGuardBuilder::default()
+        .frequency(SAMPLING_FREQUENCY)
+        // Unwinding through these libraries can deadlock in the signal handler.
+        .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+        .build()?;
+    std::thread::sleep(duration);
+    let mut report = guard.report().build()?;
+    drop(guard);
+    report.data.retain(|frames, _| {
+        frames.frames.iter().flatten().any(|symbol| symbol.name().contains(CHUNK_APPLY_FRAME))
+    });
+    let mut body = Vec::new();
+    match request.format {
+        ProfileFormat::Flamegraph => report.flamegraph(&mut body)?,
+        ProfileFormat::Pprof => {
+            // Encoding into a vector can't run out of space.
+            report.pprof()?.encode(&mut body).unwrap();
+        }
+    }
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profiling_lock() {
+        let lock = ProfilingLock::acquire().unwrap();
+        assert!(ProfilingLock::acquire().is_none());
+        drop(lock);
+        assert!(ProfilingLock::acquire().is_some());
+    }
+}
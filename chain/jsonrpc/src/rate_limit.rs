@@ -0,0 +1,351 @@
+//! Rate limiting of the JSON RPC methods and of the transactions sent through
+//! them.
+//!
+//! Each configured method gets a token bucket per client IP: a request takes
+//! a token, and the tokens are refilled at a constant rate up to the burst
+//! size.  The IPv6 clients get a bucket per /64 network, as a single host
+//! usually gets a whole /64.  Methods without a configured limit aren't
+//! limited, except for the expensive ones with a default limit.  Similarly,
+//! the transactions get a token bucket per signer account, so that a single
+//! account can't fill the transaction pool.  As anyone can claim to be the
+//! signer of a transaction, a token is only taken for the transactions
+//! accepted by the client.
+use lru::LruCache;
+use near_primitives::types::AccountId;
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, Ipv6Addr};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Maximum number of tracked client buckets.  Above it, the buckets of the
+/// clients which didn't call a method for the longest time are forgotten.
+const MAX_TRACKED_CLIENT_BUCKETS: usize = 100_000;
+
+/// Maximum number of tracked signer buckets.  Above it, the buckets of the
+/// signers which didn't send a transaction for the longest time are
+/// forgotten.
+const MAX_TRACKED_SIGNER_BUCKETS: usize = 100_000;
+
+/// Limits of the methods which are limited unless configured otherwise.
+/// `EXPERIMENTAL_simulate_tx` executes whole receipt trees, which can be paid
+/// for with an overridden balance.
+const DEFAULT_METHOD_RATE_LIMITS: &[(&str, RpcRateLimit)] =
+    &[("EXPERIMENTAL_simulate_tx", RpcRateLimit { requests_per_second: 1.0, burst: 10 })];
+
+/// The lowest rate of a limit, so that the time to wait for the next token is
+/// at most 1000 seconds.
+const MIN_REQUESTS_PER_SECOND: f64 = 0.001;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct RpcRateLimit {
+    /// Rate at which a client can keep calling the method.
+    pub requests_per_second: f64,
+    /// Number of calls a client can make at once after not calling the
+    /// method for a while.
+    pub burst: u32,
+}
+
+impl RpcRateLimit {
+    /// Checks that the limit lets the clients make calls at all.
+    pub fn validate(&self) -> Result<(), String> {
+        // Also rejects NaN.
+        if !(MIN_REQUESTS_PER_SECOND..=f64::MAX).contains(&self.requests_per_second) {
+            return Err(format!(
+                "requests_per_second must be a finite number of at least {}, got {}",
+                MIN_REQUESTS_PER_SECOND, self.requests_per_second
+            ));
+        }
+        if self.burst == 0 {
+            return Err("burst must be greater than 0".to_owned());
+        }
+        Ok(())
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    /// Refills the bucket with the tokens for the time since the last update.
+    fn refill(&mut self, limit: &RpcRateLimit, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * limit.requests_per_second).min(limit.burst as f64);
+        self.updated = now;
+    }
+
+    /// Checks that there is a token left after refilling the bucket.  Returns
+    /// how long to wait for the next token if there is none.
+    fn check(&mut self, limit: &RpcRateLimit, now: Instant) -> Result<(), Duration> {
+        self.refill(limit, now);
+        if self.tokens >= 1.0 {
+            return Ok(());
+        }
+        // The limits are validated, but never panic on an invalid one.
+        let wait = (1.0 - self.tokens) / limit.requests_per_second;
+        Err(Duration::try_from_secs_f64(wait).unwrap_or(Duration::MAX))
+    }
+
+    /// Takes a token after refilling the bucket.  Returns how long to wait for
+    /// the next token if there is none left.
+    fn take(&mut self, limit: &RpcRateLimit, now: Instant) -> Result<(), Duration> {
+        self.check(limit, now)?;
+        self.tokens -= 1.0;
+        Ok(())
+    }
+}
+
+/// Returns the client the bucket of a call from the IP belongs to.  The IPv6
+/// addresses are truncated to their /64 network, so that a host can't get a
+/// new bucket by using another address of its network.
+fn client_of(ip: IpAddr) -> IpAddr {
+    match ip.to_canonical() {
+        IpAddr::V6(ip) => IpAddr::V6(Ipv6Addr::from(u128::from(ip) & !u128::from(u64::MAX))),
+        ip => ip,
+    }
+}
+
+pub(crate) struct RateLimiter {
+    limits: HashMap<String, RpcRateLimit>,
+    buckets: Mutex<LruCache<(String, IpAddr), Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(mut limits: HashMap<String, RpcRateLimit>) -> Self {
+        for (method, limit) in DEFAULT_METHOD_RATE_LIMITS {
+            limits.entry(method.to_string()).or_insert(*limit);
+        }
+        Self { limits, buckets: Mutex::new(LruCache::new(MAX_TRACKED_CLIENT_BUCKETS)) }
+    }
+
+    /// Takes a token for the call of the method by the client.  Returns how
+    /// long the client has to wait before calling the method again if there
+    /// is no token left.
+    pub fn check(&self, method: &str, client: IpAddr, now: Instant) -> Result<(), Duration> {
+        let Some(limit) = self.limits.get(method) else {
+            return Ok(());
+        };
+        let key = (method.to_owned(), client_of(client));
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.get_mut(&key).is_none() {
+            buckets.put(key.clone(), Bucket { tokens: limit.burst as f64, updated: now });
+        }
+        buckets.get_mut(&key).unwrap().take(limit, now)
+    }
+}
+
+pub(crate) struct SignerRateLimiter {
+    limit: Option<RpcRateLimit>,
+    allowlist: HashSet<AccountId>,
+    buckets: Mutex<LruCache<AccountId, Bucket>>,
+}
+
+impl SignerRateLimiter {
+    pub fn new(limit: Option<RpcRateLimit>, allowlist: &[AccountId]) -> Self {
+        let allowlist = allowlist.iter().cloned().collect();
+        Self { limit, allowlist, buckets: Mutex::new(LruCache::new(MAX_TRACKED_SIGNER_BUCKETS)) }
+    }
+
+    fn limit_of(&self, signer_id: &AccountId) -> Option<&RpcRateLimit> {
+        self.limit.as_ref().filter(|_| !self.allowlist.contains(signer_id))
+    }
+
+    /// Checks that the account can send another transaction, without taking
+    /// a token.  Returns how long the account has to wait before sending
+    /// another transaction if there is no token left.
+    pub fn check(&self, signer_id: &AccountId, now: Instant) -> Result<(), Duration> {
+        let Some(limit) = self.limit_of(signer_id) else {
+            return Ok(());
+        };
+        match self.buckets.lock().unwrap().get_mut(signer_id) {
+            Some(bucket) => bucket.check(limit, now),
+            // A new bucket is full.
+            None => Ok(()),
+        }
+    }
+
+    /// Takes a token for a transaction signed by the account and accepted by
+    /// the client.  The transactions accepted at once are all charged, even
+    /// if there are fewer tokens left, so that the account waits for them.
+    pub fn charge(&self, signer_id: &AccountId, now: Instant) {
+        let Some(limit) = self.limit_of(signer_id) else {
+            return;
+        };
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.get_mut(signer_id).is_none() {
+            buckets.put(signer_id.clone(), Bucket { tokens: limit.burst as f64, updated: now });
+        }
+        let bucket = buckets.get_mut(signer_id).unwrap();
+        bucket.refill(limit, now);
+        bucket.tokens -= 1.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter() {
+        let limit = RpcRateLimit { requests_per_second: 2.0, burst: 3 };
+        let limiter = RateLimiter::new(HashMap::from([("view_state".to_owned(), limit)]));
+        let client: IpAddr = "1.2.3.4".parse().unwrap();
+        let other_client: IpAddr = "5.6.7.8".parse().unwrap();
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert_eq!(limiter.check("view_state", client, now), Ok(()));
+        }
+        assert_eq!(limiter.check("view_state", client, now), Err(Duration::from_millis(500)));
+        // Other clients and methods have their own limits.
+        assert_eq!(limiter.check("view_state", other_client, now), Ok(()));
+        for _ in 0..10 {
+            assert_eq!(limiter.check("block", client, now), Ok(()));
+        }
+
+        let later = now + Duration::from_millis(250);
+        assert_eq!(limiter.check("view_state", client, later), Err(Duration::from_millis(250)));
+        let later = now + Duration::from_millis(500);
+        assert_eq!(limiter.check("view_state", client, later), Ok(()));
+        assert!(limiter.check("view_state", client, later).is_err());
+        // Refilled only up to the burst.
+        let later = now + Duration::from_secs(60);
+        for _ in 0..3 {
+            assert_eq!(limiter.check("view_state", client, later), Ok(()));
+        }
+        assert!(limiter.check("view_state", client, later).is_err());
+    }
+
+    #[test]
+    fn test_rate_limiter_default_limits() {
+        let client: IpAddr = "1.2.3.4".parse().unwrap();
+        let now = Instant::now();
+        let limiter = RateLimiter::new(HashMap::new());
+        for _ in 0..10 {
+            assert_eq!(limiter.check("EXPERIMENTAL_simulate_tx", client, now), Ok(()));
+        }
+        assert_eq!(
+            limiter.check("EXPERIMENTAL_simulate_tx", client, now),
+            Err(Duration::from_secs(1))
+        );
+
+        // The default limit can be configured.
+        let limit = RpcRateLimit { requests_per_second: 1.0, burst: 20 };
+        let limiter =
+            RateLimiter::new(HashMap::from([("EXPERIMENTAL_simulate_tx".to_owned(), limit)]));
+        for _ in 0..20 {
+            assert_eq!(limiter.check("EXPERIMENTAL_simulate_tx", client, now), Ok(()));
+        }
+        assert!(limiter.check("EXPERIMENTAL_simulate_tx", client, now).is_err());
+    }
+
+    #[test]
+    fn test_rate_limiter_ipv6_network() {
+        let limit = RpcRateLimit { requests_per_second: 1.0, burst: 1 };
+        let limiter = RateLimiter::new(HashMap::from([("view_state".to_owned(), limit)]));
+        let now = Instant::now();
+
+        assert_eq!(limiter.check("view_state", "2001:db8::1".parse().unwrap(), now), Ok(()));
+        // Other addresses of the /64 network share the bucket.
+        assert!(limiter.check("view_state", "2001:db8::2:3".parse().unwrap(), now).is_err());
+        assert_eq!(limiter.check("view_state", "2001:db8:0:1::1".parse().unwrap(), now), Ok(()));
+        // The IPv4-mapped addresses share the bucket of the IPv4 address.
+        assert_eq!(limiter.check("view_state", "1.2.3.4".parse().unwrap(), now), Ok(()));
+        assert!(limiter.check("view_state", "::ffff:1.2.3.4".parse().unwrap(), now).is_err());
+    }
+
+    #[test]
+    fn test_rate_limiter_is_bounded() {
+        let limit = RpcRateLimit { requests_per_second: 1.0, burst: 1 };
+        let limiter = RateLimiter::new(HashMap::from([("view_state".to_owned(), limit)]));
+        let now = Instant::now();
+        let client = |i: u32| IpAddr::from(i.to_be_bytes());
+
+        assert_eq!(limiter.check("view_state", client(0), now), Ok(()));
+        for i in 1..=MAX_TRACKED_CLIENT_BUCKETS as u32 {
+            assert_eq!(limiter.check("view_state", client(i), now), Ok(()));
+            // The client calling the method keeps its bucket.
+            if i % 1000 == 0 {
+                assert!(limiter.check("view_state", client(0), now).is_err());
+            }
+        }
+        assert_eq!(limiter.buckets.lock().unwrap().len(), MAX_TRACKED_CLIENT_BUCKETS);
+        // The bucket of the least recently seen client is forgotten.
+        assert_eq!(limiter.check("view_state", client(1), now), Ok(()));
+    }
+
+    #[test]
+    fn test_rate_limit_validate() {
+        assert!(RpcRateLimit { requests_per_second: 0.5, burst: 1 }.validate().is_ok());
+        for requests_per_second in [0.0, -1.0, 1e-9, f64::NAN, f64::INFINITY] {
+            assert!(RpcRateLimit { requests_per_second, burst: 1 }.validate().is_err());
+        }
+        assert!(RpcRateLimit { requests_per_second: 1.0, burst: 0 }.validate().is_err());
+        // Invalid limits don't panic.
+        for requests_per_second in [0.0, 1e-300, f64::NAN] {
+            let limit = RpcRateLimit { requests_per_second, burst: 0 };
+            let mut bucket = Bucket { tokens: 0.0, updated: Instant::now() };
+            assert_eq!(bucket.take(&limit, Instant::now()), Err(Duration::MAX));
+        }
+    }
+
+    #[test]
+    fn test_signer_rate_limiter() {
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let bob: AccountId = "bob.near".parse().unwrap();
+        let relayer: AccountId = "relayer.near".parse().unwrap();
+        let limit = RpcRateLimit { requests_per_second: 1.0, burst: 2 };
+        let limiter = SignerRateLimiter::new(Some(limit), &[relayer.clone()]);
+        let now = Instant::now();
+
+        // Only the accepted transactions are charged.
+        for _ in 0..10 {
+            assert_eq!(limiter.check(&alice, now), Ok(()));
+        }
+        for _ in 0..2 {
+            assert_eq!(limiter.check(&alice, now), Ok(()));
+            limiter.charge(&alice, now);
+        }
+        assert_eq!(limiter.check(&alice, now), Err(Duration::from_secs(1)));
+        // Other accounts have their own limits, the allowlisted ones none.
+        assert_eq!(limiter.check(&bob, now), Ok(()));
+        for _ in 0..10 {
+            limiter.charge(&relayer, now);
+        }
+        assert_eq!(limiter.check(&relayer, now), Ok(()));
+        assert_eq!(limiter.check(&alice, now + Duration::from_secs(1)), Ok(()));
+
+        // The transactions accepted at once are all charged.
+        limiter.charge(&bob, now);
+        limiter.charge(&bob, now);
+        limiter.charge(&bob, now);
+        assert_eq!(limiter.check(&bob, now), Err(Duration::from_secs(2)));
+
+        let limiter = SignerRateLimiter::new(None, &[]);
+        for _ in 0..10 {
+            limiter.charge(&alice, now);
+        }
+        assert_eq!(limiter.check(&alice, now), Ok(()));
+    }
+
+    #[test]
+    fn test_signer_rate_limiter_is_bounded() {
+        let limit = RpcRateLimit { requests_per_second: 1.0, burst: 1 };
+        let limiter = SignerRateLimiter::new(Some(limit), &[]);
+        let now = Instant::now();
+        let signer = |i: usize| -> AccountId { format!("signer{i}.near").parse().unwrap() };
+
+        limiter.charge(&signer(0), now);
+        for i in 1..=MAX_TRACKED_SIGNER_BUCKETS {
+            limiter.charge(&signer(i), now);
+            // The signer sending transactions keeps its bucket.
+            if i % 1000 == 0 {
+                assert!(limiter.check(&signer(0), now).is_err());
+            }
+        }
+        assert_eq!(limiter.buckets.lock().unwrap().len(), MAX_TRACKED_SIGNER_BUCKETS);
+        // The bucket of the least recently seen signer is forgotten.
+        assert_eq!(limiter.check(&signer(1), now), Ok(()));
+    }
+}
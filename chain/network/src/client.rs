@@ -3,6 +3,7 @@ use crate::types::{NetworkInfo, ReasonForBan};
 use near_async::messaging::AsyncSender;
 use near_primitives::block::{Approval, Block, BlockHeader};
 use near_primitives::challenge::Challenge;
+use near_primitives::equivocation::EquivocationEvidence;
 use near_primitives::errors::InvalidTxError;
 use near_primitives::hash::CryptoHash;
 use near_primitives::network::{AnnounceAccount, PeerId};
@@ -82,6 +83,10 @@ pub struct SetNetworkInfo(pub NetworkInfo);
 #[rtype(result = "()")]
 pub struct RecvChallenge(pub Challenge);
 
+#[derive(actix::Message, Debug, Clone, PartialEq, Eq)]
+#[rtype(result = "()")]
+pub struct RecvEquivocationEvidence(pub EquivocationEvidence);
+
 #[derive(actix::Message, Debug, Clone, PartialEq, Eq)]
 #[rtype(result = "ProcessTxResponse")]
 pub struct ProcessTxRequest {
@@ -134,6 +139,7 @@ pub struct ClientSenderForNetwork {
     pub block: AsyncSender<BlockResponse, ()>,
     pub block_headers: AsyncSender<BlockHeadersResponse, Result<(), ReasonForBan>>,
     pub challenge: AsyncSender<RecvChallenge, ()>,
+    pub equivocation_evidence: AsyncSender<RecvEquivocationEvidence, ()>,
     pub network_info: AsyncSender<SetNetworkInfo, ()>,
     pub announce_account:
         AsyncSender<AnnounceAccountRequest, Result<Vec<AnnounceAccount>, ReasonForBan>>,
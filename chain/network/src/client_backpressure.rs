@@ -0,0 +1,196 @@
+//! Backpressure between the peers and the client.
+//!
+//! The messages the peers send to the client are forwarded to it
+//! asynchronously, so when the client is slower than the network the client
+//! mailbox grows without a bound. Instead, the number of the messages of every
+//! class in flight to the client (sent, but not processed yet) is bounded.
+//! Once a class is at its limit, a new message of the class is either:
+//! * dropped, if whoever sent it can just as well send it again or ask
+//!   another peer (transactions, requests), or
+//! * deferred, pausing the reads from the connection of the peer until the
+//!   client is done with one of the class, so that the TCP flow control slows
+//!   the peer down (blocks, approvals, endorsements). The peer actor keeps
+//!   sending to the peer in the meantime.
+use crate::network_protocol::{PeerMessage, RoutedMessageBody};
+use crate::stats::metrics;
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::AsRefStr, strum::EnumIter)]
+pub(crate) enum MessageClass {
+    Block,
+    BlockHeaders,
+    Approval,
+    ChunkEndorsement,
+    StateResponse,
+    Challenge,
+    Transaction,
+    Request,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Policy {
+    Drop,
+    Defer,
+}
+
+impl MessageClass {
+    /// Returns the class of the message, if it is sent to the client.
+    pub fn of(msg: &PeerMessage) -> Option<Self> {
+        Some(match msg {
+            PeerMessage::Block(_) => Self::Block,
+            PeerMessage::BlockHeaders(_) => Self::BlockHeaders,
+            PeerMessage::Transaction(_) => Self::Transaction,
+            PeerMessage::Challenge(_) | PeerMessage::EquivocationEvidence(_) => Self::Challenge,
+            PeerMessage::VersionedStateResponse(_) => Self::StateResponse,
+            PeerMessage::BlockRequest(_)
+            | PeerMessage::BlockHeadersRequest(_)
+            | PeerMessage::StateRequestHeader(..)
+            | PeerMessage::StateRequestPart(..) => Self::Request,
+            PeerMessage::Routed(msg) => match &msg.msg.body {
+                RoutedMessageBody::BlockApproval(_) => Self::Approval,
+                RoutedMessageBody::ChunkEndorsement(_) => Self::ChunkEndorsement,
+                RoutedMessageBody::StateResponse(_) => Self::StateResponse,
+                RoutedMessageBody::ForwardTx(_) | RoutedMessageBody::TxStatusResponse(_) => {
+                    Self::Transaction
+                }
+                RoutedMessageBody::TxStatusRequest(..) => Self::Request,
+                _ => return None,
+            },
+            _ => return None,
+        })
+    }
+
+    /// Maximal number of the messages of the class in flight to the client.
+    fn limit(self) -> usize {
+        match self {
+            Self::Block => 128,
+            Self::BlockHeaders => 16,
+            Self::Approval => 1024,
+            Self::ChunkEndorsement => 1024,
+            Self::StateResponse => 64,
+            Self::Challenge => 16,
+            Self::Transaction => 4096,
+            Self::Request => 256,
+        }
+    }
+
+    pub fn policy(self) -> Policy {
+        match self {
+            Self::Block
+            | Self::BlockHeaders
+            | Self::Approval
+            | Self::ChunkEndorsement
+            | Self::StateResponse => Policy::Defer,
+            Self::Challenge | Self::Transaction | Self::Request => Policy::Drop,
+        }
+    }
+}
+
+/// Permit to send a message to the client, released once the client is done
+/// with it.
+pub(crate) struct Permit {
+    class: MessageClass,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Permit {
+    fn new(class: MessageClass, permit: OwnedSemaphorePermit) -> Self {
+        metrics::CLIENT_BACKPRESSURE_IN_FLIGHT.with_label_values(&[class.as_ref()]).inc();
+        Self { class, _permit: permit }
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        metrics::CLIENT_BACKPRESSURE_IN_FLIGHT.with_label_values(&[self.class.as_ref()]).dec();
+    }
+}
+
+pub(crate) enum Admission {
+    /// The message can be sent to the client right away.
+    Admitted(Permit),
+    /// The message has to wait for the permit before it is sent.
+    Deferred(MessageClass, Arc<Semaphore>),
+    /// The message has to be dropped.
+    Dropped,
+}
+
+impl Admission {
+    /// Waits for the permit of a deferred message.
+    pub async fn permit(self) -> Option<Permit> {
+        match self {
+            Self::Admitted(permit) => Some(permit),
+            Self::Deferred(class, semaphore) => {
+                // The semaphores are never closed.
+                Some(Permit::new(class, semaphore.acquire_owned().await.unwrap()))
+            }
+            Self::Dropped => None,
+        }
+    }
+}
+
+pub(crate) struct ClientBackpressure {
+    semaphores: Vec<Arc<Semaphore>>,
+}
+
+impl ClientBackpressure {
+    pub fn new() -> Self {
+        use strum::IntoEnumIterator as _;
+        Self {
+            semaphores: MessageClass::iter()
+                .map(|class| Arc::new(Semaphore::new(class.limit())))
+                .collect(),
+        }
+    }
+
+    /// Decides what to do with a message of the class sent to the client.
+    pub fn admit(&self, class: MessageClass) -> Admission {
+        let semaphore = &self.semaphores[class as usize];
+        match (semaphore.clone().try_acquire_owned(), class.policy()) {
+            (Ok(permit), _) => Admission::Admitted(Permit::new(class, permit)),
+            (Err(_), Policy::Drop) => {
+                metrics::CLIENT_BACKPRESSURE_DROPPED.with_label_values(&[class.as_ref()]).inc();
+                Admission::Dropped
+            }
+            (Err(_), Policy::Defer) => {
+                metrics::CLIENT_BACKPRESSURE_DEFERRED.with_label_values(&[class.as_ref()]).inc();
+                Admission::Deferred(class, semaphore.clone())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_client_backpressure() {
+        let backpressure = ClientBackpressure::new();
+        let mut permits = vec![];
+        for _ in 0..MessageClass::Transaction.limit() {
+            permits.push(backpressure.admit(MessageClass::Transaction).permit().await.unwrap());
+        }
+        assert!(matches!(backpressure.admit(MessageClass::Transaction), Admission::Dropped));
+        permits.pop();
+        assert!(matches!(backpressure.admit(MessageClass::Transaction), Admission::Admitted(_)));
+
+        for _ in 0..MessageClass::Challenge.limit() {
+            permits.push(backpressure.admit(MessageClass::Challenge).permit().await.unwrap());
+        }
+        // The limits are per class.
+        assert!(matches!(backpressure.admit(MessageClass::Block), Admission::Admitted(_)));
+
+        for _ in 0..MessageClass::Block.limit() {
+            permits.push(backpressure.admit(MessageClass::Block).permit().await.unwrap());
+        }
+        let deferred = backpressure.admit(MessageClass::Block);
+        assert!(matches!(deferred, Admission::Deferred(..)));
+        let permit = tokio::spawn(deferred.permit());
+        tokio::task::yield_now().await;
+        assert!(!permit.is_finished());
+        permits.pop();
+        assert!(permit.await.unwrap().is_some());
+    }
+}
@@ -7,6 +7,7 @@ pub mod rayon;
 pub mod runtime;
 pub mod scope;
 mod signal;
+pub(crate) mod timeout;
 
 #[cfg(test)]
 mod tests;
@@ -1,6 +1,7 @@
 use crate::concurrency::arc_mutex::ArcMutex;
 use crate::concurrency::demux;
 use crate::concurrency::rate;
+use crate::concurrency::timeout;
 
 #[tokio::test]
 async fn test_demux() {
@@ -97,3 +98,14 @@ fn arc_mutex_update() {
     );
     assert_eq!(v3, *m.load());
 }
+
+#[tokio::test]
+async fn test_with_timeout() {
+    let clock = near_async::time::Clock::real();
+    let timeout = near_async::time::Duration::milliseconds(10);
+    let result = timeout::with_timeout(&clock, timeout, async { anyhow::Ok(1) }).await;
+    assert_eq!(result.unwrap(), 1);
+    let pending = std::future::pending::<anyhow::Result<()>>();
+    let err = timeout::with_timeout(&clock, timeout, pending).await.unwrap_err();
+    assert_eq!(err.to_string(), "timed out");
+}
@@ -0,0 +1,15 @@
+use near_async::time;
+
+/// Fails the future if it doesn't complete within the timeout.
+pub(crate) async fn with_timeout<T>(
+    clock: &time::Clock,
+    timeout: time::Duration,
+    fut: impl std::future::Future<Output = anyhow::Result<T>>,
+) -> anyhow::Result<T> {
+    // Both clock.sleep() and fut are cancellable, so it is safe to use them in
+    // tokio::select!.
+    tokio::select! {
+        _ = clock.sleep(timeout) => anyhow::bail!("timed out"),
+        result = fut => result,
+    }
+}
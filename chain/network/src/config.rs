@@ -1,5 +1,7 @@
 use crate::blacklist;
 use crate::concurrency::rate;
+use crate::dns_seed;
+use crate::network_protocol::Compression;
 use crate::network_protocol::PeerAddr;
 use crate::network_protocol::PeerInfo;
 use crate::peer_manager::peer_store;
@@ -91,13 +93,23 @@ pub struct Tier1 {
 #[derive(Clone)]
 pub struct NetworkConfig {
     pub node_addr: Option<tcp::ListenerAddr>,
+    /// Whether to map the port of `node_addr` on the NAT gateway, see
+    /// `crate::port_mapping`.
+    pub port_mapping: bool,
     pub node_key: SecretKey,
     pub validator: Option<ValidatorConfig>,
 
     pub peer_store: peer_store::Config,
+    /// DNS seeds to look up the boot nodes on, see `crate::dns_seed`.
+    pub dns_seeds: Option<dns_seed::Config>,
     pub snapshot_hosts: snapshot_hosts::Config,
     pub whitelist_nodes: Vec<PeerInfo>,
     pub handshake_timeout: time::Duration,
+    /// Compression codecs supported by this node, in the order of preference.
+    /// See `crate::network_protocol::compression`.
+    pub compression_codecs: Vec<Compression>,
+    /// Messages smaller than that are never compressed.
+    pub compression_threshold_bytes: usize,
 
     /// Whether to re-establish connection to known reliable peers from previous neard run(s).
     /// See near_network::peer_manager::connection_store for details.
@@ -163,6 +175,10 @@ pub struct NetworkConfig {
     //   * ignoring received deleted edges as well
     pub skip_tombstones: Option<time::Duration>,
 
+    /// Records the consensus messages received for a range of heights to a
+    /// file, to be replayed with `mock-node --replay`.
+    pub record_messages: Option<crate::recorder::RecordMessagesConfig>,
+
     #[cfg(test)]
     pub(crate) event_sink:
         near_async::messaging::Sender<crate::peer_manager::peer_manager_actor::Event>,
@@ -247,6 +263,25 @@ impl NetworkConfig {
                 }
             }
         }
+        let (dns_seeds, boot_nodes): (Vec<&str>, Vec<&str>) = cfg
+            .boot_nodes
+            .split(',')
+            .filter(|chunk| !chunk.is_empty())
+            .partition(|chunk| chunk.starts_with(dns_seed::PREFIX));
+        let dns_seeds = if dns_seeds.is_empty() {
+            None
+        } else {
+            Some(dns_seed::Config {
+                seeds: dns_seeds
+                    .iter()
+                    .map(|seed| seed[dns_seed::PREFIX.len()..].to_string())
+                    .collect(),
+                key: cfg
+                    .dns_seed_key
+                    .clone()
+                    .context("dns_seed_key is required by the DNS seeds in boot_nodes")?,
+            })
+        };
         let mut this = Self {
             node_key,
             validator: validator_signer.map(|signer| ValidatorConfig {
@@ -263,16 +298,13 @@ impl NetworkConfig {
                     addr.parse().context("Failed to parse SocketAddr")?,
                 )),
             },
+            port_mapping: cfg.port_mapping,
             peer_store: peer_store::Config {
-                boot_nodes: if cfg.boot_nodes.is_empty() {
-                    vec![]
-                } else {
-                    cfg.boot_nodes
-                        .split(',')
-                        .map(|chunk| chunk.parse())
-                        .collect::<Result<_, _>>()
-                        .context("boot_nodes")?
-                },
+                boot_nodes: boot_nodes
+                    .into_iter()
+                    .map(|chunk| chunk.parse())
+                    .collect::<Result<_, _>>()
+                    .context("boot_nodes")?,
                 blacklist: cfg
                     .blacklist
                     .iter()
@@ -284,6 +316,7 @@ impl NetworkConfig {
                 ban_window: cfg.ban_window.try_into()?,
                 peer_expiration_duration: cfg.peer_expiration_duration.try_into()?,
             },
+            dns_seeds,
             snapshot_hosts: snapshot_hosts::Config {
                 snapshot_hosts_cache_size: cfg.snapshot_hosts_cache_size,
                 part_selection_cache_batch_size: 10,
@@ -305,6 +338,8 @@ impl NetworkConfig {
             },
             connect_to_reliable_peers_on_startup: true,
             handshake_timeout: cfg.handshake_timeout.try_into()?,
+            compression_codecs: cfg.compression_codecs,
+            compression_threshold_bytes: cfg.compression_threshold_bytes,
             monitor_peers_max_period: cfg.monitor_peers_max_period.try_into()?,
             max_num_peers: cfg.max_num_peers,
             minimum_outbound_peers: cfg.minimum_outbound_peers,
@@ -338,6 +373,7 @@ impl NetworkConfig {
             } else {
                 None
             },
+            record_messages: cfg.experimental.record_messages,
             #[cfg(test)]
             event_sink: near_async::messaging::IntoSender::into_sender(
                 near_async::messaging::noop(),
@@ -363,6 +399,7 @@ impl NetworkConfig {
         };
         NetworkConfig {
             node_addr: Some(node_addr),
+            port_mapping: false,
             node_key,
             validator: Some(validator),
             peer_store: peer_store::Config {
@@ -373,12 +410,15 @@ impl NetworkConfig {
                 peer_expiration_duration: time::Duration::seconds(60 * 60),
                 connect_only_to_boot_nodes: false,
             },
+            dns_seeds: None,
             snapshot_hosts: snapshot_hosts::Config {
                 snapshot_hosts_cache_size: 1000,
                 part_selection_cache_batch_size: 10,
             },
             whitelist_nodes: vec![],
             handshake_timeout: time::Duration::seconds(5),
+            compression_codecs: vec![Compression::Zstd, Compression::Lz4],
+            compression_threshold_bytes: 64 * 1024,
             connect_to_reliable_peers_on_startup: true,
             monitor_peers_max_period: time::Duration::seconds(100),
             max_num_peers: 40,
@@ -411,6 +451,7 @@ impl NetworkConfig {
                 enable_outbound: true,
             }),
             skip_tombstones: None,
+            record_messages: None,
             #[cfg(test)]
             event_sink: near_async::messaging::IntoSender::into_sender(
                 near_async::messaging::noop(),
@@ -527,6 +568,25 @@ mod test {
         assert!(nc.verify().is_err());
     }
 
+    #[test]
+    fn test_dns_seeds() {
+        let node_key = near_crypto::SecretKey::from_seed(near_crypto::KeyType::ED25519, "node");
+        let seed_key = near_crypto::SecretKey::from_seed(near_crypto::KeyType::ED25519, "seed");
+        let boot_node = format!("{}@127.0.0.1:24567", node_key.public_key());
+        let mut cfg = crate::config_json::Config {
+            boot_nodes: format!("dns+seed.example.org,{boot_node}"),
+            ..Default::default()
+        };
+        assert!(config::NetworkConfig::new(cfg.clone(), node_key.clone(), None, false).is_err());
+
+        cfg.dns_seed_key = Some(seed_key.public_key());
+        let nc = config::NetworkConfig::new(cfg, node_key, None, false).unwrap();
+        let dns_seeds = nc.dns_seeds.unwrap();
+        assert_eq!(dns_seeds.seeds, vec!["seed.example.org".to_string()]);
+        assert_eq!(dns_seeds.key, seed_key.public_key());
+        assert_eq!(nc.peer_store.boot_nodes, vec![boot_node.parse().unwrap()]);
+    }
+
     #[test]
     fn test_network_config_override() {
         fn check_override_field<T: std::cmp::PartialEq>(
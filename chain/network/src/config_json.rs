@@ -1,4 +1,4 @@
-use crate::network_protocol::PeerAddr;
+use crate::network_protocol::{Compression, PeerAddr};
 use crate::stun;
 use near_async::time::Duration;
 
@@ -47,6 +47,14 @@ fn default_peer_stats_period() -> Duration {
 fn default_monitor_peers_max_period() -> Duration {
     Duration::seconds(60)
 }
+/// Compression codecs supported by default, in the order of preference.
+fn default_compression_codecs() -> Vec<Compression> {
+    vec![Compression::Zstd, Compression::Lz4]
+}
+/// Messages smaller than that are sent uncompressed.
+fn default_compression_threshold_bytes() -> usize {
+    64 * 1024
+}
 /// Maximum number of peer states to keep in memory.
 fn default_peer_states_cache_size() -> u32 {
     1000
@@ -78,12 +86,21 @@ fn default_trusted_stun_servers() -> Vec<stun::ServerAddr> {
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
 pub struct Config {
     /// Local address to listen for incoming connections.
+    /// Use `[::]:24567` to listen on both IPv6 and IPv4, on systems where IPv6
+    /// sockets are dual-stack, which is the default on Linux.
     pub addr: String,
     /// Comma separated list of nodes to connect to.
     /// Examples:
     ///   ed25519:86EtEy7epneKyrcJwSWP7zsisTkfDRH5CFVszt4qiQYw@31.192.22.209:24567
     ///   ed25519:86EtEy7epneKyrcJwSWP7zsisTkfDRH5CFVszt4qiQYw@nearnode.com:24567
+    /// An entry of the form `dns+<domain>` is a DNS seed, whose TXT records list the peers
+    /// to connect to, signed with `dns_seed_key`. The seeds are resolved periodically:
+    ///   dns+seed.example.org
     pub boot_nodes: String,
+    /// Key the records of the DNS seeds in `boot_nodes` are signed with. Required if there
+    /// are any DNS seeds.
+    #[serde(default)]
+    pub dns_seed_key: Option<near_crypto::PublicKey>,
     /// Comma separated list of whitelisted nodes. Inbound connections from the nodes on
     /// the whitelist are accepted even if the limit of the inbound connection has been reached.
     /// For each whitelisted node specifying both PeerId and one of IP:port or Host:port is required:
@@ -119,6 +136,14 @@ pub struct Config {
     /// Handshake timeout.
     #[serde(with = "near_async::time::serde_duration_as_std")]
     pub handshake_timeout: Duration,
+    /// Compression codecs (`zstd`, `lz4`) supported for the messages exchanged with the
+    /// peers, in the order of preference. Messages are compressed with the first codec
+    /// the peer supports too. Empty to disable compression.
+    #[serde(default = "default_compression_codecs")]
+    pub compression_codecs: Vec<Compression>,
+    /// Messages smaller than that are sent uncompressed.
+    #[serde(default = "default_compression_threshold_bytes")]
+    pub compression_threshold_bytes: usize,
     /// Skip waiting for peers before starting node.
     pub skip_sync_wait: bool,
     /// Ban window for peers who misbehave.
@@ -197,6 +222,11 @@ pub struct Config {
     /// such a case.
     #[serde(default = "default_trusted_stun_servers")]
     pub trusted_stun_servers: Vec<stun::ServerAddr>,
+    /// Whether to map the listening port on the NAT gateway of the local network with
+    /// NAT-PMP or UPnP, and advertise the external address of the mapping to the peers.
+    /// Makes a node behind a home router reachable for inbound connections.
+    #[serde(default)]
+    pub port_mapping: bool,
     // Experimental part of the JSON config. Regular users/validators should not have to set any values there.
     // Field names in here can change/disappear at any moment without warning.
     #[serde(default)]
@@ -257,6 +287,10 @@ pub struct ExperimentalConfig {
     /// Fields set here will override the NetworkConfig fields.
     #[serde(default)]
     pub network_config_overrides: NetworkConfigOverrides,
+
+    /// See `near_network::config::NetworkConfig::record_messages`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub record_messages: Option<crate::recorder::RecordMessagesConfig>,
 }
 
 /// Overrides values from NetworkConfig.
@@ -287,6 +321,7 @@ impl Default for ExperimentalConfig {
             tier1_connect_interval: default_tier1_connect_interval(),
             tier1_new_connections_per_attempt: default_tier1_new_connections_per_attempt(),
             network_config_overrides: Default::default(),
+            record_messages: None,
         }
     }
 }
@@ -296,6 +331,7 @@ impl Default for Config {
         Config {
             addr: "0.0.0.0:24567".to_string(),
             boot_nodes: "".to_string(),
+            dns_seed_key: None,
             whitelist_nodes: "".to_string(),
             max_num_peers: default_max_num_peers(),
             minimum_outbound_peers: default_minimum_outbound_connections(),
@@ -305,6 +341,8 @@ impl Default for Config {
             safe_set_size: default_safe_set_size(),
             archival_peer_connections_lower_bound: default_archival_peer_connections_lower_bound(),
             handshake_timeout: Duration::seconds(20),
+            compression_codecs: default_compression_codecs(),
+            compression_threshold_bytes: default_compression_threshold_bytes(),
             skip_sync_wait: false,
             peer_states_cache_size: default_peer_states_cache_size(),
             snapshot_hosts_cache_size: default_snapshot_hosts_cache_size(),
@@ -317,6 +355,7 @@ impl Default for Config {
             public_addrs: vec![],
             allow_private_ip_in_public_addrs: false,
             trusted_stun_servers: default_trusted_stun_servers(),
+            port_mapping: false,
             experimental: Default::default(),
         }
     }
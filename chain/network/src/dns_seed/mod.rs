@@ -0,0 +1,300 @@
+//! Discovery of the boot nodes through DNS seeds: domains whose TXT records
+//! list peers of the network, maintained by the operators of the seeds and
+//! rotated as the nodes come and go, so that the list of boot nodes in the
+//! config doesn't go stale.
+//!
+//! Every TXT record of the seed advertises a single peer:
+//!
+//!   <peer_id>@<host>:<port> <expires_at> <signature>
+//!
+//! where `<expires_at>` is the unix timestamp in seconds the record is valid
+//! until, and `<signature>` is the signature of
+//! `<peer_id>@<host>:<port> <expires_at>` made with the key of the seed, which
+//! the node is configured with. Records with an invalid signature are ignored,
+//! so that whoever can spoof the DNS responses can't feed the node with peer
+//! ids of their choice. The expired records are ignored as well, and the ones
+//! valid for longer than `MAX_RECORD_VALIDITY`, so that a record of a peer
+//! which has left can't be replayed for long. SRV records are not used, as
+//! they can carry neither the peer ids nor the signatures.
+//!
+//! The queries are sent to the nameservers from /etc/resolv.conf.
+use crate::concurrency::timeout::with_timeout;
+use crate::network_protocol::PeerInfo;
+use anyhow::{bail, ensure, Context as _};
+use near_async::time;
+use near_crypto::{PublicKey, Signature};
+use near_primitives::network::PeerId;
+use rand::seq::SliceRandom as _;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+#[cfg(test)]
+mod tests;
+
+/// Prefix of the entries of `boot_nodes` in the config which are DNS seeds.
+pub(crate) const PREFIX: &str = "dns+";
+
+/// How often the seeds are resolved again.
+pub(crate) const RESOLVE_DNS_SEEDS_INTERVAL: time::Duration = time::Duration::minutes(30);
+
+/// Maximum number of peers taken from a seed at a time, chosen at random
+/// among the records of the seed.
+const MAX_PEERS_PER_SEED: usize = 8;
+
+const QUERY_TIMEOUT: time::Duration = time::Duration::seconds(5);
+
+/// Longest time until the expiry of a record, the seeds have to sign the
+/// records again before they expire.
+pub(super) const MAX_RECORD_VALIDITY: time::Duration = time::Duration::days(7);
+
+const TYPE_TXT: u16 = 16;
+const TYPE_OPT: u16 = 41;
+const CLASS_IN: u16 = 1;
+/// Size of the UDP responses advertised with EDNS(0). Without it, responses
+/// are limited to 512 bytes, which fit just a couple of signed records.
+const MAX_RESPONSE_SIZE: u16 = 4096;
+
+#[derive(Clone)]
+pub struct Config {
+    /// Domains of the seeds, without the `dns+` prefix.
+    pub seeds: Vec<String>,
+    /// Key the records of the seeds are signed with.
+    pub key: PublicKey,
+}
+
+/// Returns the nameservers listed in the contents of /etc/resolv.conf.
+pub(super) fn parse_resolv_conf(resolv_conf: &str) -> Vec<SocketAddr> {
+    resolv_conf
+        .lines()
+        .filter_map(|line| {
+            let mut words = line.split_whitespace();
+            if words.next() != Some("nameserver") {
+                return None;
+            }
+            let ip: IpAddr = words.next()?.parse().ok()?;
+            Some(SocketAddr::new(ip, 53))
+        })
+        .collect()
+}
+
+/// Encodes a recursive query of the TXT records of the domain.
+pub(super) fn encode_query(id: u16, domain: &str) -> anyhow::Result<Vec<u8>> {
+    let mut query = vec![];
+    query.extend_from_slice(&id.to_be_bytes());
+    // Recursion desired; a question and an OPT record.
+    query.extend_from_slice(&[0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 1]);
+    for label in domain.trim_end_matches('.').split('.') {
+        ensure!(!label.is_empty() && label.len() < 64, "invalid domain {domain}");
+        query.push(label.len() as u8);
+        query.extend_from_slice(label.as_bytes());
+    }
+    query.push(0);
+    query.extend_from_slice(&TYPE_TXT.to_be_bytes());
+    query.extend_from_slice(&CLASS_IN.to_be_bytes());
+    // The OPT record: root domain, the size of the responses in place of the
+    // class, no extended flags and no options.
+    query.push(0);
+    query.extend_from_slice(&TYPE_OPT.to_be_bytes());
+    query.extend_from_slice(&MAX_RESPONSE_SIZE.to_be_bytes());
+    query.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+    Ok(query)
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> anyhow::Result<&'a [u8]> {
+        let bytes = self.buf.get(self.pos..self.pos + n).context("truncated message")?;
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> anyhow::Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> anyhow::Result<u16> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pos >= self.buf.len()
+    }
+
+    /// Skips a domain, which ends either with the root label or with a
+    /// pointer to the rest of the domain elsewhere in the message.
+    fn skip_domain(&mut self) -> anyhow::Result<()> {
+        loop {
+            match self.u8()? {
+                0 => return Ok(()),
+                len if len & 0xc0 == 0xc0 => {
+                    self.take(1)?;
+                    return Ok(());
+                }
+                len if len & 0xc0 == 0 => {
+                    self.take(len as usize)?;
+                }
+                len => bail!("invalid label length {len:#x}"),
+            }
+        }
+    }
+}
+
+/// Parses the response to the query with the id, returns the TXT records from
+/// the answer section. Other records, like the CNAME ones of the seeds which
+/// are aliases, are skipped.
+pub(super) fn parse_response(id: u16, response: &[u8]) -> anyhow::Result<Vec<String>> {
+    let mut r = Reader { buf: response, pos: 0 };
+    ensure!(r.u16()? == id, "response to another query");
+    let flags = r.u16()?;
+    ensure!(flags & 0x8000 != 0, "not a response");
+    ensure!(flags & 0x0200 == 0, "truncated response");
+    let rcode = flags & 0x000f;
+    ensure!(rcode == 0, "the query failed with response code {rcode}");
+    let questions = r.u16()?;
+    let answers = r.u16()?;
+    // The authority and additional sections are not needed.
+    r.take(4)?;
+    for _ in 0..questions {
+        r.skip_domain()?;
+        // Type and class.
+        r.take(4)?;
+    }
+    let mut records = vec![];
+    for _ in 0..answers {
+        r.skip_domain()?;
+        let record_type = r.u16()?;
+        // Class and TTL.
+        r.take(6)?;
+        let len = r.u16()?;
+        let data = r.take(len as usize)?;
+        if record_type != TYPE_TXT {
+            continue;
+        }
+        // The text is split into character strings of up to 255 bytes each.
+        let mut data = Reader { buf: data, pos: 0 };
+        let mut text = vec![];
+        while !data.is_empty() {
+            let len = data.u8()?;
+            text.extend_from_slice(data.take(len as usize)?);
+        }
+        records.push(String::from_utf8(text).context("record is not UTF-8")?);
+    }
+    Ok(records)
+}
+
+/// Parses the record and verifies its signature and expiry, returns the
+/// advertised peer id and `<host>:<port>`.
+pub(super) fn parse_record(
+    key: &PublicKey,
+    record: &str,
+    now: time::Utc,
+) -> anyhow::Result<(PeerId, String)> {
+    let (message, signature) = record.trim().rsplit_once(' ').context("no signature")?;
+    let signature: Signature = signature.parse().context("signature")?;
+    ensure!(signature.verify(message.as_bytes(), key), "invalid signature");
+    let (entry, expires_at) = message.split_once(' ').context("no expiry")?;
+    let expires_at: i64 = expires_at.parse().context("expiry")?;
+    let now = now.unix_timestamp();
+    ensure!(expires_at > now, "expired at {expires_at}");
+    ensure!(
+        expires_at <= now.saturating_add(MAX_RECORD_VALIDITY.whole_seconds()),
+        "valid for too long, until {expires_at}"
+    );
+    let (peer_id, addr) = entry.split_once('@').context("no address")?;
+    Ok((PeerId::new(peer_id.parse().context("peer id")?), addr.to_string()))
+}
+
+async fn query(server: SocketAddr, domain: &str) -> anyhow::Result<Vec<String>> {
+    let id = rand::random();
+    let unspecified = match server {
+        SocketAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        SocketAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+    };
+    let socket = tokio::net::UdpSocket::bind((unspecified, 0)).await?;
+    socket.connect(server).await?;
+    socket.send(&encode_query(id, domain)?).await?;
+    let mut response = vec![0; MAX_RESPONSE_SIZE as usize];
+    let n = socket.recv(&mut response).await?;
+    parse_response(id, &response[..n])
+}
+
+/// Returns a random subset of the valid records of the seed, resolved to the
+/// peers.
+async fn resolve_seed(
+    clock: &time::Clock,
+    nameservers: &[SocketAddr],
+    key: &PublicKey,
+    seed: &str,
+) -> anyhow::Result<Vec<PeerInfo>> {
+    let mut records = None;
+    let mut errs = vec![];
+    for server in nameservers {
+        match with_timeout(clock, QUERY_TIMEOUT, query(*server, seed)).await {
+            Ok(r) => {
+                records = Some(r);
+                break;
+            }
+            Err(err) => errs.push(format!("{server}: {err:#}")),
+        }
+    }
+    let Some(records) = records else {
+        if errs.is_empty() {
+            bail!("no nameservers in /etc/resolv.conf");
+        }
+        bail!("{}", errs.join("; "));
+    };
+    let mut entries = vec![];
+    for record in &records {
+        match parse_record(key, record, clock.now_utc()) {
+            Ok(entry) => entries.push(entry),
+            Err(err) => {
+                tracing::warn!(target: "network", seed, record, err = format!("{err:#}"), "Ignoring an invalid record of the DNS seed");
+            }
+        }
+    }
+    entries.shuffle(&mut rand::thread_rng());
+    entries.truncate(MAX_PEERS_PER_SEED);
+    let mut peers = vec![];
+    for (id, addr) in entries {
+        let resolved = with_timeout(clock, QUERY_TIMEOUT, async {
+            anyhow::Ok(tokio::net::lookup_host(&addr).await?.next())
+        })
+        .await;
+        match resolved {
+            Ok(Some(addr)) => peers.push(PeerInfo { id, addr: Some(addr), account_id: None }),
+            Ok(None) => {}
+            Err(err) => {
+                tracing::warn!(target: "network", seed, addr, err = format!("{err:#}"), "Failed to resolve the address of a peer of the DNS seed");
+            }
+        }
+    }
+    Ok(peers)
+}
+
+/// Resolves all the seeds, returns the peers they advertise.
+pub(crate) async fn resolve(clock: &time::Clock, cfg: &Config) -> Vec<PeerInfo> {
+    let nameservers = match std::fs::read_to_string("/etc/resolv.conf") {
+        Ok(resolv_conf) => parse_resolv_conf(&resolv_conf),
+        Err(err) => {
+            tracing::warn!(target: "network", ?err, "Failed to read /etc/resolv.conf");
+            return vec![];
+        }
+    };
+    let mut peers = vec![];
+    for seed in &cfg.seeds {
+        match resolve_seed(clock, &nameservers, &cfg.key, seed).await {
+            Ok(seed_peers) => {
+                tracing::debug!(target: "network", seed, peers = seed_peers.len(), "Resolved the DNS seed");
+                peers.extend(seed_peers);
+            }
+            Err(err) => {
+                tracing::warn!(target: "network", seed, err = format!("{err:#}"), "Failed to resolve the DNS seed");
+            }
+        }
+    }
+    peers
+}
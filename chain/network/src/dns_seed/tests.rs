@@ -0,0 +1,87 @@
+use super::*;
+use near_crypto::{KeyType, SecretKey};
+
+fn make_record(key: &SecretKey, entry: &str, expires_at: time::Utc) -> String {
+    let message = format!("{entry} {}", expires_at.unix_timestamp());
+    format!("{message} {}", key.sign(message.as_bytes()))
+}
+
+#[test]
+fn test_parse_resolv_conf() {
+    let resolv_conf = "# Generated by NetworkManager\n\
+        search example.org\n\
+        nameserver 192.168.1.1\n\
+        nameserver 2001:db8::53\n\
+        nameserver fe80::1%eth0\n\
+        options edns0\n";
+    assert_eq!(
+        parse_resolv_conf(resolv_conf),
+        vec!["192.168.1.1:53".parse().unwrap(), "[2001:db8::53]:53".parse().unwrap()]
+    );
+}
+
+#[test]
+fn test_encode_query() {
+    let query = encode_query(0x1234, "seed.near.").unwrap();
+    assert_eq!(&query[..12], &[0x12, 0x34, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 1]);
+    assert_eq!(&query[12..23], b"\x04seed\x04near\x00");
+    assert_eq!(&query[23..27], &[0, 16, 0, 1]);
+    assert_eq!(query.len(), 27 + 11);
+    assert!(encode_query(0, "seed..near").is_err());
+}
+
+#[test]
+fn test_parse_response() {
+    let mut response = vec![0x12, 0x34, 0x81, 0x80, 0, 1, 0, 2, 0, 0, 0, 0];
+    response.extend_from_slice(b"\x04seed\x04near\x00");
+    response.extend_from_slice(&[0, 16, 0, 1]);
+    // A CNAME record of the seed, pointing at the question.
+    response.extend_from_slice(&[0xc0, 12, 0, 5, 0, 1, 0, 0, 0, 60, 0, 6]);
+    response.extend_from_slice(b"\x03ns1\xc0\x11");
+    // A TXT record split into two character strings.
+    response.extend_from_slice(&[0xc0, 12, 0, 16, 0, 1, 0, 0, 0, 60, 0, 12]);
+    response.extend_from_slice(b"\x05hello\x05world");
+    assert_eq!(parse_response(0x1234, &response).unwrap(), vec!["helloworld".to_string()]);
+    assert!(parse_response(0x4321, &response).is_err());
+    assert!(parse_response(0x1234, &response[..response.len() - 1]).is_err());
+
+    // NXDOMAIN.
+    let mut failed = response.clone();
+    failed[3] = 0x83;
+    assert!(parse_response(0x1234, &failed).is_err());
+}
+
+#[test]
+fn test_parse_record() {
+    let key = SecretKey::from_seed(KeyType::ED25519, "seed");
+    let peer_key = SecretKey::from_seed(KeyType::ED25519, "peer");
+    let entry = format!("{}@1.2.3.4:24567", peer_key.public_key());
+    let now = time::FakeClock::default().now_utc();
+    let expires_at = now + time::Duration::days(1);
+    let record = make_record(&key, &entry, expires_at);
+    let (peer_id, addr) = parse_record(&key.public_key(), &record, now).unwrap();
+    assert_eq!(peer_id, PeerId::new(peer_key.public_key()));
+    assert_eq!(addr, "1.2.3.4:24567");
+
+    // Signed with another key.
+    let other_key = SecretKey::from_seed(KeyType::ED25519, "other");
+    let other_record = make_record(&other_key, &entry, expires_at);
+    assert!(parse_record(&key.public_key(), &other_record, now).is_err());
+    // Tampered with after signing.
+    let tampered = record.replace("1.2.3.4", "5.6.7.8");
+    assert!(parse_record(&key.public_key(), &tampered, now).is_err());
+    let extended = record.replace(
+        &expires_at.unix_timestamp().to_string(),
+        &(expires_at + time::Duration::days(1)).unix_timestamp().to_string(),
+    );
+    assert!(parse_record(&key.public_key(), &extended, now).is_err());
+    assert!(parse_record(&key.public_key(), &entry, now).is_err());
+
+    // Replayed after the expiry.
+    assert!(parse_record(&key.public_key(), &record, expires_at).is_err());
+    // Valid for too long.
+    let long_record = make_record(&key, &entry, now + MAX_RECORD_VALIDITY + time::Duration::SECOND);
+    assert!(parse_record(&key.public_key(), &long_record, now).is_err());
+    let record = make_record(&key, &entry, now + MAX_RECORD_VALIDITY);
+    assert!(parse_record(&key.public_key(), &record, now).is_ok());
+}
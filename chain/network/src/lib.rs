@@ -2,9 +2,12 @@ pub use crate::peer_manager::peer_manager_actor::{Event, PeerManagerActor};
 
 mod accounts_data;
 mod announce_accounts;
+mod client_backpressure;
+mod dns_seed;
 mod network_protocol;
 mod peer;
 mod peer_manager;
+mod port_mapping;
 mod private_actix;
 mod snapshot_hosts;
 mod stats;
@@ -19,6 +22,7 @@ pub mod config;
 pub mod config_json;
 pub mod debug;
 pub mod raw;
+pub mod recorder;
 pub mod routing;
 pub mod shards_manager;
 pub mod state_sync;
@@ -11,6 +11,8 @@ impl From<&net::Handshake> for mem::Handshake {
             sender_peer_id: x.sender_peer_id.clone(),
             target_peer_id: x.target_peer_id.clone(),
             sender_listen_port: x.sender_listen_port,
+            sender_addrs: vec![],
+            compressions: vec![],
             sender_chain_info: x.sender_chain_info.clone(),
             partial_edge_info: x.partial_edge_info.clone(),
             owned_account: None,
@@ -227,7 +229,7 @@ impl From<&mem::PeerMessage> for net::PeerMessage {
             mem::PeerMessage::DistanceVector(dv) => net::PeerMessage::DistanceVector(dv.into()),
 
             // This message is not supported, we translate it to an empty RoutingTableUpdate.
-            mem::PeerMessage::SyncAccountsData(_) => {
+            mem::PeerMessage::SyncAccountsData(_) | mem::PeerMessage::EquivocationEvidence(_) => {
                 net::PeerMessage::SyncRoutingTable(net::RoutingTableUpdate::default())
             }
 
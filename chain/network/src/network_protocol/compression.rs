@@ -0,0 +1,172 @@
+//! Compression of the large messages, like the state witnesses and the state
+//! parts, with a codec negotiated per connection.
+//!
+//! Every peer advertises the codecs it supports in its handshake. The sender
+//! compresses the messages over `compression_threshold_bytes` with the first
+//! codec of its config which the receiver has advertised, and sends them
+//! wrapped into a CompressedPeerMessage (see network.proto). A message which
+//! doesn't get any smaller is sent as is. Only the proto encoding supports
+//! compression.
+use crate::network_protocol::proto;
+use crate::network_protocol::proto::peer_message::Message_type as ProtoMT;
+use crate::stats::metrics;
+use bytesize::MIB;
+use protobuf::Message as _;
+use std::io;
+use std::io::Read as _;
+
+/// Limit on the size of the decompressed messages. The largest messages which
+/// are worth compressing, the state parts, are limited to 30 MiB of the trie
+/// nodes, so that the peers can't make the node decompress into much more
+/// memory than the legitimate messages take.
+pub(crate) const MAX_UNCOMPRESSED_SIZE: usize = 64 * MIB as usize;
+
+/// Largest ratio of the decompressed to the compressed size of an LZ4 block,
+/// when the block encodes a single byte repeated.
+const MAX_LZ4_RATIO: usize = 255;
+
+/// Level of the zstd compression. The lowest levels are the fastest, which
+/// matters more than the ratio for the messages on the critical path.
+const ZSTD_LEVEL: i32 = 1;
+
+#[derive(
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Debug,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    strum::IntoStaticStr,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum Compression {
+    Lz4,
+    Zstd,
+}
+
+/// Returns the codec to compress the messages to the peer with: the first
+/// codec of `own` which the peer advertised in `peer`. None if there is no
+/// codec both peers support.
+pub(crate) fn negotiate_compression(
+    own: &[Compression],
+    peer: &[Compression],
+) -> Option<Compression> {
+    own.iter().find(|codec| peer.contains(codec)).copied()
+}
+
+mod lz4 {
+    use std::ffi::{c_char, c_int};
+    use std::io;
+
+    /// The size of the input is limited by the size of the frames, so it
+    /// always fits into c_int.
+    pub(super) fn compress(data: &[u8]) -> Vec<u8> {
+        let len = c_int::try_from(data.len()).unwrap();
+        // SAFETY: LZ4_compressBound only computes the bound on the output size.
+        let bound = unsafe { lz4_sys::LZ4_compressBound(len) };
+        let mut out = vec![0u8; bound as usize];
+        // SAFETY: the input and the output buffers are valid for the lengths passed.
+        let n = unsafe {
+            lz4_sys::LZ4_compress_default(
+                data.as_ptr() as *const c_char,
+                out.as_mut_ptr() as *mut c_char,
+                len,
+                bound,
+            )
+        };
+        assert!(n > 0, "LZ4 compression into a buffer of the bound size failed");
+        out.truncate(n as usize);
+        out
+    }
+
+    /// The output buffer is allocated upfront, as the LZ4 blocks can't be
+    /// decompressed in a streaming way, the size must be checked against the
+    /// size of the input before.
+    pub(super) fn decompress(data: &[u8], size: usize) -> io::Result<Vec<u8>> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "malformed LZ4 block");
+        let len = c_int::try_from(data.len()).map_err(|_| invalid())?;
+        let mut out = vec![0u8; size];
+        // SAFETY: the input and the output buffers are valid for the lengths passed,
+        // LZ4_decompress_safe doesn't write past the end of the output buffer.
+        let n = unsafe {
+            lz4_sys::LZ4_decompress_safe(
+                data.as_ptr() as *const c_char,
+                out.as_mut_ptr() as *mut c_char,
+                len,
+                c_int::try_from(size).map_err(|_| invalid())?,
+            )
+        };
+        if n < 0 || n as usize != size {
+            return Err(invalid());
+        }
+        Ok(out)
+    }
+}
+
+/// Compresses the serialized proto PeerMessage of the type, returns the
+/// serialized PeerMessage wrapping the compressed one.
+pub(crate) fn compress(codec: Compression, msg_type: &'static str, msg: Vec<u8>) -> Vec<u8> {
+    let codec_label: &'static str = codec.into();
+    let timer = metrics::PEER_MESSAGE_COMPRESSION_TIME
+        .with_label_values(&[msg_type, codec_label, "compress"])
+        .start_timer();
+    let data = match codec {
+        Compression::Lz4 => lz4::compress(&msg),
+        Compression::Zstd => zstd::bulk::compress(&msg, ZSTD_LEVEL).unwrap(),
+    };
+    timer.observe_duration();
+    metrics::PEER_MESSAGE_COMPRESSION_RATIO
+        .with_label_values(&[msg_type, codec_label])
+        .observe(data.len() as f64 / msg.len() as f64);
+    if data.len() >= msg.len() {
+        return msg;
+    }
+    proto::PeerMessage {
+        message_type: Some(ProtoMT::Compressed(proto::CompressedPeerMessage {
+            codec: proto::compressed_peer_message::Codec::from(codec).into(),
+            uncompressed_size: msg.len() as u64,
+            data,
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .write_to_bytes()
+    .unwrap()
+}
+
+/// Decompresses the zstd frame into at most `size` bytes. The output grows
+/// with the decompressed data, rather than being allocated for the size the
+/// peer declared.
+fn zstd_decompress(data: &[u8], size: usize) -> io::Result<Vec<u8>> {
+    let mut out = vec![];
+    zstd::stream::read::Decoder::new(data)?.take(size as u64 + 1).read_to_end(&mut out)?;
+    Ok(out)
+}
+
+/// Decompresses the message, returns the codec it was compressed with and the
+/// serialized proto PeerMessage.
+pub(crate) fn decompress(msg: &proto::CompressedPeerMessage) -> io::Result<(Compression, Vec<u8>)> {
+    let invalid = |err: &str| io::Error::new(io::ErrorKind::InvalidData, err.to_string());
+    let codec = Compression::try_from(msg.codec.enum_value_or_default())
+        .map_err(|_| invalid("unknown codec"))?;
+    let size = usize::try_from(msg.uncompressed_size)
+        .ok()
+        .filter(|size| *size <= MAX_UNCOMPRESSED_SIZE)
+        .ok_or_else(|| invalid("uncompressed size over the limit"))?;
+    let data = match codec {
+        Compression::Lz4 => {
+            if size > msg.data.len().saturating_mul(MAX_LZ4_RATIO) {
+                return Err(invalid("uncompressed size over the limit of the compressed size"));
+            }
+            lz4::decompress(&msg.data, size)?
+        }
+        Compression::Zstd => zstd_decompress(&msg.data, size)?,
+    };
+    if data.len() != size {
+        return Err(invalid("uncompressed size mismatch"));
+    }
+    Ok((codec, data))
+}
@@ -2,10 +2,13 @@
 #[path = "borsh.rs"]
 mod borsh_;
 mod borsh_conv;
+mod compression;
 mod edge;
 mod peer;
 mod proto_conv;
 mod state_sync;
+pub use compression::Compression;
+pub(crate) use compression::{compress, negotiate_compression};
 pub use edge::*;
 use near_primitives::stateless_validation::ChunkEndorsement;
 use near_primitives::stateless_validation::ChunkStateWitnessAck;
@@ -34,6 +37,7 @@ use near_crypto::Signature;
 use near_o11y::OpenTelemetrySpanExt;
 use near_primitives::block::{Approval, Block, BlockHeader, GenesisId};
 use near_primitives::challenge::Challenge;
+use near_primitives::equivocation::EquivocationEvidence;
 use near_primitives::hash::CryptoHash;
 use near_primitives::merkle::combine_hash;
 use near_primitives::network::{AnnounceAccount, PeerId};
@@ -336,6 +340,9 @@ pub struct DistanceVector {
     pub edges: Vec<Edge>,
 }
 
+/// Maximal number of addresses advertised in a handshake.
+pub(crate) const MAX_SENDER_ADDRS: usize = 4;
+
 /// Structure representing handshake between peers.
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub struct Handshake {
@@ -349,6 +356,11 @@ pub struct Handshake {
     pub(crate) target_peer_id: PeerId,
     /// Sender's listening addr.
     pub(crate) sender_listen_port: Option<u16>,
+    /// Other public addresses the sender accepts connections on, besides the
+    /// IP the receiver observes with `sender_listen_port`.
+    pub(crate) sender_addrs: Vec<std::net::SocketAddr>,
+    /// Compression codecs the sender supports, in the order of its preference.
+    pub(crate) compressions: Vec<Compression>,
     /// Peer's chain information.
     pub(crate) sender_chain_info: PeerChainInfoV2,
     /// Represents new `edge`. Contains only `none` and `Signature` from the sender.
@@ -429,6 +441,8 @@ pub enum PeerMessage {
     /// Gracefully disconnect from other peer.
     Disconnect(Disconnect),
     Challenge(Challenge),
+    /// Evidence of a validator signing conflicting blocks or approvals.
+    EquivocationEvidence(Box<EquivocationEvidence>),
 
     SyncSnapshotHosts(SyncSnapshotHosts),
     StateRequestHeader(ShardId, CryptoHash),
@@ -458,6 +472,8 @@ pub enum ParsePeerMessageError {
     ProtoDecode(#[source] protobuf::Error),
     #[error("ProtoConv")]
     ProtoConv(#[source] proto_conv::ParsePeerMessageError),
+    #[error("Decompress")]
+    Decompress(#[source] std::io::Error),
 }
 
 impl PeerMessage {
@@ -486,12 +502,31 @@ impl PeerMessage {
                 .try_into()
                 .map_err(ParsePeerMessageError::BorshConv)?,
             Encoding::Proto => {
-                let proto_msg: proto::PeerMessage = proto::PeerMessage::parse_from_bytes(data)
+                let mut proto_msg: proto::PeerMessage = proto::PeerMessage::parse_from_bytes(data)
                     .map_err(ParsePeerMessageError::ProtoDecode)?;
+                let mut decompressed = None;
+                if let Some(proto::peer_message::Message_type::Compressed(compressed)) =
+                    &proto_msg.message_type
+                {
+                    let start = std::time::Instant::now();
+                    let (codec, data) = compression::decompress(compressed)
+                        .map_err(ParsePeerMessageError::Decompress)?;
+                    decompressed = Some((codec, start.elapsed()));
+                    proto_msg = proto::PeerMessage::parse_from_bytes(&data)
+                        .map_err(ParsePeerMessageError::ProtoDecode)?;
+                }
                 if let Ok(extracted_span_context) = extract_span_context(&proto_msg.trace_context) {
                     span.clone().or_current().add_link(extracted_span_context);
                 }
-                (&proto_msg).try_into().map_err(|err| ParsePeerMessageError::ProtoConv(err))?
+                let msg: PeerMessage =
+                    (&proto_msg).try_into().map_err(|err| ParsePeerMessageError::ProtoConv(err))?;
+                if let Some((codec, elapsed)) = decompressed {
+                    let codec: &'static str = codec.into();
+                    crate::stats::metrics::PEER_MESSAGE_COMPRESSION_TIME
+                        .with_label_values(&[msg.msg_variant(), codec, "decompress"])
+                        .observe(elapsed.as_secs_f64());
+                }
+                msg
             }
         })
     }
@@ -2,7 +2,7 @@
 use super::*;
 
 use crate::network_protocol::proto;
-use crate::network_protocol::{Handshake, HandshakeFailureReason};
+use crate::network_protocol::{Compression, Handshake, HandshakeFailureReason};
 use crate::network_protocol::{PeerChainInfoV2, PeerInfo};
 use near_primitives::block::GenesisId;
 use protobuf::MessageField as MF;
@@ -63,6 +63,32 @@ impl TryFrom<&proto::PeerChainInfo> for PeerChainInfoV2 {
 
 //////////////////////////////////////////
 
+impl From<Compression> for proto::compressed_peer_message::Codec {
+    fn from(x: Compression) -> Self {
+        match x {
+            Compression::Lz4 => Self::LZ4,
+            Compression::Zstd => Self::ZSTD,
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("unknown compression codec")]
+pub struct ParseCompressionError;
+
+impl TryFrom<proto::compressed_peer_message::Codec> for Compression {
+    type Error = ParseCompressionError;
+    fn try_from(x: proto::compressed_peer_message::Codec) -> Result<Self, Self::Error> {
+        match x {
+            proto::compressed_peer_message::Codec::NONE => Err(ParseCompressionError),
+            proto::compressed_peer_message::Codec::LZ4 => Ok(Self::Lz4),
+            proto::compressed_peer_message::Codec::ZSTD => Ok(Self::Zstd),
+        }
+    }
+}
+
+//////////////////////////////////////////
+
 #[derive(thiserror::Error, Debug)]
 pub enum ParseHandshakeError {
     #[error("sender_peer_id {0}")]
@@ -71,6 +97,8 @@ pub enum ParseHandshakeError {
     TargetPeerId(ParseRequiredError<ParsePublicKeyError>),
     #[error("sender_listen_port {0}")]
     SenderListenPort(std::num::TryFromIntError),
+    #[error("sender_addrs {0}")]
+    SenderAddrs(ParseVecError<ParseSocketAddrError>),
     #[error("sender_chain_info {0}")]
     SenderChainInfo(ParseRequiredError<ParsePeerChainInfoV2Error>),
     #[error("partial_edge_info {0}")]
@@ -87,6 +115,12 @@ impl From<&Handshake> for proto::Handshake {
             sender_peer_id: MF::some((&x.sender_peer_id).into()),
             target_peer_id: MF::some((&x.target_peer_id).into()),
             sender_listen_port: x.sender_listen_port.unwrap_or(0).into(),
+            sender_addrs: x.sender_addrs.iter().map(Into::into).collect(),
+            compressions: x
+                .compressions
+                .iter()
+                .map(|c| proto::compressed_peer_message::Codec::from(*c).into())
+                .collect(),
             sender_chain_info: MF::some((&x.sender_chain_info).into()),
             partial_edge_info: MF::some((&x.partial_edge_info).into()),
             owned_account: x.owned_account.as_ref().map(Into::into).into(),
@@ -114,6 +148,13 @@ impl TryFrom<&proto::Handshake> for Handshake {
                     Some(port)
                 }
             },
+            sender_addrs: try_from_slice(&p.sender_addrs).map_err(Self::Error::SenderAddrs)?,
+            // Codecs unknown to this node, supported by newer peers, are skipped.
+            compressions: p
+                .compressions
+                .iter()
+                .filter_map(|c| Compression::try_from(c.enum_value().ok()?).ok())
+                .collect(),
             sender_chain_info: try_from_required(&p.sender_chain_info)
                 .map_err(Self::Error::SenderChainInfo)?,
             partial_edge_info: try_from_required(&p.partial_edge_info)
@@ -14,6 +14,7 @@ use borsh::BorshDeserialize as _;
 use near_async::time::error::ComponentRange;
 use near_primitives::block::{Block, BlockHeader};
 use near_primitives::challenge::Challenge;
+use near_primitives::equivocation::EquivocationEvidence;
 use near_primitives::transaction::SignedTransaction;
 use protobuf::MessageField as MF;
 use std::sync::Arc;
@@ -309,6 +310,12 @@ impl From<&PeerMessage> for proto::PeerMessage {
                     borsh: borsh::to_vec(&r).unwrap(),
                     ..Default::default()
                 }),
+                PeerMessage::EquivocationEvidence(evidence) => {
+                    ProtoMT::EquivocationEvidence(proto::EquivocationEvidence {
+                        borsh: borsh::to_vec(&evidence).unwrap(),
+                        ..Default::default()
+                    })
+                }
                 PeerMessage::SyncSnapshotHosts(ssh) => ProtoMT::SyncSnapshotHosts(ssh.into()),
                 PeerMessage::StateRequestHeader(shard_id, sync_hash) => {
                     ProtoMT::StateRequestHeader(proto::StateRequestHeader {
@@ -341,6 +348,7 @@ pub type ParsePeersRequestError = std::io::Error;
 pub type ParseTransactionError = std::io::Error;
 pub type ParseRoutedError = std::io::Error;
 pub type ParseChallengeError = std::io::Error;
+pub type ParseEquivocationEvidenceError = std::io::Error;
 
 #[derive(thiserror::Error, Debug)]
 pub enum ParsePeerMessageError {
@@ -378,6 +386,8 @@ pub enum ParsePeerMessageError {
     Routed(ParseRoutedError),
     #[error("challenge: {0}")]
     Challenge(ParseChallengeError),
+    #[error("equivocation_evidence: {0}")]
+    EquivocationEvidence(ParseEquivocationEvidenceError),
     #[error("routed_created_at: {0}")]
     RoutedCreatedAtTimestamp(ComponentRange),
     #[error("sync_accounts_data: {0}")]
@@ -386,6 +396,8 @@ pub enum ParsePeerMessageError {
     StateResponse(ParseRequiredError<ParseStateInfoError>),
     #[error("sync_snapshot_hosts: {0}")]
     SyncSnapshotHosts(ParseSyncSnapshotHostsError),
+    #[error("compressed message inside a compressed message")]
+    NestedCompressed,
 }
 
 impl TryFrom<&proto::PeerMessage> for PeerMessage {
@@ -472,6 +484,10 @@ impl TryFrom<&proto::PeerMessage> for PeerMessage {
             ProtoMT::Challenge(c) => PeerMessage::Challenge(
                 Challenge::try_from_slice(&c.borsh).map_err(Self::Error::Challenge)?,
             ),
+            ProtoMT::EquivocationEvidence(e) => PeerMessage::EquivocationEvidence(Box::new(
+                EquivocationEvidence::try_from_slice(&e.borsh)
+                    .map_err(Self::Error::EquivocationEvidence)?,
+            )),
             ProtoMT::StateRequestHeader(srh) => PeerMessage::StateRequestHeader(
                 srh.shard_id,
                 try_from_required(&srh.sync_hash).map_err(Self::Error::BlockRequest)?,
@@ -487,6 +503,8 @@ impl TryFrom<&proto::PeerMessage> for PeerMessage {
             ProtoMT::SyncSnapshotHosts(srh) => PeerMessage::SyncSnapshotHosts(
                 srh.try_into().map_err(Self::Error::SyncSnapshotHosts)?,
             ),
+            // Compressed messages are unwrapped in PeerMessage::deserialize().
+            ProtoMT::Compressed(_) => return Err(Self::Error::NestedCompressed),
         })
     }
 }
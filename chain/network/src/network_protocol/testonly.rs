@@ -333,6 +333,9 @@ pub fn make_handshake<R: Rng>(rng: &mut R, chain: &Chain) -> Handshake {
         sender_peer_id: a_id,
         target_peer_id: b_id,
         sender_listen_port: Some(rng.gen()),
+        // Not supported by borsh.
+        sender_addrs: vec![],
+        compressions: vec![],
         sender_chain_info: chain.get_peer_chain_info(),
         partial_edge_info: make_partial_edge(rng),
         owned_account: None,
@@ -63,6 +63,11 @@ fn serialize_deserialize_protobuf_only() {
     let chain = data::Chain::make(&mut clock, &mut rng, 12);
     let msgs = [
         PeerMessage::Tier1Handshake(data::make_handshake(&mut rng, &chain)),
+        PeerMessage::Tier2Handshake(Handshake {
+            sender_addrs: vec![data::make_addr(&mut rng)],
+            compressions: vec![Compression::Zstd, Compression::Lz4],
+            ..data::make_handshake(&mut rng, &chain)
+        }),
         PeerMessage::SyncAccountsData(SyncAccountsData {
             accounts_data: (0..4)
                 .map(|_| Arc::new(data::make_signed_account_data(&mut rng, &clock.clock())))
@@ -79,6 +84,51 @@ fn serialize_deserialize_protobuf_only() {
     }
 }
 
+#[test]
+fn serialize_deserialize_compressed() {
+    let mut rng = make_rng(89028037453);
+    let mut clock = time::FakeClock::default();
+    let chain = data::Chain::make(&mut clock, &mut rng, 12);
+    let header = chain.get_block_headers()[0].clone();
+    let msg = PeerMessage::BlockHeaders(vec![header; 100]);
+    let bytes = msg.serialize(Encoding::Proto);
+    for codec in [Compression::Lz4, Compression::Zstd] {
+        let compressed = compress(codec, msg.msg_variant(), bytes.clone());
+        assert!(compressed.len() < bytes.len(), "{codec:?}");
+        assert_eq!(msg, PeerMessage::deserialize(Encoding::Proto, &compressed).unwrap());
+    }
+
+    // The declared size is checked before anything is allocated for it.
+    let mut compressed = proto::CompressedPeerMessage {
+        codec: proto::compressed_peer_message::Codec::LZ4.into(),
+        uncompressed_size: compression::MAX_UNCOMPRESSED_SIZE as u64 + 1,
+        data: vec![0; 16],
+        ..Default::default()
+    };
+    assert!(compression::decompress(&compressed).is_err());
+    compressed.uncompressed_size = 1 << 20;
+    assert!(compression::decompress(&compressed).is_err());
+    // A zstd frame decompressing into more than declared.
+    let data = vec![7; 1 << 20];
+    compressed.codec = proto::compressed_peer_message::Codec::ZSTD.into();
+    compressed.data = zstd::bulk::compress(&data, 1).unwrap();
+    compressed.uncompressed_size = 1000;
+    assert!(compression::decompress(&compressed).is_err());
+    compressed.uncompressed_size = data.len() as u64;
+    assert_eq!(compression::decompress(&compressed).unwrap(), (Compression::Zstd, data));
+
+    let negotiate = negotiate_compression;
+    assert_eq!(
+        negotiate(&[Compression::Zstd, Compression::Lz4], &[Compression::Lz4]),
+        Some(Compression::Lz4)
+    );
+    assert_eq!(
+        negotiate(&[Compression::Lz4], &[Compression::Zstd, Compression::Lz4]),
+        Some(Compression::Lz4)
+    );
+    assert_eq!(negotiate(&[], &[Compression::Zstd]), None);
+}
+
 #[test]
 fn serialize_deserialize() -> anyhow::Result<()> {
     let mut rng = make_rng(89028037453);
@@ -1,23 +1,25 @@
 use crate::accounts_data::AccountDataError;
 use crate::client::{
     AnnounceAccountRequest, BlockApproval, BlockHeadersRequest, BlockHeadersResponse, BlockRequest,
-    BlockResponse, ChunkEndorsementMessage, ProcessTxRequest, RecvChallenge, StateRequestHeader,
-    StateRequestPart, StateResponse, TxStatusRequest, TxStatusResponse,
+    BlockResponse, ChunkEndorsementMessage, ProcessTxRequest, RecvChallenge,
+    RecvEquivocationEvidence, StateRequestHeader, StateRequestPart, StateResponse, TxStatusRequest,
+    TxStatusResponse,
 };
+use crate::client_backpressure::{Admission, MessageClass, Permit};
 use crate::concurrency::atomic_cell::AtomicCell;
 use crate::concurrency::demux;
 use crate::config::PEERS_RESPONSE_MAX_PEERS;
 use crate::network_protocol::SnapshotHostInfoVerificationError;
 use crate::network_protocol::{
-    DistanceVector, Edge, EdgeState, Encoding, OwnedAccount, ParsePeerMessageError,
-    PartialEdgeInfo, PeerChainInfoV2, PeerIdOrHash, PeerInfo, PeersRequest, PeersResponse,
-    RawRoutedMessage, RoutedMessageBody, RoutingTableUpdate, StateResponseInfo, SyncAccountsData,
-    SyncSnapshotHosts,
+    self, Compression, DistanceVector, Edge, EdgeState, Encoding, OwnedAccount,
+    ParsePeerMessageError, PartialEdgeInfo, PeerChainInfoV2, PeerIdOrHash, PeerInfo, PeersRequest,
+    PeersResponse, RawRoutedMessage, RoutedMessageBody, RoutingTableUpdate, StateResponseInfo,
+    SyncAccountsData, SyncSnapshotHosts, MAX_SENDER_ADDRS,
 };
 use crate::peer::stream;
 use crate::peer::tracker::Tracker;
 use crate::peer_manager::connection;
-use crate::peer_manager::network_state::{NetworkState, PRUNE_EDGES_AFTER};
+use crate::peer_manager::network_state::{is_public_ip, NetworkState, PRUNE_EDGES_AFTER};
 #[cfg(test)]
 use crate::peer_manager::peer_manager_actor::Event;
 use crate::peer_manager::peer_manager_actor::MAX_TIER2_PEERS;
@@ -39,6 +41,7 @@ use actix::fut::future::wrap_future;
 use actix::{Actor as _, ActorContext as _, ActorFutureExt as _, AsyncContext as _};
 use lru::LruCache;
 use near_async::messaging::{CanSend, SendAsync};
+use near_async::metrics::ACTOR_MESSAGE_PROCESSING_TIME;
 use near_async::time;
 use near_crypto::Signature;
 use near_o11y::{handler_debug_span, log_assert, WithSpanContext};
@@ -180,6 +183,9 @@ pub(crate) struct PeerActor {
     /// Whether the PeerActor should skip protobuf support detection and use
     /// a given encoding right away.
     force_encoding: Option<Encoding>,
+    /// Codec to compress the large messages to the peer with, negotiated
+    /// during handshake. None if the messages are sent uncompressed.
+    compression: Option<Compression>,
 
     /// Peer status.
     peer_status: PeerStatus,
@@ -336,6 +342,7 @@ impl PeerActor {
                     routed_message_cache: LruCache::new(ROUTED_MESSAGE_CACHE_SIZE),
                     protocol_buffers_supported: false,
                     force_encoding,
+                    compression: None,
                     peer_info: match &stream_type {
                         tcp::StreamType::Inbound => None,
                         tcp::StreamType::Outbound { peer_id, .. } => Some(PeerInfo {
@@ -427,7 +434,18 @@ impl PeerActor {
             _ => (),
         };
 
-        let bytes = msg.serialize(enc);
+        let mut bytes = msg.serialize(enc);
+        if let Some(codec) = self.compression {
+            // Compression is negotiated in the handshakes, so they are never compressed.
+            let is_handshake =
+                matches!(msg, PeerMessage::Tier1Handshake(_) | PeerMessage::Tier2Handshake(_));
+            if enc == Encoding::Proto
+                && !is_handshake
+                && bytes.len() >= self.network_state.config.compression_threshold_bytes
+            {
+                bytes = network_protocol::compress(codec, msg.msg_variant(), bytes);
+            }
+        }
         self.tracker.lock().increment_sent(&self.clock, bytes.len() as u64);
         let bytes_len = bytes.len();
         tracing::trace!(target: "network", msg_len = bytes_len);
@@ -453,6 +471,8 @@ impl PeerActor {
             sender_peer_id: self.network_state.config.node_id(),
             target_peer_id: spec.peer_id,
             sender_listen_port: self.network_state.config.node_addr.as_ref().map(|a| a.port()),
+            sender_addrs: self.network_state.advertised_addrs.load().to_vec(),
+            compressions: self.network_state.config.compression_codecs.clone(),
             sender_chain_info: PeerChainInfoV2 {
                 genesis_id: self.network_state.genesis_id.clone(),
                 // TODO: remove `height` from PeerChainInfo
@@ -638,12 +658,24 @@ impl PeerActor {
                 .map(|port| SocketAddr::new(self.peer_addr.ip(), port)),
             account_id: None,
         };
+        let advertised_addrs = handshake
+            .sender_addrs
+            .iter()
+            .filter(|addr| is_public_ip(&addr.ip()) && Some(**addr) != peer_info.addr)
+            .take(MAX_SENDER_ADDRS)
+            .copied()
+            .collect();
+        self.compression = network_protocol::negotiate_compression(
+            &self.network_state.config.compression_codecs,
+            &handshake.compressions,
+        );
 
         let now = self.clock.now();
         let conn = Arc::new(connection::Connection {
             tier,
             addr: ctx.address(),
             peer_info: peer_info.clone(),
+            advertised_addrs,
             owned_account: handshake.owned_account.clone(),
             genesis_id: handshake.sender_chain_info.genesis_id.clone(),
             tracked_shards: handshake.sender_chain_info.tracked_shards.clone(),
@@ -1052,6 +1084,9 @@ impl PeerActor {
         msg: PeerMessage,
     ) {
         let _span = tracing::trace_span!(target: "network", "receive_message").entered();
+        if let Some(recorder) = &self.network_state.recorder {
+            recorder.record(&self.clock, &conn.peer_info.id, &msg);
+        }
         #[cfg(test)]
         let message_processed_event = {
             let sink = self.network_state.config.event_sink.clone();
@@ -1061,6 +1096,14 @@ impl PeerActor {
                 sink.send(Event::MessageProcessed(tier, msg));
             }
         };
+        let admission =
+            MessageClass::of(&msg).map(|class| self.network_state.client_backpressure.admit(class));
+        if let Some(Admission::Dropped) = admission {
+            tracing::debug!(target: "network", msg_type = <&'static str>::from(&msg), "Client is overloaded, dropping the message");
+            #[cfg(test)]
+            message_processed_event();
+            return;
+        }
         let was_requested = match &msg {
             PeerMessage::Block(block) => {
                 self.network_state.txns_since_last_block.store(0, Ordering::Release);
@@ -1082,7 +1125,9 @@ impl PeerActor {
         let clock = self.clock.clone();
         let network_state = self.network_state.clone();
         let peer_id = conn.peer_info.id.clone();
-        let handling_future = async move {
+        let handling_future = move |permit: Option<Permit>| async move {
+            // Held until the client is done with the message.
+            let _permit = permit;
             Ok(match msg {
                 PeerMessage::Routed(msg) => {
                     let msg_hash = msg.hash();
@@ -1149,6 +1194,10 @@ impl PeerActor {
                     network_state.client.send_async(RecvChallenge(challenge)).await.ok();
                     None
                 }
+                PeerMessage::EquivocationEvidence(evidence) => {
+                    network_state.client.send_async(RecvEquivocationEvidence(*evidence)).await.ok();
+                    None
+                }
                 PeerMessage::StateRequestHeader(shard_id, sync_hash) => network_state
                     .client
                     .send_async(StateRequestHeader { shard_id, sync_hash })
@@ -1174,18 +1223,37 @@ impl PeerActor {
                 }
             })
         };
-        ctx.spawn(wrap_future(handling_future.in_current_span()).map(
-            |res, act: &mut PeerActor, ctx| {
-                match res {
-                    // TODO(gprusak): make sure that for routed messages we drop routeback info correctly.
-                    Ok(Some(resp)) => act.send_message_or_log(&resp),
-                    Ok(None) => {}
-                    Err(ban_reason) => act.stop(ctx, ClosingReason::Ban(ban_reason)),
-                }
-                #[cfg(test)]
-                message_processed_event();
-            },
-        ));
+        let span = tracing::Span::current();
+        let spawn = move |ctx: &mut actix::Context<PeerActor>, permit| {
+            ctx.spawn(wrap_future(handling_future(permit).instrument(span)).map(
+                |res, act: &mut PeerActor, ctx| {
+                    match res {
+                        // TODO(gprusak): make sure that for routed messages we drop routeback info correctly.
+                        Ok(Some(resp)) => act.send_message_or_log(&resp),
+                        Ok(None) => {}
+                        Err(ban_reason) => act.stop(ctx, ClosingReason::Ban(ban_reason)),
+                    }
+                    #[cfg(test)]
+                    message_processed_event();
+                },
+            ));
+        };
+        match admission {
+            None => spawn(ctx, None),
+            Some(Admission::Admitted(permit)) => spawn(ctx, Some(permit)),
+            // Stops reading the messages of the peer until the client
+            // catches up, see `crate::client_backpressure`. The actor keeps
+            // handling everything else, sending to the peer in particular.
+            Some(admission) => {
+                self.framed.pause_reads();
+                ctx.spawn(wrap_future(admission.permit()).map(
+                    move |permit, act: &mut PeerActor, ctx| {
+                        act.framed.resume_reads();
+                        spawn(ctx, permit);
+                    },
+                ));
+            }
+        }
     }
 
     #[tracing::instrument(
@@ -1696,6 +1764,8 @@ impl actix::Handler<stream::Frame> for PeerActor {
     type Result = ();
     #[perf]
     fn handle(&mut self, stream::Frame(msg): stream::Frame, ctx: &mut Self::Context) {
+        let _timer =
+            ACTOR_MESSAGE_PROCESSING_TIME.with_label_values(&["PeerActor", "Frame"]).start_timer();
         let _span = tracing::debug_span!(
             target: "network",
             "handle",
@@ -1778,6 +1848,9 @@ impl actix::Handler<WithSpanContext<SendMessage>> for PeerActor {
 
     #[perf]
     fn handle(&mut self, msg: WithSpanContext<SendMessage>, _: &mut Self::Context) {
+        let _timer = ACTOR_MESSAGE_PROCESSING_TIME
+            .with_label_values(&["PeerActor", "SendMessage"])
+            .start_timer();
         let (_span, msg) = handler_debug_span!(target: "network", msg);
         self.send_message_or_log(&msg.message);
     }
@@ -58,6 +58,8 @@ pub(crate) enum Error {
 
 pub(crate) struct FramedStream<Actor: actix::Actor> {
     queue_send: tokio::sync::mpsc::UnboundedSender<Frame>,
+    /// Whether the recv loop reads the next frame, see `pause_reads`.
+    read_gate: tokio::sync::watch::Sender<bool>,
     stats: Arc<connection::Stats>,
     send_buf_size_metric: Arc<metrics::IntGaugeGuard>,
     addr: actix::Addr<Actor>,
@@ -76,6 +78,7 @@ where
     ) -> Self {
         let (tcp_recv, tcp_send) = tokio::io::split(stream.stream);
         let (queue_send, queue_recv) = tokio::sync::mpsc::unbounded_channel();
+        let (read_gate, read_gate_recv) = tokio::sync::watch::channel(true);
         let send_buf_size_metric = Arc::new(metrics::MetricGuard::new(
             &*metrics::PEER_DATA_WRITE_BUFFER_SIZE,
             vec![stream.peer_addr.to_string()],
@@ -94,14 +97,32 @@ where
             let addr = ctx.address();
             let stats = stats.clone();
             async move {
-                if let Err(err) =
-                    Self::run_recv_loop(stream.peer_addr, tcp_recv, addr.clone(), stats).await
+                if let Err(err) = Self::run_recv_loop(
+                    stream.peer_addr,
+                    tcp_recv,
+                    read_gate_recv,
+                    addr.clone(),
+                    stats,
+                )
+                .await
                 {
                     addr.do_send(Error::Recv(err));
                 }
             }
         }));
-        Self { queue_send, stats, send_buf_size_metric, addr: ctx.address() }
+        Self { queue_send, read_gate, stats, send_buf_size_metric, addr: ctx.address() }
+    }
+
+    /// Stops reading the frames from the connection until `resume_reads` is
+    /// called, so that the TCP flow control slows the peer down. The frame
+    /// being handled is the last one read, and the frames are still sent.
+    pub fn pause_reads(&self) {
+        self.read_gate.send_replace(false);
+    }
+
+    /// Resumes reading the frames after `pause_reads`.
+    pub fn resume_reads(&self) {
+        self.read_gate.send_replace(true);
     }
 
     /// Pushes `msg` to the send queue.
@@ -129,7 +150,8 @@ where
     }
 
     /// Event loop receiving and processing messages.
-    /// Loop waits for the message to be processed before reading the next message.
+    /// Loop waits for the message to be processed before reading the next message,
+    /// and for the reads to be resumed if they are paused.
     /// Note that if the message handler spawns an asynchronous subhandler and returns,
     /// then the loop will start reading the next message before the subhandler returns.
     /// Loop uses a fixed small buffer allocated by BufReader.
@@ -139,6 +161,7 @@ where
     async fn run_recv_loop(
         peer_addr: SocketAddr,
         read: ReadHalf,
+        mut read_gate: tokio::sync::watch::Receiver<bool>,
         addr: actix::Addr<Actor>,
         stats: Arc<connection::Stats>,
     ) -> Result<(), RecvError> {
@@ -152,6 +175,12 @@ where
             vec![peer_addr.to_string()],
         );
         loop {
+            while !*read_gate.borrow_and_update() {
+                if read_gate.changed().await.is_err() {
+                    // The stream has been dropped along with the actor.
+                    return Ok(());
+                }
+            }
             let n = read.read_u32_le().await.map_err(RecvError::IO)? as usize;
             if n > NETWORK_MESSAGE_MAX_SIZE_BYTES {
                 return Err(RecvError::MessageTooLarge {
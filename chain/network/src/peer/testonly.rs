@@ -139,6 +139,7 @@ impl PeerHandle {
             shards_manager_sender,
             state_witness_sender.break_apart().into_multi_sender(),
             vec![],
+            None,
         ));
         let actix = ActixSystem::spawn({
             let clock = clock.clone();
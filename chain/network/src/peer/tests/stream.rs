@@ -12,6 +12,8 @@ use tokio::sync::mpsc;
 struct Actor {
     stream: stream::FramedStream<Actor>,
     queue_send: mpsc::UnboundedSender<stream::Frame>,
+    /// Whether to pause the reads on every received frame.
+    pause_reads: bool,
 }
 
 impl actix::Actor for Actor {
@@ -29,9 +31,26 @@ impl actix::Handler<SendFrame> for Actor {
     }
 }
 
+#[derive(actix::Message)]
+#[rtype("()")]
+struct SetPauseReads(bool);
+
+impl actix::Handler<SetPauseReads> for Actor {
+    type Result = ();
+    fn handle(&mut self, SetPauseReads(pause): SetPauseReads, _ctx: &mut Self::Context) {
+        self.pause_reads = pause;
+        if !pause {
+            self.stream.resume_reads();
+        }
+    }
+}
+
 impl actix::Handler<stream::Frame> for Actor {
     type Result = ();
     fn handle(&mut self, frame: stream::Frame, _ctx: &mut Self::Context) {
+        if self.pause_reads {
+            self.stream.pause_reads();
+        }
         self.queue_send.send(frame).ok().unwrap();
     }
 }
@@ -56,7 +75,7 @@ impl Actor {
             system: ActixSystem::spawn(|| {
                 Actor::create(|ctx| {
                     let stream = stream::FramedStream::spawn(ctx, s, Arc::default());
-                    Self { stream, queue_send }
+                    Self { stream, queue_send, pause_reads: false }
                 })
             })
             .await,
@@ -90,3 +109,27 @@ async fn send_recv() {
         }
     }
 }
+
+#[tokio::test]
+async fn send_while_reads_paused() {
+    let mut rng = make_rng(98324532);
+    let (s1, s2) = tcp::Stream::loopback(data::make_peer_id(&mut rng), tcp::Tier::T2).await;
+    let mut a1 = Actor::spawn(s1).await;
+    let mut a2 = Actor::spawn(s2).await;
+    let frame = |byte: u8| stream::Frame(vec![byte; 100]);
+
+    a2.system.addr.send(SetPauseReads(true)).await.unwrap();
+    a1.system.addr.send(SendFrame(frame(1))).await.unwrap();
+    a1.system.addr.send(SendFrame(frame(2))).await.unwrap();
+    assert_eq!(a2.queue_recv.recv().await.unwrap(), frame(1));
+    // The reads are paused after the first frame.
+    let timeout = std::time::Duration::from_millis(100);
+    assert!(tokio::time::timeout(timeout, a2.queue_recv.recv()).await.is_err());
+
+    // The actor still sends while its reads are paused.
+    a2.system.addr.send(SendFrame(frame(3))).await.unwrap();
+    assert_eq!(a1.queue_recv.recv().await.unwrap(), frame(3));
+
+    a2.system.addr.send(SetPauseReads(false)).await.unwrap();
+    assert_eq!(a2.queue_recv.recv().await.unwrap(), frame(2));
+}
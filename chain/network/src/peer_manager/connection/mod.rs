@@ -12,6 +12,7 @@ use crate::stats::metrics;
 use crate::tcp;
 use crate::types::{BlockInfo, FullPeerInfo, PeerChainInfo, PeerType, ReasonForBan};
 use arc_swap::ArcSwap;
+use near_async::metrics::ACTOR_MAILBOX_DEPTH;
 use near_async::time;
 use near_crypto::PublicKey;
 use near_o11y::WithSpanContextExt;
@@ -21,6 +22,7 @@ use near_primitives::types::ShardId;
 use std::collections::{hash_map::Entry, HashMap};
 use std::fmt;
 use std::future::Future;
+use std::net::SocketAddr;
 use std::sync::atomic::AtomicU64;
 use std::sync::{Arc, Weak};
 
@@ -79,6 +81,8 @@ pub(crate) struct Connection {
     pub addr: actix::Addr<PeerActor>,
 
     pub peer_info: PeerInfo,
+    /// Other public addresses the peer advertised in its handshake.
+    pub advertised_addrs: Vec<SocketAddr>,
     /// AccountKey ownership proof.
     pub owned_account: Option<SignedOwnedAccount>,
     /// Chain Id and hash of genesis block.
@@ -139,7 +143,11 @@ impl Connection {
     pub fn send_message(&self, msg: Arc<PeerMessage>) {
         let msg_kind = msg.msg_variant().to_string();
         tracing::trace!(target: "network", ?msg_kind, "Send message");
-        self.addr.do_send(SendMessage { message: msg }.with_span_context());
+        self.addr.do_send(
+            SendMessage { message: msg }
+                .with_span_context()
+                .in_mailbox(ACTOR_MAILBOX_DEPTH.with_label_values(&["PeerActor"])),
+        );
     }
 
     pub fn send_accounts_data(
@@ -0,0 +1,163 @@
+//! Addresses this node advertises in its handshakes, besides the one the peers
+//! observe on the connection: the external address of the port mapped on the
+//! NAT gateway, and the public IPv4 and IPv6 addresses of the listener.
+use crate::network_protocol::{PeerInfo, MAX_SENDER_ADDRS};
+use crate::port_mapping;
+use near_async::time;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+
+/// How often the port mapping is renewed and the addresses of the local
+/// interfaces are looked up again.
+pub(crate) const UPDATE_ADVERTISED_ADDRS_INTERVAL: time::Duration = time::Duration::minutes(10);
+
+/// Addresses the routes to the internet of each IP version are looked up for.
+/// They are documentation addresses: no packets are sent to them.
+const PROBE_IPV4: IpAddr = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+const PROBE_IPV6: IpAddr = IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1));
+
+/// Whether nodes elsewhere on the internet can connect to the IP.
+// TODO: use ip.is_global() instead, once it is stable.
+pub(crate) fn is_public_ip(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let shared = ip.octets()[0] == 100 && (ip.octets()[1] & 0xc0) == 64;
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || ip.is_multicast()
+                || shared)
+        }
+        IpAddr::V6(ip) => {
+            let prefix = ip.segments()[0];
+            let unique_local = (prefix & 0xfe00) == 0xfc00;
+            let link_local = (prefix & 0xffc0) == 0xfe80;
+            let documentation = prefix == 0x2001 && ip.segments()[1] == 0xdb8;
+            // The IPv4-mapped addresses are not used on the wire, the peers
+            // advertise the IPv4 addresses themselves.
+            let ipv4_mapped = ip.to_ipv4_mapped().is_some();
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                || unique_local
+                || link_local
+                || documentation
+                || ipv4_mapped)
+        }
+    }
+}
+
+/// Returns the IP of the local interface the OS routes the packets to the IP
+/// through. Connecting a UDP socket doesn't send anything.
+fn local_ip_towards(ip: IpAddr) -> Option<IpAddr> {
+    let unspecified = match ip {
+        IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+    };
+    let socket = std::net::UdpSocket::bind((unspecified, 0)).ok()?;
+    socket.connect((ip, 53)).ok()?;
+    Some(socket.local_addr().ok()?.ip())
+}
+
+/// Returns the public addresses the listener accepts connections on. A
+/// listener on the unspecified IPv6 address is expected to be dual-stack,
+/// which it is by default on Linux.
+fn local_public_addrs(listen_addr: SocketAddr) -> Vec<SocketAddr> {
+    let ips = match listen_addr.ip() {
+        ip if !ip.is_unspecified() => vec![ip],
+        IpAddr::V4(_) => local_ip_towards(PROBE_IPV4).into_iter().collect(),
+        IpAddr::V6(_) => {
+            [PROBE_IPV6, PROBE_IPV4].into_iter().filter_map(local_ip_towards).collect()
+        }
+    };
+    ips.into_iter().filter(is_public_ip).map(|ip| SocketAddr::new(ip, listen_addr.port())).collect()
+}
+
+impl super::NetworkState {
+    /// Renews the port mapping and looks up the public addresses of the
+    /// listener again.
+    pub async fn update_advertised_addrs(&self, clock: &time::Clock) {
+        let Some(node_addr) = self.config.node_addr.as_ref() else {
+            return;
+        };
+        let mut addrs = vec![];
+        if self.config.port_mapping {
+            match port_mapping::map_port(clock, node_addr.port()).await {
+                Ok(addr) if is_public_ip(&IpAddr::V4(*addr.ip())) => addrs.push(addr.into()),
+                Ok(addr) => {
+                    tracing::warn!(target: "network", %addr, "Mapped the port on a gateway without a public IP, the node is behind another NAT");
+                }
+                Err(err) => {
+                    tracing::warn!(target: "network", err = format!("{err:#}"), "Failed to map the port on the NAT gateway");
+                }
+            }
+        }
+        for addr in local_public_addrs(**node_addr) {
+            if !addrs.iter().any(|a: &SocketAddr| a.ip() == addr.ip()) {
+                addrs.push(addr);
+            }
+        }
+        addrs.truncate(MAX_SENDER_ADDRS);
+        if **self.advertised_addrs.load() != addrs {
+            tracing::info!(target: "network", ?addrs, "Advertising addresses in the handshakes");
+            self.advertised_addrs.store(Arc::new(addrs));
+        }
+    }
+
+    /// Returns the addresses to connect to the peer on, in the order to try
+    /// them: the address it's known by, then the others it advertised. IPv6
+    /// addresses go first if this node has a public IPv6 address itself, as
+    /// there is usually no NAT in the way of IPv6 connections.
+    pub fn peer_addrs(&self, peer_info: &PeerInfo) -> Vec<SocketAddr> {
+        let mut addrs: Vec<SocketAddr> = peer_info.addr.into_iter().collect();
+        if let Some(peer_state) = self.peer_store.get_peer_state(&peer_info.id) {
+            for addr in peer_state.advertised_addrs {
+                if !addrs.contains(&addr) {
+                    addrs.push(addr);
+                }
+            }
+        }
+        if self.advertised_addrs.load().iter().any(SocketAddr::is_ipv6) {
+            // The sort is stable, so the order is kept otherwise.
+            addrs.sort_by_key(|addr| !addr.is_ipv6());
+        }
+        addrs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_public_ip;
+    use std::net::IpAddr;
+
+    #[test]
+    fn test_is_public_ip() {
+        for ip in ["8.8.8.8", "2606:4700::1111", "2a01:4f8::1"] {
+            assert!(is_public_ip(&ip.parse::<IpAddr>().unwrap()), "{ip}");
+        }
+        for ip in [
+            "10.0.0.1",
+            "100.64.0.1",
+            "127.0.0.1",
+            "169.254.0.1",
+            "192.0.2.1",
+            "224.0.0.1",
+            "239.255.255.250",
+            "255.255.255.255",
+            "::1",
+            "::",
+            "fc00::1",
+            "fe80::1",
+            "2001:db8::1",
+            "ff02::1",
+            "ff0e::1",
+            "::ffff:8.8.8.8",
+            "::ffff:10.0.0.1",
+        ] {
+            assert!(!is_public_ip(&ip.parse::<IpAddr>().unwrap()), "{ip}");
+        }
+    }
+}
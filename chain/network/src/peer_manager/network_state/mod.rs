@@ -1,6 +1,7 @@
 use crate::accounts_data::{AccountDataCache, AccountDataError};
 use crate::announce_accounts::AnnounceAccountCache;
 use crate::client::ClientSenderForNetwork;
+use crate::client_backpressure::ClientBackpressure;
 use crate::concurrency::demux;
 use crate::concurrency::runtime::Runtime;
 use crate::config;
@@ -14,6 +15,7 @@ use crate::peer_manager::connection;
 use crate::peer_manager::connection_store;
 use crate::peer_manager::peer_store;
 use crate::private_actix::RegisterPeerError;
+use crate::recorder::MessageRecorder;
 use crate::routing::route_back_cache::RouteBackCache;
 use crate::routing::NetworkTopologyChange;
 use crate::shards_manager::ShardsManagerRequestFromNetwork;
@@ -37,9 +39,12 @@ use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
 use tracing::Instrument as _;
 
+mod advertised_addrs;
 mod routing;
 mod tier1;
 
+pub(crate) use advertised_addrs::{is_public_ip, UPDATE_ADVERTISED_ADDRS_INTERVAL};
+
 /// Limit number of pending Peer actors to avoid OOM.
 pub(crate) const LIMIT_PENDING_PEERS: usize = 60;
 
@@ -95,11 +100,17 @@ pub(crate) struct NetworkState {
     /// GenesisId of the chain.
     pub genesis_id: GenesisId,
     pub client: ClientSenderForNetwork,
+    /// Bounds the messages in flight to the client.
+    pub client_backpressure: ClientBackpressure,
     pub shards_manager_adapter: Sender<ShardsManagerRequestFromNetwork>,
     pub partial_witness_adapter: PartialWitnessSenderForNetwork,
+    /// Records the received consensus messages, if configured.
+    pub recorder: Option<MessageRecorder>,
 
     /// Network-related info about the chain.
     pub chain_info: ArcSwap<Option<ChainInfo>>,
+    /// Addresses advertised in the handshakes, see `update_advertised_addrs`.
+    pub advertised_addrs: ArcSwap<Vec<SocketAddr>>,
     /// AccountsData for TIER1 accounts.
     pub accounts_data: Arc<AccountDataCache>,
     /// AnnounceAccounts mapping TIER1 account ids to peer ids.
@@ -167,6 +178,7 @@ impl NetworkState {
         shards_manager_adapter: Sender<ShardsManagerRequestFromNetwork>,
         partial_witness_adapter: PartialWitnessSenderForNetwork,
         whitelist_nodes: Vec<WhitelistNode>,
+        recorder: Option<MessageRecorder>,
     ) -> Self {
         Self {
             runtime: Runtime::new(),
@@ -181,9 +193,12 @@ impl NetworkState {
             })),
             genesis_id,
             client,
+            client_backpressure: ClientBackpressure::new(),
             shards_manager_adapter,
             partial_witness_adapter,
+            recorder,
             chain_info: Default::default(),
+            advertised_addrs: Default::default(),
             tier2: connection::Pool::new(config.node_id()),
             tier1: connection::Pool::new(config.node_id()),
             inbound_handshake_permits: Arc::new(tokio::sync::Semaphore::new(LIMIT_PENDING_PEERS)),
@@ -340,6 +355,7 @@ impl NetworkState {
                         .await.map_err(|_: ReasonForBan| RegisterPeerError::InvalidEdge)?;
                     // Write to the peer store
                     this.peer_store.peer_connected(&clock, peer_info);
+                    this.peer_store.set_advertised_addrs(&peer_info.id, conn.advertised_addrs.clone());
                 }
             }
             Ok(())
@@ -426,9 +442,10 @@ impl NetworkState {
             interval.tick(&clock).await;
 
             let result = async {
-                let stream = tcp::Stream::connect(&peer_info, tcp::Tier::T2)
+                let addrs = self.peer_addrs(&peer_info);
+                let stream = tcp::Stream::connect_any(&peer_info.id, &addrs, tcp::Tier::T2)
                     .await
-                    .context("tcp::Stream::connect()")?;
+                    .context("tcp::Stream::connect_any()")?;
                 PeerActor::spawn_and_handshake(clock.clone(), stream, None, self.clone())
                     .await
                     .context("PeerActor::spawn()")?;
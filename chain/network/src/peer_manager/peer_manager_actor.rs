@@ -1,6 +1,7 @@
 use crate::client::{ClientSenderForNetwork, SetNetworkInfo};
 use crate::config;
 use crate::debug::{DebugStatus, GetDebugStatus};
+use crate::dns_seed;
 use crate::network_protocol;
 use crate::network_protocol::SyncSnapshotHosts;
 use crate::network_protocol::{
@@ -8,8 +9,11 @@ use crate::network_protocol::{
 };
 use crate::peer::peer_actor::PeerActor;
 use crate::peer_manager::connection;
-use crate::peer_manager::network_state::{NetworkState, WhitelistNode};
+use crate::peer_manager::network_state::{
+    NetworkState, WhitelistNode, UPDATE_ADVERTISED_ADDRS_INTERVAL,
+};
 use crate::peer_manager::peer_store;
+use crate::recorder::MessageRecorder;
 use crate::shards_manager::ShardsManagerRequestFromNetwork;
 use crate::state_witness::PartialWitnessSenderForNetwork;
 use crate::stats::metrics;
@@ -229,6 +233,14 @@ impl PeerManagerActor {
             }
             v
         };
+        let recorder = match &config.record_messages {
+            Some(record_messages) => {
+                Some(MessageRecorder::open(record_messages.clone()).with_context(|| {
+                    format!("Failed opening {}", record_messages.path.display())
+                })?)
+            }
+            None => None,
+        };
         let my_peer_id = config.node_id();
         let arbiter = actix::Arbiter::new().handle();
         let clock = clock;
@@ -242,6 +254,7 @@ impl PeerManagerActor {
             shards_manager_adapter,
             partial_witness_adapter,
             whitelist_nodes,
+            recorder,
         ));
         arbiter.spawn({
             let arbiter = arbiter.clone();
@@ -280,6 +293,19 @@ impl PeerManagerActor {
                             }
                         }
                     });
+                    // Renew the port mapping and look up the addresses advertised in the
+                    // handshakes periodically.
+                    arbiter.spawn({
+                        let clock = clock.clone();
+                        let state = state.clone();
+                        let mut interval = time::Interval::new(clock.now(), UPDATE_ADVERTISED_ADDRS_INTERVAL);
+                        async move {
+                            loop {
+                                interval.tick(&clock).await;
+                                state.update_advertised_addrs(&clock).await;
+                            }
+                        }
+                    });
                 }
                 if let Some(cfg) = state.config.tier1.clone() {
                     // Connect to TIER1 proxies and broadcast the list those connections periodically.
@@ -309,6 +335,25 @@ impl PeerManagerActor {
                         }
                     });
                 }
+                if let Some(cfg) = state.config.dns_seeds.clone() {
+                    // Look up the boot nodes on the DNS seeds periodically.
+                    arbiter.spawn({
+                        let clock = clock.clone();
+                        let state = state.clone();
+                        let mut interval = time::Interval::new(clock.now(), dns_seed::RESOLVE_DNS_SEEDS_INTERVAL);
+                        async move {
+                            loop {
+                                interval.tick(&clock).await;
+                                for peer_info in dns_seed::resolve(&clock, &cfg).await {
+                                    if peer_info.addr.is_some_and(|addr| state.peer_store.is_blacklisted(&addr)) {
+                                        continue;
+                                    }
+                                    state.peer_store.add_direct_peer(&clock, peer_info);
+                                }
+                            }
+                        }
+                    });
+                }
                 // Periodically poll the connection store for connections we'd like to re-establish
                 arbiter.spawn({
                     let clock = clock.clone();
@@ -601,7 +646,8 @@ impl PeerManagerActor {
                     let clock = self.clock.clone();
                     async move {
                         let result = async {
-                            let stream = tcp::Stream::connect(&peer_info, tcp::Tier::T2).await.context("tcp::Stream::connect()")?;
+                            let addrs = state.peer_addrs(&peer_info);
+                            let stream = tcp::Stream::connect_any(&peer_info.id, &addrs, tcp::Tier::T2).await.context("tcp::Stream::connect_any()")?;
                             PeerActor::spawn_and_handshake(clock.clone(),stream,None,state.clone()).await.context("PeerActor::spawn()")?;
                             anyhow::Ok(())
                         }.await;
@@ -971,6 +1017,12 @@ impl PeerManagerActor {
                 self.state.tier2.broadcast_message(Arc::new(PeerMessage::Challenge(challenge)));
                 NetworkResponses::NoResponse
             }
+            NetworkRequests::EquivocationEvidence(evidence) => {
+                self.state
+                    .tier2
+                    .broadcast_message(Arc::new(PeerMessage::EquivocationEvidence(evidence)));
+                NetworkResponses::NoResponse
+            }
             NetworkRequests::ChunkStateWitnessAck(target, ack) => {
                 self.state.send_message_to_account(
                     &self.clock,
@@ -359,7 +359,6 @@ impl PeerStore {
         self.0.lock().update(clock)
     }
 
-    #[allow(dead_code)]
     /// Returns the state of the current peer in memory.
     pub fn get_peer_state(&self, peer_id: &PeerId) -> Option<KnownPeerState> {
         self.0.lock().peer_states.get(peer_id).cloned()
@@ -373,6 +372,17 @@ impl PeerStore {
         entry.status = KnownPeerStatus::Connected;
     }
 
+    /// Records the other addresses the peer advertised in its handshake, which
+    /// it's tried on when it can't be reached on its address.
+    pub fn set_advertised_addrs(&self, peer_id: &PeerId, addrs: Vec<SocketAddr>) {
+        let mut inner = self.0.lock();
+        let addrs: Vec<_> =
+            addrs.into_iter().filter(|addr| !inner.config.blacklist.contains(*addr)).collect();
+        if let Some(peer_state) = inner.peer_states.get_mut(peer_id) {
+            peer_state.advertised_addrs = addrs;
+        }
+    }
+
     pub fn peer_disconnected(&self, clock: &time::Clock, peer_id: &PeerId) -> anyhow::Result<()> {
         let mut inner = self.0.lock();
         if let Some(peer_state) = inner.peer_states.get_mut(peer_id) {
@@ -482,3 +482,23 @@ fn test_lru_ignore_duplicate_peers() {
     peer_store.add_indirect_peers(&clock.clock(), peer_infos[10..].iter().cloned());
     assert_peers_in_cache(&peer_store, &peer_ids[5..], &peer_addresses[5..]);
 }
+
+#[test]
+fn test_set_advertised_addrs() {
+    let clock = time::FakeClock::default();
+    let blacklist: blacklist::Blacklist =
+        ["127.0.0.1:1"].iter().map(|e| e.parse().unwrap()).collect();
+    let peer_store = PeerStore::new(&clock.clock(), make_config(&[], blacklist, false)).unwrap();
+    let peer_info = gen_peer_info(0);
+    peer_store.peer_connected(&clock.clock(), &peer_info);
+
+    // Blacklisted addresses are dropped.
+    peer_store.set_advertised_addrs(&peer_info.id, vec![get_addr(1), get_addr(2)]);
+    let peer_state = peer_store.get_peer_state(&peer_info.id).unwrap();
+    assert_eq!(peer_state.advertised_addrs, vec![get_addr(2)]);
+
+    // Addresses of unknown peers are not stored.
+    let unknown = get_peer_id("unknown".to_string());
+    peer_store.set_advertised_addrs(&unknown, vec![get_addr(3)]);
+    assert!(peer_store.get_peer_state(&unknown).is_none());
+}
@@ -96,6 +96,8 @@ async fn loop_connection() {
             sender_peer_id: pm.cfg.node_id(),
             target_peer_id: pm.cfg.node_id(),
             sender_listen_port: Some(port),
+            sender_addrs: vec![],
+            compressions: vec![],
             sender_chain_info: chain.get_peer_chain_info(),
             partial_edge_info: PartialEdgeInfo::new(
                 &pm.cfg.node_id(),
@@ -156,6 +158,8 @@ async fn owned_account_mismatch() {
             sender_peer_id: cfg.node_id(),
             target_peer_id: pm.cfg.node_id(),
             sender_listen_port: Some(port),
+            sender_addrs: vec![],
+            compressions: vec![],
             sender_chain_info: chain.get_peer_chain_info(),
             partial_edge_info: PartialEdgeInfo::new(
                 &cfg.node_id(),
@@ -282,6 +286,8 @@ async fn invalid_edge() {
                 sender_peer_id: cfg.node_id(),
                 target_peer_id: pm.cfg.node_id(),
                 sender_listen_port: Some(port),
+                sender_addrs: vec![],
+                compressions: vec![],
                 sender_chain_info: chain.get_peer_chain_info(),
                 partial_edge_info: edge.clone(),
                 owned_account: Some(
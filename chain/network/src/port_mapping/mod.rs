@@ -0,0 +1,34 @@
+//! Mapping of the listening port of the node on the NAT gateway of its local
+//! network, so that a node behind a home router becomes reachable for inbound
+//! connections.
+//!
+//! NAT-PMP (RFC 6886) is tried first, as it takes just a couple of UDP
+//! datagrams exchanged with the default gateway, and UPnP IGD otherwise. The
+//! mapping is leased for `LEASE_DURATION` and has to be renewed before the
+//! lease expires. Nothing is unmapped when the node stops, the gateway drops
+//! the mapping once its lease expires.
+use near_async::time;
+use std::net::SocketAddrV4;
+
+mod natpmp;
+mod upnp;
+
+#[cfg(test)]
+mod tests;
+
+/// Lease requested for the mapping.
+const LEASE_DURATION: time::Duration = time::Duration::hours(1);
+
+const QUERY_TIMEOUT: time::Duration = time::Duration::seconds(5);
+
+/// Maps the TCP port on the gateway to an external port, the same one if the
+/// gateway allows, and returns the external address of the mapping.
+pub(crate) async fn map_port(clock: &time::Clock, port: u16) -> anyhow::Result<SocketAddrV4> {
+    let natpmp_err = match natpmp::map_port(clock, port).await {
+        Ok(addr) => return Ok(addr),
+        Err(err) => err,
+    };
+    upnp::map_port(clock, port)
+        .await
+        .map_err(|upnp_err| anyhow::anyhow!("NAT-PMP: {natpmp_err:#}; UPnP: {upnp_err:#}"))
+}
@@ -0,0 +1,102 @@
+//! NAT-PMP client, see RFC 6886.
+use super::LEASE_DURATION;
+use anyhow::{bail, Context as _};
+use near_async::time;
+use std::net::{Ipv4Addr, SocketAddrV4};
+
+const SERVER_PORT: u16 = 5351;
+const OP_EXTERNAL_ADDRESS: u8 = 0;
+const OP_MAP_TCP: u8 = 2;
+/// The opcode of a response is the opcode of the request plus 128.
+const OP_RESPONSE: u8 = 128;
+/// Timeout of the first attempt, doubled with every retransmission.
+const INITIAL_TIMEOUT: time::Duration = time::Duration::milliseconds(250);
+const MAX_ATTEMPTS: usize = 4;
+
+/// Returns the default IPv4 gateway from the routing table of the kernel.
+/// Only Linux is supported, elsewhere the gateway is not found.
+fn default_gateway() -> Option<Ipv4Addr> {
+    parse_default_gateway(&std::fs::read_to_string("/proc/net/route").ok()?)
+}
+
+/// Parses the contents of /proc/net/route, which lists the destination and
+/// the gateway of every route in hex, in the byte order of the host. The
+/// default gateway is the gateway of the route to 0.0.0.0.
+pub(super) fn parse_default_gateway(routes: &str) -> Option<Ipv4Addr> {
+    routes.lines().skip(1).find_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.get(1) != Some(&"00000000") {
+            return None;
+        }
+        let gateway = u32::from_str_radix(fields.get(2)?, 16).ok()?;
+        (gateway != 0).then(|| Ipv4Addr::from(gateway.to_ne_bytes()))
+    })
+}
+
+/// Sends the request to the gateway and waits for its response, retransmitting
+/// the request on timeout. Returns the length of the response.
+async fn request(
+    clock: &time::Clock,
+    socket: &tokio::net::UdpSocket,
+    request: &[u8],
+    response: &mut [u8],
+) -> anyhow::Result<usize> {
+    let mut timeout = INITIAL_TIMEOUT;
+    for _ in 0..MAX_ATTEMPTS {
+        socket.send(request).await?;
+        tokio::select! {
+            _ = clock.sleep(timeout) => {}
+            n = socket.recv(response) => {
+                let n = n?;
+                if n >= 2 && response[1] == request[1] + OP_RESPONSE {
+                    return Ok(n);
+                }
+            }
+        }
+        timeout = timeout * 2;
+    }
+    bail!("no response from the gateway")
+}
+
+fn check_response(response: &[u8], len: usize) -> anyhow::Result<()> {
+    if response.len() < len {
+        bail!("response of {} bytes is too short", response.len());
+    }
+    let result_code = u16::from_be_bytes([response[2], response[3]]);
+    if result_code != 0 {
+        bail!("the gateway refused the request with result code {result_code}");
+    }
+    Ok(())
+}
+
+/// Parses the response to the external address request.
+pub(super) fn parse_external_address(response: &[u8]) -> anyhow::Result<Ipv4Addr> {
+    check_response(response, 12)?;
+    Ok(Ipv4Addr::new(response[8], response[9], response[10], response[11]))
+}
+
+/// Parses the response to the mapping request, returns the external port.
+pub(super) fn parse_mapping(response: &[u8]) -> anyhow::Result<u16> {
+    check_response(response, 16)?;
+    Ok(u16::from_be_bytes([response[10], response[11]]))
+}
+
+pub(super) async fn map_port(clock: &time::Clock, port: u16) -> anyhow::Result<SocketAddrV4> {
+    let gateway = default_gateway().context("no default gateway")?;
+    let socket = tokio::net::UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+    socket.connect((gateway, SERVER_PORT)).await?;
+    let mut response = [0; 16];
+
+    let n = request(clock, &socket, &[0, OP_EXTERNAL_ADDRESS], &mut response).await?;
+    let external_ip = parse_external_address(&response[..n])?;
+
+    let mut map_request = [0; 12];
+    map_request[1] = OP_MAP_TCP;
+    map_request[4..6].copy_from_slice(&port.to_be_bytes());
+    map_request[6..8].copy_from_slice(&port.to_be_bytes());
+    map_request[8..12].copy_from_slice(&(LEASE_DURATION.whole_seconds() as u32).to_be_bytes());
+    let n = request(clock, &socket, &map_request, &mut response).await?;
+    // The gateway may map another external port if the requested one is taken.
+    let external_port = parse_mapping(&response[..n])?;
+    Ok(SocketAddrV4::new(external_ip, external_port))
+}
@@ -0,0 +1,71 @@
+use super::natpmp;
+use super::upnp;
+use std::net::Ipv4Addr;
+
+#[cfg(target_endian = "little")]
+#[test]
+fn test_parse_default_gateway() {
+    let routes =
+        "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT\n\
+        eth0\t0001A8C0\t00000000\t0001\t0\t0\t100\t00FFFFFF\t0\t0\t0\n\
+        eth0\t00000000\t0101A8C0\t0003\t0\t0\t100\t00000000\t0\t0\t0\n";
+    assert_eq!(natpmp::parse_default_gateway(routes), Some(Ipv4Addr::new(192, 168, 1, 1)));
+    assert_eq!(natpmp::parse_default_gateway("Iface\tDestination\tGateway\n"), None);
+}
+
+#[test]
+fn test_parse_natpmp_responses() {
+    let external_address = [0, 128, 0, 0, 0, 0, 0, 7, 203, 0, 113, 5];
+    assert_eq!(
+        natpmp::parse_external_address(&external_address).unwrap(),
+        Ipv4Addr::new(203, 0, 113, 5)
+    );
+    let mapping = [0, 130, 0, 0, 0, 0, 0, 7, 0x5f, 0xe7, 0x5f, 0xe8, 0, 0, 0x0e, 0x10];
+    assert_eq!(natpmp::parse_mapping(&mapping).unwrap(), 24552);
+    // Result code 2: not authorized.
+    let refused = [0, 130, 0, 2, 0, 0, 0, 7, 0, 0, 0, 0, 0, 0, 0, 0];
+    assert!(natpmp::parse_mapping(&refused).is_err());
+    assert!(natpmp::parse_mapping(&mapping[..12]).is_err());
+}
+
+#[test]
+fn test_parse_ssdp_response() {
+    let response = "HTTP/1.1 200 OK\r\n\
+        CACHE-CONTROL: max-age=120\r\n\
+        Location: http://192.168.1.1:5000/rootDesc.xml\r\n\
+        ST: urn:schemas-upnp-org:device:InternetGatewayDevice:1\r\n\r\n";
+    let location = upnp::header(response, "LOCATION").unwrap();
+    let url = upnp::Url::parse(location).unwrap();
+    assert_eq!(url.addr, "192.168.1.1:5000".parse().unwrap());
+    assert_eq!(url.path, "/rootDesc.xml");
+    assert_eq!(
+        upnp::Url::parse("http://192.168.1.1").unwrap(),
+        upnp::Url { addr: "192.168.1.1:80".parse().unwrap(), path: "/".to_string() }
+    );
+    assert!(upnp::Url::parse("https://192.168.1.1/").is_err());
+    assert!(upnp::Url::parse("http://router.local/").is_err());
+}
+
+#[test]
+fn test_find_service() {
+    let description = "<root><device><serviceList>\
+        <service>\
+        <serviceType>urn:schemas-upnp-org:service:Layer3Forwarding:1</serviceType>\
+        <controlURL>/ctl/L3F</controlURL>\
+        </service>\
+        <service>\
+        <serviceType>urn:schemas-upnp-org:service:WANIPConnection:1</serviceType>\
+        <controlURL>ctl/IPConn</controlURL>\
+        </service>\
+        </serviceList></device></root>";
+    let (service_type, control_url) = upnp::find_service(description).unwrap();
+    assert_eq!(service_type, "urn:schemas-upnp-org:service:WANIPConnection:1");
+    let base = upnp::Url::parse("http://192.168.1.1:5000/rootDesc.xml").unwrap();
+    assert_eq!(base.join(control_url).unwrap().path, "/ctl/IPConn");
+    assert_eq!(upnp::find_service("<root></root>"), None);
+
+    let response = "<s:Envelope><s:Body><u:GetExternalIPAddressResponse>\
+        <NewExternalIPAddress>203.0.113.5</NewExternalIPAddress>\
+        </u:GetExternalIPAddressResponse></s:Body></s:Envelope>";
+    assert_eq!(upnp::element(response, "NewExternalIPAddress"), Some("203.0.113.5"));
+}
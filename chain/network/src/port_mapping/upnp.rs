@@ -0,0 +1,208 @@
+//! UPnP IGD client. The gateway is discovered with SSDP and the mapping is
+//! added through the SOAP interface of its WANIPConnection or WANPPPConnection
+//! service.
+//!
+//! The device descriptions and SOAP responses of gateways are simple enough to
+//! be parsed by looking up the elements by their tags, without an XML parser.
+use super::{LEASE_DURATION, QUERY_TIMEOUT};
+use crate::concurrency::timeout::with_timeout;
+use anyhow::{bail, ensure, Context as _};
+use near_async::time;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4};
+use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+
+const SSDP_ADDR: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(239, 255, 255, 250), 1900);
+const SEARCH_REQUEST: &str = "M-SEARCH * HTTP/1.1\r\n\
+    HOST: 239.255.255.250:1900\r\n\
+    ST: urn:schemas-upnp-org:device:InternetGatewayDevice:1\r\n\
+    MAN: \"ssdp:discover\"\r\n\
+    MX: 2\r\n\r\n";
+const SERVICE_TYPES: [&str; 2] = [
+    "urn:schemas-upnp-org:service:WANIPConnection:1",
+    "urn:schemas-upnp-org:service:WANPPPConnection:1",
+];
+/// Fault of the gateways which only support mappings without a lease.
+const ONLY_PERMANENT_LEASES_SUPPORTED: &str = "<errorCode>725</errorCode>";
+const MAX_RESPONSE_SIZE: u64 = 1 << 16;
+
+/// URL of the form `http://<ip>[:<port>]/<path>`.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct Url {
+    pub addr: SocketAddr,
+    pub path: String,
+}
+
+impl Url {
+    pub fn parse(url: &str) -> anyhow::Result<Self> {
+        let rest = url.strip_prefix("http://").with_context(|| format!("unsupported URL {url}"))?;
+        let (host, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        let addr = match host.parse() {
+            Ok(addr) => addr,
+            Err(_) => SocketAddr::new(
+                host.parse().with_context(|| format!("unsupported host in URL {url}"))?,
+                80,
+            ),
+        };
+        Ok(Self { addr, path: path.to_string() })
+    }
+
+    /// Resolves the URL, which may be relative to this one.
+    pub fn join(&self, url: &str) -> anyhow::Result<Self> {
+        if url.starts_with("http://") {
+            return Self::parse(url);
+        }
+        let path = if url.starts_with('/') { url.to_string() } else { format!("/{url}") };
+        Ok(Self { addr: self.addr, path })
+    }
+}
+
+/// Returns the value of the header in the HTTP response.
+pub(super) fn header<'a>(response: &'a str, name: &str) -> Option<&'a str> {
+    response.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        key.trim().eq_ignore_ascii_case(name).then(|| value.trim())
+    })
+}
+
+/// Returns the text of the first element with the tag.
+pub(super) fn element<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{tag}>"))? + tag.len() + 2;
+    let end = start + xml[start..].find(&format!("</{tag}>"))?;
+    Some(xml[start..end].trim())
+}
+
+/// Returns the type and the control URL of the first WAN connection service
+/// in the device description.
+pub(super) fn find_service(description: &str) -> Option<(&'static str, &str)> {
+    description.split("<service>").skip(1).find_map(|service| {
+        let service_type = element(service, "serviceType")?;
+        let service_type = SERVICE_TYPES.into_iter().find(|t| *t == service_type)?;
+        Some((service_type, element(service, "controlURL")?))
+    })
+}
+
+/// Searches for the gateway and returns the URL of its device description.
+async fn discover() -> anyhow::Result<Url> {
+    let socket = tokio::net::UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+    socket.send_to(SEARCH_REQUEST.as_bytes(), SSDP_ADDR).await?;
+    let mut buf = [0; 2048];
+    loop {
+        let (n, _) = socket.recv_from(&mut buf).await?;
+        if let Some(location) = header(&String::from_utf8_lossy(&buf[..n]), "location") {
+            return Url::parse(location);
+        }
+    }
+}
+
+/// Sends an HTTP/1.0 request, so that the response comes without chunked
+/// encoding. Returns the status and the body of the response, and the local
+/// address of the connection.
+async fn http(
+    url: &Url,
+    method: &str,
+    headers: &str,
+    body: &str,
+) -> anyhow::Result<(u16, String, SocketAddr)> {
+    let mut stream = tokio::net::TcpStream::connect(url.addr).await?;
+    let local_addr = stream.local_addr()?;
+    let request = format!(
+        "{method} {} HTTP/1.0\r\nHost: {}\r\n{headers}Content-Length: {}\r\n\r\n{body}",
+        url.path,
+        url.addr,
+        body.len()
+    );
+    stream.write_all(request.as_bytes()).await?;
+    let mut response = vec![];
+    stream.take(MAX_RESPONSE_SIZE).read_to_end(&mut response).await?;
+    let response = String::from_utf8_lossy(&response);
+    let (head, body) = response.split_once("\r\n\r\n").context("malformed HTTP response")?;
+    let status = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .context("malformed HTTP status line")?;
+    Ok((status, body.to_string(), local_addr))
+}
+
+async fn soap(
+    control_url: &Url,
+    service_type: &str,
+    action: &str,
+    args: &[(&str, String)],
+) -> anyhow::Result<(u16, String, SocketAddr)> {
+    let args: String =
+        args.iter().map(|(name, value)| format!("<{name}>{value}</{name}>")).collect();
+    let body = format!(
+        "<?xml version=\"1.0\"?>\
+        <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+        s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\
+        <s:Body><u:{action} xmlns:u=\"{service_type}\">{args}</u:{action}></s:Body>\
+        </s:Envelope>"
+    );
+    let headers = format!(
+        "Content-Type: text/xml; charset=\"utf-8\"\r\nSOAPAction: \"{service_type}#{action}\"\r\n"
+    );
+    http(control_url, "POST", &headers, &body).await
+}
+
+pub(super) async fn map_port(clock: &time::Clock, port: u16) -> anyhow::Result<SocketAddrV4> {
+    let location =
+        with_timeout(clock, QUERY_TIMEOUT, discover()).await.context("discovering the gateway")?;
+    let (status, description, _) =
+        with_timeout(clock, QUERY_TIMEOUT, http(&location, "GET", "", "")).await?;
+    ensure!(status == 200, "fetching the device description failed with status {status}");
+    let base = match element(&description, "URLBase") {
+        Some(base) => Url::parse(base)?,
+        None => location,
+    };
+    let (service_type, control_url) =
+        find_service(&description).context("the gateway has no WAN connection service")?;
+    let control_url = base.join(control_url)?;
+
+    let (status, response, local_addr) = with_timeout(
+        clock,
+        QUERY_TIMEOUT,
+        soap(&control_url, service_type, "GetExternalIPAddress", &[]),
+    )
+    .await?;
+    ensure!(status == 200, "GetExternalIPAddress failed with status {status}");
+    let external_ip: Ipv4Addr = element(&response, "NewExternalIPAddress")
+        .context("no external IP in the response")?
+        .parse()?;
+    // The mapping points at the address this node reaches the gateway from.
+    let IpAddr::V4(internal_ip) = local_addr.ip() else {
+        bail!("the gateway is reachable only over IPv6");
+    };
+
+    for lease in [LEASE_DURATION.whole_seconds(), 0] {
+        let args = [
+            ("NewRemoteHost", String::new()),
+            ("NewExternalPort", port.to_string()),
+            ("NewProtocol", "TCP".to_string()),
+            ("NewInternalPort", port.to_string()),
+            ("NewInternalClient", internal_ip.to_string()),
+            ("NewEnabled", "1".to_string()),
+            ("NewPortMappingDescription", "neard".to_string()),
+            ("NewLeaseDuration", lease.to_string()),
+        ];
+        let (status, response, _) = with_timeout(
+            clock,
+            QUERY_TIMEOUT,
+            soap(&control_url, service_type, "AddPortMapping", &args),
+        )
+        .await?;
+        if status == 200 {
+            return Ok(SocketAddrV4::new(external_ip, port));
+        }
+        // A permanent mapping stays until the gateway restarts, renewing it
+        // just adds it again.
+        ensure!(
+            response.contains(ONLY_PERMANENT_LEASES_SUPPORTED),
+            "AddPortMapping failed with status {status}"
+        );
+    }
+    bail!("AddPortMapping of a permanent mapping failed")
+}
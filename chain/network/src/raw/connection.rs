@@ -2,6 +2,7 @@ use crate::network_protocol::{
     Encoding, Handshake, HandshakeFailureReason, PartialEdgeInfo, PeerChainInfoV2, PeerIdOrHash,
     PeerMessage, Ping, Pong, RawRoutedMessage, RoutedMessageBody, RoutingTableUpdate,
 };
+use crate::recorder::RecordedMessageBody;
 use crate::tcp;
 use crate::types::{
     Edge, PartialEncodedChunkRequestMsg, PartialEncodedChunkResponseMsg, PeerInfo,
@@ -208,6 +209,8 @@ fn new_handshake(
         // we have to set this even if we have no intention of listening since otherwise
         // the peer will drop our connection
         sender_listen_port: Some(listen_port),
+        sender_addrs: vec![],
+        compressions: vec![],
         sender_chain_info: PeerChainInfoV2 {
             genesis_id: GenesisId { chain_id: chain_id.to_string(), hash: genesis_hash },
             height: head_height,
@@ -423,6 +426,17 @@ impl Connection {
         self.stream.write_message(&PeerMessage::Routed(Box::new(msg))).await
     }
 
+    /// Sends a recorded message the way it was received by the recording
+    /// node, with routed messages targeted at the peer.
+    pub async fn send_recorded_message(
+        &mut self,
+        msg: RecordedMessageBody,
+        ttl: u8,
+    ) -> io::Result<()> {
+        let peer_msg = msg.into_peer_message(&self.secret_key, self.peer_id.clone(), ttl);
+        self.stream.write_message(&peer_msg).await
+    }
+
     fn target_is_for_me(&mut self, target: &PeerIdOrHash) -> bool {
         match target {
             PeerIdOrHash::PeerId(peer_id) => peer_id == &self.my_peer_id,
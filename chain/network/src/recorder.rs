@@ -0,0 +1,259 @@
+//! Recording of the consensus messages received by a node.
+//!
+//! With `network.experimental.record_messages` configured, the node appends
+//! the blocks, chunk parts, state witness parts, approvals and endorsements it
+//! receives for a range of heights to a file. `mock-node --replay` sends the
+//! recorded messages to a rebuilt node in the same order and with the same
+//! delays, so that bugs of the message distribution can be debugged offline.
+//!
+//! A recording is a file of borsh encoded `RecordedMessage`s, one after another.
+use crate::network_protocol::{PeerMessage, RoutedMessageBody};
+use crate::types::{PartialEncodedChunkForwardMsg, PartialEncodedChunkResponseMsg};
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_async::time;
+use near_primitives::block::{Approval, Block};
+use near_primitives::network::PeerId;
+use near_primitives::sharding::PartialEncodedChunk;
+use near_primitives::stateless_validation::{ChunkEndorsement, PartialEncodedStateWitness};
+use near_primitives::types::BlockHeight;
+use parking_lot::Mutex;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct RecordMessagesConfig {
+    /// File the messages are appended to.
+    pub path: PathBuf,
+    /// First height of the recorded messages.
+    pub start_height: BlockHeight,
+    /// Last height of the recorded messages, inclusive.
+    pub end_height: BlockHeight,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, strum::IntoStaticStr)]
+#[allow(clippy::large_enum_variant)]
+pub enum RecordedMessageBody {
+    Block(Block),
+    BlockApproval(Approval),
+    PartialEncodedChunk(PartialEncodedChunk),
+    PartialEncodedChunkForward(PartialEncodedChunkForwardMsg),
+    PartialEncodedChunkResponse(PartialEncodedChunkResponseMsg),
+    PartialEncodedStateWitness(PartialEncodedStateWitness),
+    PartialEncodedStateWitnessForward(PartialEncodedStateWitness),
+    ChunkEndorsement(ChunkEndorsement),
+}
+
+impl RecordedMessageBody {
+    /// Returns the message if it's one of the recorded kinds.
+    fn from_peer_message(msg: &PeerMessage) -> Option<Self> {
+        let PeerMessage::Routed(msg) = msg else {
+            return match msg {
+                PeerMessage::Block(block) => Some(Self::Block(block.clone())),
+                _ => None,
+            };
+        };
+        Some(match &msg.msg.body {
+            RoutedMessageBody::BlockApproval(approval) => Self::BlockApproval(approval.clone()),
+            RoutedMessageBody::VersionedPartialEncodedChunk(chunk) => {
+                Self::PartialEncodedChunk(chunk.clone())
+            }
+            RoutedMessageBody::PartialEncodedChunkForward(forward) => {
+                Self::PartialEncodedChunkForward(forward.clone())
+            }
+            RoutedMessageBody::PartialEncodedChunkResponse(response) => {
+                Self::PartialEncodedChunkResponse(response.clone())
+            }
+            RoutedMessageBody::PartialEncodedStateWitness(witness) => {
+                Self::PartialEncodedStateWitness(witness.clone())
+            }
+            RoutedMessageBody::PartialEncodedStateWitnessForward(witness) => {
+                Self::PartialEncodedStateWitnessForward(witness.clone())
+            }
+            RoutedMessageBody::ChunkEndorsement(endorsement) => {
+                Self::ChunkEndorsement(endorsement.clone())
+            }
+            _ => return None,
+        })
+    }
+
+    /// Height the message belongs to, if the message itself tells.
+    fn height(&self) -> Option<BlockHeight> {
+        match self {
+            Self::Block(block) => Some(block.header().height()),
+            Self::BlockApproval(approval) => Some(approval.target_height),
+            Self::PartialEncodedChunk(chunk) => Some(chunk.height_created()),
+            Self::PartialEncodedChunkForward(forward) => Some(forward.height_created),
+            Self::PartialEncodedStateWitness(witness)
+            | Self::PartialEncodedStateWitnessForward(witness) => Some(witness.height_created()),
+            Self::PartialEncodedChunkResponse(_) | Self::ChunkEndorsement(_) => None,
+        }
+    }
+
+    /// Converts the message into the one it was received as, routed messages
+    /// being targeted at `target` and signed with `key`.
+    pub(crate) fn into_peer_message(
+        self,
+        key: &near_crypto::SecretKey,
+        target: PeerId,
+        ttl: u8,
+    ) -> PeerMessage {
+        let body = match self {
+            Self::Block(block) => return PeerMessage::Block(block),
+            Self::BlockApproval(approval) => RoutedMessageBody::BlockApproval(approval),
+            Self::PartialEncodedChunk(chunk) => {
+                RoutedMessageBody::VersionedPartialEncodedChunk(chunk)
+            }
+            Self::PartialEncodedChunkForward(forward) => {
+                RoutedMessageBody::PartialEncodedChunkForward(forward)
+            }
+            Self::PartialEncodedChunkResponse(response) => {
+                RoutedMessageBody::PartialEncodedChunkResponse(response)
+            }
+            Self::PartialEncodedStateWitness(witness) => {
+                RoutedMessageBody::PartialEncodedStateWitness(witness)
+            }
+            Self::PartialEncodedStateWitnessForward(witness) => {
+                RoutedMessageBody::PartialEncodedStateWitnessForward(witness)
+            }
+            Self::ChunkEndorsement(endorsement) => RoutedMessageBody::ChunkEndorsement(endorsement),
+        };
+        let msg = crate::network_protocol::RawRoutedMessage {
+            target: crate::network_protocol::PeerIdOrHash::PeerId(target),
+            body,
+        };
+        PeerMessage::Routed(Box::new(msg.sign(key, ttl, Some(time::Utc::now_utc()))))
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub struct RecordedMessage {
+    /// When the message was received, as a unix timestamp in nanoseconds.
+    pub received_at: u64,
+    /// Peer the message was received from.
+    pub peer_id: PeerId,
+    pub body: RecordedMessageBody,
+}
+
+/// Reads all the messages of a recording.
+pub fn read_recording(path: &Path) -> io::Result<Vec<RecordedMessage>> {
+    let mut reader = io::BufReader::new(std::fs::File::open(path)?);
+    let mut messages = vec![];
+    while !reader.fill_buf()?.is_empty() {
+        messages.push(RecordedMessage::deserialize_reader(&mut reader)?);
+    }
+    Ok(messages)
+}
+
+struct RecorderState {
+    file: std::fs::File,
+    /// Highest height of the received messages, the height of the messages
+    /// which don't tell it themselves.
+    height: Option<BlockHeight>,
+}
+
+pub(crate) struct MessageRecorder {
+    config: RecordMessagesConfig,
+    state: Mutex<RecorderState>,
+}
+
+impl MessageRecorder {
+    pub fn open(config: RecordMessagesConfig) -> io::Result<Self> {
+        let file = std::fs::OpenOptions::new().create(true).append(true).open(&config.path)?;
+        tracing::info!(target: "network", path = ?config.path, start_height = config.start_height, end_height = config.end_height, "Recording received messages");
+        Ok(Self { config, state: Mutex::new(RecorderState { file, height: None }) })
+    }
+
+    pub fn record(&self, clock: &time::Clock, peer_id: &PeerId, msg: &PeerMessage) {
+        let Some(body) = RecordedMessageBody::from_peer_message(msg) else {
+            return;
+        };
+        let mut state = self.state.lock();
+        let height = match body.height() {
+            Some(height) => {
+                state.height = state.height.max(Some(height));
+                height
+            }
+            None => match state.height {
+                Some(height) => height,
+                None => return,
+            },
+        };
+        if !(self.config.start_height..=self.config.end_height).contains(&height) {
+            return;
+        }
+        let message = RecordedMessage {
+            received_at: clock.now_utc().unix_timestamp_nanos() as u64,
+            peer_id: peer_id.clone(),
+            body,
+        };
+        // Written at once, so that a crash doesn't leave a partial message behind.
+        if let Err(err) = state.file.write_all(&borsh::to_vec(&message).unwrap()) {
+            tracing::warn!(target: "network", ?err, "Failed to record a received message");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network_protocol::{PeerIdOrHash, RawRoutedMessage};
+    use near_crypto::{KeyType, SecretKey};
+    use near_primitives::hash::CryptoHash;
+    use near_primitives::sharding::ChunkHash;
+    use near_primitives::test_utils::create_test_signer;
+
+    #[test]
+    fn test_record_messages() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("messages");
+        let config = RecordMessagesConfig { path: path.clone(), start_height: 5, end_height: 10 };
+        let recorder = MessageRecorder::open(config).unwrap();
+        let clock = time::FakeClock::default();
+        let key = SecretKey::from_seed(KeyType::ED25519, "peer");
+        let peer_id = PeerId::new(key.public_key());
+        let signer = create_test_signer("test");
+        let routed = |body: RoutedMessageBody| {
+            let msg = RawRoutedMessage { target: PeerIdOrHash::PeerId(peer_id.clone()), body };
+            PeerMessage::Routed(Box::new(msg.sign(&key, 1, None)))
+        };
+        let approval = |height| Approval::new(CryptoHash::default(), height - 1, height, &signer);
+        let endorsement = || ChunkEndorsement::new(ChunkHash::default(), &signer);
+
+        // Before any height is known.
+        recorder.record(
+            &clock.clock(),
+            &peer_id,
+            &routed(RoutedMessageBody::ChunkEndorsement(endorsement())),
+        );
+        for height in [2, 5, 10, 11] {
+            recorder.record(
+                &clock.clock(),
+                &peer_id,
+                &routed(RoutedMessageBody::BlockApproval(approval(height))),
+            );
+            recorder.record(
+                &clock.clock(),
+                &peer_id,
+                &routed(RoutedMessageBody::ChunkEndorsement(endorsement())),
+            );
+        }
+        // Not recorded at all.
+        recorder.record(
+            &clock.clock(),
+            &peer_id,
+            &PeerMessage::BlockRequest(CryptoHash::default()),
+        );
+
+        let recording = read_recording(&path).unwrap();
+        let recorded: Vec<_> = recording
+            .iter()
+            .map(|msg| match &msg.body {
+                RecordedMessageBody::BlockApproval(approval) => Some(approval.target_height),
+                RecordedMessageBody::ChunkEndorsement(_) => None,
+                body => panic!("unexpected message {body:?}"),
+            })
+            .collect();
+        assert_eq!(recorded, vec![Some(5), None, Some(10), None]);
+        assert!(recording.iter().all(|msg| msg.peer_id == peer_id));
+    }
+}
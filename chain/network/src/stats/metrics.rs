@@ -184,6 +184,48 @@ pub(crate) static PEER_MESSAGE_SENT_BY_TYPE_BYTES: Lazy<IntCounterVec> = Lazy::n
     )
     .unwrap()
 });
+pub(crate) static PEER_MESSAGE_COMPRESSION_RATIO: Lazy<HistogramVec> = Lazy::new(|| {
+    try_create_histogram_vec(
+        "near_peer_message_compression_ratio",
+        "Ratio of the compressed size to the uncompressed size of the messages sent, by message types and codecs",
+        &["type", "codec"],
+        Some(prometheus::linear_buckets(0.05, 0.05, 20).unwrap()),
+    )
+    .unwrap()
+});
+pub(crate) static PEER_MESSAGE_COMPRESSION_TIME: Lazy<HistogramVec> = Lazy::new(|| {
+    try_create_histogram_vec(
+        "near_peer_message_compression_time",
+        "Time spent on compressing the messages sent and decompressing the messages received, by message types and codecs",
+        &["type", "codec", "operation"],
+        Some(exponential_buckets(0.00001, 2., 20).unwrap()),
+    )
+    .unwrap()
+});
+pub(crate) static CLIENT_BACKPRESSURE_IN_FLIGHT: Lazy<IntGaugeVec> = Lazy::new(|| {
+    try_create_int_gauge_vec(
+        "near_client_backpressure_in_flight",
+        "Number of the messages sent to the client and not processed yet, by message classes",
+        &["class"],
+    )
+    .unwrap()
+});
+pub(crate) static CLIENT_BACKPRESSURE_DEFERRED: Lazy<IntCounterVec> = Lazy::new(|| {
+    try_create_int_counter_vec(
+        "near_client_backpressure_deferred_total",
+        "Number of the messages to the client deferred, stopping the peer which sent them, because too many messages of the class were in flight",
+        &["class"],
+    )
+    .unwrap()
+});
+pub(crate) static CLIENT_BACKPRESSURE_DROPPED: Lazy<IntCounterVec> = Lazy::new(|| {
+    try_create_int_counter_vec(
+        "near_client_backpressure_dropped_total",
+        "Number of the messages to the client dropped because too many messages of the class were in flight",
+        &["class"],
+    )
+    .unwrap()
+});
 pub(crate) static PEER_MESSAGE_SENT_BY_TYPE_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
     try_create_int_counter_vec(
         "near_peer_message_sent_by_type_total",
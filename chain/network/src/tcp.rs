@@ -104,6 +104,24 @@ impl Stream {
         Ok(Stream::new(stream, StreamType::Outbound { peer_id: peer_info.id.clone(), tier })?)
     }
 
+    /// Connects to the peer on the first of the addresses which accepts the
+    /// connection, trying them in order.
+    pub async fn connect_any(
+        peer_id: &PeerId,
+        addrs: &[std::net::SocketAddr],
+        tier: Tier,
+    ) -> anyhow::Result<Stream> {
+        let mut last_err = anyhow!("Trying to connect to peer with no public address");
+        for addr in addrs {
+            let peer_info = PeerInfo { id: peer_id.clone(), addr: Some(*addr), account_id: None };
+            match Self::connect(&peer_info, tier).await {
+                Ok(stream) => return Ok(stream),
+                Err(err) => last_err = err.context(format!("connecting to {addr}")),
+            }
+        }
+        Err(last_err)
+    }
+
     /// Establishes a loopback TCP connection to localhost with random ports.
     /// Returns a pair of streams: (outbound,inbound).
     #[cfg(test)]
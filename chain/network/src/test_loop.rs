@@ -1,4 +1,9 @@
+use near_async::time::Duration;
 use near_primitives::types::AccountId;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 /// A multi-instance test using the TestLoop framework can support routing
 /// lookup for network messages, as long as the Data type contains AccountId.
@@ -19,3 +24,212 @@ impl<InnerData: AsRef<AccountId>> SupportsRoutingLookup for Vec<InnerData> {
         self.len()
     }
 }
+
+/// Distribution of the latency of messages over a link.
+#[derive(Clone, Debug)]
+pub enum LatencyDistribution {
+    Fixed(Duration),
+    /// Normal distribution, truncated at zero.
+    Normal {
+        mean: Duration,
+        std_dev: Duration,
+    },
+    /// Pareto distribution, i.e. a latency of at least `scale` with a heavy
+    /// tail, which gets heavier the smaller `shape` is.
+    Pareto {
+        scale: Duration,
+        shape: f64,
+    },
+}
+
+impl LatencyDistribution {
+    fn sample(&self, rng: &mut StdRng) -> Duration {
+        match self {
+            LatencyDistribution::Fixed(latency) => *latency,
+            LatencyDistribution::Normal { mean, std_dev } => {
+                // Box-Muller transform, `1 - gen()` is in (0, 1].
+                let u1: f64 = 1.0 - rng.gen::<f64>();
+                let u2: f64 = rng.gen();
+                let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+                let latency = mean.as_seconds_f64() + z * std_dev.as_seconds_f64();
+                Duration::seconds_f64(latency.max(0.0))
+            }
+            LatencyDistribution::Pareto { scale, shape } => {
+                let u: f64 = 1.0 - rng.gen::<f64>();
+                Duration::seconds_f64(scale.as_seconds_f64() / u.powf(1.0 / shape))
+            }
+        }
+    }
+}
+
+/// How messages are delivered over a link between two instances.
+#[derive(Clone, Debug)]
+pub struct LinkConditions {
+    pub latency: LatencyDistribution,
+    /// Probability of a message being lost, between 0 and 1.
+    pub packet_loss: f64,
+}
+
+impl LinkConditions {
+    pub fn fixed(latency: Duration) -> Self {
+        Self { latency: LatencyDistribution::Fixed(latency), packet_loss: 0.0 }
+    }
+}
+
+struct NetworkConditionsInner {
+    default: LinkConditions,
+    /// Conditions of the links from one instance to another, by their indexes.
+    links: HashMap<(usize, usize), LinkConditions>,
+    /// Group of each instance in a network partition, by the instance index.
+    /// Instances missing from the partition are cut off from all others.
+    partition: Option<HashMap<usize, usize>>,
+    rng: StdRng,
+}
+
+/// Latency and packet loss of the links between the instances of a
+/// multi-instance test, used when routing network messages.
+///
+/// The latencies and losses are random but derived from the seed, so a test
+/// with the same seed and the same events reproduces the same timing.
+#[derive(Clone)]
+pub struct NetworkConditions(Arc<Mutex<NetworkConditionsInner>>);
+
+impl NetworkConditions {
+    pub fn new(seed: u64, default: LinkConditions) -> Self {
+        Self(Arc::new(Mutex::new(NetworkConditionsInner {
+            default,
+            links: HashMap::new(),
+            partition: None,
+            rng: StdRng::seed_from_u64(seed),
+        })))
+    }
+
+    /// Changes the conditions of messages sent by instance `from` to instance
+    /// `to`, which affects the messages sent from now on.
+    pub fn set_link(&self, from: usize, to: usize, conditions: LinkConditions) {
+        self.0.lock().unwrap().links.insert((from, to), conditions);
+    }
+
+    /// Changes the conditions of the links without specific conditions.
+    pub fn set_default(&self, conditions: LinkConditions) {
+        self.0.lock().unwrap().default = conditions;
+    }
+
+    /// Partitions the network into the given groups of instances, so that all
+    /// messages between instances of different groups are lost until
+    /// `heal_partition` is called. The conditions of the links are kept.
+    pub fn partition(&self, groups: &[Vec<usize>]) {
+        let partition = groups
+            .iter()
+            .enumerate()
+            .flat_map(|(group, instances)| instances.iter().map(move |idx| (*idx, group)))
+            .collect();
+        self.0.lock().unwrap().partition = Some(partition);
+    }
+
+    pub fn heal_partition(&self) {
+        self.0.lock().unwrap().partition = None;
+    }
+
+    /// Returns the latency of a message sent by instance `from` to instance
+    /// `to`, or None if the message is lost.
+    pub fn sample(&self, from: usize, to: usize) -> Option<Duration> {
+        let mut inner = self.0.lock().unwrap();
+        let NetworkConditionsInner { default, links, partition, rng } = &mut *inner;
+        if let Some(partition) = partition {
+            match (partition.get(&from), partition.get(&to)) {
+                (Some(from_group), Some(to_group)) if from_group == to_group => {}
+                _ => return None,
+            }
+        }
+        let conditions = links.get(&(from, to)).unwrap_or(default);
+        if conditions.packet_loss > 0.0 && rng.gen::<f64>() < conditions.packet_loss {
+            return None;
+        }
+        Some(conditions.latency.sample(rng))
+    }
+}
+
+/// Delivers all messages with the same latency.
+impl From<Duration> for NetworkConditions {
+    fn from(latency: Duration) -> Self {
+        Self::new(0, LinkConditions::fixed(latency))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(conditions: &NetworkConditions, from: usize, to: usize) -> Vec<Option<Duration>> {
+        (0..1000).map(|_| conditions.sample(from, to)).collect()
+    }
+
+    #[test]
+    fn test_network_conditions_deterministic() {
+        let link = LinkConditions {
+            latency: LatencyDistribution::Normal {
+                mean: Duration::milliseconds(100),
+                std_dev: Duration::milliseconds(20),
+            },
+            packet_loss: 0.1,
+        };
+        let first = samples(&NetworkConditions::new(42, link.clone()), 0, 1);
+        let second = samples(&NetworkConditions::new(42, link.clone()), 0, 1);
+        assert_eq!(first, second);
+        let other_seed = samples(&NetworkConditions::new(43, link), 0, 1);
+        assert_ne!(first, other_seed);
+
+        let lost = first.iter().filter(|latency| latency.is_none()).count();
+        assert!((50..150).contains(&lost), "lost {lost} messages");
+        let latencies: Vec<_> = first.into_iter().flatten().collect();
+        assert!(latencies.iter().all(|latency| *latency >= Duration::ZERO));
+        let mean = latencies.iter().map(|latency| latency.as_seconds_f64()).sum::<f64>()
+            / latencies.len() as f64;
+        assert!((0.095..0.105).contains(&mean), "mean latency {mean}");
+    }
+
+    #[test]
+    fn test_network_conditions_per_link() {
+        let conditions = NetworkConditions::from(Duration::milliseconds(10));
+        conditions.set_link(
+            0,
+            1,
+            LinkConditions {
+                latency: LatencyDistribution::Pareto {
+                    scale: Duration::milliseconds(50),
+                    shape: 2.0,
+                },
+                packet_loss: 0.0,
+            },
+        );
+        conditions.set_link(
+            1,
+            0,
+            LinkConditions { packet_loss: 1.0, ..LinkConditions::fixed(Duration::ZERO) },
+        );
+
+        assert!(samples(&conditions, 0, 2)
+            .into_iter()
+            .all(|latency| latency == Some(Duration::milliseconds(10))));
+        let pareto = samples(&conditions, 0, 1);
+        assert!(pareto.iter().all(|latency| latency.unwrap() >= Duration::milliseconds(50)));
+        assert!(pareto.iter().any(|latency| latency.unwrap() > Duration::milliseconds(200)));
+        assert!(samples(&conditions, 1, 0).iter().all(|latency| latency.is_none()));
+    }
+
+    #[test]
+    fn test_network_partition() {
+        let conditions = NetworkConditions::from(Duration::milliseconds(10));
+        conditions.partition(&[vec![0, 1], vec![2]]);
+        assert_eq!(conditions.sample(0, 1), Some(Duration::milliseconds(10)));
+        assert_eq!(conditions.sample(1, 2), None);
+        assert_eq!(conditions.sample(2, 0), None);
+        // Instances not in any group are isolated.
+        assert_eq!(conditions.sample(3, 0), None);
+
+        conditions.heal_partition();
+        assert_eq!(conditions.sample(1, 2), Some(Duration::milliseconds(10)));
+        assert_eq!(conditions.sample(3, 0), Some(Duration::milliseconds(10)));
+    }
+}
@@ -71,6 +71,12 @@ impl TransactionPool {
         }
     }
 
+    /// Changes the limit on the total size of transactions, which only applies
+    /// to transactions inserted from now on.
+    pub fn set_total_transaction_size_limit(&mut self, total_transaction_size_limit: Option<u64>) {
+        self.total_transaction_size_limit = total_transaction_size_limit;
+    }
+
     fn key(&self, account_id: &AccountId, public_key: &PublicKey) -> PoolKey {
         let mut v = borsh::to_vec(&public_key).unwrap();
         v.extend_from_slice(&self.key_seed);
@@ -122,6 +128,31 @@ impl TransactionPool {
         PoolIteratorWrapper::new(self)
     }
 
+    /// Like `pool_iterator`, but the groups are first pulled from the pool in
+    /// the descending order of the priority fee of their next transaction,
+    /// i.e. the one with the smallest nonce.
+    pub fn priority_fee_pool_iterator(&mut self) -> PoolIteratorWrapper<'_> {
+        let mut keys: Vec<_> = self
+            .transactions
+            .iter()
+            .map(|(key, transactions)| {
+                let priority_fee = transactions
+                    .iter()
+                    .min_by_key(|tx| tx.transaction.nonce())
+                    .and_then(|tx| tx.transaction.priority_fee())
+                    .unwrap_or_default();
+                (*key, priority_fee)
+            })
+            .collect();
+        // The sort is stable, so the groups with the same fee keep the order of their keys.
+        keys.sort_by_key(|(_, priority_fee)| std::cmp::Reverse(*priority_fee));
+        PoolIteratorWrapper {
+            pool: self,
+            sorted_groups: Default::default(),
+            priority_keys: Some(keys.into_iter().map(|(key, _)| key).collect()),
+        }
+    }
+
     /// Removes given transactions from the pool.
     ///
     /// In practice, used to evict transactions that have already been included into the block or
@@ -175,6 +206,11 @@ impl TransactionPool {
     pub fn transaction_size(&self) -> u64 {
         self.total_transaction_size
     }
+
+    /// Returns an iterator over all transactions in the pool, in no particular order.
+    pub fn transactions(&self) -> impl Iterator<Item = &SignedTransaction> {
+        self.transactions.values().flatten()
+    }
 }
 
 /// PoolIterator is a structure to pull transactions from the pool.
@@ -186,11 +222,36 @@ pub struct PoolIteratorWrapper<'a> {
 
     /// Queue of transaction groups. Each group there is sorted by nonce.
     sorted_groups: VecDeque<TransactionGroup>,
+
+    /// If set, the keys of the groups in the order they are pulled from the
+    /// pool, instead of the round robin order after the last used key.
+    priority_keys: Option<VecDeque<PoolKey>>,
 }
 
 impl<'a> PoolIteratorWrapper<'a> {
     pub fn new(pool: &'a mut TransactionPool) -> Self {
-        Self { pool, sorted_groups: Default::default() }
+        Self { pool, sorted_groups: Default::default(), priority_keys: None }
+    }
+
+    fn next_key(&mut self) -> PoolKey {
+        if let Some(key) = self.priority_keys.as_mut().and_then(|keys| keys.pop_front()) {
+            return key;
+        }
+        let key = *self
+            .pool
+            .transactions
+            .range((Bound::Excluded(self.pool.last_used_key), Bound::Unbounded))
+            .next()
+            .map(|(k, _v)| k)
+            .unwrap_or_else(|| {
+                self.pool
+                    .transactions
+                    .keys()
+                    .next()
+                    .expect("we've just checked that the map is not empty")
+            });
+        self.pool.last_used_key = key;
+        key
     }
 }
 
@@ -214,20 +275,7 @@ impl<'a> PoolIteratorWrapper<'a> {
 impl<'a> TransactionGroupIterator for PoolIteratorWrapper<'a> {
     fn next(&mut self) -> Option<&mut TransactionGroup> {
         if !self.pool.transactions.is_empty() {
-            let key = *self
-                .pool
-                .transactions
-                .range((Bound::Excluded(self.pool.last_used_key), Bound::Unbounded))
-                .next()
-                .map(|(k, _v)| k)
-                .unwrap_or_else(|| {
-                    self.pool
-                        .transactions
-                        .keys()
-                        .next()
-                        .expect("we've just checked that the map is not empty")
-                });
-            self.pool.last_used_key = key;
+            let key = self.next_key();
             let mut transactions =
                 self.pool.transactions.remove(&key).expect("just checked existence");
             transactions.sort_by_key(|st| std::cmp::Reverse(st.transaction.nonce()));
@@ -630,4 +678,69 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_transactions() {
+        let mut transactions = generate_transactions("alice.near", "alice.near", 1, 10);
+        transactions.extend(generate_transactions("bob.near", "bob.near", 1, 10));
+        let mut pool = TransactionPool::new(TEST_SEED, None, "");
+        for tx in transactions.iter().cloned() {
+            assert_eq!(pool.insert_transaction(tx), InsertTransactionResult::Success);
+        }
+        let mut hashes = pool.transactions().map(|tx| tx.get_hash()).collect::<Vec<_>>();
+        let mut expected = transactions.iter().map(|tx| tx.get_hash()).collect::<Vec<_>>();
+        hashes.sort();
+        expected.sort();
+        assert_eq!(hashes, expected);
+
+        pool.remove_transactions(&transactions[..5]);
+        assert_eq!(pool.transactions().count(), 15);
+    }
+
+    /// The groups are pulled in the order of the priority fee of their next
+    /// transaction, then round robin.
+    #[test]
+    fn test_priority_fee_pool_iterator() {
+        let transactions = [("alice.near", 1, 5), ("bob.near", 1, 20), ("carol.near", 2, 10)]
+            .into_iter()
+            .flat_map(|(signer_id, starting_nonce, next_priority_fee)| {
+                let signer_id: AccountId = signer_id.parse().unwrap();
+                let signer = InMemorySigner::from_seed(signer_id.clone(), KeyType::ED25519, "seed");
+                // The transaction after the next one has the highest fee of all, which
+                // doesn't matter until its group is pulled.
+                [(starting_nonce, next_priority_fee), (starting_nonce + 1, 100)].map(
+                    move |(nonce, priority_fee)| {
+                        SignedTransaction::from_actions_v1(
+                            nonce,
+                            signer_id.clone(),
+                            "bob.near".parse().unwrap(),
+                            &signer,
+                            vec![],
+                            CryptoHash::default(),
+                            priority_fee,
+                        )
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+        let mut pool = TransactionPool::new(TEST_SEED, None, "");
+        for tx in transactions {
+            assert_eq!(pool.insert_transaction(tx), InsertTransactionResult::Success);
+        }
+        let mut res = vec![];
+        let mut pool_iter = pool.priority_fee_pool_iterator();
+        while let Some(group) = pool_iter.next() {
+            if let Some(tx) = group.next() {
+                res.push((tx.transaction.signer_id().to_string(), tx.transaction.nonce()));
+            }
+        }
+        drop(pool_iter);
+        assert_eq!(pool.len(), 0);
+        let expected = [("bob.near", 1), ("carol.near", 2), ("alice.near", 1)]
+            .into_iter()
+            .chain([("bob.near", 2), ("carol.near", 3), ("alice.near", 2)])
+            .map(|(signer_id, nonce)| (signer_id.to_string(), nonce))
+            .collect::<Vec<_>>();
+        assert_eq!(res, expected);
+    }
 }
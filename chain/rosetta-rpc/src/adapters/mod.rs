@@ -1000,6 +1000,99 @@ mod tests {
         });
     }
 
+    /// The deposit of transfers inside a delegate action is paid by the relayer
+    /// signing the transaction, so it has to be separated from the gas
+    /// prepayment the same way as for plain transfers.
+    #[test]
+    fn test_convert_delegate_action_transfer_to_transactions() {
+        run_actix(async {
+            let runtime_config: RuntimeConfigView = RuntimeConfig::test().into();
+            let actor_handles = setup_no_network(
+                Clock::real(),
+                vec!["test".parse().unwrap()],
+                "other".parse().unwrap(),
+                true,
+                false,
+            );
+            let sk = SecretKey::from_seed(KeyType::ED25519, "");
+            let block_hash = near_primitives::hash::CryptoHash::default();
+            let tx_hash = near_primitives::hash::CryptoHash([1u8; 32]);
+            let relayer_account = |amount| near_primitives::views::AccountView {
+                amount,
+                code_hash: near_primitives::hash::CryptoHash::default(),
+                locked: 0,
+                #[cfg(feature = "protocol_feature_nonrefundable_transfer_nep491")]
+                permanent_storage_bytes: 0,
+                storage_paid_at: 0,
+                storage_usage: 0,
+            };
+            let transaction = near_primitives::views::SignedTransactionView {
+                signer_id: "relayer.near".parse().unwrap(),
+                public_key: sk.public_key(),
+                nonce: 0,
+                receiver_id: "account.near".parse().unwrap(),
+                actions: vec![near_primitives::views::ActionView::Delegate {
+                    delegate_action: DelegateAction {
+                        sender_id: "account.near".parse().unwrap(),
+                        receiver_id: "receiver.near".parse().unwrap(),
+                        actions: vec![Action::Transfer(TransferAction { deposit: 1000 })
+                            .try_into()
+                            .unwrap()],
+                        nonce: 0,
+                        max_block_height: 0,
+                        public_key: sk.public_key(),
+                    },
+                    signature: sk.sign(&[0]),
+                }],
+                priority_fee: 0,
+                signature: sk.sign(&[0]),
+                hash: tx_hash,
+            };
+            let accounts_changes = vec![near_primitives::views::StateChangeWithCauseView {
+                cause: near_primitives::views::StateChangeCauseView::TransactionProcessing {
+                    tx_hash,
+                },
+                value: near_primitives::views::StateChangeValueView::AccountUpdate {
+                    account_id: "relayer.near".parse().unwrap(),
+                    account: relayer_account(10_000 - 1000 - 300),
+                },
+            }];
+            let accounts_previous_state = std::collections::HashMap::from([(
+                "relayer.near".parse().unwrap(),
+                relayer_account(10_000),
+            )]);
+            let transactions = super::transactions::convert_block_changes_to_transactions(
+                &actor_handles.view_client_actor,
+                &runtime_config,
+                &block_hash,
+                accounts_changes,
+                accounts_previous_state,
+                super::transactions::ExecutionToReceipts::with_transactions(vec![transaction]),
+            )
+            .await
+            .unwrap();
+
+            let operations = &transactions[&format!("tx:{}", tx_hash)].operations;
+            let amounts: Vec<_> = operations
+                .iter()
+                .map(|operation| operation.amount.as_ref().unwrap().value)
+                .collect();
+            assert_eq!(
+                amounts,
+                vec![
+                    -crate::utils::SignedDiff::from(1000u128),
+                    -crate::utils::SignedDiff::from(300u128)
+                ]
+            );
+            assert_eq!(operations[0].metadata.as_ref().unwrap().transfer_fee_type, None);
+            assert_eq!(
+                operations[1].metadata.as_ref().unwrap().transfer_fee_type,
+                Some(crate::models::OperationMetadataTransferFeeType::GasPrepayment)
+            );
+            System::current().stop();
+        });
+    }
+
     #[test]
     fn test_near_actions_bijection() {
         let create_account_actions =
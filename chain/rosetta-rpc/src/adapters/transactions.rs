@@ -96,6 +96,16 @@ impl ExecutionToReceipts {
         }
     }
 
+    /// Creates a mapping knowing only the given transactions.  This is useful
+    /// for tests.
+    #[cfg(test)]
+    pub(crate) fn with_transactions(transactions: Vec<SignedTransactionView>) -> Self {
+        Self {
+            transactions: transactions.into_iter().map(|t| (t.hash, t)).collect(),
+            ..Self::empty()
+        }
+    }
+
     /// Returns list of related transactions for given NEAR transaction or
     /// receipt.
     fn get_related(&self, exec_hash: CryptoHash) -> Vec<crate::models::RelatedTransaction> {
@@ -305,16 +315,7 @@ pub(crate) async fn convert_block_changes_to_transactions(
                     near_primitives::views::StateChangeCauseView::TransactionProcessing {
                         tx_hash,
                     } => transactions_in_block.get(tx_hash).and_then(|t| {
-                        let total_sum = t
-                            .actions
-                            .iter()
-                            .map(|action| match action {
-                                near_primitives::views::ActionView::Transfer { deposit } => {
-                                    *deposit
-                                }
-                                _ => 0,
-                            })
-                            .sum::<u128>();
+                        let total_sum = transfer_deposit(&t.actions);
                         if total_sum == 0 {
                             None
                         } else {
@@ -370,6 +371,32 @@ pub(crate) async fn convert_block_changes_to_transactions(
     Ok(transactions.map)
 }
 
+/// Returns the total amount transferred by the given transaction actions.
+///
+/// Includes transfers inside delegate actions, since the signer of the
+/// transaction (the relayer) pays their deposit, and nonrefundable storage
+/// transfers, which are paid from the liquid balance of the signer as well.
+pub(crate) fn transfer_deposit(
+    actions: &[near_primitives::views::ActionView],
+) -> near_primitives::types::Balance {
+    actions
+        .iter()
+        .map(|action| match action {
+            near_primitives::views::ActionView::Transfer { deposit } => *deposit,
+            #[cfg(feature = "protocol_feature_nonrefundable_transfer_nep491")]
+            near_primitives::views::ActionView::NonrefundableStorageTransfer { deposit } => {
+                *deposit
+            }
+            near_primitives::views::ActionView::Delegate { delegate_action, .. } => {
+                let actions: Vec<near_primitives::views::ActionView> =
+                    delegate_action.get_actions().into_iter().map(Into::into).collect();
+                transfer_deposit(&actions)
+            }
+            _ => 0,
+        })
+        .sum()
+}
+
 fn convert_account_update_to_operations(
     runtime_config: &near_parameters::RuntimeConfigView,
     operations: &mut Vec<crate::models::Operation>,
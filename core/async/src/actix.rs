@@ -49,6 +49,10 @@ where
     }
 }
 
+fn mailbox_depth<A>() -> near_o11y::metrics::IntGauge {
+    crate::metrics::ACTOR_MAILBOX_DEPTH.with_label_values(&[crate::metrics::short_type_name::<A>()])
+}
+
 /// Allows an actix Addr<WithSpanContext<T>> to act as if it were an Addr<T>,
 /// by automatically wrapping any message sent with .with_span_context().
 ///
@@ -92,7 +96,7 @@ where
     actix::Addr<S>: CanSend<WithSpanContext<M>>,
 {
     fn send(&self, message: M) {
-        CanSend::send(&self.inner, message.with_span_context());
+        CanSend::send(&self.inner, message.with_span_context().in_mailbox(mailbox_depth::<S>()));
     }
 }
 
@@ -107,7 +111,10 @@ where
         let MessageWithCallback { message, callback: responder } = message;
         CanSend::send(
             &self.inner,
-            MessageWithCallback { message: message.with_span_context(), callback: responder },
+            MessageWithCallback {
+                message: message.with_span_context().in_mailbox(mailbox_depth::<S>()),
+                callback: responder,
+            },
         );
     }
 }
@@ -4,7 +4,7 @@ use actix::Actor;
 use near_o11y::{handler_debug_span, WithSpanContext};
 
 use crate::futures::DelayedActionRunner;
-use crate::messaging;
+use crate::{messaging, metrics};
 
 /// Wrapper on top of a generic actor to make it implement actix::Actor trait. The wrapped actor
 /// should implement the Handler trait for all the messages it would like to handle.
@@ -55,6 +55,7 @@ where
 {
     type Result = M::Result;
     fn handle(&mut self, msg: WithSpanContext<M>, ctx: &mut Self::Context) -> Self::Result {
+        let _timer = processing_timer::<T, M>();
         let (_span, msg) = handler_debug_span!(target: "actix_message_handler", msg);
         self.actor.handle(msg, ctx)
     }
@@ -87,11 +88,18 @@ where
 {
     type Result = M::Result;
     fn handle(&mut self, msg: WithSpanContext<M>, _ctx: &mut Self::Context) -> Self::Result {
+        let _timer = processing_timer::<T, M>();
         let (_span, msg) = handler_debug_span!(target: "actix_message_handler", msg);
         self.actor.handle(msg)
     }
 }
 
+fn processing_timer<T, M>() -> near_o11y::metrics::prometheus::HistogramTimer {
+    metrics::ACTOR_MESSAGE_PROCESSING_TIME
+        .with_label_values(&[metrics::short_type_name::<T>(), metrics::short_type_name::<M>()])
+        .start_timer()
+}
+
 /// Spawns an actix actor with the given actor. Returns the address of the actor and the arbiter
 /// Note that the actor should implement the Handler trait for all the messages it would like to handle.
 pub fn spawn_actix_actor<T>(actor: T) -> (actix::Addr<ActixWrapper<T>>, actix::ArbiterHandle)
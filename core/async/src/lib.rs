@@ -8,5 +8,6 @@ mod examples;
 mod functional;
 pub mod futures;
 pub mod messaging;
+pub mod metrics;
 pub mod test_loop;
 pub mod time;
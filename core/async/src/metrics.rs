@@ -0,0 +1,33 @@
+use near_o11y::metrics::{
+    exponential_buckets, try_create_histogram_vec, try_create_int_gauge_vec, HistogramVec,
+    IntGaugeVec,
+};
+use once_cell::sync::Lazy;
+
+pub static ACTOR_MAILBOX_DEPTH: Lazy<IntGaugeVec> = Lazy::new(|| {
+    try_create_int_gauge_vec(
+        "near_actor_mailbox_depth",
+        "Number of messages sent to the actor through the messaging adapters which it hasn't \
+         handled yet",
+        &["actor"],
+    )
+    .unwrap()
+});
+
+pub static ACTOR_MESSAGE_PROCESSING_TIME: Lazy<HistogramVec> = Lazy::new(|| {
+    try_create_histogram_vec(
+        "near_actor_message_processing_time",
+        "Time taken by the actor to handle a message, in seconds",
+        &["actor", "message"],
+        Some(exponential_buckets(0.0001, 2.0, 16).unwrap()),
+    )
+    .unwrap()
+});
+
+/// Returns the short name of the type used as the `actor` and `message`
+/// labels, e.g. `ClientActorInner` for `ActixWrapper<ClientActorInner>`.
+pub fn short_type_name<T>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    let name = name.rsplit_once("::").map_or(name, |(_, name)| name);
+    name.trim_end_matches('>')
+}
@@ -8,6 +8,7 @@ use near_primitives::types::{
 };
 use near_primitives::version::Version;
 use std::cmp::{max, min};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
@@ -22,12 +23,36 @@ pub enum LogSummaryStyle {
     Colored,
 }
 
+/// What the node runs for.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeRole {
+    /// Follows the chain and validates when it has a validator key.
+    #[default]
+    Regular,
+    /// Follows the chain to serve reads, e.g. behind an RPC load balancer. It
+    /// ignores the validator key, so it neither produces nor validates, keeps
+    /// no transactions in the pool and only forwards the transactions it
+    /// receives, and doesn't shadow validate the chunks.
+    ReadReplica,
+}
+
+impl NodeRole {
+    pub fn is_regular(&self) -> bool {
+        *self == NodeRole::Regular
+    }
+}
+
 /// Minimum number of epochs for which we keep store data
 pub const MIN_GC_NUM_EPOCHS_TO_KEEP: u64 = 3;
 
 /// Default number of epochs for which we keep store data
 pub const DEFAULT_GC_NUM_EPOCHS_TO_KEEP: u64 = 5;
 
+/// Minimum number of epochs for which we keep the data of a column group with
+/// its own retention.
+pub const MIN_GC_COLUMN_GROUP_NUM_EPOCHS_TO_KEEP: u64 = 1;
+
 /// Default number of concurrent requests to external storage to fetch state parts.
 pub const DEFAULT_STATE_SYNC_NUM_CONCURRENT_REQUESTS_EXTERNAL: u32 = 25;
 pub const DEFAULT_STATE_SYNC_NUM_CONCURRENT_REQUESTS_ON_CATCHUP_EXTERNAL: u32 = 5;
@@ -50,6 +75,41 @@ pub struct GCConfig {
     /// How often gc should be run
     #[serde(with = "near_async::time::serde_duration_as_std")]
     pub gc_step_period: Duration,
+
+    /// Number of epochs for which we keep the data of the column groups,
+    /// instead of `gc_num_epochs_to_keep`, e.g.
+    /// `{"transaction_results": 10, "state_changes": 2}`.  The retention may
+    /// be longer or shorter than the one of the rest of the chain data.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub gc_column_group_num_epochs_to_keep: BTreeMap<GCColumnGroup, u64>,
+}
+
+/// Group of columns which can be kept for a different number of epochs than
+/// the rest of the chain data.  Only the data of the canonical chain is kept,
+/// the data of forks is garbage collected along with the fork blocks.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum GCColumnGroup {
+    /// Execution outcomes of transactions and receipts, i.e.
+    /// `DBCol::TransactionResultForBlock` and `DBCol::OutcomeIds`.
+    TransactionResults,
+    /// `DBCol::StateChanges`.
+    StateChanges,
+    /// `DBCol::AccountIndex` and `DBCol::AccountIndexKeys`, only written with
+    /// `save_account_index`.
+    AccountIndex,
+}
+
+impl GCColumnGroup {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GCColumnGroup::TransactionResults => "transaction_results",
+            GCColumnGroup::StateChanges => "state_changes",
+            GCColumnGroup::AccountIndex => "account_index",
+        }
+    }
 }
 
 impl Default for GCConfig {
@@ -59,6 +119,7 @@ impl Default for GCConfig {
             gc_fork_clean_step: 100,
             gc_num_epochs_to_keep: DEFAULT_GC_NUM_EPOCHS_TO_KEEP,
             gc_step_period: Duration::seconds(1),
+            gc_column_group_num_epochs_to_keep: BTreeMap::new(),
         }
     }
 }
@@ -67,6 +128,14 @@ impl GCConfig {
     pub fn gc_num_epochs_to_keep(&self) -> u64 {
         max(MIN_GC_NUM_EPOCHS_TO_KEEP, self.gc_num_epochs_to_keep)
     }
+
+    /// Returns the column groups with their own retention and the number of
+    /// epochs for which their data is kept.
+    pub fn gc_column_groups(&self) -> impl Iterator<Item = (GCColumnGroup, u64)> + '_ {
+        self.gc_column_group_num_epochs_to_keep.iter().map(|(&group, &num_epochs_to_keep)| {
+            (group, max(MIN_GC_COLUMN_GROUP_NUM_EPOCHS_TO_KEEP, num_epochs_to_keep))
+        })
+    }
 }
 
 fn default_num_concurrent_requests() -> u32 {
@@ -125,6 +194,37 @@ pub struct DumpConfig {
     /// Location of a json file with credentials allowing write access to the bucket.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub credentials_file: Option<PathBuf>,
+    /// How to upload the state parts when dumping to S3.
+    #[serde(default)]
+    pub s3_upload: S3UploadConfig,
+}
+
+/// Configures the uploads of state parts and headers to S3-compatible storage.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct S3UploadConfig {
+    /// Files larger than this are uploaded in parts of this size with a
+    /// multipart upload. S3 requires the parts to be at least 5 MiB.
+    pub multipart_part_size: ByteSize,
+    /// How many times a failed request is retried before giving up on the file.
+    pub max_retries: u32,
+    /// Delay before the first retry of a request, doubled with every further retry.
+    #[serde(with = "near_async::time::serde_duration_as_std")]
+    pub initial_retry_backoff: Duration,
+    /// Compare the MD5 checksums of the uploaded data with the ones reported
+    /// by the storage, and retry the upload on a mismatch.
+    pub verify_checksums: bool,
+}
+
+impl Default for S3UploadConfig {
+    fn default() -> Self {
+        Self {
+            multipart_part_size: ByteSize::mib(16),
+            max_retries: 5,
+            initial_retry_backoff: Duration::seconds(1),
+            verify_checksums: true,
+        }
+    }
 }
 
 /// Configures how to fetch state parts during state sync.
@@ -313,6 +413,15 @@ pub fn default_produce_chunk_add_transactions_time_limit() -> Option<Duration> {
     Some(Duration::milliseconds(200))
 }
 
+/// The time limit for adding transactions to a chunk with the given minimal
+/// block production delay, a third of the delay. For the default delay, this
+/// is [`default_produce_chunk_add_transactions_time_limit`].
+pub fn block_time_produce_chunk_add_transactions_time_limit(
+    min_block_production_delay: Duration,
+) -> Duration {
+    min_block_production_delay / 3
+}
+
 /// Returns the default size of the OrphanStateWitnessPool, ie. the maximum number of
 /// state-witnesses that can be accommodated in OrphanStateWitnessPool.
 pub fn default_orphan_state_witness_pool_size() -> usize {
@@ -390,6 +499,9 @@ pub struct ClientConfig {
     pub log_summary_style: LogSummaryStyle,
     /// Produce empty blocks, use `false` for testing.
     pub produce_empty_blocks: bool,
+    /// Don't produce the chunks of shards without transactions and receipts,
+    /// on the benchmark networks with the `SkipEmptyChunks` protocol feature.
+    pub skip_empty_chunks: bool,
     /// Epoch length.
     pub epoch_length: BlockHeightDelta,
     /// Number of block producer seats
@@ -483,6 +595,11 @@ pub struct ClientConfig {
     /// which can cause extra load on the database. This option is not recommended for production use,
     /// as a large number of incoming witnesses could cause denial of service.
     pub save_latest_witnesses: bool,
+    /// Whether to keep the index of the transactions and receipts involving
+    /// every account, queried with `EXPERIMENTAL_account_activity`.
+    pub save_account_index: bool,
+    /// What the node runs for, see `NodeRole`.
+    pub node_role: NodeRole,
 }
 
 impl ClientConfig {
@@ -526,6 +643,7 @@ impl ClientConfig {
             min_num_peers: 1,
             log_summary_period: Duration::seconds(10),
             produce_empty_blocks: true,
+            skip_empty_chunks: false,
             epoch_length: 10,
             num_block_producer_seats,
             ttl_account_id_router: Duration::seconds(60 * 60),
@@ -570,6 +688,8 @@ impl ClientConfig {
             orphan_state_witness_pool_size: default_orphan_state_witness_pool_size(),
             orphan_state_witness_max_size: default_orphan_state_witness_max_size(),
             save_latest_witnesses: false,
+            save_account_index: false,
+            node_role: NodeRole::default(),
         }
     }
 }
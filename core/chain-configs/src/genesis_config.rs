@@ -7,6 +7,7 @@ use crate::genesis_validate::validate_genesis;
 use anyhow::Context;
 use chrono::{DateTime, Utc};
 use near_config_utils::ValidationError;
+use near_parameters::config::CongestionControlConfigOverride;
 use near_parameters::{RuntimeConfig, RuntimeConfigView};
 use near_primitives::epoch_manager::EpochConfig;
 use near_primitives::shard_layout::ShardLayout;
@@ -18,6 +19,7 @@ use near_primitives::{
     state_record::StateRecord,
     types::{
         AccountId, AccountInfo, Balance, BlockHeight, BlockHeightDelta, Gas, NumBlocks, NumSeats,
+        ShardId,
     },
     version::ProtocolVersion,
 };
@@ -27,7 +29,7 @@ use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Serializer;
 use sha2::digest::Digest;
 use smart_default::SmartDefault;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::fmt;
 use std::fs::File;
 use std::io::{BufReader, Read};
@@ -216,6 +218,15 @@ pub struct GenesisConfig {
     /// if algorithm is able to choose assignment with better balance of
     /// number of chunk producers for shards.
     pub chunk_producer_assignment_changes_limit: NumSeats,
+    /// Overrides of the congestion control thresholds and limits of
+    /// individual shards, keyed by shard id.
+    ///
+    /// Meant for studying the congestion control tuning of shards with
+    /// different workloads, only allowed on test chains (`test-chain-*` and
+    /// `benchmarknet*`). It is part of the genesis so that all nodes of the
+    /// chain apply the same congestion control.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub congestion_control_overrides: BTreeMap<ShardId, CongestionControlConfigOverride>,
 }
 
 impl GenesisConfig {
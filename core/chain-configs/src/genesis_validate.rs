@@ -1,4 +1,5 @@
 use crate::genesis_config::{Genesis, GenesisConfig, GenesisContents};
+use crate::is_experimental_chain_id;
 use near_config_utils::{ValidationError, ValidationErrors};
 use near_crypto::key_conversion::is_valid_staking_key;
 use near_primitives::state_record::StateRecord;
@@ -94,6 +95,16 @@ impl<'a> GenesisValidator<'a> {
             })
             .collect::<HashMap<_, _>>();
 
+        if !self.genesis_config.congestion_control_overrides.is_empty()
+            && !is_experimental_chain_id(&self.genesis_config.chain_id)
+        {
+            let error_message = format!(
+                "congestion_control_overrides are not allowed on chain {}",
+                self.genesis_config.chain_id
+            );
+            self.validation_errors.push_genesis_semantics_error(error_message)
+        }
+
         if validators.len() != self.genesis_config.validators.len() {
             let error_message = format!("Duplicate account in validators. The number of account_ids: {} does not match the number of validators: {}.", self.account_ids.len(), validators.len());
             self.validation_errors.push_genesis_semantics_error(error_message)
@@ -279,6 +290,26 @@ mod test {
         validate_genesis(genesis).unwrap();
     }
 
+    #[test]
+    #[should_panic(expected = "congestion_control_overrides are not allowed on chain")]
+    fn test_congestion_control_overrides_on_production_chain() {
+        let mut config = GenesisConfig::default();
+        config.chain_id = "mainnet".to_string();
+        config.congestion_control_overrides = [(0, Default::default())].into();
+        config.validators = vec![AccountInfo {
+            account_id: "test".parse().unwrap(),
+            public_key: VALID_ED25519_RISTRETTO_KEY.parse().unwrap(),
+            amount: 10,
+        }];
+        config.total_supply = 110;
+        let records = GenesisRecords(vec![StateRecord::Account {
+            account_id: "test".parse().unwrap(),
+            account: create_account(),
+        }]);
+        let genesis = &Genesis::new(config, records).unwrap();
+        validate_genesis(genesis).unwrap();
+    }
+
     #[test]
     #[should_panic(expected = "No validators in genesis")]
     fn test_empty_validator() {
@@ -101,4 +101,17 @@ pub struct UpdateableClientConfig {
     #[serde(default)]
     #[serde(with = "near_async::time::serde_opt_duration_as_std")]
     pub produce_chunk_add_transactions_time_limit: Option<Duration>,
+
+    /// Limit of the total size of transactions in the pool of each shard.
+    #[serde(default)]
+    pub transaction_pool_size_limit: Option<u64>,
+
+    /// Maximum number of concurrent requests fetching state parts from the
+    /// external storage, if the state is synced from the external storage.
+    #[serde(default)]
+    pub state_sync_num_concurrent_requests: Option<u32>,
+
+    /// Same as `state_sync_num_concurrent_requests`, but during catchup.
+    #[serde(default)]
+    pub state_sync_num_concurrent_requests_during_catchup: Option<u32>,
 }
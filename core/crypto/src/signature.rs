@@ -1,9 +1,11 @@
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::schema::{Declaration, Definition};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use ed25519_dalek::ed25519::signature::{Signer, Verifier};
 use once_cell::sync::Lazy;
 use primitive_types::U256;
 use secp256k1::rand::rngs::OsRng;
 use secp256k1::Message;
+use std::collections::BTreeMap;
 use std::convert::AsRef;
 use std::fmt::{Debug, Display, Formatter};
 use std::hash::{Hash, Hasher};
@@ -232,6 +234,38 @@ impl BorshDeserialize for PublicKey {
     }
 }
 
+/// Adds the definition of an enum tagged with the key type, like the public
+/// keys and the signatures, whose variants are the raw bytes of the curve.
+fn add_key_type_enum_definition<ED25519: BorshSchema, SECP256K1: BorshSchema>(
+    declaration: Declaration,
+    definitions: &mut BTreeMap<Declaration, Definition>,
+) {
+    let variants = vec![
+        (KeyType::ED25519 as i64, "ED25519".to_string(), ED25519::declaration()),
+        (KeyType::SECP256K1 as i64, "SECP256K1".to_string(), SECP256K1::declaration()),
+    ];
+    borsh::schema::add_definition(
+        declaration,
+        Definition::Enum { tag_width: 1, variants },
+        definitions,
+    );
+    ED25519::add_definitions_recursively(definitions);
+    SECP256K1::add_definitions_recursively(definitions);
+}
+
+impl BorshSchema for PublicKey {
+    fn add_definitions_recursively(definitions: &mut BTreeMap<Declaration, Definition>) {
+        add_key_type_enum_definition::<[u8; ed25519_dalek::PUBLIC_KEY_LENGTH], [u8; 64]>(
+            Self::declaration(),
+            definitions,
+        );
+    }
+
+    fn declaration() -> Declaration {
+        "PublicKey".to_string()
+    }
+}
+
 impl serde::Serialize for PublicKey {
     fn serialize<S>(
         &self,
@@ -652,6 +686,19 @@ impl Debug for Signature {
     }
 }
 
+impl BorshSchema for Signature {
+    fn add_definitions_recursively(definitions: &mut BTreeMap<Declaration, Definition>) {
+        add_key_type_enum_definition::<
+            [u8; ed25519_dalek::SIGNATURE_LENGTH],
+            [u8; SECP256K1_SIGNATURE_LENGTH],
+        >(Self::declaration(), definitions);
+    }
+
+    fn declaration() -> Declaration {
+        "Signature".to_string()
+    }
+}
+
 impl serde::Serialize for Signature {
     fn serialize<S>(
         &self,
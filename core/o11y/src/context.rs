@@ -8,11 +8,43 @@ use tracing_opentelemetry::OpenTelemetrySpanExt;
 pub struct WithSpanContext<T: actix::Message> {
     pub msg: T,
     pub context: opentelemetry::Context,
+    /// Set if the message is counted in the mailbox depth of the actor.
+    pub mailbox: Option<MailboxEntry>,
 }
 
 impl<T: actix::Message> WithSpanContext<T> {
     pub fn new(msg: T) -> Self {
-        Self { msg, context: Span::current().context() }
+        Self { msg, context: Span::current().context(), mailbox: None }
+    }
+
+    /// Counts the message in the given mailbox depth until it's dropped,
+    /// which is when the actor has handled it.
+    pub fn in_mailbox(mut self, depth: prometheus::IntGauge) -> Self {
+        self.mailbox = Some(MailboxEntry::new(depth));
+        self
+    }
+}
+
+/// A message in the mailbox of an actor, which increases the mailbox depth
+/// for as long as it exists.
+pub struct MailboxEntry(prometheus::IntGauge);
+
+impl MailboxEntry {
+    fn new(depth: prometheus::IntGauge) -> Self {
+        depth.inc();
+        Self(depth)
+    }
+}
+
+impl Drop for MailboxEntry {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
+}
+
+impl std::fmt::Debug for MailboxEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("MailboxEntry").field(&self.0.get()).finish()
     }
 }
 
@@ -0,0 +1,177 @@
+//! Statistics and heap profiles of the jemalloc allocator used by neard.
+//!
+//! The statistics are refreshed on every read, so they can be exported as
+//! metrics periodically and queried through the debug RPC.  Heap profiling is
+//! compiled into the Linux builds of neard but sampling is off until it is
+//! activated at runtime, so the memory growth of a node can be investigated
+//! without restarting it with a special build.
+use crate::metrics::{try_create_gauge, try_create_int_gauge_vec, Gauge, IntGaugeVec};
+use once_cell::sync::Lazy;
+use std::ffi::CString;
+use std::path::{Path, PathBuf};
+use tikv_jemalloc_ctl::{arenas, epoch, raw, stats};
+
+#[derive(thiserror::Error, Debug)]
+pub enum JemallocError {
+    #[error("jemalloc control failed: {0}")]
+    Ctl(#[from] tikv_jemalloc_ctl::Error),
+    #[error("invalid heap profile path {0:?}")]
+    InvalidPath(PathBuf),
+}
+
+static JEMALLOC_BYTES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    try_create_int_gauge_vec(
+        "near_jemalloc_bytes",
+        "Memory allocated by jemalloc, by statistic",
+        &["stat"],
+    )
+    .unwrap()
+});
+
+static JEMALLOC_FRAGMENTATION: Lazy<Gauge> = Lazy::new(|| {
+    try_create_gauge(
+        "near_jemalloc_fragmentation_ratio",
+        "Share of the memory in the active pages of jemalloc not allocated by the application",
+    )
+    .unwrap()
+});
+
+/// Totals over all the arenas, in bytes.
+#[derive(serde::Serialize, Debug)]
+pub struct JemallocStats {
+    /// Memory allocated by the application.
+    pub allocated: usize,
+    /// Memory in the active pages, a multiple of the page size larger than
+    /// `allocated`.
+    pub active: usize,
+    /// Memory dedicated to the allocator metadata.
+    pub metadata: usize,
+    /// Memory in the physically resident data pages mapped by the allocator.
+    pub resident: usize,
+    /// Memory in the active extents mapped by the allocator.
+    pub mapped: usize,
+    /// Memory retained by the allocator rather than returned to the OS.
+    pub retained: usize,
+    /// Share of `active` not in `allocated`.
+    pub fragmentation: f64,
+    pub arenas: Vec<ArenaStats>,
+}
+
+/// Statistics of a single arena, in bytes.
+#[derive(serde::Serialize, Debug)]
+pub struct ArenaStats {
+    pub index: u32,
+    /// Number of the threads assigned to the arena.
+    pub threads: u32,
+    pub allocated: usize,
+    pub active: usize,
+    /// Memory in the unused dirty pages, not returned to the OS yet.
+    pub dirty: usize,
+    pub resident: usize,
+}
+
+impl JemallocStats {
+    /// Reads the current statistics of the allocator.
+    pub fn read() -> Result<Self, JemallocError> {
+        // The statistics are cached by jemalloc until the epoch is advanced.
+        epoch::advance()?;
+        let allocated = stats::allocated::read()?;
+        let active = stats::active::read()?;
+        // SAFETY: `arenas.page` is `size_t`.
+        let page = unsafe { raw::read::<usize>(b"arenas.page\0")? };
+        // Arenas which were never used have no statistics.
+        let arenas = (0..arenas::narenas::read()?)
+            .filter_map(|index| ArenaStats::read(index, page).ok())
+            .collect();
+        Ok(Self {
+            allocated,
+            active,
+            metadata: stats::metadata::read()?,
+            resident: stats::resident::read()?,
+            mapped: stats::mapped::read()?,
+            retained: stats::retained::read()?,
+            fragmentation: fragmentation(allocated, active),
+            arenas,
+        })
+    }
+
+    fn export_metrics(&self) {
+        for (stat, value) in [
+            ("allocated", self.allocated),
+            ("active", self.active),
+            ("metadata", self.metadata),
+            ("resident", self.resident),
+            ("mapped", self.mapped),
+            ("retained", self.retained),
+        ] {
+            JEMALLOC_BYTES.with_label_values(&[stat]).set(value as i64);
+        }
+        JEMALLOC_FRAGMENTATION.set(self.fragmentation);
+    }
+}
+
+impl ArenaStats {
+    fn read(index: u32, page: usize) -> Result<Self, JemallocError> {
+        let read = |stat: &str| {
+            let name = format!("stats.arenas.{index}.{stat}\0");
+            // SAFETY: all the statistics read are `size_t`.
+            unsafe { raw::read::<usize>(name.as_bytes()) }
+        };
+        let threads = format!("stats.arenas.{index}.nthreads\0");
+        Ok(Self {
+            index,
+            // SAFETY: `nthreads` is `unsigned`.
+            threads: unsafe { raw::read::<u32>(threads.as_bytes())? },
+            allocated: read("small.allocated")?.saturating_add(read("large.allocated")?),
+            active: read("pactive")?.saturating_mul(page),
+            dirty: read("pdirty")?.saturating_mul(page),
+            resident: read("resident")?,
+        })
+    }
+}
+
+fn fragmentation(allocated: usize, active: usize) -> f64 {
+    if active == 0 {
+        return 0.0;
+    }
+    1.0 - allocated as f64 / active as f64
+}
+
+/// Reads the statistics of the allocator and exports them as metrics.
+pub fn export_metrics() {
+    match JemallocStats::read() {
+        Ok(stats) => stats.export_metrics(),
+        Err(err) => tracing::debug!(target: "metrics", %err, "Failed to read the jemalloc stats"),
+    }
+}
+
+/// Starts or stops sampling the allocations for the heap profiles.  Fails if
+/// the allocator wasn't started with profiling enabled.
+pub fn set_heap_profiling_active(active: bool) -> Result<(), JemallocError> {
+    // SAFETY: `prof.active` is `bool`.
+    unsafe { raw::write(b"prof.active\0", active)? };
+    Ok(())
+}
+
+/// Dumps the profile of the allocations sampled since the profiling was
+/// activated to the file, in the format of `jeprof`.
+pub fn dump_heap_profile(path: &Path) -> Result<(), JemallocError> {
+    let invalid_path = || JemallocError::InvalidPath(path.to_path_buf());
+    let c_path =
+        CString::new(path.to_str().ok_or_else(invalid_path)?).map_err(|_| invalid_path())?;
+    // SAFETY: `prof.dump` is `const char *`, and the path outlives the call.
+    unsafe { raw::write(b"prof.dump\0", c_path.as_ptr())? };
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fragmentation() {
+        assert_eq!(fragmentation(0, 0), 0.0);
+        assert_eq!(fragmentation(4096, 4096), 0.0);
+        assert_eq!(fragmentation(1024, 4096), 0.75);
+    }
+}
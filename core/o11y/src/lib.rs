@@ -4,7 +4,7 @@
 pub use context::*;
 pub use env_filter::{BuildEnvFilterError, EnvFilterBuilder};
 pub use opentelemetry::OpenTelemetryLevel;
-pub use reload::{reload, reload_log_config};
+pub use reload::{reload, reload_log_config, sample_receipt_span};
 #[cfg(feature = "io_trace")]
 pub use subscriber::make_io_tracing_layer;
 pub use subscriber::{default_subscriber, default_subscriber_with_opentelemetry, Options};
@@ -15,6 +15,7 @@ pub use {tracing, tracing_appender, tracing_subscriber};
 pub mod context;
 pub mod env_filter;
 mod io_tracer;
+pub mod jemalloc;
 pub mod log_config;
 mod log_counter;
 pub mod macros;
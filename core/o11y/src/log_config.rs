@@ -20,6 +20,11 @@ pub struct LogConfig {
     /// individual spans with something like `debug,store::trie=trace` to have specific targets be
     /// more verbose than the default.
     pub opentelemetry: Option<String>,
+    /// If set to N, only one in N receipts gets a `process_receipt` span.
+    ///
+    /// Spans of individual receipts make up the bulk of the exported trace data of a busy
+    /// node. Receipts are picked by their id, so all nodes sample the same receipts.
+    pub receipt_span_sample_rate: Option<u32>,
 }
 
 impl LogConfig {
@@ -67,17 +67,69 @@
 //! }
 //! ```
 
-use once_cell::sync::Lazy;
+use once_cell::sync::{Lazy, OnceCell};
 pub use prometheus::{
     self, core::MetricVec, core::MetricVecBuilder, exponential_buckets, linear_buckets, Counter,
     CounterVec, Encoder, Gauge, GaugeVec, Histogram, HistogramOpts, HistogramVec, IntCounter,
     IntCounterVec, IntGauge, IntGaugeVec, Opts, Result, TextEncoder,
 };
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
+
+/// Changes applied to all the exported metrics.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct MetricsConfig {
+    /// Labels added to all the metrics, e.g. `{"network": "mainnet"}`.
+    #[serde(default)]
+    pub global_labels: BTreeMap<String, String>,
+    /// Namespace replacing the `near` prefix of the metric names.
+    #[serde(default)]
+    pub namespace: Option<String>,
+}
+
+impl MetricsConfig {
+    fn apply(&self, families: &mut [prometheus::proto::MetricFamily]) {
+        for family in families {
+            if let Some(namespace) = &self.namespace {
+                if let Some(name) = family.get_name().strip_prefix("near_") {
+                    let name = format!("{namespace}_{name}");
+                    family.set_name(name);
+                }
+            }
+            for metric in family.mut_metric().iter_mut() {
+                let labels = metric.mut_label();
+                for (name, value) in &self.global_labels {
+                    // Labels of the metric itself take precedence.
+                    if labels.iter().any(|label| label.get_name() == name) {
+                        continue;
+                    }
+                    let mut label = prometheus::proto::LabelPair::default();
+                    label.set_name(name.clone());
+                    label.set_value(value.clone());
+                    labels.push(label);
+                }
+                labels.sort_by(|a, b| a.get_name().cmp(b.get_name()));
+            }
+        }
+    }
+}
+
+static METRICS_CONFIG: OnceCell<MetricsConfig> = OnceCell::new();
+
+/// Sets the changes applied to all the exported metrics.  Only the first
+/// config set in the process applies.
+pub fn set_metrics_config(config: MetricsConfig) {
+    if METRICS_CONFIG.set(config).is_err() {
+        tracing::warn!(target: "metrics", "Metrics config already set, ignoring the new one");
+    }
+}
 
 /// Collect all the metrics for reporting.
 pub fn gather() -> Vec<prometheus::proto::MetricFamily> {
-    prometheus::gather()
+    let mut families = prometheus::gather();
+    if let Some(config) = METRICS_CONFIG.get() {
+        config.apply(&mut families);
+    }
+    families
 }
 
 /// Attempts to crate an `IntCounter`, returning `Err` if the registry does not accept the counter
@@ -247,7 +299,8 @@ fn check_metric_near_prefix(name: &str) -> Result<()> {
 
 #[cfg(test)]
 mod tests {
-    use crate::metrics::check_metric_near_prefix;
+    use crate::metrics::{check_metric_near_prefix, MetricsConfig};
+    use prometheus::{IntCounterVec, Opts, Registry};
 
     #[test]
     fn test_near_prefix() {
@@ -256,4 +309,30 @@ mod tests {
         assert!(check_metric_near_prefix("near").is_err());
         assert!(check_metric_near_prefix("abc").is_err());
     }
+
+    #[test]
+    fn test_metrics_config() {
+        let registry = Registry::new();
+        let counter =
+            IntCounterVec::new(Opts::new("near_abc", "help"), &["role", "shard"]).unwrap();
+        registry.register(Box::new(counter.clone())).unwrap();
+        counter.with_label_values(&["observer", "0"]).inc();
+
+        let config = MetricsConfig {
+            global_labels: [("network", "testnet"), ("role", "validator")]
+                .into_iter()
+                .map(|(name, value)| (name.to_owned(), value.to_owned()))
+                .collect(),
+            namespace: Some("node".to_owned()),
+        };
+        let mut families = registry.gather();
+        config.apply(&mut families);
+        assert_eq!(families[0].get_name(), "node_abc");
+        let labels: Vec<_> = families[0].get_metric()[0]
+            .get_label()
+            .iter()
+            .map(|label| (label.get_name(), label.get_value()))
+            .collect();
+        assert_eq!(labels, vec![("network", "testnet"), ("role", "observer"), ("shard", "0")]);
+    }
 }
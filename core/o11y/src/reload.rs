@@ -3,6 +3,7 @@ use crate::{log_config, log_counter, BuildEnvFilterError, EnvFilterBuilder, Open
 use once_cell::sync::OnceCell;
 use opentelemetry_sdk::trace::Tracer;
 use std::str::FromStr as _;
+use std::sync::atomic::{AtomicU32, Ordering};
 use tracing_appender::non_blocking::NonBlocking;
 use tracing_opentelemetry::OpenTelemetryLayer;
 use tracing_subscriber::filter::{Filtered, Targets};
@@ -20,6 +21,9 @@ static OTLP_LAYER_RELOAD_HANDLE: OnceCell<
 // Records the level of opentelemetry tracing verbosity configured via command-line flags at the startup.
 static DEFAULT_OTLP_LEVEL: OnceCell<OpenTelemetryLevel> = OnceCell::new();
 
+// One in how many receipts gets a span, see `LogConfig::receipt_span_sample_rate`.
+static RECEIPT_SPAN_SAMPLE_RATE: AtomicU32 = AtomicU32::new(1);
+
 pub(crate) type LogLayer<Inner> = Layered<
     Filtered<
         fmt::Layer<Inner, fmt::format::DefaultFields, fmt::format::Format, NonBlocking>,
@@ -81,7 +85,17 @@ pub enum ReloadError {
     ParseOpentelemetry(#[source] tracing_subscriber::filter::ParseError),
 }
 
+/// Returns whether the receipt with the given id should get its own span.
+pub fn sample_receipt_span(receipt_id: &[u8; 32]) -> bool {
+    let rate = RECEIPT_SPAN_SAMPLE_RATE.load(Ordering::Relaxed);
+    rate <= 1 || u32::from_le_bytes(receipt_id[..4].try_into().unwrap()) % rate == 0
+}
+
 pub fn reload_log_config(config: Option<&log_config::LogConfig>) {
+    RECEIPT_SPAN_SAMPLE_RATE.store(
+        config.and_then(|config| config.receipt_span_sample_rate).unwrap_or(1),
+        Ordering::Relaxed,
+    );
     let result = if let Some(config) = config {
         reload(
             config.rust_log.as_deref(),
@@ -3,8 +3,9 @@ use crate::config_store::INITIAL_TESTNET_CONFIG;
 use crate::cost::RuntimeFeesConfig;
 use crate::parameter_table::ParameterTable;
 use near_account_id::AccountId;
-use near_primitives_core::types::{Balance, Gas};
+use near_primitives_core::types::{Balance, Gas, ShardId};
 use near_primitives_core::version::PROTOCOL_VERSION;
+use std::collections::BTreeMap;
 
 use super::parameter_table::InvalidConfigError;
 
@@ -31,6 +32,18 @@ pub struct RuntimeConfig {
     pub storage_proof_size_soft_limit: usize,
     /// The configuration for congestion control.
     pub congestion_control_config: CongestionControlConfig,
+    /// Congestion control configs of shards which don't use
+    /// `congestion_control_config`.
+    ///
+    /// Only set by the congestion control overrides of the genesis of test
+    /// chains, see [`crate::RuntimeConfigStore::with_congestion_control_overrides`].
+    pub shard_congestion_control_configs: BTreeMap<ShardId, CongestionControlConfig>,
+    /// Whether transactions may attach a priority fee, which is paid to the
+    /// chunk producer including them.
+    ///
+    /// Only set on benchmarknet from `ProtocolFeature::TransactionPriorityFee`
+    /// on, see [`crate::RuntimeConfigStore::with_priority_fee`].
+    pub priority_fee_enabled: bool,
 }
 
 impl RuntimeConfig {
@@ -59,6 +72,8 @@ impl RuntimeConfig {
             account_creation_config: AccountCreationConfig::default(),
             storage_proof_size_soft_limit: usize::MAX,
             congestion_control_config: runtime_config.congestion_control_config,
+            shard_congestion_control_configs: BTreeMap::new(),
+            priority_fee_enabled: false,
         }
     }
 
@@ -75,12 +90,23 @@ impl RuntimeConfig {
             account_creation_config: AccountCreationConfig::default(),
             storage_proof_size_soft_limit: usize::MAX,
             congestion_control_config: runtime_config.congestion_control_config,
+            shard_congestion_control_configs: BTreeMap::new(),
+            priority_fee_enabled: false,
         }
     }
 
     pub fn storage_amount_per_byte(&self) -> Balance {
         self.fees.storage_usage_config.storage_amount_per_byte
     }
+
+    /// The congestion control config that applies to the congestion of the
+    /// given shard.
+    pub fn shard_congestion_control_config(&self, shard_id: ShardId) -> CongestionControlConfig {
+        self.shard_congestion_control_configs
+            .get(&shard_id)
+            .copied()
+            .unwrap_or(self.congestion_control_config)
+    }
 }
 
 /// The structure describes configuration for creation of new accounts.
@@ -208,3 +234,60 @@ impl CongestionControlConfig {
         }
     }
 }
+
+/// Overrides of some of the values of a [`CongestionControlConfig`], the
+/// values which are not set are kept.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CongestionControlConfigOverride {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_congestion_incoming_gas: Option<Gas>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_congestion_outgoing_gas: Option<Gas>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_congestion_memory_consumption: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_congestion_missed_chunks: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_outgoing_gas: Option<Gas>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_outgoing_gas: Option<Gas>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_shard_outgoing_gas: Option<Gas>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tx_gas: Option<Gas>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_tx_gas: Option<Gas>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reject_tx_congestion_threshold: Option<f64>,
+}
+
+impl CongestionControlConfigOverride {
+    /// Returns `config` with the overridden values replaced.
+    pub fn apply(&self, config: CongestionControlConfig) -> CongestionControlConfig {
+        CongestionControlConfig {
+            max_congestion_incoming_gas: self
+                .max_congestion_incoming_gas
+                .unwrap_or(config.max_congestion_incoming_gas),
+            max_congestion_outgoing_gas: self
+                .max_congestion_outgoing_gas
+                .unwrap_or(config.max_congestion_outgoing_gas),
+            max_congestion_memory_consumption: self
+                .max_congestion_memory_consumption
+                .unwrap_or(config.max_congestion_memory_consumption),
+            max_congestion_missed_chunks: self
+                .max_congestion_missed_chunks
+                .unwrap_or(config.max_congestion_missed_chunks),
+            max_outgoing_gas: self.max_outgoing_gas.unwrap_or(config.max_outgoing_gas),
+            min_outgoing_gas: self.min_outgoing_gas.unwrap_or(config.min_outgoing_gas),
+            allowed_shard_outgoing_gas: self
+                .allowed_shard_outgoing_gas
+                .unwrap_or(config.allowed_shard_outgoing_gas),
+            max_tx_gas: self.max_tx_gas.unwrap_or(config.max_tx_gas),
+            min_tx_gas: self.min_tx_gas.unwrap_or(config.min_tx_gas),
+            reject_tx_congestion_threshold: self
+                .reject_tx_congestion_threshold
+                .unwrap_or(config.reject_tx_congestion_threshold),
+        }
+    }
+}
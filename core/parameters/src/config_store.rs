@@ -1,6 +1,11 @@
-use crate::config::{CongestionControlConfig, RuntimeConfig};
-use crate::parameter_table::{ParameterTable, ParameterTableDiff};
-use near_primitives_core::types::ProtocolVersion;
+use crate::config::{CongestionControlConfig, CongestionControlConfigOverride, RuntimeConfig};
+use crate::cost::ExtCosts;
+use crate::parameter::Parameter;
+use crate::parameter_table::{
+    InvalidConfigError, ParameterChange, ParameterTable, ParameterTableDiff,
+};
+use near_primitives_core::types::{Compute, ProtocolVersion, ShardId};
+use near_primitives_core::version::ProtocolFeature;
 use std::collections::BTreeMap;
 use std::ops::Bound;
 use std::sync::Arc;
@@ -46,11 +51,57 @@ static CONFIG_DIFFS: &[(ProtocolVersion, &str)] = &[
     (138, include_config!("138.yaml")),
     // Congestion Control
     (142, include_config!("142.yaml")),
+    // Batch ed25519 signature verification
+    (145, include_config!("145.yaml")),
+    // Gas refund penalty
+    (146, include_config!("146.yaml")),
+    // Contract call depth limit
+    (147, include_config!("147.yaml")),
+    // Receipt size limit
+    (151, include_config!("151.yaml")),
 ];
 
 /// Testnet parameters for versions <= 29, which (incorrectly) differed from mainnet parameters
 pub static INITIAL_TESTNET_CONFIG: &str = include_config!("parameters_testnet.yaml");
 
+#[derive(thiserror::Error, Debug)]
+pub enum ComputeCostOverrideError {
+    #[error("could not parse `{0}` as a parameter")]
+    UnknownParameter(String),
+    #[error("`{0}` is not the cost of a host function")]
+    NotHostFunctionCost(Parameter),
+}
+
+/// Error of [`RuntimeConfigStore::with_parameter_overrides`].
+#[derive(thiserror::Error, Debug)]
+#[error("invalid runtime parameter overrides")]
+pub struct ParameterOverridesError(#[source] InvalidConfigError);
+
+/// Returns the table of the parameters at the protocol version, from the
+/// parameter files.
+fn parameter_table_at(protocol_version: ProtocolVersion) -> ParameterTable {
+    let mut params: ParameterTable =
+        BASE_CONFIG.parse().expect("Failed parsing base parameter file.");
+    for (version, diff_bytes) in CONFIG_DIFFS.iter().take_while(|(v, _)| *v <= protocol_version) {
+        let diff: ParameterTableDiff = diff_bytes.parse().unwrap_or_else(|err| {
+            panic!("Failed parsing runtime parameters diff for version {version}. Error: {err}")
+        });
+        params.apply_diff(diff).unwrap_or_else(|err| {
+            panic!("Failed applying diff to `RuntimeConfig` for version {version}. Error: {err}")
+        });
+    }
+    params
+}
+
+/// Returns the runtime parameters with different values at the two protocol
+/// versions, with their values at `from` as `old` and at `to` as `new`.
+///
+/// The values are those of the parameter files, without the overrides of the
+/// genesis runtime config or the chain, see `RuntimeConfigStore::new`.
+pub fn parameters_diff(from: ProtocolVersion, to: ProtocolVersion) -> Vec<ParameterChange> {
+    parameter_table_at(from).diff(&parameter_table_at(to))
+}
+
 /// Stores runtime config for each protocol version where it was updated.
 #[derive(Clone, Debug)]
 pub struct RuntimeConfigStore {
@@ -119,16 +170,59 @@ impl RuntimeConfigStore {
     /// first protocol versions.
     /// For testnet, runtime config for genesis block was (incorrectly) different, that's why we
     /// need to override it specifically to preserve compatibility.
+    /// Benchmarknet experiments with the priority fee of transactions.
     pub fn for_chain_id(chain_id: &str) -> Self {
         match chain_id {
             near_primitives_core::chains::TESTNET => {
                 let genesis_runtime_config = RuntimeConfig::initial_testnet_config();
                 Self::new(Some(&genesis_runtime_config))
             }
+            _ if chain_id.starts_with(near_primitives_core::chains::BENCHMARKNET) => {
+                Self::new(None).with_priority_fee()
+            }
             _ => Self::new(None),
         }
     }
 
+    /// Constructs the store with the values of the parameters of `overrides`
+    /// replacing those of all protocol versions. The overrides are a YAML map
+    /// from the parameter names to their values, in the format of
+    /// `parameters.yaml`, e.g.:
+    ///
+    /// ```yaml
+    /// storage_amount_per_byte: 10_000_000_000_000_000_000
+    /// action_function_call: { send_sir: 1, send_not_sir: 1, execution: 1 }
+    /// ```
+    ///
+    /// This is meant for the development of contracts on local chains, which
+    /// use the configs of [`Self::new`] without a genesis runtime config, and
+    /// is NOT consensus safe.
+    pub fn with_parameter_overrides(overrides: &str) -> Result<Self, ParameterOverridesError> {
+        let overrides: ParameterTable = overrides.parse().map_err(ParameterOverridesError)?;
+        let mut params: ParameterTable =
+            BASE_CONFIG.parse().expect("Failed parsing base parameter file.");
+        let mut store = BTreeMap::new();
+        let diffs =
+            CONFIG_DIFFS.iter().map(|(protocol_version, diff)| (*protocol_version, Some(diff)));
+        for (protocol_version, diff_bytes) in std::iter::once((0, None)).chain(diffs) {
+            if let Some(diff_bytes) = diff_bytes {
+                let diff: ParameterTableDiff = diff_bytes.parse().unwrap_or_else(|err| panic!("Failed parsing runtime parameters diff for version {protocol_version}. Error: {err}"));
+                params.apply_diff(diff).unwrap_or_else(|err| panic!("Failed applying diff to `RuntimeConfig` for version {protocol_version}. Error: {err}"));
+            }
+            let mut overridden_params = params.clone();
+            overridden_params.apply_overrides(&overrides);
+            #[allow(unused_mut)]
+            let mut runtime_config =
+                RuntimeConfig::new(&overridden_params).map_err(ParameterOverridesError)?;
+            #[cfg(feature = "calimero_zero_storage")]
+            {
+                runtime_config.fees.storage_usage_config.storage_amount_per_byte = 0;
+            }
+            store.insert(protocol_version, Arc::new(runtime_config));
+        }
+        Ok(Self { store })
+    }
+
     /// Constructs test store.
     pub fn with_one_config(runtime_config: RuntimeConfig) -> Self {
         Self { store: BTreeMap::from_iter([(0, Arc::new(runtime_config))].iter().cloned()) }
@@ -152,6 +246,66 @@ impl RuntimeConfigStore {
         Self::with_one_config(RuntimeConfig::free())
     }
 
+    /// Overrides the compute costs of the given host function parameters, e.g.
+    /// `wasm_storage_read_base`, in the configs of all protocol versions.
+    ///
+    /// This is meant for experiments on test chains only and is NOT consensus
+    /// safe: chunks applied with different compute costs can differ, so nodes
+    /// with different overrides won't agree on the chain.
+    pub fn with_compute_cost_overrides(
+        mut self,
+        overrides: &BTreeMap<String, Compute>,
+    ) -> Result<Self, ComputeCostOverrideError> {
+        let mut ext_cost_overrides = vec![];
+        for (name, compute) in overrides {
+            let param: Parameter = name
+                .parse()
+                .map_err(|_| ComputeCostOverrideError::UnknownParameter(name.clone()))?;
+            let ext_cost = <ExtCosts as strum::IntoEnumIterator>::iter()
+                .find(|ext_cost| ext_cost.param() == param)
+                .ok_or(ComputeCostOverrideError::NotHostFunctionCost(param))?;
+            ext_cost_overrides.push((ext_cost, *compute));
+        }
+        for config in self.store.values_mut() {
+            let config = Arc::make_mut(config);
+            for (ext_cost, compute) in &ext_cost_overrides {
+                config.wasm_config.ext_costs.costs[*ext_cost].compute = *compute;
+            }
+        }
+        Ok(self)
+    }
+
+    /// Overrides the congestion control config of the given shards in the
+    /// configs of all protocol versions.
+    ///
+    /// Used with the congestion control overrides of the genesis config,
+    /// which are only allowed on test chains.
+    pub fn with_congestion_control_overrides(
+        mut self,
+        overrides: &BTreeMap<ShardId, CongestionControlConfigOverride>,
+    ) -> Self {
+        for config in self.store.values_mut() {
+            let config = Arc::make_mut(config);
+            for (shard_id, config_override) in overrides {
+                let shard_config = config_override.apply(config.congestion_control_config);
+                config.shard_congestion_control_configs.insert(*shard_id, shard_config);
+            }
+        }
+        self
+    }
+
+    /// Allows transactions to attach a priority fee in the configs of the
+    /// protocol versions with `ProtocolFeature::TransactionPriorityFee`.
+    pub fn with_priority_fee(mut self) -> Self {
+        let protocol_version = ProtocolFeature::TransactionPriorityFee.protocol_version();
+        let config = self.get_config(protocol_version).clone();
+        self.store.insert(protocol_version, config);
+        for (_, config) in self.store.range_mut(protocol_version..) {
+            Arc::make_mut(config).priority_fee_enabled = true;
+        }
+        self
+    }
+
     /// Returns a `RuntimeConfig` for the corresponding protocol version.
     pub fn get_config(&self, protocol_version: ProtocolVersion) -> &Arc<RuntimeConfig> {
         self.store
@@ -168,10 +322,12 @@ impl RuntimeConfigStore {
 mod tests {
     use super::*;
     use crate::cost::{ActionCosts, ExtCosts};
+    use crate::parameter_table::ParameterValue;
     use near_primitives_core::version::ProtocolFeature::{
         DecreaseFunctionCallBaseCost, LowerDataReceiptAndEcrecoverBaseCost, LowerStorageCost,
         LowerStorageKeyLimit,
     };
+    use near_primitives_core::version::PROTOCOL_VERSION;
     use std::collections::HashSet;
 
     const GENESIS_PROTOCOL_VERSION: ProtocolVersion = 29;
@@ -205,6 +361,143 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_compute_cost_overrides() {
+        let overrides = BTreeMap::from([("wasm_storage_read_base".to_string(), 42)]);
+        let store = RuntimeConfigStore::new(None).with_compute_cost_overrides(&overrides).unwrap();
+        for protocol_version in [GENESIS_PROTOCOL_VERSION, PROTOCOL_VERSION] {
+            let ext_costs = &store.get_config(protocol_version).wasm_config.ext_costs;
+            assert_eq!(ext_costs.compute_cost(ExtCosts::storage_read_base), 42);
+        }
+        let base_store = RuntimeConfigStore::new(None);
+        assert_eq!(
+            store
+                .get_config(PROTOCOL_VERSION)
+                .wasm_config
+                .ext_costs
+                .gas_cost(ExtCosts::storage_read_base),
+            base_store
+                .get_config(PROTOCOL_VERSION)
+                .wasm_config
+                .ext_costs
+                .gas_cost(ExtCosts::storage_read_base),
+        );
+
+        let unknown = BTreeMap::from([("wasm_no_such_cost".to_string(), 1)]);
+        assert!(matches!(
+            RuntimeConfigStore::new(None).with_compute_cost_overrides(&unknown),
+            Err(ComputeCostOverrideError::UnknownParameter(_))
+        ));
+        let not_host_function = BTreeMap::from([("storage_amount_per_byte".to_string(), 1)]);
+        assert!(matches!(
+            RuntimeConfigStore::new(None).with_compute_cost_overrides(&not_host_function),
+            Err(ComputeCostOverrideError::NotHostFunctionCost(Parameter::StorageAmountPerByte))
+        ));
+    }
+
+    #[test]
+    fn test_priority_fee() {
+        let version = ProtocolFeature::TransactionPriorityFee.protocol_version();
+        let store = RuntimeConfigStore::new(None).with_priority_fee();
+        assert!(!store.get_config(version - 1).priority_fee_enabled);
+        assert!(store.get_config(version).priority_fee_enabled);
+        assert!(store.get_config(version + 1).priority_fee_enabled);
+        assert_eq!(
+            store.get_config(version).wasm_config,
+            store.get_config(version - 1).wasm_config
+        );
+
+        let store = RuntimeConfigStore::for_chain_id("benchmarknet-1");
+        assert!(store.get_config(version).priority_fee_enabled);
+        let store = RuntimeConfigStore::for_chain_id(near_primitives_core::chains::MAINNET);
+        assert!(!store.get_config(version).priority_fee_enabled);
+    }
+
+    #[test]
+    fn test_parameter_overrides() {
+        let overrides = "
+storage_amount_per_byte: 42
+action_function_call: { send_sir: 1, send_not_sir: 2, execution: 3 }
+";
+        let base_store = RuntimeConfigStore::new(None);
+        let store = RuntimeConfigStore::with_parameter_overrides(overrides).unwrap();
+        for protocol_version in [GENESIS_PROTOCOL_VERSION, PROTOCOL_VERSION] {
+            let config = store.get_config(protocol_version);
+            assert_eq!(config.fees.storage_usage_config.storage_amount_per_byte, 42);
+            let function_call = config.fees.fee(ActionCosts::function_call_base);
+            assert_eq!(
+                (
+                    function_call.send_fee(true),
+                    function_call.send_fee(false),
+                    function_call.exec_fee()
+                ),
+                (1, 2, 3)
+            );
+            // The other parameters keep the values of the version.
+            let base_config = base_store.get_config(protocol_version);
+            assert_eq!(config.wasm_config, base_config.wasm_config);
+            assert_eq!(
+                config.fees.fee(ActionCosts::transfer),
+                base_config.fees.fee(ActionCosts::transfer)
+            );
+        }
+
+        assert!(RuntimeConfigStore::with_parameter_overrides("no_such_parameter: 1").is_err());
+        assert!(
+            RuntimeConfigStore::with_parameter_overrides("storage_amount_per_byte: {}").is_err()
+        );
+    }
+
+    #[test]
+    fn test_congestion_control_overrides() {
+        let overrides = BTreeMap::from([(
+            1,
+            CongestionControlConfigOverride {
+                max_congestion_incoming_gas: Some(42),
+                reject_tx_congestion_threshold: Some(0.25),
+                ..Default::default()
+            },
+        )]);
+        let base_store = RuntimeConfigStore::new(None);
+        let store = RuntimeConfigStore::new(None).with_congestion_control_overrides(&overrides);
+        for protocol_version in [GENESIS_PROTOCOL_VERSION, PROTOCOL_VERSION] {
+            let config = store.get_config(protocol_version);
+            let base_config = base_store.get_config(protocol_version).congestion_control_config;
+            assert_eq!(config.congestion_control_config, base_config);
+            assert_eq!(config.shard_congestion_control_config(0), base_config);
+            assert_eq!(
+                config.shard_congestion_control_config(1),
+                CongestionControlConfig {
+                    max_congestion_incoming_gas: 42,
+                    reject_tx_congestion_threshold: 0.25,
+                    ..base_config
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn test_parameters_diff() {
+        assert_eq!(parameters_diff(147, 147), vec![]);
+        let diff = parameters_diff(146, 147);
+        assert_eq!(
+            diff,
+            vec![ParameterChange {
+                parameter: "max_call_depth".to_string(),
+                old: Some(ParameterValue::U64(4_294_967_295)),
+                new: Some(ParameterValue::U64(4_096)),
+            }]
+        );
+        let reverse_diff = parameters_diff(147, 146);
+        assert_eq!(reverse_diff[0].old, diff[0].new);
+        assert_eq!(reverse_diff[0].new, diff[0].old);
+        // Every version with a parameter file changes some parameter.
+        for (version, _) in CONFIG_DIFFS {
+            let diff = parameters_diff(version - 1, *version);
+            assert!(!diff.is_empty(), "no parameters changed at version {version}");
+        }
+    }
+
     #[test]
     fn test_max_prepaid_gas() {
         let store = RuntimeConfigStore::new(None);
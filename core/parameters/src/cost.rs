@@ -135,6 +135,9 @@ impl ExtCostsConfig {
             ExtCosts::yield_create_byte => 300_000_000_000_000,
             ExtCosts::yield_resume_base => 300_000_000_000_000,
             ExtCosts::yield_resume_byte => 300_000_000_000_000,
+            ExtCosts::ed25519_verify_batch_base => SAFETY_MULTIPLIER * 1513656750,
+            ExtCosts::ed25519_verify_batch_signature => SAFETY_MULTIPLIER * 756828375,
+            ExtCosts::ed25519_verify_batch_byte => SAFETY_MULTIPLIER * 7157035,
         }
         .map(|_, value| ParameterCost { gas: value, compute: value * factor });
         ExtCostsConfig { costs }
@@ -230,6 +233,9 @@ pub enum ExtCosts {
     yield_create_byte = 62,
     yield_resume_base = 63,
     yield_resume_byte = 64,
+    ed25519_verify_batch_base = 65,
+    ed25519_verify_batch_signature = 66,
+    ed25519_verify_batch_byte = 67,
 }
 
 // Type of an action, used in fees logic.
@@ -342,6 +348,9 @@ impl ExtCosts {
             ExtCosts::yield_create_byte => Parameter::WasmYieldCreateByte,
             ExtCosts::yield_resume_base => Parameter::WasmYieldResumeBase,
             ExtCosts::yield_resume_byte => Parameter::WasmYieldResumeBase,
+            ExtCosts::ed25519_verify_batch_base => Parameter::WasmEd25519VerifyBatchBase,
+            ExtCosts::ed25519_verify_batch_signature => Parameter::WasmEd25519VerifyBatchSignature,
+            ExtCosts::ed25519_verify_batch_byte => Parameter::WasmEd25519VerifyBatchByte,
         }
     }
 }
@@ -359,6 +368,13 @@ pub struct RuntimeFeesConfig {
 
     /// Pessimistic gas price inflation ratio.
     pub pessimistic_gas_price_inflation_ratio: Rational32,
+
+    /// Fraction of every gas refund which is burnt instead of refunded.
+    pub gas_refund_penalty: Rational32,
+
+    /// Gas refunds worth less than this amount of gas are burnt instead of
+    /// refunded.
+    pub min_gas_refund: Gas,
 }
 
 /// Describes cost of storage per block
@@ -384,6 +400,8 @@ impl RuntimeFeesConfig {
             storage_usage_config: StorageUsageConfig::test(),
             burnt_gas_reward: Rational32::new(3, 10),
             pessimistic_gas_price_inflation_ratio: Rational32::new(103, 100),
+            gas_refund_penalty: Rational32::from_integer(0),
+            min_gas_refund: 0,
             action_fees: enum_map::enum_map! {
                 ActionCosts::create_account => Fee {
                     send_sir: 3_850_000_000_000,
@@ -477,6 +495,26 @@ impl RuntimeFeesConfig {
             storage_usage_config: StorageUsageConfig::free(),
             burnt_gas_reward: Rational32::from_integer(0),
             pessimistic_gas_price_inflation_ratio: Rational32::from_integer(0),
+            gas_refund_penalty: Rational32::from_integer(0),
+            min_gas_refund: 0,
+        }
+    }
+
+    /// The part of a gas refund of `refund` tokens, with the gas bought at
+    /// `gas_price`, that is burnt instead of refunded.
+    ///
+    /// A `gas_refund_penalty` fraction of the refund is burnt, and if less than
+    /// `min_gas_refund` gas would remain, the whole refund is burnt.
+    pub fn gas_refund_penalty_amount(&self, refund: Balance, gas_price: Balance) -> Balance {
+        let numer = *self.gas_refund_penalty.numer() as Balance;
+        let denom = *self.gas_refund_penalty.denom() as Balance;
+        // Split the multiplication to not overflow for large refunds.
+        let penalty = (refund / denom * numer + refund % denom * numer / denom).min(refund);
+        let min_refund = Balance::from(self.min_gas_refund).saturating_mul(gas_price);
+        if refund - penalty < min_refund {
+            refund
+        } else {
+            penalty
         }
     }
 
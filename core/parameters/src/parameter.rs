@@ -19,6 +19,8 @@ pub enum Parameter {
     // Gas economics config
     BurntGasReward,
     PessimisticGasPriceInflation,
+    GasRefundPenalty,
+    MinGasRefund,
 
     // Stateless validation config
     StorageProofSizeSoftLimit,
@@ -85,6 +87,9 @@ pub enum Parameter {
     WasmEcrecoverBase,
     WasmEd25519VerifyBase,
     WasmEd25519VerifyByte,
+    WasmEd25519VerifyBatchBase,
+    WasmEd25519VerifyBatchSignature,
+    WasmEd25519VerifyBatchByte,
     WasmLogBase,
     WasmLogByte,
     WasmStorageWriteBase,
@@ -129,6 +134,7 @@ pub enum Parameter {
     MaxGasBurnt,
     MaxGasBurntView,
     MaxStackHeight,
+    MaxCallDepth,
     ContractPrepareVersion,
     InitialMemoryPages,
     MaxMemoryPages,
@@ -145,6 +151,7 @@ pub enum Parameter {
     MaxLengthReturnedData,
     MaxContractSize,
     MaxTransactionSize,
+    MaxReceiptSize,
     MaxLengthStorageKey,
     MaxLengthStorageValue,
     MaxPromisesPerFunctionCallAction,
@@ -169,6 +176,7 @@ pub enum Parameter {
     VmKind,
     EthImplicitAccounts,
     YieldResume,
+    Ed25519VerifyBatch,
 
     // Congestion Control
     MaxCongestionIncomingGas,
@@ -223,6 +231,7 @@ impl Parameter {
         [
             Parameter::MaxGasBurnt,
             Parameter::MaxStackHeight,
+            Parameter::MaxCallDepth,
             Parameter::ContractPrepareVersion,
             Parameter::InitialMemoryPages,
             Parameter::MaxMemoryPages,
@@ -239,6 +248,7 @@ impl Parameter {
             Parameter::MaxLengthReturnedData,
             Parameter::MaxContractSize,
             Parameter::MaxTransactionSize,
+            Parameter::MaxReceiptSize,
             Parameter::MaxLengthStorageKey,
             Parameter::MaxLengthStorageValue,
             Parameter::MaxPromisesPerFunctionCallAction,
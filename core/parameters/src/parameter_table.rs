@@ -9,12 +9,12 @@ use crate::vm::{Config, StorageGetMode};
 use near_primitives_core::account::id::ParseAccountError;
 use near_primitives_core::types::AccountId;
 use num_rational::Rational32;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 /// Represents values supported by parameter config.
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
 #[serde(untagged)]
-pub(crate) enum ParameterValue {
+pub enum ParameterValue {
     U64(u64),
     Rational { numerator: i32, denominator: i32 },
     ParameterCost { gas: u64, compute: u64 },
@@ -222,6 +222,7 @@ impl core::fmt::Display for ParameterValue {
     }
 }
 
+#[derive(Clone)]
 pub(crate) struct ParameterTable {
     parameters: BTreeMap<Parameter, ParameterValue>,
 }
@@ -237,6 +238,16 @@ impl core::fmt::Display for ParameterTable {
     }
 }
 
+/// Change of the value of a parameter between two parameter tables.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct ParameterChange {
+    pub parameter: String,
+    /// Value in the first table, `None` if the parameter isn't defined in it.
+    pub old: Option<ParameterValue>,
+    /// Value in the second table, `None` if the parameter isn't defined in it.
+    pub new: Option<ParameterValue>,
+}
+
 /// Changes made to parameters between versions.
 pub(crate) struct ParameterTableDiff {
     parameters: BTreeMap<Parameter, (Option<ParameterValue>, Option<ParameterValue>)>,
@@ -297,6 +308,8 @@ impl TryFrom<&ParameterTable> for RuntimeConfig {
                 burnt_gas_reward: params.get(Parameter::BurntGasReward)?,
                 pessimistic_gas_price_inflation_ratio: params
                     .get(Parameter::PessimisticGasPriceInflation)?,
+                gas_refund_penalty: params.get(Parameter::GasRefundPenalty)?,
+                min_gas_refund: params.get(Parameter::MinGasRefund)?,
                 storage_usage_config: StorageUsageConfig {
                     storage_amount_per_byte: params.get(Parameter::StorageAmountPerByte)?,
                     num_bytes_account: params.get(Parameter::StorageNumBytesAccount)?,
@@ -327,6 +340,7 @@ impl TryFrom<&ParameterTable> for RuntimeConfig {
                 function_call_weight: params.get(Parameter::FunctionCallWeight)?,
                 eth_implicit_accounts: params.get(Parameter::EthImplicitAccounts)?,
                 yield_resume_host_functions: params.get(Parameter::YieldResume)?,
+                ed25519_verify_batch: params.get(Parameter::Ed25519VerifyBatch)?,
             },
             account_creation_config: AccountCreationConfig {
                 min_allowed_top_level_account_length: params
@@ -335,6 +349,8 @@ impl TryFrom<&ParameterTable> for RuntimeConfig {
             },
             storage_proof_size_soft_limit: params.get(Parameter::StorageProofSizeSoftLimit)?,
             congestion_control_config: get_congestion_control_config(params)?,
+            shard_congestion_control_configs: BTreeMap::new(),
+            priority_fee_enabled: false,
         })
     }
 }
@@ -393,6 +409,32 @@ impl ParameterTable {
         Ok(())
     }
 
+    /// Replaces the values of the parameters of `overrides`.
+    pub(crate) fn apply_overrides(&mut self, overrides: &ParameterTable) {
+        for (key, value) in &overrides.parameters {
+            self.parameters.insert(*key, value.clone());
+        }
+    }
+
+    /// Returns the parameters with different values in `other`, in the order
+    /// of the parameters.
+    pub(crate) fn diff(&self, other: &ParameterTable) -> Vec<ParameterChange> {
+        let parameters: BTreeSet<&Parameter> =
+            self.parameters.keys().chain(other.parameters.keys()).collect();
+        parameters
+            .into_iter()
+            .filter_map(|parameter| {
+                let old = self.parameters.get(parameter);
+                let new = other.parameters.get(parameter);
+                (old != new).then(|| ParameterChange {
+                    parameter: parameter.to_string(),
+                    old: old.cloned(),
+                    new: new.cloned(),
+                })
+            })
+            .collect()
+    }
+
     fn yaml_map(&self, params: impl Iterator<Item = &'static Parameter>) -> serde_yaml::Value {
         // All parameter values can be serialized as YAML, so we don't ever expect this to fail.
         serde_yaml::to_value(
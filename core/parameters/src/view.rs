@@ -41,6 +41,12 @@ pub struct RuntimeFeesConfigView {
     /// Pessimistic gas price inflation ratio.
     pub pessimistic_gas_price_inflation_ratio: Rational32,
 
+    /// Fraction of every gas refund which is burnt instead of refunded.
+    pub gas_refund_penalty: Rational32,
+
+    /// Gas refunds worth less than this amount of gas are burnt instead of refunded.
+    pub min_gas_refund: Gas,
+
     /// The maximum size of the state witness after which we defer execution of any new receipts.
     pub storage_proof_size_soft_limit: usize,
 }
@@ -182,6 +188,8 @@ impl From<crate::RuntimeConfig> for RuntimeConfigView {
                 pessimistic_gas_price_inflation_ratio: config
                     .fees
                     .pessimistic_gas_price_inflation_ratio,
+                gas_refund_penalty: config.fees.gas_refund_penalty,
+                min_gas_refund: config.fees.min_gas_refund,
                 storage_proof_size_soft_limit: config.storage_proof_size_soft_limit,
             },
             wasm_config: VMConfigView::from(config.wasm_config),
@@ -227,6 +235,8 @@ pub struct VMConfigView {
     pub eth_implicit_accounts: bool,
     /// See [`VMConfig::yield_resume_host_functions`].
     pub yield_resume_host_functions: bool,
+    /// See [`VMConfig::ed25519_verify_batch`].
+    pub ed25519_verify_batch: bool,
 
     /// Describes limits for VM and Runtime.
     ///
@@ -253,6 +263,7 @@ impl From<crate::vm::Config> for VMConfigView {
             vm_kind: config.vm_kind,
             eth_implicit_accounts: config.eth_implicit_accounts,
             yield_resume_host_functions: config.yield_resume_host_functions,
+            ed25519_verify_batch: config.ed25519_verify_batch,
         }
     }
 }
@@ -275,6 +286,7 @@ impl From<VMConfigView> for crate::vm::Config {
             vm_kind: view.vm_kind,
             eth_implicit_accounts: view.eth_implicit_accounts,
             yield_resume_host_functions: view.yield_resume_host_functions,
+            ed25519_verify_batch: view.ed25519_verify_batch,
         }
     }
 }
@@ -345,6 +357,12 @@ pub struct ExtCostsConfigView {
     pub ed25519_verify_base: Gas,
     /// Cost of getting ed25519 per byte
     pub ed25519_verify_byte: Gas,
+    /// Base cost of verifying a batch of ed25519 signatures
+    pub ed25519_verify_batch_base: Gas,
+    /// Cost of verifying a batch of ed25519 signatures per signature
+    pub ed25519_verify_batch_signature: Gas,
+    /// Cost of verifying a batch of ed25519 signatures per message byte
+    pub ed25519_verify_batch_byte: Gas,
 
     /// Cost of calling ecrecover
     pub ecrecover_base: Gas,
@@ -485,6 +503,10 @@ impl From<crate::ExtCostsConfig> for ExtCostsConfigView {
             ripemd160_block: config.gas_cost(ExtCosts::ripemd160_block),
             ed25519_verify_base: config.gas_cost(ExtCosts::ed25519_verify_base),
             ed25519_verify_byte: config.gas_cost(ExtCosts::ed25519_verify_byte),
+            ed25519_verify_batch_base: config.gas_cost(ExtCosts::ed25519_verify_batch_base),
+            ed25519_verify_batch_signature: config
+                .gas_cost(ExtCosts::ed25519_verify_batch_signature),
+            ed25519_verify_batch_byte: config.gas_cost(ExtCosts::ed25519_verify_batch_byte),
             ecrecover_base: config.gas_cost(ExtCosts::ecrecover_base),
             log_base: config.gas_cost(ExtCosts::log_base),
             log_byte: config.gas_cost(ExtCosts::log_byte),
@@ -564,6 +586,9 @@ impl From<ExtCostsConfigView> for crate::ExtCostsConfig {
                 ExtCosts::ripemd160_block => view.ripemd160_block,
                 ExtCosts::ed25519_verify_base => view.ed25519_verify_base,
                 ExtCosts::ed25519_verify_byte => view.ed25519_verify_byte,
+                ExtCosts::ed25519_verify_batch_base => view.ed25519_verify_batch_base,
+                ExtCosts::ed25519_verify_batch_signature => view.ed25519_verify_batch_signature,
+                ExtCosts::ed25519_verify_batch_byte => view.ed25519_verify_batch_byte,
                 ExtCosts::ecrecover_base => view.ecrecover_base,
                 ExtCosts::log_base => view.log_base,
                 ExtCosts::log_byte => view.log_byte,
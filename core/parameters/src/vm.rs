@@ -64,6 +64,11 @@ pub struct LimitConfig {
     /// See <https://wiki.parity.io/WebAssembly-StackHeight> to find out how the stack frame cost
     /// is calculated.
     pub max_stack_height: u32,
+    /// How deep the calls between the functions of a contract are allowed to nest?
+    ///
+    /// Rather than counted separately, the call depth is limited by accounting each function
+    /// activation for at least `max_stack_height / max_call_depth` of the stack height.
+    pub max_call_depth: u32,
     /// Whether a legacy version of stack limiting should be used, see
     /// [`ContractPrepareVersion`].
     #[serde(default = "ContractPrepareVersion::v0")]
@@ -109,6 +114,10 @@ pub struct LimitConfig {
     pub max_contract_size: u64,
     /// Max transaction size
     pub max_transaction_size: u64,
+    /// Max size of a receipt created when applying a chunk, in the borsh
+    /// serialized form. Limits how much a single receipt adds to the state
+    /// witness and to the outgoing receipts distributed over the network.
+    pub max_receipt_size: u64,
     /// Max storage key size
     pub max_length_storage_key: u64,
     /// Max storage value size
@@ -192,6 +201,9 @@ pub struct Config {
     /// Enable the `promise_yield_create` and `promise_yield_resume` host functions.
     pub yield_resume_host_functions: bool,
 
+    /// Enable the `ed25519_verify_batch` host function.
+    pub ed25519_verify_batch: bool,
+
     /// Describes limits for VM and Runtime.
     pub limit_config: LimitConfig,
 }
@@ -217,6 +229,7 @@ impl Config {
     }
 
     pub fn enable_all_features(&mut self) {
+        self.ed25519_verify_batch = true;
         self.yield_resume_host_functions = true;
         self.eth_implicit_accounts = true;
         self.function_call_weight = true;
@@ -227,6 +240,14 @@ impl Config {
     }
 }
 
+impl LimitConfig {
+    /// The least stack height accounted for a function activation, which
+    /// keeps the calls from nesting deeper than `max_call_depth`.
+    pub fn min_function_activation_stack_height(&self) -> u32 {
+        self.max_stack_height / self.max_call_depth.max(1)
+    }
+}
+
 fn wasmer2_stack_limit_default() -> i32 {
     100 * 1024
 }
@@ -3,7 +3,7 @@ use crate::checked_feature;
 use crate::hash::CryptoHash;
 use crate::serialize::dec_format;
 use crate::types::{Balance, Nonce, ProtocolVersion, StorageUsage};
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 pub use near_account_id as id;
 use std::io;
 
@@ -379,6 +379,7 @@ impl BorshSerialize for Account {
 #[derive(
     BorshSerialize,
     BorshDeserialize,
+    BorshSchema,
     PartialEq,
     Eq,
     Hash,
@@ -409,6 +410,7 @@ impl AccessKey {
 #[derive(
     BorshSerialize,
     BorshDeserialize,
+    BorshSchema,
     PartialEq,
     Eq,
     Hash,
@@ -432,6 +434,7 @@ pub enum AccessKeyPermission {
 #[derive(
     BorshSerialize,
     BorshDeserialize,
+    BorshSchema,
     serde::Serialize,
     serde::Deserialize,
     PartialEq,
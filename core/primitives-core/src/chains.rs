@@ -11,3 +11,6 @@ pub const STATELESSNET: &str = "statelessnet";
 
 /// Pre-release testing environment.
 pub const MOCKNET: &str = "mocknet";
+
+/// Prefix of the chain ids of the networks used for benchmarking.
+pub const BENCHMARKNET: &str = "benchmarknet";
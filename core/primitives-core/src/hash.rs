@@ -16,6 +16,7 @@ use std::io::Write;
     derive_more::AsMut,
     arbitrary::Arbitrary,
     borsh::BorshDeserialize,
+    borsh::BorshSchema,
     borsh::BorshSerialize,
 )]
 #[as_ref(forward)]
@@ -7,7 +7,7 @@ use crate::types::ProtocolVersion;
 /// #[cfg(feature = "protocol_feature_evm")]
 /// EVM code
 ///
-#[derive(Hash, PartialEq, Eq, Clone, Copy, Debug)]
+#[derive(Hash, PartialEq, Eq, Clone, Copy, Debug, strum::EnumIter)]
 pub enum ProtocolFeature {
     // stable features
     ImplicitAccountCreation,
@@ -159,6 +159,32 @@ pub enum ProtocolFeature {
     CongestionControl,
     // Stateless validation: Distribute state witness as reed solomon encoded parts
     PartialEncodedStateWitness,
+    /// Process delayed receipts with a higher attached gas price first, keeping
+    /// old receipts from starving.
+    DelayedReceiptPriority,
+    /// Adds the `ed25519_verify_batch` host function.
+    Ed25519VerifyBatch,
+    /// Burns a fraction of every gas refund and gas refunds below a threshold
+    /// instead of refunding them, to reduce the number of tiny refund receipts.
+    GasRefundPenalty,
+    /// Limits the call depth of contracts with the `max_call_depth` parameter.
+    MaxCallDepth,
+    /// Chunk producers may omit the chunks of shards without transactions and
+    /// receipts on the benchmark networks, which count as produced empty
+    /// chunks.
+    SkipEmptyChunks,
+    /// Transactions buy the gas of their receipts at the gas price of their
+    /// block instead of the pessimistically inflated one, and the receipts
+    /// burn the gas at the price it was bought at, so that gas price changes
+    /// need neither a gas refund nor a deficit.
+    NoPessimisticGasPrice,
+    /// Transactions may attach a priority fee, paid to the chunk producer
+    /// including them. Only enabled on benchmarknet, see
+    /// `RuntimeConfig::priority_fee_enabled`.
+    TransactionPriorityFee,
+    /// Limits the size of the receipts created when applying a chunk with the
+    /// `max_receipt_size` parameter.
+    ReceiptSizeLimit,
 }
 
 impl ProtocolFeature {
@@ -236,12 +262,32 @@ impl ProtocolFeature {
             // TODO(#11201): When stabilizing this feature in mainnet, also remove the temporary code
             // that always enables this for mocknet (see config_mocknet function).
             ProtocolFeature::ShuffleShardAssignments => 143,
+            ProtocolFeature::DelayedReceiptPriority => 144,
+            ProtocolFeature::Ed25519VerifyBatch => 145,
+            ProtocolFeature::GasRefundPenalty => 146,
+            ProtocolFeature::MaxCallDepth => 147,
+            ProtocolFeature::SkipEmptyChunks => 148,
+            ProtocolFeature::NoPessimisticGasPrice => 149,
+            ProtocolFeature::TransactionPriorityFee => 150,
+            ProtocolFeature::ReceiptSizeLimit => 151,
         }
     }
 
     pub fn enabled(&self, protocol_version: ProtocolVersion) -> bool {
         protocol_version >= self.protocol_version()
     }
+
+    /// Returns the features enabled by upgrading from the lower of the two
+    /// protocol versions to the higher one.
+    pub fn enabled_between(
+        a: ProtocolVersion,
+        b: ProtocolVersion,
+    ) -> impl Iterator<Item = ProtocolFeature> {
+        let (lower, higher) = (a.min(b), a.max(b));
+        <Self as strum::IntoEnumIterator>::iter().filter(move |feature| {
+            lower < feature.protocol_version() && feature.protocol_version() <= higher
+        })
+    }
 }
 
 /// Current protocol version used on the mainnet.
@@ -255,7 +301,7 @@ pub const PROTOCOL_VERSION: ProtocolVersion = if cfg!(feature = "statelessnet_pr
     86
 } else if cfg!(feature = "nightly_protocol") {
     // On nightly, pick big enough version to support all features.
-    143
+    151
 } else {
     // Enable all stable features.
     STABLE_PROTOCOL_VERSION
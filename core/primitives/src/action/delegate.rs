@@ -6,7 +6,7 @@
 pub use self::private_non_delegate_action::NonDelegateAction;
 use super::Action;
 use crate::signable_message::{SignableMessage, SignableMessageType};
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use near_crypto::{PublicKey, Signature};
 use near_primitives_core::hash::{hash, CryptoHash};
 use near_primitives_core::types::BlockHeight;
@@ -17,7 +17,17 @@ use std::io::{Error, ErrorKind, Read};
 /// This is an index number of Action::Delegate in Action enumeration
 const ACTION_DELEGATE_NUMBER: u8 = 8;
 /// This action allows to execute the inner actions behalf of the defined sender.
-#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Clone,
+    Debug,
+)]
 pub struct DelegateAction {
     /// Signer of the delegated actions
     pub sender_id: AccountId,
@@ -38,7 +48,17 @@ pub struct DelegateAction {
     pub public_key: PublicKey,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Clone,
+    Debug,
+)]
 pub struct SignedDelegateAction {
     pub delegate_action: DelegateAction,
     pub signature: Signature,
@@ -90,7 +110,7 @@ mod private_non_delegate_action {
     /// in several places. For example, borsh de-/serialization relies on it. If the
     /// invariant is broken, we may end up with a `Transaction` or `Receipt` that we
     /// can serialize but deserializing it back causes a parsing error.
-    #[derive(Serialize, BorshSerialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+    #[derive(Serialize, BorshSerialize, BorshSchema, Deserialize, PartialEq, Eq, Clone, Debug)]
     pub struct NonDelegateAction(Action);
 
     impl From<NonDelegateAction> for Action {
@@ -1,6 +1,6 @@
 pub mod delegate;
 
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use near_crypto::PublicKey;
 use near_primitives_core::{
     account::AccessKey,
@@ -19,6 +19,7 @@ fn base64(s: &[u8]) -> String {
 #[derive(
     BorshSerialize,
     BorshDeserialize,
+    BorshSchema,
     PartialEq,
     Eq,
     Clone,
@@ -37,6 +38,7 @@ pub struct AddKeyAction {
 #[derive(
     BorshSerialize,
     BorshDeserialize,
+    BorshSchema,
     PartialEq,
     Eq,
     Clone,
@@ -49,6 +51,7 @@ pub struct CreateAccountAction {}
 #[derive(
     BorshSerialize,
     BorshDeserialize,
+    BorshSchema,
     PartialEq,
     Eq,
     Clone,
@@ -63,6 +66,7 @@ pub struct DeleteAccountAction {
 #[derive(
     BorshSerialize,
     BorshDeserialize,
+    BorshSchema,
     PartialEq,
     Eq,
     Clone,
@@ -78,7 +82,14 @@ pub struct DeleteKeyAction {
 /// Deploy contract action
 #[serde_as]
 #[derive(
-    BorshSerialize, BorshDeserialize, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    serde::Serialize,
+    serde::Deserialize,
+    PartialEq,
+    Eq,
+    Clone,
 )]
 pub struct DeployContractAction {
     /// WebAssembly binary
@@ -96,7 +107,14 @@ impl fmt::Debug for DeployContractAction {
 
 #[serde_as]
 #[derive(
-    BorshSerialize, BorshDeserialize, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    serde::Serialize,
+    serde::Deserialize,
+    PartialEq,
+    Eq,
+    Clone,
 )]
 pub struct FunctionCallAction {
     pub method_name: String,
@@ -122,6 +140,7 @@ impl fmt::Debug for FunctionCallAction {
 #[derive(
     BorshSerialize,
     BorshDeserialize,
+    BorshSchema,
     PartialEq,
     Eq,
     Clone,
@@ -140,6 +159,7 @@ pub struct StakeAction {
 #[derive(
     BorshSerialize,
     BorshDeserialize,
+    BorshSchema,
     PartialEq,
     Eq,
     Clone,
@@ -155,6 +175,7 @@ pub struct TransferAction {
 #[derive(
     BorshSerialize,
     BorshDeserialize,
+    BorshSchema,
     PartialEq,
     Eq,
     Clone,
@@ -171,6 +192,7 @@ pub struct NonrefundableStorageTransferAction {
 #[derive(
     BorshSerialize,
     BorshDeserialize,
+    BorshSchema,
     PartialEq,
     Eq,
     Debug,
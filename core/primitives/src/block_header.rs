@@ -6,13 +6,21 @@ use crate::types::validator_stake::{ValidatorStake, ValidatorStakeIter, Validato
 use crate::types::{AccountId, Balance, BlockHeight, EpochId, MerkleHash, NumBlocks};
 use crate::validator_signer::ValidatorSigner;
 use crate::version::{get_protocol_version, ProtocolVersion, PROTOCOL_VERSION};
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use near_async::time::Utc;
 use near_crypto::{KeyType, PublicKey, Signature};
 use std::sync::Arc;
 
 #[derive(
-    BorshSerialize, BorshDeserialize, serde::Serialize, Debug, Clone, Eq, PartialEq, Default,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    serde::Serialize,
+    Debug,
+    Clone,
+    Eq,
+    PartialEq,
+    Default,
 )]
 pub struct BlockHeaderInnerLite {
     /// Height of this block.
@@ -33,7 +41,9 @@ pub struct BlockHeaderInnerLite {
     pub block_merkle_root: CryptoHash,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, serde::Serialize, Debug, Clone, Eq, PartialEq)]
+#[derive(
+    BorshSerialize, BorshDeserialize, BorshSchema, serde::Serialize, Debug, Clone, Eq, PartialEq,
+)]
 pub struct BlockHeaderInnerRest {
     /// Root hash of the previous chunks' outgoing receipts in the given block.
     pub prev_chunk_outgoing_receipts_root: MerkleHash,
@@ -71,7 +81,9 @@ pub struct BlockHeaderInnerRest {
 }
 
 /// Remove `chunks_included` from V1
-#[derive(BorshSerialize, BorshDeserialize, serde::Serialize, Debug, Clone, Eq, PartialEq)]
+#[derive(
+    BorshSerialize, BorshDeserialize, BorshSchema, serde::Serialize, Debug, Clone, Eq, PartialEq,
+)]
 pub struct BlockHeaderInnerRestV2 {
     /// Root hash of the previous chunks' outgoing receipts in the given block.
     pub prev_chunk_outgoing_receipts_root: MerkleHash,
@@ -110,7 +122,9 @@ pub struct BlockHeaderInnerRestV2 {
 /// Add `block_ordinal`
 /// Add `epoch_sync_data_hash`
 /// Use new `ValidatorStake` struct
-#[derive(BorshSerialize, BorshDeserialize, serde::Serialize, Debug, Clone, Eq, PartialEq)]
+#[derive(
+    BorshSerialize, BorshDeserialize, BorshSchema, serde::Serialize, Debug, Clone, Eq, PartialEq,
+)]
 pub struct BlockHeaderInnerRestV3 {
     /// Root hash of the previous chunks' outgoing receipts in the given block.
     pub prev_chunk_outgoing_receipts_root: MerkleHash,
@@ -154,7 +168,15 @@ pub struct BlockHeaderInnerRestV3 {
 
 /// Add `block_body_hash`
 #[derive(
-    BorshSerialize, BorshDeserialize, serde::Serialize, Debug, Clone, Eq, PartialEq, Default,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    serde::Serialize,
+    Debug,
+    Clone,
+    Eq,
+    PartialEq,
+    Default,
 )]
 pub struct BlockHeaderInnerRestV4 {
     /// Hash of block body
@@ -266,7 +288,9 @@ impl ApprovalMessage {
     }
 }
 
-#[derive(BorshSerialize, BorshDeserialize, serde::Serialize, Debug, Clone, Eq, PartialEq)]
+#[derive(
+    BorshSerialize, BorshDeserialize, BorshSchema, serde::Serialize, Debug, Clone, Eq, PartialEq,
+)]
 #[borsh(init=init)]
 pub struct BlockHeaderV1 {
     pub prev_hash: CryptoHash,
@@ -295,7 +319,9 @@ impl BlockHeaderV1 {
 }
 
 /// V1 -> V2: Remove `chunks_included` from `inner_reset`
-#[derive(BorshSerialize, BorshDeserialize, serde::Serialize, Debug, Clone, Eq, PartialEq)]
+#[derive(
+    BorshSerialize, BorshDeserialize, BorshSchema, serde::Serialize, Debug, Clone, Eq, PartialEq,
+)]
 #[borsh(init=init)]
 pub struct BlockHeaderV2 {
     pub prev_hash: CryptoHash,
@@ -315,7 +341,9 @@ pub struct BlockHeaderV2 {
 
 /// V2 -> V3: Add `prev_height` to `inner_rest` and use new `ValidatorStake`
 // Add `block_ordinal` to `inner_rest`
-#[derive(BorshSerialize, BorshDeserialize, serde::Serialize, Debug, Clone, Eq, PartialEq)]
+#[derive(
+    BorshSerialize, BorshDeserialize, BorshSchema, serde::Serialize, Debug, Clone, Eq, PartialEq,
+)]
 #[borsh(init=init)]
 pub struct BlockHeaderV3 {
     pub prev_hash: CryptoHash,
@@ -335,7 +363,15 @@ pub struct BlockHeaderV3 {
 
 /// V3 -> V4: Add hash of block body to inner_rest
 #[derive(
-    BorshSerialize, BorshDeserialize, serde::Serialize, Debug, Clone, Eq, PartialEq, Default,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    serde::Serialize,
+    Debug,
+    Clone,
+    Eq,
+    PartialEq,
+    Default,
 )]
 #[borsh(init=init)]
 pub struct BlockHeaderV4 {
@@ -386,7 +422,9 @@ impl BlockHeaderV4 {
 
 /// Versioned BlockHeader data structure.
 /// For each next version, document what are the changes between versions.
-#[derive(BorshSerialize, BorshDeserialize, serde::Serialize, Debug, Clone, Eq, PartialEq)]
+#[derive(
+    BorshSerialize, BorshDeserialize, BorshSchema, serde::Serialize, Debug, Clone, Eq, PartialEq,
+)]
 pub enum BlockHeader {
     BlockHeaderV1(Arc<BlockHeaderV1>),
     BlockHeaderV2(Arc<BlockHeaderV2>),
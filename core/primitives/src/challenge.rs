@@ -3,7 +3,7 @@ use crate::merkle::MerklePath;
 use crate::sharding::{EncodedShardChunk, ShardChunk, ShardChunkHeader};
 use crate::types::AccountId;
 use crate::validator_signer::ValidatorSigner;
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use near_crypto::Signature;
 use std::fmt::{Debug, Formatter};
 
@@ -133,6 +133,7 @@ pub type Challenges = Vec<Challenge>;
 #[derive(
     BorshSerialize,
     BorshDeserialize,
+    BorshSchema,
     PartialEq,
     Eq,
     Clone,
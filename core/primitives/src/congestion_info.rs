@@ -1,5 +1,5 @@
 use crate::errors::RuntimeError;
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use near_parameters::config::CongestionControlConfig;
 use near_primitives_core::types::{Gas, ShardId};
 
@@ -112,6 +112,7 @@ impl CongestionControl {
 #[derive(
     BorshSerialize,
     BorshDeserialize,
+    BorshSchema,
     serde::Serialize,
     serde::Deserialize,
     Debug,
@@ -316,6 +317,7 @@ impl ExtendedCongestionInfo {
 #[derive(
     BorshSerialize,
     BorshDeserialize,
+    BorshSchema,
     serde::Serialize,
     serde::Deserialize,
     Default,
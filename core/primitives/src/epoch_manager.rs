@@ -78,9 +78,23 @@ impl ShardConfig {
 
 /// Testing overrides to apply to the EpochConfig returned by the `for_protocol_version`.
 /// All fields should be optional and the default should be a no-op.
-#[derive(Clone, Default)]
+///
+/// Like the rest of the protocol version specific configs, they are only
+/// applied with the production config. Besides the tests, these are read from
+/// the `epoch_config_override_file` of the node config, so that benchmark
+/// networks can reshape the validator schedule without changing the genesis.
+#[derive(Clone, Debug, Default, serde::Deserialize)]
 pub struct AllEpochConfigTestOverrides {
+    /// Also sets the block producer seats of every shard.
+    #[serde(default)]
+    pub num_block_producer_seats: Option<NumSeats>,
+    #[serde(default)]
+    pub num_chunk_producer_seats: Option<NumSeats>,
+    #[serde(default)]
+    pub num_chunk_only_producer_seats: Option<NumSeats>,
+    #[serde(default)]
     pub block_producer_kickout_threshold: Option<u8>,
+    #[serde(default)]
     pub chunk_producer_kickout_threshold: Option<u8>,
 }
 
@@ -277,6 +291,21 @@ impl AllEpochConfig {
         config: &mut EpochConfig,
         test_overrides: &AllEpochConfigTestOverrides,
     ) {
+        if let Some(num_block_producer_seats) = test_overrides.num_block_producer_seats {
+            config.num_block_producer_seats = num_block_producer_seats;
+            config.num_block_producer_seats_per_shard =
+                config.shard_layout.shard_ids().map(|_| num_block_producer_seats).collect();
+        }
+
+        if let Some(num_chunk_producer_seats) = test_overrides.num_chunk_producer_seats {
+            config.validator_selection_config.num_chunk_producer_seats = num_chunk_producer_seats;
+        }
+
+        if let Some(num_chunk_only_producer_seats) = test_overrides.num_chunk_only_producer_seats {
+            config.validator_selection_config.num_chunk_only_producer_seats =
+                num_chunk_only_producer_seats;
+        }
+
         if let Some(block_producer_kickout_threshold) =
             test_overrides.block_producer_kickout_threshold
         {
@@ -1328,6 +1357,7 @@ pub mod epoch_sync {
     use crate::epoch_manager::block_info::BlockInfo;
     use crate::epoch_manager::epoch_info::EpochInfo;
     use crate::errors::epoch_sync::{EpochSyncHashType, EpochSyncInfoError};
+    use crate::merkle::PartialMerkleTree;
     use crate::types::EpochId;
     use borsh::{BorshDeserialize, BorshSerialize};
     use near_primitives_core::hash::CryptoHash;
@@ -1353,6 +1383,11 @@ pub mod epoch_sync {
         pub epoch_info: EpochInfo,
         pub next_epoch_info: EpochInfo,
         pub next_next_epoch_info: EpochInfo,
+        /// Block merkle tree of the last block of the previous epoch.
+        /// It is O(log(n)) in size and lets a node without the previous epochs
+        /// reconstruct and validate the block merkle trees of this epoch, so
+        /// epoch sync can start from any epoch.
+        pub prev_epoch_last_block_merkle_tree: PartialMerkleTree,
     }
 
     impl EpochSyncInfo {
@@ -0,0 +1,92 @@
+//! Evidence of validators signing conflicting messages.
+//!
+//! There is no slashing for it on chain yet, the evidence is only recorded by
+//! the nodes observing it so that the operators can see which validators
+//! equivocate.
+use crate::block_header::{Approval, BlockHeader};
+use crate::types::{AccountId, BlockHeight, EpochId};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Two conflicting messages signed by the same validator.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum EquivocationEvidence {
+    /// Two different blocks produced by the block producer of their height.
+    Block { account_id: AccountId, left: Box<BlockHeader>, right: Box<BlockHeader> },
+    /// Two different approvals for the same target height, signed with the
+    /// key the validator has in `epoch_id`.
+    Approval { epoch_id: EpochId, left: Approval, right: Approval },
+}
+
+impl EquivocationEvidence {
+    pub fn account_id(&self) -> &AccountId {
+        match self {
+            Self::Block { account_id, .. } => account_id,
+            Self::Approval { left, .. } => &left.account_id,
+        }
+    }
+
+    /// Height of the blocks, or target height of the approvals.
+    pub fn height(&self) -> BlockHeight {
+        match self {
+            Self::Block { left, .. } => left.height(),
+            Self::Approval { left, .. } => left.target_height,
+        }
+    }
+
+    pub fn epoch_id(&self) -> &EpochId {
+        match self {
+            Self::Block { left, .. } => left.epoch_id(),
+            Self::Approval { epoch_id, .. } => epoch_id,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Block { .. } => "block",
+            Self::Approval { .. } => "approval",
+        }
+    }
+
+    /// Whether the two messages conflict. The signatures are not checked.
+    pub fn is_conflicting(&self) -> bool {
+        match self {
+            Self::Block { left, right, .. } => {
+                left.height() == right.height()
+                    && left.epoch_id() == right.epoch_id()
+                    && left.hash() != right.hash()
+            }
+            Self::Approval { left, right, .. } => {
+                left.account_id == right.account_id
+                    && left.target_height == right.target_height
+                    && left.inner != right.inner
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::CryptoHash;
+    use crate::test_utils::create_test_signer;
+
+    #[test]
+    fn test_conflicting_approvals() {
+        let signer = create_test_signer("test");
+        let endorsement = Approval::new(CryptoHash::hash_bytes(b"a"), 9, 10, &signer);
+        let other_endorsement = Approval::new(CryptoHash::hash_bytes(b"b"), 9, 10, &signer);
+        let skip = Approval::new(CryptoHash::hash_bytes(b"a"), 8, 10, &signer);
+        let next_skip = Approval::new(CryptoHash::hash_bytes(b"a"), 8, 11, &signer);
+        let evidence = |left: &Approval, right: &Approval| EquivocationEvidence::Approval {
+            epoch_id: EpochId::default(),
+            left: left.clone(),
+            right: right.clone(),
+        };
+        assert!(evidence(&endorsement, &other_endorsement).is_conflicting());
+        assert!(evidence(&endorsement, &skip).is_conflicting());
+        assert!(!evidence(&endorsement, &endorsement).is_conflicting());
+        assert!(!evidence(&skip, &next_skip).is_conflicting());
+        assert_eq!(evidence(&endorsement, &skip).height(), 10);
+        assert_eq!(evidence(&endorsement, &skip).account_id().as_str(), "test");
+    }
+}
@@ -1,7 +1,7 @@
 use crate::hash::CryptoHash;
 use crate::serialize::dec_format;
 use crate::types::{AccountId, Balance, EpochId, Gas, Nonce};
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use near_crypto::PublicKey;
 use near_primitives_core::types::ProtocolVersion;
 use near_rpc_error_macro::RpcError;
@@ -11,6 +11,7 @@ use std::fmt::{Debug, Display};
 #[derive(
     BorshSerialize,
     BorshDeserialize,
+    BorshSchema,
     Debug,
     Clone,
     PartialEq,
@@ -127,6 +128,7 @@ impl std::error::Error for StorageError {}
 #[derive(
     BorshSerialize,
     BorshDeserialize,
+    BorshSchema,
     Debug,
     Clone,
     PartialEq,
@@ -185,6 +187,7 @@ impl std::error::Error for InvalidTxError {}
 #[derive(
     BorshSerialize,
     BorshDeserialize,
+    BorshSchema,
     Debug,
     Clone,
     PartialEq,
@@ -219,6 +222,7 @@ pub enum InvalidAccessKeyError {
 #[derive(
     BorshSerialize,
     BorshDeserialize,
+    BorshSchema,
     Debug,
     Clone,
     PartialEq,
@@ -267,6 +271,7 @@ pub enum ActionsValidationError {
 #[derive(
     BorshSerialize,
     BorshDeserialize,
+    BorshSchema,
     Debug,
     Clone,
     PartialEq,
@@ -290,6 +295,8 @@ pub enum ReceiptValidationError {
     NumberInputDataDependenciesExceeded { number_of_input_data_dependencies: u64, limit: u64 },
     /// An error occurred while validating actions of an ActionReceipt.
     ActionsValidation(ActionsValidationError),
+    /// The size of a new receipt exceeded the limit.
+    ReceiptSizeExceeded { size: u64, limit: u64 },
 }
 
 impl Display for ReceiptValidationError {
@@ -320,6 +327,9 @@ impl Display for ReceiptValidationError {
                 number_of_input_data_dependencies, limit
             ),
             ReceiptValidationError::ActionsValidation(e) => write!(f, "{}", e),
+            ReceiptValidationError::ReceiptSizeExceeded { size, limit } => {
+                write!(f, "The size of the receipt {} exceeded the limit {}", size, limit)
+            }
         }
     }
 }
@@ -405,6 +415,7 @@ impl std::error::Error for ActionsValidationError {}
 #[derive(
     BorshSerialize,
     BorshDeserialize,
+    BorshSchema,
     Debug,
     Clone,
     PartialEq,
@@ -426,6 +437,7 @@ impl std::error::Error for ActionError {}
 #[derive(
     BorshSerialize,
     BorshDeserialize,
+    BorshSchema,
     Debug,
     Clone,
     PartialEq,
@@ -989,6 +1001,7 @@ impl From<std::io::Error> for EpochError {
     PartialEq,
     Eq,
     BorshDeserialize,
+    BorshSchema,
     BorshSerialize,
     RpcError,
     serde::Deserialize,
@@ -1030,6 +1043,7 @@ pub enum PrepareError {
     PartialEq,
     Eq,
     BorshDeserialize,
+    BorshSchema,
     BorshSerialize,
     RpcError,
     serde::Deserialize,
@@ -1063,6 +1077,7 @@ pub enum WasmTrap {
     PartialEq,
     Eq,
     BorshDeserialize,
+    BorshSchema,
     BorshSerialize,
     RpcError,
     serde::Deserialize,
@@ -1146,6 +1161,7 @@ pub enum HostError {
     PartialEq,
     Eq,
     BorshDeserialize,
+    BorshSchema,
     BorshSerialize,
     RpcError,
     serde::Deserialize,
@@ -1164,6 +1180,7 @@ pub enum MethodResolveError {
     PartialEq,
     Eq,
     BorshDeserialize,
+    BorshSchema,
     BorshSerialize,
     RpcError,
     serde::Deserialize,
@@ -1193,6 +1210,7 @@ pub enum CompilationError {
     PartialEq,
     Eq,
     BorshDeserialize,
+    BorshSchema,
     BorshSerialize,
     serde::Serialize,
     serde::Deserialize,
@@ -1243,5 +1261,7 @@ pub mod epoch_sync {
         HashNotFound { hash: CryptoHash, hash_type: EpochSyncHashType, epoch_height: EpochHeight },
         #[error("all_block_hashes.len() < 2 for epoch {epoch_height}")]
         ShortEpoch { epoch_height: EpochHeight },
+        #[error("block merkle root of header {hash:?} doesn't match all_block_hashes for epoch {epoch_height}")]
+        InvalidBlockMerkleRoot { hash: CryptoHash, epoch_height: EpochHeight },
     }
 }
@@ -14,7 +14,9 @@ pub mod challenge;
 pub mod congestion_info;
 pub mod epoch_manager;
 pub mod epoch_sync;
+pub mod equivocation;
 pub mod errors;
+pub mod light_client;
 pub mod merkle;
 pub mod network;
 pub mod profile_data_v2;
@@ -23,6 +25,7 @@ pub mod receipt;
 pub mod reed_solomon;
 pub mod runtime;
 pub mod sandbox;
+pub mod schema;
 pub mod shard_layout;
 pub mod sharding;
 pub mod signable_message;
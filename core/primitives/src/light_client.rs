@@ -0,0 +1,391 @@
+//! Verification of light client blocks and execution outcome proofs, see
+//! <https://nomicon.io/ChainSpec/LightClient>.
+//!
+//! Only depends on the views returned by the light client RPC methods, so that
+//! bridges and other light clients can verify them without running a node.
+use crate::block_header::{Approval, ApprovalInner};
+use crate::hash::CryptoHash;
+use crate::merkle::{combine_hash, compute_root_from_path, verify_hash, MerklePath};
+use crate::types::Balance;
+use crate::views::validator_stake_view::ValidatorStakeView;
+use crate::views::{ExecutionOutcomeWithIdView, LightClientBlockLiteView, LightClientBlockView};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum LightClientError {
+    #[error("block height {height} is not higher than the height of the head {head_height}")]
+    HeightNotIncreasing { height: u64, head_height: u64 },
+    #[error("block is neither in the epoch of the head nor in the next one")]
+    UnknownEpoch,
+    #[error("block is in the next epoch but does not have the next block producers")]
+    MissingNextBlockProducers,
+    #[error("approval of block producer {index} has an invalid signature")]
+    InvalidSignature { index: usize },
+    #[error("block is approved by {approved_stake} out of {total_stake} stake, which is not more than 2/3")]
+    NotEnoughApprovals { approved_stake: Balance, total_stake: Balance },
+    #[error("next block producers do not match `next_bp_hash` of the block")]
+    InvalidNextBlockProducers,
+    #[error("outcome root computed from the proof does not match the block")]
+    InvalidOutcomeRootProof,
+    #[error("block header does not match the block hash of the outcome")]
+    InvalidBlockHeader,
+    #[error("block is not included in the block merkle tree")]
+    InvalidBlockProof,
+}
+
+/// Computes the hash of the block described by the light client block view.
+pub fn light_client_block_hash(block_view: &LightClientBlockView) -> CryptoHash {
+    LightClientBlockLiteView {
+        prev_block_hash: block_view.prev_block_hash,
+        inner_rest_hash: block_view.inner_rest_hash,
+        inner_lite: block_view.inner_lite.clone(),
+    }
+    .hash()
+}
+
+/// Checks that `block_view` is a valid successor of `head`.
+///
+/// `epoch_block_producers` are the ordered block producers of the epoch of
+/// `block_view`, i.e. those the light client learned from `next_bps` of the
+/// last block of the epoch before.
+pub fn validate_light_client_block(
+    head: &LightClientBlockLiteView,
+    epoch_block_producers: &[ValidatorStakeView],
+    block_view: &LightClientBlockView,
+) -> Result<(), LightClientError> {
+    let current_block_hash = light_client_block_hash(block_view);
+    let next_block_hash = combine_hash(&block_view.next_block_inner_hash, &current_block_hash);
+    // The approvals are for the block after the next one, which endorses the next block.
+    let approval_message = Approval::get_data_for_sig(
+        &ApprovalInner::Endorsement(next_block_hash),
+        block_view.inner_lite.height + 2,
+    );
+
+    if block_view.inner_lite.height <= head.inner_lite.height {
+        return Err(LightClientError::HeightNotIncreasing {
+            height: block_view.inner_lite.height,
+            head_height: head.inner_lite.height,
+        });
+    }
+    let epoch_id = block_view.inner_lite.epoch_id;
+    if epoch_id != head.inner_lite.epoch_id && epoch_id != head.inner_lite.next_epoch_id {
+        return Err(LightClientError::UnknownEpoch);
+    }
+    if epoch_id == head.inner_lite.next_epoch_id && block_view.next_bps.is_none() {
+        return Err(LightClientError::MissingNextBlockProducers);
+    }
+
+    // The stake of all the block producers counts, so that leaving out the
+    // approvals at the end of the list doesn't lower the threshold.
+    let total_stake: Balance = epoch_block_producers
+        .iter()
+        .map(|block_producer| block_producer.clone().into_validator_stake().stake())
+        .sum();
+    let mut approved_stake: Balance = 0;
+    for (index, (approval, block_producer)) in
+        block_view.approvals_after_next.iter().zip(epoch_block_producers).enumerate()
+    {
+        let block_producer = block_producer.clone().into_validator_stake();
+        let Some(signature) = approval else {
+            continue;
+        };
+        approved_stake += block_producer.stake();
+        if !signature.verify(&approval_message, block_producer.public_key()) {
+            return Err(LightClientError::InvalidSignature { index });
+        }
+    }
+    if approved_stake * 3 <= total_stake * 2 {
+        return Err(LightClientError::NotEnoughApprovals { approved_stake, total_stake });
+    }
+
+    if let Some(next_bps) = &block_view.next_bps {
+        let next_bp_hash = CryptoHash::hash_borsh_iter(
+            next_bps.iter().map(|bp| bp.clone().into_validator_stake()),
+        );
+        if next_bp_hash != block_view.inner_lite.next_bp_hash {
+            return Err(LightClientError::InvalidNextBlockProducers);
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the execution outcome is included in the block described by
+/// `block_header_lite`, and that this block is included in the block merkle
+/// tree with the root `block_merkle_root`, which a light client takes from
+/// the `block_merkle_root` of its head.
+///
+/// Arguments correspond to the fields of the response of the
+/// `EXPERIMENTAL_light_client_proof` RPC method.
+pub fn verify_execution_outcome_proof(
+    outcome_proof: &ExecutionOutcomeWithIdView,
+    outcome_root_proof: &MerklePath,
+    block_header_lite: &LightClientBlockLiteView,
+    block_proof: &MerklePath,
+    block_merkle_root: &CryptoHash,
+) -> Result<(), LightClientError> {
+    let outcome_hash = CryptoHash::hash_borsh(outcome_proof.to_hashes());
+    let shard_outcome_root = compute_root_from_path(&outcome_proof.proof, outcome_hash);
+    let block_outcome_root =
+        compute_root_from_path(outcome_root_proof, CryptoHash::hash_borsh(shard_outcome_root));
+    if block_outcome_root != block_header_lite.inner_lite.outcome_root {
+        return Err(LightClientError::InvalidOutcomeRootProof);
+    }
+    let block_hash = block_header_lite.hash();
+    if block_hash != outcome_proof.block_hash {
+        return Err(LightClientError::InvalidBlockHeader);
+    }
+    if !verify_hash(*block_merkle_root, block_proof, block_hash) {
+        return Err(LightClientError::InvalidBlockProof);
+    }
+    Ok(())
+}
+
+/// The state of a light client: the last verified block, together with the
+/// block producers of its epoch and of the next epoch.
+#[derive(Debug, Clone)]
+pub struct LightClientState {
+    pub head: LightClientBlockLiteView,
+    pub current_block_producers: Vec<ValidatorStakeView>,
+    pub next_block_producers: Vec<ValidatorStakeView>,
+}
+
+impl LightClientState {
+    /// Validates `block_view` against the current head and makes it the new head.
+    pub fn update(&mut self, block_view: &LightClientBlockView) -> Result<(), LightClientError> {
+        let is_next_epoch = block_view.inner_lite.epoch_id == self.head.inner_lite.next_epoch_id
+            && block_view.inner_lite.epoch_id != self.head.inner_lite.epoch_id;
+        let epoch_block_producers =
+            if is_next_epoch { &self.next_block_producers } else { &self.current_block_producers };
+        validate_light_client_block(&self.head, epoch_block_producers, block_view)?;
+
+        if is_next_epoch {
+            self.current_block_producers = std::mem::take(&mut self.next_block_producers);
+        }
+        if let Some(next_bps) = &block_view.next_bps {
+            self.next_block_producers = next_bps.clone();
+        }
+        self.head = LightClientBlockLiteView {
+            prev_block_hash: block_view.prev_block_hash,
+            inner_rest_hash: block_view.inner_rest_hash,
+            inner_lite: block_view.inner_lite.clone(),
+        };
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::hash;
+    use crate::merkle::{merklize, Direction, MerklePathItem};
+    use crate::views::validator_stake_view::ValidatorStakeViewV1;
+    use crate::views::{
+        BlockHeaderInnerLiteView, ExecutionMetadataView, ExecutionOutcomeView, ExecutionStatusView,
+    };
+    use near_crypto::{InMemorySigner, KeyType, Signer};
+
+    fn signers() -> Vec<InMemorySigner> {
+        ["test0", "test1", "test2"]
+            .iter()
+            .map(|id| InMemorySigner::from_seed(id.parse().unwrap(), KeyType::ED25519, id))
+            .collect()
+    }
+
+    fn block_producers(signers: &[InMemorySigner]) -> Vec<ValidatorStakeView> {
+        signers
+            .iter()
+            .map(|signer| {
+                ValidatorStakeView::V1(ValidatorStakeViewV1 {
+                    account_id: signer.account_id.clone(),
+                    public_key: signer.public_key(),
+                    stake: 100,
+                })
+            })
+            .collect()
+    }
+
+    fn inner_lite(height: u64, epoch_id: CryptoHash) -> BlockHeaderInnerLiteView {
+        BlockHeaderInnerLiteView {
+            height,
+            epoch_id,
+            next_epoch_id: hash(epoch_id.as_bytes()),
+            prev_state_root: CryptoHash::default(),
+            outcome_root: CryptoHash::default(),
+            timestamp: 0,
+            timestamp_nanosec: 0,
+            next_bp_hash: CryptoHash::default(),
+            block_merkle_root: CryptoHash::default(),
+        }
+    }
+
+    fn head() -> LightClientBlockLiteView {
+        LightClientBlockLiteView {
+            prev_block_hash: CryptoHash::default(),
+            inner_rest_hash: CryptoHash::default(),
+            inner_lite: inner_lite(5, hash(b"epoch")),
+        }
+    }
+
+    /// Creates a block after `head` in the same epoch, approved by the given signers.
+    fn block(signers: &[InMemorySigner], approvers: &[bool]) -> LightClientBlockView {
+        let mut block_view = LightClientBlockView {
+            prev_block_hash: hash(b"prev"),
+            next_block_inner_hash: hash(b"next"),
+            inner_lite: inner_lite(10, head().inner_lite.epoch_id),
+            inner_rest_hash: hash(b"rest"),
+            next_bps: None,
+            approvals_after_next: vec![],
+        };
+        let next_block_hash =
+            combine_hash(&block_view.next_block_inner_hash, &light_client_block_hash(&block_view));
+        let message = Approval::get_data_for_sig(&ApprovalInner::Endorsement(next_block_hash), 12);
+        block_view.approvals_after_next = signers
+            .iter()
+            .zip(approvers)
+            .map(|(signer, approves)| approves.then(|| Box::new(signer.sign(&message))))
+            .collect();
+        block_view
+    }
+
+    #[test]
+    fn test_validate_light_client_block() {
+        let signers = signers();
+        let bps = block_producers(&signers);
+        let block_view = block(&signers, &[true, true, true]);
+        assert_eq!(validate_light_client_block(&head(), &bps, &block_view), Ok(()));
+
+        let mut state = LightClientState {
+            head: head(),
+            current_block_producers: bps.clone(),
+            next_block_producers: vec![],
+        };
+        state.update(&block_view).unwrap();
+        assert_eq!(state.head.hash(), light_client_block_hash(&block_view));
+        // The same block can't be applied twice.
+        assert_eq!(
+            state.update(&block_view),
+            Err(LightClientError::HeightNotIncreasing { height: 10, head_height: 10 })
+        );
+    }
+
+    #[test]
+    fn test_validate_light_client_block_not_enough_approvals() {
+        let signers = signers();
+        let bps = block_producers(&signers);
+        let block_view = block(&signers, &[true, false, true]);
+        assert_eq!(
+            validate_light_client_block(&head(), &bps, &block_view),
+            Err(LightClientError::NotEnoughApprovals { approved_stake: 200, total_stake: 300 })
+        );
+    }
+
+    #[test]
+    fn test_validate_light_client_block_truncated_approvals() {
+        let signers = signers();
+        let bps = block_producers(&signers);
+        // A single block producer can't approve the block alone by dropping
+        // the other approvals from the list.
+        let mut block_view = block(&signers, &[true, true, true]);
+        block_view.approvals_after_next.truncate(1);
+        assert_eq!(
+            validate_light_client_block(&head(), &bps, &block_view),
+            Err(LightClientError::NotEnoughApprovals { approved_stake: 100, total_stake: 300 })
+        );
+        block_view.approvals_after_next.clear();
+        assert_eq!(
+            validate_light_client_block(&head(), &bps, &block_view),
+            Err(LightClientError::NotEnoughApprovals { approved_stake: 0, total_stake: 300 })
+        );
+    }
+
+    #[test]
+    fn test_validate_light_client_block_invalid_signature() {
+        let signers = signers();
+        let bps = block_producers(&signers);
+        let mut block_view = block(&signers, &[true, true, true]);
+        block_view.approvals_after_next[1] = Some(Box::new(signers[1].sign(b"something else")));
+        assert_eq!(
+            validate_light_client_block(&head(), &bps, &block_view),
+            Err(LightClientError::InvalidSignature { index: 1 })
+        );
+    }
+
+    #[test]
+    fn test_validate_light_client_block_invalid_next_bps() {
+        let signers = signers();
+        let bps = block_producers(&signers);
+        let mut block_view = block(&signers, &[true, true, true]);
+        // `next_bps` is not part of the block hash, so the approvals remain valid.
+        block_view.next_bps = Some(bps.clone());
+        assert_eq!(
+            validate_light_client_block(&head(), &bps, &block_view),
+            Err(LightClientError::InvalidNextBlockProducers)
+        );
+    }
+
+    fn outcome(id: &[u8]) -> ExecutionOutcomeWithIdView {
+        ExecutionOutcomeWithIdView {
+            proof: vec![],
+            block_hash: CryptoHash::default(),
+            id: hash(id),
+            outcome: ExecutionOutcomeView {
+                logs: vec![],
+                receipt_ids: vec![],
+                gas_burnt: 100,
+                tokens_burnt: 0,
+                executor_id: "test0".parse().unwrap(),
+                status: ExecutionStatusView::SuccessValue(vec![]),
+                metadata: ExecutionMetadataView::default(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_verify_execution_outcome_proof() {
+        let mut outcome_proof = outcome(b"a");
+        let other_outcome = outcome(b"b");
+        let (shard_outcome_root, paths) =
+            merklize(&[outcome_proof.to_hashes(), other_outcome.to_hashes()]);
+        outcome_proof.proof = paths[0].clone();
+        let (outcome_root, outcome_root_paths) =
+            merklize(&[hash(b"other shard"), shard_outcome_root]);
+        let outcome_root_proof = outcome_root_paths[1].clone();
+
+        let mut block_header_lite = head();
+        block_header_lite.inner_lite.outcome_root = outcome_root;
+        outcome_proof.block_hash = block_header_lite.hash();
+        let block_proof =
+            vec![MerklePathItem { hash: hash(b"previous block"), direction: Direction::Left }];
+        let block_merkle_root = combine_hash(&hash(b"previous block"), &outcome_proof.block_hash);
+
+        assert_eq!(
+            verify_execution_outcome_proof(
+                &outcome_proof,
+                &outcome_root_proof,
+                &block_header_lite,
+                &block_proof,
+                &block_merkle_root,
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            verify_execution_outcome_proof(
+                &other_outcome,
+                &outcome_root_proof,
+                &block_header_lite,
+                &block_proof,
+                &block_merkle_root,
+            ),
+            Err(LightClientError::InvalidOutcomeRootProof)
+        );
+        assert_eq!(
+            verify_execution_outcome_proof(
+                &outcome_proof,
+                &outcome_root_proof,
+                &block_header_lite,
+                &block_proof,
+                &hash(b"wrong root"),
+            ),
+            Err(LightClientError::InvalidBlockProof)
+        );
+    }
+}
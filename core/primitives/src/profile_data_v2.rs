@@ -1,6 +1,7 @@
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use near_parameters::{ActionCosts, ExtCosts};
 use near_primitives_core::types::Gas;
+use std::collections::BTreeMap;
 use std::fmt;
 use std::ops::Index;
 use strum::IntoEnumIterator;
@@ -16,7 +17,7 @@ use strum::IntoEnumIterator;
 /// It used to store an array that manually mapped `enum Cost` to gas
 /// numbers. Now `ProfileDataV2` and `Cost` are deprecated. But to lookup
 /// old gas profiles from the DB, we need to keep the code around.
-#[derive(Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+#[derive(Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize, BorshSchema)]
 pub struct ProfileDataV2 {
     data: DataArray,
 }
@@ -234,6 +235,19 @@ impl BorshSerialize for DataArray {
     }
 }
 
+/// Serialized as a vector, see above.
+impl BorshSchema for DataArray {
+    fn add_definitions_recursively(
+        definitions: &mut BTreeMap<borsh::schema::Declaration, borsh::schema::Definition>,
+    ) {
+        <Vec<u64>>::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> borsh::schema::Declaration {
+        <Vec<u64>>::declaration()
+    }
+}
+
 impl Default for ProfileDataV2 {
     fn default() -> Self {
         let costs = DataArray(Box::new([0; DataArray::LEN]));
@@ -2,7 +2,8 @@ use crate::hash::CryptoHash;
 use crate::serialize::dec_format;
 use crate::transaction::{Action, TransferAction};
 use crate::types::{AccountId, Balance, BlockHeight, ShardId};
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::schema::{Declaration, Definition, Fields};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use near_crypto::{KeyType, PublicKey};
 use near_fmt::AbbrBytes;
 use serde_with::base64::Base64;
@@ -18,6 +19,7 @@ use std::io::{Error, ErrorKind};
 #[derive(
     BorshSerialize,
     BorshDeserialize,
+    BorshSchema,
     Hash,
     Clone,
     Debug,
@@ -36,6 +38,7 @@ pub struct DataReceiver {
 #[derive(
     BorshSerialize,
     BorshDeserialize,
+    BorshSchema,
     Debug,
     PartialEq,
     Eq,
@@ -58,6 +61,7 @@ pub struct ReceiptV0 {
 #[derive(
     BorshSerialize,
     BorshDeserialize,
+    BorshSchema,
     Debug,
     PartialEq,
     Eq,
@@ -154,6 +158,25 @@ impl BorshDeserialize for Receipt {
     }
 }
 
+/// The schema only covers the untagged `ReceiptV0` encoding, the only one the
+/// runtime creates. `ReceiptV1` is encoded as the 1u8 tag followed by the
+/// `ReceiptV1` struct.
+impl BorshSchema for Receipt {
+    fn add_definitions_recursively(definitions: &mut BTreeMap<Declaration, Definition>) {
+        let fields = Fields::UnnamedFields(vec![ReceiptV0::declaration()]);
+        borsh::schema::add_definition(
+            Self::declaration(),
+            Definition::Struct { fields },
+            definitions,
+        );
+        ReceiptV0::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        "Receipt".to_string()
+    }
+}
+
 pub enum ReceiptPriority {
     /// Used in ReceiptV1
     Priority(u64),
@@ -347,6 +370,7 @@ impl Receipt {
 #[derive(
     BorshSerialize,
     BorshDeserialize,
+    BorshSchema,
     Clone,
     Debug,
     PartialEq,
@@ -365,6 +389,7 @@ pub enum ReceiptEnum {
 #[derive(
     BorshSerialize,
     BorshDeserialize,
+    BorshSchema,
     Debug,
     PartialEq,
     Eq,
@@ -398,6 +423,7 @@ pub struct ActionReceipt {
 #[derive(
     BorshSerialize,
     BorshDeserialize,
+    BorshSchema,
     Hash,
     PartialEq,
     Eq,
@@ -1,5 +1,21 @@
+use crate::types::{AccountId, Nonce};
+use near_crypto::PublicKey;
+
+/// Sandbox-only change of an access key of an existing account, for the
+/// changes that can't be expressed as a `StateRecord`.  Access keys are added
+/// or replaced with `StateRecord::AccessKey`.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AccessKeyPatch {
+    /// Removes the access key from the account.
+    Delete { account_id: AccountId, public_key: PublicKey },
+    /// Overrides the nonce of an existing access key, keeping its permission.
+    SetNonce { account_id: AccountId, public_key: PublicKey, nonce: Nonce },
+}
+
 #[cfg(feature = "sandbox")]
 pub mod state_patch {
+    use super::AccessKeyPatch;
     use crate::state_record::StateRecord;
 
     /// Changes to the state to be applied via sandbox-only state patching
@@ -12,42 +28,53 @@ pub mod state_patch {
     #[derive(Default)]
     pub struct SandboxStatePatch {
         records: Vec<StateRecord>,
+        access_key_patches: Vec<AccessKeyPatch>,
     }
 
     impl SandboxStatePatch {
         pub fn new(records: Vec<StateRecord>) -> SandboxStatePatch {
-            SandboxStatePatch { records }
+            SandboxStatePatch { records, access_key_patches: vec![] }
+        }
+
+        /// Access key patches are applied after all the records of the patch.
+        pub fn with_access_key_patches(
+            mut self,
+            access_key_patches: Vec<AccessKeyPatch>,
+        ) -> SandboxStatePatch {
+            self.access_key_patches = access_key_patches;
+            self
         }
 
         pub fn is_empty(&self) -> bool {
-            self.records.is_empty()
+            self.records.is_empty() && self.access_key_patches.is_empty()
         }
 
         pub fn clear(&mut self) {
             self.records.clear();
+            self.access_key_patches.clear();
         }
 
         pub fn take(&mut self) -> SandboxStatePatch {
-            Self { records: core::mem::take(&mut self.records) }
+            Self {
+                records: core::mem::take(&mut self.records),
+                access_key_patches: core::mem::take(&mut self.access_key_patches),
+            }
         }
 
         pub fn merge(&mut self, other: SandboxStatePatch) {
             self.records.extend(other.records);
+            self.access_key_patches.extend(other.access_key_patches);
         }
-    }
-
-    impl IntoIterator for SandboxStatePatch {
-        type Item = StateRecord;
-        type IntoIter = std::vec::IntoIter<StateRecord>;
 
-        fn into_iter(self) -> Self::IntoIter {
-            self.records.into_iter()
+        pub fn into_parts(self) -> (Vec<StateRecord>, Vec<AccessKeyPatch>) {
+            (self.records, self.access_key_patches)
         }
     }
 }
 
 #[cfg(not(feature = "sandbox"))]
 pub mod state_patch {
+    use super::AccessKeyPatch;
     use crate::state_record::StateRecord;
 
     #[derive(Default)]
@@ -66,15 +93,9 @@ pub mod state_patch {
         }
         #[inline(always)]
         pub fn merge(&self, _other: SandboxStatePatch) {}
-    }
-
-    impl IntoIterator for SandboxStatePatch {
-        type Item = StateRecord;
-        type IntoIter = std::iter::Empty<StateRecord>;
-
         #[inline(always)]
-        fn into_iter(self) -> Self::IntoIter {
-            std::iter::empty()
+        pub fn into_parts(self) -> (Vec<StateRecord>, Vec<AccessKeyPatch>) {
+            (Vec::new(), Vec::new())
         }
     }
 }
@@ -0,0 +1,101 @@
+//! Borsh schemas of the chain data structures, printed by `neard dump-schemas`
+//! for the decoders outside of nearcore, so that they don't have to follow the
+//! changes of the Rust structs.
+use crate::action::Action;
+use crate::block_header::BlockHeader;
+use crate::receipt::Receipt;
+use crate::sharding::{ShardChunk, ShardChunkHeader};
+use crate::transaction::{ExecutionOutcomeWithId, SignedTransaction, TransactionV1};
+use borsh::schema::{BorshSchemaContainer, Definition, Fields};
+use serde_json::{json, Value};
+
+/// Returns the schemas of the top level data structures, by the name of the
+/// structure.
+pub fn schemas() -> Vec<(&'static str, BorshSchemaContainer)> {
+    vec![
+        ("BlockHeader", borsh::schema_container_of::<BlockHeader>()),
+        ("ShardChunkHeader", borsh::schema_container_of::<ShardChunkHeader>()),
+        ("ShardChunk", borsh::schema_container_of::<ShardChunk>()),
+        ("SignedTransaction", borsh::schema_container_of::<SignedTransaction>()),
+        // See the schema of `Transaction` for how it is encoded.
+        ("TransactionV1", borsh::schema_container_of::<TransactionV1>()),
+        ("Receipt", borsh::schema_container_of::<Receipt>()),
+        ("Action", borsh::schema_container_of::<Action>()),
+        ("ExecutionOutcomeWithId", borsh::schema_container_of::<ExecutionOutcomeWithId>()),
+    ]
+}
+
+fn definition_to_json(definition: &Definition) -> Value {
+    match definition {
+        Definition::Primitive(size) => json!({ "primitive": size }),
+        Definition::Sequence { length_width, length_range, elements } => json!({
+            "sequence": {
+                "length_width": length_width,
+                "length_range": [length_range.start(), length_range.end()],
+                "elements": elements,
+            }
+        }),
+        Definition::Tuple { elements } => json!({ "tuple": elements }),
+        Definition::Enum { tag_width, variants } => {
+            let variants: Vec<Value> = variants
+                .iter()
+                .map(|(discriminant, name, declaration)| {
+                    json!({ "discriminant": discriminant, "name": name, "declaration": declaration })
+                })
+                .collect();
+            json!({ "enum": { "tag_width": tag_width, "variants": variants } })
+        }
+        Definition::Struct { fields } => match fields {
+            Fields::NamedFields(fields) => {
+                let fields: Vec<Value> = fields
+                    .iter()
+                    .map(|(name, declaration)| json!({ "name": name, "declaration": declaration }))
+                    .collect();
+                json!({ "struct": { "named_fields": fields } })
+            }
+            Fields::UnnamedFields(fields) => json!({ "struct": { "unnamed_fields": fields } }),
+            Fields::Empty => json!({ "struct": {} }),
+        },
+    }
+}
+
+/// Converts the schema to JSON: the declaration of the structure and the
+/// definitions of all the types it is made of, by their declarations.
+pub fn schema_to_json(container: &BorshSchemaContainer) -> Value {
+    let definitions: serde_json::Map<String, Value> = container
+        .definitions()
+        .map(|(declaration, definition)| (declaration.clone(), definition_to_json(definition)))
+        .collect();
+    json!({ "declaration": container.declaration(), "definitions": definitions })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schemas_are_complete() {
+        for (name, container) in schemas() {
+            container.validate().unwrap_or_else(|err| panic!("schema of {name}: {err:?}"));
+        }
+    }
+
+    #[test]
+    fn test_schema_to_json() {
+        let json = schema_to_json(&borsh::schema_container_of::<Action>());
+        assert_eq!(json["declaration"], "Action");
+        let transfer = &json["definitions"]["TransferAction"]["struct"]["named_fields"];
+        assert_eq!(transfer[0], json!({ "name": "deposit", "declaration": "u128" }));
+        let public_key = &json["definitions"]["PublicKey"]["enum"];
+        assert_eq!(public_key["tag_width"], 1);
+        assert_eq!(public_key["variants"][1]["declaration"], "[u8; 64]");
+
+        let json = schema_to_json(&borsh::schema_container_of::<TransactionV1>());
+        let fields =
+            json["definitions"]["TransactionV1"]["struct"]["named_fields"].as_array().unwrap();
+        assert_eq!(
+            fields.last().unwrap(),
+            &json!({ "name": "priority_fee", "declaration": "u64" })
+        );
+    }
+}
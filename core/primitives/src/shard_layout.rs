@@ -254,6 +254,15 @@ impl ShardLayout {
         }
     }
 
+    /// Returns the accounts on the boundaries between the shards, or None for
+    /// the layouts assigning the accounts to the shards by their hashes.
+    pub fn boundary_accounts(&self) -> Option<&[AccountId]> {
+        match self {
+            Self::V0(_) => None,
+            Self::V1(v1) => Some(&v1.boundary_accounts),
+        }
+    }
+
     fn num_shards(&self) -> NumShards {
         match self {
             Self::V0(v0) => v0.num_shards,
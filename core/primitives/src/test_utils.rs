@@ -13,7 +13,7 @@ use crate::transaction::{
     DeployContractAction, FunctionCallAction, SignedTransaction, StakeAction, Transaction,
     TransactionV0, TransactionV1, TransferAction,
 };
-use crate::types::{AccountId, Balance, EpochId, EpochInfoProvider, Gas, Nonce};
+use crate::types::{AccountId, Balance, BlockHeight, EpochId, EpochInfoProvider, Gas, Nonce};
 use crate::validator_signer::{InMemoryValidatorSigner, ValidatorSigner};
 use crate::version::PROTOCOL_VERSION;
 use crate::views::{ExecutionStatusView, FinalExecutionOutcomeView, FinalExecutionStatus};
@@ -706,6 +706,21 @@ impl EpochInfoProvider for MockEpochInfoProvider {
     ) -> Result<ShardId, EpochError> {
         Ok(0)
     }
+
+    /// The mock has no chunk producer assignment, so the validator with the
+    /// most stake produces all the chunks.
+    fn chunk_producer(
+        &self,
+        _epoch_id: &EpochId,
+        _height: BlockHeight,
+        _shard_id: ShardId,
+    ) -> Result<AccountId, EpochError> {
+        self.validators
+            .iter()
+            .max_by_key(|(account_id, stake)| (**stake, *account_id))
+            .map(|(account_id, _)| account_id.clone())
+            .ok_or_else(|| EpochError::ChunkProducerSelectionError("no validators".to_string()))
+    }
 }
 
 /// Encode array of `u64` to be passed as a smart contract argument.
@@ -2,7 +2,8 @@ use crate::errors::TxExecutionError;
 use crate::hash::{hash, CryptoHash};
 use crate::merkle::MerklePath;
 use crate::types::{AccountId, Balance, Gas, Nonce};
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::schema::{Declaration, Definition, Fields};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use near_crypto::{PublicKey, Signature};
 use near_fmt::{AbbrBytes, Slice};
 use near_primitives_core::serialize::{from_base64, to_base64};
@@ -11,6 +12,7 @@ use near_vm_runner::ProfileDataV3;
 use serde::de::Error as DecodeError;
 use serde::ser::Error as EncodeError;
 use std::borrow::Borrow;
+use std::collections::BTreeMap;
 use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::io::{Error, ErrorKind, Read, Write};
@@ -24,7 +26,12 @@ pub use crate::action::{
 
 pub type LogEntry = String;
 
-#[derive(BorshSerialize, BorshDeserialize, serde::Serialize, PartialEq, Eq, Debug, Clone)]
+/// Amount of yoctoNEAR per unit of the priority fee of `TransactionV1`.
+pub const PRIORITY_FEE_UNIT: Balance = 1_000_000_000_000;
+
+#[derive(
+    BorshSerialize, BorshDeserialize, BorshSchema, serde::Serialize, PartialEq, Eq, Debug, Clone,
+)]
 pub struct TransactionV0 {
     /// An account on which behalf transaction is signed
     pub signer_id: AccountId,
@@ -42,7 +49,7 @@ pub struct TransactionV0 {
     pub actions: Vec<Action>,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, PartialEq, Eq, Debug, Clone)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Eq, Debug, Clone)]
 pub struct TransactionV1 {
     /// An account on which behalf transaction is signed
     pub signer_id: AccountId,
@@ -132,6 +139,11 @@ impl Transaction {
             Transaction::V1(tx) => Some(tx.priority_fee),
         }
     }
+
+    /// The priority fee in yoctoNEAR, zero for transactions without one.
+    pub fn priority_fee_amount(&self) -> Balance {
+        Balance::from(self.priority_fee().unwrap_or_default()) * PRIORITY_FEE_UNIT
+    }
 }
 
 impl BorshSerialize for Transaction {
@@ -211,7 +223,28 @@ impl BorshDeserialize for Transaction {
     }
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Eq, Debug, Clone)]
+/// The schema only covers the untagged `TransactionV0` encoding, as borsh
+/// schemas can't describe its mix with the tagged one. `TransactionV1`,
+/// accepted on benchmarknet, is encoded as the 1u8 tag followed by the
+/// `TransactionV1` struct, whose schema is exported on its own. The two are
+/// told apart by the second byte, which is 0 only for `TransactionV0`.
+impl BorshSchema for Transaction {
+    fn add_definitions_recursively(definitions: &mut BTreeMap<Declaration, Definition>) {
+        let fields = Fields::UnnamedFields(vec![TransactionV0::declaration()]);
+        borsh::schema::add_definition(
+            Self::declaration(),
+            Definition::Struct { fields },
+            definitions,
+        );
+        TransactionV0::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        "Transaction".to_string()
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, Eq, Debug, Clone)]
 #[borsh(init=init)]
 pub struct SignedTransaction {
     pub transaction: Transaction,
@@ -292,7 +325,7 @@ impl<'de> serde::Deserialize<'de> for SignedTransaction {
 }
 
 /// The status of execution for a transaction or a receipt.
-#[derive(BorshSerialize, BorshDeserialize, PartialEq, Eq, Clone, Default)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Eq, Clone, Default)]
 pub enum ExecutionStatus {
     /// The execution is pending or unknown.
     #[default]
@@ -364,7 +397,9 @@ impl From<ExecutionStatus> for PartialExecutionStatus {
 }
 
 /// Execution outcome for one signed transaction or one receipt.
-#[derive(BorshSerialize, BorshDeserialize, PartialEq, Clone, smart_default::SmartDefault, Eq)]
+#[derive(
+    BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Clone, smart_default::SmartDefault, Eq,
+)]
 pub struct ExecutionOutcome {
     /// Logs from this transaction or receipt.
     pub logs: Vec<LogEntry>,
@@ -395,7 +430,7 @@ pub struct ExecutionOutcome {
     pub metadata: ExecutionMetadata,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, PartialEq, Clone, Eq, Debug, Default)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema, PartialEq, Clone, Eq, Debug, Default)]
 pub enum ExecutionMetadata {
     /// V1: Empty Metadata
     #[default]
@@ -423,7 +458,7 @@ impl fmt::Debug for ExecutionOutcome {
 /// Execution outcome with the identifier.
 /// For a signed transaction, the ID is the hash of the transaction.
 /// For a receipt, the ID is the receipt ID.
-#[derive(PartialEq, Clone, Default, Debug, BorshSerialize, BorshDeserialize, Eq)]
+#[derive(PartialEq, Clone, Default, Debug, BorshSerialize, BorshDeserialize, BorshSchema, Eq)]
 pub struct ExecutionOutcomeWithId {
     /// The transaction hash or the receipt ID.
     pub id: CryptoHash,
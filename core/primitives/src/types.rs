@@ -5,7 +5,7 @@ use crate::hash::CryptoHash;
 use crate::receipt::{PromiseYieldTimeout, Receipt};
 use crate::serialize::dec_format;
 use crate::trie_key::TrieKey;
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use near_crypto::PublicKey;
 /// Reexport primitive types
 pub use near_primitives_core::types::*;
@@ -489,6 +489,7 @@ impl StateRootNode {
     derive_more::AsRef,
     BorshSerialize,
     BorshDeserialize,
+    BorshSchema,
     serde::Serialize,
     serde::Deserialize,
     arbitrary::Arbitrary,
@@ -521,7 +522,7 @@ pub struct ApprovalStake {
 
 pub mod validator_stake {
     use crate::types::ApprovalStake;
-    use borsh::{BorshDeserialize, BorshSerialize};
+    use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
     use near_crypto::{KeyType, PublicKey};
     use near_primitives_core::types::{AccountId, Balance};
     use serde::Serialize;
@@ -529,7 +530,9 @@ pub mod validator_stake {
     pub use super::ValidatorStakeV1;
 
     /// Stores validator and its stake.
-    #[derive(BorshSerialize, BorshDeserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+    #[derive(
+        BorshSerialize, BorshDeserialize, BorshSchema, Serialize, Debug, Clone, PartialEq, Eq,
+    )]
     #[serde(tag = "validator_stake_struct_version")]
     pub enum ValidatorStake {
         V1(ValidatorStakeV1),
@@ -722,7 +725,9 @@ pub mod validator_stake {
 }
 
 /// Stores validator and its stake.
-#[derive(BorshSerialize, BorshDeserialize, serde::Serialize, Debug, Clone, PartialEq, Eq)]
+#[derive(
+    BorshSerialize, BorshDeserialize, BorshSchema, serde::Serialize, Debug, Clone, PartialEq, Eq,
+)]
 pub struct ValidatorStakeV1 {
     /// Account that stakes money.
     pub account_id: AccountId,
@@ -742,7 +747,7 @@ pub mod chunk_extra {
     use crate::congestion_info::CongestionInfo;
     use crate::types::validator_stake::{ValidatorStake, ValidatorStakeIter};
     use crate::types::StateRoot;
-    use borsh::{BorshDeserialize, BorshSerialize};
+    use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
     use near_primitives_core::hash::CryptoHash;
     use near_primitives_core::types::{Balance, Gas};
     use near_primitives_core::version::{ProtocolFeature, PROTOCOL_VERSION};
@@ -1108,6 +1113,14 @@ pub trait EpochInfoProvider {
         account_id: &AccountId,
         epoch_id: &EpochId,
     ) -> Result<ShardId, EpochError>;
+
+    /// The chunk producer of the shard at the given height.
+    fn chunk_producer(
+        &self,
+        epoch_id: &EpochId,
+        height: BlockHeight,
+        shard_id: ShardId,
+    ) -> Result<AccountId, EpochError>;
 }
 
 /// Mode of the trie cache.
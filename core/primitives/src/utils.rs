@@ -238,6 +238,23 @@ pub fn create_receipt_id_from_transaction(
     )
 }
 
+/// Creates the Receipt ID of the receipt paying the priority fee of the given signed transaction
+/// to the chunk producer. Uses the next salt after the one of the transaction receipt.
+pub fn create_priority_fee_receipt_id_from_transaction(
+    protocol_version: ProtocolVersion,
+    signed_transaction: &SignedTransaction,
+    prev_block_hash: &CryptoHash,
+    block_hash: &CryptoHash,
+) -> CryptoHash {
+    create_hash_upgradable(
+        protocol_version,
+        &signed_transaction.get_hash(),
+        prev_block_hash,
+        block_hash,
+        1,
+    )
+}
+
 /// Creates a new Receipt ID from a given receipt id, a block hash and a new receipt index.
 /// This method is backward compatible, so it takes the current protocol version.
 pub fn create_receipt_id_from_receipt_id(
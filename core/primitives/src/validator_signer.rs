@@ -1,5 +1,5 @@
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 use near_crypto::{InMemorySigner, KeyType, PublicKey, Signature, Signer};
 
@@ -167,7 +167,8 @@ impl ValidatorSigner for EmptyValidatorSigner {
     }
 }
 
-/// Signer that keeps secret key in memory and signs locally.
+/// Signer that keeps secret key in memory and signs locally, unless it is
+/// created with [`InMemoryValidatorSigner::from_signer`].
 #[derive(Clone)]
 pub struct InMemoryValidatorSigner {
     account_id: AccountId,
@@ -185,6 +186,12 @@ impl InMemoryValidatorSigner {
         Self { account_id, signer }
     }
 
+    /// Signs with the given signer, which doesn't have to keep the key in
+    /// memory, e.g. it can forward the signing to a remote signing service.
+    pub fn from_signer(account_id: AccountId, signer: Arc<dyn Signer>) -> Self {
+        Self { account_id, signer }
+    }
+
     pub fn public_key(&self) -> PublicKey {
         self.signer.public_key()
     }
@@ -275,3 +282,109 @@ impl ValidatorSigner for InMemoryValidatorSigner {
         self.signer.write_to_file(path)
     }
 }
+
+/// Validator signer whose key can be replaced while the node is running,
+/// e.g. to rotate the validator key without a restart.
+///
+/// All the components of the node share the same instance, so they all switch
+/// to the new key at once. The validator account can't change.
+pub struct RotatableValidatorSigner {
+    account_id: AccountId,
+    signer: RwLock<Arc<dyn ValidatorSigner>>,
+}
+
+impl RotatableValidatorSigner {
+    pub fn new(signer: Arc<dyn ValidatorSigner>) -> Self {
+        Self { account_id: signer.validator_id().clone(), signer: RwLock::new(signer) }
+    }
+
+    /// The signer currently used for signing.
+    pub fn current(&self) -> Arc<dyn ValidatorSigner> {
+        self.signer.read().unwrap().clone()
+    }
+
+    /// Starts signing with the given signer, which has to be of the same
+    /// validator account. Returns the previous signer.
+    pub fn rotate(&self, signer: Arc<dyn ValidatorSigner>) -> Arc<dyn ValidatorSigner> {
+        assert_eq!(
+            signer.validator_id(),
+            &self.account_id,
+            "The validator account can't change on key rotation"
+        );
+        std::mem::replace(&mut *self.signer.write().unwrap(), signer)
+    }
+}
+
+impl ValidatorSigner for RotatableValidatorSigner {
+    fn validator_id(&self) -> &AccountId {
+        &self.account_id
+    }
+
+    fn public_key(&self) -> PublicKey {
+        self.current().public_key()
+    }
+
+    fn sign_telemetry(&self, info: &TelemetryInfo) -> serde_json::Value {
+        self.current().sign_telemetry(info)
+    }
+
+    fn sign_block_header_parts(
+        &self,
+        prev_hash: CryptoHash,
+        inner_lite: &[u8],
+        inner_rest: &[u8],
+    ) -> (CryptoHash, Signature) {
+        self.current().sign_block_header_parts(prev_hash, inner_lite, inner_rest)
+    }
+
+    fn sign_chunk_hash(&self, chunk_hash: &ChunkHash) -> Signature {
+        self.current().sign_chunk_hash(chunk_hash)
+    }
+
+    fn sign_approval(&self, inner: &ApprovalInner, target_height: BlockHeight) -> Signature {
+        self.current().sign_approval(inner, target_height)
+    }
+
+    fn sign_chunk_endorsement(&self, inner: &ChunkEndorsementInner) -> Signature {
+        self.current().sign_chunk_endorsement(inner)
+    }
+
+    fn sign_chunk_state_witness(&self, witness_bytes: &EncodedChunkStateWitness) -> Signature {
+        self.current().sign_chunk_state_witness(witness_bytes)
+    }
+
+    fn sign_partial_encoded_state_witness(
+        &self,
+        part: &PartialEncodedStateWitnessInner,
+    ) -> Signature {
+        self.current().sign_partial_encoded_state_witness(part)
+    }
+
+    fn sign_challenge(&self, challenge_body: &ChallengeBody) -> (CryptoHash, Signature) {
+        self.current().sign_challenge(challenge_body)
+    }
+
+    fn sign_account_announce(
+        &self,
+        account_id: &AccountId,
+        peer_id: &PeerId,
+        epoch_id: &EpochId,
+    ) -> Signature {
+        self.current().sign_account_announce(account_id, peer_id, epoch_id)
+    }
+
+    fn sign_account_key_payload(&self, proto_bytes: &[u8]) -> Signature {
+        self.current().sign_account_key_payload(proto_bytes)
+    }
+
+    fn compute_vrf_with_proof(
+        &self,
+        data: &[u8],
+    ) -> (near_crypto::vrf::Value, near_crypto::vrf::Proof) {
+        self.current().compute_vrf_with_proof(data)
+    }
+
+    fn write_to_file(&self, path: &Path) -> std::io::Result<()> {
+        self.current().write_to_file(path)
+    }
+}
@@ -7,13 +7,14 @@ use crate::account::{AccessKey, AccessKeyPermission, Account, FunctionCallPermis
 use crate::action::delegate::{DelegateAction, SignedDelegateAction};
 use crate::block::{Block, BlockHeader, Tip};
 use crate::block_header::{
-    BlockHeaderInnerLite, BlockHeaderInnerRest, BlockHeaderInnerRestV2, BlockHeaderInnerRestV3,
-    BlockHeaderV1, BlockHeaderV2, BlockHeaderV3,
+    Approval, ApprovalInner, BlockHeaderInnerLite, BlockHeaderInnerRest, BlockHeaderInnerRestV2,
+    BlockHeaderInnerRestV3, BlockHeaderV1, BlockHeaderV2, BlockHeaderV3,
 };
 use crate::block_header::{BlockHeaderInnerRestV4, BlockHeaderV4};
 use crate::challenge::{Challenge, ChallengesResult};
 use crate::checked_feature;
 use crate::congestion_info::{CongestionInfo, CongestionInfoV1};
+use crate::equivocation::EquivocationEvidence;
 use crate::errors::TxExecutionError;
 use crate::hash::{hash, CryptoHash};
 use crate::merkle::{combine_hash, MerklePath};
@@ -238,6 +239,46 @@ pub struct ViewStateResult {
     pub proof: Vec<Arc<[u8]>>,
 }
 
+/// Changes of the state of an account, applied before simulating a transaction.
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct AccountStateOverride {
+    pub account_id: AccountId,
+    /// Balance the account has instead, the account is created if it doesn't
+    /// exist.
+    #[serde(default, with = "dec_format", skip_serializing_if = "Option::is_none")]
+    pub balance: Option<Balance>,
+    /// Contract data set in the state of the account.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub state: Vec<StateItem>,
+}
+
+/// Breakdown of the storage usage of an account into what it's charged for,
+/// computed from the state.
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct StorageUsageView {
+    /// Storage usage recorded in the account, which the breakdown should add
+    /// up to.
+    pub storage_usage: StorageUsage,
+    /// Fixed storage usage of the account record itself.
+    pub account: StorageUsage,
+    /// Size of the contract code.
+    pub code: StorageUsage,
+    /// Storage usage of the access keys, including the per record overhead.
+    pub access_keys: StorageUsage,
+    pub num_access_keys: u64,
+    /// Storage usage of the contract data, including the per record overhead.
+    pub data: StorageUsage,
+    pub num_data_keys: u64,
+    /// Contract data keys with the largest storage usage, largest first.
+    pub largest_data_keys: Vec<DataKeyStorageUsageView>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct DataKeyStorageUsageView {
+    pub key: StoreKey,
+    pub storage_usage: StorageUsage,
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone, Default)]
 pub struct CallResult {
     pub result: Vec<u8>,
@@ -254,16 +295,55 @@ pub struct QueryError {
 pub struct AccessKeyInfoView {
     pub public_key: PublicKey,
     pub access_key: AccessKeyView,
+    /// Only returned when listing the access keys of an account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<AccessKeyMetadataView>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct AccessKeyMetadataView {
+    /// `full_access`, or the receiver and the number of allowed methods of a
+    /// function call access key, e.g. `function_call to app.near, 2 methods`.
+    pub permission_summary: String,
+    /// Approximate height the key was added at. Keys added by transactions
+    /// start with a nonce derived from the height, so it's only unknown for
+    /// the keys of the genesis.
+    pub created_at_height: Option<BlockHeight>,
+}
+
+impl AccessKeyMetadataView {
+    pub fn new(access_key: &AccessKey, block_height: BlockHeight) -> Self {
+        let permission_summary = match &access_key.permission {
+            AccessKeyPermission::FullAccess => "full_access".to_string(),
+            AccessKeyPermission::FunctionCall(permission) if permission.method_names.is_empty() => {
+                format!("function_call to {}, any method", permission.receiver_id)
+            }
+            AccessKeyPermission::FunctionCall(permission) => format!(
+                "function_call to {}, {} methods",
+                permission.receiver_id,
+                permission.method_names.len()
+            ),
+        };
+        // The nonce only grows by one per transaction, far slower than by the
+        // multiplier per block.
+        let height = access_key.nonce / AccessKey::ACCESS_KEY_NONCE_RANGE_MULTIPLIER;
+        let created_at_height = (height > 0).then(|| height.min(block_height));
+        Self { permission_summary, created_at_height }
+    }
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct AccessKeyList {
     pub keys: Vec<AccessKeyInfoView>,
+    /// Whether the account has more keys after the listed ones, which can be
+    /// listed with `after_public_key` set to the last listed key.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub has_more: bool,
 }
 
 impl FromIterator<AccessKeyInfoView> for AccessKeyList {
     fn from_iter<I: IntoIterator<Item = AccessKeyInfoView>>(iter: I) -> Self {
-        Self { keys: iter.into_iter().collect() }
+        Self { keys: iter.into_iter().collect(), has_more: false }
     }
 }
 
@@ -305,6 +385,7 @@ pub enum QueryResponseKind {
     CallResult(CallResult),
     AccessKey(AccessKeyView),
     AccessKeyList(AccessKeyList),
+    StorageUsage(StorageUsageView),
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
@@ -329,6 +410,13 @@ pub enum QueryRequest {
     },
     ViewAccessKeyList {
         account_id: AccountId,
+        /// Lists only the keys after the given one, in the order of the
+        /// listing, to continue a listing with more keys than the `limit`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        after_public_key: Option<PublicKey>,
+        /// Maximum number of keys to list, all of them if not set.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        limit: Option<u64>,
     },
     CallFunction {
         account_id: AccountId,
@@ -336,6 +424,19 @@ pub enum QueryRequest {
         #[serde(rename = "args_base64")]
         args: FunctionArgs,
     },
+    /// Views the contract code with the given hash, if the node stores it in
+    /// the state of a tracked shard. The block only selects the shards to look
+    /// in: the code is looked up by its hash among the stored state values, so
+    /// it isn't necessarily deployed by any account at the block.
+    ViewCodeByHash {
+        code_hash: CryptoHash,
+    },
+    ViewStorageUsage {
+        account_id: AccountId,
+        /// Number of the largest contract data keys to return.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        num_largest_data_keys: Option<u64>,
+    },
 }
 
 fn is_false(v: &bool) -> bool {
@@ -363,6 +464,35 @@ pub struct StatusSyncInfo {
     pub earliest_block_time: Option<Utc>,
     pub epoch_id: Option<EpochId>,
     pub epoch_start_height: Option<BlockHeight>,
+    /// Progress of the sync, only set while the node is syncing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_progress: Option<SyncProgressView>,
+}
+
+/// Detailed progress of a syncing node.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct SyncProgressView {
+    /// Current phase of the sync, e.g. `HeaderSync`, `StateSync` or `BlockSync`.
+    pub phase: String,
+    /// Progress of the current phase in percent. Only known for header sync,
+    /// block sync and state sync.
+    pub progress_percent: Option<f64>,
+    /// Number of headers, blocks or state parts per second processed since the
+    /// node entered the current phase.
+    pub rate_per_second: Option<f64>,
+    /// Estimated time until the current phase completes, based on `rate_per_second`.
+    pub estimated_seconds_remaining: Option<u64>,
+    /// Progress of each shard during state sync.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub shards: Vec<ShardSyncProgressView>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ShardSyncProgressView {
+    pub shard_id: ShardId,
+    pub status: String,
+    pub parts_downloaded: u64,
+    pub num_parts: u64,
 }
 
 // TODO: add more information to ValidatorInfo
@@ -2400,6 +2530,21 @@ pub enum StateChangeValueView {
     },
 }
 
+impl StateChangeValueView {
+    pub fn affected_account_id(&self) -> &AccountId {
+        match self {
+            Self::AccountUpdate { account_id, .. }
+            | Self::AccountDeletion { account_id }
+            | Self::AccessKeyUpdate { account_id, .. }
+            | Self::AccessKeyDeletion { account_id, .. }
+            | Self::DataUpdate { account_id, .. }
+            | Self::DataDeletion { account_id, .. }
+            | Self::ContractCodeUpdate { account_id, .. }
+            | Self::ContractCodeDeletion { account_id } => account_id,
+        }
+    }
+}
+
 impl From<StateChangeValue> for StateChangeValueView {
     fn from(state_change: StateChangeValue) -> Self {
         match state_change {
@@ -2450,6 +2595,119 @@ pub type StateChangesView = Vec<StateChangeWithCauseView>;
 /// Maintenance windows view are a vector of maintenance window.
 pub type MaintenanceWindowsView = Vec<Range<BlockHeight>>;
 
+/// Producers of the block and the chunks at a height.
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct HeightProducersView {
+    pub height: BlockHeight,
+    pub block_producer: AccountId,
+    /// Chunk producers by shard id.
+    pub chunk_producers: Vec<AccountId>,
+}
+
+/// Producers of the heights of the next epoch, sampled from its already
+/// computed epoch info.
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct NextEpochValidatorScheduleView {
+    pub epoch_id: EpochId,
+    pub epoch_height: EpochHeight,
+    /// Estimated start height of the epoch. The current epoch can end a few
+    /// heights apart from its estimated end, so the first and the last heights
+    /// of the schedule can belong to the neighbouring epochs.
+    pub start_height: BlockHeight,
+    pub schedule: Vec<HeightProducersView>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalInnerView {
+    Endorsement(CryptoHash),
+    Skip(BlockHeight),
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct ApprovalView {
+    pub inner: ApprovalInnerView,
+    pub signature: Signature,
+}
+
+impl From<Approval> for ApprovalView {
+    fn from(approval: Approval) -> Self {
+        let inner = match approval.inner {
+            ApprovalInner::Endorsement(parent_hash) => ApprovalInnerView::Endorsement(parent_hash),
+            ApprovalInner::Skip(parent_height) => ApprovalInnerView::Skip(parent_height),
+        };
+        Self { inner, signature: approval.signature }
+    }
+}
+
+/// Two conflicting messages signed by the same validator.
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EquivocationEvidenceView {
+    Block {
+        account_id: AccountId,
+        height: BlockHeight,
+        left: Box<BlockHeaderView>,
+        right: Box<BlockHeaderView>,
+    },
+    Approval {
+        account_id: AccountId,
+        target_height: BlockHeight,
+        epoch_id: EpochId,
+        left: ApprovalView,
+        right: ApprovalView,
+    },
+}
+
+impl From<EquivocationEvidence> for EquivocationEvidenceView {
+    fn from(evidence: EquivocationEvidence) -> Self {
+        match evidence {
+            EquivocationEvidence::Block { account_id, left, right } => Self::Block {
+                account_id,
+                height: left.height(),
+                left: Box::new((*left).into()),
+                right: Box::new((*right).into()),
+            },
+            EquivocationEvidence::Approval { epoch_id, left, right } => Self::Approval {
+                account_id: left.account_id.clone(),
+                target_height: left.target_height,
+                epoch_id,
+                left: left.into(),
+                right: right.into(),
+            },
+        }
+    }
+}
+
+/// Whether an entry of the account index is a transaction or a receipt.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    serde::Serialize,
+    serde::Deserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountIndexEntryKind {
+    Transaction,
+    Receipt,
+}
+
+/// Transaction or receipt involving an account, from the account index kept
+/// by the node.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AccountIndexEntryView {
+    /// Hash of the transaction, or id of the receipt.
+    pub id: CryptoHash,
+    pub kind: AccountIndexEntryKind,
+    pub block_height: BlockHeight,
+    pub block_hash: CryptoHash,
+}
+
 /// Contains the split storage information.
 #[derive(serde::Serialize, serde::Deserialize, Debug)]
 pub struct SplitStorageInfoView {
@@ -2458,6 +2716,23 @@ pub struct SplitStorageInfoView {
     pub cold_head_height: Option<BlockHeight>,
 
     pub hot_db_kind: Option<String>,
+
+    /// Progress of copying blocks to the cold store, None if the cold store
+    /// loop is not running.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cold_store_copy: Option<ColdStoreCopyStatusView>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct ColdStoreCopyStatusView {
+    pub paused: bool,
+    /// Limit of the number of blocks copied per second, None if unlimited.
+    pub max_blocks_per_second: Option<u64>,
+    /// Number of blocks copied per second over the last minute.
+    pub blocks_per_second: Option<f64>,
+    /// Number of final blocks which are not copied to the cold store yet.
+    pub remaining_blocks: Option<u64>,
+    pub estimated_seconds_remaining: Option<u64>,
 }
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
@@ -2498,6 +2773,59 @@ impl From<CongestionInfoView> for CongestionInfo {
     }
 }
 
+/// Congestion of a shard as recorded in the chunk header of the shard included
+/// in a block.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct ShardCongestionInfoView {
+    pub shard_id: ShardId,
+    pub congestion_info: CongestionInfoView,
+    /// Number of blocks since the last chunk of the shard was included.
+    pub missed_chunks_count: u64,
+    /// Summary of the congestion between 0.0 and 1.0. A fully congested shard
+    /// doesn't accept transactions sent to it.
+    pub congestion_level: f64,
+}
+
+/// Congestion of all shards at a block.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct BlockCongestionInfoView {
+    pub block_hash: CryptoHash,
+    pub block_height: BlockHeight,
+    /// Shards ordered by shard id. Empty before congestion control is enabled.
+    pub shards: Vec<ShardCongestionInfoView>,
+}
+
+/// Outcome of a delegate action, i.e. of a meta transaction, applied in a
+/// block.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct DelegateActionOutcomeView {
+    /// Receipt with the delegate action.
+    pub receipt_id: CryptoHash,
+    pub shard_id: ShardId,
+    /// Account which signed the transaction with the delegate action and paid
+    /// for the gas.
+    pub relayer_id: AccountId,
+    /// Account which signed the delegate action, on behalf of which the
+    /// delegated actions are executed.
+    pub sender_id: AccountId,
+    pub receiver_id: AccountId,
+    /// Gas burnt for applying the delegate action.
+    pub gas_burnt: Gas,
+    /// Gas attached by the relayer to the delegated function calls.
+    pub prepaid_gas: Gas,
+    #[serde(with = "dec_format")]
+    pub tokens_burnt: Balance,
+    pub status: ExecutionStatusView,
+}
+
+/// Delegate actions applied in a block, in the shards tracked by the node.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct BlockDelegateActionsView {
+    pub block_hash: CryptoHash,
+    pub block_height: BlockHeight,
+    pub delegate_actions: Vec<DelegateActionOutcomeView>,
+}
+
 #[cfg(test)]
 #[cfg(not(feature = "nightly"))]
 #[cfg(not(feature = "statelessnet_protocol"))]
@@ -3,13 +3,15 @@ use crate::db::{ColdDB, COLD_HEAD_KEY, HEAD_KEY};
 use crate::{metrics, DBCol, DBTransaction, Database, Store, TrieChanges};
 
 use borsh::BorshDeserialize;
+use near_async::time::Instant;
 use near_primitives::block::{Block, BlockHeader, Tip};
 use near_primitives::hash::CryptoHash;
 use near_primitives::shard_layout::ShardLayout;
 use near_primitives::sharding::ShardChunk;
 use near_primitives::types::BlockHeight;
+use near_primitives::views::ColdStoreCopyStatusView;
 use rayon::prelude::{IntoParallelIterator, ParallelIterator};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::io;
 use strum::IntoEnumIterator;
 
@@ -335,6 +337,108 @@ pub fn copy_all_data_to_cold(
     Ok(CopyAllDataToColdStatus::EverythingCopied)
 }
 
+/// How far back the copied blocks are considered to compute the copy rate.
+const COPY_RATE_WINDOW: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Shared between the cold store loop and the view client. The loop reports
+/// the blocks it copies and checks whether it should pause or slow down the
+/// copying, the view client reports the progress and forwards the commands
+/// of the operator, e.g. to pause the copying during peak RPC traffic.
+#[derive(Default)]
+pub struct ColdStoreCopyControl {
+    paused: std::sync::atomic::AtomicBool,
+    inner: std::sync::Mutex<ColdStoreCopyControlInner>,
+}
+
+#[derive(Default)]
+struct ColdStoreCopyControlInner {
+    max_blocks_per_second: Option<u64>,
+    /// Time and height of the blocks copied within the last `COPY_RATE_WINDOW`.
+    recent_copies: VecDeque<(Instant, BlockHeight)>,
+}
+
+impl ColdStoreCopyControl {
+    pub fn pause(&self) {
+        self.paused.store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Limits the number of blocks copied per second, None removes the limit.
+    pub fn set_max_blocks_per_second(&self, max_blocks_per_second: Option<u64>) {
+        self.inner.lock().unwrap().max_blocks_per_second =
+            max_blocks_per_second.filter(|max| *max > 0);
+    }
+
+    /// Records that the block at `height` was copied to the cold store.
+    pub fn record_copied_block(&self, height: BlockHeight, now: Instant) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.recent_copies.push_back((now, height));
+        while let Some((time, _)) = inner.recent_copies.front() {
+            if now.saturating_duration_since(*time) <= COPY_RATE_WINDOW {
+                break;
+            }
+            inner.recent_copies.pop_front();
+        }
+    }
+
+    /// How long the cold store loop has to wait before copying the next block
+    /// to stay within the rate limit, None if it can copy it right away.
+    pub fn delay_before_next_copy(&self, now: Instant) -> Option<std::time::Duration> {
+        let inner = self.inner.lock().unwrap();
+        let max_blocks_per_second = inner.max_blocks_per_second?;
+        let (last_copy, _) = inner.recent_copies.back()?;
+        let interval = std::time::Duration::from_secs_f64(1. / max_blocks_per_second as f64);
+        interval.checked_sub(now.saturating_duration_since(*last_copy)).filter(|d| !d.is_zero())
+    }
+
+    /// Progress of copying blocks up to `final_head_height`, given the current
+    /// `cold_head_height`.
+    pub fn status(
+        &self,
+        cold_head_height: Option<BlockHeight>,
+        final_head_height: Option<BlockHeight>,
+        now: Instant,
+    ) -> ColdStoreCopyStatusView {
+        let inner = self.inner.lock().unwrap();
+        let blocks_per_second = match (inner.recent_copies.front(), inner.recent_copies.back()) {
+            (Some((first_time, first_height)), Some((_, last_height)))
+                if last_height > first_height =>
+            {
+                let elapsed = now.saturating_duration_since(*first_time).as_secs_f64();
+                (elapsed > 0.).then(|| (last_height - first_height) as f64 / elapsed)
+            }
+            _ => None,
+        };
+        let remaining_blocks = match (cold_head_height, final_head_height) {
+            (Some(cold_head_height), Some(final_head_height)) => {
+                Some(final_head_height.saturating_sub(cold_head_height))
+            }
+            _ => None,
+        };
+        let estimated_seconds_remaining = match (remaining_blocks, blocks_per_second) {
+            (Some(0), _) => Some(0),
+            (Some(remaining_blocks), Some(rate)) => {
+                Some((remaining_blocks as f64 / rate).ceil() as u64)
+            }
+            _ => None,
+        };
+        ColdStoreCopyStatusView {
+            paused: self.is_paused(),
+            max_blocks_per_second: inner.max_blocks_per_second,
+            blocks_per_second,
+            remaining_blocks,
+            estimated_seconds_remaining,
+        }
+    }
+}
+
 // The copy_state_from_store function depends on the state nodes to be present
 // in the trie changes. This isn't the case for genesis so instead this method
 // can be used to copy the genesis records from hot to cold.
@@ -636,9 +740,49 @@ impl BatchTransaction {
 
 #[cfg(test)]
 mod test {
-    use super::{combine_keys, StoreKey};
+    use super::{combine_keys, ColdStoreCopyControl, StoreKey};
     use crate::columns::DBKeyType;
     use std::collections::{HashMap, HashSet};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_cold_store_copy_control() {
+        let control = ColdStoreCopyControl::default();
+        let start = Instant::now();
+        let status = control.status(Some(100), Some(200), start);
+        assert!(!status.paused);
+        assert_eq!(status.remaining_blocks, Some(100));
+        assert_eq!(status.blocks_per_second, None);
+        assert_eq!(status.estimated_seconds_remaining, None);
+
+        for i in 0..=10 {
+            control.record_copied_block(100 + i, start + Duration::from_millis(100 * i));
+        }
+        let status = control.status(Some(110), Some(200), start + Duration::from_secs(1));
+        assert_eq!(status.blocks_per_second, Some(10.));
+        assert_eq!(status.estimated_seconds_remaining, Some(9));
+        assert_eq!(control.delay_before_next_copy(start + Duration::from_secs(1)), None);
+
+        control.set_max_blocks_per_second(Some(2));
+        assert_eq!(
+            control.delay_before_next_copy(start + Duration::from_millis(1_200)),
+            Some(Duration::from_millis(300))
+        );
+        assert_eq!(control.delay_before_next_copy(start + Duration::from_millis(1_500)), None);
+        control.set_max_blocks_per_second(None);
+        assert_eq!(control.delay_before_next_copy(start + Duration::from_millis(1_200)), None);
+
+        control.pause();
+        assert!(control.status(Some(110), Some(200), start).paused);
+        control.resume();
+        assert!(!control.is_paused());
+
+        // Copies older than the rate window are forgotten.
+        control.record_copied_block(111, start + Duration::from_secs(120));
+        let status = control.status(Some(111), Some(111), start + Duration::from_secs(121));
+        assert_eq!(status.blocks_per_second, None);
+        assert_eq!(status.estimated_seconds_remaining, Some(0));
+    }
 
     #[test]
     fn test_combine_keys() {
@@ -294,6 +294,23 @@ pub enum DBCol {
     /// Witnesses with the lowest index are garbage collected first.
     /// u64 -> LatestWitnessesKey
     LatestWitnessesByIndex,
+    /// Column to store the evidence of validators signing conflicting blocks or
+    /// approvals observed by the node, garbage collected with the blocks of its
+    /// height, and its index by validator.
+    /// - *Rows*: `EquivocationEvidenceKey` (0 || BlockHeight || kind || AccountId),
+    ///   or (1 || len(AccountId) || AccountId || BlockHeight || kind) for the index
+    /// - *Column type*: `EquivocationEvidence`, empty for the index
+    EquivocationEvidence,
+    /// Column to store the optional index of the transactions and receipts
+    /// involving the accounts, only written with `save_account_index`.
+    /// - *Rows*: `AccountIndexKey` (AccountId || 0 || BlockHeight || id || BlockHash)
+    /// - *Column type*: `AccountIndexEntryKind`
+    AccountIndex,
+    /// Keys of the entries of `DBCol::AccountIndex` added by the chunk, used
+    /// to garbage collect them.
+    /// - *Rows*: BlockShardId (BlockHash || ShardId) - 40 bytes
+    /// - *Column type*: `Vec<AccountIndexKey>`
+    AccountIndexKeys,
     /// Column to store data for Epoch Sync.
     /// Does not contain data for genesis epoch.
     /// - *Rows*: `epoch_id`
@@ -335,6 +352,8 @@ pub enum DBKeyType {
     ColumnId,
     LatestWitnessesKey,
     LatestWitnessIndex,
+    EquivocationEvidenceKey,
+    AccountIndexKey,
 }
 
 impl DBCol {
@@ -473,6 +492,11 @@ impl DBCol {
             // LatestChunkStateWitnesses stores the last N observed witnesses, used only for debugging.
             DBCol::LatestChunkStateWitnesses => false,
             DBCol::LatestWitnessesByIndex => false,
+            // EquivocationEvidence is only needed for reporting by this node.
+            DBCol::EquivocationEvidence => false,
+            // AccountIndex is only served by this node and has its own retention.
+            DBCol::AccountIndex => false,
+            DBCol::AccountIndexKeys => false,
 
             // Columns that are not GC-ed need not be copied to the cold storage.
             DBCol::BlockHeader
@@ -574,6 +598,9 @@ impl DBCol {
             DBCol::StateTransitionData => &[DBKeyType::BlockHash, DBKeyType::ShardId],
             DBCol::LatestChunkStateWitnesses => &[DBKeyType::LatestWitnessesKey],
             DBCol::LatestWitnessesByIndex => &[DBKeyType::LatestWitnessIndex],
+            DBCol::EquivocationEvidence => &[DBKeyType::EquivocationEvidenceKey],
+            DBCol::AccountIndex => &[DBKeyType::AccountIndexKey],
+            DBCol::AccountIndexKeys => &[DBKeyType::BlockHash, DBKeyType::ShardId],
             #[cfg(feature = "new_epoch_sync")]
             DBCol::EpochSyncInfo => &[DBKeyType::EpochId],
         }
@@ -2,6 +2,7 @@ use crate::trie::{
     DEFAULT_SHARD_CACHE_DELETIONS_QUEUE_CAPACITY, DEFAULT_SHARD_CACHE_TOTAL_SIZE_LIMIT,
 };
 use crate::DBCol;
+use near_async::time::Duration;
 use near_primitives::shard_layout::ShardUId;
 use std::{collections::HashMap, iter::FromIterator};
 
@@ -13,6 +14,17 @@ pub struct StoreConfig {
     /// database.
     pub path: Option<std::path::PathBuf>,
 
+    /// Where the data is kept.  With the in-memory backend all the data is
+    /// lost when the node stops, so it's only meant for benchmarks and tests
+    /// of ephemeral nodes.  State snapshots aren't supported with it.
+    #[serde(skip_serializing_if = "StoreBackend::is_default")]
+    pub backend: StoreBackend,
+
+    /// Limit of the total size of the keys and values of the in-memory
+    /// backend.  Writes exceeding it fail, which makes the node crash.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_memory_max_size: Option<bytesize::ByteSize>,
+
     /// Collect internal storage layer statistics.
     /// Minor performance impact is expected.
     pub enable_statistics: bool,
@@ -99,6 +111,60 @@ pub struct StoreConfig {
 
     // TODO (#9989): To be phased out in favor of state_snapshot_config
     pub state_snapshot_enabled: bool,
+
+    /// Write the genesis state computed from the genesis records by ingesting
+    /// SST files built from it, rather than through the write-ahead log.  This
+    /// makes initializing the database from a large records file much faster.
+    pub genesis_bulk_import: bool,
+
+    /// Creates checkpoints of the database periodically, to be used as the
+    /// restore points of backups.  Disabled by default.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub db_checkpoints: Option<DbCheckpointsConfig>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StoreBackend {
+    #[default]
+    #[serde(rename = "rocksdb")]
+    RocksDB,
+    InMemory,
+}
+
+impl StoreBackend {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+fn default_db_checkpoints_period() -> Duration {
+    Duration::hours(6)
+}
+
+fn default_db_checkpoints_retention() -> usize {
+    4
+}
+
+/// Config of the periodic checkpoints of the hot database.
+///
+/// The checkpoints are hard-linked, so the directory has to be on the same
+/// file system as the database for them to take no time and space at first.
+/// Every checkpoint is a `checkpoint-<unix timestamp>` directory, with the
+/// database in its `data` subdirectory.  To restore a node from one, replace
+/// the contents of the database directory with the contents of `data`.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DbCheckpointsConfig {
+    /// Directory of the checkpoints, relative to the home directory if not
+    /// absolute.
+    pub path: std::path::PathBuf,
+    /// Time between the checkpoints.
+    #[serde(default = "default_db_checkpoints_period")]
+    #[serde(with = "near_async::time::serde_duration_as_std")]
+    pub period: Duration,
+    /// Number of the checkpoints kept, the older ones are deleted.
+    #[serde(default = "default_db_checkpoints_retention")]
+    pub retention: usize,
 }
 
 /// Config used to control state snapshot creation. This is used for state sync and resharding.
@@ -189,6 +255,8 @@ impl Default for StoreConfig {
     fn default() -> Self {
         Self {
             path: None,
+            backend: StoreBackend::RocksDB,
+            in_memory_max_size: None,
             enable_statistics: false,
             enable_statistics_export: true,
 
@@ -280,6 +348,10 @@ impl Default for StoreConfig {
 
             // TODO: To be phased out in favor of state_snapshot_config
             state_snapshot_enabled: false,
+
+            genesis_bulk_import: false,
+
+            db_checkpoints: None,
         }
     }
 }
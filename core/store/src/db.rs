@@ -36,6 +36,9 @@ pub const GENESIS_STATE_ROOTS_KEY: &[u8; 19] = b"GENESIS_STATE_ROOTS";
 pub const COLD_HEAD_KEY: &[u8; 9] = b"COLD_HEAD";
 pub const STATE_SYNC_DUMP_KEY: &[u8; 15] = b"STATE_SYNC_DUMP";
 pub const STATE_SNAPSHOT_KEY: &[u8; 18] = b"STATE_SNAPSHOT_KEY";
+/// Prefix of the keys of the tails of the column groups with their own GC
+/// retention, followed by the name of the group.
+pub const COLUMN_GROUP_TAIL_KEY_PREFIX: &[u8; 18] = b"COLUMN_GROUP_TAIL:";
 
 // `DBCol::Misc` keys
 pub const FLAT_STATE_VALUES_INLINING_MIGRATION_STATUS_KEY: &[u8] =
@@ -164,6 +167,26 @@ impl DBTransaction {
 pub type DBIteratorItem = io::Result<(Box<[u8]>, Box<[u8]>)>;
 pub type DBIterator<'a> = Box<dyn Iterator<Item = DBIteratorItem> + 'a>;
 
+/// Storage backend of the [`crate::Store`].
+///
+/// The store only relies on the operations of this trait, so any engine
+/// implementing them can hold the node's data.  [`RocksDB`] is the default
+/// backend and [`TestDB`] keeps everything in memory; which one is opened is
+/// selected by [`crate::StoreBackend`] in the store config.
+///
+/// The backends must:
+/// - keep the columns of [`DBCol`] apart, each column being a separate key
+///   space;
+/// - return the items of the iterators in the lexicographical order of keys;
+/// - apply all the operations of the transaction passed to [`Self::write`]
+///   atomically, merging the reference counts of [`DBOp::UpdateRefcount`]
+///   (see [`refcount`]).
+///
+/// Snapshots and checkpoints aren't part of the trait, they work on the files
+/// of a RocksDB database: the migration snapshots are taken by the opener
+/// before opening the database, and [`Self::create_checkpoint`] (used for the
+/// state snapshots and the database checkpoints) fails on the backends
+/// without such files.
 pub trait Database: Sync + Send {
     /// Returns raw bytes for given `key` ignoring any reference count decoding
     /// if any.
@@ -229,6 +252,17 @@ pub trait Database: Sync + Send {
     /// Atomically apply all operations in given batch at once.
     fn write(&self, batch: DBTransaction) -> io::Result<()>;
 
+    /// Applies all operations in given batch bypassing the write-ahead log and
+    /// the memtables, if the database supports it.  Otherwise, this is the same
+    /// as [`Self::write`].
+    ///
+    /// This is only meant for loading large amounts of data, e.g. the genesis
+    /// state.  The changes of different columns are not applied atomically and
+    /// the batch may not contain range deletions.
+    fn write_bulk(&self, batch: DBTransaction) -> io::Result<()> {
+        self.write(batch)
+    }
+
     /// Flush all in-memory data to disk.
     ///
     /// This is a no-op for in-memory databases.
@@ -243,7 +277,8 @@ pub trait Database: Sync + Send {
     /// Returns statistics about the database if available.
     fn get_store_statistics(&self) -> Option<StoreStatistics>;
 
-    /// Create checkpoint in provided path
+    /// Create checkpoint in provided path.  Fails if the backend can't be
+    /// checkpointed to disk.
     fn create_checkpoint(
         &self,
         path: &std::path::Path,
@@ -5,8 +5,10 @@
 mod tests {
     use std::sync::Arc;
 
+    use std::num::NonZeroU32;
+
     use crate::{
-        db::{DBTransaction, Database, TestDB},
+        db::{refcount, DBTransaction, Database, TestDB},
         DBCol, NodeStorage,
     };
 
@@ -40,6 +42,100 @@ mod tests {
                 .map(|data| String::from_utf8(data.unwrap().0.to_vec()).unwrap())
                 .collect();
             assert_eq!(keys, vec!["aa", "aa1"]);
+
+            let keys: Vec<_> = db
+                .iter_prefix(DBCol::Block, "aa".as_bytes())
+                .map(|data| String::from_utf8(data.unwrap().0.to_vec()).unwrap())
+                .collect();
+            assert_eq!(keys, vec!["aa", "aa1"]);
         }
     }
+
+    /// Tests that all the operations of a transaction are applied.
+    #[test]
+    fn test_db_write() {
+        for db in test_and_rocksdb() {
+            let mut transaction = DBTransaction::new();
+            for key in ["a", "b", "c", "d"] {
+                transaction.set(DBCol::BlockMisc, key.into(), "val".into());
+            }
+            transaction.set(DBCol::BlockHeader, "e".into(), "val".into());
+            db.write(transaction).unwrap();
+
+            let mut transaction = DBTransaction::new();
+            transaction.set(DBCol::BlockMisc, "a".into(), "new_val".into());
+            transaction.delete(DBCol::BlockMisc, "b".into());
+            transaction.delete_range(DBCol::BlockMisc, "c".into(), "d".into());
+            transaction.delete_all(DBCol::BlockHeader);
+            db.write(transaction).unwrap();
+
+            let get = |col, key: &str| {
+                db.get_raw_bytes(col, key.as_bytes()).unwrap().map(|value| value.to_vec())
+            };
+            assert_eq!(get(DBCol::BlockMisc, "a"), Some(b"new_val".to_vec()));
+            assert_eq!(get(DBCol::BlockMisc, "b"), None);
+            assert_eq!(get(DBCol::BlockMisc, "c"), None);
+            assert_eq!(get(DBCol::BlockMisc, "d"), Some(b"val".to_vec()));
+            assert_eq!(get(DBCol::BlockHeader, "e"), None);
+        }
+    }
+
+    /// Tests that the reference counts of the updates are merged.
+    #[test]
+    fn test_db_refcount() {
+        let one = NonZeroU32::new(1).unwrap();
+        for db in test_and_rocksdb() {
+            let mut transaction = DBTransaction::new();
+            let value = refcount::add_positive_refcount(b"val", one);
+            transaction.update_refcount(DBCol::State, "a".into(), value.clone());
+            transaction.update_refcount(DBCol::State, "a".into(), value);
+            db.write(transaction).unwrap();
+            let get = |key: &[u8]| db.get_with_rc_stripped(DBCol::State, key).unwrap();
+            assert_eq!(get(b"a").as_deref(), Some(&b"val"[..]));
+
+            let mut transaction = DBTransaction::new();
+            let decrement = refcount::encode_negative_refcount(one).to_vec();
+            transaction.update_refcount(DBCol::State, "a".into(), decrement);
+            db.write(transaction).unwrap();
+            assert_eq!(get(b"a").as_deref(), Some(&b"val"[..]));
+
+            let mut transaction = DBTransaction::new();
+            let decrement = refcount::encode_negative_refcount(one).to_vec();
+            transaction.update_refcount(DBCol::State, "a".into(), decrement);
+            db.write(transaction).unwrap();
+            assert_eq!(get(b"a"), None);
+        }
+    }
+
+    #[test]
+    fn test_db_max_size() {
+        let db = TestDB::with_max_size(20);
+        let mut transaction = DBTransaction::new();
+        transaction.set(DBCol::Block, "a".into(), "val_a".into());
+        transaction.set(DBCol::Block, "b".into(), "val_b".into());
+        db.write(transaction).unwrap();
+        assert_eq!(db.size(), 12);
+
+        // Overwriting replaces the size of the old value.
+        let mut transaction = DBTransaction::new();
+        transaction.set(DBCol::Block, "a".into(), "value_a".into());
+        db.write(transaction).unwrap();
+        assert_eq!(db.size(), 14);
+
+        let mut transaction = DBTransaction::new();
+        transaction.set(DBCol::Block, "c".into(), "value_c".into());
+        assert!(db.write(transaction).is_err());
+        assert_eq!(db.get_raw_bytes(DBCol::Block, b"c").unwrap(), None);
+
+        let mut transaction = DBTransaction::new();
+        transaction.delete(DBCol::Block, "b".into());
+        transaction.set(DBCol::Block, "c".into(), "val_c".into());
+        db.write(transaction).unwrap();
+        assert_eq!(db.size(), 14);
+
+        let mut transaction = DBTransaction::new();
+        transaction.delete_all(DBCol::Block);
+        db.write(transaction).unwrap();
+        assert_eq!(db.size(), 0);
+    }
 }
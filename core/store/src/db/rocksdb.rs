@@ -13,6 +13,7 @@ use std::path::Path;
 use strum::IntoEnumIterator;
 use tracing::warn;
 
+mod bulk_import;
 mod instance_tracker;
 pub(crate) mod snapshot;
 
@@ -26,6 +27,7 @@ static CF_PROPERTY_NAMES: Lazy<Vec<std::ffi::CString>> = Lazy::new(|| {
         &[
             properties::LIVE_SST_FILES_SIZE,
             properties::ESTIMATE_LIVE_DATA_SIZE,
+            properties::ESTIMATE_NUM_KEYS,
             properties::COMPACTION_PENDING,
             properties::NUM_RUNNING_COMPACTIONS,
             properties::ESTIMATE_PENDING_COMPACTION_BYTES,
@@ -402,6 +404,10 @@ impl Database for RocksDB {
         self.db.write(batch).map_err(io::Error::other)
     }
 
+    fn write_bulk(&self, transaction: DBTransaction) -> io::Result<()> {
+        self.ingest_transaction(transaction)
+    }
+
     #[tracing::instrument(
         target = "store::db::rocksdb",
         level = "info",
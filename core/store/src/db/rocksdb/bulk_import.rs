@@ -0,0 +1,180 @@
+//! Bulk import of large amounts of data into the database.
+//!
+//! Rather than going through the write-ahead log and the memtables, the
+//! changes of each column are sorted, written into an SST file and the file is
+//! ingested into the database.  This is much faster when loading many
+//! gigabytes of state, e.g. from a genesis records file, but it’s not atomic
+//! across columns so it’s only suitable for setting up a database.
+
+use super::{set_compression_options, RocksDB};
+use crate::db::{refcount, DBOp, DBTransaction};
+use crate::DBCol;
+use ::rocksdb::{IngestExternalFileOptions, Options, SstFileWriter};
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Makes the names of the SST files unique within the process.
+static NEXT_FILE_ID: AtomicU64 = AtomicU64::new(0);
+
+enum Entry {
+    Put(Vec<u8>),
+    /// Reference count update of a reference counted column.
+    Merge(Vec<u8>),
+    Delete,
+}
+
+fn invalid_input(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, msg)
+}
+
+impl RocksDB {
+    pub(super) fn ingest_transaction(&self, transaction: DBTransaction) -> io::Result<()> {
+        let mut columns: enum_map::EnumMap<DBCol, Vec<(Vec<u8>, Entry)>> = Default::default();
+        for op in transaction.ops {
+            match op {
+                DBOp::Set { col, key, value } | DBOp::Insert { col, key, value } => {
+                    columns[col].push((key, Entry::Put(value)));
+                }
+                DBOp::UpdateRefcount { col, key, value } => {
+                    columns[col].push((key, Entry::Merge(value)));
+                }
+                DBOp::Delete { col, key } => columns[col].push((key, Entry::Delete)),
+                DBOp::DeleteAll { col } | DBOp::DeleteRange { col, .. } => {
+                    return Err(invalid_input(format!("{col}: range deletion in a bulk import")));
+                }
+            }
+        }
+        for (col, entries) in columns {
+            if !entries.is_empty() {
+                self.ingest_column(col, combine_entries(col, entries)?)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the entries, which have to be sorted by key without duplicates,
+    /// into an SST file and ingests it into the column.
+    fn ingest_column(&self, col: DBCol, entries: Vec<(Vec<u8>, Entry)>) -> io::Result<()> {
+        let _span = tracing::debug_span!(
+            target: "store::db::rocksdb",
+            "RocksDB::ingest_column",
+            %col,
+            entries = entries.len()
+        )
+        .entered();
+        let path = self.db.path().join(format!(
+            "bulk-import-{}-{}.sst",
+            std::process::id(),
+            NEXT_FILE_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        let result = self.write_sst_file(&path, entries).and_then(|()| {
+            let mut options = IngestExternalFileOptions::default();
+            options.set_move_files(true);
+            self.db
+                .ingest_external_file_cf_opts(self.cf_handle(col)?, &options, vec![&path])
+                .map_err(io::Error::other)
+        });
+        // The file is moved into the database on success, but removing it is
+        // still needed if anything failed.
+        if path.exists() {
+            std::fs::remove_file(&path)?;
+        }
+        result
+    }
+
+    fn write_sst_file(&self, path: &Path, entries: Vec<(Vec<u8>, Entry)>) -> io::Result<()> {
+        let mut options = Options::default();
+        set_compression_options(&mut options);
+        let mut writer = SstFileWriter::create(&options);
+        writer.open(path).map_err(io::Error::other)?;
+        for (key, entry) in entries {
+            match entry {
+                Entry::Put(value) => writer.put(key, value),
+                Entry::Merge(value) => writer.merge(key, value),
+                Entry::Delete => writer.delete(key),
+            }
+            .map_err(io::Error::other)?;
+        }
+        writer.finish().map_err(io::Error::other)
+    }
+}
+
+/// Sorts the entries by key and combines the entries of the same key, which
+/// SST files can’t contain.  The reference count updates are summed up, for
+/// other columns the last entry wins as it would with a regular write.
+fn combine_entries(
+    col: DBCol,
+    mut entries: Vec<(Vec<u8>, Entry)>,
+) -> io::Result<Vec<(Vec<u8>, Entry)>> {
+    // Stable, so that the entries of the same key stay in the order of writing.
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut combined: Vec<(Vec<u8>, Entry)> = Vec::with_capacity(entries.len());
+    for (key, entry) in entries {
+        if col.is_rc() != matches!(entry, Entry::Merge(_)) {
+            return Err(invalid_input(format!(
+                "{col}: a bulk import supports only reference count updates of reference \
+                 counted columns and only other writes of other columns"
+            )));
+        }
+        match combined.last_mut() {
+            Some((last_key, last_entry)) if *last_key == key => {
+                *last_entry = match (&*last_entry, entry) {
+                    (Entry::Merge(last), Entry::Merge(value)) => {
+                        Entry::Merge(refcount::refcount_merge(None, [&last[..], &value[..]]))
+                    }
+                    (_, entry) => entry,
+                };
+            }
+            _ => combined.push((key, entry)),
+        }
+    }
+    Ok(combined)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::{DBTransaction, Database};
+    use crate::{DBCol, NodeStorage};
+
+    #[test]
+    fn test_write_bulk() {
+        let (_tmp_dir, opener) = NodeStorage::test_opener();
+        let store = opener.open().unwrap().get_hot_store();
+        let mut update = store.store_update();
+        update.increment_refcount(DBCol::State, &[1], &[10]);
+        update.set(DBCol::FlatState, &[2], &[20]);
+        update.commit().unwrap();
+
+        let mut batch = DBTransaction::new();
+        // Out of order and with duplicates.
+        batch.set(DBCol::FlatState, vec![4], vec![40]);
+        batch.set(DBCol::FlatState, vec![3], vec![30]);
+        batch.set(DBCol::FlatState, vec![3], vec![31]);
+        batch.delete(DBCol::FlatState, vec![2]);
+        let mut inc = vec![10];
+        inc.extend_from_slice(&1i64.to_le_bytes());
+        batch.update_refcount(DBCol::State, vec![1], inc.clone());
+        batch.update_refcount(DBCol::State, vec![1], inc.clone());
+        batch.update_refcount(DBCol::State, vec![5], (-1i64).to_le_bytes().to_vec());
+        batch.update_refcount(
+            DBCol::State,
+            vec![5],
+            [vec![50], 1i64.to_le_bytes().to_vec()].concat(),
+        );
+        store.storage.write_bulk(batch).unwrap();
+
+        assert_eq!(store.get(DBCol::FlatState, &[2]).unwrap(), None);
+        assert_eq!(store.get(DBCol::FlatState, &[3]).unwrap().as_deref(), Some(&[31][..]));
+        assert_eq!(store.get(DBCol::FlatState, &[4]).unwrap().as_deref(), Some(&[40][..]));
+        let raw = |key: &[u8]| {
+            store.storage.get_raw_bytes(DBCol::State, key).unwrap().map(|v| v.to_vec())
+        };
+        assert_eq!(raw(&[1]), Some([vec![10], 3i64.to_le_bytes().to_vec()].concat()));
+        assert_eq!(store.get(DBCol::State, &[5]).unwrap(), None);
+
+        let mut batch = DBTransaction::new();
+        batch.delete_all(DBCol::FlatState);
+        assert!(store.storage.write_bulk(batch).is_err());
+    }
+}
@@ -6,7 +6,7 @@ use std::sync::Arc;
 use near_o11y::log_assert_fail;
 
 use crate::db::{DBIterator, DBIteratorItem, DBSlice, DBTransaction, Database, StoreStatistics};
-use crate::DBCol;
+use crate::{metrics, DBCol};
 
 /// A database that provides access to the hot and cold databases.
 ///
@@ -70,6 +70,10 @@ impl SplitDB {
     }
 }
 
+fn record_read(col: DBCol, source: &str) {
+    metrics::SPLIT_DB_READS.with_label_values(&[col.into(), source]).inc();
+}
+
 impl Database for SplitDB {
     /// Returns raw bytes for given `key` ignoring any reference count decoding
     /// if any.
@@ -78,11 +82,15 @@ impl Database for SplitDB {
     /// Then it tries to read the data from the cold db and returns the result.
     fn get_raw_bytes(&self, col: DBCol, key: &[u8]) -> io::Result<Option<DBSlice<'_>>> {
         if let Some(hot_result) = self.hot.get_raw_bytes(col, key)? {
+            record_read(col, "hot");
             return Ok(Some(hot_result));
         }
         if col.is_cold() {
-            return self.cold.get_raw_bytes(col, key);
+            let cold_result = self.cold.get_raw_bytes(col, key)?;
+            record_read(col, if cold_result.is_some() { "cold" } else { "missing" });
+            return Ok(cold_result);
         }
+        record_read(col, "missing");
         Ok(None)
     }
 
@@ -96,11 +104,15 @@ impl Database for SplitDB {
         assert!(col.is_rc());
 
         if let Some(hot_result) = self.hot.get_with_rc_stripped(col, key)? {
+            record_read(col, "hot");
             return Ok(Some(hot_result));
         }
         if col.is_cold() {
-            return self.cold.get_with_rc_stripped(col, key);
+            let cold_result = self.cold.get_with_rc_stripped(col, key)?;
+            record_read(col, if cold_result.is_some() { "cold" } else { "missing" });
+            return Ok(cold_result);
         }
+        record_read(col, "missing");
         Ok(None)
     }
 
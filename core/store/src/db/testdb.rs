@@ -1,12 +1,14 @@
 use std::collections::BTreeMap;
 use std::io;
 use std::ops::Bound;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, RwLock};
 
 use crate::db::{refcount, DBIterator, DBOp, DBSlice, DBTransaction, Database};
 use crate::{DBCol, StoreStatistics};
 
-/// An in-memory database intended for tests and IO-agnostic estimations.
+/// An in-memory database intended for tests, IO-agnostic estimations and
+/// ephemeral nodes using the in-memory store backend.
 #[derive(Default)]
 pub struct TestDB {
     // In order to ensure determinism when iterating over column's results
@@ -18,12 +20,33 @@ pub struct TestDB {
     // The TestDB doesn't produce any stats on its own, it's up to the user of
     // this class to set the stats as they need it.
     stats: RwLock<Option<StoreStatistics>>,
+
+    // Total size of the keys and values, updated while holding the lock of
+    // `db` for writing.
+    size: AtomicU64,
+    // Writes which would make `size` exceed the limit are rejected.
+    max_size: Option<u64>,
 }
 
 impl TestDB {
     pub fn new() -> Arc<TestDB> {
         Arc::new(Self::default())
     }
+
+    /// Creates a database rejecting the writes which would make the total
+    /// size of its keys and values exceed `max_size` bytes.
+    pub fn with_max_size(max_size: u64) -> Arc<TestDB> {
+        Arc::new(Self { max_size: Some(max_size), ..Self::default() })
+    }
+
+    /// Returns the total size of the keys and values in the database.
+    pub fn size(&self) -> u64 {
+        self.size.load(Ordering::Relaxed)
+    }
+}
+
+fn entry_size(key: &[u8], value: &[u8]) -> u64 {
+    (key.len() + value.len()) as u64
 }
 
 impl TestDB {
@@ -77,10 +100,34 @@ impl Database for TestDB {
 
     fn write(&self, transaction: DBTransaction) -> io::Result<()> {
         let mut db = self.db.write().unwrap();
+        let mut size = self.size.load(Ordering::Relaxed);
+        if let Some(max_size) = self.max_size {
+            // The transaction can't grow the database by more than the size
+            // of the written keys and values.
+            let written: u64 = transaction
+                .ops
+                .iter()
+                .map(|op| match op {
+                    DBOp::Set { key, value, .. }
+                    | DBOp::Insert { key, value, .. }
+                    | DBOp::UpdateRefcount { key, value, .. } => entry_size(key, value),
+                    DBOp::Delete { .. } | DBOp::DeleteAll { .. } | DBOp::DeleteRange { .. } => 0,
+                })
+                .sum();
+            if size + written > max_size {
+                return Err(io::Error::other(format!(
+                    "in-memory database of {size} bytes would exceed its limit of {max_size} bytes"
+                )));
+            }
+        }
+        let removed_size =
+            |key: &[u8], value: Option<Vec<u8>>| value.map_or(0, |value| entry_size(key, &value));
         for op in transaction.ops {
             match op {
                 DBOp::Set { col, key, value } => {
-                    db[col].insert(key, value);
+                    size += entry_size(&key, &value);
+                    let old_value = db[col].insert(key.clone(), value);
+                    size -= removed_size(&key, old_value);
                 }
                 DBOp::Insert { col, key, value } => {
                     if cfg!(debug_assertions) {
@@ -88,31 +135,47 @@ impl Database for TestDB {
                             super::assert_no_overwrite(col, &key, &value, &*old_value)
                         }
                     }
-                    db[col].insert(key, value);
+                    size += entry_size(&key, &value);
+                    let old_value = db[col].insert(key.clone(), value);
+                    size -= removed_size(&key, old_value);
                 }
                 DBOp::UpdateRefcount { col, key, value } => {
                     let existing = db[col].get(&key).map(Vec::as_slice);
                     let operands = [value.as_slice()];
                     let merged = refcount::refcount_merge(existing, operands);
                     if merged.is_empty() {
-                        db[col].remove(&key);
+                        let old_value = db[col].remove(&key);
+                        size -= removed_size(&key, old_value);
                     } else {
                         debug_assert!(
                             refcount::decode_value_with_rc(&merged).1 > 0,
                             "Inserting value with non-positive refcount"
                         );
-                        db[col].insert(key, merged);
+                        size += entry_size(&key, &merged);
+                        let old_value = db[col].insert(key.clone(), merged);
+                        size -= removed_size(&key, old_value);
                     }
                 }
                 DBOp::Delete { col, key } => {
-                    db[col].remove(&key);
+                    let old_value = db[col].remove(&key);
+                    size -= removed_size(&key, old_value);
+                }
+                DBOp::DeleteAll { col } => {
+                    size -= db[col].iter().map(|(key, value)| entry_size(key, value)).sum::<u64>();
+                    db[col].clear();
                 }
-                DBOp::DeleteAll { col } => db[col].clear(),
                 DBOp::DeleteRange { col, from, to } => {
-                    db[col].retain(|key, _| !(&from..&to).contains(&key));
+                    db[col].retain(|key, value| {
+                        let retain = !(&from..&to).contains(&key);
+                        if !retain {
+                            size -= entry_size(key, value);
+                        }
+                        retain
+                    });
                 }
             };
         }
+        self.size.store(size, Ordering::Relaxed);
         Ok(())
     }
 
@@ -133,6 +196,6 @@ impl Database for TestDB {
         _path: &std::path::Path,
         _columns_to_keep: Option<&[DBCol]>,
     ) -> anyhow::Result<()> {
-        Ok(())
+        anyhow::bail!("checkpoints aren't supported by the in-memory database")
     }
 }
@@ -24,11 +24,15 @@ use crate::{
 const STATE_DUMP_FILE: &str = "state_dump";
 const GENESIS_ROOTS_FILE: &str = "genesis_roots";
 
+/// With `bulk_import`, the state computed from the genesis records is written
+/// with [`crate::StoreUpdate::commit_bulk`] which is much faster for large
+/// records files.
 pub fn initialize_sharded_genesis_state(
     store: Store,
     genesis: &Genesis,
     genesis_epoch_config: &EpochConfig,
     home_dir: Option<&Path>,
+    bulk_import: bool,
 ) {
     // Ignore initialization if we already have genesis hash and state roots in store
     let stored_hash = get_genesis_hash(&store).expect("Store failed on genesis intialization");
@@ -47,7 +51,12 @@ pub fn initialize_sharded_genesis_state(
             }
             genesis_state_from_dump(store.clone(), home_dir.unwrap())
         } else {
-            genesis_state_from_genesis(store.clone(), genesis, &genesis_epoch_config.shard_layout)
+            genesis_state_from_genesis(
+                store.clone(),
+                genesis,
+                &genesis_epoch_config.shard_layout,
+                bulk_import,
+            )
         };
         let genesis_hash = genesis.json_hash();
         let mut store_update = store.store_update();
@@ -67,7 +76,13 @@ pub fn initialize_sharded_genesis_state(
 }
 
 pub fn initialize_genesis_state(store: Store, genesis: &Genesis, home_dir: Option<&Path>) {
-    initialize_sharded_genesis_state(store, genesis, &EpochConfig::from(&genesis.config), home_dir);
+    initialize_sharded_genesis_state(
+        store,
+        genesis,
+        &EpochConfig::from(&genesis.config),
+        home_dir,
+        false,
+    );
 }
 
 fn genesis_state_from_dump(store: Store, home_dir: &Path) -> Vec<StateRoot> {
@@ -87,6 +102,7 @@ fn genesis_state_from_genesis(
     store: Store,
     genesis: &Genesis,
     shard_layout: &ShardLayout,
+    bulk_import: bool,
 ) -> Vec<StateRoot> {
     match &genesis.contents {
         GenesisContents::Records { records } => {
@@ -166,6 +182,7 @@ fn genesis_state_from_genesis(
                 storage_usage_config,
                 genesis,
                 shard_account_ids[shard_id as usize].clone(),
+                bulk_import,
             )
         })
         .collect()
@@ -94,6 +94,8 @@ struct AutoFlushingTrieUpdate<'a> {
     state_update: Option<TrieUpdate>,
     changes: usize,
     active_writers: &'a atomic::AtomicUsize,
+    /// Whether to commit the changes with `StoreUpdate::commit_bulk`.
+    bulk_import: bool,
 }
 
 impl<'a> AutoFlushingTrieUpdate<'a> {
@@ -102,6 +104,7 @@ impl<'a> AutoFlushingTrieUpdate<'a> {
         state_root: StateRoot,
         tries: &'a ShardTries,
         uid: ShardUId,
+        bulk_import: bool,
     ) -> Self {
         active_writers.fetch_add(1, atomic::Ordering::Relaxed);
         Self {
@@ -111,6 +114,7 @@ impl<'a> AutoFlushingTrieUpdate<'a> {
             changes: 0,
             state_update: Some(tries.new_trie_update(uid, state_root)),
             shard_uid: uid,
+            bulk_import,
         }
     }
 
@@ -144,7 +148,8 @@ impl<'a> AutoFlushingTrieUpdate<'a> {
         *state_root = self.tries.apply_all(&trie_changes, self.shard_uid, &mut store_update);
         FlatStateChanges::from_state_changes(&state_changes)
             .apply_to_flat_state(&mut store_update, self.shard_uid);
-        store_update.commit().expect("Store update failed on genesis initialization");
+        if self.bulk_import { store_update.commit_bulk() } else { store_update.commit() }
+            .expect("Store update failed on genesis initialization");
         *state_update = Some(self.tries.new_trie_update(self.shard_uid, *state_root));
         *changes = 0;
         *state_root
@@ -352,10 +357,16 @@ impl GenesisStateApplier {
         config: &StorageUsageConfig,
         genesis: &Genesis,
         shard_account_ids: HashSet<AccountId>,
+        bulk_import: bool,
     ) -> StateRoot {
         let mut delayed_receipts_indices = DelayedReceiptIndices::default();
-        let mut storage =
-            AutoFlushingTrieUpdate::new(op_limit, StateRoot::default(), &tries, shard_uid);
+        let mut storage = AutoFlushingTrieUpdate::new(
+            op_limit,
+            StateRoot::default(),
+            &tries,
+            shard_uid,
+            bulk_import,
+        );
         Self::apply_batch(
             &mut storage,
             &mut delayed_receipts_indices,
@@ -13,9 +13,9 @@ pub use crate::trie::{
 use borsh::{BorshDeserialize, BorshSerialize};
 pub use columns::DBCol;
 pub use db::{
-    CHUNK_TAIL_KEY, COLD_HEAD_KEY, FINAL_HEAD_KEY, FORK_TAIL_KEY, GENESIS_JSON_HASH_KEY,
-    GENESIS_STATE_ROOTS_KEY, HEADER_HEAD_KEY, HEAD_KEY, LARGEST_TARGET_HEIGHT_KEY,
-    LATEST_KNOWN_KEY, STATE_SNAPSHOT_KEY, STATE_SYNC_DUMP_KEY, TAIL_KEY,
+    CHUNK_TAIL_KEY, COLD_HEAD_KEY, COLUMN_GROUP_TAIL_KEY_PREFIX, FINAL_HEAD_KEY, FORK_TAIL_KEY,
+    GENESIS_JSON_HASH_KEY, GENESIS_STATE_ROOTS_KEY, HEADER_HEAD_KEY, HEAD_KEY,
+    LARGEST_TARGET_HEIGHT_KEY, LATEST_KNOWN_KEY, STATE_SNAPSHOT_KEY, STATE_SYNC_DUMP_KEY, TAIL_KEY,
 };
 use metadata::{DbKind, DbVersion, KIND_KEY, VERSION_KEY};
 use near_crypto::PublicKey;
@@ -54,7 +54,7 @@ mod sync_utils;
 pub mod test_utils;
 pub mod trie;
 
-pub use crate::config::{Mode, StoreConfig};
+pub use crate::config::{Mode, StoreBackend, StoreConfig};
 pub use crate::opener::{
     checkpoint_hot_storage_and_cleanup_columns, StoreMigrator, StoreOpener, StoreOpenerError,
 };
@@ -119,20 +119,13 @@ impl NodeStorage {
         StoreOpener::new(home_dir, archive, config, cold_config)
     }
 
-    /// Constructs new object backed by given database.
-    fn from_rocksdb(
-        hot_storage: crate::db::RocksDB,
-        cold_storage: Option<crate::db::RocksDB>,
+    /// Constructs new object backed by given hot and cold databases, of any
+    /// storage backend.
+    fn from_databases(
+        hot_storage: Arc<dyn Database>,
+        cold_storage: Option<Arc<dyn Database>>,
     ) -> Self {
-        let hot_storage = Arc::new(hot_storage);
-        let cold_storage = cold_storage.map(|storage| Arc::new(storage));
-
-        let cold_db = if let Some(cold_storage) = cold_storage {
-            Some(Arc::new(crate::db::ColdDB::new(cold_storage)))
-        } else {
-            None
-        };
-
+        let cold_db = cold_storage.map(|storage| Arc::new(crate::db::ColdDB::new(storage)));
         Self { hot_storage, cold_storage: cold_db }
     }
 
@@ -698,6 +691,16 @@ impl StoreUpdate {
         }
         self.storage.write(self.transaction)
     }
+
+    /// Commits the changes bypassing the write-ahead log and the memtables of
+    /// the database, which is much faster for large updates.  Unlike
+    /// [`Self::commit`], the update is not atomic, so this is only meant for
+    /// loading large amounts of state while setting up the database.  See
+    /// [`Database::write_bulk`].
+    pub fn commit_bulk(self) -> io::Result<()> {
+        tracing::debug!(target: "store", ops = self.transaction.ops.len(), "bulk import");
+        self.storage.write_bulk(self.transaction)
+    }
 }
 
 impl fmt::Debug for StoreUpdate {
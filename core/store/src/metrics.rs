@@ -19,6 +19,29 @@ pub(crate) static DATABASE_OP_LATENCY_HIST: Lazy<HistogramVec> = Lazy::new(|| {
     .unwrap()
 });
 
+pub(crate) static SPLIT_DB_READS: Lazy<IntCounterVec> = Lazy::new(|| {
+    try_create_int_counter_vec(
+        "near_split_db_reads",
+        "Reads of the split storage used by the view client of archival nodes by column and \
+         the database that served them. `source` is hot, cold or missing if the value was found \
+         in neither.",
+        &["column", "source"],
+    )
+    .unwrap()
+});
+
+pub(crate) static TRIE_OPERATION_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
+    try_create_histogram_vec(
+        "near_trie_operation_latency_seconds",
+        "Latency of trie operations by shard, operation and the storage that served it. \
+         `op` is one of value_lookup, value_read, node_read, update or commit and `source` \
+         tells whether memtrie, flat storage or the on-disk trie was used.",
+        &["shard_id", "is_view", "op", "source"],
+        Some(exponential_buckets(0.000_001, 2., 20).unwrap()),
+    )
+    .unwrap()
+});
+
 // TODO(#9054): Rename the metric to be consistent with "accounting cache".
 pub static CHUNK_CACHE_HITS: Lazy<IntCounterVec> = Lazy::new(|| {
     try_create_int_counter_vec(
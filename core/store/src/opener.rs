@@ -1,7 +1,10 @@
 use crate::db::rocksdb::snapshot::{Snapshot, SnapshotError, SnapshotRemoveError};
 use crate::db::rocksdb::RocksDB;
+use crate::db::Database;
 use crate::metadata::{DbKind, DbMetadata, DbVersion, DB_VERSION};
-use crate::{DBCol, DBTransaction, Mode, NodeStorage, Store, StoreConfig, Temperature};
+use crate::{
+    DBCol, DBTransaction, Mode, NodeStorage, Store, StoreBackend, StoreConfig, Temperature,
+};
 use std::sync::Arc;
 
 #[derive(Debug, thiserror::Error)]
@@ -240,7 +243,8 @@ impl<'a> StoreOpener<'a> {
         self.open_in_mode(Mode::ReadWrite)
     }
 
-    /// Opens the RocksDB database(s) for hot and cold (if configured) storages.
+    /// Opens the database(s) for hot and cold (if configured) storages, of the
+    /// storage backend selected in the config.
     ///
     /// When opening in read-only mode, verifies that the database version is
     /// what the node expects and fails if it isn’t.  If database doesn’t exist,
@@ -248,6 +252,9 @@ impl<'a> StoreOpener<'a> {
     /// other hand, if mode is [`Mode::Create`], fails if the database already
     /// exists.
     pub fn open_in_mode(&self, mode: Mode) -> Result<crate::NodeStorage, StoreOpenerError> {
+        if self.hot.config.backend == StoreBackend::InMemory {
+            return self.open_in_memory(mode);
+        }
         {
             let hot_path = self.hot.path.display().to_string();
             let cold_path = match &self.cold {
@@ -277,9 +284,9 @@ impl<'a> StoreOpener<'a> {
             .as_ref()
             .map(|cold| cold.open(mode, DB_VERSION))
             .transpose()?
-            .map(|(db, _)| db);
+            .map(|(db, _)| Arc::new(db) as Arc<dyn Database>);
 
-        let storage = NodeStorage::from_rocksdb(hot_db, cold_db);
+        let storage = NodeStorage::from_databases(Arc::new(hot_db), cold_db);
 
         hot_snapshot.remove()?;
         cold_snapshot.remove()?;
@@ -287,7 +294,37 @@ impl<'a> StoreOpener<'a> {
         Ok(storage)
     }
 
+    /// Creates an empty in-memory database, as if a database on disk was
+    /// created.
+    fn open_in_memory(&self, mode: Mode) -> Result<crate::NodeStorage, StoreOpenerError> {
+        if !mode.can_create() {
+            return Err(StoreOpenerError::DbDoesNotExist);
+        }
+        if self.cold.is_some() {
+            return Err(StoreOpenerError::IO(std::io::Error::other(
+                "cold storage isn't supported with the in-memory store backend",
+            )));
+        }
+        tracing::info!(target: "db_opener", max_size = ?self.hot.config.in_memory_max_size, "Opening in-memory NodeStorage");
+        let db = match self.hot.config.in_memory_max_size {
+            Some(max_size) => crate::db::TestDB::with_max_size(max_size.as_u64()),
+            None => crate::db::TestDB::new(),
+        };
+        let storage = NodeStorage::from_databases(db, None);
+        let store = storage.get_hot_store();
+        store.set_db_version(DB_VERSION)?;
+        store.set_db_kind(if self.archive { DbKind::Archive } else { DbKind::RPC })?;
+        Ok(storage)
+    }
+
+    /// Creates the migration snapshots of the hot and cold (if configured)
+    /// databases.  Only supported with the RocksDB backend.
     pub fn create_snapshots(&self, mode: Mode) -> Result<(Snapshot, Snapshot), StoreOpenerError> {
+        if self.hot.config.backend != StoreBackend::RocksDB {
+            return Err(StoreOpenerError::SnapshotError(std::io::Error::other(
+                "snapshots are only supported with the rocksdb store backend",
+            )));
+        }
         {
             let hot_path = self.hot.path.display().to_string();
             let cold_path = match &self.cold {
@@ -578,7 +615,8 @@ pub trait StoreMigrator {
 /// If `columns_to_keep` is None doesn't cleanup columns.
 /// Otherwise deletes all columns that are not in `columns_to_keep`.
 ///
-/// `store` must be the hot DB.
+/// `store` must be the hot DB, and a RocksDB database: the checkpoint is always
+/// opened as one, and the other backends fail to create it.
 ///
 /// Returns NodeStorage of checkpoint db.
 /// `archive` -- is hot storage archival (needed to open checkpoint).
@@ -691,4 +729,24 @@ mod tests {
         check_keys_existence(&store.get_hot_store(), &DBCol::Chunks, &keys, false);
         check_keys_existence(&store.get_hot_store(), &DBCol::BlockHeader, &keys, false);
     }
+
+    /// The in-memory backend can't be snapshotted nor checkpointed.
+    #[test]
+    fn test_in_memory_snapshots_and_checkpoints() {
+        let home_dir = tempfile::tempdir().unwrap();
+        let config = StoreConfig { backend: StoreBackend::InMemory, ..StoreConfig::test_config() };
+        let opener = StoreOpener::new(home_dir.path(), false, &config, None);
+        assert!(matches!(
+            opener.create_snapshots(Mode::ReadWrite),
+            Err(StoreOpenerError::SnapshotError(_))
+        ));
+
+        let hot_store = opener.open().unwrap().get_hot_store();
+        let result = checkpoint_hot_storage_and_cleanup_columns(
+            &hot_store,
+            &home_dir.path().join("checkpoint"),
+            None,
+        );
+        assert!(matches!(result, Err(StoreOpenerError::CheckpointError(_))));
+    }
 }
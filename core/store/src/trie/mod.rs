@@ -21,9 +21,10 @@ pub use crate::trie::state_snapshot::{
     SnapshotError, StateSnapshot, StateSnapshotConfig, STATE_SNAPSHOT_COLUMNS,
 };
 pub use crate::trie::trie_storage::{TrieCache, TrieCachingStorage, TrieDBStorage, TrieStorage};
-use crate::StorageError;
+use crate::{metrics, StorageError};
 use borsh::{BorshDeserialize, BorshSerialize};
 pub use from_flat::construct_trie_from_flat;
+use near_o11y::metrics::Histogram;
 use near_primitives::challenge::PartialState;
 use near_primitives::hash::{hash, CryptoHash};
 pub use near_primitives::shard_layout::ShardUId;
@@ -359,6 +360,39 @@ pub struct Trie {
     /// what, and lookups done via get_ref with `KeyLookupMode::Trie` will
     /// also charge gas no matter what.
     charge_gas_for_trie_node_access: bool,
+    /// Latency histograms of trie operations. Only present for tries backed
+    /// by a `TrieCachingStorage`, as that is where the shard is known.
+    metrics: Option<TrieOperationMetrics>,
+}
+
+/// Latency histograms of the operations of a single `Trie`, resolved once
+/// per trie to avoid label lookups on hot paths.
+struct TrieOperationMetrics {
+    lookup_memtrie: Histogram,
+    lookup_flat_storage: Histogram,
+    lookup_trie: Histogram,
+    value_read: Histogram,
+    update_memtrie: Histogram,
+    update_trie: Histogram,
+}
+
+impl TrieOperationMetrics {
+    fn new(shard_uid: ShardUId, is_view: bool) -> Self {
+        let mut buffer = itoa::Buffer::new();
+        let shard_id = buffer.format(shard_uid.shard_id);
+        let is_view = if is_view { "1" } else { "0" };
+        let histogram = |op: &str, source: &str| {
+            metrics::TRIE_OPERATION_LATENCY.with_label_values(&[shard_id, is_view, op, source])
+        };
+        Self {
+            lookup_memtrie: histogram("value_lookup", "memtrie"),
+            lookup_flat_storage: histogram("value_lookup", "flat_storage"),
+            lookup_trie: histogram("value_lookup", "trie"),
+            value_read: histogram("value_read", "trie"),
+            update_memtrie: histogram("update", "memtrie"),
+            update_trie: histogram("update", "trie"),
+        }
+    }
 }
 
 /// Trait for reading data from a trie.
@@ -649,6 +683,9 @@ impl Trie {
             )))),
             None => RefCell::new(TrieAccountingCache::new(None)),
         };
+        let metrics = storage.as_caching_storage().map(|caching_storage| {
+            TrieOperationMetrics::new(caching_storage.shard_uid, caching_storage.is_view)
+        });
         Trie {
             storage,
             memtries,
@@ -657,6 +694,7 @@ impl Trie {
             flat_storage_chunk_view,
             accounting_cache,
             recorder: None,
+            metrics,
         }
     }
 
@@ -1480,12 +1518,15 @@ impl Trie {
         let charge_gas_for_trie_node_access =
             mode == KeyLookupMode::Trie || self.charge_gas_for_trie_node_access;
         if self.memtries.is_some() {
+            let _timer = self.metrics.as_ref().map(|m| m.lookup_memtrie.start_timer());
             self.lookup_from_memory(key, charge_gas_for_trie_node_access, |v| {
                 v.to_optimized_value_ref()
             })
         } else if mode == KeyLookupMode::FlatStorage && self.flat_storage_chunk_view.is_some() {
+            let _timer = self.metrics.as_ref().map(|m| m.lookup_flat_storage.start_timer());
             self.lookup_from_flat_storage(key)
         } else {
+            let _timer = self.metrics.as_ref().map(|m| m.lookup_trie.start_timer());
             Ok(self
                 .lookup_from_state_column(NibbleSlice::new(key), charge_gas_for_trie_node_access)?
                 .map(OptimizedValueRef::Ref))
@@ -1501,7 +1542,10 @@ impl Trie {
         optimized_value_ref: &OptimizedValueRef,
     ) -> Result<Vec<u8>, StorageError> {
         match optimized_value_ref {
-            OptimizedValueRef::Ref(value_ref) => self.retrieve_value(&value_ref.hash),
+            OptimizedValueRef::Ref(value_ref) => {
+                let _timer = self.metrics.as_ref().map(|m| m.value_read.start_timer());
+                self.retrieve_value(&value_ref.hash)
+            }
             OptimizedValueRef::AvailableValue(ValueAccessToken { value }) => {
                 let value_hash = hash(value);
                 let arc_value: Arc<[u8]> = value.clone().into();
@@ -1541,6 +1585,7 @@ impl Trie {
 
         match &self.memtries {
             Some(memtries) => {
+                let _timer = self.metrics.as_ref().map(|m| m.update_memtrie.start_timer());
                 // If we have in-memory tries, use it to construct the changes entirely (for
                 // both in-memory and on-disk updates) because it's much faster.
                 let guard = memtries.read().unwrap();
@@ -1593,6 +1638,7 @@ impl Trie {
                 Ok(trie_changes)
             }
             None => {
+                let _timer = self.metrics.as_ref().map(|m| m.update_trie.start_timer());
                 let mut memory = NodesStorage::new();
                 let mut root_node = self.move_node_to_mutable(&mut memory, &self.root)?;
                 for (key, value) in changes {
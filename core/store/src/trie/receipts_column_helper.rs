@@ -111,6 +111,24 @@ pub trait TrieQueue {
         Ok(Some(receipt))
     }
 
+    /// Put a receipt back to the front of the queue, where it was taken from
+    /// by `pop` before.
+    fn push_front(
+        &mut self,
+        state_update: &mut TrieUpdate,
+        receipt: &Receipt,
+    ) -> Result<(), IntegerOverflowError> {
+        self.debug_check_unchanged(state_update);
+
+        let index = self.indices().first_index.checked_sub(1).ok_or(IntegerOverflowError)?;
+        let key = self.trie_key(index);
+        set(state_update, key, receipt);
+
+        self.indices_mut().first_index = index;
+        self.write_indices(state_update);
+        Ok(())
+    }
+
     /// Remove up to `n` values from the end of the queue and return how many
     /// were actually remove.
     ///
@@ -11,6 +11,7 @@ use crate::trie::trie_storage::{TrieCache, TrieCachingStorage};
 use crate::trie::{TrieRefcountAddition, POISONED_LOCK_ERR};
 use crate::{metrics, DBCol, PrefetchApi};
 use crate::{Store, StoreUpdate, Trie, TrieChanges, TrieUpdate};
+use near_o11y::metrics::prometheus::HistogramTimer;
 use near_primitives::errors::StorageError;
 use near_primitives::hash::CryptoHash;
 use near_primitives::shard_layout::{self, ShardUId};
@@ -343,15 +344,34 @@ impl ShardTries {
         )
     }
 
+    #[tracing::instrument(
+        level = "debug",
+        target = "store::trie::shard_tries",
+        "ShardTries::apply_all",
+        fields(
+            num_insertions = trie_changes.insertions().len(),
+            num_deletions = trie_changes.deletions().len(),
+            shard_id = shard_uid.shard_id(),
+        ),
+        skip_all,
+    )]
     pub fn apply_all(
         &self,
         trie_changes: &TrieChanges,
         shard_uid: ShardUId,
         store_update: &mut StoreUpdate,
     ) -> StateRoot {
+        let _timer = commit_latency_timer(shard_uid, "trie");
         self.apply_all_inner(trie_changes, shard_uid, true, store_update)
     }
 
+    #[tracing::instrument(
+        level = "debug",
+        target = "store::trie::shard_tries",
+        "ShardTries::apply_memtrie_changes",
+        fields(shard_id = shard_uid.shard_id(), block_height = block_height),
+        skip_all,
+    )]
     pub fn apply_memtrie_changes(
         &self,
         trie_changes: &TrieChanges,
@@ -359,6 +379,7 @@ impl ShardTries {
         block_height: BlockHeight,
     ) {
         if let Some(memtries) = self.get_mem_tries(shard_uid) {
+            let _timer = commit_latency_timer(shard_uid, "memtrie");
             apply_memtrie_changes(
                 &mut memtries.write().unwrap(),
                 trie_changes
@@ -485,6 +506,16 @@ impl ShardTries {
     }
 }
 
+/// Starts a timer observing the latency of committing trie changes of a
+/// shard, either into the store update or into the memtrie.
+fn commit_latency_timer(shard_uid: ShardUId, source: &str) -> HistogramTimer {
+    let mut buffer = itoa::Buffer::new();
+    let shard_id = buffer.format(shard_uid.shard_id);
+    metrics::TRIE_OPERATION_LATENCY
+        .with_label_values(&[shard_id, "0", "commit", source])
+        .start_timer()
+}
+
 pub struct WrappedTrieChanges {
     tries: ShardTries,
     shard_uid: ShardUId,
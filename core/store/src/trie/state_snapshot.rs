@@ -241,6 +241,28 @@ impl ShardTries {
         Ok(Some(state_snapshot_lock.as_ref().unwrap().get_shard_uids()))
     }
 
+    /// Creates a checkpoint of the whole hot database in `checkpoint_base_path`
+    /// to restore the node from.
+    ///
+    /// The creation of the state snapshots waits for the checkpoint, so that
+    /// STATE_SNAPSHOT_KEY doesn't change meanwhile, and it is unset in the
+    /// checkpoint as the state snapshots aren't part of it.  Everything else,
+    /// the flat storage included, is updated atomically with the blocks, so
+    /// the checkpoint is consistent as is.
+    pub fn create_db_checkpoint(&self, checkpoint_base_path: &Path) -> Result<(), anyhow::Error> {
+        let _span = tracing::info_span!(target: "state_snapshot", "create_db_checkpoint").entered();
+        let _state_snapshot_lock = self.state_snapshot().read().unwrap();
+        let storage = checkpoint_hot_storage_and_cleanup_columns(
+            &self.get_store(),
+            checkpoint_base_path,
+            None,
+        )?;
+        let mut store_update = storage.get_hot_store().store_update();
+        store_update.delete(DBCol::BlockMisc, STATE_SNAPSHOT_KEY);
+        store_update.commit()?;
+        Ok(())
+    }
+
     /// Deletes all snapshots and unsets the STATE_SNAPSHOT_KEY.
     pub fn delete_state_snapshot(&self) {
         let _span =
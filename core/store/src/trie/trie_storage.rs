@@ -4,8 +4,8 @@ use crate::trie::POISONED_LOCK_ERR;
 use crate::{metrics, DBCol, MissingTrieValueContext, PrefetchApi, StorageError, Store};
 use lru::LruCache;
 use near_o11y::log_assert;
-use near_o11y::metrics::prometheus;
 use near_o11y::metrics::prometheus::core::{GenericCounter, GenericGauge};
+use near_o11y::metrics::{prometheus, Histogram};
 use near_primitives::challenge::PartialState;
 use near_primitives::hash::CryptoHash;
 use near_primitives::shard_layout::ShardUId;
@@ -385,6 +385,7 @@ struct TrieCacheInnerMetrics {
     prefetch_memory_limit_reached: GenericCounter<prometheus::core::AtomicU64>,
     prefetch_retry: GenericCounter<prometheus::core::AtomicU64>,
     prefetch_conflict: GenericCounter<prometheus::core::AtomicU64>,
+    node_read_latency: Histogram,
 }
 
 impl TrieCachingStorage {
@@ -416,6 +417,12 @@ impl TrieCachingStorage {
                 .with_label_values(&metrics_labels[..1]),
             prefetch_retry: metrics::PREFETCH_RETRY.with_label_values(&metrics_labels[..1]),
             prefetch_conflict: metrics::PREFETCH_CONFLICT.with_label_values(&metrics_labels[..1]),
+            node_read_latency: metrics::TRIE_OPERATION_LATENCY.with_label_values(&[
+                metrics_labels[0],
+                metrics_labels[1],
+                "node_read",
+                "trie",
+            ]),
         };
         TrieCachingStorage { store, shard_uid, is_view, shard_cache, prefetch_api, metrics }
     }
@@ -511,7 +518,10 @@ impl TrieStorage for TrieCachingStorage {
 
         self.metrics.shard_cache_misses.inc();
         near_o11y::io_trace!(count: "shard_cache_miss");
-        let val = match self.read_for_shard_cache_miss(guard, hash) {
+        let timer = self.metrics.node_read_latency.start_timer();
+        let result = self.read_for_shard_cache_miss(guard, hash);
+        timer.observe_duration();
+        let val = match result {
             Ok(val) => val,
             Err(e) => {
                 // Only release after attempt to read the value. See comment on fn release.
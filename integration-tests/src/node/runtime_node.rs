@@ -2,8 +2,9 @@ use std::sync::{Arc, RwLock};
 
 use near_chain_configs::Genesis;
 use near_crypto::{InMemorySigner, KeyType, Signer};
-use near_parameters::RuntimeConfig;
-use near_primitives::types::AccountId;
+use near_parameters::{RuntimeConfig, RuntimeConfigStore};
+use near_primitives::types::{AccountId, Gas, NumSeats};
+use near_primitives::version::{ProtocolVersion, PROTOCOL_VERSION};
 use testlib::runtime_utils::{add_test_contract, alice_account, bob_account, carol_account};
 
 use crate::node::Node;
@@ -26,10 +27,25 @@ impl RuntimeNode {
         Self::new_from_genesis(account_id, genesis)
     }
 
+    /// Returns the builder of a node signing with the key of the account.
+    pub fn builder(account_id: &AccountId) -> RuntimeNodeBuilder {
+        RuntimeNodeBuilder::new(account_id)
+    }
+
     pub fn new_from_genesis_and_config(
         account_id: &AccountId,
         genesis: Genesis,
         runtime_config: RuntimeConfig,
+    ) -> Self {
+        Self::new_from_parts(account_id, genesis, runtime_config, PROTOCOL_VERSION, None)
+    }
+
+    fn new_from_parts(
+        account_id: &AccountId,
+        genesis: Genesis,
+        runtime_config: RuntimeConfig,
+        protocol_version: ProtocolVersion,
+        gas_limit: Option<Gas>,
     ) -> Self {
         let signer = Arc::new(InMemorySigner::from_seed(
             account_id.clone(),
@@ -43,6 +59,8 @@ impl RuntimeNode {
             state_root: root,
             epoch_length: genesis.config.epoch_length,
             runtime_config,
+            protocol_version,
+            gas_limit,
         }));
         RuntimeNode { signer, client, genesis }
     }
@@ -59,6 +77,86 @@ impl RuntimeNode {
     }
 }
 
+/// Builder of a [`RuntimeNode`] for the tests which need the exact runtime
+/// config, protocol version, gas limit or accounts, e.g. the tests of the
+/// limits and the fees.
+pub struct RuntimeNodeBuilder {
+    account_id: AccountId,
+    accounts: Vec<AccountId>,
+    test_contract_accounts: Vec<AccountId>,
+    runtime_config: Option<RuntimeConfig>,
+    protocol_version: ProtocolVersion,
+    gas_limit: Option<Gas>,
+}
+
+impl RuntimeNodeBuilder {
+    fn new(account_id: &AccountId) -> Self {
+        Self {
+            account_id: account_id.clone(),
+            accounts: vec![alice_account(), bob_account(), carol_account()],
+            test_contract_accounts: vec![],
+            runtime_config: None,
+            protocol_version: PROTOCOL_VERSION,
+            gas_limit: None,
+        }
+    }
+
+    /// Sets the accounts of the genesis, all of them validators. Defaults to
+    /// alice, bob and carol.
+    pub fn accounts(mut self, accounts: Vec<AccountId>) -> Self {
+        self.accounts = accounts;
+        self
+    }
+
+    /// Sets the accounts of the genesis with the test contract deployed.
+    pub fn test_contract_accounts(mut self, accounts: Vec<AccountId>) -> Self {
+        self.test_contract_accounts = accounts;
+        self
+    }
+
+    /// Sets the runtime config. Defaults to the config of the protocol
+    /// version in the [`RuntimeConfigStore`].
+    pub fn runtime_config(mut self, runtime_config: RuntimeConfig) -> Self {
+        self.runtime_config = Some(runtime_config);
+        self
+    }
+
+    /// Sets the protocol version the transactions and the receipts are
+    /// applied with. Defaults to the latest one.
+    pub fn protocol_version(mut self, protocol_version: ProtocolVersion) -> Self {
+        self.protocol_version = protocol_version;
+        self
+    }
+
+    /// Sets the gas limit of the chunks. Defaults to no limit.
+    pub fn gas_limit(mut self, gas_limit: Gas) -> Self {
+        self.gas_limit = Some(gas_limit);
+        self
+    }
+
+    pub fn build(self) -> RuntimeNode {
+        let mut genesis = Genesis::test(self.accounts.clone(), self.accounts.len() as NumSeats);
+        genesis.config.protocol_version = self.protocol_version;
+        if let Some(gas_limit) = self.gas_limit {
+            genesis.config.gas_limit = gas_limit;
+        }
+        for account_id in &self.test_contract_accounts {
+            assert!(self.accounts.contains(account_id), "{account_id} is not a genesis account");
+            add_test_contract(&mut genesis, account_id);
+        }
+        let runtime_config = self.runtime_config.unwrap_or_else(|| {
+            RuntimeConfig::clone(RuntimeConfigStore::new(None).get_config(self.protocol_version))
+        });
+        RuntimeNode::new_from_parts(
+            &self.account_id,
+            genesis,
+            runtime_config,
+            self.protocol_version,
+            self.gas_limit,
+        )
+    }
+}
+
 impl Node for RuntimeNode {
     fn genesis(&self) -> &Genesis {
         &self.genesis
@@ -97,6 +195,7 @@ impl Node for RuntimeNode {
 mod tests {
     use crate::node::runtime_node::RuntimeNode;
     use crate::node::Node;
+    use near_parameters::RuntimeConfig;
     use testlib::fees_utils::FeeHelper;
     use testlib::runtime_utils::{alice_account, bob_account};
 
@@ -114,4 +213,31 @@ mod tests {
         assert_eq!(alice2, alice1 - 1 - transfer_cost);
         assert_eq!(bob2, bob1 + 1);
     }
+
+    #[test]
+    pub fn test_builder() {
+        let (alice, bob) = (alice_account(), bob_account());
+        let mut runtime_config = RuntimeConfig::test();
+        runtime_config.fees.storage_usage_config.num_bytes_account += 1;
+        let node = RuntimeNode::builder(&alice)
+            .accounts(vec![alice.clone(), bob.clone()])
+            .test_contract_accounts(vec![bob.clone()])
+            .runtime_config(runtime_config.clone())
+            .protocol_version(60)
+            .gas_limit(10u64.pow(15))
+            .build();
+        assert_eq!(node.genesis().config.protocol_version, 60);
+        assert_eq!(node.genesis().config.gas_limit, 10u64.pow(15));
+        assert_eq!(node.genesis().config.validators.len(), 2);
+        {
+            let client = node.client.read().unwrap();
+            assert_eq!(client.runtime_config, runtime_config);
+            assert_eq!((client.protocol_version, client.gas_limit), (60, Some(10u64.pow(15))));
+        }
+        assert!(!node.user().view_contract_code(&bob).unwrap().code.is_empty());
+
+        let bob1 = node.view_balance(&bob).unwrap();
+        node.user().send_money(alice, bob.clone(), 1).unwrap();
+        assert_eq!(node.view_balance(&bob).unwrap(), bob1 + 1);
+    }
 }
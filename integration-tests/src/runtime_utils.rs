@@ -64,6 +64,7 @@ pub fn get_runtime_and_trie_from_genesis(genesis: &Genesis) -> (Runtime, ShardTr
         storage_usage_config,
         genesis,
         account_ids,
+        false,
     );
     (runtime, tries, genesis_root)
 }
@@ -306,6 +306,65 @@ fn test_epoch_sync_data_hash_from_epoch_sync_info() {
     }
 }
 
+/// Check that a node that only knows genesis can record `EpochSyncInfo` of
+/// an arbitrary later epoch, and that `EpochSyncInfo` with block hashes not
+/// matching the headers is rejected.
+#[test]
+fn test_record_epoch_sync_info_from_arbitrary_epoch() {
+    init_test_logger();
+
+    let epoch_length = 5;
+    let max_height = epoch_length * 4 + 3;
+
+    let mut genesis = Genesis::test(vec!["test0".parse().unwrap(), "test1".parse().unwrap()], 1);
+    genesis.config.epoch_length = epoch_length;
+    let mut env = TestEnv::builder(&genesis.config)
+        .clients_count(2)
+        .real_stores()
+        .nightshade_runtimes(&genesis)
+        .build();
+
+    let mut last_hash = *env.clients[0].chain.genesis().hash();
+    for h in 1..max_height {
+        for tx in generate_transactions(&last_hash, h) {
+            assert_eq!(env.clients[0].process_tx(tx, false, false), ProcessTxResponse::ValidTx);
+        }
+        let block = env.clients[0].produce_block(h).unwrap().unwrap();
+        env.process_block(0, block.clone(), Provenance::PRODUCED);
+        last_hash = *block.hash();
+    }
+
+    // Skip the first two epochs.
+    let block_hash = env.clients[0].chain.get_block_hash_by_height(2 * epoch_length + 1).unwrap();
+    let epoch_id = env.clients[0].epoch_manager.get_epoch_id(&block_hash).unwrap();
+    let epoch_sync_info =
+        env.clients[0].chain.chain_store().get_epoch_sync_info(&epoch_id).unwrap();
+
+    let mut tampered_epoch_sync_info =
+        env.clients[0].chain.chain_store().get_epoch_sync_info(&epoch_id).unwrap();
+    tampered_epoch_sync_info.all_block_hashes.swap(1, 2);
+    assert!(env.clients[1]
+        .chain
+        .validate_and_record_epoch_sync_info(&tampered_epoch_sync_info)
+        .is_err());
+
+    env.clients[1].chain.validate_and_record_epoch_sync_info(&epoch_sync_info).unwrap();
+    let header_head = env.clients[1].chain.header_head().unwrap();
+    assert_eq!(&header_head.last_block_hash, epoch_sync_info.get_epoch_last_hash().unwrap());
+    assert_eq!(
+        env.clients[1]
+            .chain
+            .chain_store()
+            .get_block_merkle_tree(&header_head.last_block_hash)
+            .unwrap(),
+        env.clients[0]
+            .chain
+            .chain_store()
+            .get_block_merkle_tree(&header_head.last_block_hash)
+            .unwrap(),
+    );
+}
+
 /// This is an unreliable test that mocks/reimplements sync logic.
 /// After epoch sync is integrated into sync process we can write a better test.
 ///
@@ -24,6 +24,7 @@ mod orphan_chunk_state_witness;
 mod restore_receipts_after_fix_apply_chunks;
 mod restrict_tla;
 mod simple_test_loop_example;
+mod skip_empty_chunks;
 mod stateless_validation;
 mod storage_proof_size_limit;
 mod wallet_contract;
@@ -7,7 +7,7 @@ use near_async::test_loop::futures::{
     drive_async_computations, drive_futures, TestLoopAsyncComputationEvent,
     TestLoopDelayedActionEvent, TestLoopTask,
 };
-use near_async::test_loop::TestLoopBuilder;
+use near_async::test_loop::{TestLoop, TestLoopBuilder};
 use near_async::time::Duration;
 use near_chain::chunks_store::ReadOnlyChunksStore;
 use near_chain::state_snapshot_actor::{
@@ -39,6 +39,7 @@ use near_client::client_actor::{
 };
 use near_client::sync::sync_actor::SyncActor;
 use near_client::sync_jobs_actor::{ClientSenderForSyncJobsMessage, SyncJobsActor};
+use near_client::test_utils::test_loop::chaos::{ChaosScenario, Fault};
 use near_client::test_utils::test_loop::client_actor::{
     forward_client_messages_from_client_to_client_actor,
     forward_client_messages_from_network_to_client_actor,
@@ -60,8 +61,10 @@ use near_client::test_utils::test_loop::{
 };
 use near_client::test_utils::test_loop::{route_network_messages_to_client, ClientQueries};
 use near_client::{
-    Client, PartialWitnessActor, PartialWitnessSenderForClientMessage, SyncAdapter, SyncMessage,
+    Client, PartialWitnessActor, PartialWitnessHistory, PartialWitnessSenderForClientMessage,
+    ShadowValidator, ShadowValidatorSender, SyncAdapter, SyncMessage,
 };
+use near_crypto::KeyType;
 use near_epoch_manager::shard_tracker::{ShardTracker, TrackedConfig};
 use near_epoch_manager::EpochManager;
 use near_network::client::{
@@ -70,12 +73,14 @@ use near_network::client::{
 use near_network::shards_manager::ShardsManagerRequestFromNetwork;
 use near_network::state_sync::StateSyncResponse;
 use near_network::state_witness::PartialWitnessSenderForNetworkMessage;
+use near_network::test_loop::NetworkConditions;
 use near_network::types::{PeerManagerMessageRequest, PeerManagerMessageResponse, SetChainInfo};
 use near_primitives::network::PeerId;
 use near_primitives::shard_layout::ShardUId;
 use near_primitives::test_utils::{create_test_signer, create_user_test_signer};
 use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::AccountId;
+use near_primitives::validator_signer::{InMemoryValidatorSigner, ValidatorSigner};
 use near_store::config::StateSnapshotType;
 use near_store::genesis::initialize_genesis_state;
 use near_store::{NodeStorage, StoreConfig, TrieConfig};
@@ -174,16 +179,29 @@ enum TestEvent {
 }
 
 const ONE_NEAR: u128 = 1_000_000_000_000_000_000_000_000;
+const INITIAL_BALANCE: u128 = 10000 * ONE_NEAR;
 
-#[test]
-fn test_client_with_multi_test_loop() {
-    const NUM_CLIENTS: usize = 4;
-    const NETWORK_DELAY: Duration = Duration::milliseconds(10);
+const NUM_CLIENTS: usize = 4;
+const NETWORK_DELAY: Duration = Duration::milliseconds(10);
+
+fn accounts() -> Vec<AccountId> {
+    (0..100).map(|i| format!("account{}", i).parse().unwrap()).collect()
+}
+
+/// Sets up the nodes and starts them. The nodes keep their stores in the
+/// returned directory. If given, the chaos scenario sees all the events. With
+/// `shadow`, an extra node at index `NUM_CLIENTS` is a shadow of the first
+/// validator: the messages for the validator keep going to the first node.
+fn setup(
+    network_conditions: NetworkConditions,
+    chaos: Option<ChaosScenario<TestData>>,
+    shadow: bool,
+) -> (TestLoop<Vec<TestData>, (usize, TestEvent)>, tempfile::TempDir) {
+    let num_nodes = NUM_CLIENTS + usize::from(shadow);
     let builder = TestLoopBuilder::<(usize, TestEvent)>::new();
 
-    let initial_balance = 10000 * ONE_NEAR;
-    let accounts =
-        (0..100).map(|i| format!("account{}", i).parse().unwrap()).collect::<Vec<AccountId>>();
+    let initial_balance = INITIAL_BALANCE;
+    let accounts = accounts();
 
     let mut genesis_builder = TestGenesisBuilder::new();
     genesis_builder
@@ -207,7 +225,9 @@ fn test_client_with_multi_test_loop() {
 
     let tempdir = tempfile::tempdir().unwrap();
     let mut datas = Vec::new();
-    for idx in 0..NUM_CLIENTS {
+    for idx in 0..num_nodes {
+        let is_shadow = idx == NUM_CLIENTS;
+        let account = if is_shadow { accounts[0].clone() } else { accounts[idx].clone() };
         let mut client_config = ClientConfig::test(true, 600, 2000, 4, false, true, false, false);
         client_config.max_block_wait_delay = Duration::seconds(6);
         client_config.state_sync_enabled = true;
@@ -219,6 +239,7 @@ fn test_client_with_multi_test_loop() {
                 iteration_delay: Some(Duration::seconds(1)),
                 location: external_storage_location.clone(),
                 credentials_file: None,
+                s3_upload: Default::default(),
                 restart_dump_for_shards: None,
             }),
             sync: SyncConfig::ExternalStorage(ExternalStorageConfig {
@@ -228,6 +249,10 @@ fn test_client_with_multi_test_loop() {
             }),
         };
         client_config.tracked_shards = Vec::new();
+        if is_shadow {
+            // The validator itself dumps the state.
+            client_config.state_sync.dump = None;
+        }
 
         let homedir = tempdir.path().join(format!("{}", idx));
         std::fs::create_dir_all(&homedir).expect("Unable to create homedir");
@@ -289,7 +314,22 @@ fn test_client_with_multi_test_loop() {
         let snapshot_callbacks =
             SnapshotCallbacks { make_snapshot_callback, delete_snapshot_callback };
 
-        let validator_signer = Arc::new(create_test_signer(accounts[idx].as_str()));
+        let validator_signer: Arc<dyn ValidatorSigner> = if is_shadow {
+            Arc::new(InMemoryValidatorSigner::from_random(account.clone(), KeyType::ED25519))
+        } else {
+            Arc::new(create_test_signer(account.as_str()))
+        };
+        let shadow_validator = is_shadow.then(|| {
+            ShadowValidator::new(
+                builder.clock(),
+                store.clone(),
+                account.clone(),
+                validator_signer.public_key(),
+                &client_config,
+            )
+        });
+        let network_sender =
+            ShadowValidatorSender::new(builder.sender().for_index(idx), shadow_validator);
         let client = Client::new(
             builder.clock(),
             client_config.clone(),
@@ -298,7 +338,7 @@ fn test_client_with_multi_test_loop() {
             shard_tracker.clone(),
             state_sync_adapter,
             runtime_adapter.clone(),
-            builder.sender().for_index(idx).into_multi_sender(),
+            network_sender.clone().into_multi_sender(),
             builder.sender().for_index(idx).into_sender(),
             Some(validator_signer.clone()),
             true,
@@ -319,10 +359,10 @@ fn test_client_with_multi_test_loop() {
 
         let shards_manager = ShardsManagerActor::new(
             builder.clock(),
-            Some(accounts[idx].clone()),
+            Some(account.clone()),
             epoch_manager.clone(),
             shard_tracker.clone(),
-            builder.sender().for_index(idx).into_sender(),
+            network_sender.clone().into_sender(),
             builder.sender().for_index(idx).into_sender(),
             ReadOnlyChunksStore::new(store),
             client.chain.head().unwrap(),
@@ -330,6 +370,7 @@ fn test_client_with_multi_test_loop() {
             Duration::milliseconds(100),
         );
 
+        let partial_witness_history = PartialWitnessHistory::new();
         let client_actor = ClientActorInner::new(
             builder.clock(),
             client,
@@ -339,7 +380,7 @@ fn test_client_with_multi_test_loop() {
                 .into_wrapped_multi_sender::<ClientSenderForClientMessage, _>(),
             client_config.clone(),
             PeerId::random(),
-            builder.sender().for_index(idx).into_multi_sender(),
+            network_sender.clone().into_multi_sender(),
             None,
             noop().into_sender(),
             None,
@@ -350,18 +391,20 @@ fn test_client_with_multi_test_loop() {
                 .for_index(idx)
                 .into_wrapped_multi_sender::<SyncJobsSenderForClientMessage, _>(),
             Box::new(builder.sender().for_index(idx).into_future_spawner()),
+            partial_witness_history.clone(),
         )
         .unwrap();
 
         let partial_witness_actions = PartialWitnessActor::new(
             builder.clock(),
-            builder.sender().for_index(idx).into_multi_sender(),
+            network_sender.into_multi_sender(),
             builder
                 .sender()
                 .for_index(idx)
                 .into_wrapped_multi_sender::<ClientSenderForPartialWitnessMessage, _>(),
             validator_signer,
             epoch_manager.clone(),
+            partial_witness_history,
         );
 
         let future_spawner = builder.sender().for_index(idx).into_future_spawner();
@@ -372,7 +415,7 @@ fn test_client_with_multi_test_loop() {
             epoch_manager,
             shard_tracker,
             runtime: runtime_adapter,
-            account_id: Some(accounts[idx].clone()),
+            account_id: Some(account.clone()),
             dump_future_runner: Box::new(move |future| {
                 future_spawner.spawn_boxed("state_sync_dumper", future);
                 Box::new(|| {})
@@ -382,7 +425,7 @@ fn test_client_with_multi_test_loop() {
 
         let data = TestData {
             dummy: (),
-            account: accounts[idx].clone(),
+            account,
             client: client_actor,
             sync_jobs: sync_jobs_actor,
             shards_manager,
@@ -395,7 +438,10 @@ fn test_client_with_multi_test_loop() {
     }
 
     let mut test = builder.build(datas);
-    for idx in 0..NUM_CLIENTS {
+    if let Some(chaos) = chaos {
+        test.register_handler(chaos.into_handler());
+    }
+    for idx in 0..num_nodes {
         // Handlers that do nothing but print some information.
         test.register_handler(print_basic_client_info_before_each_event(Some(idx)).for_index(idx));
 
@@ -460,18 +506,21 @@ fn test_client_with_multi_test_loop() {
     }
     // Handles network routing. Outgoing messages are handled by emitting incoming messages to the
     // appropriate component of the appropriate node index.
-    test.register_handler(route_network_messages_to_client(test.sender(), NETWORK_DELAY));
+    test.register_handler(route_network_messages_to_client(
+        test.sender(),
+        network_conditions.clone(),
+    ));
     test.register_handler(route_shards_manager_network_messages(
         test.sender(),
         test.clock(),
-        NETWORK_DELAY,
+        network_conditions,
     ));
 
     // Bootstrap the test by starting the components.
     // We use adhoc events for these, just so that the visualizer can see these as events rather
     // than happening outside of the TestLoop framework. Other than that, we could also just remove
     // the send_adhoc_event part and the test would still work.
-    for idx in 0..NUM_CLIENTS {
+    for idx in 0..num_nodes {
         let sender = test.sender().for_index(idx);
         let shutting_down = test.shutting_down();
         test.sender().for_index(idx).send_adhoc_event("start_client", move |data| {
@@ -490,6 +539,14 @@ fn test_client_with_multi_test_loop() {
             data.state_sync_dumper.start().unwrap();
         });
     }
+    (test, tempdir)
+}
+
+#[test]
+fn test_client_with_multi_test_loop() {
+    let (mut test, _tempdir) = setup(NETWORK_DELAY.into(), None, false);
+    let initial_balance = INITIAL_BALANCE;
+    let accounts = accounts();
 
     // Give it some condition to stop running at. Here we run the test until the first client
     // reaches height 10003, with a timeout of 5sec (failing if it doesn't reach 10003 in time).
@@ -575,3 +632,95 @@ fn test_client_with_multi_test_loop() {
     // be important for properly shutting down the nodes.
     test.shutdown_and_drain_remaining_events(Duration::seconds(20));
 }
+
+/// Runs the nodes through a network partition and a crash, checking that the
+/// faults are injected at the scheduled heights.
+#[test]
+fn test_chaos_scenario() {
+    let network_conditions = NetworkConditions::from(NETWORK_DELAY);
+    let chaos = ChaosScenario::new(network_conditions.clone())
+        .at_height(10005, Fault::Partition(vec![vec![0, 1, 2], vec![3]]))
+        .at_height(10015, Fault::HealPartition)
+        .at_height(10025, Fault::Crash(3));
+    let (mut test, _tempdir) = setup(network_conditions, Some(chaos), false);
+
+    // The other nodes hold enough stake to keep producing blocks while the
+    // last node is cut off, so it falls behind.
+    test.run_until(
+        |data| data[0].client.client.chain.head().unwrap().height >= 10015,
+        Duration::seconds(20),
+    );
+    let isolated_height = test.data[3].client.client.chain.head().unwrap().height;
+    assert!(isolated_height < 10010, "The isolated node reached height {isolated_height}");
+
+    // Once the partition is healed, the node catches up.
+    test.run_until(
+        |data| data[3].client.client.chain.head().unwrap().height >= 10020,
+        Duration::seconds(20),
+    );
+
+    // The crashed node doesn't process any events, while the others go on.
+    test.run_until(
+        |data| data[0].client.client.chain.head().unwrap().height >= 10035,
+        Duration::seconds(20),
+    );
+    let crashed_height = test.data[3].client.client.chain.head().unwrap().height;
+    assert!(crashed_height < 10030, "The crashed node reached height {crashed_height}");
+
+    for idx in 0..NUM_CLIENTS {
+        test.data[idx].state_sync_dumper.stop();
+    }
+    test.shutdown_and_drain_remaining_events(Duration::seconds(20));
+}
+
+/// Returns the number of the messages of the given kind dropped by the shadow
+/// validators of the process.
+fn shadow_validator_messages(kind: &str) -> u64 {
+    let families = near_o11y::metrics::prometheus::gather();
+    let Some(family) =
+        families.iter().find(|family| family.get_name() == "near_shadow_validator_messages_total")
+    else {
+        return 0;
+    };
+    family
+        .get_metric()
+        .iter()
+        .filter(|metric| {
+            metric
+                .get_label()
+                .iter()
+                .any(|label| label.get_name() == "kind" && label.get_value() == kind)
+        })
+        .map(|metric| metric.get_counter().get_value() as u64)
+        .sum()
+}
+
+/// The shadow of a validator follows the chain and produces the approvals and
+/// chunks of the validator without sending them, while the validator itself
+/// keeps getting the messages meant for it. The shadow produces no blocks nor
+/// endorsements, which need these messages.
+#[test]
+fn test_shadow_validator() {
+    let (mut test, _tempdir) = setup(NETWORK_DELAY.into(), None, true);
+
+    test.run_until(
+        |data| data[0].client.client.chain.head().unwrap().height >= 10025,
+        Duration::seconds(20),
+    );
+    // The chain isn't disturbed by the shadow: the chunks of the validator are
+    // all there.
+    let block = test.data[0].client.client.chain.get_block_by_height(10024).unwrap();
+    assert!(block.header().chunk_mask().iter().all(|included| *included));
+    let shadow_height = test.data[NUM_CLIENTS].client.client.chain.head().unwrap().height;
+    assert!(shadow_height >= 10020, "The shadow validator is at height {shadow_height}");
+
+    assert!(shadow_validator_messages("approval") > 0);
+    assert!(shadow_validator_messages("chunk_parts") > 0);
+    assert_eq!(shadow_validator_messages("block"), 0);
+    assert_eq!(shadow_validator_messages("chunk_endorsement"), 0);
+
+    for idx in 0..=NUM_CLIENTS {
+        test.data[idx].state_sync_dumper.stop();
+    }
+    test.shutdown_and_drain_remaining_events(Duration::seconds(20));
+}
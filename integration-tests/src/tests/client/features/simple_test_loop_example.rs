@@ -26,7 +26,7 @@ use near_client::test_utils::test_loop::client_actor::{
 };
 use near_client::test_utils::test_loop::sync_jobs_actor::forward_messages_from_client_to_sync_jobs_actor;
 use near_client::test_utils::{MAX_BLOCK_PROD_TIME, MIN_BLOCK_PROD_TIME};
-use near_client::{Client, SyncAdapter, SyncMessage};
+use near_client::{Client, PartialWitnessHistory, SyncAdapter, SyncMessage};
 use near_epoch_manager::shard_tracker::{ShardTracker, TrackedConfig};
 use near_epoch_manager::EpochManager;
 use near_network::client::ClientSenderForNetworkMessage;
@@ -175,6 +175,7 @@ fn test_client_with_simple_test_loop() {
         None,
         builder.sender().into_wrapped_multi_sender::<SyncJobsSenderForClientMessage, _>(),
         Box::new(builder.sender().into_future_spawner()),
+        PartialWitnessHistory::new(),
     )
     .unwrap();
 
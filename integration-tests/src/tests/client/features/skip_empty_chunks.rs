@@ -0,0 +1,99 @@
+use near_chain::{Chain, Provenance};
+use near_chain_configs::Genesis;
+use near_client::test_utils::TestEnv;
+use near_o11y::testonly::init_test_logger;
+use near_primitives::chains::BENCHMARKNET;
+use near_primitives::types::{BlockHeight, ValidatorInfoIdentifier};
+use near_primitives::version::{ProtocolFeature, ProtocolVersion, PROTOCOL_VERSION};
+use nearcore::test_utils::TestEnvNightshadeSetupExt;
+
+/// The chunks for these heights are not produced.
+fn is_chunk_missed(height: BlockHeight) -> bool {
+    height % 4 == 1
+}
+
+/// Returns the number of chunks test0 was expected to produce in the epoch
+/// but didn't, as counted at the given height.
+fn num_missed_chunks(env: &TestEnv, height: BlockHeight) -> u64 {
+    let client = &env.clients[0];
+    let block_hash = *client.chain.get_block_by_height(height).unwrap().hash();
+    let info =
+        client.epoch_manager.get_validator_info(ValidatorInfoIdentifier::BlockHash(block_hash));
+    let validator = info.unwrap().current_validators.into_iter().next().unwrap();
+    validator.num_expected_chunks - validator.num_produced_chunks
+}
+
+/// Produces blocks on a chain with the given id and protocol version, with
+/// some chunks missing on top of blocks without receipts. Returns the number
+/// of missed chunks counted by the epoch manager.
+fn run_with_missed_chunks(chain_id: &str, protocol_version: ProtocolVersion) -> u64 {
+    init_test_logger();
+    let mut genesis = Genesis::test(vec!["test0".parse().unwrap()], 1);
+    genesis.config.chain_id = chain_id.to_string();
+    genesis.config.protocol_version = protocol_version;
+    genesis.config.epoch_length = 100;
+    let mut env = TestEnv::builder(&genesis.config).nightshade_runtimes(&genesis).build();
+
+    let final_height = 20;
+    let mut baseline = None;
+    for height in 1..=final_height {
+        let block = env.clients[0].produce_block(height).unwrap().unwrap();
+        let client = &mut env.clients[0];
+        if is_chunk_missed(height + 1) {
+            client.process_block_test_no_produce_chunk(block.into(), Provenance::PRODUCED).unwrap();
+        } else {
+            client.process_block_test(block.into(), Provenance::PRODUCED).unwrap();
+        }
+        if height == 2 {
+            baseline = Some(num_missed_chunks(&env, height));
+        }
+    }
+
+    let client = &env.clients[0];
+    let epoch_manager = client.epoch_manager.as_ref();
+    let skips_empty_chunks = Chain::skips_empty_chunks(epoch_manager, protocol_version);
+    for height in 3..=final_height {
+        let header = client.chain.get_block_header_by_height(height).unwrap();
+        let prev_header = client.chain.get_block_header(header.prev_hash()).unwrap();
+        assert!(Chain::block_has_no_receipts(epoch_manager, &prev_header).unwrap());
+        assert_eq!(header.chunk_mask(), &[!is_chunk_missed(height)]);
+        let mask = Chain::chunk_production_mask(epoch_manager, &header, &prev_header).unwrap();
+        if skips_empty_chunks {
+            assert_eq!(mask, vec![true]);
+        } else {
+            assert_eq!(mask, header.chunk_mask());
+        }
+    }
+    num_missed_chunks(&env, final_height) - baseline.unwrap()
+}
+
+/// The chunks for heights 5, 9, 13 and 17.
+const NUM_MISSING_CHUNKS: u64 = 4;
+
+/// On the benchmark networks, the chunks missing on top of blocks without
+/// receipts count as produced empty chunks.
+#[test]
+fn test_skipped_empty_chunks_count_as_produced_on_benchmarknet() {
+    if !ProtocolFeature::SkipEmptyChunks.enabled(PROTOCOL_VERSION) {
+        return;
+    }
+    let chain_id = format!("{}-test", BENCHMARKNET);
+    assert_eq!(run_with_missed_chunks(&chain_id, PROTOCOL_VERSION), 0);
+}
+
+/// Before `SkipEmptyChunks`, every missing chunk counts as missed on the
+/// benchmark networks too, whatever the binary.
+#[test]
+fn test_missing_chunks_count_as_missed_before_skip_empty_chunks() {
+    let chain_id = format!("{}-test", BENCHMARKNET);
+    let protocol_version =
+        PROTOCOL_VERSION.min(ProtocolFeature::SkipEmptyChunks.protocol_version() - 1);
+    assert_eq!(run_with_missed_chunks(&chain_id, protocol_version), NUM_MISSING_CHUNKS);
+}
+
+/// On the other chains every missing chunk counts as missed, so that the
+/// kickouts and the rewards are not affected.
+#[test]
+fn test_missing_chunks_count_as_missed() {
+    assert_eq!(run_with_missed_chunks("unittest", PROTOCOL_VERSION), NUM_MISSING_CHUNKS);
+}
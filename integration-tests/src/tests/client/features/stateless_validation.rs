@@ -1,3 +1,4 @@
+use assert_matches::assert_matches;
 use near_epoch_manager::{EpochManager, EpochManagerAdapter};
 use near_primitives::stateless_validation::{ChunkStateWitness, EncodedChunkStateWitness};
 use near_store::test_utils::create_test_store;
@@ -5,14 +6,17 @@ use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 use std::collections::HashSet;
 
-use near_chain::Provenance;
+use near_chain::{Error, Provenance};
 use near_chain_configs::{Genesis, GenesisConfig, GenesisRecords};
 use near_client::test_utils::TestEnv;
-use near_crypto::{InMemorySigner, KeyType};
+use near_client::ProcessingDoneTracker;
+use near_crypto::{InMemorySigner, KeyType, Signature};
+use near_network::types::{NetworkRequests, PeerManagerMessageRequest};
 use near_o11y::testonly::init_integration_logger;
 use near_primitives::epoch_manager::AllEpochConfigTestOverrides;
 use near_primitives::num_rational::Rational32;
 use near_primitives::shard_layout::ShardLayout;
+use near_primitives::sharding::ShardChunkHeader;
 use near_primitives::state_record::StateRecord;
 use near_primitives::test_utils::create_test_signer;
 use near_primitives::transaction::SignedTransaction;
@@ -99,6 +103,7 @@ fn run_chunk_validation_test(seed: u64, prob_missing_chunk: f64) {
     let epoch_config_test_overrides = Some(AllEpochConfigTestOverrides {
         block_producer_kickout_threshold: Some(0),
         chunk_producer_kickout_threshold: Some(0),
+        ..Default::default()
     });
 
     // Set up the records corresponding to the validator accounts.
@@ -347,3 +352,136 @@ fn test_chunk_state_witness_bad_shard_id() {
     tracing::info!(target: "test", "error message: {}", error_message);
     assert!(error_message.contains("shard"));
 }
+
+/// The implicit transitions of missing chunks are applied on top of the post
+/// state roots claimed by the witness, so a witness with a tampered post state
+/// root of an implicit transition must be rejected.
+#[test]
+fn test_chunk_state_witness_tampered_implicit_transition() {
+    init_integration_logger();
+
+    if !checked_feature!("stable", StatelessValidationV0, PROTOCOL_VERSION) {
+        println!("Test not applicable without StatelessValidation enabled");
+        return;
+    }
+
+    let accounts = vec!["test0".parse().unwrap()];
+    let genesis = Genesis::test(accounts.clone(), 1);
+    let mut env = TestEnv::builder(&genesis.config)
+        .validators(accounts)
+        .nightshade_runtimes(&genesis)
+        .build();
+
+    // No chunk is produced for the block at height 4, so the witness of the
+    // chunk at height 5 contains an implicit transition.
+    for height in 1..6 {
+        let block = env.clients[0].produce_block(height).unwrap().unwrap();
+        if height == 3 {
+            env.clients[0]
+                .process_block_test_no_produce_chunk(block.into(), Provenance::PRODUCED)
+                .unwrap();
+        } else {
+            env.process_block(0, block, Provenance::PRODUCED);
+        }
+    }
+    let mut witness =
+        std::iter::from_fn(|| env.partial_witness_adapters[0].pop_distribution_request())
+            .map(|request| request.state_witness)
+            .find(|witness| !witness.implicit_transitions.is_empty())
+            .expect("no state witness with an implicit transition");
+    env.clients[0].validate_chunk_state_witness_test(witness.clone()).unwrap();
+
+    witness.implicit_transitions[0].post_state_root = CryptoHash::hash_bytes(b"tampered");
+    let error = env.clients[0].validate_chunk_state_witness_test(witness).unwrap_err();
+    assert!(error.to_string().contains("implicit transition"), "unexpected error: {error}");
+}
+
+/// Processes the state witness on the client and returns the number of the
+/// endorsements of the chunk it sent.
+fn process_witness_and_count_endorsements(
+    env: &mut TestEnv,
+    client_idx: usize,
+    witness: &ChunkStateWitness,
+) -> Result<usize, Error> {
+    let chunk_hash = witness.chunk_header.chunk_hash();
+    let encoded_witness = EncodedChunkStateWitness::encode(witness).unwrap().0;
+    let processing_done_tracker = ProcessingDoneTracker::new();
+    let waiter = processing_done_tracker.make_waiter();
+    env.clients[client_idx]
+        .process_chunk_state_witness(encoded_witness, Some(processing_done_tracker))?;
+    waiter.wait();
+
+    let mut num_endorsements = 0;
+    env.network_adapters[client_idx].handle_filtered(|request| match &request {
+        PeerManagerMessageRequest::NetworkRequests(NetworkRequests::ChunkEndorsement(
+            _,
+            endorsement,
+        )) if endorsement.chunk_hash() == &chunk_hash => {
+            num_endorsements += 1;
+            None
+        }
+        _ => Some(request),
+    });
+    Ok(num_endorsements)
+}
+
+/// A chunk whose state witness was validated successfully is endorsed again
+/// without validating the state witness it is received with again.
+#[test]
+fn test_validated_chunk_endorsed_again() {
+    init_integration_logger();
+
+    if !checked_feature!("stable", StatelessValidationV0, PROTOCOL_VERSION) {
+        println!("Test not applicable without StatelessValidation enabled");
+        return;
+    }
+
+    // Only test0 is a validator. test1 doesn't track the shard, so it has no
+    // chunk extra and validates the chunk with the state witness.
+    let accounts: Vec<AccountId> = vec!["test0".parse().unwrap(), "test1".parse().unwrap()];
+    let genesis = Genesis::test(accounts.clone(), 1);
+    let mut env = TestEnv::builder(&genesis.config)
+        .clients(accounts)
+        .validators(vec!["test0".parse().unwrap()])
+        .nightshade_runtimes(&genesis)
+        .build();
+
+    for height in 1..6 {
+        let block = env.clients[0].produce_block(height).unwrap().unwrap();
+        for i in 0..env.clients.len() {
+            let blocks_processed =
+                env.clients[i].process_block_test(block.clone().into(), Provenance::NONE).unwrap();
+            assert_eq!(blocks_processed, vec![*block.hash()]);
+        }
+        env.process_partial_encoded_chunks();
+        for i in 0..env.clients.len() {
+            env.process_shards_manager_responses_and_finish_processing_blocks(i);
+        }
+        if height < 5 {
+            env.propagate_chunk_state_witnesses_and_endorsements(false);
+        }
+    }
+    let witness = std::iter::from_fn(|| env.partial_witness_adapters[0].pop_distribution_request())
+        .map(|request| request.state_witness)
+        .last()
+        .expect("no state witness");
+    assert_eq!(process_witness_and_count_endorsements(&mut env, 1, &witness).unwrap(), 1);
+
+    // The tampered witness is invalid, but the chunk is already validated.
+    let mut tampered_witness = witness;
+    tampered_witness.main_state_transition.post_state_root = CryptoHash::hash_bytes(b"tampered");
+    env.clients[1].validate_chunk_state_witness_test(tampered_witness.clone()).unwrap_err();
+    assert_eq!(process_witness_and_count_endorsements(&mut env, 1, &tampered_witness).unwrap(), 1);
+
+    // The chunk header of the witness isn't signed by the chunk producer, so
+    // the chunk isn't endorsed again.
+    let mut forged_witness = tampered_witness;
+    let signature = Signature::empty(KeyType::ED25519);
+    match &mut forged_witness.chunk_header {
+        ShardChunkHeader::V1(header) => header.signature = signature,
+        ShardChunkHeader::V2(header) => header.signature = signature,
+        ShardChunkHeader::V3(header) => header.signature = signature,
+    }
+    let result = process_witness_and_count_endorsements(&mut env, 1, &forged_witness);
+    assert_matches!(result, Err(Error::InvalidChunkStateWitness(_)));
+}
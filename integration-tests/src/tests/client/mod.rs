@@ -8,6 +8,8 @@ mod epoch_sync;
 mod features;
 mod flat_storage;
 mod process_blocks;
+#[cfg(feature = "test_features")]
+mod protocol_upgrade_scenario;
 mod resharding;
 mod runtimes;
 #[cfg(feature = "sandbox")]
@@ -0,0 +1,550 @@
+//! TestLoop scenario of a protocol upgrade, for testing the behavior across
+//! the boundary of the protocol version enabling a feature.
+//!
+//! The validators start at the old protocol version and vote for the next one
+//! from the chosen epoch on, regardless of the upgrade schedule, so that the
+//! chain upgrades two epochs later. The hooks run on the test loop before the
+//! voting starts and once the chain is at the new version.
+use derive_enum_from_into::{EnumFrom, EnumTryInto};
+use near_async::messaging::{noop, IntoMultiSender, IntoSender, MessageWithCallback, SendAsync};
+use near_async::test_loop::adhoc::{handle_adhoc_events, AdhocEvent, AdhocEventSender};
+use near_async::test_loop::event_handler::ignore_events;
+use near_async::test_loop::futures::{
+    drive_async_computations, drive_futures, TestLoopAsyncComputationEvent,
+    TestLoopDelayedActionEvent, TestLoopTask,
+};
+use near_async::test_loop::{TestLoop, TestLoopBuilder};
+use near_async::time::Duration;
+use near_chain::chunks_store::ReadOnlyChunksStore;
+use near_chain::ChainGenesis;
+use near_chain_configs::test_genesis::TestGenesisBuilder;
+use near_chain_configs::ClientConfig;
+use near_chunks::adapter::ShardsManagerRequestFromClient;
+use near_chunks::client::ShardsManagerResponse;
+use near_chunks::shards_manager_actor::ShardsManagerActor;
+use near_chunks::test_loop::{
+    forward_client_request_to_shards_manager, forward_network_request_to_shards_manager,
+    route_shards_manager_network_messages,
+};
+use near_client::client_actor::{
+    ClientActorInner, ClientSenderForClientMessage, ClientSenderForPartialWitnessMessage,
+    SyncJobsSenderForClientMessage,
+};
+use near_client::sync_jobs_actor::{ClientSenderForSyncJobsMessage, SyncJobsActor};
+use near_client::test_utils::test_loop::client_actor::{
+    forward_client_messages_from_client_to_client_actor,
+    forward_client_messages_from_network_to_client_actor,
+    forward_client_messages_from_shards_manager, forward_client_messages_from_sync_adapter,
+    forward_client_messages_from_sync_jobs_to_client_actor,
+};
+use near_client::test_utils::test_loop::partial_witness_actor::{
+    forward_messages_from_client_to_partial_witness_actor,
+    forward_messages_from_network_to_partial_witness_actor,
+};
+use near_client::test_utils::test_loop::sync_actor::{
+    forward_sync_actor_messages_from_client, forward_sync_actor_messages_from_network,
+    test_loop_sync_actor_maker, TestSyncActors,
+};
+use near_client::test_utils::test_loop::sync_jobs_actor::forward_messages_from_client_to_sync_jobs_actor;
+use near_client::test_utils::test_loop::{
+    forward_messages_from_partial_witness_actor_to_client, route_network_messages_to_client,
+    ClientQueries,
+};
+use near_client::{
+    Client, PartialWitnessActor, PartialWitnessHistory, PartialWitnessSenderForClientMessage,
+    SyncAdapter, SyncMessage,
+};
+use near_epoch_manager::shard_tracker::{ShardTracker, TrackedConfig};
+use near_epoch_manager::EpochManager;
+use near_network::client::{
+    ClientSenderForNetwork, ClientSenderForNetworkMessage, ProcessTxRequest,
+};
+use near_network::shards_manager::ShardsManagerRequestFromNetwork;
+use near_network::state_sync::StateSyncResponse;
+use near_network::state_witness::PartialWitnessSenderForNetworkMessage;
+use near_network::types::{PeerManagerMessageRequest, PeerManagerMessageResponse, SetChainInfo};
+use near_o11y::testonly::init_test_logger;
+use near_primitives::network::PeerId;
+use near_primitives::shard_layout::ShardUId;
+use near_primitives::test_utils::{create_test_signer, create_user_test_signer};
+use near_primitives::transaction::SignedTransaction;
+use near_primitives::types::{AccountId, Balance, BlockHeightDelta, EpochHeight, ProtocolVersion};
+use near_primitives::version::PROTOCOL_VERSION;
+use near_store::genesis::initialize_genesis_state;
+use near_store::test_utils::create_test_store;
+use nearcore::NightshadeRuntime;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+
+const ONE_NEAR: u128 = 1_000_000_000_000_000_000_000_000;
+const NETWORK_DELAY: Duration = Duration::milliseconds(10);
+
+/// Balance of the accounts in the genesis.
+pub(crate) const INITIAL_BALANCE: Balance = 10000 * ONE_NEAR;
+
+#[derive(derive_more::AsMut, derive_more::AsRef)]
+pub(crate) struct TestData {
+    pub dummy: (),
+    pub account: AccountId,
+    pub client: ClientActorInner,
+    pub sync_jobs: SyncJobsActor,
+    pub shards_manager: ShardsManagerActor,
+    pub partial_witness: PartialWitnessActor,
+    pub sync_actors: TestSyncActors,
+}
+
+impl AsMut<TestData> for TestData {
+    fn as_mut(&mut self) -> &mut Self {
+        self
+    }
+}
+
+impl AsRef<Client> for TestData {
+    fn as_ref(&self) -> &Client {
+        &self.client.client
+    }
+}
+
+#[derive(EnumTryInto, Debug, EnumFrom)]
+#[allow(clippy::large_enum_variant)]
+pub(crate) enum TestEvent {
+    Task(Arc<TestLoopTask>),
+    Adhoc(AdhocEvent<TestData>),
+    AsyncComputation(TestLoopAsyncComputationEvent),
+    ClientDelayedActions(TestLoopDelayedActionEvent<ClientActorInner>),
+    ShardsManagerDelayedActions(TestLoopDelayedActionEvent<ShardsManagerActor>),
+    SyncJobsDelayedActions(TestLoopDelayedActionEvent<SyncJobsActor>),
+    ClientEventFromNetwork(ClientSenderForNetworkMessage),
+    ClientEventFromClient(ClientSenderForClientMessage),
+    ClientEventFromSyncJobs(ClientSenderForSyncJobsMessage),
+    ClientEventFromShardsManager(ShardsManagerResponse),
+    ClientEventFromStateSyncAdapter(SyncMessage),
+    SyncJobsEventFromClient(SyncJobsSenderForClientMessage),
+    SyncActorEventFromClient((ShardUId, SyncMessage)),
+    SyncActorEventFromNetwork((ShardUId, StateSyncResponse)),
+    ShardsManagerRequestFromClient(ShardsManagerRequestFromClient),
+    ShardsManagerRequestFromNetwork(ShardsManagerRequestFromNetwork),
+    OutgoingNetworkMessage(PeerManagerMessageRequest),
+    OutgoingNetworkMessageForResult(
+        MessageWithCallback<PeerManagerMessageRequest, PeerManagerMessageResponse>,
+    ),
+    SetChainInfo(SetChainInfo),
+    PartialWitnessSenderForClient(PartialWitnessSenderForClientMessage),
+    PartialWitnessSenderForNetwork(PartialWitnessSenderForNetworkMessage),
+    ClientSenderForPartialWitness(ClientSenderForPartialWitnessMessage),
+}
+
+pub(crate) type ProtocolUpgradeTestLoop = TestLoop<Vec<TestData>, (usize, TestEvent)>;
+
+type Hook = Box<dyn FnOnce(&mut ProtocolUpgradeTestLoop)>;
+
+/// Builder of the scenario, see the module comment.
+pub(crate) struct ProtocolUpgradeScenario {
+    old_protocol_version: ProtocolVersion,
+    num_validators: usize,
+    num_accounts: usize,
+    epoch_length: BlockHeightDelta,
+    voting_epoch_height: EpochHeight,
+    before_upgrade: Option<Hook>,
+    after_upgrade: Option<Hook>,
+}
+
+impl ProtocolUpgradeScenario {
+    /// Scenario of the upgrade from the protocol version to the next one.
+    pub fn new(old_protocol_version: ProtocolVersion) -> Self {
+        assert!(
+            old_protocol_version < PROTOCOL_VERSION,
+            "cannot upgrade past the protocol version of the client"
+        );
+        Self {
+            old_protocol_version,
+            num_validators: 4,
+            num_accounts: 10,
+            epoch_length: 20,
+            voting_epoch_height: 3,
+            before_upgrade: None,
+            after_upgrade: None,
+        }
+    }
+
+    /// Number of the validators, each running a node. Defaults to 4.
+    pub fn num_validators(mut self, num_validators: usize) -> Self {
+        self.num_validators = num_validators;
+        self
+    }
+
+    /// Number of the accounts `account0`, `account1`, ... in the genesis, the
+    /// first of them the validators. Defaults to 10.
+    pub fn num_accounts(mut self, num_accounts: usize) -> Self {
+        self.num_accounts = num_accounts;
+        self
+    }
+
+    pub fn epoch_length(mut self, epoch_length: BlockHeightDelta) -> Self {
+        self.epoch_length = epoch_length;
+        self
+    }
+
+    /// Height of the epoch from which on the validators vote for the new
+    /// protocol version, so that it is enabled 2 epochs later. Defaults to 3.
+    pub fn voting_epoch_height(mut self, voting_epoch_height: EpochHeight) -> Self {
+        self.voting_epoch_height = voting_epoch_height;
+        self
+    }
+
+    /// Sets the hook running once the chain is started, at the old protocol
+    /// version. It must not run the test loop into the voting epoch.
+    pub fn before_upgrade(
+        mut self,
+        hook: impl FnOnce(&mut ProtocolUpgradeTestLoop) + 'static,
+    ) -> Self {
+        self.before_upgrade = Some(Box::new(hook));
+        self
+    }
+
+    /// Sets the hook running once all the nodes are at the new protocol
+    /// version.
+    pub fn after_upgrade(
+        mut self,
+        hook: impl FnOnce(&mut ProtocolUpgradeTestLoop) + 'static,
+    ) -> Self {
+        self.after_upgrade = Some(Box::new(hook));
+        self
+    }
+
+    pub fn accounts(&self) -> Vec<AccountId> {
+        (0..self.num_accounts).map(|i| format!("account{}", i).parse().unwrap()).collect()
+    }
+
+    /// Runs the scenario, panicking if the chain didn't upgrade as scheduled.
+    pub fn run(self) {
+        init_test_logger();
+        assert!(self.num_validators <= self.num_accounts);
+        let new_protocol_version = self.old_protocol_version + 1;
+        let mut test = self.build();
+
+        if let Some(hook) = self.before_upgrade {
+            hook(&mut test);
+        }
+        // The blocks are produced at most every 2 seconds.
+        let epoch_duration = Duration::seconds(2 * self.epoch_length as i64);
+        let voting_epoch_height = self.voting_epoch_height;
+        test.run_until(
+            |data| head_epoch_height(&data[0].client.client) >= voting_epoch_height,
+            epoch_duration * voting_epoch_height as i32,
+        );
+        for data in &test.data {
+            assert_eq!(head_protocol_version(&data.client.client), self.old_protocol_version);
+        }
+
+        for data in &mut test.data {
+            data.client.client.adv_protocol_version_vote = Some(new_protocol_version);
+        }
+        test.run_until(
+            |data| {
+                data.iter()
+                    .all(|data| head_protocol_version(&data.client.client) == new_protocol_version)
+            },
+            epoch_duration * 3,
+        );
+        for data in &test.data {
+            assert_eq!(head_epoch_height(&data.client.client), voting_epoch_height + 2);
+        }
+
+        if let Some(hook) = self.after_upgrade {
+            hook(&mut test);
+        }
+        test.shutdown_and_drain_remaining_events(Duration::seconds(20));
+    }
+
+    fn build(&self) -> ProtocolUpgradeTestLoop {
+        let builder = TestLoopBuilder::<(usize, TestEvent)>::new();
+        let accounts = self.accounts();
+        let validators = &accounts[..self.num_validators];
+
+        let mut genesis_builder = TestGenesisBuilder::new();
+        genesis_builder
+            .genesis_time_from_clock(&builder.clock())
+            .protocol_version(self.old_protocol_version)
+            .genesis_height(10000)
+            .gas_prices_free()
+            .gas_limit_one_petagas()
+            .shard_layout_simple_v1(&["account3", "account5", "account7"])
+            .transaction_validity_period(1000)
+            .epoch_length(self.epoch_length)
+            .validators_desired_roles(
+                &validators.iter().map(|account| account.as_str()).collect::<Vec<_>>(),
+                &[],
+            )
+            // The validators missing blocks must not lose their votes.
+            .kickouts_disabled();
+        for account in &accounts {
+            genesis_builder.add_user_account_simple(account.clone(), INITIAL_BALANCE);
+        }
+        let genesis = genesis_builder.build();
+
+        let tempdir = tempfile::tempdir().unwrap();
+        let mut datas = Vec::new();
+        for (idx, account) in validators.iter().enumerate() {
+            let mut client_config =
+                ClientConfig::test(true, 600, 2000, 4, false, true, false, false);
+            client_config.max_block_wait_delay = Duration::seconds(6);
+            client_config.tracked_shards = vec![0];
+
+            let homedir = tempdir.path().join(format!("{}", idx));
+            std::fs::create_dir_all(&homedir).expect("Unable to create homedir");
+            let store = create_test_store();
+            initialize_genesis_state(store.clone(), &genesis, None);
+
+            let sync_jobs_actor = SyncJobsActor::new(
+                builder
+                    .sender()
+                    .for_index(idx)
+                    .into_wrapped_multi_sender::<ClientSenderForSyncJobsMessage, _>(),
+            );
+            let chain_genesis = ChainGenesis::new(&genesis.config);
+            let epoch_manager = EpochManager::new_arc_handle(store.clone(), &genesis.config);
+            let shard_tracker = ShardTracker::new(
+                TrackedConfig::from_config(&client_config),
+                epoch_manager.clone(),
+            );
+            let sync_actors = Arc::new(Mutex::new(HashMap::new()));
+            let state_sync_adapter = Arc::new(RwLock::new(SyncAdapter::new(
+                builder.sender().for_index(idx).into_sender(),
+                builder.sender().for_index(idx).into_sender(),
+                test_loop_sync_actor_maker(builder.sender().for_index(idx), sync_actors.clone()),
+            )));
+            let runtime_adapter = NightshadeRuntime::test(
+                &homedir,
+                store.clone(),
+                &genesis.config,
+                epoch_manager.clone(),
+            );
+
+            let validator_signer = Arc::new(create_test_signer(account.as_str()));
+            let mut client = Client::new(
+                builder.clock(),
+                client_config.clone(),
+                chain_genesis,
+                epoch_manager.clone(),
+                shard_tracker.clone(),
+                state_sync_adapter,
+                runtime_adapter,
+                builder.sender().for_index(idx).into_multi_sender(),
+                builder.sender().for_index(idx).into_sender(),
+                Some(validator_signer.clone()),
+                true,
+                [0; 32],
+                None,
+                Arc::new(
+                    builder
+                        .sender()
+                        .for_index(idx)
+                        .into_async_computation_spawner(|_| Duration::milliseconds(80)),
+                ),
+                builder
+                    .sender()
+                    .for_index(idx)
+                    .into_wrapped_multi_sender::<PartialWitnessSenderForClientMessage, _>(),
+            )
+            .unwrap();
+            client.adv_protocol_version_vote = Some(self.old_protocol_version);
+
+            let shards_manager = ShardsManagerActor::new(
+                builder.clock(),
+                Some(account.clone()),
+                epoch_manager.clone(),
+                shard_tracker,
+                builder.sender().for_index(idx).into_sender(),
+                builder.sender().for_index(idx).into_sender(),
+                ReadOnlyChunksStore::new(store),
+                client.chain.head().unwrap(),
+                client.chain.header_head().unwrap(),
+                Duration::milliseconds(100),
+            );
+
+            let partial_witness_history = PartialWitnessHistory::new();
+            let client_actor = ClientActorInner::new(
+                builder.clock(),
+                client,
+                builder
+                    .sender()
+                    .for_index(idx)
+                    .into_wrapped_multi_sender::<ClientSenderForClientMessage, _>(),
+                client_config,
+                PeerId::random(),
+                builder.sender().for_index(idx).into_multi_sender(),
+                None,
+                noop().into_sender(),
+                None,
+                Default::default(),
+                None,
+                builder
+                    .sender()
+                    .for_index(idx)
+                    .into_wrapped_multi_sender::<SyncJobsSenderForClientMessage, _>(),
+                Box::new(builder.sender().for_index(idx).into_future_spawner()),
+                partial_witness_history.clone(),
+            )
+            .unwrap();
+
+            let partial_witness = PartialWitnessActor::new(
+                builder.clock(),
+                builder.sender().for_index(idx).into_multi_sender(),
+                builder
+                    .sender()
+                    .for_index(idx)
+                    .into_wrapped_multi_sender::<ClientSenderForPartialWitnessMessage, _>(),
+                validator_signer,
+                epoch_manager,
+                partial_witness_history,
+            );
+
+            datas.push(TestData {
+                dummy: (),
+                account: account.clone(),
+                client: client_actor,
+                sync_jobs: sync_jobs_actor,
+                shards_manager,
+                partial_witness,
+                sync_actors,
+            });
+        }
+
+        let mut test = builder.build(datas);
+        for idx in 0..self.num_validators {
+            test.register_handler(drive_futures().widen().for_index(idx));
+            test.register_handler(handle_adhoc_events::<TestData>().widen().for_index(idx));
+            test.register_handler(drive_async_computations().widen().for_index(idx));
+            test.register_delayed_action_handler_for_index::<ClientActorInner>(idx);
+            test.register_delayed_action_handler_for_index::<ShardsManagerActor>(idx);
+            test.register_handler(
+                forward_client_messages_from_network_to_client_actor().widen().for_index(idx),
+            );
+            test.register_handler(
+                forward_client_messages_from_client_to_client_actor().widen().for_index(idx),
+            );
+            test.register_handler(
+                forward_client_messages_from_sync_jobs_to_client_actor().widen().for_index(idx),
+            );
+            test.register_handler(
+                forward_client_messages_from_shards_manager().widen().for_index(idx),
+            );
+            test.register_handler(
+                forward_messages_from_partial_witness_actor_to_client().widen().for_index(idx),
+            );
+            test.register_handler(
+                forward_client_messages_from_sync_adapter().widen().for_index(idx),
+            );
+            test.register_handler(
+                forward_messages_from_client_to_sync_jobs_actor(
+                    test.sender().for_index(idx).into_delayed_action_runner(test.shutting_down()),
+                )
+                .widen()
+                .for_index(idx),
+            );
+            test.register_handler(forward_sync_actor_messages_from_client().widen().for_index(idx));
+            test.register_handler(
+                forward_sync_actor_messages_from_network().widen().for_index(idx),
+            );
+            test.register_handler(
+                forward_client_request_to_shards_manager().widen().for_index(idx),
+            );
+            test.register_handler(
+                forward_network_request_to_shards_manager().widen().for_index(idx),
+            );
+            test.register_handler(ignore_events::<SetChainInfo>().widen().for_index(idx));
+            test.register_handler(
+                forward_messages_from_client_to_partial_witness_actor().widen().for_index(idx),
+            );
+            test.register_handler(
+                forward_messages_from_network_to_partial_witness_actor().widen().for_index(idx),
+            );
+        }
+        test.register_handler(route_network_messages_to_client(test.sender(), NETWORK_DELAY));
+        test.register_handler(route_shards_manager_network_messages(
+            test.sender(),
+            test.clock(),
+            NETWORK_DELAY,
+        ));
+
+        for idx in 0..self.num_validators {
+            let sender = test.sender().for_index(idx);
+            let shutting_down = test.shutting_down();
+            test.sender().for_index(idx).send_adhoc_event("start_client", move |data| {
+                data.client.start(&mut sender.into_delayed_action_runner(shutting_down));
+            });
+            let sender = test.sender().for_index(idx);
+            let shutting_down = test.shutting_down();
+            test.sender().for_index(idx).send_adhoc_event("start_shards_manager", move |data| {
+                data.shards_manager.periodically_resend_chunk_requests(
+                    &mut sender.into_delayed_action_runner(shutting_down),
+                );
+            });
+        }
+        test
+    }
+}
+
+fn head_epoch_height(client: &Client) -> EpochHeight {
+    let head = client.chain.head().unwrap();
+    client.epoch_manager.get_epoch_info(&head.epoch_id).unwrap().epoch_height()
+}
+
+fn head_protocol_version(client: &Client) -> ProtocolVersion {
+    let head = client.chain.head().unwrap();
+    client.epoch_manager.get_epoch_protocol_version(&head.epoch_id).unwrap()
+}
+
+/// Sends the money between the accounts, with the transaction sent to the node
+/// of the validator `idx`.
+pub(crate) fn send_money(
+    test: &mut ProtocolUpgradeTestLoop,
+    idx: usize,
+    nonce: u64,
+    signer_id: &AccountId,
+    receiver_id: &AccountId,
+    amount: Balance,
+) {
+    let block_hash = test.data[idx].client.client.chain.head().unwrap().last_block_hash;
+    let tx = SignedTransaction::send_money(
+        nonce,
+        signer_id.clone(),
+        receiver_id.clone(),
+        &create_user_test_signer(signer_id),
+        amount,
+        block_hash,
+    );
+    drop(
+        test.sender()
+            .for_index(idx)
+            .into_wrapped_multi_sender::<ClientSenderForNetworkMessage, ClientSenderForNetwork>()
+            .send_async(ProcessTxRequest {
+                transaction: tx,
+                is_forwarded: false,
+                check_only: false,
+            }),
+    );
+}
+
+/// Checks that the money moves between the accounts both before and after
+/// the upgrade to the latest protocol version.
+#[test]
+fn test_protocol_upgrade_scenario() {
+    let scenario = ProtocolUpgradeScenario::new(PROTOCOL_VERSION - 1);
+    let accounts = scenario.accounts();
+    let (sender, receiver) = (accounts[5].clone(), accounts[8].clone());
+    let (sender_after, receiver_after) = (sender.clone(), receiver.clone());
+    scenario
+        .before_upgrade(move |test| {
+            send_money(test, 0, 1, &sender, &receiver, ONE_NEAR);
+            test.run_for(Duration::seconds(10));
+            assert_eq!(test.data.query_balance(&receiver), INITIAL_BALANCE + ONE_NEAR);
+        })
+        .after_upgrade(move |test| {
+            send_money(test, 1, 2, &sender_after, &receiver_after, ONE_NEAR);
+            test.run_for(Duration::seconds(10));
+            assert_eq!(test.data.query_balance(&receiver_after), INITIAL_BALANCE + 2 * ONE_NEAR);
+        })
+        .run();
+}
@@ -233,6 +233,7 @@ impl TestReshardingEnv {
         let epoch_config_test_overrides = Some(AllEpochConfigTestOverrides {
             block_producer_kickout_threshold: Some(0),
             chunk_producer_kickout_threshold: Some(0),
+            ..Default::default()
         });
         let env = builder
             .clients_count(num_clients)
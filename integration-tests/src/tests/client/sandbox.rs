@@ -2,14 +2,16 @@ use near_chain::Provenance;
 use near_chain_configs::Genesis;
 use near_client::test_utils::TestEnv;
 use near_client::ProcessTxResponse;
-use near_crypto::{InMemorySigner, KeyType};
-use near_primitives::account::Account;
+use near_crypto::{InMemorySigner, KeyType, PublicKey};
+use near_primitives::account::{AccessKey, Account};
 use near_primitives::sandbox::state_patch::SandboxStatePatch;
+use near_primitives::sandbox::AccessKeyPatch;
 use near_primitives::state_record::StateRecord;
 use near_primitives::transaction::{
     Action, DeployContractAction, FunctionCallAction, SignedTransaction,
 };
 use near_primitives::types::{AccountId, BlockHeight, Nonce};
+use near_primitives::views::{AccessKeyView, QueryRequest, QueryResponseKind};
 use nearcore::test_utils::TestEnvNightshadeSetupExt;
 
 fn test_setup() -> (TestEnv, InMemorySigner) {
@@ -105,3 +107,51 @@ fn test_patch_account() {
     let test1_after = env.query_account("test1".parse().unwrap());
     assert_eq!(test1_after.amount, 10);
 }
+
+fn query_access_key(
+    env: &mut TestEnv,
+    account_id: AccountId,
+    public_key: PublicKey,
+) -> Option<AccessKeyView> {
+    let request = QueryRequest::ViewAccessKey { account_id, public_key };
+    match env.query_view(request).ok()?.kind {
+        QueryResponseKind::AccessKey(view) => Some(view),
+        _ => panic!("wrong query response"),
+    }
+}
+
+#[test]
+fn test_patch_access_keys() {
+    let (mut env, signer) = test_setup();
+    let new_signer = InMemorySigner::from_seed("test0".parse().unwrap(), KeyType::ED25519, "new");
+
+    // Rotate the key of test0 and bump the nonce of the old key in the same patch.
+    env.clients[0].chain.patch_state(
+        SandboxStatePatch::new(vec![StateRecord::AccessKey {
+            account_id: "test0".parse().unwrap(),
+            public_key: new_signer.public_key.clone(),
+            access_key: AccessKey::full_access(),
+        }])
+        .with_access_key_patches(vec![AccessKeyPatch::SetNonce {
+            account_id: "test0".parse().unwrap(),
+            public_key: signer.public_key.clone(),
+            nonce: 100,
+        }]),
+    );
+    do_blocks(&mut env, 9, 20);
+    let old_key =
+        query_access_key(&mut env, "test0".parse().unwrap(), signer.public_key.clone()).unwrap();
+    assert_eq!(old_key.nonce, 100);
+    let new_key =
+        query_access_key(&mut env, "test0".parse().unwrap(), new_signer.public_key.clone());
+    assert!(new_key.is_some());
+
+    env.clients[0].chain.patch_state(SandboxStatePatch::default().with_access_key_patches(vec![
+        AccessKeyPatch::Delete {
+            account_id: "test0".parse().unwrap(),
+            public_key: signer.public_key.clone(),
+        },
+    ]));
+    do_blocks(&mut env, 20, 30);
+    assert!(query_access_key(&mut env, "test0".parse().unwrap(), signer.public_key).is_none());
+}
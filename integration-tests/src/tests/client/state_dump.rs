@@ -55,6 +55,7 @@ fn test_state_dump() {
             restart_dump_for_shards: None,
             iteration_delay: Some(Duration::ZERO),
             credentials_file: None,
+            s3_upload: Default::default(),
         });
 
         let mut state_sync_dumper = StateSyncDumper {
@@ -160,6 +161,7 @@ fn run_state_sync_with_dumped_parts(
             restart_dump_for_shards: None,
             iteration_delay: Some(Duration::ZERO),
             credentials_file: None,
+            s3_upload: Default::default(),
         });
         let mut state_sync_dumper = StateSyncDumper {
             clock: Clock::real(),
@@ -453,6 +453,7 @@ fn sync_state_dump() {
                 restart_dump_for_shards: None,
                 iteration_delay: Some(Duration::milliseconds(500)),
                 credentials_file: None,
+                s3_upload: Default::default(),
             });
             near1.config.store.state_snapshot_enabled = true;
 
@@ -9,7 +9,9 @@ use near_chain::{Chain, ChainGenesis};
 use near_chain_configs::{ClientConfig, Genesis, GenesisConfig};
 use near_chunks::shards_manager_actor::start_shards_manager;
 use near_client::adapter::client_sender_for_network;
-use near_client::{start_client, PartialWitnessActor, SyncAdapter, ViewClientActorInner};
+use near_client::{
+    start_client, PartialWitnessActor, PartialWitnessHistory, SyncAdapter, ViewClientActorInner,
+};
 use near_epoch_manager::shard_tracker::ShardTracker;
 use near_epoch_manager::EpochManager;
 use near_network::actix::ActixSystem;
@@ -85,6 +87,7 @@ fn setup_network_node(
     let network_adapter = LateBoundSender::new();
     let shards_manager_adapter = LateBoundSender::new();
     let adv = near_client::adversarial::Controls::default();
+    let partial_witness_history = PartialWitnessHistory::new();
     let state_sync_adapter = Arc::new(RwLock::new(SyncAdapter::new(
         noop().into_sender(),
         noop().into_sender(),
@@ -108,6 +111,7 @@ fn setup_network_node(
         adv.clone(),
         None,
         noop().into_multi_sender(),
+        partial_witness_history.clone(),
         true,
         None,
     )
@@ -122,6 +126,7 @@ fn setup_network_node(
         network_adapter.as_multi_sender(),
         client_config.clone(),
         adv,
+        None,
     );
     let (shards_manager_actor, _) = start_shards_manager(
         epoch_manager.clone(),
@@ -138,6 +143,7 @@ fn setup_network_node(
         client_actor.clone().with_auto_span_context().into_multi_sender(),
         signer,
         epoch_manager,
+        partial_witness_history,
     ));
     shards_manager_adapter.bind(shards_manager_actor.with_auto_span_context());
     let peer_manager = PeerManagerActor::spawn(
@@ -1,5 +1,4 @@
 use crate::node::{Node, RuntimeNode};
-use near_chain_configs::Genesis;
 use near_parameters::RuntimeConfigStore;
 use near_primitives::transaction::{Action, DeployContractAction, SignedTransaction};
 use near_primitives::types::AccountId;
@@ -16,9 +15,10 @@ fn test_deploy_max_size_contract() {
     let runtime_config_store = RuntimeConfigStore::new(None);
     let config = runtime_config_store.get_config(PROTOCOL_VERSION);
 
-    let genesis = Genesis::test(vec![account_id.clone()], 1);
-    let node =
-        RuntimeNode::new_from_genesis_and_config(&account_id, genesis, config.as_ref().clone());
+    let node = RuntimeNode::builder(&account_id)
+        .accounts(vec![account_id.clone()])
+        .runtime_config(config.as_ref().clone())
+        .build();
     let node_user = node.user();
 
     // Compute size of a deployment transaction with an almost empty contract payload
@@ -1,9 +1,7 @@
 use crate::node::{Node, RuntimeNode};
-use near_chain_configs::Genesis;
-use near_parameters::{ExtCosts, RuntimeConfig, RuntimeConfigStore};
+use near_parameters::ExtCosts;
 use near_primitives::serialize::to_base64;
 use near_primitives::types::AccountId;
-use near_primitives::version::PROTOCOL_VERSION;
 use near_primitives::views::{
     CostGasUsed, ExecutionOutcomeWithIdView, ExecutionStatusView, FinalExecutionStatus,
 };
@@ -35,19 +33,12 @@ fn test_contract_account() -> AccountId {
 }
 
 fn setup_runtime_node_with_contract(wasm_binary: &[u8]) -> RuntimeNode {
-    // Create a `RuntimeNode`. Load `RuntimeConfig` from `RuntimeConfigStore`
-    // to ensure we are using the latest configuration.
-    let mut genesis =
-        Genesis::test(vec![alice_account(), bob_account(), "carol.near".parse().unwrap()], 3);
-    add_test_contract(&mut genesis, &alice_account());
-    add_test_contract(&mut genesis, &bob_account());
-    let runtime_config_store = RuntimeConfigStore::new(None);
-    let runtime_config = runtime_config_store.get_config(PROTOCOL_VERSION);
-    let node = RuntimeNode::new_from_genesis_and_config(
-        &alice_account(),
-        genesis,
-        RuntimeConfig::clone(runtime_config),
-    );
+    // Create a `RuntimeNode` with the latest configuration from
+    // `RuntimeConfigStore`.
+    let node = RuntimeNode::builder(&alice_account())
+        .accounts(vec![alice_account(), bob_account(), "carol.near".parse().unwrap()])
+        .test_contract_accounts(vec![alice_account(), bob_account()])
+        .build();
 
     let account_id = node.account_id().unwrap();
     let node_user = node.user();
@@ -3,14 +3,16 @@ use std::{collections::HashMap, io, sync::Arc};
 use borsh::BorshDeserialize;
 
 use crate::runtime_utils::{get_runtime_and_trie, get_test_trie_viewer, TEST_SHARD_UID};
+use near_crypto::{KeyType, SecretKey};
+use near_parameters::StorageUsageConfig;
 use near_primitives::{
-    account::Account,
+    account::{AccessKey, Account},
     hash::hash as sha256,
     hash::CryptoHash,
     serialize::to_base64,
     trie_key::trie_key_parsers,
     types::{AccountId, StateRoot},
-    views::{StateItem, ViewApplyState},
+    views::{DataKeyStorageUsageView, StateItem, StorageUsageView, ViewApplyState},
 };
 use near_primitives::{
     test_utils::MockEpochInfoProvider,
@@ -18,7 +20,9 @@ use near_primitives::{
     types::{EpochId, StateChangeCause},
     version::PROTOCOL_VERSION,
 };
-use near_store::{set_account, NibbleSlice, RawTrieNode, RawTrieNodeWithSize, ShardUId};
+use near_store::{
+    set_access_key, set_account, NibbleSlice, RawTrieNode, RawTrieNodeWithSize, ShardUId,
+};
 use node_runtime::state_viewer::errors;
 use node_runtime::state_viewer::*;
 use testlib::runtime_utils::alice_account;
@@ -386,6 +390,121 @@ fn test_view_state_with_large_contract() {
     assert!(result.is_ok());
 }
 
+#[test]
+fn test_view_code_by_hash() {
+    let (_, tries, root) = get_runtime_and_trie();
+    let mut state_update = tries.new_trie_update(TEST_SHARD_UID, root);
+    let contract_code = b"\0asm contract".to_vec();
+    state_update.set(TrieKey::ContractCode { account_id: alice_account() }, contract_code.clone());
+    state_update.set(
+        TrieKey::ContractData { account_id: alice_account(), key: b"key".to_vec() },
+        b"value".to_vec(),
+    );
+    state_update.commit(StateChangeCause::InitialState);
+    let trie_changes = state_update.finalize().unwrap().1;
+    let mut db_changes = tries.store_update();
+    let new_root = tries.apply_all(&trie_changes, TEST_SHARD_UID, &mut db_changes);
+    db_changes.commit().unwrap();
+
+    let state_update = tries.new_trie_update(TEST_SHARD_UID, new_root);
+    let trie_viewer = TrieViewer::default();
+    let code = trie_viewer.view_code_by_hash(&state_update, &sha256(&contract_code)).unwrap();
+    assert_eq!(code.code(), &contract_code[..]);
+    for code_hash in [sha256(b"value"), sha256(b"unknown")] {
+        assert!(matches!(
+            trie_viewer.view_code_by_hash(&state_update, &code_hash),
+            Err(errors::ViewContractCodeError::UnknownCodeHash { .. })
+        ));
+    }
+
+    // The code isn't tied to the state root: it is found through the state
+    // root before its deployment as well, as long as it is stored.
+    let state_update = tries.new_trie_update(TEST_SHARD_UID, root);
+    let code = trie_viewer.view_code_by_hash(&state_update, &sha256(&contract_code)).unwrap();
+    assert_eq!(code.code(), &contract_code[..]);
+}
+
+#[test]
+fn test_view_access_keys_paginated() {
+    let (_, tries, root) = get_runtime_and_trie();
+    let mut state_update = tries.new_trie_update(TEST_SHARD_UID, root);
+    let account_id: AccountId = "dan.near".parse().unwrap();
+    for seed in ["a", "b", "c", "d"] {
+        let public_key = SecretKey::from_seed(KeyType::ED25519, seed).public_key();
+        set_access_key(
+            &mut state_update,
+            account_id.clone(),
+            public_key,
+            &AccessKey::full_access(),
+        );
+    }
+    let trie_viewer = TrieViewer::default();
+    let all_keys = trie_viewer.view_access_keys(&state_update, &account_id, None, None).unwrap();
+    assert_eq!(all_keys.len(), 4);
+
+    let first_page =
+        trie_viewer.view_access_keys(&state_update, &account_id, None, Some(3)).unwrap();
+    assert_eq!(first_page, all_keys[..3]);
+    let second_page = trie_viewer
+        .view_access_keys(&state_update, &account_id, Some(&first_page[2].0), Some(3))
+        .unwrap();
+    assert_eq!(second_page, all_keys[3..]);
+    let last_page = trie_viewer
+        .view_access_keys(&state_update, &account_id, Some(&second_page[0].0), None)
+        .unwrap();
+    assert!(last_page.is_empty());
+}
+
+#[test]
+fn test_view_storage_usage() {
+    let (_, tries, root) = get_runtime_and_trie();
+    let mut state_update = tries.new_trie_update(TEST_SHARD_UID, root);
+    let account_id: AccountId = "dan.near".parse().unwrap();
+    set_account(
+        &mut state_update,
+        account_id.clone(),
+        &Account::new(0, 0, 0, CryptoHash::default(), 417, PROTOCOL_VERSION),
+    );
+    let public_key = SecretKey::from_seed(KeyType::ED25519, "dan").public_key();
+    set_access_key(&mut state_update, account_id.clone(), public_key, &AccessKey::full_access());
+    for (key, value_len) in [(b"a".to_vec(), 10), (b"bb".to_vec(), 100), (b"c".to_vec(), 1)] {
+        state_update
+            .set(TrieKey::ContractData { account_id: account_id.clone(), key }, vec![0; value_len]);
+    }
+    state_update.commit(StateChangeCause::InitialState);
+    let trie_changes = state_update.finalize().unwrap().1;
+    let mut db_changes = tries.store_update();
+    let new_root = tries.apply_all(&trie_changes, TEST_SHARD_UID, &mut db_changes);
+    db_changes.commit().unwrap();
+
+    let state_update = tries.new_trie_update(TEST_SHARD_UID, new_root);
+    let storage_usage_config = StorageUsageConfig {
+        storage_amount_per_byte: 0,
+        num_bytes_account: 100,
+        num_extra_bytes_record: 40,
+    };
+    let storage_usage = TrieViewer::default()
+        .view_storage_usage(&state_update, &account_id, &storage_usage_config, 2)
+        .unwrap();
+    assert_eq!(
+        storage_usage,
+        StorageUsageView {
+            storage_usage: 417,
+            account: 100,
+            code: 0,
+            // The public key with its key type and the nonce with the permission.
+            access_keys: 40 + 33 + 9,
+            num_access_keys: 1,
+            data: 40 * 3 + 1 + 10 + 2 + 100 + 1 + 1,
+            num_data_keys: 3,
+            largest_data_keys: vec![
+                DataKeyStorageUsageView { key: b"bb".to_vec().into(), storage_usage: 142 },
+                DataKeyStorageUsageView { key: b"a".to_vec().into(), storage_usage: 51 },
+            ],
+        }
+    );
+}
+
 #[test]
 fn test_log_when_panic() {
     let (viewer, root) = get_test_trie_viewer();
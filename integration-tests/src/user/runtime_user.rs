@@ -13,8 +13,8 @@ use near_primitives::receipt::Receipt;
 use near_primitives::runtime::migration_data::{MigrationData, MigrationFlags};
 use near_primitives::test_utils::MockEpochInfoProvider;
 use near_primitives::transaction::SignedTransaction;
-use near_primitives::types::{AccountId, BlockHeightDelta, MerkleHash};
-use near_primitives::version::{ProtocolFeature, PROTOCOL_VERSION};
+use near_primitives::types::{AccountId, BlockHeightDelta, Gas, MerkleHash};
+use near_primitives::version::{ProtocolFeature, ProtocolVersion};
 use near_primitives::views::{
     AccessKeyView, AccountView, BlockView, CallResult, ChunkView, ContractCodeView,
     ExecutionOutcomeView, ExecutionOutcomeWithIdView, ExecutionStatusView,
@@ -34,6 +34,9 @@ pub struct MockClient {
     pub state_root: MerkleHash,
     pub epoch_length: BlockHeightDelta,
     pub runtime_config: RuntimeConfig,
+    pub protocol_version: ProtocolVersion,
+    /// Gas limit of the chunks, None for no limit.
+    pub gas_limit: Option<Gas>,
 }
 
 impl MockClient {
@@ -54,6 +57,8 @@ pub struct RuntimeUser {
     pub transactions: RefCell<HashSet<SignedTransaction>>,
     pub epoch_info_provider: MockEpochInfoProvider,
     pub runtime_config: Arc<RuntimeConfig>,
+    pub protocol_version: ProtocolVersion,
+    pub gas_limit: Option<Gas>,
 }
 
 impl RuntimeUser {
@@ -62,7 +67,10 @@ impl RuntimeUser {
         signer: Arc<dyn Signer>,
         client: Arc<RwLock<MockClient>>,
     ) -> Self {
-        let runtime_config = Arc::new(client.read().unwrap().runtime_config.clone());
+        let (runtime_config, protocol_version, gas_limit) = {
+            let client = client.read().unwrap();
+            (Arc::new(client.runtime_config.clone()), client.protocol_version, client.gas_limit)
+        };
         RuntimeUser {
             signer,
             trie_viewer: TrieViewer::default(),
@@ -73,6 +81,8 @@ impl RuntimeUser {
             transactions: RefCell::new(Default::default()),
             epoch_info_provider: MockEpochInfoProvider::default(),
             runtime_config,
+            protocol_version,
+            gas_limit,
         }
     }
 
@@ -156,7 +166,7 @@ impl RuntimeUser {
         let shard_id = 0;
         // TODO(congestion_control) - Set other shard ids somehow.
         let all_shard_ids = [0, 1, 2, 3, 4, 5];
-        let congestion_info = if ProtocolFeature::CongestionControl.enabled(PROTOCOL_VERSION) {
+        let congestion_info = if ProtocolFeature::CongestionControl.enabled(self.protocol_version) {
             all_shard_ids.into_iter().map(|id| (id, ExtendedCongestionInfo::default())).collect()
         } else {
             HashMap::new()
@@ -171,10 +181,10 @@ impl RuntimeUser {
             shard_id,
             epoch_height: 0,
             gas_price: MIN_GAS_PRICE,
-            gas_limit: None,
+            gas_limit: self.gas_limit,
             random_seed: Default::default(),
             epoch_id: Default::default(),
-            current_protocol_version: PROTOCOL_VERSION,
+            current_protocol_version: self.protocol_version,
             config: self.runtime_config.clone(),
             cache: None,
             is_new_chunk: true,
@@ -279,7 +289,7 @@ impl User for RuntimeUser {
     fn is_locked(&self, account_id: &AccountId) -> Result<bool, String> {
         let state_update = self.client.read().expect(POISONED_LOCK_ERR).get_state_update();
         self.trie_viewer
-            .view_access_keys(&state_update, account_id)
+            .view_access_keys(&state_update, account_id, None, Some(1))
             .map(|access_keys| access_keys.is_empty())
             .map_err(|err| err.to_string())
     }
@@ -305,7 +315,7 @@ impl User for RuntimeUser {
             epoch_id: apply_state.epoch_id,
             epoch_height: apply_state.epoch_height,
             block_timestamp: apply_state.block_timestamp,
-            current_protocol_version: PROTOCOL_VERSION,
+            current_protocol_version: self.protocol_version,
             cache: apply_state.cache,
         };
         result.result = self
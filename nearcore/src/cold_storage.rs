@@ -4,7 +4,9 @@ use near_chain::types::Tip;
 use near_epoch_manager::{EpochManagerAdapter, EpochManagerHandle};
 use near_primitives::errors::EpochError;
 use near_primitives::{hash::CryptoHash, types::BlockHeight};
-use near_store::cold_storage::{copy_all_data_to_cold, CopyAllDataToColdStatus};
+use near_store::cold_storage::{
+    copy_all_data_to_cold, ColdStoreCopyControl, CopyAllDataToColdStatus,
+};
 use near_store::{
     cold_storage::{update_cold_db, update_cold_head},
     db::ColdDB,
@@ -18,9 +20,16 @@ use crate::{metrics, NearConfig};
 pub struct ColdStoreLoopHandle {
     join_handle: std::thread::JoinHandle<()>,
     keep_going: Arc<AtomicBool>,
+    control: Arc<ColdStoreCopyControl>,
 }
 
 impl ColdStoreLoopHandle {
+    /// Returns the control used to report the progress of the loop and to
+    /// pause it or limit its copy rate.
+    pub fn control(&self) -> Arc<ColdStoreCopyControl> {
+        self.control.clone()
+    }
+
     pub fn stop(self) {
         self.keep_going.store(false, std::sync::atomic::Ordering::Relaxed);
         match self.join_handle.join() {
@@ -89,6 +98,7 @@ fn cold_store_copy(
     genesis_height: BlockHeight,
     epoch_manager: &EpochManagerHandle,
     num_threads: usize,
+    control: &ColdStoreCopyControl,
 ) -> anyhow::Result<ColdStoreCopyResult, ColdStoreError> {
     // If COLD_HEAD is not set for hot storage we default it to genesis_height.
     let cold_head = cold_store.get_ser::<Tip>(DBCol::BlockMisc, HEAD_KEY)?;
@@ -136,6 +146,7 @@ fn cold_store_copy(
     }
 
     update_cold_head(cold_db, hot_store, &next_height)?;
+    control.record_copied_block(next_height, std::time::Instant::now());
 
     let result = if next_height >= hot_final_head_height {
         Ok(ColdStoreCopyResult::LatestBlockCopied)
@@ -350,7 +361,8 @@ fn cold_store_migration_loop(
 // This method will copy data from hot storage to cold storage in a loop.
 // It will try to copy blocks as fast as possible up until cold head = final head.
 // Once the cold head reaches the final head it will sleep for one second before
-// trying to copy data at the next height.
+// trying to copy data at the next height. The copying can be paused or slowed
+// down with the `control`.
 // TODO clean up the interface, currently we need to pass hot store, cold store and
 // cold_db which is redundant.
 fn cold_store_loop(
@@ -361,15 +373,26 @@ fn cold_store_loop(
     cold_db: Arc<ColdDB>,
     genesis_height: BlockHeight,
     epoch_manager: &EpochManagerHandle,
+    control: &ColdStoreCopyControl,
 ) {
     tracing::info!(target : "cold_store", "Starting the cold store loop");
 
+    let sleep_duration = split_storage_config.cold_store_loop_sleep_duration;
     loop {
         if !keep_going.load(std::sync::atomic::Ordering::Relaxed) {
             tracing::debug!(target : "cold_store", "Stopping the cold store loop");
             break;
         }
 
+        if control.is_paused() {
+            std::thread::sleep(sleep_duration.unsigned_abs());
+            continue;
+        }
+        if let Some(delay) = control.delay_before_next_copy(std::time::Instant::now()) {
+            std::thread::sleep(delay.min(sleep_duration.unsigned_abs()));
+            continue;
+        }
+
         let instant = std::time::Instant::now();
         let result = cold_store_copy(
             &hot_store,
@@ -378,6 +401,7 @@ fn cold_store_loop(
             genesis_height,
             epoch_manager,
             split_storage_config.num_cold_store_read_threads,
+            control,
         );
         let duration = instant.elapsed();
 
@@ -387,7 +411,6 @@ fn cold_store_loop(
             tracing::debug!(target : "cold_store", "cold_store_copy took {}s", duration.as_secs_f64());
         }
 
-        let sleep_duration = split_storage_config.cold_store_loop_sleep_duration;
         match result {
             Err(err) => {
                 tracing::error!(target : "cold_store", error = format!("{err:#?}"), "cold_store_copy failed");
@@ -448,6 +471,8 @@ pub fn spawn_cold_store_loop(
     let genesis_height = config.genesis.config.genesis_height;
     let keep_going = Arc::new(AtomicBool::new(true));
     let keep_going_clone = keep_going.clone();
+    let control = Arc::new(ColdStoreCopyControl::default());
+    let control_clone = control.clone();
 
     // Perform the sanity check before spawning the thread.
     // If the check fails when the node is starting it's better to just fail
@@ -475,8 +500,9 @@ pub fn spawn_cold_store_loop(
                 cold_db,
                 genesis_height,
                 epoch_manager.as_ref(),
+                &control_clone,
             )
         })?;
 
-    Ok(Some(ColdStoreLoopHandle { join_handle, keep_going }))
+    Ok(Some(ColdStoreLoopHandle { join_handle, keep_going, control }))
 }
@@ -9,38 +9,42 @@ use near_chain_configs::test_utils::{
     TESTING_INIT_BALANCE, TESTING_INIT_STAKE,
 };
 use near_chain_configs::{
-    default_enable_multiline_logging, default_epoch_sync_enabled,
-    default_header_sync_expected_height_per_second, default_header_sync_initial_timeout,
-    default_header_sync_progress_timeout, default_header_sync_stall_ban_timeout,
-    default_log_summary_period, default_orphan_state_witness_max_size,
-    default_orphan_state_witness_pool_size, default_produce_chunk_add_transactions_time_limit,
+    block_time_produce_chunk_add_transactions_time_limit, default_enable_multiline_logging,
+    default_epoch_sync_enabled, default_header_sync_expected_height_per_second,
+    default_header_sync_initial_timeout, default_header_sync_progress_timeout,
+    default_header_sync_stall_ban_timeout, default_log_summary_period,
+    default_orphan_state_witness_max_size, default_orphan_state_witness_pool_size,
     default_state_sync, default_state_sync_enabled, default_state_sync_timeout,
     default_sync_check_period, default_sync_height_threshold, default_sync_step_period,
     default_transaction_pool_size_limit, default_trie_viewer_state_size_limit,
     default_tx_routing_height_horizon, default_view_client_threads,
-    default_view_client_throttle_period, get_initial_supply, ChunkDistributionNetworkConfig,
-    ClientConfig, GCConfig, Genesis, GenesisConfig, GenesisValidationMode, LogSummaryStyle,
-    MutableConfigValue, ReshardingConfig, StateSyncConfig, BLOCK_PRODUCER_KICKOUT_THRESHOLD,
-    CHUNK_PRODUCER_KICKOUT_THRESHOLD, EXPECTED_EPOCH_LENGTH, FISHERMEN_THRESHOLD,
-    GAS_PRICE_ADJUSTMENT_RATE, GENESIS_CONFIG_FILENAME, INITIAL_GAS_LIMIT, MAX_INFLATION_RATE,
-    MIN_BLOCK_PRODUCTION_DELAY, MIN_GAS_PRICE, NEAR_BASE, NUM_BLOCKS_PER_YEAR,
-    NUM_BLOCK_PRODUCER_SEATS, PROTOCOL_REWARD_RATE, PROTOCOL_UPGRADE_STAKE_THRESHOLD,
-    TRANSACTION_VALIDITY_PERIOD,
+    default_view_client_throttle_period, get_initial_supply, is_experimental_chain_id,
+    ChunkDistributionNetworkConfig, ClientConfig, GCConfig, Genesis, GenesisConfig,
+    GenesisValidationMode, LogSummaryStyle, MutableConfigValue, NodeRole, ReshardingConfig,
+    StateSyncConfig, BLOCK_PRODUCER_KICKOUT_THRESHOLD, CHUNK_PRODUCER_KICKOUT_THRESHOLD,
+    EXPECTED_EPOCH_LENGTH, FISHERMEN_THRESHOLD, GAS_PRICE_ADJUSTMENT_RATE, GENESIS_CONFIG_FILENAME,
+    INITIAL_GAS_LIMIT, MAX_INFLATION_RATE, MIN_BLOCK_PRODUCTION_DELAY, MIN_GAS_PRICE, NEAR_BASE,
+    NUM_BLOCKS_PER_YEAR, NUM_BLOCK_PRODUCER_SEATS, PROTOCOL_REWARD_RATE,
+    PROTOCOL_UPGRADE_STAKE_THRESHOLD, TRANSACTION_VALIDITY_PERIOD,
 };
 use near_config_utils::{ValidationError, ValidationErrors};
 use near_crypto::{InMemorySigner, KeyFile, KeyType, PublicKey, Signer};
 use near_epoch_manager::EpochManagerHandle;
+#[cfg(feature = "grpc")]
+use near_grpc::GrpcConfig;
 #[cfg(feature = "json_rpc")]
 use near_jsonrpc::RpcConfig;
 use near_network::config::NetworkConfig;
 use near_network::tcp;
 use near_o11y::log_config::LogConfig;
+use near_parameters::RuntimeConfigStore;
+use near_primitives::epoch_manager::AllEpochConfigTestOverrides;
 use near_primitives::hash::CryptoHash;
 use near_primitives::shard_layout::ShardLayout;
 use near_primitives::test_utils::create_test_signer;
 use near_primitives::types::{
-    AccountId, AccountInfo, Balance, BlockHeight, BlockHeightDelta, Gas, NumSeats, NumShards,
-    ShardId,
+    AccountId, AccountInfo, Balance, BlockHeight, BlockHeightDelta, Compute, Gas, NumSeats,
+    NumShards, ShardId,
 };
 use near_primitives::utils::{from_timestamp, get_num_seats_per_shard};
 use near_primitives::validator_signer::{InMemoryValidatorSigner, ValidatorSigner};
@@ -48,10 +52,13 @@ use near_primitives::version::PROTOCOL_VERSION;
 #[cfg(feature = "rosetta_rpc")]
 use near_rosetta_rpc::RosettaRpcConfig;
 use near_store::config::StateSnapshotType;
-use near_store::{StateSnapshotConfig, Store, TrieConfig};
+use near_store::{StateSnapshotConfig, Store, StoreBackend, TrieConfig};
 use near_telemetry::TelemetryConfig;
-use near_vm_runner::{ContractRuntimeCache, FilesystemContractRuntimeCache};
+use near_vm_runner::{
+    ContractRuntimeCache, FilesystemContractRuntimeCache, MockContractRuntimeCache,
+};
 use num_rational::Rational32;
+use std::collections::BTreeMap;
 use std::fs;
 use std::fs::File;
 use std::io::{Read, Write};
@@ -104,6 +111,9 @@ pub const MINIMUM_STAKE_DIVISOR: u64 = 10;
 pub const CONFIG_FILENAME: &str = "config.json";
 pub const NODE_KEY_FILE: &str = "node_key.json";
 pub const VALIDATOR_KEY_FILE: &str = "validator_key.json";
+/// Runtime parameter overrides of the local chains, read from the home
+/// directory, see [`RuntimeConfigStore::with_parameter_overrides`].
+pub const RUNTIME_PARAMS_OVERRIDE_FILE: &str = "runtime_params_override.yaml";
 
 pub const NETWORK_TELEMETRY_URL: &str = "https://explorer.{}.near.org/api/nodes";
 
@@ -172,6 +182,12 @@ pub struct Consensus {
     pub doomslug_step_period: Duration,
     #[serde(default = "default_sync_height_threshold")]
     pub sync_height_threshold: u64,
+    /// Don't produce the chunks of shards without transactions and receipts.
+    /// Meant for benchmark networks with idle shards, it only has an effect on
+    /// the chains whose id starts with `benchmarknet`, once the
+    /// `SkipEmptyChunks` protocol feature is enabled.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub skip_empty_chunks: bool,
 }
 
 impl Default for Consensus {
@@ -199,6 +215,7 @@ impl Default for Consensus {
             sync_step_period: default_sync_step_period(),
             doomslug_step_period: default_doomslug_step_period(),
             sync_height_threshold: default_sync_height_threshold(),
+            skip_empty_chunks: false,
         }
     }
 }
@@ -216,6 +233,10 @@ pub struct Config {
     #[cfg(feature = "rosetta_rpc")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub rosetta_rpc: Option<RosettaRpcConfig>,
+    /// gRPC server streaming the final blocks, chunks and execution outcomes.
+    #[cfg(feature = "grpc")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grpc: Option<GrpcConfig>,
     pub telemetry: TelemetryConfig,
     pub network: near_network::config_json::Config,
     pub consensus: Consensus,
@@ -285,6 +306,8 @@ pub struct Config {
     /// A node produces a chunk by adding transactions from the transaction pool until
     /// some limit is reached. This time limit ensures that adding transactions won't take
     /// longer than the specified duration, which helps to produce the chunk quickly.
+    ///
+    /// If not set, a third of `consensus.min_block_production_delay` is used.
     #[serde(default)]
     #[serde(with = "near_async::time::serde_opt_duration_as_std")]
     pub produce_chunk_add_transactions_time_limit: Option<Duration>,
@@ -309,12 +332,65 @@ pub struct Config {
     ///
     /// Each loaded contract will increase the baseline memory use of the node appreciably.
     pub max_loaded_contracts: usize,
+    /// The number of the most used contracts of the tracked shards compiled and loaded at
+    /// startup, ahead of their first execution. 0 disables the pre-warming.
+    pub prewarm_contracts: usize,
     /// Save observed instances of ChunkStateWitness to the database in DBCol::LatestChunkStateWitnesses.
     /// Saving the latest witnesses is useful for analysis and debugging.
     /// When this option is enabled, the node will save ALL witnesses it oberves, even invalid ones,
     /// which can cause extra load on the database. This option is not recommended for production use,
     /// as a large number of incoming witnesses could cause denial of service.
     pub save_latest_witnesses: bool,
+    /// Keep an index of the transactions and receipts involving every
+    /// account, so that they can be listed with the
+    /// `EXPERIMENTAL_account_activity` RPC method. The index is garbage
+    /// collected with the rest of the data, unless the `account_index` column
+    /// group gets its own number of epochs to keep in `gc`.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub save_account_index: bool,
+    /// EXPERIMENTAL, NOT CONSENSUS SAFE: overrides the compute costs of host
+    /// functions, keyed by parameter name, e.g. `wasm_storage_read_base`.
+    ///
+    /// Meant for benchmarking experiments which need different compute costs
+    /// without a protocol release. Nodes with different overrides disagree on
+    /// the result of applying chunks, so this is only accepted on test chains
+    /// whose chain id starts with `test-chain-` or `benchmarknet`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub experimental_compute_cost_overrides: BTreeMap<String, Compute>,
+    /// Signs blocks, chunks and other validator messages with a remote signing
+    /// service instead of the key in `validator_key_file`, see
+    /// `nearcore::remote_signer` for the protocol.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_validator_signer: Option<RemoteSignerConfig>,
+    /// Labels and namespace of the exported Prometheus metrics.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<near_o11y::metrics::MetricsConfig>,
+    /// Runs the node as a shadow of the given validator: it signs with a
+    /// random key instead of the key in `validator_key_file` and produces the
+    /// approvals, chunks and state witnesses of the validator without sending
+    /// them, recording whether they would have been in time. The messages of
+    /// the other validators keep going to the validator, so the shadow doesn't
+    /// produce blocks nor endorsements, see `near_client::ShadowValidator`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shadow_validator_account_id: Option<AccountId>,
+    /// EXPERIMENTAL, NOT CONSENSUS SAFE: JSON file overriding the epoch
+    /// config, i.e. the epoch length, the numbers of block producer, chunk
+    /// producer and chunk-only producer seats and the kickout thresholds.
+    ///
+    /// The file lists the `chain_ids` it applies to, and the overrides are
+    /// ignored on other chains, so that the same file can be shipped to the
+    /// nodes of several forknet and benchmark networks. All nodes of a network
+    /// need the same overrides. The overrides are applied to the genesis
+    /// config once loaded, and again on top of the production config, if the
+    /// chain uses it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epoch_config_override_file: Option<String>,
+    /// Set to `read_replica` to run the node only to serve reads: it follows
+    /// the chain and applies the chunks of the tracked shards, but ignores the
+    /// validator key, keeps no transaction pool and skips the validation work
+    /// of validators, so that it needs less CPU for the same RPC traffic.
+    #[serde(default, skip_serializing_if = "NodeRole::is_regular")]
+    pub node_role: NodeRole,
 }
 
 fn is_false(value: &bool) -> bool {
@@ -331,6 +407,8 @@ impl Default for Config {
             rpc: Some(RpcConfig::default()),
             #[cfg(feature = "rosetta_rpc")]
             rosetta_rpc: None,
+            #[cfg(feature = "grpc")]
+            grpc: None,
             telemetry: TelemetryConfig::default(),
             network: Default::default(),
             consensus: Consensus::default(),
@@ -357,19 +435,62 @@ impl Default for Config {
             enable_multiline_logging: default_enable_multiline_logging(),
             resharding_config: ReshardingConfig::default(),
             tx_routing_height_horizon: default_tx_routing_height_horizon(),
-            produce_chunk_add_transactions_time_limit:
-                default_produce_chunk_add_transactions_time_limit(),
+            produce_chunk_add_transactions_time_limit: None,
             chunk_distribution_network: None,
             orphan_state_witness_pool_size: default_orphan_state_witness_pool_size(),
             orphan_state_witness_max_size: default_orphan_state_witness_max_size(),
             max_loaded_contracts: 256,
+            prewarm_contracts: 100,
             save_latest_witnesses: false,
+            save_account_index: false,
+            experimental_compute_cost_overrides: BTreeMap::new(),
+            remote_validator_signer: None,
+            metrics: None,
+            shadow_validator_account_id: None,
+            epoch_config_override_file: None,
+            node_role: NodeRole::Regular,
         }
     }
 }
 
+fn default_remote_signer_timeout() -> Duration {
+    Duration::milliseconds(500)
+}
+
+fn default_remote_signer_retries() -> u32 {
+    2
+}
+
+/// What the remote validator signer does when the signing service fails to
+/// respond, after retrying.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RemoteSignerFailurePolicy {
+    /// Crash the node instead of going on without signing.
+    #[default]
+    Panic,
+    /// Sign with the key in `validator_key_file`, which has to be the same key
+    /// the signing service signs with.
+    LocalKey,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct RemoteSignerConfig {
+    /// Unix socket the signing service listens on.
+    pub socket_path: PathBuf,
+    /// How long to wait for the signing service to respond to one request.
+    #[serde(default = "default_remote_signer_timeout")]
+    #[serde(with = "near_async::time::serde_duration_as_std")]
+    pub timeout: Duration,
+    /// How many times a failed request is retried.
+    #[serde(default = "default_remote_signer_retries")]
+    pub retries: u32,
+    #[serde(default)]
+    pub failure_policy: RemoteSignerFailurePolicy,
+}
+
 fn default_enable_split_storage_view_client() -> bool {
-    false
+    true
 }
 
 fn default_cold_store_initial_migration_batch_size() -> usize {
@@ -390,6 +511,9 @@ fn default_cold_store_loop_sleep_duration() -> Duration {
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
 pub struct SplitStorageConfig {
+    /// Whether the view client reads data which was already migrated out of
+    /// the hot storage from the cold storage. Only used on archival nodes
+    /// once the initial migration to the cold storage is finished.
     #[serde(default = "default_enable_split_storage_view_client")]
     pub enable_split_storage_view_client: bool,
 
@@ -476,6 +600,16 @@ impl Config {
         file.write_all(str.as_bytes())
     }
 
+    /// Returns `produce_chunk_add_transactions_time_limit`, or the limit
+    /// derived from the block production delay if it's not set.
+    pub fn produce_chunk_add_transactions_time_limit_or_default(&self) -> Duration {
+        self.produce_chunk_add_transactions_time_limit.unwrap_or_else(|| {
+            block_time_produce_chunk_add_transactions_time_limit(
+                self.consensus.min_block_production_delay,
+            )
+        })
+    }
+
     pub fn rpc_addr(&self) -> Option<String> {
         #[cfg(feature = "json_rpc")]
         if let Some(rpc) = &self.rpc {
@@ -501,9 +635,17 @@ pub struct NearConfig {
     pub rpc_config: Option<RpcConfig>,
     #[cfg(feature = "rosetta_rpc")]
     pub rosetta_rpc_config: Option<RosettaRpcConfig>,
+    #[cfg(feature = "grpc")]
+    pub grpc_config: Option<GrpcConfig>,
     pub telemetry_config: TelemetryConfig,
     pub genesis: Genesis,
     pub validator_signer: Option<Arc<dyn ValidatorSigner>>,
+    /// Overrides of the epoch config read from the
+    /// `epoch_config_override_file`, if they apply to the chain.
+    pub epoch_config_overrides: Option<AllEpochConfigTestOverrides>,
+    /// Runtime parameter overrides read from the
+    /// `runtime_params_override.yaml`, if the chain is a local one.
+    pub runtime_params_overrides: Option<String>,
 }
 
 impl NearConfig {
@@ -541,6 +683,7 @@ impl NearConfig {
                 min_num_peers: config.consensus.min_num_peers,
                 log_summary_period: config.log_summary_period,
                 produce_empty_blocks: config.consensus.produce_empty_blocks,
+                skip_empty_chunks: config.consensus.skip_empty_chunks,
                 epoch_length: genesis.config.epoch_length,
                 num_block_producer_seats: genesis.config.num_block_producer_seats,
                 ttl_account_id_router: config.network.ttl_account_id_router,
@@ -576,18 +719,22 @@ impl NearConfig {
                 ),
                 tx_routing_height_horizon: config.tx_routing_height_horizon,
                 produce_chunk_add_transactions_time_limit: MutableConfigValue::new(
-                    config.produce_chunk_add_transactions_time_limit,
+                    Some(config.produce_chunk_add_transactions_time_limit_or_default()),
                     "produce_chunk_add_transactions_time_limit",
                 ),
                 chunk_distribution_network: config.chunk_distribution_network,
                 orphan_state_witness_pool_size: config.orphan_state_witness_pool_size,
                 orphan_state_witness_max_size: config.orphan_state_witness_max_size,
                 save_latest_witnesses: config.save_latest_witnesses,
+                save_account_index: config.save_account_index,
+                node_role: config.node_role,
             },
+            // A shadow validator doesn't announce the account, so that the
+            // messages for the validator keep going to the validator itself.
             network_config: NetworkConfig::new(
                 config.network,
                 network_key_pair.secret_key,
-                validator_signer.clone(),
+                validator_signer.clone().filter(|_| config.shadow_validator_account_id.is_none()),
                 config.archive,
             )?,
             telemetry_config: config.telemetry,
@@ -595,8 +742,12 @@ impl NearConfig {
             rpc_config: config.rpc,
             #[cfg(feature = "rosetta_rpc")]
             rosetta_rpc_config: config.rosetta_rpc,
+            #[cfg(feature = "grpc")]
+            grpc_config: config.grpc,
             genesis,
             validator_signer,
+            epoch_config_overrides: None,
+            runtime_params_overrides: None,
         })
     }
 
@@ -649,6 +800,11 @@ impl NightshadeRuntime {
         if config.config.store.state_snapshot_enabled {
             state_snapshot_type = StateSnapshotType::EveryEpoch;
         }
+        let in_memory = config.config.store.backend == StoreBackend::InMemory;
+        if in_memory && matches!(state_snapshot_type, StateSnapshotType::EveryEpoch) {
+            tracing::warn!(target: "config", "State snapshots aren't supported with the in-memory store backend, not creating them");
+            state_snapshot_type = StateSnapshotType::ForReshardingOnly;
+        }
         let state_snapshot_config = StateSnapshotConfig {
             state_snapshot_type,
             home_dir: home_dir.to_path_buf(),
@@ -663,11 +819,16 @@ impl NightshadeRuntime {
         // FIXME: this (and other contract runtime resources) should probably get constructed by
         // the caller and passed into this `NightshadeRuntime::from_config` here. But that's a big
         // refactor...
-        let contract_cache = FilesystemContractRuntimeCache::with_memory_cache(
-            home_dir,
-            config.config.store.path.as_ref(),
-            config.config.max_loaded_contracts,
-        )?;
+        let contract_cache: Box<dyn ContractRuntimeCache> = if in_memory {
+            Box::new(MockContractRuntimeCache::default())
+        } else {
+            Box::new(FilesystemContractRuntimeCache::with_memory_cache(
+                home_dir,
+                config.config.store.path.as_ref(),
+                config.config.max_loaded_contracts,
+            )?)
+        };
+        let runtime_config_store = runtime_config_store_with_overrides(config)?;
         Ok(NightshadeRuntime::new(
             store,
             ContractRuntimeCache::handle(&contract_cache),
@@ -675,7 +836,7 @@ impl NightshadeRuntime {
             epoch_manager,
             config.client_config.trie_viewer_state_size_limit,
             config.client_config.max_gas_burnt_view,
-            None,
+            runtime_config_store,
             config.config.gc.gc_num_epochs_to_keep(),
             TrieConfig::from_store_config(&config.config.store),
             state_snapshot_config,
@@ -683,6 +844,126 @@ impl NightshadeRuntime {
     }
 }
 
+/// Whether the chain is a local one, for the development of contracts, on
+/// which the runtime parameters can be overridden.
+fn is_local_chain_id(chain_id: &str) -> bool {
+    (chain_id.starts_with("local") || chain_id.starts_with("test"))
+        && chain_id != near_primitives::chains::TESTNET
+}
+
+/// Returns the runtime config store with the runtime parameter overrides and
+/// the experimental compute cost overrides of the config applied, or None if
+/// there are none.
+fn runtime_config_store_with_overrides(
+    config: &NearConfig,
+) -> std::io::Result<Option<RuntimeConfigStore>> {
+    let runtime_params_overrides = &config.runtime_params_overrides;
+    let compute_cost_overrides = &config.config.experimental_compute_cost_overrides;
+    if runtime_params_overrides.is_none() && compute_cost_overrides.is_empty() {
+        return Ok(None);
+    }
+    let chain_id = &config.genesis.config.chain_id;
+    if !compute_cost_overrides.is_empty() && !is_experimental_chain_id(chain_id) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("experimental_compute_cost_overrides are not allowed on chain {chain_id}"),
+        ));
+    }
+    let mut store = match runtime_params_overrides {
+        Some(overrides) => {
+            warn!(target: "neard", "Overriding runtime parameters with {RUNTIME_PARAMS_OVERRIDE_FILE}, this node is NOT consensus safe");
+            RuntimeConfigStore::with_parameter_overrides(overrides)
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?
+        }
+        None => RuntimeConfigStore::for_chain_id(chain_id),
+    };
+    if !compute_cost_overrides.is_empty() {
+        warn!(target: "neard", overrides = ?compute_cost_overrides, "Overriding compute costs, this node is NOT consensus safe");
+        store = store
+            .with_compute_cost_overrides(compute_cost_overrides)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+    }
+    Ok(Some(store))
+}
+
+/// Contents of the `epoch_config_override_file`.
+#[derive(serde::Deserialize)]
+struct EpochConfigOverrideFile {
+    /// Chain ids the overrides apply to.
+    chain_ids: Vec<String>,
+    /// Set in the genesis config, so that the client, the chain and the tools
+    /// all see the same epoch length as the epoch manager.
+    #[serde(default)]
+    epoch_length: Option<BlockHeightDelta>,
+    #[serde(flatten)]
+    overrides: AllEpochConfigTestOverrides,
+}
+
+impl EpochConfigOverrideFile {
+    /// Applies the overrides to the genesis config. The other overrides than
+    /// the epoch length are also applied by the epoch manager on top of the
+    /// production config, which replaces the values of the genesis.
+    fn apply_to_genesis(&self, genesis_config: &mut GenesisConfig) {
+        if let Some(epoch_length) = self.epoch_length {
+            genesis_config.epoch_length = epoch_length;
+        }
+        let overrides = &self.overrides;
+        if let Some(num_block_producer_seats) = overrides.num_block_producer_seats {
+            genesis_config.num_block_producer_seats = num_block_producer_seats;
+            genesis_config.num_block_producer_seats_per_shard =
+                genesis_config.shard_layout.shard_ids().map(|_| num_block_producer_seats).collect();
+        }
+        if let Some(num_chunk_producer_seats) = overrides.num_chunk_producer_seats {
+            genesis_config.num_chunk_producer_seats = num_chunk_producer_seats;
+        }
+        if let Some(num_chunk_only_producer_seats) = overrides.num_chunk_only_producer_seats {
+            genesis_config.num_chunk_only_producer_seats = num_chunk_only_producer_seats;
+        }
+        if let Some(threshold) = overrides.block_producer_kickout_threshold {
+            genesis_config.block_producer_kickout_threshold = threshold;
+        }
+        if let Some(threshold) = overrides.chunk_producer_kickout_threshold {
+            genesis_config.chunk_producer_kickout_threshold = threshold;
+        }
+    }
+}
+
+/// Reads the epoch config overrides from the file, returning None if they
+/// don't apply to the chain.
+fn load_epoch_config_overrides(
+    path: &Path,
+    chain_id: &str,
+) -> anyhow::Result<Option<EpochConfigOverrideFile>> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let file: EpochConfigOverrideFile =
+        serde_json::from_str(&contents).with_context(|| format!("parsing {}", path.display()))?;
+    if !file.chain_ids.iter().any(|id| id == chain_id) {
+        info!(target: "neard", chain_ids = ?file.chain_ids, "Epoch config overrides don't apply to chain {chain_id}");
+        return Ok(None);
+    }
+    if matches!(chain_id, near_primitives::chains::MAINNET | near_primitives::chains::TESTNET) {
+        bail!("epoch config overrides are not allowed on chain {chain_id}");
+    }
+    warn!(target: "neard", epoch_length = ?file.epoch_length, overrides = ?file.overrides, "Overriding the epoch config, this node is NOT consensus safe");
+    Ok(Some(file))
+}
+
+/// Reads the runtime parameter overrides from the file, returning None if
+/// there is no file or the chain is not a local one.
+fn load_runtime_params_overrides(path: &Path, chain_id: &str) -> anyhow::Result<Option<String>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    if !is_local_chain_id(chain_id) {
+        warn!(target: "neard", "Ignoring {}, runtime parameter overrides are not allowed on chain {chain_id}", path.display());
+        return Ok(None);
+    }
+    let overrides =
+        fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    Ok(Some(overrides))
+}
+
 /// Generates or loads a signer key from given file.
 ///
 /// If the file already exists, loads the file (panicking if the file is
@@ -1233,7 +1514,31 @@ pub fn load_config(
     };
 
     let validator_file = dir.join(&config.validator_key_file);
-    let validator_signer = if validator_file.exists() {
+    let validator_signer = if config.node_role == NodeRole::ReadReplica {
+        if validator_file.exists() {
+            tracing::warn!(
+                target: "config",
+                validator_key_file = %validator_file.display(),
+                "Ignoring the validator key of a read replica"
+            );
+        }
+        None
+    } else if let Some(account_id) = &config.shadow_validator_account_id {
+        let signer = InMemoryValidatorSigner::from_random(account_id.clone(), KeyType::ED25519);
+        Some(Arc::new(signer) as Arc<dyn ValidatorSigner>)
+    } else if let Some(remote_signer_config) = &config.remote_validator_signer {
+        match crate::remote_signer::create_remote_validator_signer(
+            remote_signer_config.clone(),
+            &validator_file,
+        ) {
+            Ok(signer) => Some(Arc::new(signer) as Arc<dyn ValidatorSigner>),
+            Err(err) => {
+                let error_message = format!("Failed initializing remote validator signer: {err:#}");
+                validation_errors.push_validator_key_file_error(error_message);
+                None
+            }
+        }
+    } else if validator_file.exists() {
         match InMemoryValidatorSigner::from_file(&validator_file) {
             Ok(signer) => Some(Arc::new(signer) as Arc<dyn ValidatorSigner>),
             Err(_) => {
@@ -1303,12 +1608,22 @@ pub fn load_config(
     if genesis.is_none() || network_signer.is_none() {
         panic!("Genesis and network_signer should not be None by now.")
     }
-    let near_config = NearConfig::new(
-        config,
-        genesis.unwrap(),
-        network_signer.unwrap().into(),
-        validator_signer,
+    let mut genesis = genesis.unwrap();
+    let epoch_config_overrides = match &config.epoch_config_override_file {
+        Some(file) => load_epoch_config_overrides(&dir.join(file), &genesis.config.chain_id)?,
+        None => None,
+    };
+    if let Some(overrides) = &epoch_config_overrides {
+        overrides.apply_to_genesis(&mut genesis.config);
+    }
+    let runtime_params_overrides = load_runtime_params_overrides(
+        &dir.join(RUNTIME_PARAMS_OVERRIDE_FILE),
+        &genesis.config.chain_id,
     )?;
+    let mut near_config =
+        NearConfig::new(config, genesis, network_signer.unwrap().into(), validator_signer)?;
+    near_config.epoch_config_overrides = epoch_config_overrides.map(|file| file.overrides);
+    near_config.runtime_params_overrides = runtime_params_overrides;
     Ok(near_config)
 }
 
@@ -1340,14 +1655,17 @@ mod tests {
     use std::str::FromStr;
 
     use near_async::time::Duration;
-    use near_chain_configs::{GCConfig, Genesis, GenesisValidationMode};
+    use near_chain_configs::{
+        default_produce_chunk_add_transactions_time_limit, GCConfig, Genesis, GenesisValidationMode,
+    };
     use near_crypto::InMemorySigner;
     use near_primitives::shard_layout::account_id_to_shard_id;
     use near_primitives::types::{AccountId, NumShards};
     use tempfile::tempdir;
 
     use crate::config::{
-        create_testnet_configs, generate_or_load_key, init_configs, Config, CONFIG_FILENAME,
+        create_testnet_configs, generate_or_load_key, init_configs, load_config,
+        load_epoch_config_overrides, load_runtime_params_overrides, Config, CONFIG_FILENAME,
     };
 
     #[test]
@@ -1494,6 +1812,7 @@ mod tests {
                     gc_fork_clean_step: 420,
                     gc_num_epochs_to_keep: 24,
                     gc_step_period: Duration::seconds(1),
+                    gc_column_group_num_epochs_to_keep: Default::default(),
                 }
             } else {
                 GCConfig {
@@ -1501,6 +1820,7 @@ mod tests {
                     gc_fork_clean_step: 100,
                     gc_num_epochs_to_keep: 5,
                     gc_step_period: Duration::seconds(1),
+                    gc_column_group_num_epochs_to_keep: Default::default(),
                 }
             };
             assert_eq!(want_gc, config.gc);
@@ -1572,6 +1892,28 @@ mod tests {
         assert_eq!(genesis.config.shard_layout.shard_ids().count() as NumShards, num_shards);
     }
 
+    #[test]
+    fn test_produce_chunk_add_transactions_time_limit() {
+        let mut config = Config::default();
+        assert_eq!(config.produce_chunk_add_transactions_time_limit, None);
+        assert_eq!(
+            Some(config.produce_chunk_add_transactions_time_limit_or_default()),
+            default_produce_chunk_add_transactions_time_limit()
+        );
+
+        config.consensus.min_block_production_delay = Duration::milliseconds(1_200);
+        assert_eq!(
+            config.produce_chunk_add_transactions_time_limit_or_default(),
+            Duration::milliseconds(400)
+        );
+
+        config.produce_chunk_add_transactions_time_limit = Some(Duration::milliseconds(50));
+        assert_eq!(
+            config.produce_chunk_add_transactions_time_limit_or_default(),
+            Duration::milliseconds(50)
+        );
+    }
+
     #[test]
     fn test_generate_or_load_key() {
         let tmp = tempfile::tempdir().unwrap();
@@ -1628,4 +1970,79 @@ mod tests {
         }
         test_err("bad_key", "fred", "");
     }
+    #[test]
+    fn test_load_epoch_config_overrides() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("epoch_config_overrides.json");
+        std::fs::write(
+            &path,
+            r#"{"chain_ids": ["benchmarknet", "mainnet"], "epoch_length": 100, "num_chunk_producer_seats": 8}"#,
+        )
+        .unwrap();
+
+        let file = load_epoch_config_overrides(&path, "benchmarknet").unwrap().unwrap();
+        assert_eq!(file.epoch_length, Some(100));
+        assert_eq!(file.overrides.num_chunk_producer_seats, Some(8));
+        assert_eq!(file.overrides.num_block_producer_seats, None);
+        assert!(load_epoch_config_overrides(&path, "forknet").unwrap().is_none());
+        assert!(load_epoch_config_overrides(&path, "mainnet").is_err());
+    }
+
+    /// The overridden epoch length is the one of the genesis config, which the
+    /// client config and the chain are created from.
+    #[test]
+    fn test_load_config_with_epoch_config_overrides() {
+        let temp_dir = tempdir().unwrap();
+        init_configs(
+            &temp_dir.path(),
+            Some("localnet".to_string()),
+            None,
+            Some("seed1"),
+            1,
+            false,
+            None,
+            false,
+            None,
+            None,
+            false,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        std::fs::write(
+            temp_dir.path().join("epoch_config_overrides.json"),
+            r#"{"chain_ids": ["localnet"], "epoch_length": 123, "chunk_producer_kickout_threshold": 10}"#,
+        )
+        .unwrap();
+        let config_path = temp_dir.path().join(CONFIG_FILENAME);
+        let mut config = Config::from_file(&config_path).unwrap();
+        config.epoch_config_override_file = Some("epoch_config_overrides.json".to_string());
+        config.write_to_file(&config_path).unwrap();
+
+        let near_config = load_config(temp_dir.path(), GenesisValidationMode::Full).unwrap();
+        assert_eq!(near_config.genesis.config.epoch_length, 123);
+        assert_eq!(near_config.client_config.epoch_length, 123);
+        assert_eq!(near_config.genesis.config.chunk_producer_kickout_threshold, 10);
+        let overrides = near_config.epoch_config_overrides.unwrap();
+        assert_eq!(overrides.chunk_producer_kickout_threshold, Some(10));
+    }
+
+    #[test]
+    fn test_load_runtime_params_overrides() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join(super::RUNTIME_PARAMS_OVERRIDE_FILE);
+        assert!(load_runtime_params_overrides(&path, "localnet").unwrap().is_none());
+
+        std::fs::write(&path, "storage_amount_per_byte: 1").unwrap();
+        for chain_id in ["localnet", "test-chain-abcde"] {
+            assert_eq!(
+                load_runtime_params_overrides(&path, chain_id).unwrap().as_deref(),
+                Some("storage_amount_per_byte: 1")
+            );
+        }
+        for chain_id in ["testnet", "mainnet", "benchmarknet"] {
+            assert!(load_runtime_params_overrides(&path, chain_id).unwrap().is_none());
+        }
+    }
 }
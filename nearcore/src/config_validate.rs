@@ -1,9 +1,11 @@
-use near_chain_configs::{ExternalStorageLocation, SyncConfig};
+use near_async::time::Duration;
+use near_chain_configs::{ExternalStorageLocation, NodeRole, SyncConfig};
 use near_config_utils::{ValidationError, ValidationErrors};
+use near_store::StoreBackend;
 use std::collections::HashSet;
 use std::path::Path;
 
-use crate::config::Config;
+use crate::config::{Config, NearConfig, CONFIG_FILENAME};
 
 /// Validate Config extracted from config.json.
 /// This function does not panic. It returns the error if any validation fails.
@@ -148,6 +150,17 @@ impl<'a> ConfigValidator<'a> {
             }
         }
 
+        if let Some(db_checkpoints) = &self.config.store.db_checkpoints {
+            if db_checkpoints.retention == 0 || db_checkpoints.period <= Duration::ZERO {
+                let error_message = format!("'config.store.db_checkpoints.retention' and 'config.store.db_checkpoints.period' should be greater than 0, got {} and {:?}.", db_checkpoints.retention, db_checkpoints.period);
+                self.validation_errors.push_config_semantics_error(error_message);
+            }
+            if self.config.store.backend != StoreBackend::RocksDB {
+                let error_message = "'config.store.db_checkpoints' can only be set with 'config.store.backend' rocksdb, the other backends can't be checkpointed.".to_string();
+                self.validation_errors.push_config_semantics_error(error_message);
+            }
+        }
+
         let tx_routing_height_horizon = self.config.tx_routing_height_horizon;
         if tx_routing_height_horizon < 2 {
             let error_message = format!("'config.tx_routing_height_horizon' needs to be at least 2, got {tx_routing_height_horizon}.");
@@ -157,6 +170,49 @@ impl<'a> ConfigValidator<'a> {
             let error_message = format!("'config.tx_routing_height_horizon' can't be too high to avoid spamming the network. Keep it below 100. Got {tx_routing_height_horizon}.");
             self.validation_errors.push_config_semantics_error(error_message);
         }
+
+        if self.config.shadow_validator_account_id.is_some()
+            && self.config.remote_validator_signer.is_some()
+        {
+            let error_message = "'config.shadow_validator_account_id' and 'config.remote_validator_signer' can't be both set, a shadow validator signs with a random key.".to_string();
+            self.validation_errors.push_config_semantics_error(error_message);
+        }
+
+        #[cfg(feature = "json_rpc")]
+        if let Some(rpc) = &self.config.rpc {
+            for (method, limit) in &rpc.limits_config.method_rate_limits {
+                if let Err(err) = limit.validate() {
+                    let error_message = format!(
+                        "'config.rpc.limits_config.method_rate_limits.{method}' is invalid: {err}."
+                    );
+                    self.validation_errors.push_config_semantics_error(error_message);
+                }
+            }
+            if let Some(limit) = &rpc.limits_config.transaction_signer_rate_limit {
+                if let Err(err) = limit.validate() {
+                    let error_message = format!(
+                        "'config.rpc.limits_config.transaction_signer_rate_limit' is invalid: {err}."
+                    );
+                    self.validation_errors.push_config_semantics_error(error_message);
+                }
+            }
+        }
+
+        #[cfg(feature = "grpc")]
+        if let Some(grpc) = &self.config.grpc {
+            if grpc.max_streams == 0 || grpc.max_streams_per_client == 0 {
+                let error_message = "'config.grpc.max_streams' and 'config.grpc.max_streams_per_client' should be greater than 0.".to_string();
+                self.validation_errors.push_config_semantics_error(error_message);
+            }
+        }
+
+        if self.config.node_role == NodeRole::ReadReplica
+            && (self.config.shadow_validator_account_id.is_some()
+                || self.config.remote_validator_signer.is_some())
+        {
+            let error_message = "'config.shadow_validator_account_id' and 'config.remote_validator_signer' can't be set with 'config.node_role' read_replica, a read replica doesn't sign.".to_string();
+            self.validation_errors.push_config_semantics_error(error_message);
+        }
     }
 
     fn result_with_full_error(&self) -> Result<(), ValidationError> {
@@ -169,6 +225,115 @@ impl<'a> ConfigValidator<'a> {
     }
 }
 
+/// Fields of `node_key.json` and `validator_key.json`.
+const KEY_FILE_FIELDS: &[&str] = &["account_id", "public_key", "secret_key", "private_key"];
+
+/// Checks done by `neard validate-config --strict` on top of the checks done
+/// when the node starts, for a config that passed those. Unknown fields and
+/// inconsistent combinations of settings, which the node otherwise accepts,
+/// are reported as errors.
+pub fn validate_config_strict(home_dir: &Path, near_config: &NearConfig) -> anyhow::Result<()> {
+    let mut validation_errors = ValidationErrors::new();
+    let config = &near_config.config;
+
+    let config_file = home_dir.join(CONFIG_FILENAME);
+    match unrecognised_config_fields(&config_file) {
+        Ok(fields) => {
+            for field in fields {
+                validation_errors.push_config_file_error(format!("unknown field 'config.{field}'"));
+            }
+        }
+        Err(error_message) => validation_errors.push_config_file_error(error_message),
+    }
+    for field in unrecognised_key_file_fields(&home_dir.join(&config.node_key_file)) {
+        validation_errors.push_node_key_file_error(format!("unknown field '{field}'"));
+    }
+    let validator_key_file = home_dir.join(&config.validator_key_file);
+    if validator_key_file.exists() {
+        for field in unrecognised_key_file_fields(&validator_key_file) {
+            validation_errors.push_validator_key_file_error(format!("unknown field '{field}'"));
+        }
+    }
+
+    validate_config_consistency(
+        config,
+        near_config.validator_signer.is_some(),
+        &mut validation_errors,
+    );
+
+    if let Some(validator_signer) = &near_config.validator_signer {
+        if validator_signer.public_key() == near_config.network_config.node_key.public_key() {
+            validation_errors.push_cross_file_semantics_error(
+                "validator_key.json and node_key.json contain the same key. Use a separate key for the node, the node key is used on every network connection.".to_string(),
+            );
+        }
+    }
+
+    validation_errors.return_ok_or_error()
+}
+
+/// Reports combinations of settings which are valid on their own but don't
+/// make sense together.
+fn validate_config_consistency(
+    config: &Config,
+    is_validator: bool,
+    validation_errors: &mut ValidationErrors,
+) {
+    if !config.archive && config.cold_store.is_some() {
+        validation_errors.push_config_semantics_error("'config.cold_store' is configured, but 'config.archive' is false. Only archival nodes keep a cold storage, set 'config.archive' to true or remove 'config.cold_store'.".to_string());
+    }
+    if !config.archive && config.split_storage.is_some() {
+        validation_errors.push_config_semantics_error("'config.split_storage' is configured, but 'config.archive' is false. Only archival nodes use split storage, set 'config.archive' to true or remove 'config.split_storage'.".to_string());
+    }
+    if config.archive && config.cold_store.is_none() && config.split_storage.is_some() {
+        validation_errors.push_config_semantics_error("'config.split_storage' is configured, but 'config.cold_store' is not. Split storage needs a cold storage.".to_string());
+    }
+    if config.archive && config.tracked_shards.is_empty() {
+        validation_errors.push_config_semantics_error("'config.archive' is true, but 'config.tracked_shards' is empty. Archival nodes have to track all shards, set 'config.tracked_shards' to [0].".to_string());
+    }
+    if !is_validator
+        && config.tracked_shards.is_empty()
+        && config.tracked_accounts.is_empty()
+        && config.tracked_shard_schedule.as_ref().map_or(true, |schedule| schedule.is_empty())
+    {
+        validation_errors.push_config_semantics_error("The node has no validator key and 'config.tracked_shards', 'config.tracked_accounts' and 'config.tracked_shard_schedule' are empty, so it doesn't track any shard. Set 'config.tracked_shards' to [0] to track all shards.".to_string());
+    }
+    if is_validator && config.tracked_shard_schedule.is_some() {
+        validation_errors.push_config_semantics_error("'config.tracked_shard_schedule' is configured for a validator. Validators track the shards they are assigned to, remove 'config.tracked_shard_schedule'.".to_string());
+    }
+}
+
+fn unrecognised_config_fields(path: &Path) -> Result<Vec<String>, String> {
+    let json_str = std::fs::read_to_string(path)
+        .and_then(|json_str| near_config_utils::strip_comments_from_json_str(&json_str))
+        .map_err(|err| format!("Failed to read config from {}: {err}", path.display()))?;
+    let mut unrecognised_fields = vec![];
+    serde_ignored::deserialize::<_, _, Config>(
+        &mut serde_json::Deserializer::from_str(&json_str),
+        |field| unrecognised_fields.push(field.to_string()),
+    )
+    .map_err(|err| format!("Failed to deserialize config from {}: {err}", path.display()))?;
+    Ok(unrecognised_fields)
+}
+
+/// Files which fail to load are already reported when loading the config.
+fn unrecognised_key_file_fields(path: &Path) -> Vec<String> {
+    let Ok(json_str) = std::fs::read_to_string(path)
+        .and_then(|json_str| near_config_utils::strip_comments_from_json_str(&json_str))
+    else {
+        return vec![];
+    };
+    let Ok(fields) = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&json_str)
+    else {
+        return vec![];
+    };
+    fields
+        .into_iter()
+        .map(|(field, _)| field)
+        .filter(|field| !KEY_FILE_FIELDS.contains(&field.as_str()))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -250,4 +415,120 @@ mod tests {
         config.tx_routing_height_horizon = 1_000_000_000;
         validate_config(&config).unwrap();
     }
+
+    #[test]
+    #[should_panic(
+        expected = "\\nconfig.json semantic issue: 'config.store.db_checkpoints' can only be set with 'config.store.backend' rocksdb, the other backends can't be checkpointed."
+    )]
+    fn test_in_memory_db_checkpoints() {
+        let mut config = Config::default();
+        config.store.backend = StoreBackend::InMemory;
+        config.store.db_checkpoints =
+            Some(serde_json::from_value(serde_json::json!({"path": "checkpoints"})).unwrap());
+        validate_config(&config).unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "\\nconfig.json semantic issue: 'config.shadow_validator_account_id' and 'config.remote_validator_signer' can't be set with 'config.node_role' read_replica, a read replica doesn't sign."
+    )]
+    fn test_read_replica_shadow_validator() {
+        let mut config = Config::default();
+        config.node_role = NodeRole::ReadReplica;
+        config.shadow_validator_account_id = Some("test".parse().unwrap());
+        validate_config(&config).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "json_rpc")]
+    #[should_panic(
+        expected = "\\nconfig.json semantic issue: 'config.rpc.limits_config.method_rate_limits.query' is invalid: requests_per_second must be a finite number of at least 0.001, got NaN."
+    )]
+    fn test_rpc_method_rate_limit_nan() {
+        let mut config = Config::default();
+        config.rpc.as_mut().unwrap().limits_config.method_rate_limits.insert(
+            "query".to_string(),
+            near_jsonrpc::RpcRateLimit { requests_per_second: f64::NAN, burst: 1 },
+        );
+        validate_config(&config).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "json_rpc")]
+    #[should_panic(
+        expected = "\\nconfig.json semantic issue: 'config.rpc.limits_config.transaction_signer_rate_limit' is invalid: burst must be greater than 0."
+    )]
+    fn test_rpc_transaction_signer_rate_limit_zero_burst() {
+        let mut config = Config::default();
+        config.rpc.as_mut().unwrap().limits_config.transaction_signer_rate_limit =
+            Some(near_jsonrpc::RpcRateLimit { requests_per_second: 1.0, burst: 0 });
+        validate_config(&config).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "grpc")]
+    #[should_panic(
+        expected = "\\nconfig.json semantic issue: 'config.grpc.max_streams' and 'config.grpc.max_streams_per_client' should be greater than 0."
+    )]
+    fn test_grpc_zero_max_streams_per_client() {
+        let mut config = Config::default();
+        config.grpc =
+            Some(near_grpc::GrpcConfig { max_streams_per_client: 0, ..Default::default() });
+        validate_config(&config).unwrap();
+    }
+
+    fn config_consistency_errors(config: &Config, is_validator: bool) -> anyhow::Result<()> {
+        let mut validation_errors = ValidationErrors::new();
+        validate_config_consistency(config, is_validator, &mut validation_errors);
+        validation_errors.return_ok_or_error()
+    }
+
+    #[test]
+    fn test_config_consistency() {
+        let mut config = Config::default();
+        config.tracked_shards = vec![0];
+        config_consistency_errors(&config, false).unwrap();
+        config_consistency_errors(&config, true).unwrap();
+
+        // A non-validator which doesn't track any shard.
+        config.tracked_shards = vec![];
+        let err = config_consistency_errors(&config, false).unwrap_err().to_string();
+        assert!(err.contains("it doesn't track any shard"), "{err}");
+        config_consistency_errors(&config, true).unwrap();
+
+        // An archival node which doesn't track all shards.
+        config.archive = true;
+        let err = config_consistency_errors(&config, true).unwrap_err().to_string();
+        assert!(err.contains("Archival nodes have to track all shards"), "{err}");
+
+        // Cold storage of a non-archival node.
+        config.archive = false;
+        config.tracked_shards = vec![0];
+        config.cold_store = Some(config.store.clone());
+        let err = config_consistency_errors(&config, true).unwrap_err().to_string();
+        assert!(err.contains("'config.cold_store' is configured, but 'config.archive' is false"));
+    }
+
+    #[test]
+    fn test_unrecognised_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_file = dir.path().join(CONFIG_FILENAME);
+        std::fs::write(
+            &config_file,
+            r#"{"archive": true, "achrive": true, "gc": {"gc_blocks_limits": 1}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            unrecognised_config_fields(&config_file).unwrap(),
+            vec!["achrive".to_string(), "gc.gc_blocks_limits".to_string()]
+        );
+
+        let key_file = dir.path().join("node_key.json");
+        std::fs::write(
+            &key_file,
+            r#"{"account_id": "", "public_key": "", "secret_key": "", "comment": ""}"#,
+        )
+        .unwrap();
+        assert_eq!(unrecognised_key_file_fields(&key_file), vec!["comment".to_string()]);
+    }
 }
@@ -0,0 +1,107 @@
+//! Periodic checkpoints of the hot database, to be used as the restore points
+//! of backups, see `DbCheckpointsConfig`.
+use actix_rt::ArbiterHandle;
+use near_store::config::DbCheckpointsConfig;
+use near_store::ShardTries;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const CHECKPOINT_PREFIX: &str = "checkpoint-";
+/// Suffix of a checkpoint being created, renamed once it is complete.
+const INCOMPLETE_SUFFIX: &str = ".incomplete";
+
+/// Spawns a background loop creating the checkpoints, if they are configured.
+pub fn spawn_db_checkpoints_loop(
+    home_dir: &Path,
+    config: Option<&DbCheckpointsConfig>,
+    tries: ShardTries,
+) -> anyhow::Result<Option<ArbiterHandle>> {
+    let Some(config) = config else {
+        return Ok(None);
+    };
+    let dir = home_dir.join(&config.path);
+    std::fs::create_dir_all(&dir)?;
+    tracing::info!(target: "db_checkpoints", dir = %dir.display(), period = ?config.period, "Spawning the database checkpoints loop");
+
+    let arbiter = actix_rt::Arbiter::new();
+    let mut interval = actix_rt::time::interval(config.period.unsigned_abs());
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    let retention = config.retention;
+    arbiter.spawn(async move {
+        // The first tick completes right away, the node has just started.
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            match create_checkpoint(&dir, &tries) {
+                Ok(path) => {
+                    tracing::info!(target: "db_checkpoints", path = %path.display(), "Created a database checkpoint")
+                }
+                Err(err) => {
+                    tracing::error!(target: "db_checkpoints", ?err, "Failed to create a database checkpoint")
+                }
+            }
+            if let Err(err) = remove_old_checkpoints(&dir, retention) {
+                tracing::error!(target: "db_checkpoints", ?err, "Failed to remove the old database checkpoints");
+            }
+        }
+    });
+    Ok(Some(arbiter.handle()))
+}
+
+fn create_checkpoint(dir: &Path, tries: &ShardTries) -> anyhow::Result<PathBuf> {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let path = dir.join(format!("{CHECKPOINT_PREFIX}{timestamp}"));
+    let incomplete_path = dir.join(format!("{CHECKPOINT_PREFIX}{timestamp}{INCOMPLETE_SUFFIX}"));
+    tries.create_db_checkpoint(&incomplete_path)?;
+    std::fs::rename(&incomplete_path, &path)?;
+    Ok(path)
+}
+
+/// Removes the checkpoints beyond the newest `retention` ones, and the
+/// incomplete ones left over by a crash.
+fn remove_old_checkpoints(dir: &Path, retention: usize) -> anyhow::Result<()> {
+    let mut checkpoints = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let Some(name) = name.strip_prefix(CHECKPOINT_PREFIX) else {
+            continue;
+        };
+        if let Ok(timestamp) = name.parse::<u64>() {
+            checkpoints.push((timestamp, path));
+        } else if name.ends_with(INCOMPLETE_SUFFIX) {
+            std::fs::remove_dir_all(&path)?;
+        }
+    }
+    checkpoints.sort();
+    let excess = checkpoints.len().saturating_sub(retention);
+    for (_, path) in checkpoints.into_iter().take(excess) {
+        tracing::info!(target: "db_checkpoints", path = %path.display(), "Removing a database checkpoint");
+        std::fs::remove_dir_all(&path)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remove_old_checkpoints() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in
+            ["checkpoint-30", "checkpoint-10", "checkpoint-20", "checkpoint-40.incomplete", "other"]
+        {
+            std::fs::create_dir(dir.path().join(name)).unwrap();
+        }
+        remove_old_checkpoints(dir.path(), 2).unwrap();
+        let mut names: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["checkpoint-20", "checkpoint-30", "other"]);
+    }
+}
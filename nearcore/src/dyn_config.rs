@@ -1,15 +1,37 @@
 use crate::config::Config;
-use near_chain_configs::UpdateableClientConfig;
+use near_chain_configs::{ExternalStorageConfig, SyncConfig, UpdateableClientConfig};
 use near_dyn_configs::{UpdateableConfigLoaderError, UpdateableConfigs};
 use near_o11y::log_config::LogConfig;
+use near_primitives::validator_signer::{InMemoryValidatorSigner, ValidatorSigner};
 use serde::Deserialize;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
 
 pub const LOG_CONFIG_FILENAME: &str = "log_config.json";
 
+/// Fields of `config.json` which are applied while the node is running, with
+/// nested fields separated by dots. Changes of all the other fields require a
+/// restart.
+/// Keep this list in-sync with `get_updateable_client_config()` and
+/// `core/dyn-configs/README.md`.
+pub const UPDATEABLE_CONFIG_FIELDS: &[&str] = &[
+    "expected_shutdown",
+    "resharding_config",
+    "produce_chunk_add_transactions_time_limit",
+    "transaction_pool_size_limit",
+    "state_sync.sync.ExternalStorage.num_concurrent_requests",
+    "state_sync.sync.ExternalStorage.num_concurrent_requests_during_catchup",
+];
+
 /// This function gets called at the startup and each time a config needs to be reloaded.
+/// If `running_config` is given, reports which changes of `config.json` compared
+/// to it are applied and which ones require a restart. Once the configs are
+/// read successfully, the applied fields of `running_config` are updated, so
+/// that the next reload is compared to the config the node is actually running.
 pub fn read_updateable_configs(
     home_dir: &Path,
+    running_config: Option<&mut Config>,
 ) -> Result<UpdateableConfigs, UpdateableConfigLoaderError> {
     let mut errs = vec![];
     let log_config = match read_log_config(home_dir) {
@@ -19,22 +41,39 @@ pub fn read_updateable_configs(
             None
         }
     };
-    let updateable_client_config =
-        match Config::from_file(&home_dir.join(crate::config::CONFIG_FILENAME))
-            .map(get_updateable_client_config)
-        {
-            Ok(config) => Some(config),
-            Err(err) => {
-                errs.push(UpdateableConfigLoaderError::ConfigFileError {
-                    file: PathBuf::from(crate::config::CONFIG_FILENAME),
-                    err: err.into(),
-                });
+    let config = match Config::from_file(&home_dir.join(crate::config::CONFIG_FILENAME)) {
+        Ok(config) => Some(config),
+        Err(err) => {
+            errs.push(UpdateableConfigLoaderError::ConfigFileError {
+                file: PathBuf::from(crate::config::CONFIG_FILENAME),
+                err: err.into(),
+            });
+            None
+        }
+    };
+    if let (Some(running_config), Some(config)) = (&running_config, &config) {
+        report_config_changes(&diff_configs(running_config, config));
+    }
+    let validator_signer =
+        match config.as_ref().map(|config| read_validator_signer(home_dir, config)) {
+            Some(Ok(signer)) => signer,
+            Some(Err(err)) => {
+                errs.push(err);
                 None
             }
+            None => None,
         };
+    if let (Some(running_config), Some(config), true) = (running_config, &config, errs.is_empty()) {
+        apply_updateable_fields(running_config, config);
+    }
+    let updateable_client_config = config.map(get_updateable_client_config);
     if errs.is_empty() {
         crate::metrics::CONFIG_CORRECT.set(1);
-        Ok(UpdateableConfigs { log_config, client_config: updateable_client_config })
+        Ok(UpdateableConfigs {
+            log_config,
+            client_config: updateable_client_config,
+            validator_signer,
+        })
     } else {
         tracing::warn!(target: "neard", "Dynamically updateable configs are not valid. Please fix this ASAP otherwise the node will be unable to restart: {:?}", &errs);
         crate::metrics::CONFIG_CORRECT.set(0);
@@ -43,12 +82,164 @@ pub fn read_updateable_configs(
 }
 
 pub fn get_updateable_client_config(config: Config) -> UpdateableClientConfig {
+    let external_storage_config = external_storage_config(&config);
+    let state_sync_num_concurrent_requests =
+        external_storage_config.map(|external| external.num_concurrent_requests);
+    let state_sync_num_concurrent_requests_during_catchup =
+        external_storage_config.map(|external| external.num_concurrent_requests_during_catchup);
     // All fields that can be updated while the node is running should be explicitly set here.
-    // Keep this list in-sync with `core/dyn-configs/README.md`.
+    // Keep this list in-sync with `UPDATEABLE_CONFIG_FIELDS` and `core/dyn-configs/README.md`.
     UpdateableClientConfig {
         expected_shutdown: config.expected_shutdown,
         resharding_config: config.resharding_config,
-        produce_chunk_add_transactions_time_limit: config.produce_chunk_add_transactions_time_limit,
+        produce_chunk_add_transactions_time_limit: Some(
+            config.produce_chunk_add_transactions_time_limit_or_default(),
+        ),
+        transaction_pool_size_limit: config.transaction_pool_size_limit,
+        state_sync_num_concurrent_requests,
+        state_sync_num_concurrent_requests_during_catchup,
+    }
+}
+
+fn external_storage_config(config: &Config) -> Option<&ExternalStorageConfig> {
+    match &config.state_sync.as_ref()?.sync {
+        SyncConfig::ExternalStorage(external) => Some(external),
+        SyncConfig::Peers => None,
+    }
+}
+
+/// Copies the fields which are applied while the node is running from the new
+/// config to the running one.
+/// Keep this list in-sync with `UPDATEABLE_CONFIG_FIELDS`.
+fn apply_updateable_fields(running_config: &mut Config, new_config: &Config) {
+    running_config.expected_shutdown = new_config.expected_shutdown;
+    running_config.resharding_config = new_config.resharding_config;
+    running_config.produce_chunk_add_transactions_time_limit =
+        new_config.produce_chunk_add_transactions_time_limit;
+    running_config.transaction_pool_size_limit = new_config.transaction_pool_size_limit;
+    let running_external =
+        running_config.state_sync.as_mut().and_then(|state_sync| match &mut state_sync.sync {
+            SyncConfig::ExternalStorage(external) => Some(external),
+            SyncConfig::Peers => None,
+        });
+    if let (Some(running), Some(new)) = (running_external, external_storage_config(new_config)) {
+        running.num_concurrent_requests = new.num_concurrent_requests;
+        running.num_concurrent_requests_during_catchup = new.num_concurrent_requests_during_catchup;
+    }
+}
+
+/// Changed fields of `config.json`, with nested fields separated by dots.
+#[derive(Debug, Default, PartialEq)]
+pub struct ConfigChanges {
+    /// Changed fields which are applied while the node is running.
+    pub accepted: Vec<String>,
+    /// Changed fields which are only applied after a restart.
+    pub rejected: Vec<String>,
+}
+
+/// Compares the config the node is running with to the new one.
+pub fn diff_configs(running_config: &Config, new_config: &Config) -> ConfigChanges {
+    let mut changed = vec![];
+    diff_json(
+        "",
+        &serde_json::to_value(running_config).unwrap_or_default(),
+        &serde_json::to_value(new_config).unwrap_or_default(),
+        &mut changed,
+    );
+    let mut changes = ConfigChanges::default();
+    for field in changed {
+        let accepted = UPDATEABLE_CONFIG_FIELDS
+            .iter()
+            .any(|updateable| field == *updateable || field.starts_with(&format!("{updateable}.")));
+        if accepted {
+            changes.accepted.push(field);
+        } else {
+            changes.rejected.push(field);
+        }
+    }
+    changes
+}
+
+fn diff_json(
+    path: &str,
+    old: &serde_json::Value,
+    new: &serde_json::Value,
+    changed: &mut Vec<String>,
+) {
+    let (serde_json::Value::Object(old), serde_json::Value::Object(new)) = (old, new) else {
+        if old != new {
+            changed.push(path.to_string());
+        }
+        return;
+    };
+    let keys: std::collections::BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    for key in keys {
+        let field = if path.is_empty() { key.clone() } else { format!("{path}.{key}") };
+        let null = serde_json::Value::Null;
+        diff_json(&field, old.get(key).unwrap_or(&null), new.get(key).unwrap_or(&null), changed);
+    }
+}
+
+fn report_config_changes(changes: &ConfigChanges) {
+    if !changes.accepted.is_empty() {
+        tracing::info!(target: "neard", accepted = ?changes.accepted, "Applying the changed config fields");
+    }
+    if !changes.rejected.is_empty() {
+        tracing::warn!(target: "neard", rejected = ?changes.rejected, "Changes of these config fields are only applied after a restart");
+    }
+    crate::metrics::CONFIG_REJECTED_CHANGES.set(changes.rejected.len() as i64);
+}
+
+/// Detects changes of the files of the dynamic configs by polling their
+/// modification times.
+pub struct ConfigFilesWatcher {
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl ConfigFilesWatcher {
+    pub fn new(home_dir: &Path, config: &Config) -> Self {
+        let files = [
+            home_dir.join(crate::config::CONFIG_FILENAME),
+            home_dir.join(LOG_CONFIG_FILENAME),
+            home_dir.join(&config.validator_key_file),
+        ];
+        Self { files: files.into_iter().map(|file| (file.clone(), modified(&file))).collect() }
+    }
+
+    /// Whether any of the files changed since the last call.
+    pub fn changed(&mut self) -> bool {
+        let mut changed = false;
+        for (file, last_modified) in &mut self.files {
+            let modified = modified(file);
+            if modified != *last_modified {
+                tracing::info!(target: "neard", ?file, "Config file changed");
+                *last_modified = modified;
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+fn modified(file: &Path) -> Option<SystemTime> {
+    std::fs::metadata(file).and_then(|metadata| metadata.modified()).ok()
+}
+
+/// Reads the validator key to rotate to. Keys held by a remote signer are not
+/// rotated by the node.
+fn read_validator_signer(
+    home_dir: &Path,
+    config: &Config,
+) -> Result<Option<Arc<dyn ValidatorSigner>>, UpdateableConfigLoaderError> {
+    let validator_file = home_dir.join(&config.validator_key_file);
+    if config.remote_validator_signer.is_some() || !validator_file.exists() {
+        return Ok(None);
+    }
+    match InMemoryValidatorSigner::from_file(&validator_file) {
+        Ok(signer) => Ok(Some(Arc::new(signer))),
+        Err(err) => {
+            Err(UpdateableConfigLoaderError::ValidatorKeyFile { file: validator_file, err })
+        }
     }
 }
 
@@ -87,3 +278,54 @@ where
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_configs() {
+        let running_config = Config::default();
+        assert_eq!(diff_configs(&running_config, &running_config), ConfigChanges::default());
+
+        let mut new_config = Config::default();
+        new_config.expected_shutdown = Some(100);
+        new_config.transaction_pool_size_limit = Some(1000);
+        new_config.archive = !running_config.archive;
+        new_config.network.max_num_peers += 1;
+        let changes = diff_configs(&running_config, &new_config);
+        assert_eq!(
+            changes,
+            ConfigChanges {
+                accepted: vec![
+                    "expected_shutdown".to_string(),
+                    "transaction_pool_size_limit".to_string()
+                ],
+                rejected: vec!["archive".to_string(), "network.max_num_peers".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn test_reload_updates_running_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_file = dir.path().join(crate::config::CONFIG_FILENAME);
+        let mut running_config = Config::default();
+        let mut new_config = Config::default();
+        new_config.expected_shutdown = Some(100);
+        new_config.network.max_num_peers += 1;
+        new_config.write_to_file(&config_file).unwrap();
+
+        let configs = read_updateable_configs(dir.path(), Some(&mut running_config)).unwrap();
+        assert_eq!(configs.client_config.unwrap().expected_shutdown, Some(100));
+        assert_eq!(running_config.expected_shutdown, Some(100));
+        // Rejected changes are only applied after a restart, so they stay
+        // reported on every reload.
+        assert_eq!(running_config.network.max_num_peers, Config::default().network.max_num_peers);
+        let changes = diff_configs(&running_config, &new_config);
+        assert_eq!(
+            changes,
+            ConfigChanges { accepted: vec![], rejected: vec!["network.max_num_peers".to_string()] }
+        );
+    }
+}
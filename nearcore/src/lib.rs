@@ -1,7 +1,7 @@
 pub use crate::config::NightshadeRuntimeExt;
 pub use crate::config::{init_configs, load_config, load_test_config, NearConfig};
 use crate::entity_debug::EntityDebugHandlerImpl;
-use crate::metrics::spawn_trie_metrics_loop;
+use crate::metrics::{spawn_jemalloc_metrics_loop, spawn_trie_metrics_loop};
 
 use crate::cold_storage::spawn_cold_store_loop;
 use crate::state_sync::StateSyncDumper;
@@ -13,6 +13,7 @@ use near_async::actix::AddrWithAutoSpanContextExt;
 use near_async::actix_wrapper::{spawn_actix_actor, ActixWrapper};
 use near_async::messaging::{noop, IntoMultiSender, IntoSender, LateBoundSender};
 use near_async::time::{self, Clock};
+use near_chain::chunk_apply_results::ChunkApplyResults;
 pub use near_chain::runtime::NightshadeRuntime;
 use near_chain::state_snapshot_actor::{
     get_delete_snapshot_callback, get_make_snapshot_callback, SnapshotCallbacks, StateSnapshotActor,
@@ -26,8 +27,9 @@ use near_client::adapter::client_sender_for_network;
 use near_client::gc_actor::GCActor;
 use near_client::sync::adapter::SyncAdapter;
 use near_client::{
-    start_client, ClientActor, ConfigUpdater, PartialWitnessActor, StartClientResult,
-    ViewClientActor, ViewClientActorInner,
+    start_client, ClientActor, ConfigUpdater, PartialWitnessActor, PartialWitnessHistory,
+    ShadowValidator, ShadowValidatorSender, StartClientResult, ViewClientActor,
+    ViewClientActorInner,
 };
 use near_epoch_manager::shard_tracker::{ShardTracker, TrackedConfig};
 use near_epoch_manager::EpochManager;
@@ -35,6 +37,7 @@ use near_epoch_manager::EpochManagerAdapter;
 use near_network::PeerManagerActor;
 use near_primitives::block::GenesisId;
 use near_primitives::types::EpochId;
+use near_primitives::validator_signer::RotatableValidatorSigner;
 use near_store::flat::FlatStateValuesInliningMigrationHandle;
 use near_store::genesis::initialize_sharded_genesis_state;
 use near_store::metadata::DbKind;
@@ -51,14 +54,17 @@ pub mod cold_storage;
 pub mod config;
 #[cfg(test)]
 mod config_duration_test;
-mod config_validate;
+pub mod config_validate;
+mod db_checkpoints;
 mod download_file;
 pub mod dyn_config;
 #[cfg(feature = "json_rpc")]
 mod entity_debug;
 mod entity_debug_serializer;
 mod metrics;
+pub mod migration_bundle;
 pub mod migrations;
+pub mod remote_signer;
 pub mod state_sync;
 pub mod test_utils;
 
@@ -196,8 +202,10 @@ fn get_split_store(config: &NearConfig, storage: &NodeStorage) -> anyhow::Result
         return Ok(None);
     }
 
-    // SplitStore should only be used in the view client if it is enabled.
-    if !config.config.split_storage.as_ref().is_some_and(|c| c.enable_split_storage_view_client) {
+    // SplitStore is used in the view client unless it is explicitly disabled,
+    // so that queries for data migrated out of the hot store are served from
+    // the cold store.
+    if config.config.split_storage.as_ref().is_some_and(|c| !c.enable_split_storage_view_client) {
         return Ok(None);
     }
 
@@ -227,12 +235,49 @@ pub struct NearNode {
     // A handle that allows the main process to interrupt resharding if needed.
     // This typically happens when the main process is interrupted.
     pub resharding_handle: ReshardingHandle,
+    /// Subscriptions to the results of applying the chunks, for the embedders.
+    pub chunk_apply_results: ChunkApplyResults,
+}
+
+/// Makes all the components of the node share a validator signer whose key
+/// can be rotated when the validator key file is reloaded.
+fn make_validator_signer_rotatable(
+    config: &mut NearConfig,
+) -> Option<Arc<RotatableValidatorSigner>> {
+    // The random key of a shadow validator is never reloaded from the file.
+    if config.config.shadow_validator_account_id.is_some() {
+        return None;
+    }
+    let validator_signer = Arc::new(RotatableValidatorSigner::new(config.validator_signer.take()?));
+    config.validator_signer = Some(validator_signer.clone());
+    if let Some(validator) = &mut config.network_config.validator {
+        validator.signer = validator_signer.clone();
+    }
+    Some(validator_signer)
 }
 
 pub fn start_with_config(home_dir: &Path, config: NearConfig) -> anyhow::Result<NearNode> {
     start_with_config_and_synchronization(home_dir, config, None, None)
 }
 
+/// Compiles the most used contracts in the background, so that the node
+/// doesn't have to wait for it before it starts.
+fn spawn_contract_prewarming(
+    config: &NearConfig,
+    runtime: Arc<NightshadeRuntime>,
+) -> anyhow::Result<()> {
+    let limit = config.config.prewarm_contracts;
+    if limit == 0 {
+        return Ok(());
+    }
+    std::thread::Builder::new().name("prewarm_contracts".to_string()).spawn(move || {
+        if let Err(err) = runtime.prewarm_contracts(limit) {
+            tracing::warn!(target: "neard", ?err, "Failed to prewarm contracts");
+        }
+    })?;
+    Ok(())
+}
+
 pub fn start_with_config_and_synchronization(
     home_dir: &Path,
     mut config: NearConfig,
@@ -241,6 +286,14 @@ pub fn start_with_config_and_synchronization(
     shutdown_signal: Option<broadcast::Sender<()>>,
     config_updater: Option<ConfigUpdater>,
 ) -> anyhow::Result<NearNode> {
+    let config_updater =
+        config_updater.map(|config_updater| match make_validator_signer_rotatable(&mut config) {
+            Some(validator_signer) => config_updater.with_validator_signer(validator_signer),
+            None => config_updater,
+        });
+    if let Some(metrics_config) = &config.config.metrics {
+        near_o11y::metrics::set_metrics_config(metrics_config.clone());
+    }
     let storage = open_storage(home_dir, &mut config)?;
     let db_metrics_arbiter = if config.client_config.enable_statistics_export {
         let period = config.client_config.log_summary_period;
@@ -255,9 +308,14 @@ pub fn start_with_config_and_synchronization(
         storage.get_hot_store(),
         config.client_config.log_summary_period,
     )?;
+    let jemalloc_metrics_arbiter =
+        spawn_jemalloc_metrics_loop(config.client_config.log_summary_period);
 
-    let epoch_manager =
-        EpochManager::new_arc_handle(storage.get_hot_store(), &config.genesis.config);
+    let epoch_manager = EpochManager::new_arc_handle_with_test_overrides(
+        storage.get_hot_store(),
+        &config.genesis.config,
+        config.epoch_config_overrides.clone(),
+    );
     let genesis_epoch_config = epoch_manager.get_epoch_config(&EpochId::default())?;
     // Initialize genesis_state in store either from genesis config or dump before other components.
     // We only initialize if the genesis state is not already initialized in store.
@@ -267,6 +325,7 @@ pub fn start_with_config_and_synchronization(
         &config.genesis,
         &genesis_epoch_config,
         Some(home_dir),
+        config.config.store.genesis_bulk_import,
     );
 
     let shard_tracker =
@@ -278,14 +337,23 @@ pub fn start_with_config_and_synchronization(
         epoch_manager.clone(),
     )
     .context("could not create the transaction runtime")?;
+    spawn_contract_prewarming(&config, runtime.clone())?;
+    let db_checkpoints_arbiter = db_checkpoints::spawn_db_checkpoints_loop(
+        home_dir,
+        config.config.store.db_checkpoints.as_ref(),
+        runtime.get_tries(),
+    )?;
 
     // Get the split store. If split store is some then create a new set of structures for
     // the view client. Otherwise just re-use the existing ones.
     let split_store = get_split_store(&config, &storage)?;
     let (view_epoch_manager, view_shard_tracker, view_runtime) =
         if let Some(split_store) = &split_store {
-            let view_epoch_manager =
-                EpochManager::new_arc_handle(split_store.clone(), &config.genesis.config);
+            let view_epoch_manager = EpochManager::new_arc_handle_with_test_overrides(
+                split_store.clone(),
+                &config.genesis.config,
+                config.epoch_config_overrides.clone(),
+            );
             let view_shard_tracker = ShardTracker::new(
                 TrackedConfig::from_config(&config.client_config),
                 epoch_manager.clone(),
@@ -339,6 +407,7 @@ pub fn start_with_config_and_synchronization(
         network_adapter.as_multi_sender(),
         config.client_config.clone(),
         adv.clone(),
+        cold_store_loop_handle.as_ref().map(|handle| handle.control()),
     );
 
     let state_snapshot_sender = LateBoundSender::new();
@@ -360,6 +429,7 @@ pub fn start_with_config_and_synchronization(
     );
     let snapshot_callbacks = SnapshotCallbacks { make_snapshot_callback, delete_snapshot_callback };
 
+    let partial_witness_history = PartialWitnessHistory::new();
     let (partial_witness_actor, partial_witness_arbiter) = if config.validator_signer.is_some() {
         let my_signer = config.validator_signer.clone().unwrap();
         let (partial_witness_actor, partial_witness_arbiter) =
@@ -369,6 +439,7 @@ pub fn start_with_config_and_synchronization(
                 client_adapter_for_partial_witness_actor.as_multi_sender(),
                 my_signer,
                 epoch_manager.clone(),
+                partial_witness_history.clone(),
             ));
         (Some(partial_witness_actor), Some(partial_witness_arbiter))
     } else {
@@ -384,7 +455,12 @@ pub fn start_with_config_and_synchronization(
         config.client_config.archive,
     ));
 
-    let StartClientResult { client_actor, client_arbiter_handle, resharding_handle } = start_client(
+    let StartClientResult {
+        client_actor,
+        client_arbiter_handle,
+        resharding_handle,
+        chunk_apply_results,
+    } = start_client(
         Clock::real(),
         config.client_config.clone(),
         chain_genesis.clone(),
@@ -405,6 +481,7 @@ pub fn start_with_config_and_synchronization(
             .clone()
             .map(|actor| actor.with_auto_span_context().into_multi_sender())
             .unwrap_or_else(|| noop().into_multi_sender()),
+        partial_witness_history,
         true,
         None,
     );
@@ -419,7 +496,7 @@ pub fn start_with_config_and_synchronization(
         network_adapter.as_sender(),
         client_adapter_for_shards_manager.as_sender(),
         config.validator_signer.as_ref().map(|signer| signer.validator_id().clone()),
-        split_store.unwrap_or_else(|| storage.get_hot_store()),
+        split_store.clone().unwrap_or_else(|| storage.get_hot_store()),
         config.client_config.chunk_request_retry_period,
     );
     shards_manager_adapter.bind(shards_manager_actor.with_auto_span_context());
@@ -459,10 +536,33 @@ pub fn start_with_config_and_synchronization(
         genesis_id,
     )
     .context("PeerManager::spawn()")?;
-    network_adapter.bind(network_actor.clone().with_auto_span_context());
+    let shadow_validator = config.config.shadow_validator_account_id.clone().map(|account_id| {
+        let signer = config.validator_signer.as_ref().expect("shadow validator without a signer");
+        ShadowValidator::new(
+            Clock::real(),
+            hot_store.clone(),
+            account_id,
+            signer.public_key(),
+            &config.client_config,
+        )
+    });
+    network_adapter.bind(ShadowValidatorSender::new(
+        network_actor.clone().with_auto_span_context(),
+        shadow_validator,
+    ));
     if let SyncConfig::Peers = config.client_config.state_sync.sync {
         network_adapter_for_sync.bind(network_actor.clone().with_auto_span_context())
     }
+    #[cfg(feature = "grpc")]
+    let grpc_arbiter = match config.grpc_config {
+        Some(grpc_config) => Some(near_grpc::start_grpc(
+            grpc_config,
+            split_store.unwrap_or_else(|| hot_store.clone()),
+            config.genesis.config.genesis_height,
+        )?),
+        None => None,
+    };
+
     #[cfg(feature = "json_rpc")]
     if let Some(rpc_config) = config.rpc_config {
         let entity_debug_handler = EntityDebugHandlerImpl {
@@ -504,15 +604,23 @@ pub fn start_with_config_and_synchronization(
         client_arbiter_handle,
         shards_manager_arbiter_handle,
         trie_metrics_arbiter,
+        jemalloc_metrics_arbiter,
         state_snapshot_arbiter,
         gc_arbiter,
     ];
     if let Some(db_metrics_arbiter) = db_metrics_arbiter {
         arbiters.push(db_metrics_arbiter);
     }
+    if let Some(db_checkpoints_arbiter) = db_checkpoints_arbiter {
+        arbiters.push(db_checkpoints_arbiter);
+    }
     if let Some(partial_witness_arbiter) = partial_witness_arbiter {
         arbiters.push(partial_witness_arbiter);
     }
+    #[cfg(feature = "grpc")]
+    if let Some(grpc_arbiter) = grpc_arbiter {
+        arbiters.push(grpc_arbiter);
+    }
 
     Ok(NearNode {
         client: client_actor,
@@ -523,6 +631,7 @@ pub fn start_with_config_and_synchronization(
         state_sync_dumper,
         flat_state_migration_handle,
         resharding_handle,
+        chunk_apply_results,
     })
 }
 
@@ -33,6 +33,14 @@ pub(crate) static CONFIG_CORRECT: Lazy<IntGauge> = Lazy::new(|| {
     .unwrap()
 });
 
+pub(crate) static CONFIG_REJECTED_CHANGES: Lazy<IntGauge> = Lazy::new(|| {
+    try_create_int_gauge(
+        "near_config_rejected_changes",
+        "Number of changed config fields which are only applied after a restart",
+    )
+    .unwrap()
+});
+
 pub(crate) static COLD_STORE_COPY_RESULT: Lazy<IntCounterVec> = Lazy::new(|| {
     try_create_int_counter_vec(
         "near_cold_store_copy_result",
@@ -42,6 +50,25 @@ pub(crate) static COLD_STORE_COPY_RESULT: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+pub(crate) static REMOTE_SIGNER_REQUEST_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
+    try_create_histogram_vec(
+        "near_remote_signer_request_latency_sec",
+        "Latency of requests to the remote validator signer, including retries",
+        &["method"],
+        Some(exponential_buckets(0.0001, 2.0, 16).unwrap()),
+    )
+    .unwrap()
+});
+
+pub(crate) static REMOTE_SIGNER_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    try_create_int_counter_vec(
+        "near_remote_signer_requests_total",
+        "Requests to the remote validator signer by result: ok, error, or fallback when the local key was used instead",
+        &["method", "result"],
+    )
+    .unwrap()
+});
+
 pub(crate) static STATE_SYNC_DUMP_ITERATION_ELAPSED: Lazy<HistogramVec> = Lazy::new(|| {
     try_create_histogram_vec(
         "near_state_sync_dump_iteration_elapsed_sec",
@@ -114,8 +141,11 @@ fn export_postponed_receipt_count(near_config: &NearConfig, store: &Store) -> an
         near_config.genesis.config.genesis_height,
         near_config.client_config.save_trie_changes,
     );
-    let epoch_manager =
-        EpochManager::new_from_genesis_config(store.clone(), &near_config.genesis.config)?;
+    let epoch_manager = EpochManager::new_from_genesis_config_with_test_overrides(
+        store.clone(),
+        &near_config.genesis.config,
+        near_config.epoch_config_overrides.clone(),
+    )?;
 
     let head = chain_store.final_head()?;
     let block = chain_store.get_block(&head.last_block_hash)?;
@@ -220,6 +250,25 @@ pub fn spawn_trie_metrics_loop(
     Ok(arbiter.handle())
 }
 
+/// Spawns a background loop that will periodically export the allocator
+/// metrics.
+pub fn spawn_jemalloc_metrics_loop(period: Duration) -> ArbiterHandle {
+    tracing::debug!(target:"metrics", "Spawning the jemalloc metrics loop.");
+    let arbiter = actix_rt::Arbiter::new();
+
+    let mut interval = actix_rt::time::interval(period.unsigned_abs());
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    arbiter.spawn(async move {
+        loop {
+            interval.tick().await;
+            near_o11y::jemalloc::export_metrics();
+        }
+    });
+
+    arbiter.handle()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,329 @@
+//! Bundles of everything a node needs to move to new hardware: the keys, the
+//! config files and a consistent snapshot of the database.
+//!
+//! `neard migrate export` writes the bundle into a new directory, with a
+//! `manifest.json` listing the checksums of the files and the head of the
+//! chain in the snapshot.  Without the snapshot, the manifest only points at
+//! the head, and the new node gets the state from a snapshot at or after it or
+//! syncs it.  `neard migrate import` verifies the checksums, moves the files
+//! into a new home dir and then checks that the node loads its config and that
+//! the keys and the head of the database match the manifest.
+//!
+//! A validator must never run on both machines at the same time, as it would
+//! sign conflicting blocks, chunks and endorsements with the same key.  The
+//! node has to be stopped for the export anyway, and it must not be started
+//! again on the old machine.
+use crate::config::{load_config, Config, CONFIG_FILENAME};
+use crate::dyn_config::LOG_CONFIG_FILENAME;
+use anyhow::{bail, ensure, Context};
+use near_chain_configs::GenesisValidationMode;
+use near_crypto::PublicKey;
+use near_primitives::block::Tip;
+use near_primitives::hash::{hash, CryptoHash};
+use near_primitives::types::{AccountId, BlockHeight};
+use near_store::{checkpoint_hot_storage_and_cleanup_columns, DBCol, Mode, NodeStorage, HEAD_KEY};
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+pub const MANIFEST_FILENAME: &str = "manifest.json";
+
+/// Directory of the database snapshot in the bundle, where
+/// `checkpoint_hot_storage_and_cleanup_columns` puts it.
+const STATE_DIR: &str = "data";
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq)]
+pub struct BundleManifest {
+    pub chain_id: String,
+    pub node_public_key: PublicKey,
+    pub validator_account_id: Option<AccountId>,
+    /// Head of the chain in the database snapshot, or at the time of the
+    /// export if the bundle has no snapshot.
+    pub head_height: BlockHeight,
+    pub head_hash: CryptoHash,
+    /// Whether the bundle has a snapshot of the database.
+    pub has_state: bool,
+    /// Checksums of the files, by their paths relative to the home dir.
+    pub files: BTreeMap<String, CryptoHash>,
+}
+
+/// Returns the paths relative to the home dir of the files of the node which
+/// go into the bundle.
+fn node_files(home_dir: &Path, config: &Config) -> Vec<String> {
+    let mut files = vec![
+        CONFIG_FILENAME.to_string(),
+        config.genesis_file.clone(),
+        config.node_key_file.clone(),
+    ];
+    files.extend(config.genesis_records_file.clone());
+    files.extend(config.epoch_config_override_file.clone());
+    for optional in [config.validator_key_file.as_str(), LOG_CONFIG_FILENAME] {
+        if home_dir.join(optional).exists() {
+            files.push(optional.to_string());
+        }
+    }
+    files
+}
+
+/// Checks that the name of a file in the bundle is a file directly in the
+/// home dir, so that a crafted manifest can't make the import read or write
+/// files elsewhere.
+fn ensure_file_name(name: &str) -> anyhow::Result<()> {
+    let mut components = Path::new(name).components();
+    ensure!(
+        matches!((components.next(), components.next()), (Some(Component::Normal(_)), None)),
+        "{name} is not a file directly in the home dir"
+    );
+    Ok(())
+}
+
+fn store_path(home_dir: &Path, config: &Config) -> PathBuf {
+    home_dir.join(config.store.path.clone().unwrap_or_else(|| PathBuf::from("data")))
+}
+
+/// Exports the node in the home dir into a new bundle directory.  The node
+/// must be stopped, the database can't be opened otherwise.
+pub fn export_bundle(
+    home_dir: &Path,
+    bundle_dir: &Path,
+    with_state: bool,
+) -> anyhow::Result<BundleManifest> {
+    let near_config = load_config(home_dir, GenesisValidationMode::UnsafeFast)?;
+    let config = &near_config.config;
+    ensure!(!bundle_dir.exists(), "{} already exists", bundle_dir.display());
+
+    let storage =
+        NodeStorage::opener(home_dir, config.archive, &config.store, config.cold_store.as_ref())
+            .open_in_mode(Mode::ReadWriteExisting)
+            .context("opening the database, make sure the node is stopped")?;
+    if with_state && storage.has_cold() {
+        bail!("exporting the database of a node with cold storage is not supported");
+    }
+    let store = storage.get_hot_store();
+    let head =
+        store.get_ser::<Tip>(DBCol::BlockMisc, HEAD_KEY)?.context("the database has no head")?;
+
+    std::fs::create_dir_all(bundle_dir)?;
+    if with_state {
+        tracing::info!(target: "neard", height = head.height, "Making a snapshot of the database");
+        checkpoint_hot_storage_and_cleanup_columns(&store, bundle_dir, None)?;
+    }
+
+    let mut files = BTreeMap::new();
+    for name in node_files(home_dir, config) {
+        ensure_file_name(&name)?;
+        let contents = std::fs::read(home_dir.join(&name))
+            .with_context(|| format!("reading {name} from the home dir"))?;
+        std::fs::write(bundle_dir.join(&name), &contents)?;
+        files.insert(name, hash(&contents));
+    }
+
+    let manifest = BundleManifest {
+        chain_id: near_config.genesis.config.chain_id.clone(),
+        node_public_key: near_config.network_config.node_key.public_key(),
+        validator_account_id: near_config
+            .validator_signer
+            .as_ref()
+            .map(|signer| signer.validator_id().clone()),
+        head_height: head.height,
+        head_hash: head.last_block_hash,
+        has_state: with_state,
+        files,
+    };
+    // The manifest is written last, so that an interrupted export doesn't
+    // leave a bundle which looks complete.
+    std::fs::write(bundle_dir.join(MANIFEST_FILENAME), serde_json::to_vec_pretty(&manifest)?)?;
+    Ok(manifest)
+}
+
+/// Moves the directory, or copies it if it's on another file system.
+fn move_dir(from: &Path, to: &Path) -> anyhow::Result<()> {
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            move_dir(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Imports the bundle into the home dir, which must not have the files of a
+/// node yet, and verifies the imported node.  The database snapshot is moved
+/// out of the bundle.
+pub fn import_bundle(bundle_dir: &Path, home_dir: &Path) -> anyhow::Result<BundleManifest> {
+    let manifest_path = bundle_dir.join(MANIFEST_FILENAME);
+    let manifest: BundleManifest = serde_json::from_slice(
+        &std::fs::read(&manifest_path)
+            .with_context(|| format!("reading {}", manifest_path.display()))?,
+    )
+    .with_context(|| format!("parsing {}", manifest_path.display()))?;
+
+    // Check everything before touching the home dir.
+    for (name, checksum) in &manifest.files {
+        ensure_file_name(name)?;
+        let contents = std::fs::read(bundle_dir.join(name))
+            .with_context(|| format!("reading {name} from the bundle"))?;
+        ensure!(hash(&contents) == *checksum, "{name} in the bundle doesn't match its checksum");
+        ensure!(!home_dir.join(name).exists(), "{name} already exists in the home dir");
+    }
+    let config = Config::from_file_skip_validation(&bundle_dir.join(CONFIG_FILENAME))?;
+    let store_path = store_path(home_dir, &config);
+    if manifest.has_state {
+        ensure!(bundle_dir.join(STATE_DIR).is_dir(), "the bundle has no database snapshot");
+        ensure!(!store_path.exists(), "{} already exists", store_path.display());
+    }
+
+    std::fs::create_dir_all(home_dir)?;
+    for name in manifest.files.keys() {
+        std::fs::copy(bundle_dir.join(name), home_dir.join(name))?;
+    }
+    if manifest.has_state {
+        tracing::info!(target: "neard", path = %store_path.display(), "Moving the database snapshot");
+        move_dir(&bundle_dir.join(STATE_DIR), &store_path)?;
+    }
+    verify_import(home_dir, &manifest)?;
+    Ok(manifest)
+}
+
+/// Checks that the imported node loads its config, and that its keys and the
+/// head of its database match the manifest.
+fn verify_import(home_dir: &Path, manifest: &BundleManifest) -> anyhow::Result<()> {
+    let near_config = load_config(home_dir, GenesisValidationMode::Full)
+        .context("loading the config of the imported node")?;
+    ensure!(
+        near_config.genesis.config.chain_id == manifest.chain_id,
+        "imported chain id {} doesn't match the bundle",
+        near_config.genesis.config.chain_id
+    );
+    ensure!(
+        near_config.network_config.node_key.public_key() == manifest.node_public_key,
+        "imported node key doesn't match the bundle"
+    );
+    let validator_account_id =
+        near_config.validator_signer.as_ref().map(|signer| signer.validator_id().clone());
+    ensure!(
+        validator_account_id == manifest.validator_account_id,
+        "imported validator {validator_account_id:?} doesn't match the bundle"
+    );
+    if manifest.has_state {
+        let config = &near_config.config;
+        let storage = NodeStorage::opener(home_dir, config.archive, &config.store, None)
+            .open_in_mode(Mode::ReadOnly)
+            .context("opening the imported database")?;
+        let head = storage
+            .get_hot_store()
+            .get_ser::<Tip>(DBCol::BlockMisc, HEAD_KEY)?
+            .context("the imported database has no head")?;
+        ensure!(
+            head.height == manifest.head_height && head.last_block_hash == manifest.head_hash,
+            "imported head {} at height {} doesn't match the bundle",
+            head.last_block_hash,
+            head.height
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_primitives::types::EpochId;
+
+    fn init_node(home_dir: &Path) {
+        crate::config::init_configs(
+            home_dir,
+            Some("localnet".to_string()),
+            Some("test.near".parse().unwrap()),
+            Some("test.near"),
+            1,
+            false,
+            None,
+            false,
+            None,
+            None,
+            false,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        let near_config = load_config(home_dir, GenesisValidationMode::UnsafeFast).unwrap();
+        let storage =
+            NodeStorage::opener(home_dir, false, &near_config.config.store, None).open().unwrap();
+        let tip = Tip {
+            height: 10,
+            last_block_hash: hash(b"head"),
+            prev_block_hash: hash(b"prev"),
+            epoch_id: EpochId::default(),
+            next_epoch_id: EpochId::default(),
+        };
+        let mut update = storage.get_hot_store().store_update();
+        update.set_ser(DBCol::BlockMisc, HEAD_KEY, &tip).unwrap();
+        update.commit().unwrap();
+    }
+
+    #[test]
+    fn test_export_import() {
+        let tmp = tempfile::tempdir().unwrap();
+        let old_home = tmp.path().join("old");
+        let new_home = tmp.path().join("new");
+        let bundle = tmp.path().join("bundle");
+        init_node(&old_home);
+
+        let exported = export_bundle(&old_home, &bundle, true).unwrap();
+        assert_eq!(exported.head_height, 10);
+        assert_eq!(exported.validator_account_id, Some("test.near".parse().unwrap()));
+        assert!(exported.files.contains_key("node_key.json"));
+
+        let imported = import_bundle(&bundle, &new_home).unwrap();
+        assert_eq!(imported, exported);
+        assert!(new_home.join("data").is_dir());
+        // The home dir already has a node.
+        assert!(import_bundle(&bundle, &new_home).is_err());
+    }
+
+    #[test]
+    fn test_import_corrupted_bundle() {
+        let tmp = tempfile::tempdir().unwrap();
+        let old_home = tmp.path().join("old");
+        let new_home = tmp.path().join("new");
+        let bundle = tmp.path().join("bundle");
+        init_node(&old_home);
+
+        export_bundle(&old_home, &bundle, false).unwrap();
+        std::fs::write(bundle.join(CONFIG_FILENAME), b"{}").unwrap();
+        let err = import_bundle(&bundle, &new_home).unwrap_err();
+        assert!(err.to_string().contains("checksum"), "{err:#}");
+        assert!(!new_home.join(CONFIG_FILENAME).exists());
+    }
+
+    #[test]
+    fn test_import_bundle_with_paths() {
+        let tmp = tempfile::tempdir().unwrap();
+        let old_home = tmp.path().join("old");
+        let new_home = tmp.path().join("new");
+        let bundle = tmp.path().join("bundle");
+        init_node(&old_home);
+        export_bundle(&old_home, &bundle, false).unwrap();
+
+        let manifest_path = bundle.join(MANIFEST_FILENAME);
+        let mut manifest: BundleManifest =
+            serde_json::from_slice(&std::fs::read(&manifest_path).unwrap()).unwrap();
+        let contents = b"outside";
+        std::fs::write(tmp.path().join("outside.json"), contents).unwrap();
+        for name in ["../outside.json", "/etc/outside.json", "sub/outside.json", "."] {
+            manifest.files.insert(name.to_string(), hash(contents));
+            std::fs::write(&manifest_path, serde_json::to_vec(&manifest).unwrap()).unwrap();
+            let err = import_bundle(&bundle, &new_home).unwrap_err();
+            assert!(err.to_string().contains("not a file directly in the home dir"), "{err:#}");
+            assert!(!new_home.exists());
+            manifest.files.remove(name);
+        }
+    }
+}
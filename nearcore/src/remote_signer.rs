@@ -0,0 +1,401 @@
+//! Validator signer keeping the validator key in a separate signing service,
+//! e.g. a process backed by an HSM, which the node talks to over a unix socket.
+//!
+//! The protocol is newline delimited JSON. Each request line is answered with
+//! one response line:
+//! - `{"method": "info"}` returns `{"account_id": "...", "public_key": "ed25519:..."}`
+//! - `{"method": "sign", "data": "<base64>"}` returns `{"signature": "ed25519:..."}`
+//! - `{"method": "compute_vrf_with_proof", "data": "<base64>"}` returns
+//!   `{"value": "<base58>", "proof": "<base58>"}`
+//!
+//! The service reports failures as `{"error": "<message>"}`.
+
+use crate::config::{RemoteSignerConfig, RemoteSignerFailurePolicy};
+use crate::metrics;
+use anyhow::Context;
+use near_crypto::key_conversion::convert_public_key;
+use near_crypto::{InMemorySigner, PublicKey, Signature, Signer};
+use near_primitives::serialize::to_base64;
+use near_primitives::types::AccountId;
+use near_primitives::validator_signer::InMemoryValidatorSigner;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+#[derive(serde::Serialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+enum Request {
+    Info,
+    Sign { data: String },
+    ComputeVrfWithProof { data: String },
+}
+
+impl Request {
+    fn method(&self) -> &'static str {
+        match self {
+            Request::Info => "info",
+            Request::Sign { .. } => "sign",
+            Request::ComputeVrfWithProof { .. } => "compute_vrf_with_proof",
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct InfoResponse {
+    account_id: AccountId,
+    public_key: PublicKey,
+}
+
+#[derive(serde::Deserialize)]
+struct SignResponse {
+    signature: Signature,
+}
+
+/// The VRF value and proof are decoded by hand, since their `Deserialize`
+/// implementations only accept borrowed strings, which a `serde_json::Value`
+/// can't provide.
+#[derive(serde::Deserialize)]
+struct ComputeVrfWithProofResponse {
+    value: String,
+    proof: String,
+}
+
+impl ComputeVrfWithProofResponse {
+    fn decode(self) -> anyhow::Result<(near_crypto::vrf::Value, near_crypto::vrf::Proof)> {
+        let value = near_crypto::vrf::Value::try_from(self.value.as_str())
+            .map_err(|()| anyhow::anyhow!("The remote signer returned an invalid VRF value"))?;
+        let proof = near_crypto::vrf::Proof::try_from(self.proof.as_str())
+            .map_err(|()| anyhow::anyhow!("The remote signer returned an invalid VRF proof"))?;
+        Ok((value, proof))
+    }
+}
+
+/// Connection to the remote signing service.
+struct RemoteSignerClient {
+    config: RemoteSignerConfig,
+    connection: Mutex<Option<BufReader<UnixStream>>>,
+}
+
+impl RemoteSignerClient {
+    /// Sends the request, retrying it on failure.
+    fn request<T: serde::de::DeserializeOwned>(&self, request: &Request) -> anyhow::Result<T> {
+        let method = request.method();
+        let _timer =
+            metrics::REMOTE_SIGNER_REQUEST_LATENCY.with_label_values(&[method]).start_timer();
+        let mut attempt = 0;
+        loop {
+            match self.try_request(request) {
+                Ok(response) => {
+                    metrics::REMOTE_SIGNER_REQUESTS.with_label_values(&[method, "ok"]).inc();
+                    return Ok(response);
+                }
+                Err(err) if attempt < self.config.retries => {
+                    tracing::warn!(target: "remote_signer", method, attempt, ?err, "Remote signer request failed, retrying");
+                    attempt += 1;
+                }
+                Err(err) => {
+                    metrics::REMOTE_SIGNER_REQUESTS.with_label_values(&[method, "error"]).inc();
+                    return Err(err);
+                }
+            }
+        }
+    }
+
+    fn try_request<T: serde::de::DeserializeOwned>(&self, request: &Request) -> anyhow::Result<T> {
+        let response = {
+            let mut connection = self.connection.lock().unwrap();
+            if connection.is_none() {
+                *connection = Some(BufReader::new(self.open_stream()?));
+            }
+            let response = Self::exchange(connection.as_mut().unwrap(), request);
+            if response.is_err() {
+                // The response may still arrive later, so the connection can't be reused.
+                *connection = None;
+            }
+            response?
+        };
+        if let Some(error) = response.get("error") {
+            anyhow::bail!("The remote signer failed: {error}");
+        }
+        Ok(serde_json::from_value(response)?)
+    }
+
+    fn open_stream(&self) -> anyhow::Result<UnixStream> {
+        let stream = UnixStream::connect(&self.config.socket_path)?;
+        let timeout = self.config.timeout.unsigned_abs();
+        stream.set_read_timeout(Some(timeout))?;
+        stream.set_write_timeout(Some(timeout))?;
+        Ok(stream)
+    }
+
+    fn exchange(
+        stream: &mut BufReader<UnixStream>,
+        request: &Request,
+    ) -> anyhow::Result<serde_json::Value> {
+        let mut line = serde_json::to_string(request)?;
+        line.push('\n');
+        stream.get_mut().write_all(line.as_bytes())?;
+        let mut response = String::new();
+        if stream.read_line(&mut response)? == 0 {
+            anyhow::bail!("The remote signer closed the connection");
+        }
+        Ok(serde_json::from_str(&response)?)
+    }
+}
+
+/// `Signer` forwarding all the signing to the remote signing service.
+///
+/// The `Signer` trait can't report errors, so if a request still fails after
+/// the configured retries, the signer either signs with the local fallback key
+/// or panics, depending on the failure policy.
+pub struct RemoteSigner {
+    client: RemoteSignerClient,
+    account_id: AccountId,
+    public_key: PublicKey,
+    /// The key the VRF outputs of the service are verified with.
+    vrf_public_key: near_crypto::vrf::PublicKey,
+    fallback: Option<InMemorySigner>,
+}
+
+impl RemoteSigner {
+    /// Connects to the signing service and asks it for the validator account
+    /// and key. `validator_key_file` is only read with the `local_key` failure
+    /// policy and has to contain the same key.
+    pub fn connect(config: RemoteSignerConfig, validator_key_file: &Path) -> anyhow::Result<Self> {
+        let fallback = match config.failure_policy {
+            RemoteSignerFailurePolicy::Panic => None,
+            RemoteSignerFailurePolicy::LocalKey => {
+                Some(InMemorySigner::from_file(validator_key_file).with_context(|| {
+                    format!(
+                        "Failed reading the fallback validator key from {}",
+                        validator_key_file.display()
+                    )
+                })?)
+            }
+        };
+        let socket_path = config.socket_path.clone();
+        let client = RemoteSignerClient { config, connection: Mutex::new(None) };
+        let info: InfoResponse = client.request(&Request::Info).with_context(|| {
+            format!("Failed connecting to the remote signer at {}", socket_path.display())
+        })?;
+        if let Some(fallback) = &fallback {
+            anyhow::ensure!(
+                fallback.account_id == info.account_id && fallback.public_key == info.public_key,
+                "The fallback validator key {} of {} doesn't match the remote signer key {} of {}",
+                fallback.public_key,
+                fallback.account_id,
+                info.public_key,
+                info.account_id,
+            );
+        }
+        let vrf_public_key = match &info.public_key {
+            PublicKey::ED25519(public_key) => convert_public_key(public_key),
+            PublicKey::SECP256K1(_) => None,
+        }
+        .with_context(|| {
+            format!("The remote signer key {} isn't a valid validator key", info.public_key)
+        })?;
+        Ok(Self {
+            client,
+            account_id: info.account_id,
+            public_key: info.public_key,
+            vrf_public_key,
+            fallback,
+        })
+    }
+
+    pub fn account_id(&self) -> &AccountId {
+        &self.account_id
+    }
+
+    /// Returns the signer to use instead of the remote one, or panics if there
+    /// is none.
+    fn on_failure(&self, method: &str, err: anyhow::Error) -> &InMemorySigner {
+        let Some(fallback) = &self.fallback else {
+            panic!("The remote signer failed to {method} and there is no fallback: {err:#}");
+        };
+        tracing::error!(target: "remote_signer", method, ?err, "Remote signer failed, using the local fallback key");
+        metrics::REMOTE_SIGNER_REQUESTS.with_label_values(&[method, "fallback"]).inc();
+        fallback
+    }
+}
+
+impl Signer for RemoteSigner {
+    fn public_key(&self) -> PublicKey {
+        self.public_key.clone()
+    }
+
+    fn sign(&self, data: &[u8]) -> Signature {
+        let response = self
+            .client
+            .request::<SignResponse>(&Request::Sign { data: to_base64(data) })
+            .and_then(|response| {
+                anyhow::ensure!(
+                    response.signature.verify(data, &self.public_key),
+                    "The remote signer returned an invalid signature"
+                );
+                Ok(response)
+            });
+        match response {
+            Ok(response) => response.signature,
+            Err(err) => self.on_failure("sign", err).sign(data),
+        }
+    }
+
+    fn compute_vrf_with_proof(
+        &self,
+        data: &[u8],
+    ) -> (near_crypto::vrf::Value, near_crypto::vrf::Proof) {
+        let response = self
+            .client
+            .request::<ComputeVrfWithProofResponse>(&Request::ComputeVrfWithProof {
+                data: to_base64(data),
+            })
+            .and_then(ComputeVrfWithProofResponse::decode)
+            .and_then(|(value, proof)| {
+                anyhow::ensure!(
+                    self.vrf_public_key.is_vrf_valid(&data, &value, &proof),
+                    "The remote signer returned an invalid VRF output"
+                );
+                Ok((value, proof))
+            });
+        match response {
+            Ok(response) => response,
+            Err(err) => self.on_failure("compute_vrf_with_proof", err).compute_vrf_with_proof(data),
+        }
+    }
+
+    fn write_to_file(&self, _path: &Path) -> std::io::Result<()> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "The key of a remote signer can't be written to a file",
+        ))
+    }
+}
+
+/// Creates the validator signer signing with the remote signing service.
+pub fn create_remote_validator_signer(
+    config: RemoteSignerConfig,
+    validator_key_file: &Path,
+) -> anyhow::Result<InMemoryValidatorSigner> {
+    let signer = RemoteSigner::connect(config, validator_key_file)?;
+    Ok(InMemoryValidatorSigner::from_signer(signer.account_id().clone(), Arc::new(signer)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_async::time::Duration;
+    use near_crypto::KeyType;
+    use near_primitives::validator_signer::ValidatorSigner;
+    use std::os::unix::net::UnixListener;
+
+    /// Serves requests of a single connection signing with the given key, and
+    /// stops once the connection is closed.
+    fn serve(listener: UnixListener, key: InMemorySigner) -> std::thread::JoinHandle<()> {
+        serve_with_vrf_key(listener, key.clone(), key)
+    }
+
+    /// Like `serve`, but computes the VRF outputs with `vrf_key`.
+    fn serve_with_vrf_key(
+        listener: UnixListener,
+        key: InMemorySigner,
+        vrf_key: InMemorySigner,
+    ) -> std::thread::JoinHandle<()> {
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut writer = stream;
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 0 {
+                let request: serde_json::Value = serde_json::from_str(&line).unwrap();
+                let data = request.get("data").map(|data| {
+                    near_primitives::serialize::from_base64(data.as_str().unwrap()).unwrap()
+                });
+                let response = match request["method"].as_str().unwrap() {
+                    "info" => serde_json::json!({
+                        "account_id": key.account_id,
+                        "public_key": key.public_key,
+                    }),
+                    "sign" => serde_json::json!({ "signature": key.sign(&data.unwrap()) }),
+                    "compute_vrf_with_proof" => {
+                        let (value, proof) = vrf_key.compute_vrf_with_proof(&data.unwrap());
+                        serde_json::json!({ "value": value, "proof": proof })
+                    }
+                    method => serde_json::json!({ "error": format!("unknown method {method}") }),
+                };
+                writeln!(writer, "{response}").unwrap();
+                line.clear();
+            }
+        })
+    }
+
+    fn config(dir: &Path, failure_policy: RemoteSignerFailurePolicy) -> RemoteSignerConfig {
+        RemoteSignerConfig {
+            socket_path: dir.join("signer.sock"),
+            timeout: Duration::seconds(1),
+            retries: 1,
+            failure_policy,
+        }
+    }
+
+    #[test]
+    fn test_remote_signer() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = InMemorySigner::from_seed("test".parse().unwrap(), KeyType::ED25519, "test");
+        let key_file = dir.path().join("validator_key.json");
+        key.write_to_file(&key_file).unwrap();
+        // Without a fallback key every failed request panics, so the test
+        // checks that the remote service is actually used.
+        let config = config(dir.path(), RemoteSignerFailurePolicy::Panic);
+        let server = serve(UnixListener::bind(&config.socket_path).unwrap(), key.clone());
+
+        let signer = create_remote_validator_signer(config, &key_file).unwrap();
+        assert_eq!(signer.validator_id(), &key.account_id);
+        assert_eq!(ValidatorSigner::public_key(&signer), key.public_key);
+        let chunk_hash = near_primitives::sharding::ChunkHash::default();
+        let signature = signer.sign_chunk_hash(&chunk_hash);
+        assert!(signature.verify(chunk_hash.as_ref(), &key.public_key));
+
+        let (value, proof) = signer.compute_vrf_with_proof(b"data");
+        assert_eq!((value, proof), key.compute_vrf_with_proof(b"data"));
+
+        drop(signer);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_remote_signer_invalid_vrf() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = InMemorySigner::from_seed("test".parse().unwrap(), KeyType::ED25519, "test");
+        let other_key =
+            InMemorySigner::from_seed("test".parse().unwrap(), KeyType::ED25519, "other");
+        let key_file = dir.path().join("validator_key.json");
+        key.write_to_file(&key_file).unwrap();
+        let config = config(dir.path(), RemoteSignerFailurePolicy::LocalKey);
+        let server = serve_with_vrf_key(
+            UnixListener::bind(&config.socket_path).unwrap(),
+            key.clone(),
+            other_key.clone(),
+        );
+
+        // The VRF output of another key is rejected, and computed with the
+        // fallback key instead.
+        let signer = create_remote_validator_signer(config, &key_file).unwrap();
+        let (value, proof) = signer.compute_vrf_with_proof(b"data");
+        assert_ne!((value, proof), other_key.compute_vrf_with_proof(b"data"));
+        assert_eq!((value, proof), key.compute_vrf_with_proof(b"data"));
+
+        drop(signer);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_remote_signer_unavailable() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config(dir.path(), RemoteSignerFailurePolicy::Panic);
+        let key_file = dir.path().join("validator_key.json");
+        assert!(create_remote_validator_signer(config, &key_file).is_err());
+    }
+}
@@ -58,7 +58,8 @@ impl StateSyncDumper {
         let external = match dump_config.location {
             ExternalStorageLocation::S3 { bucket, region } => ExternalConnection::S3 {
                 bucket: Arc::new(create_bucket_readwrite(&bucket, &region, std::time::Duration::from_secs(30), dump_config.credentials_file).expect(
-                    "Failed to authenticate connection to S3. Please either provide AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY in the environment, or create a credentials file and link it in config.json as 's3_credentials_file'."))
+                    "Failed to authenticate connection to S3. Please either provide AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY in the environment, or create a credentials file and link it in config.json as 's3_credentials_file'.")),
+                upload: dump_config.s3_upload,
             },
             ExternalStorageLocation::Filesystem { root_dir } => ExternalConnection::Filesystem { root_dir },
             ExternalStorageLocation::GCS { bucket } => {
@@ -146,6 +146,12 @@ impl NeardCmd {
             NeardSubCommand::StatePartsDumpCheck(cmd) => {
                 cmd.run()?;
             }
+            NeardSubCommand::Migrate(cmd) => {
+                cmd.run(&home_dir)?;
+            }
+            NeardSubCommand::DumpSchemas(cmd) => {
+                cmd.run()?;
+            }
             #[cfg(feature = "new_epoch_sync")]
             NeardSubCommand::EpochSync(cmd) => {
                 cmd.run(&home_dir)?;
@@ -279,6 +285,16 @@ pub(super) enum NeardSubCommand {
     /// Check completeness of dumped state parts of an epoch
     StatePartsDumpCheck(StatePartsDumpCheckCommand),
 
+    /// Moves a node to new hardware: exports its keys, config files and a
+    /// snapshot of its database into a bundle, and imports the bundle on the
+    /// new machine, verifying the imported node.
+    Migrate(MigrateCommand),
+
+    /// Prints the borsh schemas of the block headers, chunks, transactions,
+    /// receipts, actions and execution outcomes at the current protocol
+    /// version as JSON, for decoding them outside of nearcore.
+    DumpSchemas(DumpSchemasCommand),
+
     #[cfg(feature = "new_epoch_sync")]
     /// Testing tool for epoch sync
     EpochSync(EpochSyncCommand),
@@ -445,6 +461,10 @@ pub(super) struct RunCmd {
     /// configuration will be taken.
     #[clap(long)]
     max_gas_burnt_view: Option<Gas>,
+    /// Reload the dynamic configs whenever one of the config files changes,
+    /// same as on SIGHUP.
+    #[clap(long)]
+    watch_config: bool,
 }
 
 impl RunCmd {
@@ -546,11 +566,15 @@ impl RunCmd {
             .await
             .global();
 
-            let updateable_configs = nearcore::dyn_config::read_updateable_configs(home_dir)
+            let updateable_configs = nearcore::dyn_config::read_updateable_configs(home_dir, None)
                 .unwrap_or_else(|e| panic!("Error reading dynamic configs: {:#}", e));
             let mut updateable_config_loader =
                 UpdateableConfigLoader::new(updateable_configs.clone(), tx_config_update);
             let config_updater = ConfigUpdater::new(rx_config_update);
+            let mut running_config = near_config.config.clone();
+            let mut config_files_watcher = self.watch_config.then(|| {
+                nearcore::dyn_config::ConfigFilesWatcher::new(home_dir, &running_config)
+            });
 
             let nearcore::NearNode {
                 rpc_servers,
@@ -568,10 +592,15 @@ impl RunCmd {
             .expect("start_with_config");
 
             let sig = loop {
-                let sig = wait_for_interrupt_signal(home_dir, &mut rx_crash).await;
-                if sig == "SIGHUP" {
-                    let maybe_updateable_configs =
-                        nearcore::dyn_config::read_updateable_configs(home_dir);
+                let sig = tokio::select! {
+                    sig = wait_for_interrupt_signal(home_dir, &mut rx_crash) => sig,
+                    _ = wait_for_config_files_change(&mut config_files_watcher) => CONFIG_FILES_CHANGED,
+                };
+                if sig == "SIGHUP" || sig == CONFIG_FILES_CHANGED {
+                    let maybe_updateable_configs = nearcore::dyn_config::read_updateable_configs(
+                        home_dir,
+                        Some(&mut running_config),
+                    );
                     updateable_config_loader.reload(maybe_updateable_configs);
                 } else {
                     break sig;
@@ -599,6 +628,23 @@ impl RunCmd {
     }
 }
 
+const CONFIG_FILES_CHANGED: &str = "Config files changed";
+
+/// Resolves once the watched config files change, never if they are not watched.
+async fn wait_for_config_files_change(
+    watcher: &mut Option<nearcore::dyn_config::ConfigFilesWatcher>,
+) {
+    let Some(watcher) = watcher else {
+        return std::future::pending().await;
+    };
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+        if watcher.changed() {
+            return;
+        }
+    }
+}
+
 #[cfg(not(unix))]
 async fn wait_for_interrupt_signal(_home_dir: &Path, mut _rx_crash: &Receiver<()>) -> &str {
     // TODO(#6372): Support graceful shutdown on windows.
@@ -834,11 +880,120 @@ fn make_env_filter(verbose: Option<&str>) -> Result<EnvFilter, BuildEnvFilterErr
 }
 
 #[derive(clap::Parser)]
-pub(super) struct ValidateConfigCommand {}
+pub(super) struct ValidateConfigCommand {
+    /// Also report unknown fields and inconsistent combinations of settings,
+    /// which the node accepts when starting.
+    #[clap(long)]
+    strict: bool,
+}
 
 impl ValidateConfigCommand {
     pub(super) fn run(&self, home_dir: &Path) -> anyhow::Result<()> {
-        nearcore::config::load_config(home_dir, GenesisValidationMode::Full)?;
+        let near_config = nearcore::config::load_config(home_dir, GenesisValidationMode::Full)?;
+        if self.strict {
+            nearcore::config_validate::validate_config_strict(home_dir, &near_config)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(clap::Parser)]
+pub(super) struct MigrateCommand {
+    #[clap(subcommand)]
+    subcmd: MigrateSubCommand,
+}
+
+#[derive(clap::Subcommand)]
+enum MigrateSubCommand {
+    /// Exports the node into a new bundle directory.  The node must be
+    /// stopped, and a validator must not be started again on this machine.
+    Export(MigrateExportCmd),
+    /// Imports a bundle into the home dir, which must not have a node yet, and
+    /// verifies that the imported node matches the bundle.
+    Import(MigrateImportCmd),
+}
+
+#[derive(clap::Parser)]
+struct MigrateExportCmd {
+    /// Directory to create the bundle in.
+    #[clap(long)]
+    bundle: PathBuf,
+    /// Leave the database out of the bundle and only record the head of the
+    /// chain, for nodes which get the state from a snapshot taken at or after
+    /// the head, or sync it.
+    #[clap(long)]
+    without_state: bool,
+}
+
+#[derive(clap::Parser)]
+struct MigrateImportCmd {
+    /// Directory of the bundle made by `neard migrate export`.
+    #[clap(long)]
+    bundle: PathBuf,
+}
+
+impl MigrateCommand {
+    pub(super) fn run(&self, home_dir: &Path) -> anyhow::Result<()> {
+        match &self.subcmd {
+            MigrateSubCommand::Export(cmd) => {
+                let manifest = nearcore::migration_bundle::export_bundle(
+                    home_dir,
+                    &cmd.bundle,
+                    !cmd.without_state,
+                )?;
+                println!(
+                    "Exported {} files and {} at height {} to {}",
+                    manifest.files.len(),
+                    if manifest.has_state { "the database" } else { "the head" },
+                    manifest.head_height,
+                    cmd.bundle.display()
+                );
+                if manifest.validator_account_id.is_some() {
+                    println!("Don't start the validator on this machine again.");
+                }
+            }
+            MigrateSubCommand::Import(cmd) => {
+                let manifest = nearcore::migration_bundle::import_bundle(&cmd.bundle, home_dir)?;
+                println!(
+                    "Imported and verified the node of chain {} with the head at height {}",
+                    manifest.chain_id, manifest.head_height
+                );
+                if !manifest.has_state {
+                    println!(
+                        "The bundle has no database, get a snapshot taken at or after height {} before starting the node.",
+                        manifest.head_height
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(clap::Parser)]
+pub(super) struct DumpSchemasCommand {
+    /// File to write the schemas to instead of stdout.
+    #[clap(long)]
+    output: Option<PathBuf>,
+}
+
+impl DumpSchemasCommand {
+    pub(super) fn run(&self) -> anyhow::Result<()> {
+        let schemas: serde_json::Map<String, Value> = near_primitives::schema::schemas()
+            .iter()
+            .map(|(name, container)| {
+                (name.to_string(), near_primitives::schema::schema_to_json(container))
+            })
+            .collect();
+        let json = serde_json::json!({
+            "protocol_version": near_primitives::version::PROTOCOL_VERSION,
+            "schemas": schemas,
+        });
+        let json = serde_json::to_string_pretty(&json)?;
+        match &self.output {
+            Some(path) => std::fs::write(path, json)?,
+            None => println!("{json}"),
+        }
         Ok(())
     }
 }
@@ -35,6 +35,13 @@ static DEFAULT_HOME: Lazy<PathBuf> = Lazy::new(get_default_home);
 #[global_allocator]
 static ALLOC: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
 
+/// Default options of jemalloc: the heap profiling is enabled, but the
+/// allocations aren't sampled until it is activated through the debug RPC.
+#[cfg(target_os = "linux")]
+#[allow(non_upper_case_globals)]
+#[export_name = "_rjem_malloc_conf"]
+static malloc_conf: &u8 = &b"prof:true,prof_active:false\0"[0];
+
 fn main() -> anyhow::Result<()> {
     if env::var("RUST_BACKTRACE").is_err() {
         // Enable backtraces on panics by default.
@@ -0,0 +1,61 @@
+#!/usr/bin/env python3
+# test fast forwarding while a shard skips chunk production, so that the blocks
+# right after the fast forward are missing the chunks of that shard.
+
+import sys
+import pathlib
+
+sys.path.append(str(pathlib.Path(__file__).resolve().parents[2] / 'lib'))
+
+import utils
+from cluster import start_cluster
+
+MIN_BLOCK_PROD_TIME = 1  # seconds
+MAX_BLOCK_PROD_TIME = 2  # seconds
+BLOCKS_TO_FASTFORWARD = 20
+SKIP_CHUNKS_BLOCKS = 5
+CONFIG = utils.figure_out_sandbox_binary()
+CONFIG.update({
+    "consensus": {
+        "min_block_production_delay": {
+            "secs": MIN_BLOCK_PROD_TIME,
+            "nanos": 0,
+        },
+        "max_block_production_delay": {
+            "secs": MAX_BLOCK_PROD_TIME,
+            "nanos": 0,
+        },
+    }
+})
+
+nodes = start_cluster(1, 0, 1, CONFIG, [["epoch_length", 100]], {})
+
+utils.wait_for_blocks(nodes[0], target=10)
+start_height = nodes[0].get_latest_block().height
+fast_forward_params = {
+    "delta_height": BLOCKS_TO_FASTFORWARD,
+    "skip_chunks_shard_ids": [0],
+    "skip_chunks_delta_height": SKIP_CHUNKS_BLOCKS,
+}
+nodes[0].json_rpc('sandbox_fast_forward', fast_forward_params, timeout=60)
+
+skip_until = start_height + BLOCKS_TO_FASTFORWARD + SKIP_CHUNKS_BLOCKS
+utils.wait_for_blocks(nodes[0], target=skip_until + 5)
+
+
+def has_new_chunk(height):
+    block = nodes[0].get_block_by_height(height)
+    if 'result' not in block:
+        # The height was skipped.
+        return None
+    chunk = block['result']['chunks'][0]
+    return chunk['height_included'] == height
+
+
+# The chunk produced right before the request may still be included in the
+# first block after the fast forward, so only check the blocks after it.
+first_checked = start_height + BLOCKS_TO_FASTFORWARD + 2
+for height in range(first_checked, skip_until + 1):
+    assert has_new_chunk(height) is not True, height
+assert any(
+    has_new_chunk(height) for height in range(skip_until + 2, skip_until + 5))
@@ -62,6 +62,15 @@ extern "C" {
         pub_key_len: u64,
         pub_key_ptr: u64,
     ) -> u64;
+    #[cfg(feature = "nightly")]
+    fn ed25519_verify_batch(
+        sigs_len: u64,
+        sigs_ptr: u64,
+        msgs_len: u64,
+        msgs_ptr: u64,
+        pub_keys_len: u64,
+        pub_keys_ptr: u64,
+    ) -> u64;
     // #####################
     // # Miscellaneous API #
     // #####################
@@ -576,6 +585,131 @@ pub unsafe fn ed25519_verify_16kib_64() {
     }
 }
 
+/// Calls `ed25519_verify_batch` `calls` times with a batch that repeats the
+/// same signature, message and public key to fill the given buffers.
+#[cfg(feature = "nightly")]
+unsafe fn ed25519_verify_batch_repeated(
+    calls: usize,
+    signature: &[u8; 64],
+    message: &[u8],
+    public_key: &[u8; 32],
+    signatures: &mut [u8],
+    messages: &mut [u8],
+    public_keys: &mut [u8],
+) {
+    for chunk in signatures.chunks_exact_mut(64) {
+        chunk.copy_from_slice(signature);
+    }
+    for chunk in public_keys.chunks_exact_mut(32) {
+        chunk.copy_from_slice(public_key);
+    }
+    // Each message is prefixed with its length as a little-endian u32.
+    for chunk in messages.chunks_exact_mut(4 + message.len()) {
+        chunk[..4].copy_from_slice(&(message.len() as u32).to_le_bytes());
+        chunk[4..].copy_from_slice(message);
+    }
+    for _ in 0..calls {
+        let result = ed25519_verify_batch(
+            signatures.len() as _,
+            signatures.as_ptr() as _,
+            messages.len() as _,
+            messages.as_ptr() as _,
+            public_keys.len() as _,
+            public_keys.as_ptr() as _,
+        );
+        // check that result was positive, as negative results could have exited
+        // early and do not reflect the full cost.
+        assert!(result == 1);
+    }
+}
+
+// private key: OReNDSAXOnl-U6Wki95ut01ehQW_9wcAF_utjzRNreg
+// public key: M4QwJx4Sogjr0KcMI_gsvt-lEU6tgd9GWmgejE_JYlA
+#[cfg(feature = "nightly")]
+const ED25519_PUBLIC_KEY: [u8; 32] = [
+    51, 132, 48, 39, 30, 18, 162, 8, 235, 208, 167, 12, 35, 248, 44, 190, 223, 165, 17, 78, 173,
+    129, 223, 70, 90, 104, 30, 140, 79, 201, 98, 80,
+];
+
+// 32 bytes message ("kajdlfkjalkfjaklfjdkladjfkljadsk")
+#[cfg(feature = "nightly")]
+const ED25519_MESSAGE_32B: [u8; 32] = [
+    107, 97, 106, 100, 108, 102, 107, 106, 97, 108, 107, 102, 106, 97, 107, 108, 102, 106, 100,
+    107, 108, 97, 100, 106, 102, 107, 108, 106, 97, 100, 115, 107,
+];
+
+#[cfg(feature = "nightly")]
+const ED25519_SIGNATURE_32B: [u8; 64] = [
+    149, 193, 241, 158, 225, 107, 146, 130, 116, 224, 233, 136, 232, 153, 211, 60, 115, 141, 183,
+    174, 15, 52, 27, 186, 34, 68, 124, 158, 81, 3, 8, 76, 93, 28, 91, 68, 252, 151, 172, 240, 129,
+    224, 239, 135, 26, 141, 111, 133, 134, 22, 149, 132, 90, 150, 33, 113, 191, 76, 109, 64, 0, 13,
+    104, 6,
+];
+
+/// Function to measure `ed25519_verify_batch_base`, verifying a batch of a
+/// single signature 500 times.
+#[cfg(feature = "nightly")]
+#[no_mangle]
+pub unsafe fn ed25519_verify_batch_1_500() {
+    let mut signatures = [0u8; 64];
+    let mut messages = [0u8; 4 + 32];
+    let mut public_keys = [0u8; 32];
+    ed25519_verify_batch_repeated(
+        500,
+        &ED25519_SIGNATURE_32B,
+        &ED25519_MESSAGE_32B,
+        &ED25519_PUBLIC_KEY,
+        &mut signatures,
+        &mut messages,
+        &mut public_keys,
+    );
+}
+
+/// Function to measure `ed25519_verify_batch_signature`, verifying a batch of
+/// 64 signatures 64 times.
+#[cfg(feature = "nightly")]
+#[no_mangle]
+pub unsafe fn ed25519_verify_batch_64_64() {
+    let mut signatures = [0u8; 64 * 64];
+    let mut messages = [0u8; 64 * (4 + 32)];
+    let mut public_keys = [0u8; 64 * 32];
+    ed25519_verify_batch_repeated(
+        64,
+        &ED25519_SIGNATURE_32B,
+        &ED25519_MESSAGE_32B,
+        &ED25519_PUBLIC_KEY,
+        &mut signatures,
+        &mut messages,
+        &mut public_keys,
+    );
+}
+
+/// Function to measure `ed25519_verify_batch_byte`, verifying a batch of a
+/// single signature over a 16kiB message 64 times.
+#[cfg(feature = "nightly")]
+#[no_mangle]
+pub unsafe fn ed25519_verify_batch_16kib_64() {
+    // Signature of the same key over `[b'a'; 16384]`.
+    let signature: [u8; 64] = [
+        137, 224, 108, 168, 192, 229, 57, 250, 232, 231, 200, 55, 155, 62, 134, 111, 71, 124, 174,
+        95, 190, 201, 113, 11, 86, 70, 91, 98, 228, 43, 233, 215, 135, 6, 42, 252, 28, 247, 101,
+        57, 100, 50, 105, 41, 225, 221, 157, 121, 76, 28, 236, 247, 124, 228, 20, 203, 91, 18, 146,
+        99, 254, 153, 41, 6,
+    ];
+    let mut signatures = [0u8; 64];
+    let mut messages = [0u8; 4 + 16384];
+    let mut public_keys = [0u8; 32];
+    ed25519_verify_batch_repeated(
+        64,
+        &signature,
+        &[b'a'; 16384],
+        &ED25519_PUBLIC_KEY,
+        &mut signatures,
+        &mut messages,
+        &mut public_keys,
+    );
+}
+
 #[repr(C)]
 struct MultiexpElem([u8; 64], [u8; 32]);
 
@@ -6,6 +6,7 @@ use crate::ContractCode;
 use borsh::{BorshDeserialize, BorshSerialize};
 use near_parameters::vm::VMKind;
 use near_primitives_core::hash::CryptoHash;
+use near_primitives_core::types::AccountId;
 use std::any::Any;
 use std::collections::HashMap;
 use std::fmt;
@@ -83,6 +84,15 @@ pub struct CompiledContractInfo {
     pub compiled: CompiledContract,
 }
 
+/// How often a contract has been run.
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct ContractUsage {
+    pub code_hash: CryptoHash,
+    /// An account the contract is deployed to.
+    pub account_id: AccountId,
+    pub count: u64,
+}
+
 /// Cache for compiled modules
 pub trait ContractRuntimeCache: Send + Sync {
     fn handle(&self) -> Box<dyn ContractRuntimeCache>;
@@ -97,6 +107,12 @@ pub trait ContractRuntimeCache: Send + Sync {
     fn has(&self, key: &CryptoHash) -> std::io::Result<bool> {
         self.get(key).map(|entry| entry.is_some())
     }
+    /// Notes that the contract deployed to the account has been run.
+    fn record_use(&self, _code_hash: &CryptoHash, _account_id: &AccountId) {}
+    /// Returns the most used contracts, most used first.
+    fn most_used(&self, _limit: usize) -> Vec<ContractUsage> {
+        vec![]
+    }
 }
 
 impl fmt::Debug for dyn ContractRuntimeCache {
@@ -121,6 +137,14 @@ impl ContractRuntimeCache for Box<dyn ContractRuntimeCache> {
     fn has(&self, key: &CryptoHash) -> std::io::Result<bool> {
         <dyn ContractRuntimeCache>::has(&**self, key)
     }
+
+    fn record_use(&self, code_hash: &CryptoHash, account_id: &AccountId) {
+        <dyn ContractRuntimeCache>::record_use(&**self, code_hash, account_id)
+    }
+
+    fn most_used(&self, limit: usize) -> Vec<ContractUsage> {
+        <dyn ContractRuntimeCache>::most_used(&**self, limit)
+    }
 }
 
 impl<C: ContractRuntimeCache> ContractRuntimeCache for &C {
@@ -139,6 +163,14 @@ impl<C: ContractRuntimeCache> ContractRuntimeCache for &C {
     fn has(&self, key: &CryptoHash) -> std::io::Result<bool> {
         <C as ContractRuntimeCache>::has(self, key)
     }
+
+    fn record_use(&self, code_hash: &CryptoHash, account_id: &AccountId) {
+        <C as ContractRuntimeCache>::record_use(self, code_hash, account_id)
+    }
+
+    fn most_used(&self, limit: usize) -> Vec<ContractUsage> {
+        <C as ContractRuntimeCache>::most_used(self, limit)
+    }
 }
 
 #[derive(Default, Clone)]
@@ -203,6 +235,11 @@ impl fmt::Debug for MockContractRuntimeCache {
 /// This cache however does not implement any clean-up policies. While it is possible to truncate
 /// a file that has been written to the cache before (`put` an empty buffer), the file will remain
 /// in place until an operator (or somebody else) removes files at their own discretion.
+///
+/// The cache also counts the uses of the contracts and saves the counts of the most used ones in
+/// the directory every minute, so that they can be compiled and loaded ahead of their first use
+/// after a restart. The uses are only queued when contracts run, counting and saving them is left
+/// to a background thread started on the first use.
 #[derive(Clone)]
 pub struct FilesystemContractRuntimeCache {
     state: Arc<FilesystemContractRuntimeCacheState>,
@@ -211,9 +248,60 @@ pub struct FilesystemContractRuntimeCache {
 struct FilesystemContractRuntimeCacheState {
     dir: rustix::fd::OwnedFd,
     any_cache: AnyCache,
+    usage_sender: std::sync::mpsc::SyncSender<(CryptoHash, AccountId)>,
+    usage: Mutex<UsageState>,
+    usage_thread: std::sync::Once,
     test_temp_dir: Option<tempfile::TempDir>,
 }
 
+/// Name of the file keeping the usage counts, which can't be mistaken for a cache key.
+const USAGE_FILENAME: &str = "usage";
+/// How many of the most used contracts have their counts saved.
+const MAX_SAVED_USAGE: usize = 1000;
+/// How many contracts have their uses counted. Once there are more, only the most used ones are
+/// kept.
+const MAX_COUNTED_USAGE: usize = 10 * MAX_SAVED_USAGE;
+/// How many uses can be queued before they are counted. Uses beyond that are dropped.
+const USAGE_QUEUE_SIZE: usize = 100_000;
+const COUNT_USAGE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+const SAVE_USAGE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+struct UsageState {
+    receiver: std::sync::mpsc::Receiver<(CryptoHash, AccountId)>,
+    counts: HashMap<CryptoHash, (AccountId, u64)>,
+}
+
+impl UsageState {
+    /// Counts the queued uses.
+    fn count(&mut self) {
+        for (code_hash, account_id) in self.receiver.try_iter() {
+            self.counts.entry(code_hash).or_insert((account_id, 0)).1 += 1;
+        }
+        if self.counts.len() > MAX_COUNTED_USAGE {
+            self.counts = self
+                .most_used(MAX_SAVED_USAGE)
+                .into_iter()
+                .map(|usage| (usage.code_hash, (usage.account_id, usage.count)))
+                .collect();
+        }
+    }
+
+    fn most_used(&self, limit: usize) -> Vec<ContractUsage> {
+        let mut usage: Vec<_> = self
+            .counts
+            .iter()
+            .map(|(code_hash, (account_id, count))| ContractUsage {
+                code_hash: *code_hash,
+                account_id: account_id.clone(),
+                count: *count,
+            })
+            .collect();
+        usage.sort_by(|a, b| b.count.cmp(&a.count).then(a.code_hash.cmp(&b.code_hash)));
+        usage.truncate(limit);
+        usage
+    }
+}
+
 impl FilesystemContractRuntimeCache {
     pub fn new<SP: AsRef<std::path::Path> + ?Sized>(
         home_dir: &std::path::Path,
@@ -246,15 +334,102 @@ impl FilesystemContractRuntimeCache {
             path = %path.display(),
             message = "opened a contract executable cache directory"
         );
+        let counts = match Self::load_usage(&dir) {
+            // The counts from before are halved, so that the contracts which are no longer used
+            // make way for the new ones over the restarts.
+            Ok(usage) => usage
+                .into_iter()
+                .map(|usage| (usage.code_hash, (usage.account_id, usage.count / 2)))
+                .collect(),
+            Err(err) => {
+                tracing::debug!(target: "vm", ?err, "failed loading the contract usage counts");
+                HashMap::new()
+            }
+        };
+        let (usage_sender, usage_receiver) = std::sync::mpsc::sync_channel(USAGE_QUEUE_SIZE);
         Ok(Self {
             state: Arc::new(FilesystemContractRuntimeCacheState {
                 dir,
                 any_cache: AnyCache::new(memory_cache_size),
+                usage_sender,
+                usage: Mutex::new(UsageState { receiver: usage_receiver, counts }),
+                usage_thread: std::sync::Once::new(),
                 test_temp_dir: None,
             }),
         })
     }
 
+    fn load_usage(dir: &rustix::fd::OwnedFd) -> std::io::Result<Vec<ContractUsage>> {
+        use rustix::fs::{Mode, OFlags};
+        let file = match rustix::fs::openat(dir, USAGE_FILENAME, OFlags::RDONLY, Mode::empty()) {
+            Err(rustix::io::Errno::NOENT) => return Ok(vec![]),
+            Err(e) => return Err(e.into()),
+            Ok(file) => file,
+        };
+        let mut buffer = Vec::new();
+        std::fs::File::from(file).read_to_end(&mut buffer)?;
+        Ok(borsh::from_slice(&buffer)?)
+    }
+
+    /// Writes the file with the contents produced by `write` under the filename, atomically.
+    fn write_file(
+        &self,
+        filename: &str,
+        write: impl FnOnce(&mut tempfile::NamedTempFile) -> std::io::Result<()>,
+    ) -> std::io::Result<()> {
+        use rustix::fs::{Mode, OFlags};
+        let mut temp_file = tempfile::Builder::new().make_in("", |filename| {
+            let mode = Mode::RUSR | Mode::WUSR | Mode::RGRP | Mode::WGRP;
+            let flags = OFlags::CREATE | OFlags::TRUNC | OFlags::WRONLY;
+            Ok(std::fs::File::from(rustix::fs::openat(&self.state.dir, filename, flags, mode)?))
+        })?;
+        write(&mut temp_file)?;
+        let temp_filename = temp_file.into_temp_path();
+        // This is atomic, so there wouldn't be instances where getters see an intermediate state.
+        rustix::fs::renameat(&self.state.dir, &*temp_filename, &self.state.dir, filename)?;
+        // Don't attempt deleting the temporary file now that it has been moved.
+        std::mem::forget(temp_filename);
+        Ok(())
+    }
+
+    /// Counts the queued uses and saves the counts of the most used contracts.
+    fn save_usage(&self) {
+        let most_used = {
+            let mut usage = self.state.usage.lock().unwrap();
+            usage.count();
+            usage.most_used(MAX_SAVED_USAGE)
+        };
+        let result = self
+            .write_file(USAGE_FILENAME, |temp_file| borsh::to_writer(&mut *temp_file, &most_used));
+        if let Err(err) = result {
+            tracing::debug!(target: "vm", ?err, "failed saving the contract usage counts");
+        }
+    }
+
+    /// Starts the thread counting the uses and saving the counts periodically. The thread stops
+    /// once the cache is dropped.
+    fn spawn_usage_thread(&self) {
+        let state = Arc::downgrade(&self.state);
+        let result =
+            std::thread::Builder::new().name("contract_usage".to_string()).spawn(move || {
+                let mut saved_at = std::time::Instant::now();
+                loop {
+                    std::thread::sleep(COUNT_USAGE_INTERVAL);
+                    let Some(state) = state.upgrade() else { break };
+                    let cache = FilesystemContractRuntimeCache { state };
+                    if saved_at.elapsed() < SAVE_USAGE_INTERVAL {
+                        cache.state.usage.lock().unwrap().count();
+                    } else {
+                        saved_at = std::time::Instant::now();
+                        cache.save_usage();
+                    }
+                }
+            });
+        if let Err(err) = result {
+            tracing::warn!(target: "vm", ?err, "failed starting the contract usage thread");
+        }
+    }
+
     pub fn test() -> std::io::Result<Self> {
         let tempdir = tempfile::TempDir::new()?;
         let mut cache = Self::new(tempdir.path(), None::<&str>)?;
@@ -291,35 +466,24 @@ impl ContractRuntimeCache for FilesystemContractRuntimeCache {
         fields(key = key.to_string(), value.len = value.compiled.debug_len()),
     )]
     fn put(&self, key: &CryptoHash, value: CompiledContractInfo) -> std::io::Result<()> {
-        use rustix::fs::{Mode, OFlags};
-        let final_filename = key.to_string();
-        let mut temp_file = tempfile::Builder::new().make_in("", |filename| {
-            let mode = Mode::RUSR | Mode::WUSR | Mode::RGRP | Mode::WGRP;
-            let flags = OFlags::CREATE | OFlags::TRUNC | OFlags::WRONLY;
-            Ok(std::fs::File::from(rustix::fs::openat(&self.state.dir, filename, flags, mode)?))
-        })?;
-        // This section manually "serializes" the data. The cache is quite sensitive to
-        // unnecessary overheads and in order to enable things like mmap-based file access, we want
-        // to have full control of what has been written.
-        match value.compiled {
-            CompiledContract::CompileModuleError(e) => {
-                borsh::to_writer(&mut temp_file, &e)?;
-                temp_file.write_all(&[ERROR_TAG])?;
+        self.write_file(&key.to_string(), |temp_file| {
+            // This section manually "serializes" the data. The cache is quite sensitive to
+            // unnecessary overheads and in order to enable things like mmap-based file access, we
+            // want to have full control of what has been written.
+            match value.compiled {
+                CompiledContract::CompileModuleError(e) => {
+                    borsh::to_writer(&mut *temp_file, &e)?;
+                    temp_file.write_all(&[ERROR_TAG])?;
+                }
+                CompiledContract::Code(bytes) => {
+                    temp_file.write_all(&bytes)?;
+                    // Writing the tag at the end gives us well aligned buffer of the data above
+                    // which is necessary for 0-copy deserialization later on.
+                    temp_file.write_all(&[CODE_TAG])?;
+                }
             }
-            CompiledContract::Code(bytes) => {
-                temp_file.write_all(&bytes)?;
-                // Writing the tag at the end gives us well aligned buffer of the data above which
-                // is necessary for 0-copy deserialization later on.
-                temp_file.write_all(&[CODE_TAG])?;
-            }
-        }
-        temp_file.write_all(&value.wasm_bytes.to_le_bytes())?;
-        let temp_filename = temp_file.into_temp_path();
-        // This is atomic, so there wouldn't be instances where getters see an intermediate state.
-        rustix::fs::renameat(&self.state.dir, &*temp_filename, &self.state.dir, final_filename)?;
-        // Don't attempt deleting the temporary file now that it has been moved.
-        std::mem::forget(temp_filename);
-        Ok(())
+            temp_file.write_all(&value.wasm_bytes.to_le_bytes())
+        })
     }
 
     #[tracing::instrument(
@@ -376,6 +540,19 @@ impl ContractRuntimeCache for FilesystemContractRuntimeCache {
             }
         })
     }
+
+    fn record_use(&self, code_hash: &CryptoHash, account_id: &AccountId) {
+        self.state.usage_thread.call_once(|| self.spawn_usage_thread());
+        // A full queue means the counting can't keep up, so the use is rather not counted than
+        // slowing down the contract execution.
+        let _ = self.state.usage_sender.try_send((*code_hash, account_id.clone()));
+    }
+
+    fn most_used(&self, limit: usize) -> Vec<ContractUsage> {
+        let mut usage = self.state.usage.lock().unwrap();
+        usage.count();
+        usage.most_used(limit)
+    }
 }
 
 type AnyCacheValue = dyn Any + Send;
@@ -580,4 +757,48 @@ mod tests {
         );
         assert!(matches!(result, Err("mikan")));
     }
+
+    #[test]
+    fn filesystem_cache_usage() {
+        let tempdir = tempfile::TempDir::new().unwrap();
+        let cache = FilesystemContractRuntimeCache::new(tempdir.path(), None::<&str>).unwrap();
+        let popular = CryptoHash::hash_bytes(b"popular");
+        let other = CryptoHash::hash_bytes(b"other");
+        let account_id: AccountId = "alice.near".parse().unwrap();
+        for _ in 0..4 {
+            cache.record_use(&popular, &account_id);
+        }
+        cache.record_use(&other, &account_id);
+        cache.save_usage();
+        let expected = vec![
+            ContractUsage { code_hash: popular, account_id: account_id.clone(), count: 4 },
+            ContractUsage { code_hash: other, account_id: account_id.clone(), count: 1 },
+        ];
+        assert_eq!(cache.most_used(10), expected);
+        assert_eq!(cache.most_used(1), expected[..1]);
+
+        let cache = FilesystemContractRuntimeCache::new(tempdir.path(), None::<&str>).unwrap();
+        assert_eq!(
+            cache.most_used(10),
+            vec![
+                ContractUsage { code_hash: popular, account_id: account_id.clone(), count: 2 },
+                ContractUsage { code_hash: other, account_id, count: 0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn filesystem_cache_usage_is_bounded() {
+        let cache = FilesystemContractRuntimeCache::test().unwrap();
+        let account_id: AccountId = "alice.near".parse().unwrap();
+        let popular = CryptoHash::hash_bytes(b"popular");
+        cache.record_use(&popular, &account_id);
+        cache.record_use(&popular, &account_id);
+        for i in 0..MAX_COUNTED_USAGE {
+            cache.record_use(&CryptoHash::hash_borsh(i as u64), &account_id);
+        }
+        let most_used = cache.most_used(usize::MAX);
+        assert_eq!(most_used.len(), MAX_SAVED_USAGE);
+        assert_eq!(most_used[0].code_hash, popular);
+    }
 }
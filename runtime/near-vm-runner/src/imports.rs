@@ -137,6 +137,13 @@ imports! {
         pub_key_len: u64,
         pub_key_ptr: u64
     ] -> [u64]>,
+    #[ed25519_verify_batch] ed25519_verify_batch<[sigs_len: u64,
+        sigs_ptr: u64,
+        msgs_len: u64,
+        msgs_ptr: u64,
+        pub_keys_len: u64,
+        pub_keys_ptr: u64
+    ] -> [u64]>,
     #[math_extension] ripemd160<[value_len: u64, value_ptr: u64, register_id: u64] -> []>,
     #[math_extension] ecrecover<[hash_len: u64, hash_ptr: u64, sign_len: u64, sig_ptr: u64, v: u64, malleability_flag: u64, register_id: u64] -> [u64]>,
     // #####################
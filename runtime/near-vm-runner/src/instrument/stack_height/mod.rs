@@ -35,7 +35,8 @@
 //! # Stack cost
 //!
 //! Stack cost of the function is calculated as a sum of it's locals
-//! and the maximal height of the value stack.
+//! and the maximal height of the value stack, but at least the minimal stack
+//! cost, which limits how deep the calls can nest.
 //!
 //! All values are treated equally, as they have the same size.
 //!
@@ -126,10 +127,11 @@ impl Context {
 pub fn inject_limiter(
     mut module: elements::Module,
     stack_limit: u32,
+    min_stack_cost: u32,
 ) -> Result<elements::Module, Error> {
     let mut ctx = Context {
         stack_height_global_idx: generate_stack_height_global(&mut module),
-        func_stack_costs: compute_stack_costs(&module)?,
+        func_stack_costs: compute_stack_costs(&module, min_stack_cost)?,
         stack_limit,
     };
 
@@ -204,7 +206,7 @@ impl<'a> ModuleCtx<'a> {
 /// Calculate stack costs for all functions.
 ///
 /// Returns a vector with a stack cost for each function, including imports.
-fn compute_stack_costs(module: &elements::Module) -> Result<Vec<u32>, Error> {
+fn compute_stack_costs(module: &elements::Module, min_stack_cost: u32) -> Result<Vec<u32>, Error> {
     let module_ctx = ModuleCtx::new(module);
 
     // TODO: optimize!
@@ -214,7 +216,7 @@ fn compute_stack_costs(module: &elements::Module) -> Result<Vec<u32>, Error> {
                 // We can't calculate stack_cost of the import functions.
                 Ok(0)
             } else {
-                compute_stack_cost(func_idx as u32, &module_ctx)
+                Ok(compute_stack_cost(func_idx as u32, &module_ctx)?.max(min_stack_cost))
             }
         })
         .collect()
@@ -387,7 +389,26 @@ mod tests {
 "#,
         );
 
-        let module = inject_limiter(module, 1024).expect("Failed to inject stack counter");
+        let module = inject_limiter(module, 1024, 0).expect("Failed to inject stack counter");
         validate_module(module);
     }
+
+    #[test]
+    fn test_min_stack_cost() {
+        let module = parse_wat(
+            r#"
+(module
+    (func (result i32)
+        i32.const 1
+    )
+    (func
+        (local i32 i32 i32)
+    )
+)
+"#,
+        );
+
+        assert_eq!(compute_stack_costs(&module, 0).unwrap(), vec![1, 3]);
+        assert_eq!(compute_stack_costs(&module, 2).unwrap(), vec![2, 3]);
+    }
 }
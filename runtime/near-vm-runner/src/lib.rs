@@ -30,7 +30,7 @@ mod wasmtime_runner;
 pub use crate::logic::with_ext_cost_counter;
 pub use cache::{
     get_contract_cache_key, precompile_contract, CompiledContract, CompiledContractInfo,
-    ContractRuntimeCache, FilesystemContractRuntimeCache, MockContractRuntimeCache,
+    ContractRuntimeCache, ContractUsage, FilesystemContractRuntimeCache, MockContractRuntimeCache,
     NoContractRuntimeCache,
 };
 pub use code::ContractCode;
@@ -0,0 +1,148 @@
+use super::{HostError, VMLogicError};
+use curve25519_dalek::constants::ED25519_BASEPOINT_POINT;
+use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+use curve25519_dalek::scalar::Scalar;
+use curve25519_dalek::traits::{IsIdentity, VartimeMultiscalarMul};
+use sha2::{Digest, Sha512};
+
+pub(super) const SIGNATURE_SIZE: usize = ed25519_dalek::SIGNATURE_LENGTH;
+pub(super) const PUBLIC_KEY_SIZE: usize = ed25519_dalek::PUBLIC_KEY_LENGTH;
+const MESSAGE_LEN_SIZE: usize = std::mem::size_of::<u32>();
+
+/// Domain separator for the hash the batch coefficients are derived from.
+const COEFFICIENTS_DOMAIN: &[u8] = b"near ed25519_verify_batch";
+
+fn invalid_input(msg: String) -> VMLogicError {
+    HostError::Ed25519VerifyInvalidInput { msg }.into()
+}
+
+pub(super) fn split_signatures(data: &[u8]) -> Result<&[[u8; SIGNATURE_SIZE]], VMLogicError> {
+    stdx::as_chunks_exact(data).map_err(|e| invalid_input(format!("invalid signatures: {e}")))
+}
+
+pub(super) fn split_public_keys(
+    data: &[u8],
+    num_signatures: usize,
+) -> Result<&[[u8; PUBLIC_KEY_SIZE]], VMLogicError> {
+    let public_keys = stdx::as_chunks_exact(data)
+        .map_err(|e| invalid_input(format!("invalid public keys: {e}")))?;
+    if public_keys.len() != num_signatures {
+        return Err(invalid_input(format!(
+            "expected {num_signatures} public keys, got {}",
+            public_keys.len()
+        )));
+    }
+    Ok(public_keys)
+}
+
+/// Splits messages which are each prefixed with their length as a
+/// little-endian `u32`.
+pub(super) fn split_messages(
+    mut data: &[u8],
+    num_signatures: usize,
+) -> Result<Vec<&[u8]>, VMLogicError> {
+    let mut messages = Vec::with_capacity(num_signatures);
+    while !data.is_empty() {
+        if data.len() < MESSAGE_LEN_SIZE {
+            return Err(invalid_input("truncated message length".to_string()));
+        }
+        let (len, rest) = data.split_at(MESSAGE_LEN_SIZE);
+        let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+        if rest.len() < len {
+            return Err(invalid_input(format!(
+                "message length {len} exceeds the remaining {} bytes",
+                rest.len()
+            )));
+        }
+        let (message, rest) = rest.split_at(len);
+        messages.push(message);
+        data = rest;
+    }
+    if messages.len() != num_signatures {
+        return Err(invalid_input(format!(
+            "expected {num_signatures} messages, got {}",
+            messages.len()
+        )));
+    }
+    Ok(messages)
+}
+
+/// Checks all signatures at once with the cofactored batch equation
+/// `[8](sum(z_i * s_i) * B - sum(z_i * R_i) - sum(z_i * k_i * A_i)) == 0`.
+///
+/// The coefficients `z_i` have to be unpredictable to whoever produced the
+/// signatures, but all nodes must agree on the result, so instead of being
+/// random they are derived from a hash of the whole batch.
+pub(super) fn verify_batch(
+    signatures: &[[u8; SIGNATURE_SIZE]],
+    messages: &[&[u8]],
+    public_keys: &[[u8; PUBLIC_KEY_SIZE]],
+) -> bool {
+    debug_assert_eq!(signatures.len(), messages.len());
+    debug_assert_eq!(signatures.len(), public_keys.len());
+    if signatures.is_empty() {
+        return true;
+    }
+
+    let mut seed = Sha512::new();
+    seed.update(COEFFICIENTS_DOMAIN);
+    for ((signature, message), public_key) in signatures.iter().zip(messages).zip(public_keys) {
+        seed.update(signature);
+        seed.update(public_key);
+        seed.update((message.len() as u64).to_le_bytes());
+        seed.update(message);
+    }
+    let seed = seed.finalize();
+
+    let num_points = 2 * signatures.len() + 1;
+    let mut scalars = Vec::with_capacity(num_points);
+    let mut points = Vec::with_capacity(num_points);
+    let mut basepoint_scalar = Scalar::ZERO;
+    for (i, ((signature, message), public_key)) in
+        signatures.iter().zip(messages).zip(public_keys).enumerate()
+    {
+        let (r_bytes, s_bytes): (&[u8; 32], &[u8; 32]) = stdx::split_array(signature);
+        // Same sanity check as in `ed25519_verify`.
+        if s_bytes[31] & 0b1110_0000 != 0 {
+            return false;
+        }
+        let Some(s) = Option::<Scalar>::from(Scalar::from_canonical_bytes(*s_bytes)) else {
+            return false;
+        };
+        let Some(r) = CompressedEdwardsY(*r_bytes).decompress() else {
+            return false;
+        };
+        let Some(a) = CompressedEdwardsY(*public_key).decompress() else {
+            return false;
+        };
+
+        let k = wide_scalar(
+            Sha512::new().chain_update(r_bytes).chain_update(public_key).chain_update(message),
+        );
+        let z = coefficient(&seed, i as u64);
+
+        basepoint_scalar += z * s;
+        scalars.push(-z);
+        points.push(r);
+        scalars.push(-(z * k));
+        points.push(a);
+    }
+    scalars.push(basepoint_scalar);
+    points.push(ED25519_BASEPOINT_POINT);
+
+    EdwardsPoint::vartime_multiscalar_mul(scalars, points).mul_by_cofactor().is_identity()
+}
+
+fn wide_scalar(hasher: Sha512) -> Scalar {
+    let mut bytes = [0u8; 64];
+    bytes.copy_from_slice(&hasher.finalize());
+    Scalar::from_bytes_mod_order_wide(&bytes)
+}
+
+/// 128 bit coefficient of the `index`-th signature in the batch.
+fn coefficient(seed: &[u8], index: u64) -> Scalar {
+    let hash = Sha512::new().chain_update(seed).chain_update(index.to_le_bytes()).finalize();
+    let mut bytes = [0u8; 32];
+    bytes[..16].copy_from_slice(&hash[..16]);
+    Scalar::from_bytes_mod_order(bytes)
+}
@@ -1198,6 +1198,64 @@ impl<'a> VMLogic<'a> {
         }
     }
 
+    /// Verify a batch of ED25519 signatures, each with its own message and
+    /// public key.
+    ///
+    /// `signatures` holds the 64 byte signatures and `public_keys` the 32 byte
+    /// public keys, both concatenated in the same order. `messages` holds the
+    /// messages in the same order, each prefixed with its length as a
+    /// little-endian `u32`.
+    ///
+    /// Returns a bool indicating whether all signatures are valid (1) or not
+    /// (0) as a `u64`. An empty batch is valid. The batch is checked with the
+    /// cofactored verification equation, so unlike [`Self::ed25519_verify`] it
+    /// accepts signatures whose `R` or public key have a small order component.
+    ///
+    /// # Errors
+    ///
+    /// * If the size of `signatures` is not a multiple of 64, the size of
+    ///   `public_keys` is not 32 times the number of signatures, or `messages`
+    ///   does not consist of exactly one length-prefixed message per
+    ///   signature, returns [HostError::Ed25519VerifyInvalidInput].
+    /// * If any of the signatures, messages or public keys arguments are out
+    ///   of memory bounds, returns [`HostError::MemoryAccessViolation`]
+    ///
+    /// # Cost
+    ///
+    /// Each input can either be in memory or in a register, see
+    /// [`Self::ed25519_verify`] for the definition of `input_cost`.
+    ///
+    /// `input_cost(num_bytes_signatures) + input_cost(num_bytes_messages) +
+    ///  input_cost(num_bytes_public_keys) + ed25519_verify_batch_base +
+    ///  ed25519_verify_batch_signature * num_signatures +
+    ///  ed25519_verify_batch_byte * num_bytes_messages`
+    pub fn ed25519_verify_batch(
+        &mut self,
+        signatures_len: u64,
+        signatures_ptr: u64,
+        messages_len: u64,
+        messages_ptr: u64,
+        public_keys_len: u64,
+        public_keys_ptr: u64,
+    ) -> Result<u64> {
+        use super::ed25519_batch;
+
+        self.gas_counter.pay_base(ed25519_verify_batch_base)?;
+
+        let signatures = get_memory_or_register!(self, signatures_ptr, signatures_len)?;
+        let signatures = ed25519_batch::split_signatures(&signatures)?;
+        self.gas_counter.pay_per(ed25519_verify_batch_signature, signatures.len() as u64)?;
+
+        let messages = get_memory_or_register!(self, messages_ptr, messages_len)?;
+        self.gas_counter.pay_per(ed25519_verify_batch_byte, messages.len() as u64)?;
+        let messages = ed25519_batch::split_messages(&messages, signatures.len())?;
+
+        let public_keys = get_memory_or_register!(self, public_keys_ptr, public_keys_len)?;
+        let public_keys = ed25519_batch::split_public_keys(&public_keys, signatures.len())?;
+
+        Ok(ed25519_batch::verify_batch(signatures, &messages, public_keys) as u64)
+    }
+
     /// Consume gas. Counts both towards `burnt_gas` and `used_gas`.
     ///
     /// # Errors
@@ -1,6 +1,7 @@
 mod alt_bn128;
 mod context;
 mod dependencies;
+mod ed25519_batch;
 pub mod errors;
 pub mod gas_counter;
 mod logic;
@@ -0,0 +1,231 @@
+use crate::logic::tests::helpers::*;
+use crate::logic::tests::vm_logic_builder::VMLogicBuilder;
+use crate::logic::HostError;
+use crate::logic::VMLogicError;
+use crate::map;
+use ed25519_dalek::Signer;
+use near_parameters::ExtCosts;
+use std::collections::HashMap;
+
+/// Signatures, length-prefixed messages and public keys of a batch, packed
+/// the way `ed25519_verify_batch` expects them.
+#[derive(Default)]
+struct Batch {
+    signatures: Vec<u8>,
+    messages: Vec<u8>,
+    public_keys: Vec<u8>,
+}
+
+impl Batch {
+    /// Batch of `n` valid signatures, each by a different key over a
+    /// different message.
+    fn valid(n: u8) -> Self {
+        let mut batch = Self::default();
+        for i in 0..n {
+            let key = ed25519_dalek::SigningKey::from_bytes(&[i; 32]);
+            let message = vec![i; 10 + i as usize];
+            batch.push(&key.sign(&message).to_bytes(), &message, &key.verifying_key().to_bytes());
+        }
+        batch
+    }
+
+    fn push(&mut self, signature: &[u8; 64], message: &[u8], public_key: &[u8; 32]) {
+        self.signatures.extend_from_slice(signature);
+        self.messages.extend_from_slice(&(message.len() as u32).to_le_bytes());
+        self.messages.extend_from_slice(message);
+        self.public_keys.extend_from_slice(public_key);
+    }
+}
+
+fn ed25519_verify_batch(batch: &Batch) -> Result<u64, VMLogicError> {
+    let mut logic_builder = VMLogicBuilder::default();
+    let mut logic = logic_builder.build();
+
+    let signatures = logic.internal_mem_write(&batch.signatures);
+    let messages = logic.internal_mem_write(&batch.messages);
+    let public_keys = logic.internal_mem_write(&batch.public_keys);
+
+    logic.ed25519_verify_batch(
+        signatures.len,
+        signatures.ptr,
+        messages.len,
+        messages.ptr,
+        public_keys.len,
+        public_keys.ptr,
+    )
+}
+
+#[track_caller]
+fn check_ed25519_verify_batch(batch: &Batch, want: u64) {
+    assert_eq!(ed25519_verify_batch(batch), Ok(want));
+}
+
+#[track_caller]
+fn check_invalid_input(batch: &Batch, want_costs: HashMap<ExtCosts, u64>) {
+    let result = ed25519_verify_batch(batch);
+    assert!(
+        matches!(result, Err(VMLogicError::HostError(HostError::Ed25519VerifyInvalidInput { .. }))),
+        "unexpected result {result:?}"
+    );
+    assert_costs(want_costs);
+}
+
+#[test]
+fn test_ed25519_verify_batch_valid() {
+    for n in [1, 2, 5, 16] {
+        check_ed25519_verify_batch(&Batch::valid(n), 1);
+    }
+    // An empty batch is trivially valid.
+    check_ed25519_verify_batch(&Batch::default(), 1);
+}
+
+#[test]
+fn test_ed25519_verify_batch_costs() {
+    let batch = Batch::valid(3);
+    check_ed25519_verify_batch(&batch, 1);
+    let messages_len = batch.messages.len() as u64;
+    assert_eq!(messages_len, 3 * 4 + 10 + 11 + 12);
+    assert_costs(map! {
+        ExtCosts::read_memory_base: 3,
+        ExtCosts::read_memory_byte: 3 * 64 + messages_len + 3 * 32,
+        ExtCosts::ed25519_verify_batch_base: 1,
+        ExtCosts::ed25519_verify_batch_signature: 3,
+        ExtCosts::ed25519_verify_batch_byte: messages_len,
+    });
+}
+
+#[test]
+fn test_ed25519_verify_batch_invalid_signature() {
+    let valid = Batch::valid(4);
+    for i in 0..4 {
+        // Wrong message.
+        let mut batch = Batch::valid(4);
+        let message_start = (0..i).map(|j| 4 + 10 + j).sum::<usize>() + 4;
+        batch.messages[message_start] ^= 1;
+        check_ed25519_verify_batch(&batch, 0);
+
+        // Wrong public key.
+        let mut batch = Batch::valid(4);
+        batch.public_keys[i * 32..(i + 1) * 32]
+            .copy_from_slice(&valid.public_keys[(i + 1) % 4 * 32..((i + 1) % 4 + 1) * 32]);
+        check_ed25519_verify_batch(&batch, 0);
+
+        // Corrupted signature.
+        let mut batch = Batch::valid(4);
+        batch.signatures[i * 64 + 40] ^= 1;
+        check_ed25519_verify_batch(&batch, 0);
+
+        // Not properly reduced `s` scalar.
+        let mut batch = Batch::valid(4);
+        batch.signatures[i * 64 + 63] |= 0b1110_0000;
+        check_ed25519_verify_batch(&batch, 0);
+    }
+
+    // Two signatures swapped between their messages.
+    let mut batch = Batch::valid(2);
+    let (first, second) = batch.signatures.split_at_mut(64);
+    first.swap_with_slice(second);
+    check_ed25519_verify_batch(&batch, 0);
+}
+
+/// The batch gives the same result as verifying every signature separately.
+#[test]
+fn test_ed25519_verify_batch_matches_single_verify() {
+    let batch = Batch::valid(8);
+    let mut logic_builder = VMLogicBuilder::default();
+    let mut logic = logic_builder.build();
+    let mut offset = 0;
+    for i in 0..8 {
+        let len = u32::from_le_bytes(batch.messages[offset..offset + 4].try_into().unwrap());
+        let message = &batch.messages[offset + 4..offset + 4 + len as usize];
+        offset += 4 + len as usize;
+        let signature = logic.internal_mem_write(&batch.signatures[i * 64..(i + 1) * 64]);
+        let message = logic.internal_mem_write(message);
+        let public_key = logic.internal_mem_write(&batch.public_keys[i * 32..(i + 1) * 32]);
+        let result = logic.ed25519_verify(
+            signature.len,
+            signature.ptr,
+            message.len,
+            message.ptr,
+            public_key.len,
+            public_key.ptr,
+        );
+        assert_eq!(result, Ok(1));
+    }
+    check_ed25519_verify_batch(&batch, 1);
+}
+
+#[test]
+fn test_ed25519_verify_batch_invalid_input() {
+    // Signatures are not a multiple of 64 bytes.
+    let mut batch = Batch::valid(2);
+    batch.signatures.pop();
+    check_invalid_input(
+        &batch,
+        map! {
+            ExtCosts::read_memory_base: 1,
+            ExtCosts::read_memory_byte: 127,
+            ExtCosts::ed25519_verify_batch_base: 1,
+        },
+    );
+
+    // Less public keys than signatures.
+    let mut batch = Batch::valid(2);
+    batch.public_keys.truncate(32);
+    let messages_len = batch.messages.len() as u64;
+    check_invalid_input(
+        &batch,
+        map! {
+            ExtCosts::read_memory_base: 3,
+            ExtCosts::read_memory_byte: 128 + messages_len + 32,
+            ExtCosts::ed25519_verify_batch_base: 1,
+            ExtCosts::ed25519_verify_batch_signature: 2,
+            ExtCosts::ed25519_verify_batch_byte: messages_len,
+        },
+    );
+
+    // More messages than signatures.
+    let mut batch = Batch::valid(2);
+    batch.messages.extend_from_slice(&0u32.to_le_bytes());
+    let messages_len = batch.messages.len() as u64;
+    check_invalid_input(
+        &batch,
+        map! {
+            ExtCosts::read_memory_base: 2,
+            ExtCosts::read_memory_byte: 128 + messages_len,
+            ExtCosts::ed25519_verify_batch_base: 1,
+            ExtCosts::ed25519_verify_batch_signature: 2,
+            ExtCosts::ed25519_verify_batch_byte: messages_len,
+        },
+    );
+
+    // Message length prefix points past the end.
+    let mut batch = Batch::valid(2);
+    batch.messages.pop();
+    let messages_len = batch.messages.len() as u64;
+    check_invalid_input(
+        &batch,
+        map! {
+            ExtCosts::read_memory_base: 2,
+            ExtCosts::read_memory_byte: 128 + messages_len,
+            ExtCosts::ed25519_verify_batch_base: 1,
+            ExtCosts::ed25519_verify_batch_signature: 2,
+            ExtCosts::ed25519_verify_batch_byte: messages_len,
+        },
+    );
+
+    // Truncated length prefix.
+    let mut batch = Batch::valid(1);
+    batch.messages.extend_from_slice(&[1, 0]);
+    let messages_len = batch.messages.len() as u64;
+    check_invalid_input(
+        &batch,
+        map! {
+            ExtCosts::read_memory_base: 2,
+            ExtCosts::read_memory_byte: 64 + messages_len,
+            ExtCosts::ed25519_verify_batch_base: 1,
+            ExtCosts::ed25519_verify_batch_signature: 1,
+            ExtCosts::ed25519_verify_batch_byte: messages_len,
+        },
+    );
+}
@@ -1,6 +1,7 @@
 mod alt_bn128;
 mod context;
 mod ed25519_verify;
+mod ed25519_verify_batch;
 mod gas_counter;
 pub(crate) mod helpers;
 mod iterators;
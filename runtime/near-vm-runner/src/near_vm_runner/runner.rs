@@ -514,7 +514,11 @@ impl near_vm_vm::Tunables for &NearVM {
 
     /// Instrumentation configuration: stack limiter config
     fn stack_limiter_cfg(&self) -> Box<dyn finite_wasm::max_stack::SizeConfig> {
-        Box::new(MaxStackCfg)
+        Box::new(MaxStackCfg {
+            min_activation_size: u64::from(
+                self.config.limit_config.min_function_activation_stack_height(),
+            ),
+        })
     }
 
     /// Instrumentation configuration: gas accounting config
@@ -523,7 +527,9 @@ impl near_vm_vm::Tunables for &NearVM {
     }
 }
 
-struct MaxStackCfg;
+struct MaxStackCfg {
+    min_activation_size: u64,
+}
 
 impl finite_wasm::max_stack::SizeConfig for MaxStackCfg {
     fn size_of_value(&self, ty: finite_wasm::wasmparser::ValType) -> u8 {
@@ -553,7 +559,7 @@ impl finite_wasm::max_stack::SizeConfig for MaxStackCfg {
             );
             last_idx_plus_one = idx.saturating_add(1);
         }
-        res
+        res.max(self.min_activation_size)
     }
 }
 
@@ -96,6 +96,7 @@ impl<'a> ContractModule<'a> {
         let module = crate::instrument::stack_height::inject_limiter(
             module,
             config.limit_config.max_stack_height,
+            config.limit_config.min_function_activation_stack_height(),
         )
         .map_err(|_| PrepareError::StackHeightInstrumentation)?;
         Ok(Self { module, config })
@@ -278,7 +278,11 @@ pub(crate) fn prepare_contract(
     }
 
     let res = finite_wasm::Analysis::new()
-        .with_stack(Box::new(SimpleMaxStackCfg))
+        .with_stack(Box::new(SimpleMaxStackCfg {
+            min_activation_size: u64::from(
+                config.limit_config.min_function_activation_stack_height(),
+            ),
+        }))
         .with_gas(Box::new(SimpleGasCostCfg(u64::from(config.regular_op_cost))))
         .analyze(&lightly_steamed)
         .map_err(|err| {
@@ -295,7 +299,9 @@ pub(crate) fn prepare_contract(
 }
 
 // TODO: refactor to avoid copy-paste with the ones currently defined in near_vm_runner
-struct SimpleMaxStackCfg;
+struct SimpleMaxStackCfg {
+    min_activation_size: u64,
+}
 
 impl finite_wasm::max_stack::SizeConfig for SimpleMaxStackCfg {
     fn size_of_value(&self, ty: wp::ValType) -> u8 {
@@ -325,7 +331,7 @@ impl finite_wasm::max_stack::SizeConfig for SimpleMaxStackCfg {
             );
             last_idx_plus_one = idx.saturating_add(1);
         }
-        res
+        res.max(self.min_activation_size)
     }
 }
 
@@ -1,7 +1,9 @@
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::schema::{Declaration, Definition, Fields};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use enum_map::{enum_map, Enum, EnumMap};
 use near_parameters::{ActionCosts, ExtCosts, ExtCostsConfig};
 use near_primitives_core::types::{Compute, Gas};
+use std::collections::BTreeMap;
 use std::fmt;
 use strum::IntoEnumIterator;
 
@@ -170,6 +172,29 @@ impl BorshSerialize for ProfileDataV3 {
     }
 }
 
+/// The enum maps are serialized as vectors indexed by `borsh_action_index` and
+/// `borsh_ext_index`.
+impl BorshSchema for ProfileDataV3 {
+    fn add_definitions_recursively(definitions: &mut BTreeMap<Declaration, Definition>) {
+        let fields = Fields::NamedFields(vec![
+            ("actions_profile".to_string(), <Vec<u64>>::declaration()),
+            ("wasm_ext_profile".to_string(), <Vec<u64>>::declaration()),
+            ("wasm_gas".to_string(), u64::declaration()),
+        ]);
+        borsh::schema::add_definition(
+            Self::declaration(),
+            Definition::Struct { fields },
+            definitions,
+        );
+        <Vec<u64>>::add_definitions_recursively(definitions);
+        u64::add_definitions_recursively(definitions);
+    }
+
+    fn declaration() -> Declaration {
+        "ProfileDataV3".to_string()
+    }
+}
+
 /// Fixed index of an action cost for borsh (de)serialization.
 ///
 /// We use borsh to store profiles on the DB and borsh is quite fragile with
@@ -266,8 +291,8 @@ mod test {
             keccak256_byte -> 18 [0% host]
             keccak512_base -> 19 [0% host]
             keccak512_byte -> 20 [0% host]
-            ripemd160_base -> 21 [1% host]
-            ripemd160_block -> 22 [1% host]
+            ripemd160_base -> 21 [0% host]
+            ripemd160_block -> 22 [0% host]
             ecrecover_base -> 23 [1% host]
             log_base -> 24 [1% host]
             log_byte -> 25 [1% host]
@@ -287,10 +312,10 @@ mod test {
             storage_iter_create_prefix_byte -> 39 [1% host]
             storage_iter_create_range_base -> 40 [1% host]
             storage_iter_create_from_byte -> 41 [1% host]
-            storage_iter_create_to_byte -> 42 [2% host]
-            storage_iter_next_base -> 43 [2% host]
-            storage_iter_next_key_byte -> 44 [2% host]
-            storage_iter_next_value_byte -> 45 [2% host]
+            storage_iter_create_to_byte -> 42 [1% host]
+            storage_iter_next_base -> 43 [1% host]
+            storage_iter_next_key_byte -> 44 [1% host]
+            storage_iter_next_value_byte -> 45 [1% host]
             touching_trie_node -> 46 [2% host]
             read_cached_trie_node -> 47 [2% host]
             promise_and_base -> 48 [2% host]
@@ -308,8 +333,11 @@ mod test {
             ed25519_verify_byte -> 60 [2% host]
             yield_create_base -> 61 [2% host]
             yield_create_byte -> 62 [2% host]
-            yield_resume_base -> 63 [3% host]
-            yield_resume_byte -> 64 [3% host]
+            yield_resume_base -> 63 [2% host]
+            yield_resume_byte -> 64 [2% host]
+            ed25519_verify_batch_base -> 65 [2% host]
+            ed25519_verify_batch_signature -> 66 [2% host]
+            ed25519_verify_batch_byte -> 67 [2% host]
             ------ Actions --------
             create_account -> 1000
             delete_account -> 1001
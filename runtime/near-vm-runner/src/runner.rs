@@ -60,6 +60,9 @@ pub fn run(
     cache: Option<&dyn ContractRuntimeCache>,
 ) -> VMResult {
     let span = tracing::Span::current();
+    if let Some(cache) = cache {
+        cache.record_use(&account.code_hash(), &context.current_account_id);
+    }
     let vm_kind = wasm_config.vm_kind;
     let runtime = vm_kind
         .runtime(wasm_config.clone())
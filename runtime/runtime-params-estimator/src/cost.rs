@@ -725,6 +725,30 @@ pub enum Cost {
     /// `promise_yield_resume` host function.
     YieldResumeByte,
 
+    /// Estimates `ed25519_verify_batch_base`, which covers the base cost of the
+    /// `ed25519_verify_batch` host function.
+    ///
+    /// Estimation: Verify a batch of a single signature over a 32 bytes
+    /// message many times. This also includes the cost of one signature in the
+    /// batch, which is small enough compared to the fixed cost of the final
+    /// multiscalar multiplication that we are okay overcharging it.
+    Ed25519VerifyBatchBase,
+    /// Estimates `ed25519_verify_batch_signature`, the cost charged per
+    /// signature in calls to the `ed25519_verify_batch` host function.
+    ///
+    /// Estimation: Verify batches of 64 signatures over 32 bytes messages,
+    /// subtract the base cost and divide the remainder by the number of
+    /// signatures. Like for `Ed25519VerifyBase`, the multiplication is not
+    /// constant time, but the variation averages out over the batch.
+    Ed25519VerifyBatchSignature,
+    /// Estimates `ed25519_verify_batch_byte`, the cost charged per byte of
+    /// messages in calls to the `ed25519_verify_batch` host function.
+    ///
+    /// Estimation: Same as for `Ed25519VerifyByte`, verify a batch of one
+    /// signature over a large message many times, subtract the base cost and
+    /// divide by the total bytes of the messages.
+    Ed25519VerifyBatchByte,
+
     __Count,
 }
 
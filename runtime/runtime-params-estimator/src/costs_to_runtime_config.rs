@@ -40,6 +40,8 @@ pub fn costs_to_runtime_config(cost_table: &CostTable) -> anyhow::Result<Runtime
         account_creation_config: AccountCreationConfig::default(),
         storage_proof_size_soft_limit: usize::MAX,
         congestion_control_config: latest_runtime_config.congestion_control_config,
+        shard_congestion_control_configs: Default::default(),
+        priority_fee_enabled: false,
     };
     Ok(res)
 }
@@ -155,6 +157,9 @@ fn estimation(cost: ExtCosts) -> Option<Cost> {
         ExtCosts::alt_bn128_pairing_check_element => Cost::AltBn128PairingCheckElement,
         ExtCosts::yield_create_base => Cost::YieldCreateBase,
         ExtCosts::yield_create_byte => Cost::YieldCreateByte,
+        ExtCosts::ed25519_verify_batch_base => Cost::Ed25519VerifyBatchBase,
+        ExtCosts::ed25519_verify_batch_signature => Cost::Ed25519VerifyBatchSignature,
+        ExtCosts::ed25519_verify_batch_byte => Cost::Ed25519VerifyBatchByte,
         _ => return None,
     })
 }
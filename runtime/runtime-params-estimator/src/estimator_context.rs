@@ -50,6 +50,8 @@ pub(crate) struct CachedCosts {
     pub(crate) function_call_base: Option<GasCost>,
     #[cfg(feature = "nightly")]
     pub(crate) yield_create_base: Option<GasCost>,
+    #[cfg(feature = "nightly")]
+    pub(crate) ed25519_verify_batch_base: Option<GasCost>,
 }
 
 impl<'c> EstimatorContext<'c> {
@@ -137,6 +139,7 @@ impl<'c> EstimatorContext<'c> {
             max_actions_per_receipt: u64::MAX,
             max_promises_per_function_call_action: u64::MAX,
             max_number_input_data_dependencies: u64::MAX,
+            max_receipt_size: u64::MAX,
             max_length_storage_key: u64::MAX,
 
             max_total_prepaid_gas: u64::MAX,
@@ -262,6 +262,12 @@ static ALL_COSTS: &[(Cost, fn(&mut EstimatorContext) -> GasCost)] = &[
     (Cost::YieldResumeBase, yield_resume_base),
     #[cfg(feature = "nightly")]
     (Cost::YieldResumeByte, yield_resume_byte),
+    #[cfg(feature = "nightly")]
+    (Cost::Ed25519VerifyBatchBase, ed25519_verify_batch_base),
+    #[cfg(feature = "nightly")]
+    (Cost::Ed25519VerifyBatchSignature, ed25519_verify_batch_signature),
+    #[cfg(feature = "nightly")]
+    (Cost::Ed25519VerifyBatchByte, ed25519_verify_batch_byte),
     (Cost::CpuBenchmarkSha256, cpu_benchmark_sha256),
     (Cost::OneCPUInstruction, one_cpu_instruction),
     (Cost::OneNanosecond, one_nanosecond),
@@ -1044,6 +1050,50 @@ fn ed25519_verify_byte(ctx: &mut EstimatorContext) -> GasCost {
     byte - base / iteration_bytes
 }
 
+#[cfg(feature = "nightly")]
+fn ed25519_verify_batch_base(ctx: &mut EstimatorContext) -> GasCost {
+    if let Some(cost) = &ctx.cached.ed25519_verify_batch_base {
+        return cost.clone();
+    }
+    let cost = fn_cost(ctx, "ed25519_verify_batch_1_500", ExtCosts::ed25519_verify_batch_base, 500);
+    ctx.cached.ed25519_verify_batch_base.insert(cost).clone()
+}
+
+#[cfg(feature = "nightly")]
+fn ed25519_verify_batch_signature(ctx: &mut EstimatorContext) -> GasCost {
+    let base = ed25519_verify_batch_base(ctx);
+    // inside the WASM function, there are 64 calls to `ed25519_verify_batch`
+    let call_num = 64;
+    // each call verifies a batch of 64 signatures
+    let batch_size = 64;
+    let signature = fn_cost(
+        ctx,
+        "ed25519_verify_batch_64_64",
+        ExtCosts::ed25519_verify_batch_signature,
+        call_num * batch_size,
+    );
+    // need to subtract the base cost, which has already been divided by the batch size
+    signature - base / batch_size
+}
+
+#[cfg(feature = "nightly")]
+fn ed25519_verify_batch_byte(ctx: &mut EstimatorContext) -> GasCost {
+    let base = ed25519_verify_batch_base(ctx);
+    // inside the WASM function, there are 64 calls to `ed25519_verify_batch`
+    let call_num = 64;
+    // each call checks a single message of size 16kiB
+    let iteration_bytes = 16384;
+    let total_bytes = call_num * iteration_bytes;
+    let byte = fn_cost(
+        ctx,
+        "ed25519_verify_batch_16kib_64",
+        ExtCosts::ed25519_verify_batch_byte,
+        total_bytes,
+    );
+    // need to subtract the base cost, which has already been divided by the number of bytes per iteration
+    byte - base / iteration_bytes
+}
+
 fn alt_bn128g1_multiexp_base(ctx: &mut EstimatorContext) -> GasCost {
     fn_cost(ctx, "alt_bn128_g1_multiexp_1_10", ExtCosts::alt_bn128_g1_multiexp_base, 10)
 }
@@ -1,12 +1,13 @@
 use crate::near_primitives::shard_layout::ShardUId;
 use near_crypto::PublicKey;
+use near_parameters::StorageUsageConfig;
 use near_primitives::account::{AccessKey, Account};
 use near_primitives::hash::CryptoHash;
 use near_primitives::types::{
     AccountId, BlockHeight, EpochHeight, EpochId, EpochInfoProvider, MerkleHash,
 };
 use near_primitives::version::ProtocolVersion;
-use near_primitives::views::ViewStateResult;
+use near_primitives::views::{StorageUsageView, ViewStateResult};
 use near_vm_runner::ContractCode;
 
 /// Adapter for querying runtime.
@@ -43,6 +44,13 @@ pub trait ViewRuntimeAdapter {
         current_protocol_version: ProtocolVersion,
     ) -> Result<Vec<u8>, crate::state_viewer::errors::CallFunctionError>;
 
+    fn view_code_by_hash(
+        &self,
+        shard_uid: &ShardUId,
+        state_root: MerkleHash,
+        code_hash: &CryptoHash,
+    ) -> Result<ContractCode, crate::state_viewer::errors::ViewContractCodeError>;
+
     fn view_access_key(
         &self,
         shard_uid: &ShardUId,
@@ -56,6 +64,8 @@ pub trait ViewRuntimeAdapter {
         shard_uid: &ShardUId,
         state_root: MerkleHash,
         account_id: &AccountId,
+        after_public_key: Option<&PublicKey>,
+        limit: Option<usize>,
     ) -> Result<Vec<(PublicKey, AccessKey)>, crate::state_viewer::errors::ViewAccessKeyError>;
 
     fn view_state(
@@ -66,4 +76,13 @@ pub trait ViewRuntimeAdapter {
         prefix: &[u8],
         include_proof: bool,
     ) -> Result<ViewStateResult, crate::state_viewer::errors::ViewStateError>;
+
+    fn view_storage_usage(
+        &self,
+        shard_uid: &ShardUId,
+        state_root: MerkleHash,
+        account_id: &AccountId,
+        storage_usage_config: &StorageUsageConfig,
+        num_largest_data_keys: usize,
+    ) -> Result<StorageUsageView, crate::state_viewer::errors::ViewStateError>;
 }
@@ -37,6 +37,29 @@ fn get_delayed_receipts(
         .collect()
 }
 
+/// Checks that the number of processed delayed receipts matches the number of
+/// receipts removed from the front of the delayed receipt queue.
+///
+/// With `DelayedReceiptPriority` the processed receipts are not necessarily
+/// the oldest ones, the receipts left in the priority window are put back to
+/// the front of the queue, so only the number of removed indices is known.
+fn check_processed_delayed_receipts(
+    initial_indices: &DelayedReceiptIndices,
+    final_indices: &DelayedReceiptIndices,
+    processed_delayed_receipts: &[Receipt],
+) -> Result<(), StorageError> {
+    let removed = final_indices.first_index.checked_sub(initial_indices.first_index);
+    if removed != Some(processed_delayed_receipts.len() as u64) {
+        return Err(StorageError::StorageInconsistentState(format!(
+            "Processed {} delayed receipts, but the delayed receipt queue moved from index {} to {}",
+            processed_delayed_receipts.len(),
+            initial_indices.first_index,
+            final_indices.first_index
+        )));
+    }
+    Ok(())
+}
+
 /// Calculates and returns cost of a receipt.
 fn receipt_cost(
     config: &RuntimeConfig,
@@ -268,6 +291,7 @@ pub(crate) fn check_balance(
     validator_accounts_update: &Option<ValidatorAccountsUpdate>,
     incoming_receipts: &[Receipt],
     yield_timeout_receipts: &[Receipt],
+    processed_delayed_receipts: &[Receipt],
     transactions: &[SignedTransaction],
     outgoing_receipts: &[Receipt],
     stats: &ApplyStats,
@@ -281,9 +305,10 @@ pub(crate) fn check_balance(
         get(final_state, &TrieKey::DelayedReceiptIndices)?.unwrap_or_default();
 
     // Previously delayed receipts that were processed this time.
-    let processed_delayed_receipts = get_delayed_receipts(
-        initial_state,
-        initial_delayed_receipt_indices.first_index..final_delayed_receipt_indices.first_index,
+    check_processed_delayed_receipts(
+        &initial_delayed_receipt_indices,
+        &final_delayed_receipt_indices,
+        processed_delayed_receipts,
     )?;
     // Receipts that were not processed this time and are delayed now.
     let new_delayed_receipts = get_delayed_receipts(
@@ -300,7 +325,7 @@ pub(crate) fn check_balance(
     let all_accounts_ids = all_touched_accounts(
         incoming_receipts,
         yield_timeout_receipts,
-        &processed_delayed_receipts,
+        processed_delayed_receipts,
         transactions,
         validator_accounts_update,
     )?;
@@ -319,7 +344,7 @@ pub(crate) fn check_balance(
     let incoming_receipts_balance =
         receipts_cost(incoming_receipts)? + receipts_cost(yield_timeout_receipts)?;
     let outgoing_receipts_balance = receipts_cost(outgoing_receipts)?;
-    let processed_delayed_receipts_balance = receipts_cost(&processed_delayed_receipts)?;
+    let processed_delayed_receipts_balance = receipts_cost(processed_delayed_receipts)?;
     let new_delayed_receipts_balance = receipts_cost(&new_delayed_receipts)?;
     let forwarded_buffered_receipts_balance = receipts_cost(&forwarded_receipts)?;
     let new_buffered_receipts_balance = receipts_cost(&new_buffered_receipts)?;
@@ -328,7 +353,7 @@ pub(crate) fn check_balance(
     let all_potential_postponed_receipt_ids = potential_postponed_receipt_ids(
         incoming_receipts,
         yield_timeout_receipts,
-        &processed_delayed_receipts,
+        processed_delayed_receipts,
         initial_state,
     )?;
 
@@ -421,6 +446,7 @@ mod tests {
             &[],
             &[],
             &[],
+            &[],
             &ApplyStats::default(),
         )
         .unwrap();
@@ -439,6 +465,7 @@ mod tests {
             &[],
             &[],
             &[],
+            &[],
             &ApplyStats::default(),
         )
         .unwrap_err();
@@ -503,6 +530,7 @@ mod tests {
             &[],
             &[],
             &[],
+            &[],
             &ApplyStats::default(),
         )
         .unwrap();
@@ -549,6 +577,7 @@ mod tests {
             &None,
             &[],
             &[],
+            &[],
             &[tx],
             &[receipt],
             &ApplyStats {
@@ -621,6 +650,7 @@ mod tests {
                 &None,
                 &[receipt],
                 &[],
+                &[],
                 &[tx],
                 &[],
                 &ApplyStats::default(),
@@ -663,6 +693,7 @@ mod tests {
                 &None,
                 &[receipt],
                 &[],
+                &[],
                 &[tx],
                 &[],
                 &ApplyStats::default(),
@@ -740,6 +771,7 @@ mod tests {
             &None,
             &[],
             &[],
+            &[],
             &[tx],
             &[],
             &ApplyStats {
@@ -806,12 +838,64 @@ mod tests {
             &[],
             &[],
             &[],
+            &[],
             &outgoing_receipts,
             &ApplyStats::default(),
         )
         .unwrap();
     }
 
+    /// With `DelayedReceiptPriority` a delayed receipt can be processed before
+    /// older ones, which are put back to the front of the queue. The balance
+    /// of the receipt actually processed must be accounted for, not the one
+    /// of the oldest receipt.
+    #[test]
+    fn test_delayed_receipt_processed_out_of_order() {
+        let account_id = alice_account();
+        let gas_price = 10;
+
+        let tx = transfer_tx(account_id, bob_account(), 100);
+        let oldest_receipt = extract_transfer_receipt(&tx, gas_price, 100);
+        let priority_receipt = extract_transfer_receipt(&tx, gas_price * 2, 1000);
+
+        let final_state = prepare_state_change(
+            |trie_update| {
+                let indices = DelayedReceiptIndices { first_index: 0, next_available_index: 2 };
+                set(trie_update, TrieKey::DelayedReceiptIndices, &indices);
+                set(trie_update, TrieKey::DelayedReceipt { index: 0 }, &oldest_receipt);
+                set(trie_update, TrieKey::DelayedReceipt { index: 1 }, &priority_receipt);
+            },
+            |trie_update| {
+                // Both receipts were taken, the oldest one was put back.
+                let indices = DelayedReceiptIndices { first_index: 1, next_available_index: 2 };
+                set(trie_update, TrieKey::DelayedReceiptIndices, &indices);
+                trie_update.remove(TrieKey::DelayedReceipt { index: 0 });
+                set(trie_update, TrieKey::DelayedReceipt { index: 1 }, &oldest_receipt);
+            },
+        );
+
+        // The processed receipt is forwarded, keeping the balance in a receipt.
+        let check = |processed_delayed_receipts: &[Receipt]| {
+            check_balance(
+                &RuntimeConfig::test(),
+                &final_state,
+                &None,
+                &[],
+                &[],
+                processed_delayed_receipts,
+                &[],
+                &[priority_receipt.clone()],
+                &ApplyStats::default(),
+            )
+        };
+        check(&[priority_receipt.clone()]).unwrap();
+        assert_matches!(
+            check(&[oldest_receipt.clone()]),
+            Err(RuntimeError::BalanceMismatchError { .. })
+        );
+        assert_matches!(check(&[]), Err(RuntimeError::StorageError(_)));
+    }
+
     /// Test a balance mismatch error is produced for buffered receipts.
     #[test]
     fn test_buffered_receipt_balance_error() {
@@ -864,6 +948,7 @@ mod tests {
             &[],
             &[],
             &[],
+            &[],
             &outgoing_receipts,
             &ApplyStats::default(),
         );
@@ -2,7 +2,7 @@
 
 use near_primitives::account::AccessKeyPermission;
 use near_primitives::errors::IntegerOverflowError;
-use near_primitives::version::FIXED_MINIMUM_NEW_RECEIPT_GAS_VERSION;
+use near_primitives::version::{ProtocolFeature, FIXED_MINIMUM_NEW_RECEIPT_GAS_VERSION};
 use near_primitives_core::types::ProtocolVersion;
 use num_bigint::BigUint;
 use num_traits::cast::ToPrimitive;
@@ -28,6 +28,8 @@ pub struct TransactionCost {
     pub total_cost: Balance,
     /// The amount of tokens burnt by converting this transaction to a receipt.
     pub burnt_amount: Balance,
+    /// The priority fee paid to the chunk producer, included in `total_cost`.
+    pub priority_fee_amount: Balance,
 }
 
 /// Multiplies `gas_price` by the power of `inflation_base` with exponent `inflation_exponent`.
@@ -280,8 +282,12 @@ pub fn tx_cost(
         // overcharging to 6x, which was the value before the cost increase.
         4_855_842_000_000 // 4.855TGas.
     };
-    // In case the config is free, we don't care about the maximum depth.
-    let receipt_gas_price = if gas_price == 0 {
+    let receipt_gas_price = if ProtocolFeature::NoPessimisticGasPrice.enabled(protocol_version) {
+        // The receipts burn the gas at the price it is bought at, whatever the gas price when
+        // they execute.
+        gas_price
+    } else if gas_price == 0 {
+        // In case the config is free, we don't care about the maximum depth.
         0
     } else {
         let maximum_depth =
@@ -305,7 +311,16 @@ pub fn tx_cost(
     let remaining_gas_amount = safe_gas_to_balance(receipt_gas_price, gas_remaining)?;
     let mut total_cost = safe_add_balance(burnt_amount, remaining_gas_amount)?;
     total_cost = safe_add_balance(total_cost, total_deposit(&transaction.actions())?)?;
-    Ok(TransactionCost { gas_burnt, gas_remaining, receipt_gas_price, total_cost, burnt_amount })
+    let priority_fee_amount = transaction.priority_fee_amount();
+    total_cost = safe_add_balance(total_cost, priority_fee_amount)?;
+    Ok(TransactionCost {
+        gas_burnt,
+        gas_remaining,
+        receipt_gas_price,
+        total_cost,
+        burnt_amount,
+        priority_fee_amount,
+    })
 }
 
 /// Total sum of gas that would need to be burnt before we start executing the given actions.
@@ -378,6 +393,9 @@ pub fn total_prepaid_gas(actions: &[Action]) -> Result<Gas, IntegerOverflowError
 #[cfg(test)]
 mod tests {
     use super::*;
+    use near_crypto::{KeyType, PublicKey};
+    use near_primitives::hash::CryptoHash;
+    use near_primitives::transaction::{TransactionV0, TransferAction};
 
     #[test]
     fn test_safe_gas_price_inflated() {
@@ -387,4 +405,39 @@ mod tests {
         assert_eq!(safe_gas_price_inflated(10000, Rational32::new(101, 100), 3).unwrap(), 10304);
         assert_eq!(safe_gas_price_inflated(10000, Rational32::new(101, 100), 32).unwrap(), 13750);
     }
+
+    #[test]
+    fn test_tx_cost_receipt_gas_price() {
+        let config = RuntimeConfig::test();
+        let transaction = Transaction::V0(TransactionV0 {
+            signer_id: "alice.near".parse().unwrap(),
+            public_key: PublicKey::empty(KeyType::ED25519),
+            nonce: 0,
+            receiver_id: "bob.near".parse().unwrap(),
+            block_hash: CryptoHash::default(),
+            actions: vec![Action::Transfer(TransferAction { deposit: 1 })],
+        });
+        let gas_price = 100_000_000;
+        let cost = |protocol_version| {
+            tx_cost(&config, &transaction, gas_price, false, protocol_version).unwrap()
+        };
+        let version = ProtocolFeature::NoPessimisticGasPrice.protocol_version();
+
+        // The gas of the receipt is inflated for the hop to the receiver.
+        let inflated = safe_gas_price_inflated(
+            gas_price,
+            config.fees.pessimistic_gas_price_inflation_ratio,
+            1,
+        )
+        .unwrap();
+        assert!(inflated > gas_price);
+        assert_eq!(cost(version - 1).receipt_gas_price, inflated);
+
+        let cost = cost(version);
+        assert_eq!(cost.receipt_gas_price, gas_price);
+        assert_eq!(
+            cost.total_cost,
+            cost.burnt_amount + Balance::from(cost.gas_remaining) * gas_price + 1
+        );
+    }
 }
@@ -87,7 +87,7 @@ impl<'a> ReceiptSink<'a> {
                 .iter()
                 .map(|(&shard_id, congestion)| {
                     let other_congestion_control = CongestionControl::new(
-                        apply_state.config.congestion_control_config,
+                        apply_state.config.shard_congestion_control_config(shard_id),
                         congestion.congestion_info,
                         congestion.missed_chunks_count,
                     );
@@ -427,6 +427,21 @@ impl DelayedReceiptQueueWrapper {
         Ok(receipt)
     }
 
+    /// Put a receipt taken by `pop` back to the front of the queue.
+    pub(crate) fn push_front(
+        &mut self,
+        trie_update: &mut TrieUpdate,
+        receipt: &Receipt,
+        config: &RuntimeConfig,
+    ) -> Result<(), RuntimeError> {
+        let delayed_gas = receipt_congestion_gas(receipt, &config)?;
+        let delayed_bytes = receipt_size(receipt)? as u64;
+        self.new_delayed_gas = safe_add_gas(self.new_delayed_gas, delayed_gas)?;
+        self.new_delayed_bytes = safe_add_gas(self.new_delayed_bytes, delayed_bytes)?;
+        self.queue.push_front(trie_update, receipt)?;
+        Ok(())
+    }
+
     pub(crate) fn len(&self) -> u64 {
         self.queue.len()
     }
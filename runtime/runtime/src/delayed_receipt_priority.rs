@@ -0,0 +1,310 @@
+use crate::congestion_control::DelayedReceiptQueueWrapper;
+use near_parameters::RuntimeConfig;
+use near_primitives::errors::RuntimeError;
+use near_primitives::receipt::{Receipt, ReceiptEnum};
+use near_primitives::types::Balance;
+use near_store::TrieUpdate;
+
+/// Number of receipts at the front of the delayed receipt queue which are
+/// considered for priority ordering at once.
+pub(crate) const DELAYED_RECEIPT_PRIORITY_WINDOW: usize = 16;
+
+/// Takes receipts from the front of the delayed receipt queue ordered by the
+/// gas price attached to them, used with
+/// [`near_primitives::version::ProtocolFeature::DelayedReceiptPriority`].
+///
+/// Only the first [`DELAYED_RECEIPT_PRIORITY_WINDOW`] receipts of the queue
+/// are ordered by priority, which bounds the number of receipts read from the
+/// trie on top of the ones processed. To protect old receipts from starving,
+/// every second receipt taken is the oldest one, which means a receipt is
+/// processed after at most twice as many receipts as were ahead of it in the
+/// queue, no matter the gas price of the receipts delayed after it.
+///
+/// The receipts taken out of the queue are recorded in the state witness, so
+/// the window stops growing once the recorded storage reaches the proof size
+/// limit of the chunk, where the chunk stops processing receipts anyway.
+///
+/// The receipts taken out of the queue but not processed must be put back
+/// with [`DelayedReceiptPriorityWindow::put_back`].
+pub(crate) struct DelayedReceiptPriorityWindow {
+    /// Receipts popped from the queue, in queue order.
+    receipts: Vec<Receipt>,
+    take_oldest_next: bool,
+}
+
+impl DelayedReceiptPriorityWindow {
+    pub(crate) fn new() -> Self {
+        Self {
+            receipts: Vec::with_capacity(DELAYED_RECEIPT_PRIORITY_WINDOW),
+            take_oldest_next: false,
+        }
+    }
+
+    /// Takes the next receipt to process, or None if the queue is empty.
+    pub(crate) fn next(
+        &mut self,
+        queue: &mut DelayedReceiptQueueWrapper,
+        trie_update: &mut TrieUpdate,
+        config: &RuntimeConfig,
+        proof_size_limit: Option<usize>,
+    ) -> Result<Option<Receipt>, RuntimeError> {
+        while self.receipts.len() < DELAYED_RECEIPT_PRIORITY_WINDOW {
+            // The caller checks the limit before taking a receipt, so the
+            // first receipt is taken as without the window.
+            if !self.receipts.is_empty()
+                && proof_size_limit.is_some_and(|limit| {
+                    trie_update.trie.recorded_storage_size_upper_bound() > limit
+                })
+            {
+                break;
+            }
+            let Some(receipt) = queue.pop(trie_update, config)? else {
+                break;
+            };
+            self.receipts.push(receipt);
+        }
+        if self.receipts.is_empty() {
+            return Ok(None);
+        }
+        let index = if self.take_oldest_next { 0 } else { highest_priority_index(&self.receipts) };
+        self.take_oldest_next = !self.take_oldest_next;
+        Ok(Some(self.receipts.remove(index)))
+    }
+
+    /// Puts the receipts which were not taken back to the front of the queue,
+    /// in their original order.
+    pub(crate) fn put_back(
+        self,
+        queue: &mut DelayedReceiptQueueWrapper,
+        trie_update: &mut TrieUpdate,
+        config: &RuntimeConfig,
+    ) -> Result<(), RuntimeError> {
+        for receipt in self.receipts.iter().rev() {
+            queue.push_front(trie_update, receipt, config)?;
+        }
+        Ok(())
+    }
+}
+
+/// Index of the receipt with the highest gas price, the oldest one among
+/// receipts with the same gas price.
+fn highest_priority_index(receipts: &[Receipt]) -> usize {
+    let mut best = 0;
+    for (index, receipt) in receipts.iter().enumerate().skip(1) {
+        if receipt_gas_price(receipt) > receipt_gas_price(&receipts[best]) {
+            best = index;
+        }
+    }
+    best
+}
+
+fn receipt_gas_price(receipt: &Receipt) -> Balance {
+    match receipt.receipt() {
+        ReceiptEnum::Action(action_receipt) | ReceiptEnum::PromiseYield(action_receipt) => {
+            action_receipt.gas_price
+        }
+        ReceiptEnum::Data(_) | ReceiptEnum::PromiseResume(_) => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_crypto::{KeyType, PublicKey};
+    use near_primitives::hash::{hash, CryptoHash};
+    use near_primitives::receipt::{ActionReceipt, ReceiptV0};
+    use near_primitives::shard_layout::ShardUId;
+    use near_primitives::transaction::{Action, FunctionCallAction, TransferAction};
+    use near_primitives::types::StateChangeCause;
+    use near_store::test_utils::TestTriesBuilder;
+    use near_store::trie::receipts_column_helper::DelayedReceiptQueue;
+
+    fn receipt(id: u64, gas_price: Balance) -> Receipt {
+        Receipt::V0(ReceiptV0 {
+            predecessor_id: "alice.near".parse().unwrap(),
+            receiver_id: "bob.near".parse().unwrap(),
+            receipt_id: hash(&id.to_le_bytes()),
+            receipt: ReceiptEnum::Action(ActionReceipt {
+                signer_id: "alice.near".parse().unwrap(),
+                signer_public_key: PublicKey::empty(KeyType::ED25519),
+                gas_price,
+                output_data_receivers: vec![],
+                input_data_ids: vec![],
+                actions: vec![Action::Transfer(TransferAction { deposit: 1 })],
+            }),
+        })
+    }
+
+    fn large_receipt(id: u64, size: usize) -> Receipt {
+        let mut receipt = receipt(id, 1);
+        let ReceiptEnum::Action(action_receipt) = receipt.receipt_mut() else { unreachable!() };
+        action_receipt.actions = vec![Action::FunctionCall(Box::new(FunctionCallAction {
+            method_name: "main".to_string(),
+            args: vec![0; size],
+            gas: 0,
+            deposit: 0,
+        }))];
+        receipt
+    }
+
+    fn init_queue() -> (TrieUpdate, DelayedReceiptQueueWrapper) {
+        let tries = TestTriesBuilder::new().build();
+        let trie_update = tries.new_trie_update(ShardUId::single_shard(), CryptoHash::default());
+        let queue =
+            DelayedReceiptQueueWrapper::new(DelayedReceiptQueue::load(&trie_update).unwrap());
+        (trie_update, queue)
+    }
+
+    /// Applies the given number of blocks processing up to `per_block` delayed
+    /// receipts each, pushing the receipts returned by `arrivals` for every
+    /// block first. Returns the number of blocks every receipt waited for,
+    /// indexed by the id of the receipt.
+    fn simulate(
+        blocks: u64,
+        per_block: usize,
+        mut arrivals: impl FnMut(u64) -> Vec<Balance>,
+    ) -> Vec<(Balance, Option<u64>)> {
+        let config = RuntimeConfig::test();
+        let (mut trie_update, mut queue) = init_queue();
+        let mut receipts: Vec<(CryptoHash, Balance, u64, Option<u64>)> = vec![];
+        for block in 0..blocks {
+            for gas_price in arrivals(block) {
+                let receipt = receipt(receipts.len() as u64, gas_price);
+                queue.push(&mut trie_update, &receipt, &config).unwrap();
+                receipts.push((receipt.get_hash(), gas_price, block, None));
+            }
+            let mut window = DelayedReceiptPriorityWindow::new();
+            for _ in 0..per_block {
+                let Some(receipt) =
+                    window.next(&mut queue, &mut trie_update, &config, None).unwrap()
+                else {
+                    break;
+                };
+                let entry = receipts.iter_mut().find(|r| r.0 == receipt.get_hash()).unwrap();
+                assert!(entry.3.is_none(), "receipt processed twice");
+                entry.3 = Some(block - entry.2);
+            }
+            window.put_back(&mut queue, &mut trie_update, &config).unwrap();
+        }
+        let processed = receipts.iter().filter(|r| r.3.is_some()).count() as u64;
+        assert_eq!(queue.len() + processed, receipts.len() as u64, "receipts lost in the queue");
+        receipts.into_iter().map(|(_, gas_price, _, waited)| (gas_price, waited)).collect()
+    }
+
+    #[test]
+    fn test_alternates_priority_and_age() {
+        let config = RuntimeConfig::test();
+        let (mut trie_update, mut queue) = init_queue();
+        for (id, gas_price) in [1, 1, 5, 1, 7].into_iter().enumerate() {
+            queue.push(&mut trie_update, &receipt(id as u64, gas_price), &config).unwrap();
+        }
+        let mut window = DelayedReceiptPriorityWindow::new();
+        let mut taken = vec![];
+        while let Some(receipt) = window.next(&mut queue, &mut trie_update, &config, None).unwrap()
+        {
+            taken.push(receipt.get_hash());
+        }
+        let expected: Vec<_> =
+            [4, 0, 2, 1, 3].iter().map(|id| receipt(*id, 0).get_hash()).collect();
+        assert_eq!(taken, expected);
+    }
+
+    #[test]
+    fn test_put_back_keeps_queue_order() {
+        let config = RuntimeConfig::test();
+        let (mut trie_update, mut queue) = init_queue();
+        for id in 0..20 {
+            queue.push(&mut trie_update, &receipt(id, id as Balance), &config).unwrap();
+        }
+        let mut window = DelayedReceiptPriorityWindow::new();
+        // Takes the highest priority receipt in the window, #15.
+        window.next(&mut queue, &mut trie_update, &config, None).unwrap().unwrap();
+        window.put_back(&mut queue, &mut trie_update, &config).unwrap();
+        assert_eq!(queue.len(), 19);
+        let mut remaining = vec![];
+        while let Some(receipt) = queue.pop(&mut trie_update, &config).unwrap() {
+            remaining.push(receipt.get_hash());
+        }
+        let expected: Vec<_> =
+            (0..20).filter(|id| *id != 15).map(|id| receipt(id, 0).get_hash()).collect();
+        assert_eq!(remaining, expected);
+    }
+
+    /// The window doesn't take the receipts from the queue beyond the proof
+    /// size limit, so that they aren't recorded in the state witness.
+    #[test]
+    fn test_window_stops_at_proof_size_limit() {
+        let config = RuntimeConfig::test();
+        let tries = TestTriesBuilder::new().build();
+        let shard_uid = ShardUId::single_shard();
+        let mut trie_update = tries.new_trie_update(shard_uid, CryptoHash::default());
+        let mut queue =
+            DelayedReceiptQueueWrapper::new(DelayedReceiptQueue::load(&trie_update).unwrap());
+        let receipt_size = 100_000;
+        for id in 0..20 {
+            queue.push(&mut trie_update, &large_receipt(id, receipt_size), &config).unwrap();
+        }
+        trie_update.commit(StateChangeCause::UpdatedDelayedReceipts);
+        let trie_changes = trie_update.finalize().unwrap().1;
+        let mut store_update = tries.store_update();
+        let root = tries.apply_all(&trie_changes, shard_uid, &mut store_update);
+        store_update.commit().unwrap();
+
+        let mut trie_update =
+            TrieUpdate::new(tries.get_trie_for_shard(shard_uid, root).recording_reads());
+        let mut queue =
+            DelayedReceiptQueueWrapper::new(DelayedReceiptQueue::load(&trie_update).unwrap());
+        let proof_size_limit = 5 * receipt_size / 2;
+        let mut window = DelayedReceiptPriorityWindow::new();
+        window
+            .next(&mut queue, &mut trie_update, &config, Some(proof_size_limit))
+            .unwrap()
+            .unwrap();
+        // The third receipt takes the recorded storage above the limit.
+        assert_eq!(window.receipts.len() + 1, 3);
+        assert!(trie_update.trie.recorded_storage_size_upper_bound() > proof_size_limit);
+        // Once above the limit, the window only hands out the receipts it has.
+        assert!(window
+            .next(&mut queue, &mut trie_update, &config, Some(proof_size_limit))
+            .unwrap()
+            .is_some());
+        assert_eq!(window.receipts.len(), 1);
+        window.put_back(&mut queue, &mut trie_update, &config).unwrap();
+        assert_eq!(queue.len(), 18);
+    }
+
+    /// Expensive receipts delayed behind a burst of cheap ones are processed
+    /// before the burst, as long as they are within the priority window.
+    #[test]
+    fn test_spam_burst_fairness() {
+        let results = simulate(10, 4, |block| match block {
+            0 => [vec![1; 10], vec![10; 2]].concat(),
+            _ => vec![],
+        });
+        // In FIFO order, the expensive receipts would wait for two blocks.
+        assert!(results.iter().filter(|r| r.0 == 10).all(|r| r.1 == Some(0)));
+        // The cheap receipts are still processed.
+        let max_cheap_wait = results.iter().filter(|r| r.0 == 1).map(|r| r.1.unwrap()).max();
+        assert_eq!(max_cheap_wait, Some(2));
+    }
+
+    /// Sustained load of expensive receipts doesn't starve older cheap ones.
+    #[test]
+    fn test_no_starvation() {
+        let per_block = 10;
+        // Newer receipts always have a higher gas price than older ones.
+        let results = simulate(200, per_block, |block| {
+            let mut arrivals = vec![100 + block as Balance; per_block];
+            if block == 0 {
+                arrivals.extend([1; 5]);
+            }
+            arrivals
+        });
+        let cheap: Vec<_> = results.iter().filter(|r| r.0 == 1).collect();
+        assert!(cheap.iter().all(|r| r.1.is_some()), "cheap receipts starved");
+        // At most twice as many receipts as were ahead of it are processed before a receipt.
+        let ahead = per_block + 5;
+        let bound = (2 * ahead / per_block) as u64;
+        assert!(cheap.iter().all(|r| r.1.unwrap() <= bound));
+    }
+}
@@ -5,8 +5,11 @@ use crate::config::{
     total_prepaid_exec_fees, total_prepaid_gas,
 };
 use crate::congestion_control::DelayedReceiptQueueWrapper;
+use crate::delayed_receipt_priority::DelayedReceiptPriorityWindow;
 use crate::prefetch::TriePrefetcher;
-use crate::verifier::{check_storage_stake, validate_receipt, StorageStakingError};
+use crate::verifier::{
+    check_storage_stake, validate_receipt, StorageStakingError, ValidateReceiptMode,
+};
 pub use crate::verifier::{
     validate_transaction, verify_and_charge_transaction, ZERO_BALANCE_ACCOUNT_STORAGE_LIMIT,
 };
@@ -20,15 +23,17 @@ use near_primitives::account::Account;
 use near_primitives::checked_feature;
 use near_primitives::congestion_info::{CongestionInfo, ExtendedCongestionInfo};
 use near_primitives::errors::{
-    ActionError, ActionErrorKind, IntegerOverflowError, RuntimeError, TxExecutionError,
+    ActionError, ActionErrorKind, IntegerOverflowError, ReceiptValidationError, RuntimeError,
+    TxExecutionError,
 };
 use near_primitives::hash::CryptoHash;
 use near_primitives::receipt::{
     ActionReceipt, DataReceipt, DelayedReceiptIndices, PromiseYieldIndices, PromiseYieldTimeout,
-    Receipt, ReceiptEnum, ReceiptV0, ReceivedData,
+    Receipt, ReceiptEnum, ReceiptPriority, ReceiptV0, ReceivedData,
 };
 use near_primitives::runtime::migration_data::{MigrationData, MigrationFlags};
 use near_primitives::sandbox::state_patch::SandboxStatePatch;
+use near_primitives::sandbox::AccessKeyPatch;
 use near_primitives::state_record::StateRecord;
 #[cfg(feature = "protocol_feature_nonrefundable_transfer_nep491")]
 use near_primitives::transaction::NonrefundableStorageTransferAction;
@@ -43,17 +48,18 @@ use near_primitives::types::{
     StateRoot,
 };
 use near_primitives::utils::{
-    create_action_hash_from_receipt_id, create_receipt_id_from_receipt_id,
-    create_receipt_id_from_transaction,
+    create_action_hash_from_receipt_id, create_priority_fee_receipt_id_from_transaction,
+    create_receipt_id_from_receipt_id, create_receipt_id_from_transaction,
 };
 use near_primitives::version::{ProtocolFeature, ProtocolVersion};
 use near_primitives_core::apply::ApplyChunkReason;
 use near_store::trie::receipts_column_helper::DelayedReceiptQueue;
 use near_store::{
-    get, get_account, get_postponed_receipt, get_promise_yield_receipt, get_received_data,
-    has_received_data, remove_postponed_receipt, remove_promise_yield_receipt, set, set_access_key,
-    set_account, set_code, set_postponed_receipt, set_promise_yield_receipt, set_received_data,
-    PartialStorage, StorageError, Trie, TrieAccess, TrieChanges, TrieUpdate,
+    get, get_access_key, get_account, get_postponed_receipt, get_promise_yield_receipt,
+    get_received_data, has_received_data, remove_access_key, remove_postponed_receipt,
+    remove_promise_yield_receipt, set, set_access_key, set_account, set_code,
+    set_postponed_receipt, set_promise_yield_receipt, set_received_data, PartialStorage,
+    StorageError, Trie, TrieAccess, TrieChanges, TrieUpdate,
 };
 use near_vm_runner::logic::types::PromiseResult;
 use near_vm_runner::logic::ReturnData;
@@ -72,10 +78,12 @@ mod balance_checker;
 pub mod config;
 mod congestion_control;
 mod conversions;
+mod delayed_receipt_priority;
 pub mod ext;
 mod metrics;
 mod prefetch;
 pub mod receipt_manager;
+mod simulation;
 pub mod state_viewer;
 mod verifier;
 
@@ -154,6 +162,19 @@ pub struct VerificationResult {
     pub receipt_gas_price: Balance,
     /// The balance that was burnt to convert the transaction into a receipt and send it.
     pub burnt_amount: Balance,
+    /// The priority fee of the transaction, paid to the chunk producer.
+    pub priority_fee_amount: Balance,
+}
+
+/// Balances which were not refunded when generating the refunds of a receipt.
+#[derive(Debug, Default)]
+struct GasRefundResult {
+    /// The part of the gas burnt at the current gas price which could not be
+    /// covered by the refund, see [`ApplyStats::gas_deficit_amount`].
+    gas_deficit_amount: Balance,
+    /// The part of the gas refund that is burnt instead of refunded, see
+    /// [`ProtocolFeature::GasRefundPenalty`].
+    refund_penalty_amount: Balance,
 }
 
 #[derive(Debug, Default)]
@@ -272,7 +293,8 @@ impl Runtime {
     /// outgoing receipts.
     /// When transaction is converted to a receipt, the account is charged for the full value of
     /// the generated receipt.
-    /// In case of successful verification (expected for valid chunks), returns the receipt and
+    /// In case of successful verification (expected for valid chunks), returns the receipt, the
+    /// receipt paying the priority fee of the transaction to the chunk producer if it has one, and
     /// `ExecutionOutcomeWithId` for the transaction.
     /// In case of an error, returns either `InvalidTxError` if the transaction verification failed
     /// or a `StorageError` wrapped into `RuntimeError`.
@@ -286,8 +308,10 @@ impl Runtime {
         state_update: &mut TrieUpdate,
         apply_state: &ApplyState,
         signed_transaction: &SignedTransaction,
+        verify_signature: bool,
         stats: &mut ApplyStats,
-    ) -> Result<(Receipt, ExecutionOutcomeWithId), RuntimeError> {
+        epoch_info_provider: &dyn EpochInfoProvider,
+    ) -> Result<(Receipt, Option<Receipt>, ExecutionOutcomeWithId), RuntimeError> {
         let span = tracing::Span::current();
         metrics::TRANSACTION_PROCESSED_TOTAL.inc();
 
@@ -296,7 +320,7 @@ impl Runtime {
             state_update,
             apply_state.gas_price,
             signed_transaction,
-            true,
+            verify_signature,
             Some(apply_state.block_height),
             apply_state.current_protocol_version,
         ) {
@@ -325,6 +349,31 @@ impl Runtime {
                         actions: transaction.actions().to_vec(),
                     }),
                 });
+                let mut receipt_ids = vec![*receipt.receipt_id()];
+                let priority_fee_receipt = if verification_result.priority_fee_amount > 0 {
+                    let chunk_producer = epoch_info_provider.chunk_producer(
+                        &apply_state.epoch_id,
+                        apply_state.block_height,
+                        apply_state.shard_id,
+                    )?;
+                    let mut priority_fee_receipt = Receipt::new_balance_refund(
+                        &chunk_producer,
+                        verification_result.priority_fee_amount,
+                        ReceiptPriority::NoPriority,
+                    );
+                    priority_fee_receipt.set_receipt_id(
+                        create_priority_fee_receipt_id_from_transaction(
+                            apply_state.current_protocol_version,
+                            signed_transaction,
+                            &apply_state.prev_block_hash,
+                            &apply_state.block_hash,
+                        ),
+                    );
+                    receipt_ids.push(*priority_fee_receipt.receipt_id());
+                    Some(priority_fee_receipt)
+                } else {
+                    None
+                };
                 stats.tx_burnt_amount =
                     safe_add_balance(stats.tx_burnt_amount, verification_result.burnt_amount)?;
                 let gas_burnt = verification_result.gas_burnt;
@@ -334,7 +383,7 @@ impl Runtime {
                     outcome: ExecutionOutcome {
                         status: ExecutionStatus::SuccessReceiptId(*receipt.receipt_id()),
                         logs: vec![],
-                        receipt_ids: vec![*receipt.receipt_id()],
+                        receipt_ids,
                         gas_burnt,
                         // TODO(#8806): Support compute costs for actions. For now they match burnt gas.
                         compute_usage: Some(compute_usage),
@@ -347,7 +396,7 @@ impl Runtime {
                 };
                 span.record("gas_burnt", gas_burnt);
                 span.record("compute_usage", compute_usage);
-                Ok((receipt, outcome))
+                Ok((receipt, priority_fee_receipt, outcome))
             }
             Err(e) => {
                 metrics::TRANSACTION_PROCESSED_FAILED_TOTAL.inc();
@@ -531,6 +580,7 @@ impl Runtime {
                     &mut result,
                     receipt.priority(),
                 )?;
+                metrics::report_delegate_action(&result);
             }
         };
         Ok(result)
@@ -627,8 +677,14 @@ impl Runtime {
                         &apply_state.config.wasm_config.limit_config,
                         receipt,
                         apply_state.current_protocol_version,
+                        ValidateReceiptMode::NewReceipt,
                     )
                 }) {
+                    if let ReceiptValidationError::ReceiptSizeExceeded { .. } = e {
+                        metrics::RECEIPT_SIZE_EXCEEDED_TOTAL
+                            .with_label_values(&[&apply_state.shard_id.to_string()])
+                            .inc();
+                    }
                     new_result.result = Err(ActionErrorKind::NewReceiptValidationError(e).into());
                 }
             }
@@ -680,7 +736,17 @@ impl Runtime {
             }
         }
 
-        let gas_deficit_amount = if receipt.predecessor_id().is_system() {
+        // The price the gas of the receipt is burnt at. Without the pessimistic gas price, the
+        // gas is burnt at the price it was bought at, so that the difference with the current gas
+        // price is settled without a refund or a deficit.
+        let gas_price = if ProtocolFeature::NoPessimisticGasPrice
+            .enabled(apply_state.current_protocol_version)
+        {
+            action_receipt.gas_price
+        } else {
+            apply_state.gas_price
+        };
+        let refund_result = if receipt.predecessor_id().is_system() {
             // We will set gas_burnt for refund receipts to be 0 when we calculate tx_burnt_amount
             // Here we don't set result.gas_burnt to be zero if CountRefundReceiptsInGasLimit is
             // enabled because we want it to be counted in gas limit calculation later
@@ -701,17 +767,19 @@ impl Runtime {
                     total_deposit(&action_receipt.actions)?,
                 )?
             }
-            0
+            GasRefundResult::default()
         } else {
             // Calculating and generating refunds
             self.generate_refund_receipts(
-                apply_state.gas_price,
+                gas_price,
                 receipt,
                 action_receipt,
                 &mut result,
                 &apply_state.config,
+                apply_state.current_protocol_version,
             )?
         };
+        let gas_deficit_amount = refund_result.gas_deficit_amount;
         stats.gas_deficit_amount = safe_add_balance(stats.gas_deficit_amount, gas_deficit_amount)?;
 
         // Moving validator proposals
@@ -739,8 +807,9 @@ impl Runtime {
         let gas_burnt: Gas =
             if receipt.predecessor_id().is_system() { 0 } else { result.gas_burnt };
         // `gas_deficit_amount` is strictly less than `gas_price * gas_burnt`.
-        let mut tx_burnt_amount =
-            safe_gas_to_balance(apply_state.gas_price, gas_burnt)? - gas_deficit_amount;
+        let mut tx_burnt_amount = safe_gas_to_balance(gas_price, gas_burnt)? - gas_deficit_amount;
+        // The refund penalty is burnt together with the gas.
+        tx_burnt_amount = safe_add_balance(tx_burnt_amount, refund_result.refund_penalty_amount)?;
         // The amount of tokens burnt for the execution of this receipt. It's used in the execution
         // outcome.
         let tokens_burnt = tx_burnt_amount;
@@ -751,8 +820,8 @@ impl Runtime {
             / *apply_state.config.fees.burnt_gas_reward.denom() as u64;
         // The balance that the current account should receive as a reward for function call
         // execution.
-        let receiver_reward = safe_gas_to_balance(apply_state.gas_price, receiver_gas_reward)?
-            .saturating_sub(gas_deficit_amount);
+        let receiver_reward =
+            safe_gas_to_balance(gas_price, receiver_gas_reward)?.saturating_sub(gas_deficit_amount);
         if receiver_reward > 0 {
             let mut account = get_account(state_update, account_id)?;
             if let Some(ref mut account) = account {
@@ -888,7 +957,8 @@ impl Runtime {
         action_receipt: &ActionReceipt,
         result: &mut ActionResult,
         config: &RuntimeConfig,
-    ) -> Result<Balance, RuntimeError> {
+        protocol_version: ProtocolVersion,
+    ) -> Result<GasRefundResult, RuntimeError> {
         let total_deposit = total_deposit(&action_receipt.actions)?;
         let prepaid_gas = safe_add_gas(
             total_prepaid_gas(&action_receipt.actions)?,
@@ -934,6 +1004,15 @@ impl Runtime {
             )?;
         }
 
+        // Instead of refunding it, some of the gas refund is burnt as a penalty, all of it if the
+        // remaining refund would be small.
+        let mut refund_penalty_amount = 0;
+        if ProtocolFeature::GasRefundPenalty.enabled(protocol_version) {
+            refund_penalty_amount =
+                config.fees.gas_refund_penalty_amount(gas_balance_refund, action_receipt.gas_price);
+            gas_balance_refund -= refund_penalty_amount;
+        }
+
         if deposit_refund > 0 {
             result.new_receipts.push(Receipt::new_balance_refund(
                 receipt.predecessor_id(),
@@ -951,7 +1030,7 @@ impl Runtime {
                 receipt.priority(),
             ));
         }
-        Ok(gas_deficit_amount)
+        Ok(GasRefundResult { gas_deficit_amount, refund_penalty_amount })
     }
 
     fn process_receipt(
@@ -1428,6 +1507,7 @@ impl Runtime {
         let mut outcomes = vec![];
         let mut processed_delayed_receipts = vec![];
         let mut metrics = metrics::ApplyMetrics::default();
+        let receipt_metrics = metrics::ReceiptMetrics::new(apply_state.shard_id);
 
         let mut receipt_sink = ReceiptSink::new(
             protocol_version,
@@ -1442,12 +1522,20 @@ impl Runtime {
 
         total.add(gas_used_for_migrations, gas_used_for_migrations)?;
 
+        let span = tracing::debug_span!(
+            target: "runtime",
+            "process_transactions",
+            num_transactions = transactions.len(),
+        )
+        .entered();
         for signed_transaction in transactions {
-            let (receipt, outcome_with_id) = self.process_transaction(
+            let (receipt, priority_fee_receipt, outcome_with_id) = self.process_transaction(
                 &mut state_update,
                 apply_state,
                 signed_transaction,
+                true,
                 &mut stats,
+                epoch_info_provider,
             )?;
             if receipt.receiver_id() == signed_transaction.transaction.signer_id() {
                 local_receipts.push(receipt);
@@ -1459,6 +1547,16 @@ impl Runtime {
                     epoch_info_provider,
                 )?;
             }
+            // Never processed locally, even on the shard of the chunk producer, so that the
+            // balance checker sees the fee leave the shard with the receipt.
+            if let Some(priority_fee_receipt) = priority_fee_receipt {
+                receipt_sink.forward_or_buffer_receipt(
+                    priority_fee_receipt,
+                    apply_state,
+                    &mut state_update,
+                    epoch_info_provider,
+                )?;
+            }
 
             total.add(
                 outcome_with_id.outcome.gas_burnt,
@@ -1473,21 +1571,26 @@ impl Runtime {
 
             outcomes.push(outcome_with_id);
         }
+        drop(span);
         metrics.tx_processing_done(total.gas, total.compute);
 
         let mut process_receipt = |receipt: &Receipt,
                                    state_update: &mut TrieUpdate,
                                    total: &mut TotalResourceGuard|
          -> Result<_, RuntimeError> {
-            let span = tracing::debug_span!(
-                target: "runtime",
-                "process_receipt",
-                receipt_id = %receipt.receipt_id(),
-                predecessor = %receipt.predecessor_id(),
-                receiver = %receipt.receiver_id(),
-                gas_burnt = tracing::field::Empty,
-                compute_usage = tracing::field::Empty,
-            )
+            let span = if near_o11y::sample_receipt_span(receipt.receipt_id().as_bytes()) {
+                tracing::debug_span!(
+                    target: "runtime",
+                    "process_receipt",
+                    receipt_id = %receipt.receipt_id(),
+                    predecessor = %receipt.predecessor_id(),
+                    receiver = %receipt.receiver_id(),
+                    gas_burnt = tracing::field::Empty,
+                    compute_usage = tracing::field::Empty,
+                )
+            } else {
+                tracing::Span::none()
+            }
             .entered();
             let node_counter_before = state_update.trie().get_trie_nodes_count();
             let recorded_storage_size_before = state_update.trie().recorded_storage_size();
@@ -1504,6 +1607,9 @@ impl Runtime {
             );
             let node_counter_after = state_update.trie().get_trie_nodes_count();
             tracing::trace!(target: "runtime", ?node_counter_before, ?node_counter_after);
+            let touched_trie_nodes = node_counter_after
+                .checked_sub(&node_counter_before)
+                .map_or(0, |diff| diff.db_reads + diff.mem_reads);
 
             let recorded_storage_diff = state_update
                 .trie()
@@ -1534,6 +1640,7 @@ impl Runtime {
                 total.add(gas_burnt, compute_usage)?;
                 span.record("gas_burnt", gas_burnt);
                 span.record("compute_usage", compute_usage);
+                receipt_metrics.observe(gas_burnt, compute_usage, touched_trie_nodes);
 
                 if !checked_feature!("stable", ComputeCosts, protocol_version) {
                     assert_eq!(total.compute, total.gas, "Compute usage must match burnt gas");
@@ -1555,6 +1662,12 @@ impl Runtime {
 
         // We first process local receipts. They contain staking, local contract calls, etc.
         let local_processing_start = std::time::Instant::now();
+        let span = tracing::debug_span!(
+            target: "runtime",
+            "process_local_receipts",
+            num_receipts = local_receipts.len(),
+        )
+        .entered();
         if let Some(prefetcher) = &mut prefetcher {
             // Prefetcher is allowed to fail
             _ = prefetcher.prefetch_receipts_data(&local_receipts);
@@ -1572,6 +1685,7 @@ impl Runtime {
                 process_receipt(receipt, &mut state_update, &mut total)?;
             }
         }
+        drop(span);
         metrics.local_receipts_done(
             local_receipts.len() as u64,
             local_processing_start.elapsed(),
@@ -1582,7 +1696,16 @@ impl Runtime {
         // Then we process the delayed receipts. It's a backlog of receipts from the past blocks.
         let delayed_processing_start = std::time::Instant::now();
         let mut delayed_receipt_count = 0;
-        while delayed_receipts.len() > 0 {
+        let span = tracing::debug_span!(
+            target: "runtime",
+            "process_delayed_receipts",
+            num_receipts = tracing::field::Empty,
+        )
+        .entered();
+        let mut priority_window = ProtocolFeature::DelayedReceiptPriority
+            .enabled(protocol_version)
+            .then(DelayedReceiptPriorityWindow::new);
+        loop {
             if total.compute >= compute_limit
                 || proof_size_limit.is_some_and(|limit| {
                     state_update.trie.recorded_storage_size_upper_bound() > limit
@@ -1590,10 +1713,19 @@ impl Runtime {
             {
                 break;
             }
+            let receipt = match &mut priority_window {
+                Some(window) => window.next(
+                    &mut delayed_receipts,
+                    &mut state_update,
+                    &apply_state.config,
+                    proof_size_limit,
+                )?,
+                None => delayed_receipts.pop(&mut state_update, &apply_state.config)?,
+            };
+            let Some(receipt) = receipt else {
+                break;
+            };
             delayed_receipt_count += 1;
-            let receipt = delayed_receipts
-                .pop(&mut state_update, &apply_state.config)?
-                .expect("queue is not empty");
 
             if let Some(prefetcher) = &mut prefetcher {
                 // Prefetcher is allowed to fail
@@ -1605,6 +1737,7 @@ impl Runtime {
                 &apply_state.config.wasm_config.limit_config,
                 &receipt,
                 protocol_version,
+                ValidateReceiptMode::ExistingReceipt,
             )
             .map_err(|e| {
                 StorageError::StorageInconsistentState(format!(
@@ -1616,6 +1749,11 @@ impl Runtime {
             process_receipt(&receipt, &mut state_update, &mut total)?;
             processed_delayed_receipts.push(receipt);
         }
+        if let Some(window) = priority_window {
+            window.put_back(&mut delayed_receipts, &mut state_update, &apply_state.config)?;
+        }
+        span.record("num_receipts", delayed_receipt_count);
+        drop(span);
         metrics.delayed_receipts_done(
             delayed_receipt_count,
             delayed_processing_start.elapsed(),
@@ -1625,6 +1763,12 @@ impl Runtime {
 
         // And then we process the new incoming receipts. These are receipts from other shards.
         let incoming_processing_start = std::time::Instant::now();
+        let span = tracing::debug_span!(
+            target: "runtime",
+            "process_incoming_receipts",
+            num_receipts = incoming_receipts.len(),
+        )
+        .entered();
         if let Some(prefetcher) = &mut prefetcher {
             // Prefetcher is allowed to fail
             _ = prefetcher.prefetch_receipts_data(&incoming_receipts);
@@ -1636,6 +1780,7 @@ impl Runtime {
                 &apply_state.config.wasm_config.limit_config,
                 receipt,
                 protocol_version,
+                ValidateReceiptMode::ExistingReceipt,
             )
             .map_err(RuntimeError::ReceiptValidationError)?;
             if total.compute >= compute_limit
@@ -1648,6 +1793,7 @@ impl Runtime {
                 process_receipt(receipt, &mut state_update, &mut total)?;
             }
         }
+        drop(span);
         metrics.incoming_receipts_done(
             incoming_receipts.len() as u64,
             incoming_processing_start.elapsed(),
@@ -1745,7 +1891,7 @@ impl Runtime {
         metrics::report_congestion_metrics(
             &receipt_sink,
             apply_state.shard_id,
-            &apply_state.config.congestion_control_config,
+            &apply_state.config.shard_congestion_control_config(apply_state.shard_id),
         );
 
         let _span = tracing::debug_span!(target: "runtime", "apply_commit").entered();
@@ -1772,7 +1918,7 @@ impl Runtime {
                 apply_state.shard_id,
                 &other_shards,
                 congestion_seed,
-                &apply_state.config.congestion_control_config,
+                &apply_state.config.shard_congestion_control_config(apply_state.shard_id),
             );
         }
 
@@ -1782,10 +1928,12 @@ impl Runtime {
             validator_accounts_update,
             incoming_receipts,
             &timeout_receipts,
+            &processed_delayed_receipts,
             transactions,
             &outgoing_receipts,
             &stats,
         )?;
+        metrics::report_outgoing_receipt_sizes(apply_state.shard_id, &outgoing_receipts);
 
         state_update.commit(StateChangeCause::UpdatedDelayedReceipts);
         self.apply_state_patch(&mut state_update, state_patch);
@@ -1847,7 +1995,8 @@ impl Runtime {
         if state_patch.is_empty() {
             return;
         }
-        for record in state_patch {
+        let (records, access_key_patches) = state_patch.into_parts();
+        for record in records {
             match record {
                 StateRecord::Account { account_id, account } => {
                     set_account(state_update, account_id, &account);
@@ -1868,6 +2017,23 @@ impl Runtime {
                 _ => unimplemented!("patch_state can only patch Account, AccessKey, Contract and Data kind of StateRecord")
             }
         }
+        for access_key_patch in access_key_patches {
+            match access_key_patch {
+                AccessKeyPatch::Delete { account_id, public_key } => {
+                    remove_access_key(state_update, account_id, public_key);
+                }
+                AccessKeyPatch::SetNonce { account_id, public_key, nonce } => {
+                    let access_key = get_access_key(state_update, &account_id, &public_key)
+                        .expect("Failed to read state");
+                    let Some(mut access_key) = access_key else {
+                        tracing::warn!(target: "runtime", %account_id, %public_key, "patch_state: access key to set the nonce of does not exist");
+                        continue;
+                    };
+                    access_key.nonce = nonce;
+                    set_access_key(state_update, account_id, public_key, &access_key);
+                }
+            }
+        }
         state_update.commit(StateChangeCause::Migration);
     }
 }
@@ -2708,14 +2874,24 @@ mod tests {
         );
     }
 
+    /// Applies the receipts at a protocol version with the pessimistic gas
+    /// price, at which the gas is burnt at the current gas price.
+    fn with_pessimistic_gas_price(apply_state: &mut ApplyState) {
+        apply_state.current_protocol_version = std::cmp::min(
+            apply_state.current_protocol_version,
+            ProtocolFeature::NoPessimisticGasPrice.protocol_version() - 1,
+        );
+    }
+
     #[test]
     fn test_apply_deficit_gas_for_transfer() {
         let initial_balance = to_yocto(1_000_000);
         let initial_locked = to_yocto(500_000);
         let small_transfer = to_yocto(10_000);
         let gas_limit = 10u64.pow(15);
-        let (runtime, tries, root, apply_state, _, epoch_info_provider) =
+        let (runtime, tries, root, mut apply_state, _, epoch_info_provider) =
             setup_runtime(initial_balance, initial_locked, gas_limit);
+        with_pessimistic_gas_price(&mut apply_state);
 
         let n = 1;
         let mut receipts = generate_receipts(small_transfer, n);
@@ -2742,8 +2918,9 @@ mod tests {
         let initial_balance = to_yocto(1_000_000);
         let initial_locked = to_yocto(500_000);
         let gas_limit = 10u64.pow(15);
-        let (runtime, tries, root, apply_state, _, epoch_info_provider) =
+        let (runtime, tries, root, mut apply_state, _, epoch_info_provider) =
             setup_runtime(initial_balance, initial_locked, gas_limit);
+        with_pessimistic_gas_price(&mut apply_state);
 
         let gas = 2 * 10u64.pow(14);
         let gas_price = GAS_PRICE / 10;
@@ -2800,13 +2977,118 @@ mod tests {
         };
     }
 
+    /// Applies a failing function call receipt with the given gas refund
+    /// penalty and returns the result together with the attached gas.
+    #[cfg(feature = "nightly")]
+    fn apply_with_gas_refund_penalty(
+        gas_refund_penalty: (i32, i32),
+        min_gas_refund: Gas,
+    ) -> (ApplyResult, Gas) {
+        let (runtime, tries, root, mut apply_state, _, epoch_info_provider) =
+            setup_runtime(to_yocto(1_000_000), to_yocto(500_000), 10u64.pow(15));
+        let mut config = RuntimeConfig::test();
+        config.fees.gas_refund_penalty = gas_refund_penalty.into();
+        config.fees.min_gas_refund = min_gas_refund;
+        apply_state.config = Arc::new(config);
+
+        let gas = 2 * 10u64.pow(14);
+        let receipts = vec![Receipt::V0(ReceiptV0 {
+            predecessor_id: bob_account(),
+            receiver_id: alice_account(),
+            receipt_id: CryptoHash::default(),
+            receipt: ReceiptEnum::Action(ActionReceipt {
+                signer_id: bob_account(),
+                signer_public_key: PublicKey::empty(KeyType::ED25519),
+                gas_price: GAS_PRICE,
+                output_data_receivers: vec![],
+                input_data_ids: vec![],
+                actions: vec![Action::FunctionCall(Box::new(FunctionCallAction {
+                    method_name: "hello".to_string(),
+                    args: b"world".to_vec(),
+                    gas,
+                    deposit: 0,
+                }))],
+            }),
+        })];
+        let result = runtime
+            .apply(
+                tries.get_trie_for_shard(ShardUId::single_shard(), root),
+                &None,
+                &apply_state,
+                &receipts,
+                &[],
+                &epoch_info_provider,
+                Default::default(),
+            )
+            .unwrap();
+        (result, gas)
+    }
+
+    #[cfg(feature = "nightly")]
+    fn gas_refunds(result: &ApplyResult) -> Vec<Balance> {
+        result
+            .outgoing_receipts
+            .iter()
+            .map(|receipt| match receipt.receipt() {
+                ReceiptEnum::Action(ActionReceipt { actions, .. }) => match actions[..] {
+                    [Action::Transfer(TransferAction { deposit })] => deposit,
+                    _ => panic!("unexpected refund actions {actions:?}"),
+                },
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    #[cfg(feature = "nightly")]
+    fn test_gas_refund_penalty() {
+        let (no_penalty, gas) = apply_with_gas_refund_penalty((0, 1), 0);
+        // The function call fails, so all attached gas is refunded.
+        let full_refund = Balance::from(gas) * GAS_PRICE;
+        assert_eq!(gas_refunds(&no_penalty), vec![full_refund]);
+
+        let (result, _) = apply_with_gas_refund_penalty((1, 10), 0);
+        let penalty = full_refund / 10;
+        assert_eq!(gas_refunds(&result), vec![full_refund - penalty]);
+        // The penalty is burnt, which also keeps the balance checker happy.
+        assert_eq!(result.stats.tx_burnt_amount, no_penalty.stats.tx_burnt_amount + penalty);
+        assert_eq!(
+            result.outcomes[0].outcome.tokens_burnt,
+            no_penalty.outcomes[0].outcome.tokens_burnt + penalty
+        );
+        assert_eq!(result.outcomes[0].outcome.gas_burnt, no_penalty.outcomes[0].outcome.gas_burnt);
+        assert_eq!(result.stats.gas_deficit_amount, 0);
+    }
+
+    #[test]
+    #[cfg(feature = "nightly")]
+    fn test_small_gas_refund_burnt() {
+        let (no_penalty, gas) = apply_with_gas_refund_penalty((0, 1), 0);
+        let full_refund = Balance::from(gas) * GAS_PRICE;
+
+        // Refunds of exactly the threshold are still made.
+        let (result, _) = apply_with_gas_refund_penalty((0, 1), gas);
+        assert_eq!(gas_refunds(&result), vec![full_refund]);
+
+        // Smaller ones are burnt without creating a refund receipt.
+        let (result, _) = apply_with_gas_refund_penalty((0, 1), gas + 1);
+        assert_eq!(gas_refunds(&result), Vec::<Balance>::new());
+        assert_eq!(result.stats.tx_burnt_amount, no_penalty.stats.tx_burnt_amount + full_refund);
+
+        // The threshold applies to the refund after the penalty.
+        let (result, _) = apply_with_gas_refund_penalty((1, 10), gas);
+        assert_eq!(gas_refunds(&result), Vec::<Balance>::new());
+        assert_eq!(result.stats.tx_burnt_amount, no_penalty.stats.tx_burnt_amount + full_refund);
+    }
+
     #[test]
     fn test_apply_deficit_gas_for_function_call_partial() {
         let initial_balance = to_yocto(1_000_000);
         let initial_locked = to_yocto(500_000);
         let gas_limit = 10u64.pow(15);
-        let (runtime, tries, root, apply_state, _, epoch_info_provider) =
+        let (runtime, tries, root, mut apply_state, _, epoch_info_provider) =
             setup_runtime(initial_balance, initial_locked, gas_limit);
+        with_pessimistic_gas_price(&mut apply_state);
 
         let gas = 1_000_000;
         let gas_price = GAS_PRICE / 10;
@@ -2856,6 +3138,145 @@ mod tests {
         assert_eq!(result.stats.tx_burnt_amount, total_receipt_cost);
     }
 
+    /// Applies a transfer receipt whose gas was bought at the given gas price,
+    /// checking the balances as every apply does.
+    #[cfg(feature = "nightly")]
+    fn apply_transfer_bought_at(gas_price: Balance) -> ApplyResult {
+        let (runtime, tries, root, apply_state, _, epoch_info_provider) =
+            setup_runtime(to_yocto(1_000_000), to_yocto(500_000), 10u64.pow(15));
+        assert!(
+            ProtocolFeature::NoPessimisticGasPrice.enabled(apply_state.current_protocol_version)
+        );
+        let mut receipts = generate_receipts(to_yocto(10_000), 1);
+        if let ReceiptEnum::Action(action_receipt) = receipts[0].receipt_mut() {
+            action_receipt.gas_price = gas_price;
+        }
+        runtime
+            .apply(
+                tries.get_trie_for_shard(ShardUId::single_shard(), root),
+                &None,
+                &apply_state,
+                &receipts,
+                &[],
+                &epoch_info_provider,
+                Default::default(),
+            )
+            .unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "nightly")]
+    fn test_no_pessimistic_gas_price() {
+        let result = apply_transfer_bought_at(GAS_PRICE);
+        let gas_burnt = result.outcomes[0].outcome.gas_burnt;
+        assert_eq!(result.stats.tx_burnt_amount, Balance::from(gas_burnt) * GAS_PRICE);
+        assert!(result.outgoing_receipts.is_empty());
+
+        // Bought below the current gas price, the gas is still burnt at the
+        // purchase price, with no deficit.
+        let result = apply_transfer_bought_at(GAS_PRICE / 10);
+        assert_eq!(result.outcomes[0].outcome.gas_burnt, gas_burnt);
+        assert_eq!(result.stats.tx_burnt_amount, Balance::from(gas_burnt) * GAS_PRICE / 10);
+        assert_eq!(
+            result.outcomes[0].outcome.tokens_burnt,
+            Balance::from(gas_burnt) * GAS_PRICE / 10
+        );
+        assert_eq!(result.stats.gas_deficit_amount, 0);
+        assert!(result.outgoing_receipts.is_empty());
+
+        // Bought above it, the difference is burnt rather than refunded.
+        let result = apply_transfer_bought_at(GAS_PRICE * 2);
+        assert_eq!(result.stats.tx_burnt_amount, Balance::from(gas_burnt) * GAS_PRICE * 2);
+        assert_eq!(result.stats.gas_deficit_amount, 0);
+        assert!(result.outgoing_receipts.is_empty());
+    }
+
+    /// The transactions buy the gas of their receipts at the current gas price,
+    /// which the receipts spend without a refund.
+    #[test]
+    #[cfg(feature = "nightly")]
+    fn test_no_pessimistic_gas_price_transaction() {
+        let (runtime, tries, root, apply_state, signer, epoch_info_provider) =
+            setup_runtime(to_yocto(1_000_000), to_yocto(500_000), 10u64.pow(15));
+        let transaction = SignedTransaction::send_money(
+            1,
+            alice_account(),
+            bob_account(),
+            &*signer,
+            to_yocto(10),
+            CryptoHash::default(),
+        );
+        let result = runtime
+            .apply(
+                tries.get_trie_for_shard(ShardUId::single_shard(), root),
+                &None,
+                &apply_state,
+                &[],
+                &[transaction],
+                &epoch_info_provider,
+                Default::default(),
+            )
+            .unwrap();
+        assert_eq!(result.outgoing_receipts.len(), 1);
+        match result.outgoing_receipts[0].receipt() {
+            ReceiptEnum::Action(action_receipt) => {
+                assert_eq!(action_receipt.gas_price, GAS_PRICE)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// The priority fee is charged from the signer and paid to the chunk
+    /// producer with a balance refund receipt.
+    #[test]
+    fn test_transaction_priority_fee() {
+        let (runtime, tries, root, mut apply_state, signer, _) =
+            setup_runtime(to_yocto(1_000_000), 0, 10u64.pow(15));
+        let mut config = RuntimeConfig::test();
+        config.priority_fee_enabled = true;
+        apply_state.config = Arc::new(config);
+        let epoch_info_provider =
+            MockEpochInfoProvider::new([(bob_account(), to_yocto(100))].into_iter());
+        let transaction = SignedTransaction::from_actions_v1(
+            1,
+            alice_account(),
+            alice_account(),
+            &*signer,
+            vec![Action::Transfer(TransferAction { deposit: 1 })],
+            CryptoHash::default(),
+            7,
+        );
+        let result = runtime
+            .apply(
+                tries.get_trie_for_shard(ShardUId::single_shard(), root),
+                &None,
+                &apply_state,
+                &[],
+                &[transaction],
+                &epoch_info_provider,
+                Default::default(),
+            )
+            .unwrap();
+
+        let receipt_ids = &result.outcomes[0].outcome.receipt_ids;
+        assert_eq!(receipt_ids.len(), 2);
+        let priority_fee_receipt = result
+            .outgoing_receipts
+            .iter()
+            .find(|receipt| receipt.receipt_id() == &receipt_ids[1])
+            .unwrap();
+        assert_eq!(priority_fee_receipt.receiver_id(), &bob_account());
+        let ReceiptEnum::Action(action_receipt) = priority_fee_receipt.receipt() else {
+            panic!("expected an action receipt");
+        };
+        assert_eq!(
+            action_receipt.actions,
+            vec![Action::Transfer(TransferAction {
+                deposit: 7 * near_primitives::transaction::PRIORITY_FEE_UNIT
+            })]
+        );
+    }
+
     #[test]
     fn test_delete_key_add_key() {
         let initial_locked = to_yocto(500_000);
@@ -3461,7 +3882,7 @@ mod tests {
     impl ApplyState {
         fn congestion_control(&self, shard_id: ShardId, missed_chunks: u64) -> CongestionControl {
             CongestionControl::new(
-                self.config.congestion_control_config,
+                self.config.shard_congestion_control_config(shard_id),
                 self.congestion_info[&shard_id].congestion_info,
                 missed_chunks,
             )
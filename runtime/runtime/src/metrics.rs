@@ -1,4 +1,5 @@
 use crate::congestion_control::ReceiptSink;
+use crate::ActionResult;
 use near_o11y::metrics::{
     exponential_buckets, linear_buckets, try_create_counter_vec, try_create_gauge_vec,
     try_create_histogram_vec, try_create_histogram_with_buckets, try_create_int_counter,
@@ -7,7 +8,9 @@ use near_o11y::metrics::{
 };
 use near_parameters::config::CongestionControlConfig;
 use near_primitives::congestion_info::CongestionInfo;
-use near_primitives::types::ShardId;
+use near_primitives::errors::ActionErrorKind;
+use near_primitives::receipt::Receipt;
+use near_primitives::types::{Compute, Gas, ShardId};
 use once_cell::sync::Lazy;
 use std::time::Duration;
 
@@ -187,6 +190,21 @@ pub static FUNCTION_CALL_PROCESSED_CACHE_ERRORS: Lazy<IntCounterVec> = Lazy::new
     )
     .unwrap()
 });
+pub static DELEGATE_ACTION_PROCESSED: Lazy<IntCounterVec> = Lazy::new(|| {
+    try_create_int_counter_vec(
+        "near_delegate_action_processed_total",
+        "The number of delegate actions processed since starting this node, by their result",
+        &["result"],
+    )
+    .unwrap()
+});
+pub static DELEGATE_ACTION_GAS: Lazy<IntCounter> = Lazy::new(|| {
+    try_create_int_counter(
+        "near_delegate_action_gas_total",
+        "Gas paid by relayers for the delegate actions they signed, including the gas prepaid for the delegated actions",
+    )
+    .unwrap()
+});
 static CHUNK_COMPUTE: Lazy<HistogramVec> = Lazy::new(|| {
     try_create_histogram_vec(
         "near_chunk_compute",
@@ -295,6 +313,33 @@ static CHUNK_TX_TGAS: Lazy<HistogramVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+static RECEIPT_TGAS: Lazy<HistogramVec> = Lazy::new(|| {
+    try_create_histogram_vec(
+        "near_receipt_tgas",
+        "Tgas burnt by a single receipt, as a histogram. Reported for all processed receipts that produced an outcome.",
+        &["shard_id"],
+        buckets_for_receipt_gas(),
+    )
+    .unwrap()
+});
+static RECEIPT_COMPUTE: Lazy<HistogramVec> = Lazy::new(|| {
+    try_create_histogram_vec(
+        "near_receipt_compute",
+        "Compute usage of a single receipt, as a histogram in ms. Reported for all processed receipts that produced an outcome.",
+        &["shard_id"],
+        buckets_for_receipt_gas(),
+    )
+    .unwrap()
+});
+static RECEIPT_TOUCHED_TRIE_NODES: Lazy<HistogramVec> = Lazy::new(|| {
+    try_create_histogram_vec(
+        "near_receipt_touched_trie_nodes",
+        "Number of trie nodes read (from db and memory) while processing a single receipt",
+        &["shard_id"],
+        Some(exponential_buckets(1., 2., 20).unwrap()),
+    )
+    .unwrap()
+});
 pub static RECEIPT_RECORDED_SIZE: Lazy<Histogram> = Lazy::new(|| {
     try_create_histogram_with_buckets(
         "near_receipt_recorded_size",
@@ -319,6 +364,23 @@ pub static RECEIPT_RECORDED_SIZE_UPPER_BOUND_RATIO: Lazy<Histogram> = Lazy::new(
     )
     .unwrap()
 });
+static OUTGOING_RECEIPT_SIZE: Lazy<HistogramVec> = Lazy::new(|| {
+    try_create_histogram_vec(
+        "near_outgoing_receipt_size_bytes",
+        "Size of a single outgoing receipt of an applied chunk, as a histogram in bytes",
+        &["shard_id"],
+        Some(exponential_buckets(128., 2., 17).unwrap()),
+    )
+    .unwrap()
+});
+pub static RECEIPT_SIZE_EXCEEDED_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    try_create_int_counter_vec(
+        "near_receipt_size_exceeded_total",
+        "The number of actions failed because a receipt they created exceeded max_receipt_size, since starting this node",
+        &["shard_id"],
+    )
+    .unwrap()
+});
 pub static CHUNK_RECORDED_SIZE: Lazy<Histogram> = Lazy::new(|| {
     try_create_histogram_with_buckets(
         "near_chunk_recorded_size",
@@ -406,6 +468,12 @@ static CONGESTION_OUTGOING_GAS: Lazy<IntGaugeVec> = Lazy::new(|| {
 fn buckets_for_gas() -> Option<Vec<f64>> {
     Some(vec![0., 50., 100., 200., 300., 400., 500., 600., 700., 800., 900., 1000., 1100., 1200.])
 }
+/// Buckets used for gas and compute usage of a single receipt, in Tgas or ms.
+///
+/// A single receipt can burn at most 300 Tgas, most receipts burn only a few.
+fn buckets_for_receipt_gas() -> Option<Vec<f64>> {
+    Some(vec![0.1, 0.5, 1., 2., 5., 10., 20., 50., 100., 150., 200., 250., 300., 500.])
+}
 /// Buckets used for receipt compute time usage, in ms.
 ///
 /// Ideally the range should be 0-1300 ms. But when we increase the compute cost
@@ -618,6 +686,65 @@ impl ApplyMetrics {
     }
 }
 
+/// Histograms observed for every processed receipt of a shard.
+///
+/// The label lookup is done once per chunk rather than once per receipt.
+pub struct ReceiptMetrics {
+    tgas: Histogram,
+    compute: Histogram,
+    touched_trie_nodes: Histogram,
+}
+
+impl ReceiptMetrics {
+    pub fn new(shard_id: ShardId) -> Self {
+        let shard_id = shard_id.to_string();
+        Self {
+            tgas: RECEIPT_TGAS.with_label_values(&[&shard_id]),
+            compute: RECEIPT_COMPUTE.with_label_values(&[&shard_id]),
+            touched_trie_nodes: RECEIPT_TOUCHED_TRIE_NODES.with_label_values(&[&shard_id]),
+        }
+    }
+
+    pub fn observe(&self, gas_burnt: Gas, compute_usage: Compute, touched_trie_nodes: u64) {
+        const TERA: f64 = 1_000_000_000_000_f64;
+        self.tgas.observe(gas_burnt as f64 / TERA);
+        self.compute.observe(compute_usage as f64 / TERA);
+        self.touched_trie_nodes.observe(touched_trie_nodes as f64);
+    }
+}
+
+/// Reports the sizes of the outgoing receipts of a chunk applied by the shard.
+pub(crate) fn report_outgoing_receipt_sizes(shard_id: ShardId, outgoing_receipts: &[Receipt]) {
+    let histogram = OUTGOING_RECEIPT_SIZE.with_label_values(&[&shard_id.to_string()]);
+    for receipt in outgoing_receipts {
+        // `borsh::object_length` may only fail when the size overflows u32.
+        let size = borsh::object_length(receipt).unwrap_or(usize::MAX);
+        histogram.observe(size as f64);
+    }
+}
+
+/// Reports the delegate action with its result. The actions aren't reported by
+/// relayer, as anyone can become one, so the metrics would grow without bound;
+/// the `EXPERIMENTAL_delegate_actions` RPC method lists them by relayer instead.
+pub(crate) fn report_delegate_action(result: &ActionResult) {
+    let result_label = match &result.result {
+        Ok(_) => "ok",
+        Err(err) => match &err.kind {
+            ActionErrorKind::DelegateActionInvalidSignature => "invalid_signature",
+            ActionErrorKind::DelegateActionExpired => "expired",
+            ActionErrorKind::DelegateActionSenderDoesNotMatchTxReceiver { .. } => {
+                "sender_does_not_match_tx_receiver"
+            }
+            ActionErrorKind::DelegateActionAccessKeyError(_) => "access_key_error",
+            ActionErrorKind::DelegateActionInvalidNonce { .. } => "invalid_nonce",
+            ActionErrorKind::DelegateActionNonceTooLarge { .. } => "nonce_too_large",
+            _ => "other",
+        },
+    };
+    DELEGATE_ACTION_PROCESSED.with_label_values(&[result_label]).inc();
+    DELEGATE_ACTION_GAS.inc_by(result.gas_used);
+}
+
 pub fn report_congestion_metrics(
     receipt_sink: &ReceiptSink,
     sender_shard_id: ShardId,
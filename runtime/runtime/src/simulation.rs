@@ -0,0 +1,137 @@
+//! Simulation of a transaction and the receipts it generates, for the
+//! `EXPERIMENTAL_simulate_tx` RPC method.
+//!
+//! The transaction is executed on top of the state of the shards as if it was
+//! included in the next block, and the receipts one after another in the order
+//! they're generated, all within the same block. Nothing is stored. There is
+//! no congestion control, and the yielded receipts are never resumed. As the
+//! RPC nodes serve the simulations to anyone, their cost is bounded by the
+//! number of receipts and the gas burnt.
+use crate::congestion_control::{ReceiptSink, ReceiptSinkV1};
+use crate::{ApplyState, ApplyStats, Runtime};
+use near_primitives::account::Account;
+use near_primitives::errors::{RuntimeError, StorageError};
+use near_primitives::hash::CryptoHash;
+use near_primitives::transaction::{ExecutionOutcomeWithId, SignedTransaction};
+use near_primitives::trie_key::TrieKey;
+use near_primitives::types::{EpochInfoProvider, Gas, ShardId, StateChangeCause};
+use near_primitives::views::AccountStateOverride;
+use near_store::{get_account, set_account, TrieUpdate};
+use std::collections::{HashMap, VecDeque};
+
+/// Maximum number of receipts a simulation executes, the remaining ones are
+/// left unexecuted.
+const MAX_SIMULATED_RECEIPTS: usize = 100;
+
+/// Gas burnt by a simulation above which the remaining receipts are left
+/// unexecuted, the default gas limit of a chunk.
+const MAX_SIMULATED_GAS: Gas = 1_000_000_000_000_000;
+
+fn shard_state_update(
+    state_updates: &mut HashMap<ShardId, TrieUpdate>,
+    shard_id: ShardId,
+) -> Result<&mut TrieUpdate, RuntimeError> {
+    state_updates.get_mut(&shard_id).ok_or_else(|| {
+        StorageError::StorageInconsistentState(format!("state of shard {shard_id} not given"))
+            .into()
+    })
+}
+
+impl Runtime {
+    /// Applies the overrides and executes the transaction and the receipts it
+    /// generates, in the state updates of the shards of their receivers. The
+    /// state updates of the shards of the signer and of the overridden
+    /// accounts must be given, the receipts of the shards without a state
+    /// update are left unexecuted, as well as the ones above
+    /// `MAX_SIMULATED_RECEIPTS` or once `MAX_SIMULATED_GAS` is burnt. Returns
+    /// the outcomes of the transaction and of the executed receipts, in the
+    /// order of execution.
+    pub fn simulate_transaction(
+        &self,
+        state_updates: &mut HashMap<ShardId, TrieUpdate>,
+        apply_state: &mut ApplyState,
+        transaction: &SignedTransaction,
+        verify_signature: bool,
+        state_overrides: &[AccountStateOverride],
+        epoch_info_provider: &dyn EpochInfoProvider,
+    ) -> Result<Vec<ExecutionOutcomeWithId>, RuntimeError> {
+        for state_override in state_overrides {
+            let account_id = &state_override.account_id;
+            let shard_id =
+                epoch_info_provider.account_id_to_shard_id(account_id, &apply_state.epoch_id)?;
+            let state_update = shard_state_update(state_updates, shard_id)?;
+            if let Some(balance) = state_override.balance {
+                let mut account = get_account(state_update, account_id)?.unwrap_or_else(|| {
+                    Account::new(
+                        0,
+                        0,
+                        0,
+                        CryptoHash::default(),
+                        apply_state.config.fees.storage_usage_config.num_bytes_account,
+                        apply_state.current_protocol_version,
+                    )
+                });
+                account.set_amount(balance);
+                set_account(state_update, account_id.clone(), &account);
+            }
+            for item in &state_override.state {
+                let key = TrieKey::ContractData {
+                    account_id: account_id.clone(),
+                    key: item.key.to_vec(),
+                };
+                state_update.set(key, item.value.to_vec());
+            }
+            state_update.commit(StateChangeCause::Migration);
+        }
+
+        let mut stats = ApplyStats::default();
+        let signer_id = transaction.transaction.signer_id();
+        apply_state.shard_id =
+            epoch_info_provider.account_id_to_shard_id(signer_id, &apply_state.epoch_id)?;
+        let (receipt, priority_fee_receipt, outcome) = self.process_transaction(
+            shard_state_update(state_updates, apply_state.shard_id)?,
+            apply_state,
+            transaction,
+            verify_signature,
+            &mut stats,
+            epoch_info_provider,
+        )?;
+
+        let mut gas_burnt = outcome.outcome.gas_burnt;
+        let mut outcomes = vec![outcome];
+        let mut receipts = VecDeque::from([receipt]);
+        receipts.extend(priority_fee_receipt);
+        let mut validator_proposals = vec![];
+        let mut num_executed = 0;
+        while let Some(receipt) = receipts.pop_front() {
+            if num_executed == MAX_SIMULATED_RECEIPTS || gas_burnt >= MAX_SIMULATED_GAS {
+                break;
+            }
+            let shard_id = epoch_info_provider
+                .account_id_to_shard_id(receipt.receiver_id(), &apply_state.epoch_id)?;
+            let Some(state_update) = state_updates.get_mut(&shard_id) else {
+                continue;
+            };
+            apply_state.shard_id = shard_id;
+            let mut new_receipts = vec![];
+            let mut receipt_sink =
+                ReceiptSink::V1(ReceiptSinkV1 { outgoing_receipts: &mut new_receipts });
+            let outcome = self.process_receipt(
+                state_update,
+                apply_state,
+                &receipt,
+                &mut receipt_sink,
+                &mut validator_proposals,
+                &mut stats,
+                epoch_info_provider,
+            )?;
+            if let Some(outcome) = outcome {
+                gas_burnt = gas_burnt.saturating_add(outcome.outcome.gas_burnt);
+                outcomes.push(outcome);
+            }
+            receipts.extend(new_receipts);
+            num_executed += 1;
+        }
+        Ok(outcomes)
+    }
+}
@@ -16,6 +16,8 @@ pub enum ViewContractCodeError {
     AccountDoesNotExist { requested_account_id: near_primitives::types::AccountId },
     #[error("Contract code for contract ID #{contract_account_id} does not exist")]
     NoContractCode { contract_account_id: near_primitives::types::AccountId },
+    #[error("Contract code with hash #{code_hash} does not exist")]
+    UnknownCodeHash { code_hash: near_primitives::hash::CryptoHash },
     #[error("Internal error: #{error_message}")]
     InternalError { error_message: String },
 }
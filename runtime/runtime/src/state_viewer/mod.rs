@@ -3,26 +3,34 @@ use crate::ext::RuntimeExt;
 use crate::receipt_manager::ReceiptManager;
 use crate::ApplyState;
 use near_crypto::{KeyType, PublicKey};
-use near_parameters::RuntimeConfigStore;
+use near_parameters::{RuntimeConfigStore, StorageUsageConfig};
 use near_primitives::account::{AccessKey, Account};
 use near_primitives::borsh::BorshDeserialize;
+use near_primitives::errors::StorageError;
 use near_primitives::hash::CryptoHash;
 use near_primitives::receipt::ActionReceipt;
 use near_primitives::runtime::migration_data::{MigrationData, MigrationFlags};
 use near_primitives::transaction::FunctionCallAction;
-use near_primitives::trie_key::trie_key_parsers;
+use near_primitives::trie_key::{trie_key_parsers, TrieKey};
 use near_primitives::types::{AccountId, EpochInfoProvider, Gas};
 use near_primitives::version::PROTOCOL_VERSION;
-use near_primitives::views::{StateItem, ViewApplyState, ViewStateResult};
+use near_primitives::views::{
+    DataKeyStorageUsageView, StateItem, StorageUsageView, ViewApplyState, ViewStateResult,
+};
 use near_primitives_core::config::ViewConfig;
 use near_store::{get_access_key, get_account, get_code, TrieUpdate};
 use near_vm_runner::logic::ReturnData;
 use near_vm_runner::ContractCode;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 use std::{str, sync::Arc, time::Instant};
 use tracing::debug;
 
 pub mod errors;
 
+/// Prefix of all wasm modules.
+const WASM_MAGIC: &[u8] = b"\0asm";
+
 pub struct TrieViewer {
     /// Upper bound of the byte size of contract state that is still viewable. None is no limit
     state_size_limit: Option<u64>,
@@ -71,6 +79,33 @@ impl TrieViewer {
         })
     }
 
+    /// Returns the contract code with the given hash if it is stored as a
+    /// value of the shard of the trie. The values are looked up by their hash
+    /// in the storage shared by all the state roots of the shard, so the code
+    /// isn't necessarily reachable from the state root of the trie: it may
+    /// have been deployed at an earlier state root which isn't garbage
+    /// collected yet, or at a state root of a fork.
+    pub fn view_code_by_hash(
+        &self,
+        state_update: &TrieUpdate,
+        code_hash: &CryptoHash,
+    ) -> Result<ContractCode, errors::ViewContractCodeError> {
+        let code = match state_update.trie().retrieve_value(code_hash) {
+            Ok(code) => code,
+            Err(StorageError::MissingTrieValue(..)) => {
+                return Err(errors::ViewContractCodeError::UnknownCodeHash {
+                    code_hash: *code_hash,
+                })
+            }
+            Err(err) => return Err(err.into()),
+        };
+        // Any other value of the trie with the same hash isn't contract code.
+        if !code.starts_with(WASM_MAGIC) {
+            return Err(errors::ViewContractCodeError::UnknownCodeHash { code_hash: *code_hash });
+        }
+        Ok(ContractCode::new(code, Some(*code_hash)))
+    }
+
     pub fn view_access_key(
         &self,
         state_update: &TrieUpdate,
@@ -82,16 +117,31 @@ impl TrieViewer {
         })
     }
 
+    /// Returns the access keys of the account in the order of their public
+    /// keys in the trie, only the ones after `after_public_key` if given and
+    /// at most `limit` of them.
     pub fn view_access_keys(
         &self,
         state_update: &TrieUpdate,
         account_id: &AccountId,
+        after_public_key: Option<&PublicKey>,
+        limit: Option<usize>,
     ) -> Result<Vec<(PublicKey, AccessKey)>, errors::ViewAccessKeyError> {
         let prefix = trie_key_parsers::get_raw_prefix_for_access_keys(account_id);
         let raw_prefix: &[u8] = prefix.as_ref();
+        let after_key = after_public_key.map(|public_key| {
+            TrieKey::AccessKey { account_id: account_id.clone(), public_key: public_key.clone() }
+                .to_vec()
+        });
         let access_keys =
             state_update
                 .iter(&prefix)?
+                // Only the keys are read from the trie until the first listed one.
+                .filter(|key| match (key, &after_key) {
+                    (Ok(key), Some(after_key)) => key > after_key,
+                    _ => true,
+                })
+                .take(limit.unwrap_or(usize::MAX))
                 .map(|key| {
                     let key = key?;
                     let public_key = &key[raw_prefix.len()..];
@@ -153,6 +203,89 @@ impl TrieViewer {
         Ok(ViewStateResult { values, proof })
     }
 
+    /// Computes what the storage usage of the account is made of, with the
+    /// `num_largest_data_keys` contract data keys of the largest storage
+    /// usage. The contract data is iterated over like for `view_state`, so the
+    /// same size limit applies.
+    pub fn view_storage_usage(
+        &self,
+        state_update: &TrieUpdate,
+        account_id: &AccountId,
+        storage_usage_config: &StorageUsageConfig,
+        num_largest_data_keys: usize,
+    ) -> Result<StorageUsageView, errors::ViewStateError> {
+        let account = get_account(state_update, account_id)?.ok_or_else(|| {
+            errors::ViewStateError::AccountDoesNotExist { requested_account_id: account_id.clone() }
+        })?;
+        let code = get_code(state_update, account_id, Some(account.code_hash()))?
+            .map(|c| c.code().len() as u64)
+            .unwrap_or_default();
+        if let Some(limit) = self.state_size_limit {
+            if account.storage_usage().saturating_sub(code) > limit {
+                return Err(errors::ViewStateError::AccountStateTooLarge {
+                    requested_account_id: account_id.clone(),
+                });
+            }
+        }
+
+        let prefix = trie_key_parsers::get_raw_prefix_for_access_keys(account_id);
+        let mut access_keys = 0;
+        let mut num_access_keys = 0;
+        for key in state_update.iter(&prefix)? {
+            let key = key?;
+            let access_key =
+                near_store::get_access_key_raw(state_update, &key)?.ok_or_else(|| {
+                    errors::ViewStateError::InternalError {
+                        error_message: "Unexpected missing key from iterator".to_string(),
+                    }
+                })?;
+            // The public key is stored borsh serialized in the trie key.
+            access_keys += storage_usage_config.num_extra_bytes_record
+                + (key.len() - prefix.len()) as u64
+                + borsh::object_length(&access_key).unwrap() as u64;
+            num_access_keys += 1;
+        }
+
+        let query = trie_key_parsers::get_raw_prefix_for_contract_data(account_id, &[]);
+        let mut data = 0;
+        let mut num_data_keys = 0;
+        let mut largest_data_keys = BinaryHeap::new();
+        let mut iter = state_update.trie().disk_iter()?;
+        iter.seek_prefix(&query)?;
+        for item in iter {
+            let (key, value) = item?;
+            let key = key[query.len()..].to_vec();
+            let storage_usage =
+                storage_usage_config.num_extra_bytes_record + (key.len() + value.len()) as u64;
+            data += storage_usage;
+            num_data_keys += 1;
+            // A min-heap of the largest keys seen so far.
+            largest_data_keys.push(Reverse((storage_usage, key)));
+            if largest_data_keys.len() > num_largest_data_keys {
+                largest_data_keys.pop();
+            }
+        }
+        let largest_data_keys = largest_data_keys
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((storage_usage, key))| DataKeyStorageUsageView {
+                key: key.into(),
+                storage_usage,
+            })
+            .collect();
+
+        Ok(StorageUsageView {
+            storage_usage: account.storage_usage(),
+            account: storage_usage_config.num_bytes_account,
+            code,
+            access_keys,
+            num_access_keys,
+            data,
+            num_data_keys,
+            largest_data_keys,
+        })
+    }
+
     pub fn call_function(
         &self,
         mut state_update: TrieUpdate,
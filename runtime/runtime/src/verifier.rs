@@ -98,8 +98,10 @@ pub fn validate_transaction(
     verify_signature: bool,
     current_protocol_version: ProtocolVersion,
 ) -> Result<TransactionCost, RuntimeError> {
-    // Don't allow V1 currently. This will be changed when the new protocol version is introduced.
-    if matches!(signed_transaction.transaction, near_primitives::transaction::Transaction::V1(_)) {
+    // V1 only adds the priority fee, which is an experiment limited to benchmarknet for now.
+    if matches!(signed_transaction.transaction, near_primitives::transaction::Transaction::V1(_))
+        && !config.priority_fee_enabled
+    {
         return Err(InvalidTxError::InvalidTransactionVersion.into());
     }
     let transaction = &signed_transaction.transaction;
@@ -148,14 +150,20 @@ pub fn verify_and_charge_transaction(
     current_protocol_version: ProtocolVersion,
 ) -> Result<VerificationResult, RuntimeError> {
     let _span = tracing::debug_span!(target: "runtime", "verify_and_charge_transaction").entered();
-    let TransactionCost { gas_burnt, gas_remaining, receipt_gas_price, total_cost, burnt_amount } =
-        validate_transaction(
-            config,
-            gas_price,
-            signed_transaction,
-            verify_signature,
-            current_protocol_version,
-        )?;
+    let TransactionCost {
+        gas_burnt,
+        gas_remaining,
+        receipt_gas_price,
+        total_cost,
+        burnt_amount,
+        priority_fee_amount,
+    } = validate_transaction(
+        config,
+        gas_price,
+        signed_transaction,
+        verify_signature,
+        current_protocol_version,
+    )?;
 
     let transaction = &signed_transaction.transaction;
     let signer_id = transaction.signer_id();
@@ -286,7 +294,25 @@ pub fn verify_and_charge_transaction(
     set_access_key(state_update, signer_id.clone(), transaction.public_key().clone(), &access_key);
     set_account(state_update, signer_id.clone(), &signer);
 
-    Ok(VerificationResult { gas_burnt, gas_remaining, receipt_gas_price, burnt_amount })
+    Ok(VerificationResult {
+        gas_burnt,
+        gas_remaining,
+        receipt_gas_price,
+        burnt_amount,
+        priority_fee_amount,
+    })
+}
+
+/// Whether a validated receipt was just created or was already accepted
+/// before, which decides the limits checked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ValidateReceiptMode {
+    /// The receipt was created when applying the current chunk.
+    NewReceipt,
+    /// The receipt was created before, e.g. an incoming or a delayed receipt.
+    /// The limits on new receipts are not checked, as the receipt may have
+    /// been created before the limits were lowered.
+    ExistingReceipt,
 }
 
 /// Validates a given receipt. Checks validity of the Action or Data receipt.
@@ -294,7 +320,19 @@ pub(crate) fn validate_receipt(
     limit_config: &LimitConfig,
     receipt: &Receipt,
     current_protocol_version: ProtocolVersion,
+    mode: ValidateReceiptMode,
 ) -> Result<(), ReceiptValidationError> {
+    if mode == ValidateReceiptMode::NewReceipt {
+        // `borsh::object_length` may only fail when the size overflows u32.
+        let size = borsh::object_length(receipt).map_or(u64::MAX, |size| size as u64);
+        if size > limit_config.max_receipt_size {
+            return Err(ReceiptValidationError::ReceiptSizeExceeded {
+                size,
+                limit: limit_config.max_receipt_size,
+            });
+        }
+    }
+
     // We retain these checks here as to maintain backwards compatibility
     // with AccountId validation since we illegally parse an AccountId
     // in near-vm-logic/logic.rs#fn(VMLogic::read_and_parse_account_id)
@@ -583,6 +621,7 @@ mod tests {
     use near_primitives::test_utils::account_new;
     use near_primitives::transaction::{
         CreateAccountAction, DeleteAccountAction, DeleteKeyAction, StakeAction, TransferAction,
+        PRIORITY_FEE_UNIT,
     };
     use near_primitives::types::{AccountId, Balance, MerkleHash, StateChangeCause};
     use near_primitives::version::PROTOCOL_VERSION;
@@ -1061,6 +1100,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_validate_transaction_priority_fee() {
+        let mut config = RuntimeConfig::test();
+        config.priority_fee_enabled = true;
+        let (signer, mut state_update, gas_price) =
+            setup_common(TESTING_INIT_BALANCE, 0, Some(AccessKey::full_access()));
+
+        let deposit = 100;
+        let transaction = SignedTransaction::from_actions_v1(
+            1,
+            alice_account(),
+            bob_account(),
+            &*signer,
+            vec![Action::Transfer(TransferAction { deposit })],
+            CryptoHash::default(),
+            3,
+        );
+        let verification_result = verify_and_charge_transaction(
+            &config,
+            &mut state_update,
+            gas_price,
+            &transaction,
+            true,
+            None,
+            PROTOCOL_VERSION,
+        )
+        .expect("valid transaction");
+        let priority_fee_amount = 3 * PRIORITY_FEE_UNIT;
+        assert_eq!(verification_result.priority_fee_amount, priority_fee_amount);
+
+        let account = get_account(&state_update, &alice_account()).unwrap().unwrap();
+        // Balance is decreased by the (TX fees + transfer balance + priority fee).
+        assert_eq!(
+            account.amount(),
+            TESTING_INIT_BALANCE
+                - Balance::from(verification_result.gas_remaining)
+                    * verification_result.receipt_gas_price
+                - verification_result.burnt_amount
+                - deposit
+                - priority_fee_amount
+        );
+    }
+
     #[test]
     fn test_validate_transaction_invalid_transaction_version() {
         let config = RuntimeConfig::test();
@@ -1550,6 +1632,34 @@ mod tests {
             &limit_config,
             &Receipt::new_balance_refund(&alice_account(), 10, ReceiptPriority::NoPriority),
             PROTOCOL_VERSION,
+            ValidateReceiptMode::NewReceipt,
+        )
+        .expect("valid receipt");
+    }
+
+    #[test]
+    fn test_validate_receipt_too_large() {
+        let mut limit_config = test_limit_config();
+        let receipt =
+            Receipt::new_balance_refund(&alice_account(), 10, ReceiptPriority::NoPriority);
+        let size = borsh::object_length(&receipt).unwrap() as u64;
+        limit_config.max_receipt_size = size - 1;
+        assert_eq!(
+            validate_receipt(
+                &limit_config,
+                &receipt,
+                PROTOCOL_VERSION,
+                ValidateReceiptMode::NewReceipt
+            )
+            .expect_err("expected an error"),
+            ReceiptValidationError::ReceiptSizeExceeded { size, limit: size - 1 }
+        );
+        // The receipts created before the limit was lowered are still valid.
+        validate_receipt(
+            &limit_config,
+            &receipt,
+            PROTOCOL_VERSION,
+            ValidateReceiptMode::ExistingReceipt,
         )
         .expect("valid receipt");
     }
@@ -88,6 +88,7 @@ impl StandaloneRuntime {
             &runtime_config.fees.storage_usage_config,
             &genesis,
             account_ids,
+            false,
         );
         let congestion_info: HashMap<_, _> =
             if ProtocolFeature::CongestionControl.enabled(PROTOCOL_VERSION) {
@@ -24,6 +24,8 @@ pub fn random_config() -> RuntimeConfig {
                 (101 + rng.next_u32() % 10).try_into().unwrap(),
                 100,
             ),
+            gas_refund_penalty: Rational32::new((rng.next_u32() % 10).try_into().unwrap(), 100),
+            min_gas_refund: rng.next_u64() % 1_000_000_000_000,
         },
         ..RuntimeConfig::test()
     }
@@ -3,6 +3,7 @@
 use near_parameters::{ActionCosts, RuntimeConfig, RuntimeFeesConfig};
 use near_primitives::transaction::Action;
 use near_primitives::types::{AccountId, Balance, Gas};
+use near_primitives::version::{ProtocolFeature, PROTOCOL_VERSION};
 
 pub struct FeeHelper {
     pub rt_cfg: RuntimeConfig,
@@ -19,6 +20,9 @@ impl FeeHelper {
     }
 
     pub fn gas_to_balance_inflated(&self, gas: Gas) -> Balance {
+        if ProtocolFeature::NoPessimisticGasPrice.enabled(PROTOCOL_VERSION) {
+            return self.gas_to_balance(gas);
+        }
         gas as Balance
             * (self.gas_price * (*self.cfg().pessimistic_gas_price_inflation_ratio.numer() as u128)
                 / (*self.cfg().pessimistic_gas_price_inflation_ratio.denom() as u128))
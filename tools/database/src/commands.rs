@@ -10,6 +10,8 @@ use crate::make_snapshot::MakeSnapshotCommand;
 use crate::memtrie::LoadMemTrieCommand;
 use crate::run_migrations::RunMigrationsCommand;
 use crate::state_perf::StatePerfCommand;
+use crate::streaming_export::{ExportDatabaseCommand, ImportDatabaseCommand};
+use crate::verify::VerifyDatabaseCommand;
 use crate::write_to_db::WriteCryptoHashCommand;
 use clap::Parser;
 use std::path::PathBuf;
@@ -38,6 +40,13 @@ enum SubCommand {
     /// Corrupt the state snapshot.
     CorruptStateSnapshot(CorruptStateSnapshotCommand),
 
+    /// Export columns of the database to a file, e.g. to migrate the database
+    /// to another machine or RocksDB version
+    Export(ExportDatabaseCommand),
+
+    /// Import columns exported with `export` into the database
+    Import(ImportDatabaseCommand),
+
     /// Make snapshot of the database
     MakeSnapshot(MakeSnapshotCommand),
 
@@ -59,6 +68,9 @@ enum SubCommand {
     AnalyzeDelayedReceipt(AnalyzeDelayedReceiptCommand),
     /// Analyze size of contracts present in the current state
     AnalyzeContractSizes(AnalyzeContractSizesCommand),
+    /// Verify the integrity of the chain data, state roots and flat storage,
+    /// optionally repairing the data which can be derived from other data
+    Verify(VerifyDatabaseCommand),
 }
 
 impl DatabaseCommand {
@@ -69,6 +81,8 @@ impl DatabaseCommand {
             SubCommand::ChangeDbKind(cmd) => cmd.run(home),
             SubCommand::CompactDatabase(cmd) => cmd.run(home),
             SubCommand::CorruptStateSnapshot(cmd) => cmd.run(home),
+            SubCommand::Export(cmd) => cmd.run(home),
+            SubCommand::Import(cmd) => cmd.run(home),
             SubCommand::MakeSnapshot(cmd) => {
                 let near_config = nearcore::config::load_config(
                     &home,
@@ -84,6 +98,7 @@ impl DatabaseCommand {
             SubCommand::HighLoadStats(cmd) => cmd.run(home),
             SubCommand::AnalyzeDelayedReceipt(cmd) => cmd.run(home),
             SubCommand::AnalyzeContractSizes(cmd) => cmd.run(home),
+            SubCommand::Verify(cmd) => cmd.run(home),
         }
     }
 }
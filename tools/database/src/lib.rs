@@ -12,5 +12,7 @@ mod make_snapshot;
 mod memtrie;
 mod run_migrations;
 mod state_perf;
+mod streaming_export;
 mod utils;
+mod verify;
 mod write_to_db;
@@ -0,0 +1,458 @@
+//! Streaming export and import of the database, to migrate it between RocksDB
+//! versions, machines or storage backends.
+//!
+//! The stream starts with `MAGIC`, followed by frames. A frame is the length
+//! of its payload as a little-endian u32, the borsh-serialized `Frame` and the
+//! hash of the payload. The entries of a column follow the frame starting the
+//! column, in the order of keys, as the raw bytes stored in the database, i.e.
+//! with the reference counts of the reference-counted columns. The stream ends
+//! with `Frame::End`, so that an interrupted export is never mistaken for a
+//! complete one.
+use crate::utils::{open_rocksdb, resolve_column};
+use anyhow::Context;
+use borsh::{BorshDeserialize, BorshSerialize};
+use clap::{Parser, ValueEnum};
+use indicatif::{ProgressBar, ProgressStyle};
+use near_primitives::hash::{hash, CryptoHash};
+use near_store::db::{DBTransaction, Database};
+use near_store::DBCol;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use strum::IntoEnumIterator;
+
+const MAGIC: &[u8; 8] = b"NEARDBS1";
+
+/// Size of the entries after which they are written as a frame.
+const FRAME_ENTRIES_SIZE: usize = 16 * 1024 * 1024;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+enum Frame {
+    /// Starts the entries of the column.
+    Column { name: String },
+    /// Keys and values of the current column.
+    Entries(Vec<(Vec<u8>, Vec<u8>)>),
+    /// Ends the stream, with the number of the entries in it.
+    End { num_entries: u64 },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    /// Stream of checksummed frames with the entries of one column after another.
+    Streaming,
+}
+
+/// Exports the columns of the database to a file, to be imported with
+/// `neard database import`.
+#[derive(Parser)]
+pub(crate) struct ExportDatabaseCommand {
+    #[clap(long, value_enum)]
+    format: ExportFormat,
+
+    /// File to write the export to.
+    #[clap(long)]
+    output: PathBuf,
+
+    /// Export only these columns, instead of all of them.
+    #[clap(long, value_delimiter = ',')]
+    columns: Vec<String>,
+
+    /// Continue an interrupted export to the output file, from the last
+    /// complete frame in it.
+    #[clap(long)]
+    resume: bool,
+}
+
+/// Imports the columns exported with `neard database export` into the
+/// database, which is created if it doesn't exist yet.
+#[derive(Parser)]
+pub(crate) struct ImportDatabaseCommand {
+    /// File to read the export from.
+    #[clap(long)]
+    input: PathBuf,
+
+    /// Import only these columns, instead of all of the exported ones.
+    #[clap(long, value_delimiter = ',')]
+    columns: Vec<String>,
+
+    /// Continue an interrupted import, from the checkpoint saved next to the
+    /// input file.
+    #[clap(long)]
+    resume: bool,
+}
+
+fn resolve_columns(names: &[String]) -> anyhow::Result<Vec<DBCol>> {
+    if names.is_empty() {
+        return Ok(DBCol::iter().collect());
+    }
+    names.iter().map(|name| resolve_column(name)).collect()
+}
+
+fn column_name(col: DBCol) -> String {
+    <&str>::from(col).to_string()
+}
+
+fn progress_bar(len: u64, template: &str) -> ProgressBar {
+    let bar = ProgressBar::new(len);
+    bar.set_style(ProgressStyle::default_bar().template(template));
+    bar
+}
+
+/// Writes the frame, returning the number of bytes written.
+fn write_frame(writer: &mut impl Write, frame: &Frame) -> io::Result<u64> {
+    let payload = borsh::to_vec(frame)?;
+    let len = u32::try_from(payload.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "frame too large"))?;
+    writer.write_all(&len.to_le_bytes())?;
+    writer.write_all(&payload)?;
+    writer.write_all(hash(&payload).as_ref())?;
+    Ok(4 + payload.len() as u64 + 32)
+}
+
+/// Reads the next frame with the number of its bytes, or returns none at the
+/// end of the stream.
+fn read_frame(reader: &mut impl Read) -> io::Result<Option<(Frame, u64)>> {
+    let mut len = [0u8; 4];
+    match reader.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err),
+    }
+    let mut payload = vec![0u8; u32::from_le_bytes(len) as usize];
+    reader.read_exact(&mut payload)?;
+    let mut checksum = [0u8; 32];
+    reader.read_exact(&mut checksum)?;
+    if hash(&payload) != CryptoHash(checksum) {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "frame checksum mismatch"));
+    }
+    let frame = Frame::try_from_slice(&payload)?;
+    Ok(Some((frame, 4 + payload.len() as u64 + 32)))
+}
+
+fn check_magic(reader: &mut impl Read) -> anyhow::Result<()> {
+    let mut magic = [0u8; MAGIC.len()];
+    reader.read_exact(&mut magic).context("reading the header")?;
+    anyhow::ensure!(&magic == MAGIC, "not a streaming export of the database");
+    Ok(())
+}
+
+/// Where an interrupted export stopped: after the frame ending at `offset`,
+/// in the middle of the last column, after its last exported key.
+#[derive(Default)]
+struct ExportPosition {
+    offset: u64,
+    columns: Vec<DBCol>,
+    last_key: Option<Vec<u8>>,
+    num_entries: u64,
+    finished: bool,
+}
+
+/// Reads the frames of an interrupted export up to the first incomplete or
+/// corrupted one, which is where the export continues.
+fn read_export_position(file: &mut File) -> anyhow::Result<ExportPosition> {
+    let mut reader = BufReader::new(file);
+    check_magic(&mut reader)?;
+    let mut position = ExportPosition { offset: MAGIC.len() as u64, ..Default::default() };
+    while let Ok(Some((frame, len))) = read_frame(&mut reader) {
+        match frame {
+            Frame::Column { name } => {
+                position.columns.push(resolve_column(&name)?);
+                position.last_key = None;
+            }
+            Frame::Entries(entries) => {
+                position.num_entries += entries.len() as u64;
+                position.last_key = entries.last().map(|(key, _)| key.clone());
+            }
+            Frame::End { .. } => position.finished = true,
+        }
+        position.offset += len;
+        if position.finished {
+            break;
+        }
+    }
+    Ok(position)
+}
+
+/// Exports the columns to the output file, continuing the export already in
+/// the file if `resume`. Returns the number of the entries in the export.
+fn export(
+    db: &dyn Database,
+    columns: &[DBCol],
+    output: &Path,
+    resume: bool,
+    progress: &ProgressBar,
+) -> anyhow::Result<u64> {
+    let mut position = ExportPosition::default();
+    let mut file = if resume && output.exists() {
+        let mut file = OpenOptions::new().read(true).write(true).open(output)?;
+        position = read_export_position(&mut file)?;
+        anyhow::ensure!(
+            columns.starts_with(&position.columns),
+            "the export in {} has other columns than the requested ones",
+            output.display()
+        );
+        file.set_len(position.offset)?;
+        file.seek(SeekFrom::End(0))?;
+        file
+    } else {
+        let mut file = File::create(output)?;
+        file.write_all(MAGIC)?;
+        file
+    };
+    if position.finished {
+        return Ok(position.num_entries);
+    }
+
+    let mut writer = BufWriter::new(&mut file);
+    let mut num_entries = position.num_entries;
+    // The last column of an interrupted export continues after its last key.
+    let resumed_columns = position.columns.len().saturating_sub(1);
+    for (index, &col) in columns.iter().enumerate().skip(resumed_columns) {
+        let last_key = if index < position.columns.len() {
+            position.last_key.take()
+        } else {
+            write_frame(&mut writer, &Frame::Column { name: column_name(col) })?;
+            None
+        };
+        progress.set_message(&column_name(col));
+        let mut entries = vec![];
+        let mut entries_size = 0;
+        for item in db.iter_raw_bytes(col) {
+            let (key, value) = item?;
+            if last_key.as_ref().is_some_and(|last_key| key.as_ref() <= last_key.as_slice()) {
+                continue;
+            }
+            entries_size += key.len() + value.len();
+            entries.push((key.into_vec(), value.into_vec()));
+            if entries_size >= FRAME_ENTRIES_SIZE {
+                num_entries += entries.len() as u64;
+                progress.inc(entries.len() as u64);
+                write_frame(&mut writer, &Frame::Entries(std::mem::take(&mut entries)))?;
+                entries_size = 0;
+            }
+        }
+        if !entries.is_empty() {
+            num_entries += entries.len() as u64;
+            progress.inc(entries.len() as u64);
+            write_frame(&mut writer, &Frame::Entries(entries))?;
+        }
+    }
+    write_frame(&mut writer, &Frame::End { num_entries })?;
+    writer.flush()?;
+    drop(writer);
+    file.sync_all()?;
+    Ok(num_entries)
+}
+
+/// Progress of an import saved after every frame, so that an interrupted
+/// import continues after the last imported frame.
+#[derive(BorshSerialize, BorshDeserialize, Default)]
+struct ImportCheckpoint {
+    offset: u64,
+    column: Option<String>,
+    num_entries: u64,
+}
+
+fn checkpoint_path(input: &Path) -> PathBuf {
+    let mut path = input.as_os_str().to_owned();
+    path.push(".import-checkpoint");
+    path.into()
+}
+
+fn save_checkpoint(path: &Path, checkpoint: &ImportCheckpoint) -> io::Result<()> {
+    // Written to a temporary file first, so that the checkpoint is replaced
+    // atomically.
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    std::fs::write(&tmp_path, borsh::to_vec(checkpoint)?)?;
+    std::fs::rename(&tmp_path, path)
+}
+
+/// Imports the columns from the input file, all of them if `columns` is none,
+/// continuing after the saved checkpoint if `resume`. Returns the number of
+/// the entries imported.
+fn import(
+    db: &dyn Database,
+    input: &Path,
+    columns: Option<&[DBCol]>,
+    resume: bool,
+    progress: &ProgressBar,
+) -> anyhow::Result<u64> {
+    let checkpoint_path = checkpoint_path(input);
+    let mut reader = BufReader::new(File::open(input)?);
+    check_magic(&mut reader)?;
+    let mut checkpoint = ImportCheckpoint { offset: MAGIC.len() as u64, ..Default::default() };
+    if resume && checkpoint_path.exists() {
+        checkpoint = ImportCheckpoint::try_from_slice(&std::fs::read(&checkpoint_path)?)?;
+        reader.seek(SeekFrom::Start(checkpoint.offset))?;
+    }
+    progress.set_position(checkpoint.offset);
+
+    let mut column = checkpoint.column.as_deref().map(resolve_column).transpose()?;
+    let mut num_imported = 0;
+    loop {
+        let Some((frame, len)) = read_frame(&mut reader)? else {
+            anyhow::bail!("{} ends before the end of the export", input.display());
+        };
+        checkpoint.offset += len;
+        match frame {
+            Frame::Column { name } => {
+                let col = resolve_column(&name)?;
+                progress.set_message(&name);
+                checkpoint.column = Some(name);
+                column = Some(col);
+            }
+            Frame::Entries(entries) => {
+                let col = column.context("entries before the first column")?;
+                checkpoint.num_entries += entries.len() as u64;
+                if columns.map_or(true, |columns| columns.contains(&col)) {
+                    num_imported += entries.len() as u64;
+                    let mut transaction = DBTransaction::new();
+                    for (key, value) in entries {
+                        transaction.set(col, key, value);
+                    }
+                    db.write(transaction)?;
+                }
+            }
+            Frame::End { num_entries } => {
+                anyhow::ensure!(
+                    num_entries == checkpoint.num_entries,
+                    "the export has {} entries, expected {num_entries}",
+                    checkpoint.num_entries
+                );
+                break;
+            }
+        }
+        save_checkpoint(&checkpoint_path, &checkpoint)?;
+        progress.set_position(checkpoint.offset);
+    }
+    db.flush()?;
+    if checkpoint_path.exists() {
+        std::fs::remove_file(&checkpoint_path)?;
+    }
+    Ok(num_imported)
+}
+
+impl ExportDatabaseCommand {
+    pub(crate) fn run(&self, home: &Path) -> anyhow::Result<()> {
+        let columns = resolve_columns(&self.columns)?;
+        let db = open_rocksdb(home, near_store::Mode::ReadOnly)?;
+        let progress = progress_bar(0, "[{elapsed_precise}] {msg}: {pos} entries");
+        let num_entries = match self.format {
+            ExportFormat::Streaming => export(&db, &columns, &self.output, self.resume, &progress)?,
+        };
+        progress.finish();
+        println!("Exported {num_entries} entries to {}", self.output.display());
+        Ok(())
+    }
+}
+
+impl ImportDatabaseCommand {
+    pub(crate) fn run(&self, home: &Path) -> anyhow::Result<()> {
+        let columns =
+            if self.columns.is_empty() { None } else { Some(resolve_columns(&self.columns)?) };
+        let db = open_rocksdb(home, near_store::Mode::ReadWrite)?;
+        let progress = progress_bar(
+            std::fs::metadata(&self.input)?.len(),
+            "[{elapsed_precise}] {msg} {wide_bar} {bytes}/{total_bytes} ({eta})",
+        );
+        let num_entries = import(&db, &self.input, columns.as_deref(), self.resume, &progress)?;
+        progress.finish();
+        println!("Imported {num_entries} entries from {}", self.input.display());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_store::db::TestDB;
+
+    fn populate(db: &dyn Database, columns: &[DBCol], num_keys: u8) {
+        let mut transaction = DBTransaction::new();
+        for &col in columns {
+            for key in 0..num_keys {
+                transaction.set(col, vec![key], vec![key; 10]);
+            }
+        }
+        db.write(transaction).unwrap();
+    }
+
+    fn entries(db: &dyn Database, col: DBCol) -> Vec<(Box<[u8]>, Box<[u8]>)> {
+        db.iter_raw_bytes(col).map(Result::unwrap).collect()
+    }
+
+    #[test]
+    fn test_export_import() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("export");
+        let columns = [DBCol::Block, DBCol::State];
+        let source = TestDB::new();
+        populate(source.as_ref(), &columns, 5);
+
+        let progress = ProgressBar::hidden();
+        assert_eq!(export(source.as_ref(), &columns, &path, false, &progress).unwrap(), 10);
+        // Resuming a complete export does nothing.
+        assert_eq!(export(source.as_ref(), &columns, &path, true, &progress).unwrap(), 10);
+
+        let target = TestDB::new();
+        assert_eq!(import(target.as_ref(), &path, None, false, &progress).unwrap(), 10);
+        for col in columns {
+            assert_eq!(entries(target.as_ref(), col), entries(source.as_ref(), col));
+        }
+        assert!(!checkpoint_path(&path).exists());
+
+        let target = TestDB::new();
+        let only_state = [DBCol::State];
+        assert_eq!(import(target.as_ref(), &path, Some(&only_state), false, &progress).unwrap(), 5);
+        assert!(entries(target.as_ref(), DBCol::Block).is_empty());
+    }
+
+    #[test]
+    fn test_resume_export() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("export");
+        let columns = [DBCol::Block, DBCol::State];
+        let source = TestDB::new();
+        populate(source.as_ref(), &columns, 5);
+
+        // An export interrupted in the middle of the second column, with a
+        // partially written frame at the end.
+        let mut file = File::create(&path).unwrap();
+        file.write_all(MAGIC).unwrap();
+        let block_entries: Vec<_> = entries(source.as_ref(), DBCol::Block)
+            .into_iter()
+            .map(|(key, value)| (key.into_vec(), value.into_vec()))
+            .collect();
+        write_frame(&mut file, &Frame::Column { name: column_name(DBCol::Block) }).unwrap();
+        write_frame(&mut file, &Frame::Entries(block_entries)).unwrap();
+        write_frame(&mut file, &Frame::Column { name: column_name(DBCol::State) }).unwrap();
+        write_frame(&mut file, &Frame::Entries(vec![(vec![0], vec![0; 10])])).unwrap();
+        file.write_all(&[1, 2, 3]).unwrap();
+        drop(file);
+
+        let progress = ProgressBar::hidden();
+        assert_eq!(export(source.as_ref(), &columns, &path, true, &progress).unwrap(), 10);
+        let target = TestDB::new();
+        assert_eq!(import(target.as_ref(), &path, None, false, &progress).unwrap(), 10);
+        for col in columns {
+            assert_eq!(entries(target.as_ref(), col), entries(source.as_ref(), col));
+        }
+    }
+
+    #[test]
+    fn test_import_corrupted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("export");
+        let source = TestDB::new();
+        populate(source.as_ref(), &[DBCol::Block], 5);
+        let progress = ProgressBar::hidden();
+        export(source.as_ref(), &[DBCol::Block], &path, false, &progress).unwrap();
+
+        let mut bytes = std::fs::read(&path).unwrap();
+        let last = bytes.len() - 40;
+        bytes[last] ^= 1;
+        std::fs::write(&path, &bytes).unwrap();
+        assert!(import(TestDB::new().as_ref(), &path, None, false, &progress).is_err());
+    }
+}
@@ -0,0 +1,339 @@
+use anyhow::Context;
+use clap::Parser;
+use near_chain::{ChainStore, ChainStoreAccess, Error};
+use near_chain_configs::GenesisValidationMode;
+use near_epoch_manager::{EpochManager, EpochManagerAdapter};
+use near_primitives::block::Tip;
+use near_primitives::hash::{hash, CryptoHash};
+use near_primitives::merkle::merklize;
+use near_primitives::types::BlockHeight;
+use near_store::flat::{store_helper, FlatStorageStatus};
+use near_store::{DBCol, Mode, NodeStorage, ShardUId, Store, Trie, TrieCachingStorage};
+use std::path::Path;
+
+/// Verifies the integrity of the database: the hashes and cross-references
+/// of the headers, blocks and chunks of the canonical chain, the state roots
+/// of the final head and the consistency of the flat storage heads.
+#[derive(Parser)]
+pub(crate) struct VerifyDatabaseCommand {
+    /// Verify only the given number of the latest heights of the canonical
+    /// chain, instead of all the heights from the tail.
+    #[clap(long)]
+    num_heights: Option<u64>,
+
+    /// Repair the data which can be derived from other data: the flat storage
+    /// of the shards with an inconsistent one is removed, and the node builds
+    /// it again from the state on the next start.
+    #[clap(long)]
+    repair: bool,
+}
+
+struct Verifier {
+    chain_store: ChainStore,
+    store: Store,
+    problems: Vec<String>,
+}
+
+impl Verifier {
+    fn report(&mut self, problem: String) {
+        println!("{problem}");
+        self.problems.push(problem);
+    }
+
+    /// Returns the hash of the canonical block at the height, if any.
+    fn canonical_block_hash(&self, height: BlockHeight) -> anyhow::Result<Option<CryptoHash>> {
+        match self.chain_store.get_block_hash_by_height(height) {
+            Ok(hash) => Ok(Some(hash)),
+            Err(Error::DBNotFoundErr(_)) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn verify_chain(&mut self, start: BlockHeight, head: &Tip) -> anyhow::Result<()> {
+        let tail = self.chain_store.tail()?;
+        println!("Verifying heights {start} to {}", head.height);
+        let mut prev_hash = None;
+        for height in start..=head.height {
+            let Some(block_hash) = self.canonical_block_hash(height)? else {
+                continue;
+            };
+            if let Err(err) = self.verify_header(height, &block_hash, prev_hash) {
+                self.report(format!("Header {block_hash} at height {height}: {err:#}"));
+            }
+            // Only the headers are kept below the tail.
+            if height >= tail {
+                if let Err(err) = self.verify_block(height, &block_hash) {
+                    self.report(format!("Block {block_hash} at height {height}: {err:#}"));
+                }
+            }
+            prev_hash = Some(block_hash);
+            if height % 10000 == 0 {
+                println!("Verified height {height}");
+            }
+        }
+        Ok(())
+    }
+
+    /// Verifies the header of the canonical block at the height, and that it
+    /// links to the previous canonical block, if that one is known.
+    fn verify_header(
+        &self,
+        height: BlockHeight,
+        block_hash: &CryptoHash,
+        prev_hash: Option<CryptoHash>,
+    ) -> anyhow::Result<()> {
+        let header = self.chain_store.get_block_header(block_hash)?;
+        anyhow::ensure!(header.hash() == block_hash, "hash mismatch, computed {}", header.hash());
+        anyhow::ensure!(header.height() == height, "stored at the height {}", header.height());
+        if let Some(prev_hash) = prev_hash {
+            anyhow::ensure!(
+                header.prev_hash() == &prev_hash,
+                "previous block {} is not the previous canonical block {prev_hash}",
+                header.prev_hash()
+            );
+        }
+        Ok(())
+    }
+
+    /// Verifies the block against its header, and its new chunks against
+    /// their headers.
+    fn verify_block(&self, height: BlockHeight, block_hash: &CryptoHash) -> anyhow::Result<()> {
+        let block = self.chain_store.get_block(block_hash)?;
+        anyhow::ensure!(block.hash() == block_hash, "hash mismatch, computed {}", block.hash());
+        block.check_validity().map_err(|err| anyhow::anyhow!("invalid block: {err:?}"))?;
+        for chunk_header in block.chunks().iter() {
+            if !chunk_header.is_new_chunk(height) {
+                continue;
+            }
+            let chunk_hash = chunk_header.chunk_hash();
+            let shard_id = chunk_header.shard_id();
+            match self.chain_store.get_chunk(&chunk_hash) {
+                Ok(chunk) => {
+                    anyhow::ensure!(
+                        chunk.compute_header_hash() == chunk_hash,
+                        "chunk {chunk_hash:?} of shard {shard_id}: hash mismatch"
+                    );
+                    anyhow::ensure!(
+                        merklize(chunk.transactions()).0 == chunk_header.tx_root(),
+                        "chunk {chunk_hash:?} of shard {shard_id}: transactions don't match the tx root"
+                    );
+                }
+                // Only the parts of chunks of untracked shards are kept.
+                Err(Error::DBNotFoundErr(_)) => {
+                    let partial_chunk =
+                        self.chain_store.get_partial_chunk(&chunk_hash).with_context(|| {
+                            format!("chunk {chunk_hash:?} of shard {shard_id}: missing")
+                        })?;
+                    anyhow::ensure!(
+                        partial_chunk.chunk_hash() == chunk_hash,
+                        "partial chunk {chunk_hash:?} of shard {shard_id}: hash mismatch"
+                    );
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok(())
+    }
+
+    /// Verifies that the roots of the state of the shards at the final head
+    /// are stored, for the shards which have a chunk extra.
+    fn verify_state_roots(&mut self, final_head: &Tip, shard_uids: &[ShardUId]) {
+        for &shard_uid in shard_uids {
+            let chunk_extra =
+                match self.chain_store.get_chunk_extra(&final_head.last_block_hash, &shard_uid) {
+                    Ok(chunk_extra) => chunk_extra,
+                    // Not tracked.
+                    Err(Error::DBNotFoundErr(_)) => continue,
+                    Err(err) => {
+                        self.report(format!("Chunk extra of {shard_uid} at final head: {err}"));
+                        continue;
+                    }
+                };
+            let state_root = *chunk_extra.state_root();
+            if state_root == Trie::EMPTY_ROOT {
+                continue;
+            }
+            let key = TrieCachingStorage::get_key_from_shard_uid_and_hash(shard_uid, &state_root);
+            match self.store.get(DBCol::State, &key) {
+                Ok(Some(value)) if hash(&value) == state_root => {}
+                Ok(Some(_)) => {
+                    self.report(format!("State root {state_root} of {shard_uid}: hash mismatch"))
+                }
+                Ok(None) => self.report(format!("State root {state_root} of {shard_uid}: missing")),
+                Err(err) => self.report(format!("State root {state_root} of {shard_uid}: {err}")),
+            }
+        }
+    }
+
+    /// Verifies that the flat storage head of the shard is a final canonical
+    /// block, and that the deltas of all the canonical blocks after it up to
+    /// the final head are stored.
+    fn verify_flat_storage(&self, shard_uid: ShardUId, final_head: &Tip) -> anyhow::Result<()> {
+        let status = store_helper::get_flat_storage_status(&self.store, shard_uid)?;
+        let FlatStorageStatus::Ready(ready_status) = status else {
+            return Ok(());
+        };
+        let flat_head = ready_status.flat_head;
+        anyhow::ensure!(
+            flat_head.height <= final_head.height,
+            "flat head at the height {} is above the final head",
+            flat_head.height
+        );
+        anyhow::ensure!(
+            self.canonical_block_hash(flat_head.height)? == Some(flat_head.hash),
+            "flat head {} is not on the canonical chain",
+            flat_head.hash
+        );
+        self.chain_store
+            .get_chunk_extra(&final_head.last_block_hash, &shard_uid)
+            .context("flat storage of a shard without the chunk extra at the final head")?;
+        for height in flat_head.height + 1..=final_head.height {
+            let Some(block_hash) = self.canonical_block_hash(height)? else {
+                continue;
+            };
+            anyhow::ensure!(
+                store_helper::get_delta_changes(&self.store, shard_uid, block_hash)?.is_some(),
+                "missing delta of block {block_hash} at height {height}"
+            );
+        }
+        Ok(())
+    }
+}
+
+impl VerifyDatabaseCommand {
+    pub(crate) fn run(&self, home: &Path) -> anyhow::Result<()> {
+        let near_config = nearcore::config::load_config(home, GenesisValidationMode::UnsafeFast)
+            .unwrap_or_else(|e| panic!("Error loading config: {:#}", e));
+        let opener = NodeStorage::opener(
+            home,
+            near_config.config.archive,
+            &near_config.config.store,
+            near_config.config.cold_store.as_ref(),
+        );
+        let mode = if self.repair { Mode::ReadWriteExisting } else { Mode::ReadOnly };
+        let store = opener.open_in_mode(mode)?.get_hot_store();
+        let chain_store =
+            ChainStore::new(store.clone(), near_config.genesis.config.genesis_height, false);
+        let epoch_manager =
+            EpochManager::new_arc_handle(store.clone(), &near_config.genesis.config);
+        let mut verifier = Verifier { chain_store, store: store.clone(), problems: vec![] };
+
+        let head = verifier.chain_store.head()?;
+        let final_head = verifier.chain_store.final_head()?;
+        let tail = verifier.chain_store.tail()?;
+        let start = match self.num_heights {
+            Some(num_heights) => head.height.saturating_sub(num_heights),
+            None => tail,
+        };
+        verifier.verify_chain(start, &head)?;
+
+        let shard_uids: Vec<_> =
+            epoch_manager.get_shard_layout(&final_head.epoch_id)?.shard_uids().collect();
+        println!("Verifying the state roots at the final head {}", final_head.last_block_hash);
+        verifier.verify_state_roots(&final_head, &shard_uids);
+
+        let mut repaired = 0;
+        for shard_uid in shard_uids {
+            println!("Verifying the flat storage of {shard_uid}");
+            let Err(err) = verifier.verify_flat_storage(shard_uid, &final_head) else {
+                continue;
+            };
+            if !self.repair {
+                verifier.report(format!("Flat storage of {shard_uid}: {err:#}"));
+                continue;
+            }
+            println!("Flat storage of {shard_uid}: {err:#}, removing it to be rebuilt");
+            let mut store_update = store.store_update();
+            store_helper::remove_all_deltas(&mut store_update, shard_uid);
+            store_helper::remove_all_flat_state_values(&mut store_update, shard_uid);
+            store_helper::set_flat_storage_status(
+                &mut store_update,
+                shard_uid,
+                FlatStorageStatus::Empty,
+            );
+            store_update.commit()?;
+            repaired += 1;
+        }
+
+        if repaired > 0 {
+            println!("Removed {repaired} flat storages, the node rebuilds them on the next start");
+        }
+        if !verifier.problems.is_empty() {
+            anyhow::bail!("Found {} problems with the database", verifier.problems.len());
+        }
+        println!("No problems found");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Verifier;
+    use near_async::time::{Duration, FakeClock, Utc};
+    use near_chain::test_utils::{process_block_sync, setup};
+    use near_chain::{BlockProcessingArtifact, ChainStore, ChainStoreAccess, Provenance};
+    use near_epoch_manager::EpochManagerAdapter;
+    use near_primitives::test_utils::TestBlockBuilder;
+    use near_primitives::utils::MaybeValidated;
+    use near_store::{DBCol, TrieCachingStorage};
+
+    /// Builds a chain, checks that it verifies, then corrupts a header and
+    /// removes a state root and checks that both are reported.
+    #[test]
+    fn test_verify_database() {
+        let clock = FakeClock::new(Utc::from_unix_timestamp(1601510400).unwrap());
+        let (mut chain, epoch_manager, _, signer) = setup(clock.clock());
+        for _ in 0..5 {
+            clock.advance(Duration::milliseconds(1));
+            let prev = chain.get_block(&chain.head().unwrap().last_block_hash).unwrap();
+            let block = TestBlockBuilder::new(clock.clock(), &prev, signer.clone()).build();
+            process_block_sync(
+                &mut chain,
+                &None,
+                MaybeValidated::from(block),
+                Provenance::PRODUCED,
+                &mut BlockProcessingArtifact::default(),
+            )
+            .unwrap();
+        }
+        let store = chain.chain_store().store().clone();
+        let head = chain.head().unwrap();
+        let final_head = chain.chain_store().final_head().unwrap();
+        let shard_uids: Vec<_> =
+            epoch_manager.get_shard_layout(&final_head.epoch_id).unwrap().shard_uids().collect();
+        let verifier = |store: &near_store::Store| Verifier {
+            chain_store: ChainStore::new(store.clone(), 0, false),
+            store: store.clone(),
+            problems: vec![],
+        };
+
+        let mut clean = verifier(&store);
+        clean.verify_chain(0, &head).unwrap();
+        clean.verify_state_roots(&final_head, &shard_uids);
+        assert_eq!(clean.problems, Vec::<String>::new());
+
+        let header_2 = chain.get_block_header(&chain.get_block_hash_by_height(2).unwrap()).unwrap();
+        let hash_3 = chain.get_block_hash_by_height(3).unwrap();
+        let chunk_extra = chain
+            .chain_store()
+            .get_chunk_extra(&final_head.last_block_hash, &shard_uids[0])
+            .unwrap();
+        let mut store_update = store.store_update();
+        store_update.set_ser(DBCol::BlockHeader, hash_3.as_ref(), &header_2).unwrap();
+        store_update.delete(
+            DBCol::State,
+            &TrieCachingStorage::get_key_from_shard_uid_and_hash(
+                shard_uids[0],
+                chunk_extra.state_root(),
+            ),
+        );
+        store_update.commit().unwrap();
+
+        let mut corrupted = verifier(&store);
+        corrupted.verify_chain(0, &head).unwrap();
+        corrupted.verify_state_roots(&final_head, &shard_uids);
+        assert_eq!(corrupted.problems.len(), 2, "{:?}", corrupted.problems);
+        assert!(corrupted.problems[0].starts_with(&format!("Header {hash_3} at height 3")));
+        assert!(corrupted.problems[1].contains("missing"));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::progress::Progress;
 use crate::single_shard_storage_mutator::SingleShardStorageMutator;
 use crate::storage_mutator::StorageMutator;
 use anyhow::Context;
@@ -28,8 +29,8 @@ use near_primitives::version::{ProtocolVersion, PROTOCOL_VERSION};
 use near_store::db::RocksDB;
 use near_store::flat::{store_helper, BlockInfo, FlatStorageManager, FlatStorageStatus};
 use near_store::{
-    checkpoint_hot_storage_and_cleanup_columns, DBCol, Store, TrieDBStorage, TrieStorage,
-    FINAL_HEAD_KEY,
+    checkpoint_hot_storage_and_cleanup_columns, DBCol, ShardTries, Store, Trie, TrieDBStorage,
+    TrieStorage, FINAL_HEAD_KEY,
 };
 use nearcore::{load_config, open_storage, NearConfig, NightshadeRuntime, NightshadeRuntimeExt};
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
@@ -63,6 +64,8 @@ enum SubCommand {
 
     /// Creates a DB snapshot, then
     /// Updates the state to ensure every account has a full access key that is known to us.
+    /// The changes are written to the flat storage, and the trie of every shard is then
+    /// built from scratch from its flat storage, with all shards in parallel.
     AmendAccessKeys(AmendAccessKeysCmd),
 
     /// Creates a DB snapshot, then
@@ -315,11 +318,10 @@ impl ForkNetworkCommand {
         home_dir: &Path,
     ) -> anyhow::Result<Vec<StateRoot>> {
         // Open storage with migration
-        near_config.config.store.load_mem_tries_for_tracked_shards = true;
         let storage = open_storage(&home_dir, near_config).unwrap();
         let store = storage.get_hot_store();
 
-        let (prev_state_roots, prev_hash, epoch_id, block_height) =
+        let (prev_state_roots, prev_hash, epoch_id, _block_height) =
             self.get_state_roots_and_hash(store.clone())?;
         tracing::info!(?prev_state_roots, ?epoch_id, ?prev_hash);
 
@@ -332,19 +334,21 @@ impl ForkNetworkCommand {
         let runtime =
             NightshadeRuntime::from_config(home_dir, store.clone(), &near_config, epoch_manager)
                 .context("could not create the transaction runtime")?;
-        runtime.get_tries().load_mem_tries_for_enabled_shards(&all_shard_uids).unwrap();
+        let shard_tries = runtime.get_tries();
 
+        // Both the flat state and the trie nodes are written by ingesting SST
+        // files, which is much faster than going through the write-ahead log.
         let make_storage_mutator: MakeSingleShardStorageMutatorFn =
             Arc::new(move |prev_state_root| {
-                SingleShardStorageMutator::new(&runtime.clone(), prev_state_root)
+                SingleShardStorageMutator::new(&runtime.clone(), prev_state_root, true)
             });
 
         let new_state_roots = self.prepare_state(
             batch_size,
             &all_shard_uids,
             store,
+            &shard_tries,
             &prev_state_roots,
-            block_height,
             make_storage_mutator.clone(),
         )?;
         Ok(new_state_roots)
@@ -483,19 +487,27 @@ impl ForkNetworkCommand {
         return Ok(());
     }
 
+    /// Rewrites the flat state of the shard so that every account has a full
+    /// access key known to us, then builds the trie of the shard from the new
+    /// flat state.
     fn prepare_shard_state(
         &self,
         batch_size: u64,
         shard_uid: ShardUId,
         store: Store,
+        shard_tries: &ShardTries,
         prev_state_root: StateRoot,
-        block_height: BlockHeight,
         make_storage_mutator: MakeSingleShardStorageMutatorFn,
     ) -> anyhow::Result<StateRoot> {
         // Doesn't support secrets.
         tracing::info!(?shard_uid);
         let mut storage_mutator: SingleShardStorageMutator = make_storage_mutator(prev_state_root)?;
 
+        // Counting the entries is fast compared to the passes below, and gives
+        // the estimates of the remaining time.
+        let num_entries =
+            store_helper::iter_flat_state_entries(shard_uid, &store, None, None).count() as u64;
+
         // TODO: allow mutating the state with a secret, so this can be used to prepare a public test network
         let default_key = near_mirror::key_mapping::default_extra_key(None).public_key();
         // Keeps track of accounts that have a full access key.
@@ -514,8 +526,9 @@ impl ForkNetworkCommand {
         let mut contract_code_updated = 0;
         let mut postponed_receipts_updated = 0;
         let mut received_data_updated = 0;
-        let mut fake_block_height = block_height + 1;
+        let mut progress = Progress::new(shard_uid, "amending the state", num_entries);
         for item in store_helper::iter_flat_state_entries(shard_uid, &store, None, None) {
+            progress.inc();
             let (key, value) = match item {
                 Ok((key, FlatStateValue::Ref(ref_value))) => {
                     ref_keys_retrieved += 1;
@@ -610,11 +623,11 @@ impl ForkNetworkCommand {
                         + index_delayed_receipt
                         + received_data_updated,
                 );
-                let state_root = storage_mutator.commit(&shard_uid, fake_block_height)?;
-                fake_block_height += 1;
-                storage_mutator = make_storage_mutator(state_root)?;
+                storage_mutator.commit_to_flat_state(&shard_uid)?;
+                storage_mutator = make_storage_mutator(prev_state_root)?;
             }
         }
+        progress.finish();
 
         tracing::info!(
             ?shard_uid,
@@ -637,7 +650,9 @@ impl ForkNetworkCommand {
         // Iterating over the whole flat state is very fast compared to writing all the updates.
         let mut num_added = 0;
         let mut num_accounts = 0;
+        let mut progress = Progress::new(shard_uid, "adding full access keys", num_entries);
         for item in store_helper::iter_flat_state_entries(shard_uid, &store, None, None) {
+            progress.inc();
             if let Ok((key, _)) = item {
                 if key[0] == col::ACCOUNT {
                     num_accounts += 1;
@@ -662,18 +677,76 @@ impl ForkNetworkCommand {
                     )?;
                     num_added += 1;
                     if storage_mutator.should_commit(batch_size) {
-                        let state_root = storage_mutator.commit(&shard_uid, fake_block_height)?;
-                        fake_block_height += 1;
-                        storage_mutator = make_storage_mutator(state_root)?;
+                        storage_mutator.commit_to_flat_state(&shard_uid)?;
+                        storage_mutator = make_storage_mutator(prev_state_root)?;
                     }
                 }
             }
         }
+        storage_mutator.commit_to_flat_state(&shard_uid)?;
+        progress.finish();
         tracing::info!(?shard_uid, num_accounts, num_added, "Pass 2 done");
 
-        let state_root = storage_mutator.commit(&shard_uid, fake_block_height)?;
+        let state_root =
+            self.build_trie_from_flat_state(batch_size, shard_uid, &store, shard_tries)?;
+        let mut update = store.store_update();
+        update.set_ser(
+            DBCol::Misc,
+            format!("FORK_TOOL_SHARD_ID:{}", shard_uid.shard_id).as_bytes(),
+            &state_root,
+        )?;
+        update.commit()?;
 
-        tracing::info!(?shard_uid, "Commit done");
+        tracing::info!(?shard_uid, ?state_root, "Commit done");
+        Ok(state_root)
+    }
+
+    /// Builds the trie of the shard from scratch from its flat state and
+    /// returns the state root. The entries come sorted by key, so each batch
+    /// only touches the rightmost path of the trie built so far, which is much
+    /// cheaper than updating the original trie in place. The nodes are written
+    /// by ingesting SST files, the nodes of the original trie stay in the
+    /// database.
+    fn build_trie_from_flat_state(
+        &self,
+        batch_size: u64,
+        shard_uid: ShardUId,
+        store: &Store,
+        shard_tries: &ShardTries,
+    ) -> anyhow::Result<StateRoot> {
+        let num_entries =
+            store_helper::iter_flat_state_entries(shard_uid, store, None, None).count() as u64;
+        let trie_storage = TrieDBStorage::new(store.clone(), shard_uid);
+        let mut progress = Progress::new(shard_uid, "building the trie", num_entries);
+        let mut state_root = Trie::EMPTY_ROOT;
+        let mut batch = Vec::new();
+        let mut entries = store_helper::iter_flat_state_entries(shard_uid, store, None, None);
+        loop {
+            let entry = entries.next().transpose()?;
+            let is_last = entry.is_none();
+            if let Some((key, value)) = entry {
+                let value = match value {
+                    FlatStateValue::Ref(ref_value) => {
+                        trie_storage.retrieve_raw_bytes(&ref_value.hash)?.to_vec()
+                    }
+                    FlatStateValue::Inlined(value) => value,
+                };
+                batch.push((key, Some(value)));
+                progress.inc();
+            }
+            if batch.len() >= batch_size as usize || (is_last && !batch.is_empty()) {
+                let trie_changes = shard_tries
+                    .get_trie_for_shard(shard_uid, state_root)
+                    .update(std::mem::take(&mut batch))?;
+                let mut update = shard_tries.store_update();
+                state_root = shard_tries.apply_all(&trie_changes, shard_uid, &mut update);
+                update.commit_bulk()?;
+            }
+            if is_last {
+                break;
+            }
+        }
+        progress.finish();
         Ok(state_root)
     }
 
@@ -682,8 +755,8 @@ impl ForkNetworkCommand {
         batch_size: u64,
         all_shard_uids: &[ShardUId],
         store: Store,
+        shard_tries: &ShardTries,
         prev_state_roots: &[StateRoot],
-        block_height: BlockHeight,
         make_storage_mutator: MakeSingleShardStorageMutatorFn,
     ) -> anyhow::Result<Vec<StateRoot>> {
         let state_roots = all_shard_uids
@@ -694,8 +767,8 @@ impl ForkNetworkCommand {
                         batch_size,
                         *shard_uid,
                         store.clone(),
+                        shard_tries,
                         prev_state_roots[shard_uid.shard_id as usize],
-                        block_height,
                         make_storage_mutator.clone(),
                     )
                     .unwrap();
@@ -826,6 +899,7 @@ impl ForkNetworkCommand {
             num_chunk_validator_seats: original_config.num_chunk_validator_seats,
             chunk_producer_assignment_changes_limit: original_config
                 .chunk_producer_assignment_changes_limit,
+            congestion_control_overrides: original_config.congestion_control_overrides.clone(),
         };
 
         let genesis = Genesis::new_from_state_roots(new_config, new_state_roots);
@@ -1,3 +1,4 @@
 pub mod cli;
+mod progress;
 mod single_shard_storage_mutator;
 mod storage_mutator;
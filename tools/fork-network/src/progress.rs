@@ -0,0 +1,68 @@
+use near_primitives::shard_layout::ShardUId;
+use std::time::{Duration, Instant};
+
+/// How often the progress is logged.
+const LOG_PERIOD: Duration = Duration::from_secs(30);
+
+/// Tracks how many of the flat state entries of a shard a stage of the
+/// preparation has processed, and periodically logs the progress with an
+/// estimate of the remaining time.
+pub(crate) struct Progress {
+    shard_uid: ShardUId,
+    stage: &'static str,
+    total: u64,
+    processed: u64,
+    start: Instant,
+    last_log: Instant,
+}
+
+impl Progress {
+    pub(crate) fn new(shard_uid: ShardUId, stage: &'static str, total: u64) -> Self {
+        let now = Instant::now();
+        tracing::info!(?shard_uid, stage, total, "Starting");
+        Self { shard_uid, stage, total, processed: 0, start: now, last_log: now }
+    }
+
+    pub(crate) fn inc(&mut self) {
+        self.processed += 1;
+        if self.last_log.elapsed() >= LOG_PERIOD {
+            self.last_log = Instant::now();
+            self.log();
+        }
+    }
+
+    /// Estimates the remaining time from the average rate so far.
+    fn eta(&self) -> Option<Duration> {
+        if self.processed == 0 {
+            return None;
+        }
+        let remaining = self.total.saturating_sub(self.processed);
+        Some(self.start.elapsed().mul_f64(remaining as f64 / self.processed as f64))
+    }
+
+    fn log(&self) {
+        let percent = 100.0 * self.processed as f64 / self.total.max(1) as f64;
+        let elapsed = self.start.elapsed();
+        let rate = self.processed as f64 / elapsed.as_secs_f64().max(f64::EPSILON);
+        tracing::info!(
+            shard_uid = ?self.shard_uid,
+            stage = self.stage,
+            processed = self.processed,
+            total = self.total,
+            percent = format!("{percent:.1}"),
+            per_second = format!("{rate:.0}"),
+            eta_seconds = self.eta().map(|eta| eta.as_secs()),
+            "Progress"
+        );
+    }
+
+    pub(crate) fn finish(self) {
+        tracing::info!(
+            shard_uid = ?self.shard_uid,
+            stage = self.stage,
+            processed = self.processed,
+            elapsed_seconds = self.start.elapsed().as_secs(),
+            "Done"
+        );
+    }
+}
@@ -5,10 +5,12 @@ use near_primitives::borsh;
 use near_primitives::hash::CryptoHash;
 use near_primitives::receipt::Receipt;
 use near_primitives::shard_layout::ShardUId;
+use near_primitives::state::FlatStateValue;
 use near_primitives::trie_key::TrieKey;
 use near_primitives::types::{AccountId, StateRoot};
 use near_primitives::types::{StoreKey, StoreValue};
-use near_store::{flat::FlatStateChanges, DBCol, ShardTries};
+use near_store::flat::{store_helper, FlatStateChanges};
+use near_store::{DBCol, ShardTries};
 use nearcore::NightshadeRuntime;
 
 /// Object that updates the existing state. Combines all changes, commits them
@@ -17,11 +19,17 @@ pub(crate) struct SingleShardStorageMutator {
     updates: Vec<(Vec<u8>, Option<Vec<u8>>)>,
     state_root: StateRoot,
     shard_tries: ShardTries,
+    /// Whether to commit with `StoreUpdate::commit_bulk`.
+    bulk_import: bool,
 }
 
 impl SingleShardStorageMutator {
-    pub(crate) fn new(runtime: &NightshadeRuntime, state_root: StateRoot) -> anyhow::Result<Self> {
-        Ok(Self { updates: Vec::new(), state_root, shard_tries: runtime.get_tries() })
+    pub(crate) fn new(
+        runtime: &NightshadeRuntime,
+        state_root: StateRoot,
+        bulk_import: bool,
+    ) -> anyhow::Result<Self> {
+        Ok(Self { updates: Vec::new(), state_root, shard_tries: runtime.get_tries(), bulk_import })
     }
 
     fn set(&mut self, key: TrieKey, value: Vec<u8>) -> anyhow::Result<()> {
@@ -141,6 +149,29 @@ impl SingleShardStorageMutator {
         self.updates.len() >= batch_size as usize
     }
 
+    /// Writes the changes to the flat state only, leaving the trie as it is.
+    /// The values are inlined because the trie doesn't have the new ones, the
+    /// trie is built from the flat state afterwards.
+    pub(crate) fn commit_to_flat_state(self, shard_uid: &ShardUId) -> anyhow::Result<()> {
+        let num_updates = self.updates.len();
+        tracing::debug!(?shard_uid, num_updates, "commit to flat state");
+        let mut update = self.shard_tries.store_update();
+        for (key, value) in self.updates {
+            store_helper::set_flat_state_value(
+                &mut update,
+                *shard_uid,
+                key,
+                value.map(FlatStateValue::Inlined),
+            );
+        }
+        if self.bulk_import {
+            update.commit_bulk()?;
+        } else {
+            update.commit()?;
+        }
+        Ok(())
+    }
+
     /// The fake block height is used to allow memtries to garbage collect.
     /// Otherwise it would take significantly more memory holding old nodes.
     pub(crate) fn commit(
@@ -176,7 +207,11 @@ impl SingleShardStorageMutator {
             &state_root,
         )?;
 
-        update.commit()?;
+        if self.bulk_import {
+            update.commit_bulk()?;
+        } else {
+            update.commit()?;
+        }
         tracing::info!(?shard_uid, ?state_root, "Commit is done");
         Ok(state_root)
     }
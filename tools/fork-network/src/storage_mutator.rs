@@ -26,7 +26,7 @@ impl StorageMutator {
 
         let mut mutators = vec![];
         for state_root in state_roots {
-            mutators.push(SingleShardStorageMutator::new(runtime, state_root)?);
+            mutators.push(SingleShardStorageMutator::new(runtime, state_root, false)?);
         }
         Ok(Self { epoch_manager, epoch_id, mutators })
     }
@@ -7,13 +7,14 @@ use near_chain::{Block, Chain, ChainStoreAccess, Error};
 use near_client::sync::header::MAX_BLOCK_HEADERS;
 use near_crypto::SecretKey;
 use near_network::raw::{DirectMessage, Listener, Message, RoutedMessage};
+use near_network::recorder::{read_recording, RecordedMessage, RecordedMessageBody};
 use near_network::tcp;
 use near_network::types::{PartialEncodedChunkRequestMsg, PartialEncodedChunkResponseMsg};
 use near_primitives::sharding::ChunkHash;
 use near_primitives::types::{BlockHeight, ShardId};
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::future::Future;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use std::task::Poll;
 use std::time::Duration;
@@ -42,6 +43,10 @@ pub struct MockNetworkConfig {
     // How long we'll wait until sending replies to the client
     pub response_delay: Duration,
     pub incoming_requests: Option<MockIncomingRequestsConfig>,
+    // Recording made with `network.experimental.record_messages` to send to
+    // the client instead of producing blocks
+    #[serde(default)]
+    pub replay: Option<PathBuf>,
 }
 
 impl MockNetworkConfig {
@@ -65,7 +70,7 @@ fn default_delay() -> Duration {
 
 impl Default for MockNetworkConfig {
     fn default() -> Self {
-        Self { response_delay: default_delay(), incoming_requests: None }
+        Self { response_delay: default_delay(), incoming_requests: None, replay: None }
     }
 }
 
@@ -213,6 +218,46 @@ impl IncomingRequests {
     }
 }
 
+// The messages of a recording, sent with the same delays between them as they
+// were received with
+struct Replay {
+    messages: VecDeque<RecordedMessage>,
+    started_at: tokio::time::Instant,
+    first_received_at: u64,
+}
+
+impl Replay {
+    fn new(path: &Path) -> anyhow::Result<Self> {
+        let messages = read_recording(path)
+            .with_context(|| format!("failed reading recording {}", path.display()))?;
+        tracing::info!(target: "mock_node", ?path, num_messages = messages.len(), "Replaying recorded messages");
+        let first_received_at = messages.first().map_or(0, |msg| msg.received_at);
+        Ok(Self {
+            messages: messages.into(),
+            started_at: tokio::time::Instant::now(),
+            first_received_at,
+        })
+    }
+
+    // Cancel safe, the message is only removed once it's due.
+    async fn next(&mut self) -> RecordedMessageBody {
+        let Some(msg) = self.messages.front() else {
+            tracing::info!(target: "mock_node", "All recorded messages replayed");
+            return futures::future::pending().await;
+        };
+        let delay = Duration::from_nanos(msg.received_at.saturating_sub(self.first_received_at));
+        tokio::time::sleep_until(self.started_at + delay).await;
+        self.messages.pop_front().unwrap().body
+    }
+}
+
+async fn next_replayed(r: Option<&mut Replay>) -> RecordedMessageBody {
+    match r {
+        Some(r) => r.next().await,
+        None => futures::future::pending().await,
+    }
+}
+
 struct InFlightMessage {
     message: Message,
     sent_at: tokio::time::Instant,
@@ -283,6 +328,7 @@ struct MockPeer {
     network_config: MockNetworkConfig,
     block_production: tokio::time::Interval,
     incoming_requests: IncomingRequests,
+    replay: Option<Replay>,
 }
 
 impl MockPeer {
@@ -310,6 +356,7 @@ impl MockPeer {
         .await?;
         let incoming_requests =
             IncomingRequests::new(&network_config.incoming_requests, &chain, network_start_height);
+        let replay = network_config.replay.as_deref().map(Replay::new).transpose()?;
         // make sure we start at a height that actually exists, because we want self.produce_block()
         // to give the first block immediately. Otherwise the node won't even try asking us for block headers
         // until we give it a block.
@@ -336,6 +383,7 @@ impl MockPeer {
             network_config,
             block_production: tokio::time::interval(block_production_delay),
             incoming_requests,
+            replay,
         })
     }
 
@@ -430,6 +478,10 @@ impl MockPeer {
         let mut conn = self.listener.accept().await?;
         let messages = InFlightMessages::new(self.network_config.response_delay);
         tokio::pin!(messages);
+        let mut replay = self.replay.take();
+        if let Some(replay) = &mut replay {
+            replay.started_at = tokio::time::Instant::now();
+        }
 
         loop {
             tokio::select! {
@@ -445,10 +497,18 @@ impl MockPeer {
                         Message::Routed(msg) => conn.send_routed_message(msg, conn.peer_id().clone(), 100).await?,
                     };
                 }
-                msg = self.incoming_message(target_height) => {
+                msg = self.incoming_message(target_height), if replay.is_none() => {
                     let msg = msg?;
                     messages.as_mut().queue_message(msg);
                 }
+                msg = next_replayed(replay.as_mut()) => {
+                    if let RecordedMessageBody::Block(block) = &msg {
+                        // Serve the requests of the client up to the replayed blocks.
+                        self.current_height = self.current_height.max(block.header().height());
+                    }
+                    tracing::debug!("mock peer replaying message {}", <&'static str>::from(&msg));
+                    conn.send_recorded_message(msg, 100).await?;
+                }
             }
         }
     }
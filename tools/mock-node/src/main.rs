@@ -46,6 +46,11 @@ use std::time::{Duration, Instant};
 ///
 /// # Mixed: client starts at genesis and tries to catch up with the network, which starts at height 20.
 /// $ mock-node ~/.near/localnet/node0 --network-height 20
+///
+/// # Replay the messages node0 received from height 61 on, recorded with
+/// # "record_messages": {"path": "/tmp/messages", "start_height": 61, "end_height": 80}
+/// # in the "experimental" section of its network config.
+/// $ mock-node ~/.near/localnet/node0 --client-height 60 --replay /tmp/messages
 /// ```
 #[derive(clap::Parser)]
 struct Cli {
@@ -83,6 +88,11 @@ struct Cli {
     /// port the mock node should listen on
     #[clap(long)]
     mock_port: Option<u16>,
+    /// Recording of the messages received by a node, made with
+    /// `network.experimental.record_messages` in its config, which the mock
+    /// network sends to the client instead of producing blocks.
+    #[clap(long)]
+    replay: Option<PathBuf>,
 }
 
 async fn target_height_reached(client: &JsonRpcClient, target_height: BlockHeight) -> bool {
@@ -136,6 +146,9 @@ fn main() -> anyhow::Result<()> {
     if let Some(delay) = args.network_delay {
         network_config.response_delay = Duration::from_millis(delay);
     }
+    if let Some(replay) = args.replay {
+        network_config.replay = Some(replay);
+    }
 
     let client_height = args.start_height.unwrap_or(args.client_height);
     let network_height = args.start_height.or(args.network_height);
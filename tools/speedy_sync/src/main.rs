@@ -252,6 +252,7 @@ fn load_snapshot(load_cmd: LoadCmd) {
                 ReshardingConfig::default(),
                 "resharding_config",
             ),
+            save_account_index: false,
         },
         None,
         Arc::new(RayonAsyncComputationSpawner),
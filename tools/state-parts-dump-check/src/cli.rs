@@ -221,7 +221,7 @@ fn create_external_connection(
     } else if let (Some(bucket), Some(region)) = (bucket, region) {
         let bucket = create_bucket_readonly(&bucket, &region, Duration::from_secs(5))
             .expect("Failed to create an S3 bucket");
-        ExternalConnection::S3 { bucket: Arc::new(bucket) }
+        ExternalConnection::S3 { bucket: Arc::new(bucket), upload: Default::default() }
     } else if let Some(bucket) = gcs_bucket {
         ExternalConnection::GCS {
             gcs_client: Arc::new(cloud_storage::Client::default()),
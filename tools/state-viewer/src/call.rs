@@ -0,0 +1,297 @@
+//! Local execution of a contract call against the state after a block.
+//!
+//! The call is applied as a function call receipt on top of the post-state of
+//! the block, as if it was included in the next chunk of the shard. Nothing is
+//! written to the store, so the command can be used on a copy of the data of
+//! a production node to debug the contracts offline.
+//!
+//! The delayed receipts of the shard are applied before the call, the same way
+//! they would be in the next chunk, but the chunk gets no gas limit so that
+//! the call is executed even if the delayed receipts use up the gas of a real
+//! chunk. Only the outcome of the call and the state changes it caused are
+//! printed.
+use anyhow::Context;
+use clap::Parser;
+use near_chain::types::{
+    ApplyChunkBlockContext, ApplyChunkShardContext, RuntimeAdapter, RuntimeStorageConfig,
+};
+use near_chain::{ChainStore, ChainStoreAccess};
+use near_crypto::{KeyType, PublicKey};
+use near_epoch_manager::{EpochManager, EpochManagerAdapter};
+use near_primitives::action::{Action, FunctionCallAction};
+use near_primitives::apply::ApplyChunkReason;
+use near_primitives::hash::{hash, CryptoHash};
+use near_primitives::merkle::combine_hash;
+use near_primitives::receipt::{ActionReceipt, Receipt, ReceiptEnum, ReceiptV0};
+use near_primitives::serialize::to_base64;
+use near_primitives::transaction::ExecutionOutcomeWithId;
+use near_primitives::types::{
+    AccountId, Balance, BlockHeight, Gas, RawStateChangesWithTrieKey, StateChangeCause,
+};
+use near_primitives::views::{ExecutionOutcomeView, ExecutionStatusView};
+use near_store::Store;
+use nearcore::{NearConfig, NightshadeRuntime, NightshadeRuntimeExt};
+use std::path::Path;
+
+/// Receipt id of the call.
+fn call_receipt_id() -> CryptoHash {
+    hash(b"state-viewer call")
+}
+
+/// Whether the state change was caused by processing the receipt.
+fn is_caused_by(cause: &StateChangeCause, receipt_id: &CryptoHash) -> bool {
+    match cause {
+        StateChangeCause::ActionReceiptProcessingStarted { receipt_hash }
+        | StateChangeCause::ActionReceiptGasReward { receipt_hash }
+        | StateChangeCause::ReceiptProcessing { receipt_hash }
+        | StateChangeCause::PostponedReceipt { receipt_hash } => receipt_hash == receipt_id,
+        _ => false,
+    }
+}
+
+/// Result of the call executed on top of a block.
+struct CallResult {
+    block_hash: CryptoHash,
+    block_height: BlockHeight,
+    outcome: ExecutionOutcomeWithId,
+    /// Changes of the state caused by the call, with the intermediate values
+    /// of the keys the call changed several times.
+    state_changes: Vec<RawStateChangesWithTrieKey>,
+}
+
+#[derive(Parser)]
+pub struct CallCmd {
+    /// Account of the contract to call.
+    #[clap(long)]
+    account: AccountId,
+    /// Method to call.
+    #[clap(long)]
+    method: String,
+    /// Arguments of the call, passed to the contract as is.
+    #[clap(long, default_value = "{}")]
+    args: String,
+    /// Account the call is made by. Defaults to the contract account.
+    #[clap(long)]
+    signer: Option<AccountId>,
+    /// Attached deposit in yoctoNEAR.
+    #[clap(long, default_value = "0")]
+    deposit: Balance,
+    /// Attached gas.
+    #[clap(long, default_value = "300000000000000")]
+    gas: Gas,
+    /// Height of the block to execute the call after. Defaults to the head.
+    #[clap(long)]
+    height: Option<BlockHeight>,
+    #[clap(long)]
+    use_flat_storage: bool,
+}
+
+impl CallCmd {
+    pub(crate) fn run(self, home_dir: &Path, near_config: NearConfig, store: Store) {
+        self.call(home_dir, near_config, store).unwrap();
+    }
+
+    fn call(self, home_dir: &Path, near_config: NearConfig, store: Store) -> anyhow::Result<()> {
+        let chain_store =
+            ChainStore::new(store.clone(), near_config.genesis.config.genesis_height, false);
+        let epoch_manager =
+            EpochManager::new_arc_handle(store.clone(), &near_config.genesis.config);
+        let runtime =
+            NightshadeRuntime::from_config(home_dir, store, &near_config, epoch_manager.clone())?;
+        let result = self.execute(&chain_store, epoch_manager.as_ref(), runtime.as_ref())?;
+
+        println!("Executed after block {} at height {}", result.block_hash, result.block_height);
+        let outcome = result.outcome;
+        let view = ExecutionOutcomeView::from(outcome.outcome);
+        if let ExecutionStatusView::SuccessValue(value) = &view.status {
+            match std::str::from_utf8(value) {
+                Ok(value) => println!("Result of {}: {value}", outcome.id),
+                Err(_) => println!("Result of {}: {}", outcome.id, to_base64(value)),
+            }
+        }
+        println!("Outcome {}:\n{}", outcome.id, serde_json::to_string_pretty(&view)?);
+        println!("State changes:");
+        for change in &result.state_changes {
+            match change.changes.last().and_then(|change| change.data.as_ref()) {
+                Some(value) => println!("  {:?}: {}", change.trie_key, to_base64(value)),
+                None => println!("  {:?}: deleted", change.trie_key),
+            }
+        }
+        Ok(())
+    }
+
+    fn execute(
+        self,
+        chain_store: &ChainStore,
+        epoch_manager: &dyn EpochManagerAdapter,
+        runtime: &dyn RuntimeAdapter,
+    ) -> anyhow::Result<CallResult> {
+        let block_hash = match self.height {
+            Some(height) => chain_store.get_block_hash_by_height(height)?,
+            None => chain_store.head()?.last_block_hash,
+        };
+        let block = chain_store.get_block(&block_hash)?;
+        let epoch_id = epoch_manager.get_epoch_id_from_prev_block(&block_hash)?;
+        let shard_id = epoch_manager.account_id_to_shard_id(&self.account, &epoch_id)?;
+        let shard_uid = epoch_manager.shard_id_to_uid(shard_id, &epoch_id)?;
+        let chunk_extra = chain_store
+            .get_chunk_extra(&block_hash, &shard_uid)
+            .with_context(|| format!("shard {shard_id} is not tracked at block {block_hash}"))?;
+        if self.use_flat_storage {
+            runtime.get_flat_storage_manager().create_flat_storage_for_shard(shard_uid)?;
+        }
+
+        let gas_price = block.header().next_gas_price();
+        let signer_id = self.signer.unwrap_or_else(|| self.account.clone());
+        let receipt = Receipt::V0(ReceiptV0 {
+            predecessor_id: signer_id.clone(),
+            receiver_id: self.account,
+            receipt_id: call_receipt_id(),
+            receipt: ReceiptEnum::Action(ActionReceipt {
+                signer_id,
+                signer_public_key: PublicKey::empty(KeyType::ED25519),
+                gas_price,
+                output_data_receivers: vec![],
+                input_data_ids: vec![],
+                actions: vec![Action::FunctionCall(Box::new(FunctionCallAction {
+                    method_name: self.method,
+                    args: self.args.into_bytes(),
+                    gas: self.gas,
+                    deposit: self.deposit,
+                }))],
+            }),
+        });
+
+        let result = runtime.apply_chunk(
+            RuntimeStorageConfig::new(*chunk_extra.state_root(), self.use_flat_storage),
+            ApplyChunkReason::UpdateTrackedShard,
+            ApplyChunkShardContext {
+                shard_id,
+                last_validator_proposals: chunk_extra.validator_proposals(),
+                // The delayed receipts are applied first and could use up the
+                // gas limit of the chunk, which would delay the call as well.
+                gas_limit: Gas::MAX,
+                is_first_block_with_chunk_of_version: false,
+                is_new_chunk: true,
+            },
+            ApplyChunkBlockContext {
+                height: block.header().height() + 1,
+                block_timestamp: block.header().raw_timestamp() + 1_000_000_000,
+                challenges_result: vec![],
+                prev_block_hash: block_hash,
+                block_hash: combine_hash(&block_hash, &hash(b"state-viewer call block")),
+                gas_price,
+                random_seed: hash(b"state-viewer call"),
+                congestion_info: block.shards_congestion_info(),
+            },
+            &[receipt],
+            &[],
+        )?;
+
+        let receipt_id = call_receipt_id();
+        let outcome = result
+            .outcomes
+            .into_iter()
+            .find(|outcome| outcome.id == receipt_id)
+            .context("the call was not executed")?;
+        let state_changes = result
+            .trie_changes
+            .state_changes()
+            .iter()
+            .filter_map(|change| {
+                let changes: Vec<_> = change
+                    .changes
+                    .iter()
+                    .filter(|change| is_caused_by(&change.cause, &receipt_id))
+                    .cloned()
+                    .collect();
+                (!changes.is_empty()).then(|| RawStateChangesWithTrieKey {
+                    trie_key: change.trie_key.clone(),
+                    changes,
+                })
+            })
+            .collect();
+        Ok(CallResult { block_hash, block_height: block.header().height(), outcome, state_changes })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CallCmd;
+    use near_chain::types::RuntimeAdapter;
+    use near_chain::ChainStore;
+    use near_chain_configs::Genesis;
+    use near_client::test_utils::TestEnv;
+    use near_epoch_manager::EpochManager;
+    use near_primitives::hash::hash;
+    use near_primitives::state_record::StateRecord;
+    use near_primitives::transaction::ExecutionStatus;
+    use near_primitives::types::AccountId;
+    use near_store::genesis::initialize_genesis_state;
+    use nearcore::NightshadeRuntime;
+    use std::sync::Arc;
+
+    /// Calls the test contract deployed at genesis and checks that only the
+    /// outcome of the call and its state changes are returned.
+    #[test]
+    fn test_call() {
+        near_o11y::testonly::init_test_logger();
+        let account_id: AccountId = "test0".parse().unwrap();
+        let mut genesis = Genesis::test(vec![account_id.clone()], 1);
+        let code = near_test_contracts::rs_contract();
+        let records = genesis.force_read_records().as_mut();
+        for record in records.iter_mut() {
+            if let StateRecord::Account { account_id: record_account_id, account } = record {
+                if record_account_id == &account_id {
+                    account.set_code_hash(hash(code));
+                }
+            }
+        }
+        records.push(StateRecord::Contract { account_id: account_id.clone(), code: code.to_vec() });
+
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let home_dir = tmp_dir.path();
+        let store = near_store::test_utils::create_test_store();
+        initialize_genesis_state(store.clone(), &genesis, Some(home_dir));
+        let epoch_manager = EpochManager::new_arc_handle(store.clone(), &genesis.config);
+        let runtime = NightshadeRuntime::test(
+            home_dir,
+            store.clone(),
+            &genesis.config,
+            epoch_manager.clone(),
+        );
+        let mut env = TestEnv::builder(&genesis.config)
+            .stores(vec![store.clone()])
+            .epoch_managers(vec![epoch_manager.clone()])
+            .runtimes(vec![runtime.clone() as Arc<dyn RuntimeAdapter>])
+            .build();
+        for height in 1..=3 {
+            env.produce_block(0, height);
+        }
+
+        let cmd = CallCmd {
+            account: account_id.clone(),
+            method: "log_something".to_string(),
+            args: String::new(),
+            signer: None,
+            deposit: 0,
+            gas: 300 * 10u64.pow(12),
+            height: None,
+            use_flat_storage: false,
+        };
+        let chain_store = ChainStore::new(store, genesis.config.genesis_height, false);
+        let result = cmd.execute(&chain_store, epoch_manager.as_ref(), runtime.as_ref()).unwrap();
+
+        assert_eq!(result.block_height, 3);
+        assert_eq!(result.outcome.id, super::call_receipt_id());
+        let outcome = &result.outcome.outcome;
+        assert_eq!(outcome.logs, vec!["hello".to_string()]);
+        assert!(matches!(outcome.status, ExecutionStatus::SuccessValue(_)));
+        assert!(!result.state_changes.is_empty());
+        for change in &result.state_changes {
+            for change in &change.changes {
+                assert!(super::is_caused_by(&change.cause, &result.outcome.id));
+            }
+        }
+    }
+}
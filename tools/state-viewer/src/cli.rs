@@ -1,7 +1,10 @@
+use crate::call::CallCmd;
 use crate::commands::*;
 use crate::contract_accounts::ContractAccountFilter;
 use crate::rocksdb_stats::get_rocksdb_stats;
+use crate::trace_tx::TraceTxCmd;
 use crate::trie_iteration_benchmark::TrieIterationBenchmarkCmd;
+use crate::validator_projection::ValidatorProjectionCmd;
 
 use crate::latest_witnesses::LatestWitnessesCmd;
 use near_chain_configs::{GenesisChangeConfig, GenesisValidationMode};
@@ -34,6 +37,10 @@ pub enum StateViewerSubCommand {
     /// even if it's not included in any block on disk
     #[clap(alias = "apply_tx")]
     ApplyTx(ApplyTxCmd),
+    /// Execute a contract call on top of the state after a block, printing
+    /// the outcomes, the logs, the gas profiles and the state changes without
+    /// writing anything to the store.
+    Call(CallCmd),
     /// Print chain from start_index to end_index.
     Chain(ChainCmd),
     /// Check whether the node has all the blocks up to its head.
@@ -72,6 +79,10 @@ pub enum StateViewerSubCommand {
     Receipts(ReceiptsCmd),
     /// Replay headers from chain.
     Replay(ReplayCmd),
+    /// Dump the inputs of chunks in a range of blocks and re-apply them
+    /// with the current binary, checking state roots and outcomes.
+    /// Used to validate runtime changes for backward compatibility.
+    ReplayDump(ReplayDumpCmd),
     /// Dump stats for the RocksDB storage.
     #[clap(name = "rocksdb-stats", alias = "rocksdb_stats")]
     RocksDBStats(RocksDBStatsCmd),
@@ -87,8 +98,14 @@ pub enum StateViewerSubCommand {
     /// Iterates over the Flat State and prints some statistics.
     /// e.g. large accounts, total, average and median size, middle account
     StateStats(StateStatsCmd),
+    /// Print the receipts of a transaction across shards and blocks as a
+    /// tree, with the outcomes and the chunks they were executed in.
+    TraceTx(TraceTxCmd),
     /// Benchmark how long does it take to iterate the trie.
     TrieIterationBenchmark(TrieIterationBenchmarkCmd),
+    /// Project the seat prices, chunk producer assignments and rewards of the
+    /// next epochs, optionally with changed stakes or another validator set.
+    ValidatorProjection(ValidatorProjectionCmd),
     /// View head of the storage.
     #[clap(alias = "view_chain")]
     ViewChain(ViewChainCmd),
@@ -132,6 +149,7 @@ impl StateViewerSubCommand {
             StateViewerSubCommand::ApplyRange(cmd) => cmd.run(home_dir, near_config, store),
             StateViewerSubCommand::ApplyReceipt(cmd) => cmd.run(home_dir, near_config, store),
             StateViewerSubCommand::ApplyTx(cmd) => cmd.run(home_dir, near_config, store),
+            StateViewerSubCommand::Call(cmd) => cmd.run(home_dir, near_config, store),
             StateViewerSubCommand::Chain(cmd) => cmd.run(near_config, store),
             StateViewerSubCommand::CheckBlock => check_block_chunk_existence(near_config, store),
             StateViewerSubCommand::Chunks(cmd) => cmd.run(near_config, store),
@@ -146,6 +164,7 @@ impl StateViewerSubCommand {
             StateViewerSubCommand::PartialChunks(cmd) => cmd.run(near_config, store),
             StateViewerSubCommand::Receipts(cmd) => cmd.run(near_config, store),
             StateViewerSubCommand::Replay(cmd) => cmd.run(near_config, store),
+            StateViewerSubCommand::ReplayDump(cmd) => cmd.run(home_dir, near_config, store),
             StateViewerSubCommand::RocksDBStats(cmd) => cmd.run(store_opener.path()),
             StateViewerSubCommand::ScanDbColumn(cmd) => cmd.run(store),
             StateViewerSubCommand::State => state(home_dir, near_config, store),
@@ -154,7 +173,9 @@ impl StateViewerSubCommand {
             StateViewerSubCommand::StateStats(cmd) => cmd.run(home_dir, near_config, store),
             StateViewerSubCommand::ViewChain(cmd) => cmd.run(near_config, store),
             StateViewerSubCommand::ViewTrie(cmd) => cmd.run(store),
+            StateViewerSubCommand::TraceTx(cmd) => cmd.run(near_config, store),
             StateViewerSubCommand::TrieIterationBenchmark(cmd) => cmd.run(near_config, store),
+            StateViewerSubCommand::ValidatorProjection(cmd) => cmd.run(near_config, store),
             StateViewerSubCommand::LatestWitnesses(cmd) => cmd.run(near_config, store),
         }
     }
@@ -609,6 +630,18 @@ impl StateChangesCmd {
     }
 }
 
+#[derive(clap::Parser)]
+pub struct ReplayDumpCmd {
+    #[clap(subcommand)]
+    command: crate::replay_dump::ReplayDumpSubCommand,
+}
+
+impl ReplayDumpCmd {
+    pub fn run(self, home_dir: &Path, near_config: NearConfig, store: Store) {
+        self.command.run(home_dir, near_config, store)
+    }
+}
+
 #[derive(clap::Parser)]
 pub struct StatePartsCmd {
     /// Shard id.
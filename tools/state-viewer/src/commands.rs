@@ -17,7 +17,7 @@ use near_chain::types::{
     ApplyChunkBlockContext, ApplyChunkResult, ApplyChunkShardContext, RuntimeAdapter,
     RuntimeStorageConfig,
 };
-use near_chain::{ChainStore, ChainStoreAccess, ChainStoreUpdate, Error};
+use near_chain::{Chain, ChainStore, ChainStoreAccess, ChainStoreUpdate, Error};
 use near_chain_configs::GenesisChangeConfig;
 use near_epoch_manager::types::BlockHeaderInfo;
 use near_epoch_manager::EpochManagerHandle;
@@ -725,14 +725,17 @@ pub(crate) fn replay_chain(
         if let Ok(block_hash) = chain_store.get_block_hash_by_height(height) {
             let header = chain_store.get_block_header(&block_hash).unwrap().clone();
             println!("Height: {}, header: {:#?}", height, header);
-            epoch_manager
-                .add_validator_proposals(BlockHeaderInfo::new(
-                    &header,
-                    chain_store.get_block_height(header.last_final_block()).unwrap(),
-                ))
-                .unwrap()
-                .commit()
-                .unwrap();
+            let mut block_header_info = BlockHeaderInfo::new(
+                &header,
+                chain_store.get_block_height(header.last_final_block()).unwrap(),
+            );
+            // Counts the produced chunks as the chain does.
+            if let Ok(prev_header) = chain_store.get_block_header(header.prev_hash()) {
+                block_header_info.chunk_mask =
+                    Chain::chunk_production_mask(epoch_manager.as_ref(), &header, &prev_header)
+                        .unwrap();
+            }
+            epoch_manager.add_validator_proposals(block_header_info).unwrap().commit().unwrap();
         }
     }
 }
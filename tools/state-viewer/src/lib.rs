@@ -2,17 +2,21 @@
 
 mod apply_chain_range;
 mod apply_chunk;
+mod call;
 pub mod cli;
 mod commands;
 mod contract_accounts;
 mod epoch_info;
 mod latest_witnesses;
+mod replay_dump;
 mod rocksdb_stats;
 mod scan_db;
 mod state_changes;
 mod state_dump;
 mod state_parts;
+mod trace_tx;
 mod trie_iteration_benchmark;
 mod tx_dump;
+mod validator_projection;
 
 pub use cli::StateViewerSubCommand;
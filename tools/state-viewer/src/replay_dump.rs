@@ -0,0 +1,325 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_chain::chain::collect_receipts_from_response;
+use near_chain::migrations::check_if_block_is_first_with_chunk_of_version;
+use near_chain::types::{
+    ApplyChunkBlockContext, ApplyChunkResult, ApplyChunkShardContext, RuntimeAdapter,
+    RuntimeStorageConfig,
+};
+use near_chain::{ChainStore, ChainStoreAccess};
+use near_epoch_manager::{EpochManager, EpochManagerAdapter};
+use near_primitives::apply::ApplyChunkReason;
+use near_primitives::challenge::SlashedValidator;
+use near_primitives::congestion_info::{CongestionInfo, ExtendedCongestionInfo};
+use near_primitives::hash::CryptoHash;
+use near_primitives::receipt::Receipt;
+use near_primitives::transaction::SignedTransaction;
+use near_primitives::types::validator_stake::{ValidatorStake, ValidatorStakeIter};
+use near_primitives::types::{Balance, BlockHeight, Gas, ShardId, StateRoot};
+use near_store::Store;
+use nearcore::{NearConfig, NightshadeRuntime, NightshadeRuntimeExt};
+use std::path::{Path, PathBuf};
+
+#[derive(clap::Subcommand, Debug, Clone)]
+pub(crate) enum ReplayDumpSubCommand {
+    /// Records everything needed to re-apply the chunks of a range of blocks:
+    /// transactions, incoming receipts, block context and the resulting
+    /// state roots and outcome roots as observed by this node.
+    Dump {
+        /// Lower (inclusive) bound of the range of block heights to dump.
+        #[clap(long)]
+        height_from: BlockHeight,
+        /// Upper (inclusive) bound of the range of block heights to dump.
+        #[clap(long)]
+        height_to: BlockHeight,
+        /// Only dump chunks of these shards. All shards are dumped if not set.
+        #[clap(long)]
+        shard_ids: Option<Vec<ShardId>>,
+        /// Location of the output file.
+        #[clap(long, value_parser)]
+        file: PathBuf,
+    },
+    /// Re-applies every chunk of a dump created by the Dump subcommand using
+    /// the runtime of the current binary and checks that state roots,
+    /// outcome roots, burnt gas and burnt balance match the recorded ones.
+    /// Needs the pre-state of every recorded chunk to be present in the store.
+    Check {
+        /// Location of the input file.
+        #[clap(long, value_parser)]
+        file: PathBuf,
+        #[clap(long)]
+        use_flat_storage: bool,
+        /// Keep going after the first mismatch and report all of them.
+        #[clap(long)]
+        keep_going: bool,
+    },
+}
+
+impl ReplayDumpSubCommand {
+    pub(crate) fn run(self, home_dir: &Path, near_config: NearConfig, store: Store) {
+        match self {
+            ReplayDumpSubCommand::Dump { height_from, height_to, shard_ids, file } => {
+                let dump =
+                    dump_chunk_applies(height_from, height_to, shard_ids, &near_config, store)
+                        .unwrap();
+                println!(
+                    "Recorded {} chunks, writing them to {}",
+                    dump.chunks.len(),
+                    file.display()
+                );
+                std::fs::write(file, borsh::to_vec(&dump).unwrap()).unwrap();
+            }
+            ReplayDumpSubCommand::Check { file, use_flat_storage, keep_going } => {
+                let data = std::fs::read(&file).unwrap();
+                let dump = ReplayDump::try_from_slice(&data).unwrap();
+                let mismatches = check_replay_dump(
+                    &dump,
+                    use_flat_storage,
+                    keep_going,
+                    home_dir,
+                    &near_config,
+                    store,
+                )
+                .unwrap();
+                if mismatches.is_empty() {
+                    println!(
+                        "All {} recorded chunks reproduced the expected results",
+                        dump.chunks.len()
+                    );
+                } else {
+                    for mismatch in &mismatches {
+                        println!("{}", mismatch);
+                    }
+                    panic!(
+                        "{} of {} recorded chunks did not reproduce",
+                        mismatches.len(),
+                        dump.chunks.len()
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// A recorded range of chunk applications.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct ReplayDump {
+    /// Chain id of the network the dump was recorded on, checked before replaying.
+    pub chain_id: String,
+    pub chunks: Vec<RecordedChunkApply>,
+}
+
+/// Inputs and observed results of applying a single new chunk.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct RecordedChunkApply {
+    pub shard_id: ShardId,
+    pub prev_state_root: StateRoot,
+    pub last_validator_proposals: Vec<ValidatorStake>,
+    pub gas_limit: Gas,
+    pub is_first_block_with_chunk_of_version: bool,
+    pub height: BlockHeight,
+    pub block_hash: CryptoHash,
+    pub prev_block_hash: CryptoHash,
+    pub block_timestamp: u64,
+    pub gas_price: Balance,
+    pub challenges_result: Vec<SlashedValidator>,
+    pub random_seed: CryptoHash,
+    /// Congestion info of all shards, with the number of missed chunks.
+    pub congestion_info: Vec<(ShardId, CongestionInfo, u64)>,
+    pub receipts: Vec<Receipt>,
+    pub transactions: Vec<SignedTransaction>,
+    pub expected: RecordedChunkResult,
+}
+
+/// The part of `ChunkExtra` that is compared after replaying a chunk.
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub(crate) struct RecordedChunkResult {
+    pub state_root: StateRoot,
+    pub outcome_root: CryptoHash,
+    pub gas_used: Gas,
+    pub balance_burnt: Balance,
+}
+
+impl RecordedChunkResult {
+    fn from_apply_result(result: &ApplyChunkResult) -> Self {
+        let (outcome_root, _) = ApplyChunkResult::compute_outcomes_proof(&result.outcomes);
+        Self {
+            state_root: result.new_root,
+            outcome_root,
+            gas_used: result.total_gas_burnt,
+            balance_burnt: result.total_balance_burnt,
+        }
+    }
+}
+
+/// Reads blocks in the given range and records the inputs of all new chunks
+/// of the selected shards. Heights without a block and missing chunks are
+/// skipped.
+fn dump_chunk_applies(
+    height_from: BlockHeight,
+    height_to: BlockHeight,
+    shard_ids: Option<Vec<ShardId>>,
+    near_config: &NearConfig,
+    store: Store,
+) -> anyhow::Result<ReplayDump> {
+    assert!(height_from <= height_to, "--height-from must be less than or equal to --height-to");
+
+    let epoch_manager = EpochManager::new_arc_handle(store.clone(), &near_config.genesis.config);
+    let chain_store = ChainStore::new(store, near_config.genesis.config.genesis_height, false);
+
+    let mut chunks = vec![];
+    for height in height_from..=height_to {
+        let Ok(block_hash) = chain_store.get_block_hash_by_height(height) else {
+            continue;
+        };
+        let block = chain_store.get_block(&block_hash)?;
+        if block.header().is_genesis() {
+            continue;
+        }
+        let prev_block = chain_store.get_block(block.header().prev_hash())?;
+        for chunk_header in block.chunks().iter() {
+            let shard_id = chunk_header.shard_id();
+            if chunk_header.height_included() != height {
+                continue;
+            }
+            if let Some(shard_ids) = &shard_ids {
+                if !shard_ids.contains(&shard_id) {
+                    continue;
+                }
+            }
+            let shard_uid = epoch_manager.shard_id_to_uid(shard_id, block.header().epoch_id())?;
+            let chunk = chain_store.get_chunk(&chunk_header.chunk_hash())?;
+            let chunk_extra = chain_store.get_chunk_extra(&block_hash, &shard_uid)?;
+
+            let receipt_proof_response = chain_store.get_incoming_receipts_for_shard(
+                epoch_manager.as_ref(),
+                shard_id,
+                block_hash,
+                prev_block.chunks()[shard_id as usize].height_included(),
+            )?;
+            let receipts = collect_receipts_from_response(&receipt_proof_response);
+            let is_first_block_with_chunk_of_version =
+                check_if_block_is_first_with_chunk_of_version(
+                    &chain_store,
+                    epoch_manager.as_ref(),
+                    block.header().prev_hash(),
+                    shard_id,
+                )?;
+            let mut congestion_info = prev_block
+                .shards_congestion_info()
+                .into_iter()
+                .map(|(shard_id, info)| (shard_id, info.congestion_info, info.missed_chunks_count))
+                .collect::<Vec<_>>();
+            congestion_info.sort_by_key(|(shard_id, _, _)| *shard_id);
+
+            chunks.push(RecordedChunkApply {
+                shard_id,
+                prev_state_root: chunk.prev_state_root(),
+                last_validator_proposals: chunk_header.prev_validator_proposals().collect(),
+                gas_limit: chunk_header.gas_limit(),
+                is_first_block_with_chunk_of_version,
+                height,
+                block_hash,
+                prev_block_hash: *block.header().prev_hash(),
+                block_timestamp: block.header().raw_timestamp(),
+                gas_price: prev_block.header().next_gas_price(),
+                challenges_result: block.header().challenges_result().clone(),
+                random_seed: *block.header().random_value(),
+                congestion_info,
+                receipts,
+                transactions: chunk.transactions().to_vec(),
+                expected: RecordedChunkResult {
+                    state_root: *chunk_extra.state_root(),
+                    outcome_root: *chunk_extra.outcome_root(),
+                    gas_used: chunk_extra.gas_used(),
+                    balance_burnt: chunk_extra.balance_burnt(),
+                },
+            });
+        }
+    }
+    Ok(ReplayDump { chain_id: near_config.genesis.config.chain_id.clone(), chunks })
+}
+
+/// Re-applies all chunks of the dump and returns a description of every
+/// chunk whose results differ from the recorded ones.
+fn check_replay_dump(
+    dump: &ReplayDump,
+    use_flat_storage: bool,
+    keep_going: bool,
+    home_dir: &Path,
+    near_config: &NearConfig,
+    store: Store,
+) -> anyhow::Result<Vec<String>> {
+    anyhow::ensure!(
+        dump.chain_id == near_config.genesis.config.chain_id,
+        "dump was recorded on chain {}, but the node is configured for {}",
+        dump.chain_id,
+        near_config.genesis.config.chain_id
+    );
+    let epoch_manager = EpochManager::new_arc_handle(store.clone(), &near_config.genesis.config);
+    let runtime = NightshadeRuntime::from_config(home_dir, store, near_config, epoch_manager)?;
+
+    let mut mismatches = vec![];
+    for recorded in &dump.chunks {
+        let result = replay_chunk(runtime.as_ref(), recorded, use_flat_storage)?;
+        let actual = RecordedChunkResult::from_apply_result(&result);
+        if actual != recorded.expected {
+            mismatches.push(format!(
+                "Mismatch at height {} shard {} (block {}):\nexpected: {:?}\nactual: {:?}\noutcomes: {:#?}",
+                recorded.height,
+                recorded.shard_id,
+                recorded.block_hash,
+                recorded.expected,
+                actual,
+                result.outcomes,
+            ));
+            if !keep_going {
+                break;
+            }
+        } else {
+            tracing::debug!(
+                target: "state-viewer",
+                height = recorded.height,
+                shard_id = recorded.shard_id,
+                "chunk reproduced"
+            );
+        }
+    }
+    Ok(mismatches)
+}
+
+fn replay_chunk(
+    runtime: &dyn RuntimeAdapter,
+    recorded: &RecordedChunkApply,
+    use_flat_storage: bool,
+) -> anyhow::Result<ApplyChunkResult> {
+    let congestion_info = recorded
+        .congestion_info
+        .iter()
+        .map(|(shard_id, info, missed_chunks_count)| {
+            (*shard_id, ExtendedCongestionInfo::new(*info, *missed_chunks_count))
+        })
+        .collect();
+    Ok(runtime.apply_chunk(
+        RuntimeStorageConfig::new(recorded.prev_state_root, use_flat_storage),
+        ApplyChunkReason::UpdateTrackedShard,
+        ApplyChunkShardContext {
+            shard_id: recorded.shard_id,
+            last_validator_proposals: ValidatorStakeIter::new(&recorded.last_validator_proposals),
+            gas_limit: recorded.gas_limit,
+            is_new_chunk: true,
+            is_first_block_with_chunk_of_version: recorded.is_first_block_with_chunk_of_version,
+        },
+        ApplyChunkBlockContext {
+            height: recorded.height,
+            block_hash: recorded.block_hash,
+            prev_block_hash: recorded.prev_block_hash,
+            block_timestamp: recorded.block_timestamp,
+            gas_price: recorded.gas_price,
+            challenges_result: recorded.challenges_result.clone(),
+            random_seed: recorded.random_seed,
+            congestion_info,
+        },
+        &recorded.receipts,
+        &recorded.transactions,
+    )?)
+}
@@ -222,7 +222,7 @@ fn create_external_connection(
             }
         }
         .expect("Failed to create an S3 bucket");
-        ExternalConnection::S3 { bucket: Arc::new(bucket) }
+        ExternalConnection::S3 { bucket: Arc::new(bucket), upload: Default::default() }
     } else if let Some(bucket) = gcs_bucket {
         if let Some(credentials_file) = credentials_file {
             std::env::set_var("SERVICE_ACCOUNT", &credentials_file);
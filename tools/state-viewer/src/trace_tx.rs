@@ -0,0 +1,115 @@
+//! Tracing of the receipts of a transaction across shards and blocks.
+//!
+//! Starting from the outcome of the transaction, the command follows the
+//! receipts each outcome created and prints them as a tree, with the block,
+//! shard and chunk each receipt was executed in, its status and the burnt gas.
+//! Only the outcomes on the canonical chain are considered, and the tree ends
+//! where the outcomes were garbage collected or not executed yet.
+use anyhow::Context;
+use clap::Parser;
+use near_chain::{ChainStore, ChainStoreAccess, Error};
+use near_epoch_manager::{EpochManager, EpochManagerAdapter, EpochManagerHandle};
+use near_primitives::hash::CryptoHash;
+use near_primitives::receipt::ReceiptEnum;
+use near_primitives::transaction::ExecutionOutcomeWithIdAndProof;
+use near_primitives::views::ExecutionStatusView;
+use near_store::Store;
+use nearcore::NearConfig;
+use std::sync::Arc;
+
+#[derive(Parser)]
+pub struct TraceTxCmd {
+    /// Hash of the transaction.
+    tx_hash: CryptoHash,
+}
+
+struct Tracer {
+    chain_store: ChainStore,
+    epoch_manager: Arc<EpochManagerHandle>,
+}
+
+impl Tracer {
+    /// Returns the outcome of the transaction or receipt on the canonical
+    /// chain, if it's stored.
+    fn canonical_outcome(
+        &self,
+        id: &CryptoHash,
+    ) -> anyhow::Result<Option<ExecutionOutcomeWithIdAndProof>> {
+        for outcome in self.chain_store.get_outcomes_by_id(id)? {
+            let header = self.chain_store.get_block_header(&outcome.block_hash)?;
+            match self.chain_store.get_block_hash_by_height(header.height()) {
+                Ok(hash) if hash == outcome.block_hash => return Ok(Some(outcome)),
+                Ok(_) | Err(Error::DBNotFoundErr(_)) => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Describes the receipt by its kind and sender, if it's stored.
+    fn describe_receipt(&self, receipt_id: &CryptoHash) -> anyhow::Result<String> {
+        let Some(receipt) = self.chain_store.get_receipt(receipt_id)? else {
+            return Ok(format!("Receipt {receipt_id}"));
+        };
+        let kind = match receipt.receipt() {
+            ReceiptEnum::Action(_) | ReceiptEnum::PromiseYield(_) => "Action receipt",
+            ReceiptEnum::Data(_) | ReceiptEnum::PromiseResume(_) => "Data receipt",
+        };
+        Ok(format!("{kind} {receipt_id} from {}", receipt.predecessor_id()))
+    }
+
+    fn print(&self, id: &CryptoHash, description: String, depth: usize) -> anyhow::Result<()> {
+        let indent = "  ".repeat(depth);
+        let Some(outcome) = self.canonical_outcome(id)? else {
+            println!("{indent}{description}: no outcome on the canonical chain");
+            return Ok(());
+        };
+        let block = self.chain_store.get_block(&outcome.block_hash)?;
+        let executor_id = &outcome.outcome_with_id.outcome.executor_id;
+        let shard_id =
+            self.epoch_manager.account_id_to_shard_id(executor_id, block.header().epoch_id())?;
+        let chunk_hash = block
+            .chunks()
+            .iter()
+            .find(|chunk| chunk.shard_id() == shard_id)
+            .map(|chunk| chunk.chunk_hash().0.to_string())
+            .unwrap_or_default();
+        let status: ExecutionStatusView = outcome.outcome_with_id.outcome.status.clone().into();
+        println!(
+            "{indent}{description} executed by {executor_id} in block {} at height {}, shard {shard_id}, chunk {chunk_hash}",
+            outcome.block_hash,
+            block.header().height(),
+        );
+        println!(
+            "{indent}  status {status:?}, gas burnt {:.3} Tgas, {} logs",
+            outcome.outcome_with_id.outcome.gas_burnt as f64 / 1e12,
+            outcome.outcome_with_id.outcome.logs.len(),
+        );
+        for receipt_id in &outcome.outcome_with_id.outcome.receipt_ids {
+            self.print(receipt_id, self.describe_receipt(receipt_id)?, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl TraceTxCmd {
+    pub(crate) fn run(self, near_config: NearConfig, store: Store) {
+        self.trace(near_config, store).unwrap_or_else(|err| panic!("{err:#}"))
+    }
+
+    fn trace(self, near_config: NearConfig, store: Store) -> anyhow::Result<()> {
+        let genesis_config = &near_config.genesis.config;
+        let chain_store = ChainStore::new(store.clone(), genesis_config.genesis_height, false);
+        let epoch_manager = EpochManager::new_arc_handle(store, genesis_config);
+        let tx = chain_store
+            .get_transaction(&self.tx_hash)?
+            .with_context(|| format!("transaction {} not found", self.tx_hash))?;
+        let description = format!(
+            "Transaction {} from {} to {}",
+            self.tx_hash,
+            tx.transaction.signer_id(),
+            tx.transaction.receiver_id()
+        );
+        Tracer { chain_store, epoch_manager }.print(&self.tx_hash, description, 0)
+    }
+}
@@ -0,0 +1,389 @@
+//! Projection of the validator selection and the rewards of the next epochs.
+//!
+//! The epoch after the next one is selected from the proposals of the current
+//! epoch and the validators of the next epoch, who roll over with their stake
+//! increased by the rewards of the current epoch. The projection repeats this
+//! for a number of epochs, applying the given stake changes as proposals of the
+//! current epoch and assuming that all validators keep the given uptime and
+//! that nobody is kicked out. The chunk producer assignments of the projected
+//! epochs depend on the randomness of blocks which don't exist yet, so they
+//! are only an example of what the assignments may look like.
+use anyhow::Context;
+use clap::Parser;
+use near_chain::{ChainStore, ChainStoreAccess};
+use near_chain_configs::NEAR_BASE;
+use near_crypto::{KeyType, PublicKey};
+use near_epoch_manager::{
+    proposals_to_epoch_info, EpochManager, EpochManagerAdapter, RewardCalculator,
+};
+use near_primitives::epoch_manager::epoch_info::EpochInfo;
+use near_primitives::hash::hash;
+use near_primitives::serialize::dec_format;
+use near_primitives::types::validator_stake::ValidatorStake;
+use near_primitives::types::{
+    AccountId, Balance, BlockChunkValidatorStats, ChunkValidatorStats, ValidatorStats,
+};
+use near_store::Store;
+use nearcore::NearConfig;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Number of blocks, chunks and endorsements a validator is assumed to be
+/// expected to produce, to express the uptime as a ratio.
+const EXPECTED_PER_VALIDATOR: u64 = 1000;
+
+#[derive(Parser)]
+pub struct ValidatorProjectionCmd {
+    /// Number of epochs to project, after the next epoch which is already
+    /// decided.
+    #[arg(long, default_value_t = 3)]
+    num_epochs: u64,
+
+    /// JSON file with a list of validators with their `account_id`,
+    /// `public_key` and `stake`, used as the proposals of the current epoch
+    /// instead of the validators of the next epoch, e.g. the
+    /// `current_validators` returned by the `validators` RPC method.
+    #[arg(long)]
+    validators_file: Option<PathBuf>,
+
+    /// Stake changes proposed in the current epoch, as
+    /// `<account_id>=<stake in NEAR>`. A stake of 0 unstakes.
+    #[arg(long, value_parser = parse_stake_change)]
+    stake_change: Vec<(AccountId, Balance)>,
+
+    /// Ratio of the expected blocks, chunks and endorsements all validators
+    /// produce, which determines their rewards.
+    #[arg(long, default_value_t = 1.0)]
+    uptime: f64,
+
+    /// Print only the projection for the given validator.
+    #[arg(long)]
+    validator_account_id: Option<AccountId>,
+}
+
+#[derive(serde::Deserialize)]
+struct SuppliedValidator {
+    account_id: AccountId,
+    public_key: PublicKey,
+    #[serde(with = "dec_format")]
+    stake: Balance,
+}
+
+fn parse_stake_change(value: &str) -> anyhow::Result<(AccountId, Balance)> {
+    let (account_id, stake) =
+        value.split_once('=').context("expected a stake change as <account_id>=<stake>")?;
+    let stake: Balance = stake.parse().context("invalid stake in NEAR")?;
+    Ok((account_id.parse()?, stake.checked_mul(NEAR_BASE).context("stake too large")?))
+}
+
+/// Validators of an epoch, with the rewards they receive at its end if the
+/// epoch is projected.
+struct ProjectedEpoch {
+    epoch_info: Arc<EpochInfo>,
+    validator_reward: Option<HashMap<AccountId, Balance>>,
+}
+
+fn format_near(balance: Balance) -> String {
+    format!("{:.3} NEAR", balance as f64 / NEAR_BASE as f64)
+}
+
+impl ValidatorProjectionCmd {
+    pub(crate) fn run(self, near_config: NearConfig, store: Store) {
+        let epochs = self.project(near_config, store).unwrap_or_else(|err| panic!("{err:#}"));
+        for epoch in &epochs {
+            self.print_epoch(&epoch.epoch_info, epoch.validator_reward.as_ref());
+        }
+    }
+
+    /// Returns the next epoch followed by the projected epochs.
+    fn project(
+        &self,
+        near_config: NearConfig,
+        store: Store,
+    ) -> anyhow::Result<Vec<ProjectedEpoch>> {
+        let genesis_config = &near_config.genesis.config;
+        let chain_store = ChainStore::new(store.clone(), genesis_config.genesis_height, false);
+        let epoch_manager = EpochManager::new_arc_handle(store, genesis_config);
+        let reward_calculator = RewardCalculator::new(genesis_config);
+
+        let head = chain_store.head()?;
+        let head_header = chain_store.get_block_header(&head.last_block_hash)?;
+        let current_epoch_info = epoch_manager.get_epoch_info(&head.epoch_id)?;
+        let next_epoch_id = epoch_manager.get_next_epoch_id(&head.last_block_hash)?;
+        let next_epoch_info = epoch_manager.get_epoch_info(&next_epoch_id)?;
+        let epoch_config = epoch_manager.get_epoch_config(&next_epoch_id)?;
+        let protocol_version = next_epoch_info.protocol_version();
+
+        // The duration of the epochs determines the inflation, so it's
+        // estimated from the blocks of the current epoch so far.
+        let epoch_start_height = epoch_manager.get_epoch_start_height(&head.last_block_hash)?;
+        let epoch_start_header = chain_store
+            .get_block_hash_by_height(epoch_start_height)
+            .and_then(|hash| chain_store.get_block_header(&hash));
+        let block_duration = match epoch_start_header {
+            Ok(start) if head.height > start.height() => {
+                (head_header.raw_timestamp() - start.raw_timestamp())
+                    / (head.height - start.height())
+            }
+            _ => near_config.client_config.min_block_production_delay.whole_nanoseconds() as u64,
+        };
+        let epoch_duration = block_duration * epoch_config.epoch_length;
+
+        let mut proposals = BTreeMap::new();
+        if let Some(path) = &self.validators_file {
+            let validators: Vec<SuppliedValidator> = serde_json::from_slice(
+                &std::fs::read(path).with_context(|| format!("reading {}", path.display()))?,
+            )
+            .with_context(|| format!("parsing {}", path.display()))?;
+            for validator in next_epoch_info.validators_iter() {
+                proposals.insert(
+                    validator.account_id().clone(),
+                    ValidatorStake::new(
+                        validator.account_id().clone(),
+                        validator.take_public_key(),
+                        0,
+                    ),
+                );
+            }
+            for validator in validators {
+                proposals.insert(
+                    validator.account_id.clone(),
+                    ValidatorStake::new(
+                        validator.account_id,
+                        validator.public_key,
+                        validator.stake,
+                    ),
+                );
+            }
+        }
+        for (account_id, stake) in &self.stake_change {
+            let public_key = next_epoch_info
+                .get_validator_by_account(account_id)
+                .map(|validator| validator.take_public_key())
+                .unwrap_or_else(|| PublicKey::empty(KeyType::ED25519));
+            proposals.insert(
+                account_id.clone(),
+                ValidatorStake::new(account_id.clone(), public_key, *stake),
+            );
+        }
+        let mut proposals: Vec<_> = proposals.into_values().collect();
+
+        println!(
+            "Current epoch {}, estimated epoch duration {} s, total supply {}",
+            current_epoch_info.epoch_height(),
+            epoch_duration / 1_000_000_000,
+            format_near(head_header.total_supply()),
+        );
+        let mut epochs =
+            vec![ProjectedEpoch { epoch_info: next_epoch_info.clone(), validator_reward: None }];
+
+        let mut total_supply = head_header.total_supply();
+        let mut rewarded_epoch_info = current_epoch_info;
+        let mut prev_epoch_info = next_epoch_info;
+        for _ in 0..self.num_epochs {
+            let (validator_reward, minted_amount) = self.compute_rewards(
+                &reward_calculator,
+                &rewarded_epoch_info,
+                total_supply,
+                epoch_duration,
+                genesis_config.protocol_version,
+            );
+            let epoch_height = prev_epoch_info.epoch_height() + 1;
+            let epoch_info = proposals_to_epoch_info(
+                &epoch_config,
+                hash(&epoch_height.to_le_bytes()).0,
+                &prev_epoch_info,
+                std::mem::take(&mut proposals),
+                HashMap::new(),
+                validator_reward.clone(),
+                minted_amount,
+                protocol_version,
+                protocol_version,
+                true,
+            )
+            .with_context(|| format!("selecting the validators of epoch {epoch_height}"))?;
+            total_supply += minted_amount;
+            rewarded_epoch_info = prev_epoch_info;
+            prev_epoch_info = Arc::new(epoch_info);
+            epochs.push(ProjectedEpoch {
+                epoch_info: prev_epoch_info.clone(),
+                validator_reward: Some(validator_reward),
+            });
+        }
+        Ok(epochs)
+    }
+
+    /// Computes the rewards of the validators of the epoch, as they are
+    /// computed at its end.
+    fn compute_rewards(
+        &self,
+        reward_calculator: &RewardCalculator,
+        epoch_info: &EpochInfo,
+        total_supply: Balance,
+        epoch_duration: u64,
+        genesis_protocol_version: u32,
+    ) -> (HashMap<AccountId, Balance>, Balance) {
+        let produced = (self.uptime.clamp(0.0, 1.0) * EXPECTED_PER_VALIDATOR as f64) as u64;
+        let stats = ValidatorStats { produced, expected: EXPECTED_PER_VALIDATOR };
+        let mut validator_block_chunk_stats = HashMap::new();
+        let mut validator_stake = HashMap::new();
+        for validator in epoch_info.validators_iter() {
+            validator_block_chunk_stats.insert(
+                validator.account_id().clone(),
+                BlockChunkValidatorStats {
+                    block_stats: stats.clone(),
+                    chunk_stats: ChunkValidatorStats {
+                        production: stats.clone(),
+                        endorsement: stats.clone(),
+                    },
+                },
+            );
+            validator_stake.insert(validator.account_id().clone(), validator.stake());
+        }
+        reward_calculator.calculate_reward(
+            validator_block_chunk_stats,
+            &validator_stake,
+            total_supply,
+            epoch_info.protocol_version(),
+            genesis_protocol_version,
+            epoch_duration,
+        )
+    }
+
+    fn print_epoch(
+        &self,
+        epoch_info: &EpochInfo,
+        validator_reward: Option<&HashMap<AccountId, Balance>>,
+    ) {
+        let kind = if validator_reward.is_some() { "projected" } else { "decided" };
+        let validators: Vec<_> = epoch_info.validators_iter().collect();
+        println!(
+            "\nEpoch {} ({kind}): seat price {}, {} validators",
+            epoch_info.epoch_height(),
+            format_near(epoch_info.seat_price()),
+            validators.len(),
+        );
+        let mut chunk_producer_shards: HashMap<&AccountId, Vec<usize>> = HashMap::new();
+        for (shard_index, chunk_producers) in
+            epoch_info.chunk_producers_settlement().iter().enumerate()
+        {
+            for &validator_id in chunk_producers {
+                chunk_producer_shards
+                    .entry(epoch_info.validator_account_id(validator_id))
+                    .or_default()
+                    .push(shard_index);
+            }
+        }
+        for validator in &validators {
+            let account_id = validator.account_id();
+            if self.validator_account_id.as_ref().is_some_and(|wanted| wanted != account_id) {
+                continue;
+            }
+            let is_block_producer = epoch_info
+                .block_producers_settlement()
+                .iter()
+                .any(|&id| epoch_info.validator_account_id(id) == account_id);
+            let reward = validator_reward
+                .map(|rewards| {
+                    let reward = rewards.get(account_id).copied().unwrap_or_default();
+                    format!(", reward received {}", format_near(reward))
+                })
+                .unwrap_or_default();
+            println!(
+                "  {account_id}: stake {}, block producer: {is_block_producer}, chunk producer of shards {:?}{reward}",
+                format_near(validator.stake()),
+                chunk_producer_shards.get(account_id).cloned().unwrap_or_default(),
+            );
+        }
+        if let Some(account_id) = &self.validator_account_id {
+            if !validators.iter().any(|validator| validator.account_id() == account_id) {
+                println!("  {account_id}: not a validator");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stake_change() {
+        assert_eq!(
+            parse_stake_change("pool.poolv1.near=1000").unwrap(),
+            ("pool.poolv1.near".parse().unwrap(), 1000 * NEAR_BASE)
+        );
+        assert_eq!(parse_stake_change("pool.poolv1.near=0").unwrap().1, 0);
+        assert!(parse_stake_change("pool.poolv1.near").is_err());
+        assert!(parse_stake_change("pool.poolv1.near=1.5").is_err());
+    }
+
+    fn epoch_validators(epoch: &ProjectedEpoch) -> Vec<AccountId> {
+        let mut validators: Vec<_> = epoch
+            .epoch_info
+            .validators_iter()
+            .map(|validator| validator.take_account_id())
+            .collect();
+        validators.sort();
+        validators
+    }
+
+    /// Unstaking in the current epoch removes the validator from the epoch
+    /// after the next one.
+    #[test]
+    fn test_project_unstake() {
+        let validators: Vec<AccountId> = vec!["test0".parse().unwrap(), "test1".parse().unwrap()];
+        let mut genesis = near_chain_configs::Genesis::test(validators.clone(), 2);
+        genesis.config.epoch_length = 5;
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let store = near_store::test_utils::create_test_store();
+        near_store::genesis::initialize_genesis_state(
+            store.clone(),
+            &genesis,
+            Some(tmp_dir.path()),
+        );
+        let epoch_manager = EpochManager::new_arc_handle(store.clone(), &genesis.config);
+        let runtime = nearcore::NightshadeRuntime::test(
+            tmp_dir.path(),
+            store.clone(),
+            &genesis.config,
+            epoch_manager.clone(),
+        ) as Arc<dyn near_chain::types::RuntimeAdapter>;
+        // Builds the chain and saves the genesis block.
+        let _env = near_client::test_utils::TestEnv::builder(&genesis.config)
+            .stores(vec![store.clone()])
+            .epoch_managers(vec![epoch_manager])
+            .runtimes(vec![runtime])
+            .build();
+
+        let signer = near_crypto::InMemorySigner::from_seed(
+            validators[0].clone(),
+            KeyType::ED25519,
+            validators[0].as_str(),
+        );
+        let near_config = NearConfig::new(
+            nearcore::config::Config::default(),
+            genesis,
+            near_crypto::KeyFile::from(&signer),
+            None,
+        )
+        .unwrap();
+        let cmd = ValidatorProjectionCmd {
+            num_epochs: 2,
+            validators_file: None,
+            stake_change: vec![(validators[1].clone(), 0)],
+            uptime: 1.0,
+            validator_account_id: None,
+        };
+        let epochs = cmd.project(near_config, store).unwrap();
+        assert_eq!(epochs.len(), 3);
+        assert_eq!(epoch_validators(&epochs[0]), validators);
+        assert!(epochs[0].validator_reward.is_none());
+        assert_eq!(epoch_validators(&epochs[1]), validators[..1]);
+        // The validators of the current epoch are rewarded at its end.
+        let reward = epochs[1].validator_reward.as_ref().unwrap();
+        assert!(validators.iter().all(|account_id| reward.contains_key(account_id)));
+        assert_eq!(epoch_validators(&epochs[2]), validators[..1]);
+    }
+}